@@ -60,6 +60,8 @@ make bench-show          # Open benchmark report
 make bench-save          # Save benchmark history snapshot
 make bench-compare       # Compare benchmark runs
 make bench-json          # Output benchmarks in JSON
+make bench-baseline      # Save a Criterion baseline (BENCH_BASELINE, default main)
+make bench-regress       # Compare against the saved baseline and flag regressions
 make bench-clean         # Remove benchmark data
 ```
 
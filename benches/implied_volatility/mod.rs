@@ -0,0 +1,70 @@
+use criterion::{BenchmarkId, Criterion, Throughput};
+use orderbook_rs::{BlackScholes, IVParams};
+use std::hint::black_box;
+
+pub mod solver;
+
+/// Register benchmarks of bulk Black-Scholes revaluation
+pub fn register_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("BlackScholes - Batch Pricing");
+
+    for count in [100, 1_000, 10_000] {
+        let options = options(count);
+        let vols: Vec<f64> = (0..count).map(|i| 0.15 + (i % 50) as f64 * 0.004).collect();
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("price_loop", count),
+            &options,
+            |b, options| {
+                b.iter(|| {
+                    black_box(
+                        options
+                            .iter()
+                            .zip(&vols)
+                            .map(|(params, &vol)| BlackScholes::price(params, vol))
+                            .collect::<Vec<f64>>(),
+                    )
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("price_batch", count),
+            &options,
+            |b, options| {
+                b.iter(|| black_box(BlackScholes::price_batch(black_box(options), &vols).unwrap()))
+            },
+        );
+        #[cfg(feature = "rayon")]
+        group.bench_with_input(
+            BenchmarkId::new("price_batch_parallel", count),
+            &options,
+            |b, options| {
+                b.iter(|| {
+                    black_box(
+                        BlackScholes::price_batch_parallel(black_box(options), &vols).unwrap(),
+                    )
+                })
+            },
+        );
+    }
+
+    group.finish();
+
+    solver::register_benchmarks(c);
+}
+
+/// Calls and puts across a ladder of strikes and expiries
+fn options(count: usize) -> Vec<IVParams> {
+    (0..count)
+        .map(|i| {
+            let strike = 70.0 + (i % 61) as f64;
+            let expiry = 0.05 + (i % 12) as f64 / 12.0;
+            if i % 2 == 0 {
+                IVParams::call(100.0, strike, expiry, 0.04)
+            } else {
+                IVParams::put(100.0, strike, expiry, 0.04)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,89 @@
+use criterion::{BenchmarkId, Criterion, Throughput};
+use orderbook_rs::orderbook::implied_volatility::{solve_iv, solve_iv_bisection};
+use orderbook_rs::{BlackScholes, IVConfig, IVParams, OrderBook, PriceSource, SolverConfig};
+use pricelevel::{OrderId, Side, TimeInForce};
+use std::hint::black_box;
+
+/// Register benchmarks of implied volatility solving from prices and books
+pub fn register_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Implied Volatility - Solving");
+    let config = SolverConfig::default();
+
+    for count in [100usize, 1_000] {
+        let quotes = quotes(count);
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("newton_raphson", count),
+            &quotes,
+            |b, quotes| {
+                b.iter(|| {
+                    for (params, price) in quotes {
+                        let _ = black_box(solve_iv(params, *price, &config));
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("bisection", count),
+            &quotes,
+            |b, quotes| {
+                b.iter(|| {
+                    for (params, price) in quotes {
+                        let _ = black_box(solve_iv_bisection(params, *price, &config));
+                    }
+                })
+            },
+        );
+    }
+    group.throughput(Throughput::Elements(1));
+
+    // Prices in cents: 5.40 / 5.50
+    let book = OrderBook::<()>::new("TEST-C-100");
+    let _ = book.add_limit_order(
+        OrderId::new_uuid(),
+        540,
+        10,
+        Side::Buy,
+        TimeInForce::Gtc,
+        None,
+    );
+    let _ = book.add_limit_order(
+        OrderId::new_uuid(),
+        550,
+        10,
+        Side::Sell,
+        TimeInForce::Gtc,
+        None,
+    );
+    let params = IVParams::call(100.0, 100.0, 0.25, 0.05);
+    let iv_config = IVConfig::default().with_price_scale(100.0);
+    group.bench_function("book_mid_price", |b| {
+        b.iter(|| {
+            black_box(book.implied_volatility_with_config(
+                &params,
+                PriceSource::MidPrice,
+                &iv_config,
+            ))
+        })
+    });
+
+    group.finish();
+}
+
+/// Options across strikes and expiries with their prices at known volatilities
+fn quotes(count: usize) -> Vec<(IVParams, f64)> {
+    (0..count)
+        .map(|i| {
+            let strike = 80.0 + (i % 41) as f64;
+            let expiry = 0.1 + (i % 8) as f64 / 8.0;
+            let params = if i % 2 == 0 {
+                IVParams::call(100.0, strike, expiry, 0.03)
+            } else {
+                IVParams::put(100.0, strike, expiry, 0.03)
+            };
+            let price = BlackScholes::price(&params, 0.15 + (i % 30) as f64 * 0.01);
+            (params, price)
+        })
+        .collect()
+}
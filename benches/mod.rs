@@ -1,19 +1,36 @@
 use criterion::{criterion_group, criterion_main};
 
 mod concurrent;
+mod implied_volatility;
 mod order_book;
 mod simple;
+#[cfg(feature = "wire")]
+mod wire;
 
 use concurrent::register_benchmarks as register_concurrent_benchmarks;
+use implied_volatility::register_benchmarks as register_implied_volatility_benchmarks;
 use order_book::register_benchmarks as register_order_book_benchmarks;
 use simple::basic::benchmark_data;
+#[cfg(feature = "wire")]
+use wire::register_benchmarks as register_wire_benchmarks;
 
 // Define the benchmark groups
+#[cfg(not(feature = "wire"))]
 criterion_group!(
     benches,
     benchmark_data,
     register_order_book_benchmarks,
     register_concurrent_benchmarks,
+    register_implied_volatility_benchmarks,
+);
+#[cfg(feature = "wire")]
+criterion_group!(
+    benches,
+    benchmark_data,
+    register_order_book_benchmarks,
+    register_concurrent_benchmarks,
+    register_implied_volatility_benchmarks,
+    register_wire_benchmarks,
 );
 
 criterion_main!(benches);
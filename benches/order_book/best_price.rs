@@ -0,0 +1,84 @@
+use criterion::{BenchmarkId, Criterion};
+use orderbook_rs::{BookConfig, OrderBook};
+use pricelevel::{OrderId, Side, TimeInForce};
+use std::hint::black_box;
+
+/// Register benchmarks for best price reads while the touch churns
+pub fn register_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("OrderBook - Best Price Under Churn");
+
+    for levels in [100u64, 10_000] {
+        let book = deep_book(&BookConfig::default(), levels);
+        group.bench_with_input(
+            BenchmarkId::new("cancel_and_replace_touch", levels),
+            &levels,
+            |b, _| b.iter(|| churn_touch(&book)),
+        );
+
+        // Reads between mutations that leave the best prices untouched
+        group.bench_with_input(
+            BenchmarkId::new("read_best_behind_touch", levels),
+            &levels,
+            |b, &levels| {
+                b.iter(|| {
+                    let id = OrderId::new_uuid();
+                    let price = 10_000 - levels / 2;
+                    let _ = book.add_limit_order(id, price, 1, Side::Buy, TimeInForce::Gtc, None);
+                    black_box((book.best_bid(), book.best_ask()));
+                    let _ = book.cancel_order(id);
+                    black_box((book.best_bid(), book.best_ask()))
+                })
+            },
+        );
+
+        let config = BookConfig::default()
+            .with_tick_size(1)
+            .with_price_band(10_000 - levels, 10_001 + levels)
+            .with_dense_ladder();
+        let book = deep_book(&config, levels);
+        group.bench_with_input(
+            BenchmarkId::new("cancel_and_replace_touch_dense_ladder", levels),
+            &levels,
+            |b, _| b.iter(|| churn_touch(&book)),
+        );
+    }
+
+    group.finish();
+}
+
+/// Book with `levels` bid levels below 10_000 and as many ask levels above it
+fn deep_book(config: &BookConfig, levels: u64) -> OrderBook {
+    let book = OrderBook::new_with_config("TEST-SYMBOL", *config);
+    for offset in 1..=levels {
+        let _ = book.add_limit_order(
+            OrderId::new_uuid(),
+            10_000 - offset,
+            10,
+            Side::Buy,
+            TimeInForce::Gtc,
+            None,
+        );
+        let _ = book.add_limit_order(
+            OrderId::new_uuid(),
+            10_000 + offset,
+            10,
+            Side::Sell,
+            TimeInForce::Gtc,
+            None,
+        );
+    }
+    book
+}
+
+/// Improves both sides of the book, reads the best prices, then cancels the
+/// improving orders so the best levels empty and the best prices fall back.
+fn churn_touch(book: &OrderBook) -> (Option<u64>, Option<u64>) {
+    let bid = OrderId::new_uuid();
+    let ask = OrderId::new_uuid();
+    let _ = book.add_limit_order(bid, 10_000, 1, Side::Buy, TimeInForce::Gtc, None);
+    let _ = book.add_limit_order(ask, 10_001, 1, Side::Sell, TimeInForce::Gtc, None);
+    black_box((book.best_bid(), book.best_ask()));
+    let _ = book.cancel_order(bid);
+    let _ = book.cancel_order(ask);
+    black_box((book.best_bid(), book.best_ask()))
+}
@@ -0,0 +1,130 @@
+use criterion::{BenchmarkId, Criterion};
+use orderbook_rs::{BookConfig, OrderBook};
+use pricelevel::{OrderId, Side, TimeInForce};
+use std::hint::black_box;
+
+/// Lowest bid price of the books, the ask side starts `DEPTH` ticks above it
+const BASE_PRICE: u64 = 100_000;
+
+/// Number of levels resting on each side
+const DEPTH: u64 = 5_000;
+
+/// Register benchmarks comparing books keeping their levels in ordered maps
+/// with books backed by a dense price ladder
+pub fn register_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("OrderBook - Dense Ladder");
+
+    let sparse = BookConfig::default().with_tick_size(1);
+    let dense = sparse
+        .with_price_band(BASE_PRICE, BASE_PRICE + 2 * DEPTH)
+        .with_dense_ladder();
+
+    for (name, config) in [("skipmap", sparse), ("dense_ladder", dense)] {
+        // Orders joining and leaving existing levels spread across the book
+        group.bench_with_input(
+            BenchmarkId::new("add_cancel_resting_levels", name),
+            &config,
+            |b, config| {
+                let book = deep_book(config);
+                let mut offset = 0;
+                b.iter(|| {
+                    offset = (offset + 7_919) % DEPTH;
+                    let id = OrderId::new_uuid();
+                    let _ = book.add_limit_order(
+                        id,
+                        BASE_PRICE + offset,
+                        5,
+                        Side::Buy,
+                        TimeInForce::Gtc,
+                        None,
+                    );
+                    black_box(book.cancel_order(id))
+                })
+            },
+        );
+
+        // Quantity amends of an order resting deep in the book
+        group.bench_with_input(
+            BenchmarkId::new("amend_quantity", name),
+            &config,
+            |b, config| {
+                let book = deep_book(config);
+                let id = OrderId::new_uuid();
+                let _ = book.add_limit_order(
+                    id,
+                    BASE_PRICE + DEPTH / 2,
+                    10,
+                    Side::Buy,
+                    TimeInForce::Gtc,
+                    None,
+                );
+                let mut quantity = 10;
+                b.iter(|| {
+                    quantity = if quantity == 10 { 20 } else { 10 };
+                    black_box(book.update_order(pricelevel::OrderUpdate::UpdateQuantity {
+                        order_id: id,
+                        new_quantity: quantity,
+                    }))
+                })
+            },
+        );
+
+        // Market orders sweeping the best levels, which are then refilled
+        group.bench_with_input(
+            BenchmarkId::new("sweep_and_refill", name),
+            &config,
+            |b, config| {
+                let book = deep_book(config);
+                b.iter(|| {
+                    let _ = black_box(book.submit_market_order(OrderId::new_uuid(), 50, Side::Buy));
+                    for offset in 0..5 {
+                        let _ = book.add_limit_order(
+                            OrderId::new_uuid(),
+                            BASE_PRICE + DEPTH + offset,
+                            10,
+                            Side::Sell,
+                            TimeInForce::Gtc,
+                            None,
+                        );
+                    }
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("best_prices", name),
+            &config,
+            |b, config| {
+                let book = deep_book(config);
+                b.iter(|| black_box((book.best_bid(), book.best_ask())))
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Book with `DEPTH` bid levels starting at `BASE_PRICE` and as many ask
+/// levels right above them
+fn deep_book(config: &BookConfig) -> OrderBook {
+    let book = OrderBook::new_with_config("TEST-SYMBOL", *config);
+    for offset in 0..DEPTH {
+        let _ = book.add_limit_order(
+            OrderId::new_uuid(),
+            BASE_PRICE + offset,
+            10,
+            Side::Buy,
+            TimeInForce::Gtc,
+            None,
+        );
+        let _ = book.add_limit_order(
+            OrderId::new_uuid(),
+            BASE_PRICE + DEPTH + offset,
+            10,
+            Side::Sell,
+            TimeInForce::Gtc,
+            None,
+        );
+    }
+    book
+}
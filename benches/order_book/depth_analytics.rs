@@ -0,0 +1,97 @@
+use criterion::{BenchmarkId, Criterion};
+use orderbook_rs::OrderBook;
+use pricelevel::{OrderId, Side, TimeInForce};
+use std::hint::black_box;
+
+/// Register benchmarks of depth analytics on shallow and deep books
+pub fn register_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("OrderBook - Depth Analytics");
+
+    for levels in [100u64, 10_000] {
+        let book = deep_book(levels);
+
+        group.bench_with_input(
+            BenchmarkId::new("total_depth_at_levels_10", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.total_depth_at_levels(10, Side::Buy))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("total_depth_all_levels", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.total_depth_at_levels(usize::MAX, Side::Buy))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("order_book_imbalance_10", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.order_book_imbalance(10))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("buy_sell_pressure", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.buy_sell_pressure())),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("depth_statistics_10", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.depth_statistics(Side::Sell, 10))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("depth_statistics_all", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.depth_statistics(Side::Sell, 0))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("depth_distribution_20", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.depth_distribution(Side::Buy, 20))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("price_at_depth", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.price_at_depth(500, Side::Sell))),
+        );
+        group.bench_with_input(BenchmarkId::new("vwap", levels), &levels, |b, _| {
+            b.iter(|| black_box(book.vwap(500, Side::Buy)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("market_impact", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.market_impact(500, Side::Buy))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("levels_until_depth_500", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.levels_until_depth(500, Side::Buy).count())),
+        );
+        group.bench_with_input(BenchmarkId::new("micro_price", levels), &levels, |b, _| {
+            b.iter(|| black_box(book.micro_price()))
+        });
+    }
+
+    group.finish();
+}
+
+/// Book with `levels` bid and ask levels of varying size
+fn deep_book(levels: u64) -> OrderBook<()> {
+    let book = OrderBook::new("TEST-SYMBOL");
+    for offset in 1..=levels {
+        let quantity = 5 + offset % 20;
+        let _ = book.add_limit_order(
+            OrderId::new_uuid(),
+            100_000 - offset,
+            quantity,
+            Side::Buy,
+            TimeInForce::Gtc,
+            None,
+        );
+        let _ = book.add_limit_order(
+            OrderId::new_uuid(),
+            100_000 + offset,
+            quantity,
+            Side::Sell,
+            TimeInForce::Gtc,
+            None,
+        );
+    }
+    book
+}
@@ -1,7 +1,13 @@
 pub mod add_orders;
+pub mod best_price;
+pub mod dense_ladder;
+pub mod depth_analytics;
 pub mod match_orders;
 pub mod matching;
 pub mod mixed_operations;
+pub mod pooling;
+pub mod snapshots;
+pub mod throughput;
 pub mod update_orders;
 
 // Import common benchmarks into the main bench group
@@ -11,4 +17,10 @@ pub fn register_benchmarks(c: &mut criterion::Criterion) {
     update_orders::register_benchmarks(c);
     mixed_operations::register_benchmarks(c);
     matching::register_benchmarks(c);
+    best_price::register_benchmarks(c);
+    dense_ladder::register_benchmarks(c);
+    throughput::register_benchmarks(c);
+    snapshots::register_benchmarks(c);
+    depth_analytics::register_benchmarks(c);
+    pooling::register_benchmarks(c);
 }
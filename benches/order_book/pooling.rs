@@ -0,0 +1,80 @@
+use criterion::{BenchmarkId, Criterion};
+use orderbook_rs::OrderBook;
+use pricelevel::{OrderId, Side, TimeInForce};
+use std::hint::black_box;
+
+/// Register benchmarks comparing the add/cancel path with and without the
+/// level and order pools
+pub fn register_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("OrderBook - Pooling");
+
+    for (name, capacity) in [("pooled", None), ("unpooled", Some(0))] {
+        // Orders added and cancelled one at a time over a few price levels,
+        // emptying a level on every cancel and handing the returned handles back
+        group.bench_with_input(
+            BenchmarkId::new("add_cancel_churn", name),
+            &capacity,
+            |b, &capacity| {
+                let order_book = pooled_book(capacity);
+                let mut price = 0;
+                b.iter(|| {
+                    let id = OrderId::new_uuid();
+                    price = (price + 1) % 8;
+                    if let Ok(added) = order_book.add_limit_order(
+                        id,
+                        1000 + price,
+                        10,
+                        Side::Buy,
+                        TimeInForce::Gtc,
+                        None,
+                    ) {
+                        black_box(order_book.recycle_order_handle(added));
+                    }
+                    if let Ok(Some(cancelled)) = order_book.cancel_order(id) {
+                        black_box(order_book.recycle_order_handle(cancelled));
+                    }
+                })
+            },
+        );
+
+        // Orders resting and then swept by a market order
+        group.bench_with_input(
+            BenchmarkId::new("add_sweep", name),
+            &capacity,
+            |b, &capacity| {
+                let order_book = pooled_book(capacity);
+                b.iter(|| {
+                    for i in 0..10 {
+                        if let Ok(added) = order_book.add_limit_order(
+                            OrderId::new_uuid(),
+                            1000 + i,
+                            10,
+                            Side::Sell,
+                            TimeInForce::Gtc,
+                            None,
+                        ) {
+                            black_box(order_book.recycle_order_handle(added));
+                        }
+                    }
+                    let _ = black_box(order_book.submit_market_order(
+                        OrderId::new_uuid(),
+                        100,
+                        Side::Buy,
+                    ));
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Creates a book, disabling both pools when `capacity` is set
+fn pooled_book(capacity: Option<usize>) -> OrderBook {
+    let mut order_book: OrderBook = OrderBook::new("TEST-SYMBOL");
+    if let Some(capacity) = capacity {
+        order_book.set_level_pool_capacity(capacity);
+        order_book.set_order_pool_capacity(capacity);
+    }
+    order_book
+}
@@ -0,0 +1,73 @@
+use criterion::{BenchmarkId, Criterion};
+use orderbook_rs::OrderBook;
+use pricelevel::{OrderId, Side, TimeInForce};
+use std::hint::black_box;
+
+/// Register benchmarks of snapshot creation on books of increasing depth
+pub fn register_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("OrderBook - Snapshots");
+
+    for levels in [100u64, 1_000, 10_000] {
+        let book = deep_book(levels);
+
+        group.bench_with_input(
+            BenchmarkId::new("create_snapshot_top_10", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.create_snapshot(10))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("create_snapshot_full", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.create_snapshot(usize::MAX))),
+        );
+        let mut buffer = book.create_snapshot(10);
+        group.bench_with_input(
+            BenchmarkId::new("create_snapshot_into_top_10", levels),
+            &levels,
+            |b, _| {
+                b.iter(|| {
+                    book.create_snapshot_into(10, &mut buffer);
+                    black_box(buffer.bids.len())
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("create_snapshot_package_top_10", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.create_snapshot_package(10))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("enriched_snapshot_top_10", levels),
+            &levels,
+            |b, _| b.iter(|| black_box(book.enriched_snapshot(10))),
+        );
+    }
+
+    group.finish();
+}
+
+/// Book with `levels` bid and ask levels of three orders each
+fn deep_book(levels: u64) -> OrderBook<()> {
+    let book = OrderBook::new("TEST-SYMBOL");
+    for offset in 1..=levels {
+        for _ in 0..3 {
+            let _ = book.add_limit_order(
+                OrderId::new_uuid(),
+                100_000 - offset,
+                10,
+                Side::Buy,
+                TimeInForce::Gtc,
+                None,
+            );
+            let _ = book.add_limit_order(
+                OrderId::new_uuid(),
+                100_000 + offset,
+                10,
+                Side::Sell,
+                TimeInForce::Gtc,
+                None,
+            );
+        }
+    }
+    book
+}
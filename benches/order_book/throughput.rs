@@ -0,0 +1,129 @@
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput};
+use orderbook_rs::OrderBook;
+use pricelevel::{OrderId, Side, TimeInForce};
+use std::hint::black_box;
+
+/// Register benchmarks of add, cancel and match throughput on prepared books
+pub fn register_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("OrderBook - Throughput");
+
+    for count in [1_000u64, 10_000] {
+        group.throughput(Throughput::Elements(count));
+
+        group.bench_with_input(
+            BenchmarkId::new("add_orders", count),
+            &count,
+            |b, &count| {
+                b.iter_batched(
+                    || (OrderBook::<()>::new("TEST-SYMBOL"), order_ids(count)),
+                    |(book, ids)| {
+                        for (index, id) in ids.into_iter().enumerate() {
+                            let _ = black_box(book.add_limit_order(
+                                id,
+                                price(index),
+                                10,
+                                Side::Buy,
+                                TimeInForce::Gtc,
+                                None,
+                            ));
+                        }
+                        book
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("cancel_orders", count),
+            &count,
+            |b, &count| {
+                b.iter_batched(
+                    || resting_book(count, Side::Buy),
+                    |(book, ids)| {
+                        for id in ids {
+                            let _ = black_box(book.cancel_order(id));
+                        }
+                        book
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("match_orders", count),
+            &count,
+            |b, &count| {
+                b.iter_batched(
+                    || resting_book(count, Side::Sell),
+                    |(book, _)| {
+                        // Each market order fills one resting order
+                        for _ in 0..count {
+                            let _ = black_box(book.submit_market_order(
+                                OrderId::new_uuid(),
+                                10,
+                                Side::Buy,
+                            ));
+                        }
+                        book
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("engine_add_orders", count),
+            &count,
+            |b, &count| {
+                b.iter_batched(
+                    || {
+                        let engine = OrderBook::<()>::new("TEST-SYMBOL").into_engine();
+                        (engine, order_ids(count))
+                    },
+                    |(engine, ids)| {
+                        let handle = engine.handle();
+                        for (index, id) in ids.into_iter().enumerate() {
+                            let _ = handle.dispatch(move |book| {
+                                let _ = book.add_limit_order(
+                                    id,
+                                    price(index),
+                                    10,
+                                    Side::Buy,
+                                    TimeInForce::Gtc,
+                                    None,
+                                );
+                            });
+                        }
+                        // Wait until the matching thread applied every order
+                        black_box(handle.submit(|book| book.best_bid()).wait().ok());
+                        engine
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn order_ids(count: u64) -> Vec<OrderId> {
+    (0..count).map(|_| OrderId::new_uuid()).collect()
+}
+
+/// Spreads orders over 100 price levels
+fn price(index: usize) -> u64 {
+    10_000 + (index % 100) as u64
+}
+
+/// Book with `count` resting orders on `side` and their IDs
+fn resting_book(count: u64, side: Side) -> (OrderBook<()>, Vec<OrderId>) {
+    let book = OrderBook::new("TEST-SYMBOL");
+    let ids = order_ids(count);
+    for (index, &id) in ids.iter().enumerate() {
+        let _ = book.add_limit_order(id, price(index), 10, side, TimeInForce::Gtc, None);
+    }
+    (book, ids)
+}
@@ -0,0 +1,98 @@
+use criterion::Criterion;
+use orderbook_rs::wire::{WireEncoder, WireMessage};
+use orderbook_rs::{LevelChange, OrderBook, OrderBookDelta, OrderBookSnapshot};
+use pricelevel::{OrderId, Side, TimeInForce};
+use std::hint::black_box;
+
+/// Register benchmarks comparing the FlatBuffers wire format with JSON
+pub fn register_benchmarks(c: &mut Criterion) {
+    let snapshot = snapshot(100);
+    let delta = delta(100);
+    let mut encoder = WireEncoder::new();
+
+    let mut group = c.benchmark_group("Wire - Snapshot (100 levels per side)");
+    group.bench_function("json_encode", |b| {
+        b.iter(|| black_box(serde_json::to_vec(black_box(&snapshot)).unwrap()))
+    });
+    group.bench_function("wire_encode", |b| {
+        b.iter(|| black_box(encoder.encode_snapshot(black_box(&snapshot)).len()))
+    });
+
+    let json = serde_json::to_vec(&snapshot).unwrap();
+    let wire = encoder.encode_snapshot(&snapshot).to_vec();
+    group.bench_function("json_decode_top_of_book", |b| {
+        b.iter(|| {
+            let decoded: OrderBookSnapshot = serde_json::from_slice(black_box(&json)).unwrap();
+            black_box((decoded.best_bid(), decoded.best_ask()))
+        })
+    });
+    group.bench_function("wire_decode_top_of_book", |b| {
+        b.iter(|| match WireMessage::decode(black_box(&wire)).unwrap() {
+            WireMessage::Snapshot(view) => black_box((view.best_bid(), view.best_ask())),
+            WireMessage::Delta(_) => unreachable!(),
+        })
+    });
+    group.bench_function("wire_decode_all_levels", |b| {
+        b.iter(|| match WireMessage::decode(black_box(&wire)).unwrap() {
+            WireMessage::Snapshot(view) => black_box(
+                view.bids()
+                    .iter()
+                    .chain(view.asks().iter())
+                    .map(|level| level.visible_quantity)
+                    .sum::<u64>(),
+            ),
+            WireMessage::Delta(_) => unreachable!(),
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("Wire - Delta (100 changes)");
+    group.bench_function("json_encode", |b| {
+        b.iter(|| black_box(serde_json::to_vec(black_box(&delta)).unwrap()))
+    });
+    group.bench_function("wire_encode", |b| {
+        b.iter(|| black_box(encoder.encode_delta(black_box(&delta)).len()))
+    });
+
+    let json = serde_json::to_vec(&delta).unwrap();
+    let wire = encoder.encode_delta(&delta).to_vec();
+    group.bench_function("json_decode", |b| {
+        b.iter(|| {
+            let decoded: OrderBookDelta = serde_json::from_slice(black_box(&json)).unwrap();
+            black_box(decoded.changes.len())
+        })
+    });
+    group.bench_function("wire_decode", |b| {
+        b.iter(|| match WireMessage::decode(black_box(&wire)).unwrap() {
+            WireMessage::Delta(view) => black_box(view.changes().count()),
+            WireMessage::Snapshot(_) => unreachable!(),
+        })
+    });
+    group.finish();
+}
+
+fn snapshot(levels: u64) -> OrderBookSnapshot {
+    let book: OrderBook = OrderBook::new("BENCH");
+    for i in 0..levels {
+        for (price, side) in [(10_000 - i, Side::Buy), (10_001 + i, Side::Sell)] {
+            book.add_limit_order(OrderId::new(), price, 10 + i, side, TimeInForce::Gtc, None)
+                .unwrap();
+        }
+    }
+    book.create_snapshot(levels as usize)
+}
+
+fn delta(changes: u64) -> OrderBookDelta {
+    OrderBookDelta {
+        symbol: "BENCH".to_string(),
+        from_sequence: 1,
+        to_sequence: changes + 1,
+        changes: (0..changes)
+            .map(|i| LevelChange::Updated {
+                side: if i % 2 == 0 { Side::Buy } else { Side::Sell },
+                price: 10_000 + i,
+                quantity: 5 + i,
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,109 @@
+// examples/src/bin/binance_depth_mirror.rs
+//
+// This example mirrors live Binance spot books into a BookManager through the
+// `BinanceAdapter`, using the combined partial depth and trade streams, and
+// reports the top of book and trades as they arrive.
+//
+// The stream is followed for ten seconds; `mirror_feed` does the same until the
+// exchange closes the connection.
+//
+// Run this example with:
+//   cargo run --bin binance_depth_mirror -- [symbol ...]
+//   (from the examples directory, requires network access)
+//
+// Symbols default to BTC/USDT and ETH/USDT. Prices are mirrored in cents and
+// quantities with 5 decimal places.
+
+use orderbook_rs::feeds::{BinanceAdapter, BookFeedAdapter, FeedEvent, apply_feed_event};
+use orderbook_rs::{BookManager, BookManagerStd};
+use pricelevel::setup_logger;
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+
+fn main() {
+    setup_logger();
+    info!("Binance Depth Mirror Example");
+
+    let mut symbols: Vec<String> = std::env::args().skip(1).collect();
+    if symbols.is_empty() {
+        symbols = vec!["BTC/USDT".to_string(), "ETH/USDT".to_string()];
+    }
+
+    let mut manager = BookManagerStd::<()>::new();
+    for symbol in &symbols {
+        manager.add_book(symbol);
+    }
+
+    let mut adapter = BinanceAdapter::new()
+        .with_price_decimals(2)
+        .with_quantity_decimals(5);
+    if let Err(e) = adapter
+        .connect()
+        .and_then(|()| adapter.subscribe(&manager.symbols()))
+    {
+        error!("Cannot subscribe to Binance: {}", e);
+        return;
+    }
+
+    let started = Instant::now();
+    let mut last_report = Instant::now();
+    let mut depth_events = 0;
+    for event in adapter.events() {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                error!("Feed failed: {}", e);
+                break;
+            }
+        };
+
+        match &event {
+            FeedEvent::Trade {
+                symbol,
+                price,
+                quantity,
+                taker_side,
+                ..
+            } => info!(
+                "{} trade: {} {:.5} @ {:.2}",
+                symbol,
+                taker_side,
+                *quantity as f64 / 100_000.0,
+                *price as f64 / 100.0
+            ),
+            _ => match apply_feed_event(&manager, &event) {
+                Ok(true) => depth_events += 1,
+                Ok(false) => {}
+                Err(e) => error!("Cannot apply depth: {}", e),
+            },
+        }
+
+        if last_report.elapsed() >= Duration::from_secs(1) {
+            last_report = Instant::now();
+            report(&manager, &symbols);
+        }
+        if started.elapsed() >= Duration::from_secs(10) {
+            break;
+        }
+    }
+
+    info!("Applied {} depth events", depth_events);
+}
+
+fn report(manager: &BookManagerStd<()>, symbols: &[String]) {
+    for symbol in symbols {
+        let Some(book) = manager.get_book(symbol) else {
+            continue;
+        };
+        match (book.best_bid(), book.best_ask()) {
+            (Some(bid), Some(ask)) => info!(
+                "{}: bid {:.2} / ask {:.2}, imbalance {:+.3}",
+                symbol,
+                bid as f64 / 100.0,
+                ask as f64 / 100.0,
+                book.order_book_imbalance(5)
+            ),
+            _ => info!("{}: waiting for depth", symbol),
+        }
+    }
+}
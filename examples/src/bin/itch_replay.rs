@@ -0,0 +1,166 @@
+// examples/src/bin/itch_replay.rs
+//
+// This example replays a NASDAQ TotalView-ITCH 5.0 capture into an order book
+// for a single stock and prints the resulting top of book and depth.
+//
+// Capture files, as published by NASDAQ, hold each message preceded by its
+// length as a big-endian u16. Prices are in 1/10,000 of a dollar.
+//
+// Run this example with:
+//   cargo run --bin itch_replay -- <capture file> <stock>
+//   (from the examples directory)
+//
+// Without arguments, a small synthetic capture for AAPL is replayed instead.
+
+use orderbook_rs::OrderBook;
+use orderbook_rs::feeds::{ItchBook, ItchReader};
+use pricelevel::setup_logger;
+use std::fs::File;
+use std::io::BufReader;
+use std::time::Instant;
+use tracing::{error, info};
+
+fn main() {
+    setup_logger();
+    info!("ITCH 5.0 Replay Example");
+
+    let args: Vec<String> = std::env::args().collect();
+    let (stock, result) = match args.as_slice() {
+        [_, path, stock, ..] => {
+            info!("Replaying {} from {}", stock, path);
+            let file = match File::open(path) {
+                Ok(file) => file,
+                Err(e) => {
+                    error!("Cannot open {}: {}", path, e);
+                    return;
+                }
+            };
+            let mut itch = ItchBook::new(OrderBook::<()>::new(stock), stock);
+            let started = Instant::now();
+            let result = itch
+                .replay(ItchReader::new(BufReader::new(file)))
+                .map(|applied| (itch, applied, started.elapsed()));
+            (stock.clone(), result)
+        }
+        _ => {
+            info!("No capture given, replaying a synthetic AAPL capture");
+            let capture = synthetic_capture();
+            let mut itch = ItchBook::new(OrderBook::<()>::new("AAPL"), "AAPL");
+            let started = Instant::now();
+            let result = itch
+                .replay(ItchReader::new(capture.as_slice()))
+                .map(|applied| (itch, applied, started.elapsed()));
+            ("AAPL".to_string(), result)
+        }
+    };
+
+    match result {
+        Ok((itch, applied, elapsed)) => {
+            info!(
+                "Applied {} book messages for {} in {:?}",
+                applied, stock, elapsed
+            );
+            display_book(itch.book());
+        }
+        Err(e) => error!("Replay failed: {}", e),
+    }
+}
+
+fn display_book(book: &OrderBook<()>) {
+    let dollars = |price: u64| price as f64 / 10_000.0;
+
+    match (book.best_bid(), book.best_ask()) {
+        (Some(bid), Some(ask)) => info!(
+            "Best bid ${:.4} / best ask ${:.4} (spread ${:.4})",
+            dollars(bid),
+            dollars(ask),
+            dollars(ask.saturating_sub(bid))
+        ),
+        (bid, ask) => info!("Best bid {:?} / best ask {:?}", bid, ask),
+    }
+
+    let (bids, asks) = book.depth(5);
+    info!("Top 5 asks:");
+    for (price, quantity, orders) in asks.iter().rev() {
+        info!(
+            "  ${:>10.4}  {:>8} shares  {:>4} orders",
+            dollars(*price),
+            quantity,
+            orders
+        );
+    }
+    info!("Top 5 bids:");
+    for (price, quantity, orders) in &bids {
+        info!(
+            "  ${:>10.4}  {:>8} shares  {:>4} orders",
+            dollars(*price),
+            quantity,
+            orders
+        );
+    }
+}
+
+/// Builds a short capture with add, execute, cancel, replace and delete messages.
+fn synthetic_capture() -> Vec<u8> {
+    let mut capture = Vec::new();
+    let mut push = |message: Vec<u8>| {
+        capture.extend_from_slice(&(message.len() as u16).to_be_bytes());
+        capture.extend(message);
+    };
+
+    let header = |kind: u8, nanos: u64| {
+        let mut data = vec![kind];
+        data.extend_from_slice(&1u16.to_be_bytes());
+        data.extend_from_slice(&0u16.to_be_bytes());
+        data.extend_from_slice(&nanos.to_be_bytes()[2..]);
+        data
+    };
+    let add = |order_ref: u64, side: u8, shares: u32, price: u32| {
+        let mut data = header(b'A', 34_200_000_000_000 + order_ref);
+        data.extend_from_slice(&order_ref.to_be_bytes());
+        data.push(side);
+        data.extend_from_slice(&shares.to_be_bytes());
+        data.extend_from_slice(b"AAPL    ");
+        data.extend_from_slice(&price.to_be_bytes());
+        data
+    };
+    let with_shares = |kind: u8, order_ref: u64, shares: u32| {
+        let mut data = header(kind, 34_300_000_000_000);
+        data.extend_from_slice(&order_ref.to_be_bytes());
+        data.extend_from_slice(&shares.to_be_bytes());
+        if kind == b'E' {
+            data.extend_from_slice(&order_ref.to_be_bytes());
+        }
+        data
+    };
+
+    for i in 0..5u32 {
+        push(add(
+            u64::from(i) + 1,
+            b'B',
+            100 * (i + 1),
+            1_899_500 - i * 100,
+        ));
+        push(add(
+            u64::from(i) + 11,
+            b'S',
+            100 * (i + 1),
+            1_900_500 + i * 100,
+        ));
+    }
+    push(with_shares(b'E', 11, 60));
+    push(with_shares(b'X', 1, 50));
+
+    let mut replace = header(b'U', 34_400_000_000_000);
+    replace.extend_from_slice(&2u64.to_be_bytes());
+    replace.extend_from_slice(&21u64.to_be_bytes());
+    replace.extend_from_slice(&150u32.to_be_bytes());
+    replace.extend_from_slice(&1_900_000u32.to_be_bytes());
+    push(replace);
+
+    let mut delete = header(b'D', 34_500_000_000_000);
+    delete.extend_from_slice(&15u64.to_be_bytes());
+    push(delete);
+
+    capture
+}
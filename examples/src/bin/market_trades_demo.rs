@@ -7,7 +7,7 @@
 //! 4. Use TradeListener to capture trades in real-time
 
 use orderbook_rs::prelude::{
-    OrderBook, OrderId, Side, TimeInForce, TradeInfo, TradeListener, TradeResult, TransactionInfo,
+    OrderBook, OrderId, Side, TimeInForce, TradeInfo, TradeListener, TradeResult,
 };
 use pricelevel::setup_logger;
 use std::sync::{Arc, Mutex};
@@ -66,7 +66,7 @@ fn main() {
 
 /// Add bid orders (buy side) to the order book
 fn add_bid_orders(book: &OrderBook) {
-    let bid_levels = vec![
+    let bid_levels = [
         (49900, 100), // price, quantity
         (49850, 150),
         (49800, 200),
@@ -99,7 +99,7 @@ fn add_bid_orders(book: &OrderBook) {
 
 /// Add ask orders (sell side) to the order book
 fn add_ask_orders(book: &OrderBook) {
-    let ask_levels = vec![
+    let ask_levels = [
         (50100, 100), // price, quantity
         (50150, 150),
         (50200, 200),
@@ -180,30 +180,7 @@ fn execute_market_orders(book: &OrderBook) {
 
 /// Helper function to create TradeInfo from TradeResult
 fn create_trade_info_from_result(trade_result: &TradeResult) -> TradeInfo {
-    let match_result = &trade_result.match_result;
-
-    let transactions: Vec<TransactionInfo> = match_result
-        .transactions
-        .as_vec()
-        .iter()
-        .map(|tx| TransactionInfo {
-            price: tx.price,
-            quantity: tx.quantity,
-            transaction_id: tx.transaction_id.to_string(),
-            maker_order_id: tx.maker_order_id.to_string(),
-            taker_order_id: tx.taker_order_id.to_string(),
-        })
-        .collect();
-
-    TradeInfo {
-        symbol: trade_result.symbol.clone(),
-        order_id: match_result.order_id.to_string(),
-        executed_quantity: match_result.executed_quantity(),
-        remaining_quantity: match_result.remaining_quantity,
-        is_complete: match_result.is_complete,
-        transaction_count: match_result.transactions.transactions.len(),
-        transactions: transactions,
-    }
+    TradeInfo::from(trade_result)
 }
 
 /// Display the current state of the order book
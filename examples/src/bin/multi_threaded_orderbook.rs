@@ -30,7 +30,7 @@ fn run_performance_test() {
     populate_orderbook(&book, 1000);
 
     // Create thread performance counters
-    let mut operation_counters = vec![0; THREAD_COUNT];
+    let mut operation_counters = [0; THREAD_COUNT];
 
     // Synchronization barrier to ensure all threads start at the same time
     let barrier = Arc::new(Barrier::new(THREAD_COUNT + 1)); // +1 for main thread
@@ -1,6 +1,6 @@
 mod helpers;
 
-use orderbook_rs::OrderBook;
+use orderbook_rs::{BookConfig, MatchingEngine, OrderBook};
 use pricelevel::{OrderId, Side, TimeInForce, setup_logger};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -13,6 +13,7 @@ use tracing::info;
 const THREAD_COUNT: usize = 12;
 const TEST_DURATION_MS: u64 = 3000; // 3 seconds per test
 const SYMBOL: &str = "TEST/USD";
+const LOCATION_SHARDS: usize = 256; // Order location shards of the sharded mode
 
 fn main() {
     // Set up logging
@@ -47,6 +48,11 @@ fn run_tests() -> Result<(), String> {
         Err(e) => return Err(format!("Price Level Distribution test failed: {}", e)),
     }
 
+    match test_engine_mode_hot_spot() {
+        Ok(_) => info!("Engine Mode Hot Spot test completed successfully"),
+        Err(e) => return Err(format!("Engine Mode Hot Spot test failed: {}", e)),
+    }
+
     Ok(())
 }
 
@@ -177,10 +183,10 @@ fn test_read_write_ratio() -> Result<(), String> {
                 }
 
                 // Update the operation counter
-                if let Ok(mut counters) = thread_counters.lock() {
-                    if thread_id < counters.len() {
-                        counters[thread_id] = local_counter;
-                    }
+                if let Ok(mut counters) = thread_counters.lock()
+                    && thread_id < counters.len()
+                {
+                    counters[thread_id] = local_counter;
                 }
 
                 local_counter
@@ -252,146 +258,160 @@ fn test_hot_spot_contention() -> Result<(), String> {
     let test_cases = [0, 25, 50, 75, 100]; // Percentage targeting hot spot
     let mut results = HashMap::new();
 
-    for &hot_spot_percentage in &test_cases {
-        info!(
-            "\nTesting with {}% operations targeting hot spot...",
-            hot_spot_percentage
-        );
+    // Each case runs against the default order location map and a sharded one
+    let modes = [
+        ("default", BookConfig::default()),
+        (
+            "sharded",
+            BookConfig::default().with_location_shards(LOCATION_SHARDS),
+        ),
+    ];
+
+    for (mode, config) in modes {
+        for &hot_spot_percentage in &test_cases {
+            info!(
+                "\nTesting with {}% operations targeting hot spot ({} locations)...",
+                hot_spot_percentage, mode
+            );
 
-        // Create a fresh order book for each test
-        let order_book = Arc::new(OrderBook::new(SYMBOL));
+            // Create a fresh order book for each test
+            let order_book = Arc::new(OrderBook::new_with_config(SYMBOL, config));
 
-        // Pre-populate with orders (first 20 are hot spot)
-        helpers::setup_orders_for_hot_spot_test(&order_book);
+            // Pre-populate with orders (first 20 are hot spot)
+            helpers::setup_orders_for_hot_spot_test(&order_book);
 
-        // Counter for operations performed by each thread
-        let operation_counters = Arc::new(Mutex::new(vec![0; THREAD_COUNT]));
+            // Counter for operations performed by each thread
+            let operation_counters = Arc::new(Mutex::new(vec![0; THREAD_COUNT]));
 
-        // Flag to signal when to stop the test
-        let running = Arc::new(AtomicBool::new(true));
+            // Flag to signal when to stop the test
+            let running = Arc::new(AtomicBool::new(true));
 
-        // Barrier for synchronized start
-        let barrier = Arc::new(Barrier::new(THREAD_COUNT + 1));
+            // Barrier for synchronized start
+            let barrier = Arc::new(Barrier::new(THREAD_COUNT + 1));
 
-        // Spawn worker threads
-        let mut handles = Vec::with_capacity(THREAD_COUNT);
+            // Spawn worker threads
+            let mut handles = Vec::with_capacity(THREAD_COUNT);
 
-        for thread_id in 0..THREAD_COUNT {
-            let thread_book = Arc::clone(&order_book);
-            let thread_barrier = Arc::clone(&barrier);
-            let thread_running = Arc::clone(&running);
-            let thread_counters = Arc::clone(&operation_counters);
-            let hot_pct = hot_spot_percentage;
+            for thread_id in 0..THREAD_COUNT {
+                let thread_book = Arc::clone(&order_book);
+                let thread_barrier = Arc::clone(&barrier);
+                let thread_running = Arc::clone(&running);
+                let thread_counters = Arc::clone(&operation_counters);
+                let hot_pct = hot_spot_percentage;
 
-            let handle = thread::spawn(move || {
-                // Wait for synchronized start
-                thread_barrier.wait();
+                let handle = thread::spawn(move || {
+                    // Wait for synchronized start
+                    thread_barrier.wait();
 
-                let mut local_counter = 0;
+                    let mut local_counter = 0;
 
-                while thread_running.load(Ordering::Relaxed) {
-                    // Determine if this operation targets the hot spot
-                    let target_hot_spot = (local_counter % 100) < hot_pct;
-
-                    // Choose an order ID based on hot spot decision
-                    let order_id = if target_hot_spot {
-                        // Target one of the first 20 orders (hot spot)
-                        let hot_idx = local_counter % 20;
-                        OrderId::from_u64(hot_idx)
-                    } else {
-                        // Target one of the remaining orders
-                        let cold_idx = 20 + (local_counter % 480);
-                        OrderId::from_u64(cold_idx)
-                    };
-
-                    // Perform operation on the selected order
-                    match local_counter % 3 {
-                        0 => {
-                            // Try to look up the order
-                            let _ = thread_book.get_order(order_id);
-                        }
-                        1 => {
-                            // Try to cancel the order
-                            let _ = thread_book.cancel_order(order_id);
-                        }
-                        _ => {
-                            // Try to modify the order quantity
-                            let update = pricelevel::OrderUpdate::UpdateQuantity {
-                                order_id,
-                                new_quantity: 15,
-                            };
-                            let _ = thread_book.update_order(update);
+                    while thread_running.load(Ordering::Relaxed) {
+                        // Determine if this operation targets the hot spot
+                        let target_hot_spot = (local_counter % 100) < hot_pct;
+
+                        // Choose an order ID based on hot spot decision
+                        let order_id = if target_hot_spot {
+                            // Target one of the first 20 orders (hot spot)
+                            let hot_idx = local_counter % 20;
+                            OrderId::from_u64(hot_idx)
+                        } else {
+                            // Target one of the remaining orders
+                            let cold_idx = 20 + (local_counter % 480);
+                            OrderId::from_u64(cold_idx)
+                        };
+
+                        // Perform operation on the selected order
+                        match local_counter % 3 {
+                            0 => {
+                                // Try to look up the order
+                                let _ = thread_book.get_order(order_id);
+                            }
+                            1 => {
+                                // Try to cancel the order
+                                let _ = thread_book.cancel_order(order_id);
+                            }
+                            _ => {
+                                // Try to modify the order quantity
+                                let update = pricelevel::OrderUpdate::UpdateQuantity {
+                                    order_id,
+                                    new_quantity: 15,
+                                };
+                                let _ = thread_book.update_order(update);
+                            }
                         }
-                    }
 
-                    local_counter += 1;
+                        local_counter += 1;
 
-                    // Small sleep to prevent CPU monopolization
-                    if local_counter % 1000 == 0 {
-                        thread::sleep(Duration::from_micros(1));
+                        // Small sleep to prevent CPU monopolization
+                        if local_counter % 1000 == 0 {
+                            thread::sleep(Duration::from_micros(1));
+                        }
                     }
-                }
 
-                // Update the operation counter
-                if let Ok(mut counters) = thread_counters.lock() {
-                    if thread_id < counters.len() {
+                    // Update the operation counter
+                    if let Ok(mut counters) = thread_counters.lock()
+                        && thread_id < counters.len()
+                    {
                         counters[thread_id] = local_counter;
                     }
-                }
 
-                local_counter
-            });
+                    local_counter
+                });
 
-            handles.push(handle);
-        }
+                handles.push(handle);
+            }
 
-        // Start the test
-        let start_time = Instant::now();
+            // Start the test
+            let start_time = Instant::now();
 
-        // Sincronizamos el inicio de todos los hilos
-        barrier.wait();
+            // Sincronizamos el inicio de todos los hilos
+            barrier.wait();
 
-        // Run for the specified duration
-        thread::sleep(Duration::from_millis(TEST_DURATION_MS));
+            // Run for the specified duration
+            thread::sleep(Duration::from_millis(TEST_DURATION_MS));
 
-        // Signal threads to stop
-        running.store(false, Ordering::Relaxed);
+            // Signal threads to stop
+            running.store(false, Ordering::Relaxed);
 
-        // Wait for all threads to finish
-        let mut total_ops = 0;
-        for (i, handle) in handles.into_iter().enumerate() {
-            match handle.join() {
-                Ok(count) => {
-                    total_ops += count;
-                    info!("Thread {} completed with {} operations", i, count);
-                }
-                Err(_) => {
-                    info!("Thread {} panicked", i);
+            // Wait for all threads to finish
+            let mut total_ops = 0;
+            for (i, handle) in handles.into_iter().enumerate() {
+                match handle.join() {
+                    Ok(count) => {
+                        total_ops += count;
+                        info!("Thread {} completed with {} operations", i, count);
+                    }
+                    Err(_) => {
+                        info!("Thread {} panicked", i);
+                    }
                 }
             }
-        }
 
-        let elapsed = start_time.elapsed();
+            let elapsed = start_time.elapsed();
 
-        // Calculate operations per second
-        let ops_per_second = total_ops as f64 / elapsed.as_secs_f64();
+            // Calculate operations per second
+            let ops_per_second = total_ops as f64 / elapsed.as_secs_f64();
 
-        info!("Completed {} operations in {:?}", total_ops, elapsed);
-        info!("Throughput: {:.2} operations/second", ops_per_second);
+            info!("Completed {} operations in {:?}", total_ops, elapsed);
+            info!("Throughput: {:.2} operations/second", ops_per_second);
 
-        // Store result
-        results.insert(hot_spot_percentage, ops_per_second);
+            // Store result
+            results.insert((mode, hot_spot_percentage), ops_per_second);
+        }
     }
 
     // Print summary table
     info!("\nHot Spot Contention Results:");
     info!("---------------------------");
-    info!("Hot %  |  Operations/second");
-    info!("-------------------------");
+    info!("Hot %  |  Default ops/second  |  Sharded ops/second");
+    info!("---------------------------------------------------");
 
     for &pct in &test_cases {
-        if let Some(&ops) = results.get(&pct) {
-            info!("{}%    |  {:.2}", pct, ops);
+        if let (Some(&default), Some(&sharded)) = (
+            results.get(&("default", pct)),
+            results.get(&("sharded", pct)),
+        ) {
+            info!("{}%    |  {:.2}  |  {:.2}", pct, default, sharded);
         }
     }
 
@@ -488,9 +508,9 @@ fn test_price_level_distribution() -> Result<(), String> {
                             // Add limit buy/sell
                             let side = if op_type == 0 { Side::Buy } else { Side::Sell };
                             let price = if side == Side::Buy {
-                                10000 - (local_counter % max_level as u64) as u64 * 10
+                                10000 - (local_counter % max_level as u64) * 10
                             } else {
-                                10100 + (local_counter % max_level as u64) as u64 * 10
+                                10100 + (local_counter % max_level as u64) * 10
                             };
                             let _ = thread_book.add_limit_order(
                                 OrderId::new_uuid(),
@@ -541,10 +561,10 @@ fn test_price_level_distribution() -> Result<(), String> {
                 }
 
                 // Update the operation counter
-                if let Ok(mut counters) = thread_counters.lock() {
-                    if thread_id < counters.len() {
-                        counters[thread_id] = local_counter as usize;
-                    }
+                if let Ok(mut counters) = thread_counters.lock()
+                    && thread_id < counters.len()
+                {
+                    counters[thread_id] = local_counter as usize;
                 }
 
                 info!(
@@ -643,3 +663,85 @@ fn test_price_level_distribution() -> Result<(), String> {
 
     Ok(())
 }
+
+// Test the hot spot workload with every mutation applied by a single matching thread
+fn test_engine_mode_hot_spot() -> Result<(), String> {
+    info!("\n[TEST] Engine Mode Hot Spot");
+    info!("-------------------------");
+
+    let order_book = OrderBook::new(SYMBOL);
+    helpers::setup_orders_for_hot_spot_test(&order_book);
+    let engine = MatchingEngine::new(order_book);
+
+    let running = Arc::new(AtomicBool::new(true));
+    let barrier = Arc::new(Barrier::new(THREAD_COUNT + 1));
+    let mut handles = Vec::with_capacity(THREAD_COUNT);
+
+    for _ in 0..THREAD_COUNT {
+        let thread_book = Arc::clone(engine.book());
+        let thread_engine = engine.handle();
+        let thread_barrier = Arc::clone(&barrier);
+        let thread_running = Arc::clone(&running);
+
+        handles.push(thread::spawn(move || {
+            thread_barrier.wait();
+
+            let mut local_counter = 0;
+            while thread_running.load(Ordering::Relaxed) {
+                // Every operation targets the hot spot
+                let order_id = OrderId::from_u64(local_counter % 20);
+
+                // Reads go to the book, writes are queued for the matching thread
+                let queued = match local_counter % 3 {
+                    0 => {
+                        let _ = thread_book.get_order(order_id);
+                        Ok(())
+                    }
+                    1 => thread_engine.dispatch(move |book| {
+                        let _ = book.cancel_order(order_id);
+                    }),
+                    _ => thread_engine.dispatch(move |book| {
+                        let _ = book.update_order(pricelevel::OrderUpdate::UpdateQuantity {
+                            order_id,
+                            new_quantity: 15,
+                        });
+                    }),
+                };
+                if queued.is_err() {
+                    break;
+                }
+
+                local_counter += 1;
+            }
+            local_counter
+        }));
+    }
+
+    let start_time = Instant::now();
+    barrier.wait();
+    thread::sleep(Duration::from_millis(TEST_DURATION_MS));
+    running.store(false, Ordering::Relaxed);
+
+    let mut total_ops = 0;
+    for handle in handles {
+        total_ops += handle
+            .join()
+            .map_err(|_| "worker thread panicked".to_string())?;
+    }
+
+    // Wait for the matching thread to apply every queued command
+    engine
+        .handle()
+        .submit(|_| ())
+        .wait()
+        .map_err(|e| e.to_string())?;
+    let elapsed = start_time.elapsed();
+
+    info!("Completed {} operations in {:?}", total_ops, elapsed);
+    info!(
+        "Throughput: {:.2} operations/second",
+        total_ops as f64 / elapsed.as_secs_f64()
+    );
+
+    Ok(())
+}
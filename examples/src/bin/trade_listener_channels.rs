@@ -4,9 +4,12 @@
 //! 1. Use TradeListener with channels for async communication
 //! 2. Manage multiple order books with symbol-aware trade routing
 //! 3. Use BookManager to handle trades from multiple symbols
-//! 4. Demonstrate real-world patterns for trading systems
+//! 4. Route trades to a custom handler, here a per-symbol volume tally
+//! 5. Demonstrate real-world patterns for trading systems
 
-use orderbook_rs::prelude::{BookManager, BookManagerStd, OrderId, Side, TimeInForce};
+use orderbook_rs::prelude::{BookManager, BookManagerStd, OrderId, Side, TimeInForce, TradeEvent};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 use tracing::{info, warn};
@@ -85,8 +88,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("Starting TradeListener channels example");
 
-    // Create a BookManagerStd with unit type (no extra data)
-    let mut manager = BookManagerStd::<()>::new();
+    // Create a BookManagerStd with unit type (no extra data) whose trade
+    // processor tallies the traded volume of every symbol
+    let volumes: Arc<Mutex<HashMap<String, u64>>> = Arc::new(Mutex::new(HashMap::new()));
+    let tally = Arc::clone(&volumes);
+    let mut manager =
+        BookManagerStd::<()>::new().with_handler(Arc::new(move |event: &TradeEvent| {
+            let quantity = event.trade_result.match_result.executed_quantity();
+            info!("Trade on {}: {} units", event.symbol, quantity);
+            *tally
+                .lock()
+                .unwrap()
+                .entry(event.symbol.clone())
+                .or_default() += quantity;
+        }));
 
     // Add multiple order books
     let symbols = vec!["BTC/USD", "ETH/USD", "SOL/USD"];
@@ -95,7 +110,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Start the trade processor
-    let _processor_handle = manager.start_trade_processor();
+    manager.start_trade_processor();
 
     // Add liquidity to all books
     for symbol in &symbols {
@@ -128,13 +143,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    // Wait a bit more for all events to be processed
-    thread::sleep(Duration::from_secs(1));
+    // Process the remaining trade events, stop the processor and keep final snapshots
+    let snapshots = manager.shutdown(Some(5));
+    for (symbol, snapshot) in &snapshots {
+        info!(
+            "{} - Final snapshot: {} bid levels, {} ask levels",
+            symbol,
+            snapshot.bids.len(),
+            snapshot.asks.len()
+        );
+    }
 
-    info!("Example completed successfully");
+    for (symbol, volume) in volumes.lock().unwrap().iter() {
+        info!("{} - Traded volume: {}", symbol, volume);
+    }
 
-    // Note: In a real application, you'd want to gracefully shutdown
-    // the trade processor thread, but for this example we'll just let it finish
+    info!("Example completed successfully");
 
     Ok(())
 }
@@ -143,7 +167,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 mod tests {
     use super::*;
     use orderbook_rs::prelude::{OrderBook, TradeListener, TradeResult};
-    use std::sync::{Arc, mpsc};
+    use std::sync::mpsc;
 
     #[test]
     fn test_book_manager_with_channels() {
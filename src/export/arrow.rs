@@ -0,0 +1,398 @@
+//! Apache Arrow record batches and Parquet files of book data.
+//!
+//! Snapshots, depth ladders and trades convert into Arrow [`RecordBatch`]es with
+//! one row per price level or per transaction, so recordings can be loaded into
+//! pandas, polars or DuckDB without custom parsing. Timestamps are stored as UTC
+//! millisecond timestamps and sides as `"BUY"` / `"SELL"`; prices and quantities
+//! stay in book units.
+//!
+//! A [`ParquetRecorder`] appends batches to one Parquet file as they are produced,
+//! which keeps memory bounded for long recordings.
+
+use crate::orderbook::book::DepthLevel;
+use crate::orderbook::trade::TradeResult;
+use crate::orderbook::{OrderBook, OrderBookError, OrderBookSnapshot};
+use ::arrow::array::{
+    ArrayRef, RecordBatch, StringBuilder, TimestampMillisecondBuilder, UInt32Builder, UInt64Builder,
+};
+use ::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use pricelevel::Side;
+use std::fs::File;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::trace;
+
+/// Data that converts into Arrow record batches with a fixed schema.
+pub trait ArrowRecord: Sized {
+    /// Schema of the batches produced by [`ArrowRecord::to_record_batch`]
+    fn schema() -> SchemaRef;
+
+    /// Converts `records` into a single batch
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::SerializationError` if the batch cannot be built.
+    fn to_record_batch(records: &[Self]) -> Result<RecordBatch, OrderBookError>;
+}
+
+/// The aggregated price levels of a book at a point in time, as returned by
+/// [`OrderBook::depth`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepthLadder {
+    /// Book symbol
+    pub symbol: String,
+    /// Capture time in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// Bid levels, best first
+    pub bids: Vec<DepthLevel>,
+    /// Ask levels, best first
+    pub asks: Vec<DepthLevel>,
+}
+
+impl DepthLadder {
+    /// Captures up to `levels` levels per side of `book`, timestamped by the book's clock
+    pub fn capture<T>(book: &OrderBook<T>, levels: usize) -> Self
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let (bids, asks) = book.depth(levels);
+        Self {
+            symbol: book.symbol().to_string(),
+            timestamp: book.clock().now_millis(),
+            bids,
+            asks,
+        }
+    }
+}
+
+/// One row per price level: symbol, timestamp, side, level (0 is the best),
+/// price, visible and hidden quantity and order count.
+impl ArrowRecord for OrderBookSnapshot {
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("symbol", DataType::Utf8, false),
+            timestamp_field(),
+            Field::new("side", DataType::Utf8, false),
+            Field::new("level", DataType::UInt32, false),
+            Field::new("price", DataType::UInt64, false),
+            Field::new("visible_quantity", DataType::UInt64, false),
+            Field::new("hidden_quantity", DataType::UInt64, false),
+            Field::new("order_count", DataType::UInt64, false),
+        ]))
+    }
+
+    fn to_record_batch(records: &[Self]) -> Result<RecordBatch, OrderBookError> {
+        let mut columns = LevelColumns::default();
+        let mut hidden = UInt64Builder::new();
+        for snapshot in records {
+            let sides = [(Side::Buy, &snapshot.bids), (Side::Sell, &snapshot.asks)];
+            for (side, levels) in sides {
+                for (index, level) in levels.iter().enumerate() {
+                    columns.push(
+                        &snapshot.symbol,
+                        snapshot.timestamp,
+                        side,
+                        index,
+                        (level.price, level.visible_quantity, level.order_count),
+                    )?;
+                    hidden.append_value(level.hidden_quantity);
+                }
+            }
+        }
+
+        let [symbol, timestamp, side, level, price, quantity, orders] = columns.finish();
+        batch(
+            Self::schema(),
+            vec![
+                symbol,
+                timestamp,
+                side,
+                level,
+                price,
+                quantity,
+                Arc::new(hidden.finish()),
+                orders,
+            ],
+        )
+    }
+}
+
+/// One row per price level: symbol, timestamp, side, level (0 is the best),
+/// price, visible quantity and order count.
+impl ArrowRecord for DepthLadder {
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("symbol", DataType::Utf8, false),
+            timestamp_field(),
+            Field::new("side", DataType::Utf8, false),
+            Field::new("level", DataType::UInt32, false),
+            Field::new("price", DataType::UInt64, false),
+            Field::new("quantity", DataType::UInt64, false),
+            Field::new("order_count", DataType::UInt64, false),
+        ]))
+    }
+
+    fn to_record_batch(records: &[Self]) -> Result<RecordBatch, OrderBookError> {
+        let mut columns = LevelColumns::default();
+        for ladder in records {
+            let sides = [(Side::Buy, &ladder.bids), (Side::Sell, &ladder.asks)];
+            for (side, levels) in sides {
+                for (index, level) in levels.iter().enumerate() {
+                    columns.push(&ladder.symbol, ladder.timestamp, side, index, *level)?;
+                }
+            }
+        }
+        batch(Self::schema(), columns.finish().to_vec())
+    }
+}
+
+/// One row per transaction: symbol, timestamp, transaction ID, price, quantity,
+/// taker side and the taker and maker order IDs.
+impl ArrowRecord for TradeResult {
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("symbol", DataType::Utf8, false),
+            timestamp_field(),
+            Field::new("transaction_id", DataType::Utf8, false),
+            Field::new("price", DataType::UInt64, false),
+            Field::new("quantity", DataType::UInt64, false),
+            Field::new("taker_side", DataType::Utf8, false),
+            Field::new("taker_order_id", DataType::Utf8, false),
+            Field::new("maker_order_id", DataType::Utf8, false),
+        ]))
+    }
+
+    fn to_record_batch(records: &[Self]) -> Result<RecordBatch, OrderBookError> {
+        let mut symbol = StringBuilder::new();
+        let mut timestamp = TimestampMillisecondBuilder::new().with_timezone("UTC");
+        let mut transaction_id = StringBuilder::new();
+        let mut price = UInt64Builder::new();
+        let mut quantity = UInt64Builder::new();
+        let mut taker_side = StringBuilder::new();
+        let mut taker_order_id = StringBuilder::new();
+        let mut maker_order_id = StringBuilder::new();
+
+        for trade in records {
+            for transaction in trade.match_result.transactions.as_vec() {
+                symbol.append_value(&trade.symbol);
+                timestamp.append_value(millis(transaction.timestamp)?);
+                transaction_id.append_value(transaction.transaction_id.to_string());
+                price.append_value(transaction.price);
+                quantity.append_value(transaction.quantity);
+                taker_side.append_value(transaction.taker_side.to_string());
+                taker_order_id.append_value(transaction.taker_order_id.to_string());
+                maker_order_id.append_value(transaction.maker_order_id.to_string());
+            }
+        }
+
+        batch(
+            Self::schema(),
+            vec![
+                Arc::new(symbol.finish()),
+                Arc::new(timestamp.finish()),
+                Arc::new(transaction_id.finish()),
+                Arc::new(price.finish()),
+                Arc::new(quantity.finish()),
+                Arc::new(taker_side.finish()),
+                Arc::new(taker_order_id.finish()),
+                Arc::new(maker_order_id.finish()),
+            ],
+        )
+    }
+}
+
+/// Streams records of one kind into a Parquet file.
+///
+/// Every call to [`ParquetRecorder::write`] converts its records into a batch
+/// and hands it to the Parquet writer, which flushes a row group whenever enough
+/// rows are buffered. The file is only readable once [`ParquetRecorder::finish`]
+/// wrote its footer.
+///
+/// # Examples
+/// ```no_run
+/// use orderbook_rs::export::ParquetRecorder;
+/// use orderbook_rs::{DefaultOrderBook, OrderBookSnapshot};
+///
+/// let book = DefaultOrderBook::new("BTC/USD");
+/// let mut recorder = ParquetRecorder::<OrderBookSnapshot, _>::create("book.parquet").unwrap();
+/// for _ in 0..10 {
+///     recorder.write(&[book.create_snapshot(20)]).unwrap();
+/// }
+/// recorder.finish().unwrap();
+/// ```
+pub struct ParquetRecorder<R: ArrowRecord, W: Write + Send> {
+    writer: ArrowWriter<W>,
+    rows: usize,
+    _records: PhantomData<fn(&R)>,
+}
+
+impl<R: ArrowRecord> ParquetRecorder<R, File> {
+    /// Creates or truncates the Parquet file at `path`
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::SerializationError` if the file cannot be created.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, OrderBookError> {
+        let file = File::create(path.as_ref()).map_err(|e| {
+            serialization(format!("cannot create {}: {e}", path.as_ref().display()))
+        })?;
+        Self::new(file)
+    }
+}
+
+impl<R: ArrowRecord, W: Write + Send> ParquetRecorder<R, W> {
+    /// Starts a Parquet stream on `writer`
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::SerializationError` if the file header cannot be written.
+    pub fn new(writer: W) -> Result<Self, OrderBookError> {
+        let writer = ArrowWriter::try_new(writer, R::schema(), None)
+            .map_err(|e| serialization(e.to_string()))?;
+        Ok(Self {
+            writer,
+            rows: 0,
+            _records: PhantomData,
+        })
+    }
+
+    /// Appends `records` to the file
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::SerializationError` if the records cannot be converted or written.
+    pub fn write(&mut self, records: &[R]) -> Result<(), OrderBookError> {
+        let batch = R::to_record_batch(records)?;
+        self.writer
+            .write(&batch)
+            .map_err(|e| serialization(e.to_string()))?;
+        self.rows += batch.num_rows();
+        trace!("Parquet: Wrote {} rows", batch.num_rows());
+        Ok(())
+    }
+
+    /// Flushes the buffered rows as a row group
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::SerializationError` if the row group cannot be written.
+    pub fn flush(&mut self) -> Result<(), OrderBookError> {
+        self.writer
+            .flush()
+            .map_err(|e| serialization(e.to_string()))
+    }
+
+    /// Number of rows written so far
+    pub fn rows_written(&self) -> usize {
+        self.rows
+    }
+
+    /// Writes the buffered rows and the file footer, returning the underlying writer
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::SerializationError` if the file cannot be completed.
+    pub fn finish(self) -> Result<W, OrderBookError> {
+        self.writer
+            .into_inner()
+            .map_err(|e| serialization(e.to_string()))
+    }
+}
+
+/// Writes `records` into a new Parquet file at `path`
+///
+/// # Errors
+/// Returns `OrderBookError::SerializationError` if the file cannot be written.
+pub fn write_parquet<R: ArrowRecord>(
+    path: impl AsRef<Path>,
+    records: &[R],
+) -> Result<usize, OrderBookError> {
+    let mut recorder = ParquetRecorder::<R, File>::create(path)?;
+    recorder.write(records)?;
+    let rows = recorder.rows_written();
+    recorder.finish()?;
+    Ok(rows)
+}
+
+/// Reads every record batch of the Parquet file at `path`
+///
+/// # Errors
+/// Returns `OrderBookError::DeserializationError` if the file cannot be read.
+pub fn read_parquet(path: impl AsRef<Path>) -> Result<Vec<RecordBatch>, OrderBookError> {
+    let deserialization = |message: String| OrderBookError::DeserializationError {
+        message: format!("Parquet error: {message}"),
+    };
+    let file = File::open(path.as_ref())
+        .map_err(|e| deserialization(format!("cannot open {}: {e}", path.as_ref().display())))?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+        .and_then(|builder| builder.build())
+        .map_err(|e| deserialization(e.to_string()))?;
+    reader
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| deserialization(e.to_string()))
+}
+
+/// Columns shared by the snapshot and depth ladder schemas.
+#[derive(Default)]
+struct LevelColumns {
+    symbol: StringBuilder,
+    timestamp: TimestampMillisecondBuilder,
+    side: StringBuilder,
+    level: UInt32Builder,
+    price: UInt64Builder,
+    quantity: UInt64Builder,
+    order_count: UInt64Builder,
+}
+
+impl LevelColumns {
+    fn push(
+        &mut self,
+        symbol: &str,
+        timestamp: u64,
+        side: Side,
+        index: usize,
+        (price, quantity, order_count): DepthLevel,
+    ) -> Result<(), OrderBookError> {
+        self.symbol.append_value(symbol);
+        self.timestamp.append_value(millis(timestamp)?);
+        self.side.append_value(side.to_string());
+        self.level.append_value(index as u32);
+        self.price.append_value(price);
+        self.quantity.append_value(quantity);
+        self.order_count.append_value(order_count as u64);
+        Ok(())
+    }
+
+    fn finish(mut self) -> [ArrayRef; 7] {
+        [
+            Arc::new(self.symbol.finish()),
+            Arc::new(self.timestamp.finish().with_timezone("UTC")),
+            Arc::new(self.side.finish()),
+            Arc::new(self.level.finish()),
+            Arc::new(self.price.finish()),
+            Arc::new(self.quantity.finish()),
+            Arc::new(self.order_count.finish()),
+        ]
+    }
+}
+
+fn timestamp_field() -> Field {
+    Field::new(
+        "timestamp",
+        DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+        false,
+    )
+}
+
+fn millis(timestamp: u64) -> Result<i64, OrderBookError> {
+    i64::try_from(timestamp)
+        .map_err(|_| serialization(format!("timestamp {timestamp} out of range")))
+}
+
+fn batch(schema: SchemaRef, columns: Vec<ArrayRef>) -> Result<RecordBatch, OrderBookError> {
+    RecordBatch::try_new(schema, columns).map_err(|e| serialization(e.to_string()))
+}
+
+fn serialization(message: String) -> OrderBookError {
+    OrderBookError::SerializationError {
+        message: format!("Arrow export error: {message}"),
+    }
+}
@@ -0,0 +1,8 @@
+/// Arrow record batches and Parquet files of snapshots, depth ladders and trades.
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
+mod tests;
+
+#[cfg(feature = "arrow")]
+pub use self::arrow::{ArrowRecord, DepthLadder, ParquetRecorder, read_parquet, write_parquet};
@@ -0,0 +1,168 @@
+//! Unit tests for Arrow and Parquet export.
+
+#[cfg(test)]
+mod tests {
+    use crate::export::arrow::{
+        ArrowRecord, DepthLadder, ParquetRecorder, read_parquet, write_parquet,
+    };
+    use crate::orderbook::trade::TradeResult;
+    use crate::orderbook::{OrderBook, OrderBookSnapshot};
+    use crate::utils::ManualClock;
+    use arrow::array::{Array, AsArray, RecordBatch};
+    use arrow::datatypes::{TimestampMillisecondType, UInt32Type, UInt64Type};
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn book() -> OrderBook<()> {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.set_clock(Arc::new(ManualClock::new(1_700_000_000_000)));
+        for (price, quantity, side) in [
+            (100, 10, Side::Buy),
+            (100, 5, Side::Buy),
+            (99, 7, Side::Buy),
+            (101, 3, Side::Sell),
+        ] {
+            book.add_limit_order(
+                OrderId::new(),
+                price,
+                quantity,
+                side,
+                TimeInForce::Gtc,
+                None,
+            )
+            .unwrap();
+        }
+        book
+    }
+
+    fn strings(batch: &RecordBatch, column: &str) -> Vec<String> {
+        batch
+            .column_by_name(column)
+            .unwrap()
+            .as_string::<i32>()
+            .iter()
+            .map(|value| value.unwrap().to_string())
+            .collect()
+    }
+
+    fn integers(batch: &RecordBatch, column: &str) -> Vec<u64> {
+        batch
+            .column_by_name(column)
+            .unwrap()
+            .as_primitive::<UInt64Type>()
+            .values()
+            .to_vec()
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("orderbook-{name}-{}.parquet", OrderId::new()))
+    }
+
+    #[test]
+    fn test_snapshot_rows_per_level() {
+        let snapshot = book().create_snapshot(10);
+        let batch = OrderBookSnapshot::to_record_batch(&[snapshot]).unwrap();
+
+        assert_eq!(batch.schema(), OrderBookSnapshot::schema());
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(strings(&batch, "side"), vec!["BUY", "BUY", "SELL"]);
+        assert_eq!(integers(&batch, "price"), vec![100, 99, 101]);
+        assert_eq!(integers(&batch, "visible_quantity"), vec![15, 7, 3]);
+        assert_eq!(integers(&batch, "order_count"), vec![2, 1, 1]);
+        let levels = batch.column_by_name("level").unwrap();
+        assert_eq!(levels.as_primitive::<UInt32Type>().values(), &[0, 1, 0]);
+        let timestamps = batch.column_by_name("timestamp").unwrap();
+        assert_eq!(
+            timestamps
+                .as_primitive::<TimestampMillisecondType>()
+                .value(0),
+            1_700_000_000_000
+        );
+    }
+
+    #[test]
+    fn test_depth_ladder_capture() {
+        let book = book();
+        let ladder = DepthLadder::capture(&book, 1);
+
+        assert_eq!(ladder.symbol, "BTC/USD");
+        assert_eq!(ladder.timestamp, 1_700_000_000_000);
+        assert_eq!(ladder.bids, vec![(100, 15, 2)]);
+        assert_eq!(ladder.asks, vec![(101, 3, 1)]);
+
+        let batch = DepthLadder::to_record_batch(&[ladder.clone(), ladder]).unwrap();
+        assert_eq!(batch.num_rows(), 4);
+        assert_eq!(strings(&batch, "symbol"), vec!["BTC/USD"; 4]);
+        assert_eq!(integers(&batch, "quantity"), vec![15, 3, 15, 3]);
+    }
+
+    #[test]
+    fn test_trade_rows_per_transaction() {
+        let book = book();
+        let taker = OrderId::new();
+        let result = book.match_market_order(taker, 12, Side::Sell).unwrap();
+        let trade = TradeResult::new("BTC/USD".to_string(), result);
+
+        let batch = TradeResult::to_record_batch(&[trade]).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(integers(&batch, "price"), vec![100, 100]);
+        assert_eq!(integers(&batch, "quantity"), vec![10, 2]);
+        assert_eq!(strings(&batch, "taker_side"), vec!["SELL", "SELL"]);
+        assert_eq!(
+            strings(&batch, "taker_order_id"),
+            vec![taker.to_string(); 2]
+        );
+        assert_eq!(
+            batch.column_by_name("transaction_id").unwrap().null_count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_empty_records_produce_empty_batch() {
+        let batch = TradeResult::to_record_batch(&[]).unwrap();
+        assert_eq!(batch.num_rows(), 0);
+        assert_eq!(batch.schema(), TradeResult::schema());
+    }
+
+    #[test]
+    fn test_recorder_streams_batches_into_one_file() {
+        let book = book();
+        let path = temp_path("depth");
+
+        let mut recorder = ParquetRecorder::<DepthLadder, _>::create(&path).unwrap();
+        for _ in 0..3 {
+            recorder.write(&[DepthLadder::capture(&book, 10)]).unwrap();
+            recorder.flush().unwrap();
+        }
+        assert_eq!(recorder.rows_written(), 9);
+        recorder.finish().unwrap();
+
+        let batches = read_parquet(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 9);
+        assert_eq!(batches[0].schema(), DepthLadder::schema());
+        assert_eq!(integers(&batches[0], "price")[..3], [100, 99, 101]);
+    }
+
+    #[test]
+    fn test_write_parquet_round_trip() {
+        let snapshot = book().create_snapshot(10);
+        let path = temp_path("snapshot");
+
+        assert_eq!(write_parquet(&path, &[snapshot]).unwrap(), 3);
+        let batches = read_parquet(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(integers(&batches[0], "visible_quantity"), vec![15, 7, 3]);
+
+        assert!(read_parquet(&path).is_err());
+    }
+
+    #[test]
+    fn test_recorder_writes_to_memory() {
+        let recorder = ParquetRecorder::<OrderBookSnapshot, _>::new(Vec::new()).unwrap();
+        let bytes = recorder.finish().unwrap();
+        assert_eq!(&bytes[..4], b"PAR1");
+    }
+}
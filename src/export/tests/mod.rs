@@ -0,0 +1,2 @@
+#[cfg(feature = "arrow")]
+mod arrow;
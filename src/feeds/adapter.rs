@@ -0,0 +1,174 @@
+//! Exchange adapters producing normalized market data events.
+//!
+//! A [`BookFeedAdapter`] connects to an exchange, subscribes to the books of some
+//! symbols and yields [`FeedEvent`]s, which [`mirror_feed`] applies to the books of
+//! a [`BookManager`] so they mirror the exchange's live books.
+
+use crate::orderbook::OrderBookError;
+use crate::orderbook::manager::BookManager;
+use pricelevel::Side;
+use tracing::trace;
+
+/// A normalized market data event. Prices and quantities are in book units.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeedEvent {
+    /// The top levels of a book, replacing every visible level
+    DepthSnapshot {
+        /// Book symbol
+        symbol: String,
+        /// Exchange update ID the snapshot reflects
+        update_id: u64,
+        /// Bid `(price, quantity)` pairs, best first
+        bids: Vec<(u64, u64)>,
+        /// Ask `(price, quantity)` pairs, best first
+        asks: Vec<(u64, u64)>,
+    },
+    /// New quantities of changed levels; a zero quantity removes the level
+    DepthUpdate {
+        /// Book symbol
+        symbol: String,
+        /// First exchange update ID covered by the event
+        first_update_id: u64,
+        /// Last exchange update ID covered by the event
+        final_update_id: u64,
+        /// Changed bid `(price, quantity)` pairs
+        bids: Vec<(u64, u64)>,
+        /// Changed ask `(price, quantity)` pairs
+        asks: Vec<(u64, u64)>,
+    },
+    /// A trade printed on the exchange
+    Trade {
+        /// Book symbol
+        symbol: String,
+        /// Exchange trade ID
+        trade_id: u64,
+        /// Trade price
+        price: u64,
+        /// Traded quantity
+        quantity: u64,
+        /// Side of the aggressive order
+        taker_side: Side,
+        /// Trade time in milliseconds since the Unix epoch
+        timestamp: u64,
+    },
+}
+
+impl FeedEvent {
+    /// Book symbol the event refers to
+    pub fn symbol(&self) -> &str {
+        match self {
+            FeedEvent::DepthSnapshot { symbol, .. }
+            | FeedEvent::DepthUpdate { symbol, .. }
+            | FeedEvent::Trade { symbol, .. } => symbol,
+        }
+    }
+}
+
+/// Source of normalized market data from an exchange.
+///
+/// Adapters are blocking: [`BookFeedAdapter::next_event`] waits for the next event,
+/// so an adapter is usually driven from its own thread.
+pub trait BookFeedAdapter {
+    /// Opens the connection to the exchange
+    ///
+    /// # Errors
+    /// Returns an error if the connection cannot be established.
+    fn connect(&mut self) -> Result<(), OrderBookError>;
+
+    /// Subscribes to the depth and trades of the given book symbols
+    ///
+    /// # Errors
+    /// Returns an error if the adapter is not connected or the request fails.
+    fn subscribe(&mut self, symbols: &[String]) -> Result<(), OrderBookError>;
+
+    /// Waits for the next event, returning `None` once the exchange closes the stream
+    ///
+    /// # Errors
+    /// Returns an error if the connection fails or a message cannot be decoded.
+    fn next_event(&mut self) -> Result<Option<FeedEvent>, OrderBookError>;
+
+    /// Iterator over the remaining events of the stream
+    fn events(&mut self) -> FeedEvents<'_, Self>
+    where
+        Self: Sized,
+    {
+        FeedEvents { adapter: self }
+    }
+}
+
+/// Iterator returned by [`BookFeedAdapter::events`].
+pub struct FeedEvents<'a, A> {
+    adapter: &'a mut A,
+}
+
+impl<A: BookFeedAdapter> Iterator for FeedEvents<'_, A> {
+    type Item = Result<FeedEvent, OrderBookError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.adapter.next_event().transpose()
+    }
+}
+
+/// Applies a depth event to the matching book of `manager`
+///
+/// Returns `true` if a book was changed. Trades and events for symbols without a
+/// book are left to the caller.
+///
+/// # Errors
+/// Returns an error if the book rejects a price.
+pub fn apply_feed_event<T, M>(manager: &M, event: &FeedEvent) -> Result<bool, OrderBookError>
+where
+    T: Clone + Send + Sync + Default + 'static,
+    M: BookManager<T>,
+{
+    let Some(book) = manager.get_book(event.symbol()) else {
+        return Ok(false);
+    };
+    match event {
+        FeedEvent::DepthSnapshot { bids, asks, .. } => book.apply_l2_snapshot(bids, asks)?,
+        FeedEvent::DepthUpdate { bids, asks, .. } => {
+            let levels = bids
+                .iter()
+                .map(|level| (Side::Buy, level))
+                .chain(asks.iter().map(|level| (Side::Sell, level)));
+            for (side, (price, quantity)) in levels {
+                book.apply_l2_update(*price, *quantity, side)?;
+            }
+        }
+        FeedEvent::Trade { .. } => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// Connects `adapter`, subscribes to every book of `manager` and mirrors the
+/// stream into the books until the exchange closes it
+///
+/// Trades are handed to `on_trade`. Returns the number of events applied to books.
+///
+/// # Errors
+/// Returns the first connection, subscription, decoding or apply error.
+pub fn mirror_feed<T, M, A>(
+    adapter: &mut A,
+    manager: &M,
+    mut on_trade: impl FnMut(&FeedEvent),
+) -> Result<usize, OrderBookError>
+where
+    T: Clone + Send + Sync + Default + 'static,
+    M: BookManager<T>,
+    A: BookFeedAdapter,
+{
+    adapter.connect()?;
+    adapter.subscribe(&manager.symbols())?;
+
+    let mut applied = 0;
+    for event in adapter.events() {
+        let event = event?;
+        if matches!(event, FeedEvent::Trade { .. }) {
+            on_trade(&event);
+        } else if apply_feed_event(manager, &event)? {
+            applied += 1;
+        }
+    }
+    trace!("Feed closed after {} book events", applied);
+    Ok(applied)
+}
@@ -0,0 +1,255 @@
+//! Binance spot WebSocket adapter for combined depth and trade streams.
+//!
+//! [`BinanceAdapter`] subscribes to the partial book depth stream
+//! (`<symbol>@depth20@100ms`) and the trade stream (`<symbol>@trade`) of each
+//! symbol over one combined stream connection. Partial depth messages are full
+//! snapshots of the top 20 levels, so no REST snapshot is needed to mirror a book.
+
+use super::adapter::{BookFeedAdapter, FeedEvent};
+use super::decimal::parse_decimal;
+use crate::orderbook::OrderBookError;
+use pricelevel::Side;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::net::TcpStream;
+use tracing::{debug, trace};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+/// Combined stream endpoint of Binance spot.
+pub const BINANCE_STREAM_ENDPOINT: &str = "wss://stream.binance.com:9443/stream";
+
+/// Mirrors Binance spot books through a WebSocket connection.
+///
+/// Book symbols are mapped to Binance symbols by dropping separators and
+/// lowercasing, so `"BTC/USDT"` subscribes to `btcusdt`. Binance prices and
+/// quantities are decimal strings, converted to book units with the configured
+/// number of decimal places.
+///
+/// # Examples
+/// ```no_run
+/// use orderbook_rs::feeds::{BinanceAdapter, mirror_feed};
+/// use orderbook_rs::{BookManager, BookManagerStd};
+///
+/// let mut manager = BookManagerStd::<()>::new();
+/// manager.add_book("BTC/USDT");
+///
+/// let mut adapter = BinanceAdapter::new().with_price_decimals(2).with_quantity_decimals(5);
+/// mirror_feed(&mut adapter, &manager, |trade| println!("{trade:?}")).unwrap();
+/// ```
+pub struct BinanceAdapter {
+    endpoint: String,
+    price_decimals: u32,
+    quantity_decimals: u32,
+    /// Book symbol of each subscribed Binance stream symbol
+    symbols: HashMap<String, String>,
+    socket: Option<WebSocket<MaybeTlsStream<TcpStream>>>,
+    next_request_id: u64,
+}
+
+impl Default for BinanceAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BinanceAdapter {
+    /// Create an adapter for the Binance spot endpoint
+    ///
+    /// Prices and quantities have no decimal places until configured otherwise.
+    pub fn new() -> Self {
+        Self {
+            endpoint: BINANCE_STREAM_ENDPOINT.to_string(),
+            price_decimals: 0,
+            quantity_decimals: 0,
+            symbols: HashMap::new(),
+            socket: None,
+            next_request_id: 1,
+        }
+    }
+
+    /// Connects to another combined stream endpoint, e.g. the testnet
+    #[must_use]
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_string();
+        self
+    }
+
+    /// Sets the number of decimal places of a book price, e.g. 2 for cents
+    #[must_use]
+    pub fn with_price_decimals(mut self, decimals: u32) -> Self {
+        self.price_decimals = decimals;
+        self
+    }
+
+    /// Sets the number of decimal places of a book quantity
+    #[must_use]
+    pub fn with_quantity_decimals(mut self, decimals: u32) -> Self {
+        self.quantity_decimals = decimals;
+        self
+    }
+
+    /// Binance stream symbol of a book symbol
+    pub fn stream_symbol(symbol: &str) -> String {
+        symbol
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    }
+
+    /// Decodes a combined stream message
+    ///
+    /// Returns `None` for subscription responses and streams of unsubscribed symbols.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::DeserializationError` if the message is not valid JSON
+    /// or a depth or trade payload is malformed.
+    pub fn decode(&self, text: &str) -> Result<Option<FeedEvent>, OrderBookError> {
+        let message: Value = serde_json::from_str(text).map_err(|e| invalid(e.to_string()))?;
+        let (Some(stream), Some(data)) = (message["stream"].as_str(), message.get("data")) else {
+            return Ok(None);
+        };
+        let Some((stream_symbol, kind)) = stream.split_once('@') else {
+            return Ok(None);
+        };
+        let Some(symbol) = self.symbols.get(stream_symbol).cloned() else {
+            return Ok(None);
+        };
+
+        if kind.starts_with("depth") {
+            return Ok(Some(FeedEvent::DepthSnapshot {
+                symbol,
+                update_id: integer(data, "lastUpdateId")?,
+                bids: self.levels(&data["bids"])?,
+                asks: self.levels(&data["asks"])?,
+            }));
+        }
+        if kind == "trade" {
+            let buyer_is_maker = data["m"]
+                .as_bool()
+                .ok_or_else(|| invalid("trade without maker flag".to_string()))?;
+            return Ok(Some(FeedEvent::Trade {
+                symbol,
+                trade_id: integer(data, "t")?,
+                price: parse_decimal(string(data, "p")?, self.price_decimals)?,
+                quantity: parse_decimal(string(data, "q")?, self.quantity_decimals)?,
+                taker_side: if buyer_is_maker {
+                    Side::Sell
+                } else {
+                    Side::Buy
+                },
+                timestamp: integer(data, "T")?,
+            }));
+        }
+        Ok(None)
+    }
+
+    /// Routes the streams of `symbol` to its book, returning its stream symbol.
+    pub(crate) fn register(&mut self, symbol: &str) -> String {
+        let stream_symbol = Self::stream_symbol(symbol);
+        self.symbols
+            .insert(stream_symbol.clone(), symbol.to_string());
+        stream_symbol
+    }
+
+    fn levels(&self, levels: &Value) -> Result<Vec<(u64, u64)>, OrderBookError> {
+        let levels = levels
+            .as_array()
+            .ok_or_else(|| invalid("depth without levels".to_string()))?;
+        levels
+            .iter()
+            .map(|level| match (level[0].as_str(), level[1].as_str()) {
+                (Some(price), Some(quantity)) => Ok((
+                    parse_decimal(price, self.price_decimals)?,
+                    parse_decimal(quantity, self.quantity_decimals)?,
+                )),
+                _ => Err(invalid(format!("malformed depth level {level}"))),
+            })
+            .collect()
+    }
+
+    fn socket(&mut self) -> Result<&mut WebSocket<MaybeTlsStream<TcpStream>>, OrderBookError> {
+        self.socket
+            .as_mut()
+            .ok_or_else(|| OrderBookError::InvalidOperation {
+                message: "Binance adapter is not connected".to_string(),
+            })
+    }
+}
+
+impl BookFeedAdapter for BinanceAdapter {
+    fn connect(&mut self) -> Result<(), OrderBookError> {
+        debug!("Binance: Connecting to {}", self.endpoint);
+        let (socket, _) = tungstenite::connect(self.endpoint.as_str()).map_err(connection)?;
+        self.socket = Some(socket);
+        Ok(())
+    }
+
+    fn subscribe(&mut self, symbols: &[String]) -> Result<(), OrderBookError> {
+        let mut params = Vec::with_capacity(symbols.len() * 2);
+        for symbol in symbols {
+            let stream_symbol = self.register(symbol);
+            params.push(format!("{stream_symbol}@depth20@100ms"));
+            params.push(format!("{stream_symbol}@trade"));
+        }
+
+        let request = serde_json::json!({
+            "method": "SUBSCRIBE",
+            "params": params,
+            "id": self.next_request_id,
+        });
+        self.next_request_id += 1;
+        debug!("Binance: Subscribing to {:?}", params);
+        self.socket()?
+            .send(Message::text(request.to_string()))
+            .map_err(connection)
+    }
+
+    fn next_event(&mut self) -> Result<Option<FeedEvent>, OrderBookError> {
+        loop {
+            let message = match self.socket()?.read() {
+                Ok(message) => message,
+                Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                    return Ok(None);
+                }
+                Err(e) => return Err(connection(e)),
+            };
+            match message {
+                Message::Text(text) => {
+                    if let Some(event) = self.decode(text.as_str())? {
+                        return Ok(Some(event));
+                    }
+                    trace!("Binance: Skipping {}", text.as_str());
+                }
+                Message::Close(_) => return Ok(None),
+                // Pings are answered by the socket itself
+                _ => {}
+            }
+        }
+    }
+}
+
+fn integer(data: &Value, field: &str) -> Result<u64, OrderBookError> {
+    data[field]
+        .as_u64()
+        .ok_or_else(|| invalid(format!("missing integer field {field}")))
+}
+
+fn string<'a>(data: &'a Value, field: &str) -> Result<&'a str, OrderBookError> {
+    data[field]
+        .as_str()
+        .ok_or_else(|| invalid(format!("missing string field {field}")))
+}
+
+fn invalid(reason: String) -> OrderBookError {
+    OrderBookError::DeserializationError {
+        message: format!("Invalid Binance message: {reason}"),
+    }
+}
+
+fn connection(e: tungstenite::Error) -> OrderBookError {
+    OrderBookError::InvalidOperation {
+        message: format!("Binance connection error: {e}"),
+    }
+}
@@ -0,0 +1,41 @@
+//! Conversion between exchange decimal strings and the book's integer units.
+
+use crate::orderbook::OrderBookError;
+
+/// Formats `value` as a decimal with `decimals` implied decimal places.
+#[cfg(feature = "fix")]
+pub(crate) fn format_decimal(value: u64, decimals: u32) -> String {
+    if decimals == 0 {
+        return value.to_string();
+    }
+    let scale = 10u64.pow(decimals);
+    format!(
+        "{}.{:0width$}",
+        value / scale,
+        value % scale,
+        width = decimals as usize
+    )
+}
+
+/// Parses a non-negative decimal into an integer with `decimals` implied decimal places.
+///
+/// Trailing zeros beyond `decimals` are accepted, other digits are not.
+pub(crate) fn parse_decimal(value: &str, decimals: u32) -> Result<u64, OrderBookError> {
+    let invalid = || OrderBookError::DeserializationError {
+        message: format!("{value:?} is not a decimal with at most {decimals} places"),
+    };
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let fraction = fraction.trim_end_matches('0');
+    if whole.is_empty()
+        || fraction.len() > decimals as usize
+        || !whole
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    let padded = format!("{whole}{fraction:0<width$}", width = decimals as usize);
+    padded.parse::<u64>().map_err(|_| invalid())
+}
@@ -0,0 +1,493 @@
+//! FIX 4.4 market data: full refresh (`35=W`) and incremental refresh (`35=X`).
+//!
+//! [`FixMarketData`] applies market data messages received from a FIX session to
+//! an `OrderBook`, and generates them from a local book and its deltas, so a book
+//! can sit on either side of a market data session. Entries are aggregated by
+//! price level; prices and sizes are converted between FIX decimals and the book's
+//! integer units with a configurable number of decimal places.
+
+use super::decimal::{format_decimal, parse_decimal};
+use crate::orderbook::{LevelChange, OrderBook, OrderBookDelta, OrderBookError};
+use pricelevel::Side;
+use tracing::trace;
+
+/// Field separator of the FIX tag=value encoding.
+pub const SOH: char = '\u{1}';
+
+const BEGIN_STRING: &str = "FIX.4.4";
+
+const TAG_BEGIN_STRING: u32 = 8;
+const TAG_BODY_LENGTH: u32 = 9;
+const TAG_CHECKSUM: u32 = 10;
+const TAG_MSG_SEQ_NUM: u32 = 34;
+const TAG_MSG_TYPE: u32 = 35;
+const TAG_SENDER_COMP_ID: u32 = 49;
+const TAG_SENDING_TIME: u32 = 52;
+const TAG_SYMBOL: u32 = 55;
+const TAG_TARGET_COMP_ID: u32 = 56;
+const TAG_NO_MD_ENTRIES: u32 = 268;
+const TAG_MD_ENTRY_TYPE: u32 = 269;
+const TAG_MD_ENTRY_PX: u32 = 270;
+const TAG_MD_ENTRY_SIZE: u32 = 271;
+const TAG_MD_UPDATE_ACTION: u32 = 279;
+
+/// `MsgType` of a market data full refresh
+pub const MSG_TYPE_FULL_REFRESH: &str = "W";
+/// `MsgType` of a market data incremental refresh
+pub const MSG_TYPE_INCREMENTAL_REFRESH: &str = "X";
+
+/// A FIX 4.4 message: its type and body fields, in order.
+///
+/// The standard header fields `BeginString`, `BodyLength` and `MsgType`, and the
+/// `CheckSum` trailer, are computed by [`FixMessage::encode`] and checked by
+/// [`FixMessage::parse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixMessage {
+    /// `MsgType` (35)
+    pub msg_type: String,
+    /// Every other field between `MsgType` and `CheckSum`
+    pub fields: Vec<(u32, String)>,
+}
+
+impl FixMessage {
+    /// Create an empty message of the given type
+    pub fn new(msg_type: &str) -> Self {
+        Self {
+            msg_type: msg_type.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Appends a field
+    pub fn push(&mut self, tag: u32, value: impl Into<String>) {
+        self.fields.push((tag, value.into()));
+    }
+
+    /// Value of the first field with `tag`
+    pub fn get(&self, tag: u32) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == tag)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Encodes the message with its header and checksum, fields separated by [`SOH`]
+    pub fn encode(&self) -> String {
+        let mut body = format!("{TAG_MSG_TYPE}={}{SOH}", self.msg_type);
+        for (tag, value) in &self.fields {
+            body.push_str(&format!("{tag}={value}{SOH}"));
+        }
+
+        let mut message = format!(
+            "{TAG_BEGIN_STRING}={BEGIN_STRING}{SOH}{TAG_BODY_LENGTH}={}{SOH}{body}",
+            body.len()
+        );
+        let checksum = checksum(&message);
+        message.push_str(&format!("{TAG_CHECKSUM}={checksum:03}{SOH}"));
+        message
+    }
+
+    /// Parses a message encoded with [`SOH`] separators
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::DeserializationError` if the message is not FIX 4.4,
+    /// is malformed, or its body length or checksum do not match.
+    pub fn parse(data: &str) -> Result<Self, OrderBookError> {
+        let mut fields = Vec::new();
+        for field in data.split_terminator(SOH) {
+            let (tag, value) = field
+                .split_once('=')
+                .ok_or_else(|| malformed(format!("field {field:?} has no '='")))?;
+            let tag = tag
+                .parse::<u32>()
+                .map_err(|_| malformed(format!("invalid tag {tag:?}")))?;
+            fields.push((tag, value.to_string()));
+        }
+
+        match fields.first() {
+            Some((TAG_BEGIN_STRING, version)) if version == BEGIN_STRING => {}
+            _ => {
+                return Err(malformed(format!(
+                    "message does not start with 8={BEGIN_STRING}"
+                )));
+            }
+        }
+        let body_length: usize = match fields.get(1) {
+            Some((TAG_BODY_LENGTH, length)) => length
+                .parse()
+                .map_err(|_| malformed(format!("invalid body length {length:?}")))?,
+            _ => return Err(malformed("missing body length".to_string())),
+        };
+        let msg_type = match fields.get(2) {
+            Some((TAG_MSG_TYPE, msg_type)) => msg_type.clone(),
+            _ => return Err(malformed("missing message type".to_string())),
+        };
+        let Some((TAG_CHECKSUM, expected)) = fields.last() else {
+            return Err(malformed("missing checksum".to_string()));
+        };
+
+        let trailer = data
+            .rfind(&format!("{SOH}{TAG_CHECKSUM}="))
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        // The body starts after the BeginString and BodyLength fields
+        let header_len = data
+            .match_indices(SOH)
+            .nth(1)
+            .map_or(data.len(), |(index, _)| index + 1);
+        if trailer.checked_sub(header_len) != Some(body_length) {
+            return Err(malformed(format!(
+                "body length {body_length} does not match the message"
+            )));
+        }
+        let actual = format!("{:03}", checksum(&data[..trailer]));
+        if *expected != actual {
+            return Err(OrderBookError::ChecksumMismatch {
+                expected: expected.clone(),
+                actual,
+            });
+        }
+
+        let fields = fields[3..fields.len() - 1].to_vec();
+        Ok(Self { msg_type, fields })
+    }
+}
+
+fn checksum(data: &str) -> u32 {
+    data.bytes().map(u32::from).sum::<u32>() % 256
+}
+
+fn malformed(reason: String) -> OrderBookError {
+    OrderBookError::DeserializationError {
+        message: format!("Malformed FIX message: {reason}"),
+    }
+}
+
+/// One market data entry of a refresh message.
+#[derive(Debug, Default)]
+struct Entry {
+    action: Option<String>,
+    side: Option<Side>,
+    price: Option<String>,
+    size: Option<String>,
+    symbol: Option<String>,
+    /// Entry type other than bid or offer, e.g. a trade
+    ignored: bool,
+}
+
+/// Converts FIX market data to and from an order book.
+///
+/// Generated messages carry the configured comp IDs and consecutive `MsgSeqNum`s;
+/// session level concerns such as logon, heartbeats and resends are left to the
+/// FIX engine carrying the messages.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::OrderBook;
+/// use orderbook_rs::feeds::{FixMarketData, FixMessage};
+/// use pricelevel::{OrderId, Side, TimeInForce};
+///
+/// let source = OrderBook::<()>::new("EUR/USD");
+/// source.add_limit_order(OrderId::new(), 108_250, 1_000_000, Side::Buy, TimeInForce::Gtc, None).unwrap();
+///
+/// let mut publisher = FixMarketData::new("EXCH", "CLIENT").with_price_decimals(5);
+/// let wire = publisher.full_refresh(&source, 10).encode();
+///
+/// let mirror = OrderBook::<()>::new("EUR/USD");
+/// let subscriber = FixMarketData::new("CLIENT", "EXCH").with_price_decimals(5);
+/// subscriber.apply(&mirror, &FixMessage::parse(&wire).unwrap()).unwrap();
+/// assert_eq!(mirror.best_bid(), Some(108_250));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixMarketData {
+    sender_comp_id: String,
+    target_comp_id: String,
+    next_seq_num: u64,
+    price_decimals: u32,
+    quantity_decimals: u32,
+}
+
+impl FixMarketData {
+    /// Create a converter sending from `sender_comp_id` to `target_comp_id`
+    ///
+    /// Prices and sizes have no decimal places until configured otherwise.
+    pub fn new(sender_comp_id: &str, target_comp_id: &str) -> Self {
+        Self {
+            sender_comp_id: sender_comp_id.to_string(),
+            target_comp_id: target_comp_id.to_string(),
+            next_seq_num: 1,
+            price_decimals: 0,
+            quantity_decimals: 0,
+        }
+    }
+
+    /// Sets the number of decimal places of a book price, e.g. 2 for cents
+    #[must_use]
+    pub fn with_price_decimals(mut self, decimals: u32) -> Self {
+        self.price_decimals = decimals;
+        self
+    }
+
+    /// Sets the number of decimal places of a book quantity
+    #[must_use]
+    pub fn with_quantity_decimals(mut self, decimals: u32) -> Self {
+        self.quantity_decimals = decimals;
+        self
+    }
+
+    /// Sets the `MsgSeqNum` of the next generated message
+    #[must_use]
+    pub fn with_next_seq_num(mut self, seq_num: u64) -> Self {
+        self.next_seq_num = seq_num;
+        self
+    }
+
+    /// `MsgSeqNum` of the next generated message
+    pub fn next_seq_num(&self) -> u64 {
+        self.next_seq_num
+    }
+
+    /// Generates a full refresh of the top `depth` visible levels of each side
+    pub fn full_refresh<T>(&mut self, book: &OrderBook<T>, depth: usize) -> FixMessage
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let mut message = self.header(MSG_TYPE_FULL_REFRESH, book.clock().now_millis());
+        message.push(TAG_SYMBOL, book.symbol());
+
+        let (bids, asks) = book.depth(depth);
+        message.push(TAG_NO_MD_ENTRIES, (bids.len() + asks.len()).to_string());
+        let levels = bids
+            .iter()
+            .map(|level| (Side::Buy, level))
+            .chain(asks.iter().map(|level| (Side::Sell, level)));
+        for (side, (price, quantity, _)) in levels {
+            message.push(TAG_MD_ENTRY_TYPE, entry_type(side));
+            message.push(TAG_MD_ENTRY_PX, format_decimal(*price, self.price_decimals));
+            message.push(
+                TAG_MD_ENTRY_SIZE,
+                format_decimal(*quantity, self.quantity_decimals),
+            );
+        }
+        message
+    }
+
+    /// Generates an incremental refresh of the level changes in `delta`
+    ///
+    /// `sending_time` is in milliseconds since the Unix epoch.
+    pub fn incremental_refresh(&mut self, delta: &OrderBookDelta, sending_time: u64) -> FixMessage {
+        let mut message = self.header(MSG_TYPE_INCREMENTAL_REFRESH, sending_time);
+        message.push(TAG_NO_MD_ENTRIES, delta.changes.len().to_string());
+        for change in &delta.changes {
+            let (action, side, price, quantity) = match *change {
+                LevelChange::Added {
+                    side,
+                    price,
+                    quantity,
+                } => ("0", side, price, Some(quantity)),
+                LevelChange::Updated {
+                    side,
+                    price,
+                    quantity,
+                } => ("1", side, price, Some(quantity)),
+                LevelChange::Removed { side, price } => ("2", side, price, None),
+            };
+            message.push(TAG_MD_UPDATE_ACTION, action);
+            message.push(TAG_MD_ENTRY_TYPE, entry_type(side));
+            message.push(TAG_SYMBOL, delta.symbol.as_str());
+            message.push(TAG_MD_ENTRY_PX, format_decimal(price, self.price_decimals));
+            if let Some(quantity) = quantity {
+                message.push(
+                    TAG_MD_ENTRY_SIZE,
+                    format_decimal(quantity, self.quantity_decimals),
+                );
+            }
+        }
+        message
+    }
+
+    fn header(&mut self, msg_type: &str, sending_time: u64) -> FixMessage {
+        let mut message = FixMessage::new(msg_type);
+        message.push(TAG_SENDER_COMP_ID, self.sender_comp_id.as_str());
+        message.push(TAG_TARGET_COMP_ID, self.target_comp_id.as_str());
+        message.push(TAG_MSG_SEQ_NUM, self.next_seq_num.to_string());
+        message.push(TAG_SENDING_TIME, utc_timestamp(sending_time));
+        self.next_seq_num += 1;
+        message
+    }
+
+    /// Applies a full or incremental refresh to `book`, returning the number of
+    /// bid and offer entries applied
+    ///
+    /// A full refresh replaces every visible level of the book; entries of other
+    /// types, such as trades, are skipped.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::InvalidOperation` if the message is not a market
+    /// data refresh or refers to another symbol, and
+    /// `OrderBookError::DeserializationError` if an entry is incomplete or has a
+    /// price or size the configured decimals cannot represent.
+    pub fn apply<T>(
+        &self,
+        book: &OrderBook<T>,
+        message: &FixMessage,
+    ) -> Result<usize, OrderBookError>
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let full = match message.msg_type.as_str() {
+            MSG_TYPE_FULL_REFRESH => true,
+            MSG_TYPE_INCREMENTAL_REFRESH => false,
+            other => {
+                return Err(OrderBookError::InvalidOperation {
+                    message: format!("FIX message type {other} is not a market data refresh"),
+                });
+            }
+        };
+        if full {
+            check_symbol(book, message.get(TAG_SYMBOL))?;
+        }
+
+        // Resolve every entry before touching the book, so a bad message changes nothing
+        let mut levels = Vec::new();
+        for entry in entries(message, full) {
+            if entry.ignored {
+                continue;
+            }
+            check_symbol(book, entry.symbol.as_deref())?;
+            let side = entry.side.ok_or_else(|| incomplete("MDEntryType"))?;
+            let price = entry
+                .price
+                .as_deref()
+                .ok_or_else(|| incomplete("MDEntryPx"))?;
+            let price = parse_decimal(price, self.price_decimals)?;
+            let quantity = match entry.action.as_deref() {
+                Some("2") => 0,
+                Some("0" | "1") | None => {
+                    let size = entry
+                        .size
+                        .as_deref()
+                        .ok_or_else(|| incomplete("MDEntrySize"))?;
+                    parse_decimal(size, self.quantity_decimals)?
+                }
+                Some(other) => {
+                    return Err(malformed(format!("unsupported MDUpdateAction {other}")));
+                }
+            };
+            levels.push((side, price, quantity));
+        }
+
+        trace!(
+            "Order book {}: Applying FIX {} with {} entries",
+            book.symbol(),
+            message.msg_type,
+            levels.len()
+        );
+        if full {
+            let side_levels = |side: Side| -> Vec<(u64, u64)> {
+                levels
+                    .iter()
+                    .filter(|level| level.0 == side)
+                    .map(|(_, price, quantity)| (*price, *quantity))
+                    .collect()
+            };
+            book.apply_l2_snapshot(&side_levels(Side::Buy), &side_levels(Side::Sell))?;
+        } else {
+            for (side, price, quantity) in &levels {
+                book.apply_l2_update(*price, *quantity, *side)?;
+            }
+        }
+        Ok(levels.len())
+    }
+}
+
+/// Splits the repeating group of market data entries into entries.
+fn entries(message: &FixMessage, full: bool) -> Vec<Entry> {
+    let delimiter = if full {
+        TAG_MD_ENTRY_TYPE
+    } else {
+        TAG_MD_UPDATE_ACTION
+    };
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut in_group = false;
+
+    for (tag, value) in &message.fields {
+        if *tag == TAG_NO_MD_ENTRIES {
+            in_group = true;
+            continue;
+        }
+        if !in_group {
+            continue;
+        }
+        if *tag == delimiter || entries.is_empty() {
+            entries.push(Entry::default());
+        }
+        let Some(entry) = entries.last_mut() else {
+            continue;
+        };
+        match *tag {
+            TAG_MD_UPDATE_ACTION => entry.action = Some(value.clone()),
+            TAG_MD_ENTRY_TYPE => match value.as_str() {
+                "0" => entry.side = Some(Side::Buy),
+                "1" => entry.side = Some(Side::Sell),
+                _ => entry.ignored = true,
+            },
+            TAG_MD_ENTRY_PX => entry.price = Some(value.clone()),
+            TAG_MD_ENTRY_SIZE => entry.size = Some(value.clone()),
+            TAG_SYMBOL => entry.symbol = Some(value.clone()),
+            _ => {}
+        }
+    }
+    entries
+}
+
+fn check_symbol<T>(book: &OrderBook<T>, symbol: Option<&str>) -> Result<(), OrderBookError>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    match symbol {
+        Some(symbol) if symbol != book.symbol() => Err(OrderBookError::InvalidOperation {
+            message: format!(
+                "FIX symbol {symbol} does not match order book symbol {}",
+                book.symbol()
+            ),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn incomplete(field: &str) -> OrderBookError {
+    malformed(format!("market data entry without {field}"))
+}
+
+fn entry_type(side: Side) -> &'static str {
+    match side {
+        Side::Buy => "0",
+        Side::Sell => "1",
+    }
+}
+
+/// Formats milliseconds since the Unix epoch as a FIX `UTCTimestamp`.
+fn utc_timestamp(millis: u64) -> String {
+    let days = (millis / 86_400_000) as i64;
+    let ms_of_day = millis % 86_400_000;
+
+    // Civil date from days since 1970-01-01 (proleptic Gregorian calendar)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}{month:02}{day:02}-{:02}:{:02}:{:02}.{:03}",
+        ms_of_day / 3_600_000,
+        ms_of_day / 60_000 % 60,
+        ms_of_day / 1_000 % 60,
+        ms_of_day % 1_000
+    )
+}
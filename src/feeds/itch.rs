@@ -0,0 +1,466 @@
+//! NASDAQ TotalView-ITCH 5.0 decoding.
+//!
+//! [`ItchReader`] splits a byte stream into messages and [`ItchMessage::decode`]
+//! parses the order messages: add, execute, cancel, delete and replace. An
+//! [`ItchBook`] applies the messages of one stock to an `OrderBook` through an
+//! [`L3FeedApplier`]. Prices are kept as ITCH transmits them, in units of
+//! 1/10,000 of a dollar.
+
+use crate::orderbook::{L3FeedApplier, L3Message, L3Snapshot, L3Update, OrderBook, OrderBookError};
+use pricelevel::Side;
+use std::io::{ErrorKind, Read};
+use tracing::trace;
+
+/// Length of the header shared by every message: type, stock locate, tracking
+/// number and timestamp.
+const HEADER_LEN: usize = 11;
+
+/// A decoded ITCH 5.0 message.
+///
+/// Every message carries the stock locate code, which identifies the stock for
+/// the day, and a timestamp in nanoseconds since midnight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItchMessage {
+    /// Stock directory (`R`), announcing the locate code of a stock
+    StockDirectory {
+        /// Locate code of the stock
+        stock_locate: u16,
+        /// Nanoseconds since midnight
+        timestamp: u64,
+        /// Stock symbol, without padding
+        stock: String,
+    },
+    /// Add order (`A`), or add order with MPID attribution (`F`)
+    AddOrder {
+        /// Locate code of the stock
+        stock_locate: u16,
+        /// Nanoseconds since midnight
+        timestamp: u64,
+        /// Order reference number
+        order_ref: u64,
+        /// Side of the order
+        side: Side,
+        /// Displayed shares
+        shares: u32,
+        /// Stock symbol, without padding
+        stock: String,
+        /// Limit price in 1/10,000 of a dollar
+        price: u32,
+        /// Market participant ID, for `F` messages
+        attribution: Option<String>,
+    },
+    /// Order executed (`E`)
+    OrderExecuted {
+        /// Locate code of the stock
+        stock_locate: u16,
+        /// Nanoseconds since midnight
+        timestamp: u64,
+        /// Order reference number
+        order_ref: u64,
+        /// Executed shares
+        shares: u32,
+        /// Match number of the execution
+        match_number: u64,
+    },
+    /// Order executed at a price other than the order's price (`C`)
+    OrderExecutedWithPrice {
+        /// Locate code of the stock
+        stock_locate: u16,
+        /// Nanoseconds since midnight
+        timestamp: u64,
+        /// Order reference number
+        order_ref: u64,
+        /// Executed shares
+        shares: u32,
+        /// Match number of the execution
+        match_number: u64,
+        /// Whether the execution should be shown on time and sales
+        printable: bool,
+        /// Execution price in 1/10,000 of a dollar
+        price: u32,
+    },
+    /// Partial cancellation (`X`)
+    OrderCancel {
+        /// Locate code of the stock
+        stock_locate: u16,
+        /// Nanoseconds since midnight
+        timestamp: u64,
+        /// Order reference number
+        order_ref: u64,
+        /// Cancelled shares
+        shares: u32,
+    },
+    /// Order deletion (`D`)
+    OrderDelete {
+        /// Locate code of the stock
+        stock_locate: u16,
+        /// Nanoseconds since midnight
+        timestamp: u64,
+        /// Order reference number
+        order_ref: u64,
+    },
+    /// Order replacement (`U`); the new order loses time priority
+    OrderReplace {
+        /// Locate code of the stock
+        stock_locate: u16,
+        /// Nanoseconds since midnight
+        timestamp: u64,
+        /// Reference number of the replaced order
+        original_ref: u64,
+        /// Reference number of the new order
+        new_ref: u64,
+        /// Displayed shares of the new order
+        shares: u32,
+        /// Limit price of the new order in 1/10,000 of a dollar
+        price: u32,
+    },
+    /// Any other message type, which does not change the book
+    Other {
+        /// Message type
+        kind: u8,
+        /// Locate code of the stock, or zero for market-wide messages
+        stock_locate: u16,
+        /// Nanoseconds since midnight
+        timestamp: u64,
+    },
+}
+
+impl ItchMessage {
+    /// Decodes one message, without its length prefix
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::DeserializationError` if the message is shorter
+    /// than its type requires or has an unknown side indicator.
+    pub fn decode(data: &[u8]) -> Result<Self, OrderBookError> {
+        if data.len() < HEADER_LEN {
+            return Err(truncated(data.first().copied().unwrap_or(0), data.len()));
+        }
+        let kind = data[0];
+        let stock_locate = u16::from_be_bytes([data[1], data[2]]);
+        let timestamp = data[5..11]
+            .iter()
+            .fold(0u64, |acc, byte| (acc << 8) | u64::from(*byte));
+
+        let required = match kind {
+            b'R' => 39,
+            b'A' => 36,
+            b'F' => 40,
+            b'E' => 31,
+            b'C' => 36,
+            b'X' => 23,
+            b'D' => 19,
+            b'U' => 35,
+            _ => HEADER_LEN,
+        };
+        if data.len() < required {
+            return Err(truncated(kind, data.len()));
+        }
+
+        let message = match kind {
+            b'R' => ItchMessage::StockDirectory {
+                stock_locate,
+                timestamp,
+                stock: alpha(&data[11..19]),
+            },
+            b'A' | b'F' => ItchMessage::AddOrder {
+                stock_locate,
+                timestamp,
+                order_ref: be_u64(data, 11),
+                side: match data[19] {
+                    b'B' => Side::Buy,
+                    b'S' => Side::Sell,
+                    other => {
+                        return Err(OrderBookError::DeserializationError {
+                            message: format!("Invalid ITCH side indicator {:?}", other as char),
+                        });
+                    }
+                },
+                shares: be_u32(data, 20),
+                stock: alpha(&data[24..32]),
+                price: be_u32(data, 32),
+                attribution: (kind == b'F').then(|| alpha(&data[36..40])),
+            },
+            b'E' => ItchMessage::OrderExecuted {
+                stock_locate,
+                timestamp,
+                order_ref: be_u64(data, 11),
+                shares: be_u32(data, 19),
+                match_number: be_u64(data, 23),
+            },
+            b'C' => ItchMessage::OrderExecutedWithPrice {
+                stock_locate,
+                timestamp,
+                order_ref: be_u64(data, 11),
+                shares: be_u32(data, 19),
+                match_number: be_u64(data, 23),
+                printable: data[31] == b'Y',
+                price: be_u32(data, 32),
+            },
+            b'X' => ItchMessage::OrderCancel {
+                stock_locate,
+                timestamp,
+                order_ref: be_u64(data, 11),
+                shares: be_u32(data, 19),
+            },
+            b'D' => ItchMessage::OrderDelete {
+                stock_locate,
+                timestamp,
+                order_ref: be_u64(data, 11),
+            },
+            b'U' => ItchMessage::OrderReplace {
+                stock_locate,
+                timestamp,
+                original_ref: be_u64(data, 11),
+                new_ref: be_u64(data, 19),
+                shares: be_u32(data, 27),
+                price: be_u32(data, 31),
+            },
+            _ => ItchMessage::Other {
+                kind,
+                stock_locate,
+                timestamp,
+            },
+        };
+        Ok(message)
+    }
+
+    /// Locate code of the stock the message refers to
+    pub fn stock_locate(&self) -> u16 {
+        match *self {
+            ItchMessage::StockDirectory { stock_locate, .. }
+            | ItchMessage::AddOrder { stock_locate, .. }
+            | ItchMessage::OrderExecuted { stock_locate, .. }
+            | ItchMessage::OrderExecutedWithPrice { stock_locate, .. }
+            | ItchMessage::OrderCancel { stock_locate, .. }
+            | ItchMessage::OrderDelete { stock_locate, .. }
+            | ItchMessage::OrderReplace { stock_locate, .. }
+            | ItchMessage::Other { stock_locate, .. } => stock_locate,
+        }
+    }
+
+    /// The book change described by the message, if any
+    pub fn to_l3(&self) -> Option<L3Message> {
+        match self {
+            ItchMessage::AddOrder {
+                order_ref,
+                side,
+                shares,
+                price,
+                ..
+            } => Some(L3Message::Add {
+                exchange_id: order_ref.to_string(),
+                side: *side,
+                price: u64::from(*price),
+                quantity: u64::from(*shares),
+            }),
+            ItchMessage::OrderExecuted {
+                order_ref, shares, ..
+            }
+            | ItchMessage::OrderExecutedWithPrice {
+                order_ref, shares, ..
+            } => Some(L3Message::Execute {
+                exchange_id: order_ref.to_string(),
+                quantity: u64::from(*shares),
+            }),
+            ItchMessage::OrderCancel {
+                order_ref, shares, ..
+            } => Some(L3Message::Reduce {
+                exchange_id: order_ref.to_string(),
+                quantity: u64::from(*shares),
+            }),
+            ItchMessage::OrderDelete { order_ref, .. } => Some(L3Message::Delete {
+                exchange_id: order_ref.to_string(),
+            }),
+            ItchMessage::OrderReplace {
+                original_ref,
+                new_ref,
+                shares,
+                price,
+                ..
+            } => Some(L3Message::Replace {
+                exchange_id: original_ref.to_string(),
+                new_exchange_id: new_ref.to_string(),
+                price: u64::from(*price),
+                quantity: u64::from(*shares),
+            }),
+            ItchMessage::StockDirectory { .. } | ItchMessage::Other { .. } => None,
+        }
+    }
+}
+
+fn truncated(kind: u8, len: usize) -> OrderBookError {
+    OrderBookError::DeserializationError {
+        message: format!(
+            "Truncated ITCH message of type {:?}: {len} bytes",
+            kind as char
+        ),
+    }
+}
+
+fn be_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&data[offset..offset + 4]);
+    u32::from_be_bytes(bytes)
+}
+
+fn be_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_be_bytes(bytes)
+}
+
+/// Decodes a space padded alphanumeric field.
+fn alpha(data: &[u8]) -> String {
+    String::from_utf8_lossy(data).trim_end().to_string()
+}
+
+/// Reads messages from a stream in which each message is preceded by its length
+/// as a big-endian `u16`, the layout of NASDAQ's ITCH capture files.
+pub struct ItchReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+}
+
+impl<R: Read> ItchReader<R> {
+    /// Create a reader over `reader`, which is best buffered
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Reads the next message, or `None` at the end of the stream
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::DeserializationError` if the stream cannot be read,
+    /// ends inside a message, or holds a malformed message.
+    pub fn next_message(&mut self) -> Result<Option<ItchMessage>, OrderBookError> {
+        let mut length = [0u8; 2];
+        match self.reader.read_exact(&mut length) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(read_error(e)),
+        }
+
+        self.buffer
+            .resize(usize::from(u16::from_be_bytes(length)), 0);
+        self.reader
+            .read_exact(&mut self.buffer)
+            .map_err(read_error)?;
+        ItchMessage::decode(&self.buffer).map(Some)
+    }
+}
+
+impl<R: Read> Iterator for ItchReader<R> {
+    type Item = Result<ItchMessage, OrderBookError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_message().transpose()
+    }
+}
+
+fn read_error(e: std::io::Error) -> OrderBookError {
+    OrderBookError::DeserializationError {
+        message: format!("Failed to read ITCH stream: {e}"),
+    }
+}
+
+/// Maintains the order book of one stock from an ITCH stream.
+///
+/// The book starts empty, as it is at the start of a trading day, and the stock's
+/// locate code is learned from its stock directory or first add order message.
+/// Messages for other stocks are skipped.
+pub struct ItchBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    stock: String,
+    stock_locate: Option<u16>,
+    feed: L3FeedApplier<T>,
+    sequence: u64,
+}
+
+impl<T> ItchBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Create a builder maintaining `book` from the messages of `stock`
+    pub fn new(book: OrderBook<T>, stock: &str) -> Self {
+        let start_of_day =
+            |_: &str| -> Result<L3Snapshot, OrderBookError> { Ok(L3Snapshot::default()) };
+        Self {
+            stock: stock.to_string(),
+            stock_locate: None,
+            feed: L3FeedApplier::new(book, start_of_day),
+            sequence: 0,
+        }
+    }
+
+    /// Applies a message, returning `true` if it changed the book
+    ///
+    /// # Errors
+    /// Returns an error if the message refers to an order unknown to the book.
+    pub fn apply(&mut self, message: &ItchMessage) -> Result<bool, OrderBookError> {
+        if self.stock_locate.is_none() {
+            match message {
+                ItchMessage::StockDirectory {
+                    stock_locate,
+                    stock,
+                    ..
+                }
+                | ItchMessage::AddOrder {
+                    stock_locate,
+                    stock,
+                    ..
+                } if *stock == self.stock => {
+                    trace!("ITCH: {} has locate code {}", self.stock, stock_locate);
+                    self.stock_locate = Some(*stock_locate);
+                }
+                _ => return Ok(false),
+            }
+        }
+        if Some(message.stock_locate()) != self.stock_locate {
+            return Ok(false);
+        }
+        let Some(message) = message.to_l3() else {
+            return Ok(false);
+        };
+
+        self.feed.apply(L3Update {
+            sequence: self.sequence + 1,
+            message,
+        })?;
+        self.sequence += 1;
+        Ok(true)
+    }
+
+    /// Applies every message read from `reader`, returning how many changed the book
+    ///
+    /// # Errors
+    /// Returns the first read, decoding or apply error.
+    pub fn replay<R: Read>(&mut self, reader: ItchReader<R>) -> Result<usize, OrderBookError> {
+        let mut applied = 0;
+        for message in reader {
+            if self.apply(&message?)? {
+                applied += 1;
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Locate code of the stock, once known
+    pub fn stock_locate(&self) -> Option<u16> {
+        self.stock_locate
+    }
+
+    /// The book maintained from the stream
+    pub fn book(&self) -> &OrderBook<T> {
+        self.feed.book()
+    }
+
+    /// Stops applying the stream and returns the book
+    pub fn into_book(self) -> OrderBook<T> {
+        self.feed.into_book()
+    }
+}
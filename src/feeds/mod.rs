@@ -0,0 +1,23 @@
+/// Exchange adapters producing normalized market data events.
+pub mod adapter;
+/// Binance spot WebSocket adapter for combined depth and trade streams.
+#[cfg(feature = "binance")]
+pub mod binance;
+#[cfg(any(feature = "binance", feature = "fix"))]
+mod decimal;
+/// FIX 4.4 market data full and incremental refreshes.
+#[cfg(feature = "fix")]
+pub mod fix;
+/// NASDAQ TotalView-ITCH 5.0 decoding.
+#[cfg(feature = "itch")]
+pub mod itch;
+
+mod tests;
+
+pub use adapter::{BookFeedAdapter, FeedEvent, FeedEvents, apply_feed_event, mirror_feed};
+#[cfg(feature = "binance")]
+pub use binance::BinanceAdapter;
+#[cfg(feature = "fix")]
+pub use fix::{FixMarketData, FixMessage};
+#[cfg(feature = "itch")]
+pub use itch::{ItchBook, ItchMessage, ItchReader};
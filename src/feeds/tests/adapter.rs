@@ -0,0 +1,122 @@
+//! Unit tests for feed adapters and mirroring into managed books.
+
+#[cfg(test)]
+mod tests {
+    use crate::feeds::adapter::{BookFeedAdapter, FeedEvent, apply_feed_event, mirror_feed};
+    use crate::orderbook::OrderBookError;
+    use crate::orderbook::manager::{BookManager, BookManagerStd};
+    use pricelevel::Side;
+    use std::collections::VecDeque;
+
+    /// Adapter replaying scripted events.
+    #[derive(Default)]
+    struct ScriptedAdapter {
+        events: VecDeque<FeedEvent>,
+        connected: bool,
+        subscribed: Vec<String>,
+    }
+
+    impl BookFeedAdapter for ScriptedAdapter {
+        fn connect(&mut self) -> Result<(), OrderBookError> {
+            self.connected = true;
+            Ok(())
+        }
+
+        fn subscribe(&mut self, symbols: &[String]) -> Result<(), OrderBookError> {
+            self.subscribed.extend_from_slice(symbols);
+            Ok(())
+        }
+
+        fn next_event(&mut self) -> Result<Option<FeedEvent>, OrderBookError> {
+            Ok(self.events.pop_front())
+        }
+    }
+
+    fn snapshot(symbol: &str, bids: Vec<(u64, u64)>, asks: Vec<(u64, u64)>) -> FeedEvent {
+        FeedEvent::DepthSnapshot {
+            symbol: symbol.to_string(),
+            update_id: 1,
+            bids,
+            asks,
+        }
+    }
+
+    #[test]
+    fn test_apply_feed_event_updates_managed_book() {
+        let mut manager = BookManagerStd::<()>::new();
+        manager.add_book("BTC/USDT");
+
+        let applied = apply_feed_event(
+            &manager,
+            &snapshot("BTC/USDT", vec![(100, 5), (99, 7)], vec![(101, 3)]),
+        )
+        .unwrap();
+        assert!(applied);
+
+        let update = FeedEvent::DepthUpdate {
+            symbol: "BTC/USDT".to_string(),
+            first_update_id: 2,
+            final_update_id: 3,
+            bids: vec![(100, 0)],
+            asks: vec![(102, 4)],
+        };
+        assert!(apply_feed_event(&manager, &update).unwrap());
+
+        let book = manager.get_book("BTC/USDT").unwrap();
+        assert_eq!(book.depth(usize::MAX).0, vec![(99, 7, 1)]);
+        assert_eq!(book.depth(usize::MAX).1, vec![(101, 3, 1), (102, 4, 1)]);
+
+        let unknown = snapshot("ETH/USDT", vec![(1, 1)], vec![]);
+        assert!(!apply_feed_event(&manager, &unknown).unwrap());
+    }
+
+    #[test]
+    fn test_depth_snapshot_replaces_levels() {
+        let mut manager = BookManagerStd::<()>::new();
+        manager.add_book("BTC/USDT");
+        apply_feed_event(
+            &manager,
+            &snapshot("BTC/USDT", vec![(100, 5)], vec![(105, 1)]),
+        )
+        .unwrap();
+        apply_feed_event(&manager, &snapshot("BTC/USDT", vec![(98, 2)], vec![])).unwrap();
+
+        let book = manager.get_book("BTC/USDT").unwrap();
+        assert_eq!(book.depth(usize::MAX).0, vec![(98, 2, 1)]);
+        assert_eq!(book.best_ask(), None);
+    }
+
+    #[test]
+    fn test_mirror_feed_routes_depth_and_trades() {
+        let mut manager = BookManagerStd::<()>::new();
+        manager.add_book("BTC/USDT");
+
+        let trade = FeedEvent::Trade {
+            symbol: "BTC/USDT".to_string(),
+            trade_id: 9,
+            price: 101,
+            quantity: 1,
+            taker_side: Side::Buy,
+            timestamp: 0,
+        };
+        let mut adapter = ScriptedAdapter {
+            events: VecDeque::from([
+                snapshot("BTC/USDT", vec![(100, 5)], vec![(101, 3)]),
+                trade.clone(),
+                snapshot("BTC/USDT", vec![(100, 5)], vec![(101, 2)]),
+            ]),
+            ..Default::default()
+        };
+
+        let mut trades = Vec::new();
+        let applied =
+            mirror_feed(&mut adapter, &manager, |event| trades.push(event.clone())).unwrap();
+
+        assert_eq!(applied, 2);
+        assert_eq!(trades, vec![trade]);
+        assert!(adapter.connected);
+        assert_eq!(adapter.subscribed, vec!["BTC/USDT".to_string()]);
+        let book = manager.get_book("BTC/USDT").unwrap();
+        assert_eq!(book.depth(usize::MAX).1, vec![(101, 2, 1)]);
+    }
+}
@@ -0,0 +1,75 @@
+//! Unit tests for the Binance adapter's message decoding.
+
+#[cfg(test)]
+mod tests {
+    use crate::feeds::adapter::FeedEvent;
+    use crate::feeds::binance::BinanceAdapter;
+    use pricelevel::Side;
+
+    fn adapter() -> BinanceAdapter {
+        let mut adapter = BinanceAdapter::new()
+            .with_price_decimals(2)
+            .with_quantity_decimals(5);
+        adapter.register("BTC/USDT");
+        adapter
+    }
+
+    #[test]
+    fn test_stream_symbol() {
+        assert_eq!(BinanceAdapter::stream_symbol("BTC/USDT"), "btcusdt");
+        assert_eq!(BinanceAdapter::stream_symbol("ethbtc"), "ethbtc");
+    }
+
+    #[test]
+    fn test_decode_partial_depth() {
+        let text = r#"{"stream":"btcusdt@depth20@100ms","data":{"lastUpdateId":160,
+            "bids":[["64000.10","0.50000000"],["63999.00","1.25"]],
+            "asks":[["64000.20","0.00100000"]]}}"#;
+
+        assert_eq!(
+            adapter().decode(text).unwrap(),
+            Some(FeedEvent::DepthSnapshot {
+                symbol: "BTC/USDT".to_string(),
+                update_id: 160,
+                bids: vec![(6_400_010, 50_000), (6_399_900, 125_000)],
+                asks: vec![(6_400_020, 100)],
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_trade() {
+        let text = r#"{"stream":"btcusdt@trade","data":{"e":"trade","E":1,"s":"BTCUSDT",
+            "t":12345,"p":"64000.10","q":"0.01000","T":1700000000000,"m":true,"M":true}}"#;
+
+        assert_eq!(
+            adapter().decode(text).unwrap(),
+            Some(FeedEvent::Trade {
+                symbol: "BTC/USDT".to_string(),
+                trade_id: 12345,
+                price: 6_400_010,
+                quantity: 1_000,
+                taker_side: Side::Sell,
+                timestamp: 1_700_000_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_skips_responses_and_unknown_streams() {
+        let adapter = adapter();
+        assert_eq!(adapter.decode(r#"{"result":null,"id":1}"#).unwrap(), None);
+        let other = r#"{"stream":"ethusdt@trade","data":{}}"#;
+        assert_eq!(adapter.decode(other).unwrap(), None);
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed_messages() {
+        let adapter = adapter();
+        assert!(adapter.decode("not json").is_err());
+        let too_precise = r#"{"stream":"btcusdt@depth20@100ms","data":{"lastUpdateId":1,"bids":[["1.001","1"]],"asks":[]}}"#;
+        assert!(adapter.decode(too_precise).is_err());
+        let missing = r#"{"stream":"btcusdt@trade","data":{"t":1}}"#;
+        assert!(adapter.decode(missing).is_err());
+    }
+}
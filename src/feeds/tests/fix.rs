@@ -0,0 +1,221 @@
+//! Unit tests for FIX 4.4 market data refreshes.
+
+#[cfg(test)]
+mod tests {
+    use crate::feeds::fix::{FixMarketData, FixMessage, SOH};
+    use crate::orderbook::{OrderBook, OrderBookError};
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn add(book: &OrderBook<()>, price: u64, quantity: u64, side: Side) -> OrderId {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, side, TimeInForce::Gtc, None)
+            .unwrap();
+        id
+    }
+
+    /// Converts a readable `|` separated message into the wire format.
+    fn wire(fields: &str) -> String {
+        fields.replace('|', &SOH.to_string())
+    }
+
+    fn publisher() -> FixMarketData {
+        FixMarketData::new("EXCH", "CLIENT")
+            .with_price_decimals(2)
+            .with_quantity_decimals(1)
+    }
+
+    #[test]
+    fn test_encode_and_parse_round_trip() {
+        let mut message = FixMessage::new("X");
+        message.push(49, "EXCH");
+        message.push(268, "0");
+
+        let encoded = message.encode();
+        assert!(encoded.starts_with(&wire("8=FIX.4.4|9=")));
+        assert!(encoded.ends_with(SOH));
+        assert_eq!(FixMessage::parse(&encoded).unwrap(), message);
+    }
+
+    #[test]
+    fn test_parse_rejects_corrupted_messages() {
+        let encoded = FixMessage::new("W").encode();
+
+        let tampered = encoded.replace("35=W", "35=X");
+        assert!(matches!(
+            FixMessage::parse(&tampered),
+            Err(OrderBookError::ChecksumMismatch { .. })
+        ));
+        let longer = encoded.replace("9=5", "9=6");
+        assert!(FixMessage::parse(&longer).is_err());
+        assert!(FixMessage::parse(&encoded.replace("FIX.4.4", "FIX.4.2")).is_err());
+        assert!(FixMessage::parse("garbage").is_err());
+    }
+
+    #[test]
+    fn test_full_refresh_generation() {
+        let mut book = OrderBook::<()>::new("EUR/USD");
+        book.set_clock(Arc::new(ManualClock::new(1_700_000_000_123)));
+        add(&book, 10_025, 15, Side::Buy);
+        add(&book, 10_050, 7, Side::Sell);
+
+        let mut fix = publisher();
+        let message = fix.full_refresh(&book, 10);
+
+        assert_eq!(message.msg_type, "W");
+        assert_eq!(message.get(34), Some("1"));
+        assert_eq!(message.get(52), Some("20231114-22:13:20.123"));
+        assert_eq!(message.get(55), Some("EUR/USD"));
+        assert_eq!(message.get(268), Some("2"));
+        let entries: Vec<(u32, &str)> = message
+            .fields
+            .iter()
+            .filter(|(tag, _)| [269, 270, 271].contains(tag))
+            .map(|(tag, value)| (*tag, value.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (269, "0"),
+                (270, "100.25"),
+                (271, "1.5"),
+                (269, "1"),
+                (270, "100.50"),
+                (271, "0.7"),
+            ]
+        );
+        assert_eq!(fix.next_seq_num(), 2);
+    }
+
+    #[test]
+    fn test_full_refresh_replaces_mirror() {
+        let source = OrderBook::<()>::new("EUR/USD");
+        add(&source, 10_025, 15, Side::Buy);
+        add(&source, 10_020, 30, Side::Buy);
+        add(&source, 10_050, 7, Side::Sell);
+
+        let mirror = OrderBook::<()>::new("EUR/USD");
+        add(&mirror, 9_900, 5, Side::Buy);
+        add(&mirror, 10_020, 1, Side::Buy);
+
+        let encoded = publisher().full_refresh(&source, 10).encode();
+        let applied = publisher()
+            .apply(&mirror, &FixMessage::parse(&encoded).unwrap())
+            .unwrap();
+
+        assert_eq!(applied, 3);
+        assert_eq!(
+            mirror.depth(usize::MAX).0,
+            vec![(10_025, 15, 1), (10_020, 30, 1)]
+        );
+        assert_eq!(mirror.depth(usize::MAX).1, vec![(10_050, 7, 1)]);
+    }
+
+    #[test]
+    fn test_incremental_refresh_keeps_mirror_in_step() {
+        let mut source = OrderBook::<()>::new("EUR/USD");
+        source.enable_delta_tracking(100);
+        let maker = add(&source, 10_025, 15, Side::Buy);
+        add(&source, 10_050, 7, Side::Sell);
+
+        let mut fix = publisher();
+        let mirror = OrderBook::<()>::new("EUR/USD");
+        let snapshot = fix.full_refresh(&source, usize::MAX);
+        fix.apply(&mirror, &snapshot).unwrap();
+        let start = source.delta_sequence().unwrap();
+
+        source.cancel_order(maker).unwrap();
+        add(&source, 10_020, 4, Side::Buy);
+        add(&source, 10_050, 3, Side::Sell);
+
+        let delta = source.delta_since(start).unwrap();
+        let message = fix.incremental_refresh(&delta, 0);
+        assert_eq!(message.msg_type, "X");
+        assert_eq!(message.get(52), Some("19700101-00:00:00.000"));
+        assert_eq!(message.get(279), Some("2"));
+
+        let encoded = message.encode();
+        let applied = fix
+            .apply(&mirror, &FixMessage::parse(&encoded).unwrap())
+            .unwrap();
+        assert_eq!(applied, 3);
+        assert_eq!(mirror.depth(usize::MAX).0, vec![(10_020, 4, 1)]);
+        assert_eq!(mirror.depth(usize::MAX).1, vec![(10_050, 10, 1)]);
+    }
+
+    #[test]
+    fn test_apply_skips_trade_entries() {
+        let book = OrderBook::<()>::new("ABC");
+        let message = FixMessage::parse(
+            &FixMessage {
+                msg_type: "X".to_string(),
+                fields: vec![
+                    (268, "2".to_string()),
+                    (279, "0".to_string()),
+                    (269, "2".to_string()),
+                    (270, "101".to_string()),
+                    (271, "5".to_string()),
+                    (279, "0".to_string()),
+                    (269, "0".to_string()),
+                    (270, "100".to_string()),
+                    (271, "8".to_string()),
+                ],
+            }
+            .encode(),
+        )
+        .unwrap();
+
+        let applied = FixMarketData::new("A", "B").apply(&book, &message).unwrap();
+        assert_eq!(applied, 1);
+        assert_eq!(book.depth(usize::MAX).0, vec![(100, 8, 1)]);
+        assert_eq!(book.best_ask(), None);
+    }
+
+    #[test]
+    fn test_apply_rejects_invalid_messages() {
+        let book = OrderBook::<()>::new("ABC");
+        let fix = FixMarketData::new("A", "B").with_price_decimals(2);
+        let refresh = |fields: &[(u32, &str)]| FixMessage {
+            msg_type: "X".to_string(),
+            fields: fields
+                .iter()
+                .map(|(tag, value)| (*tag, value.to_string()))
+                .collect(),
+        };
+
+        let other_symbol = refresh(&[
+            (268, "1"),
+            (279, "0"),
+            (269, "0"),
+            (55, "XYZ"),
+            (270, "1"),
+            (271, "1"),
+        ]);
+        assert!(fix.apply(&book, &other_symbol).is_err());
+
+        let too_precise = refresh(&[
+            (268, "1"),
+            (279, "0"),
+            (269, "0"),
+            (270, "1.001"),
+            (271, "1"),
+        ]);
+        assert!(fix.apply(&book, &too_precise).is_err());
+
+        let missing_size = refresh(&[
+            (268, "2"),
+            (279, "0"),
+            (269, "0"),
+            (270, "1.00"),
+            (271, "1"),
+            (279, "1"),
+            (269, "1"),
+            (270, "2.00"),
+        ]);
+        assert!(fix.apply(&book, &missing_size).is_err());
+        assert!(book.best_bid().is_none());
+
+        assert!(fix.apply(&book, &FixMessage::new("D")).is_err());
+    }
+}
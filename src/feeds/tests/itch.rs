@@ -0,0 +1,200 @@
+//! Unit tests for ITCH 5.0 decoding.
+
+#[cfg(test)]
+mod tests {
+    use crate::feeds::itch::{ItchBook, ItchMessage, ItchReader};
+    use crate::orderbook::OrderBook;
+    use pricelevel::Side;
+
+    fn header(kind: u8, locate: u16, timestamp: u64) -> Vec<u8> {
+        let mut data = vec![kind];
+        data.extend_from_slice(&locate.to_be_bytes());
+        data.extend_from_slice(&0u16.to_be_bytes());
+        data.extend_from_slice(&timestamp.to_be_bytes()[2..]);
+        data
+    }
+
+    fn stock(symbol: &str) -> Vec<u8> {
+        format!("{symbol:<8}").into_bytes()
+    }
+
+    fn directory(locate: u16, symbol: &str) -> Vec<u8> {
+        let mut data = header(b'R', locate, 1);
+        data.extend(stock(symbol));
+        data.resize(39, b' ');
+        data
+    }
+
+    fn add(
+        locate: u16,
+        order_ref: u64,
+        side: u8,
+        shares: u32,
+        symbol: &str,
+        price: u32,
+    ) -> Vec<u8> {
+        let mut data = header(b'A', locate, 2);
+        data.extend_from_slice(&order_ref.to_be_bytes());
+        data.push(side);
+        data.extend_from_slice(&shares.to_be_bytes());
+        data.extend(stock(symbol));
+        data.extend_from_slice(&price.to_be_bytes());
+        data
+    }
+
+    fn executed(locate: u16, order_ref: u64, shares: u32) -> Vec<u8> {
+        let mut data = header(b'E', locate, 3);
+        data.extend_from_slice(&order_ref.to_be_bytes());
+        data.extend_from_slice(&shares.to_be_bytes());
+        data.extend_from_slice(&77u64.to_be_bytes());
+        data
+    }
+
+    fn cancel(locate: u16, order_ref: u64, shares: u32) -> Vec<u8> {
+        let mut data = header(b'X', locate, 4);
+        data.extend_from_slice(&order_ref.to_be_bytes());
+        data.extend_from_slice(&shares.to_be_bytes());
+        data
+    }
+
+    fn delete(locate: u16, order_ref: u64) -> Vec<u8> {
+        let mut data = header(b'D', locate, 5);
+        data.extend_from_slice(&order_ref.to_be_bytes());
+        data
+    }
+
+    fn replace(locate: u16, original: u64, new: u64, shares: u32, price: u32) -> Vec<u8> {
+        let mut data = header(b'U', locate, 6);
+        data.extend_from_slice(&original.to_be_bytes());
+        data.extend_from_slice(&new.to_be_bytes());
+        data.extend_from_slice(&shares.to_be_bytes());
+        data.extend_from_slice(&price.to_be_bytes());
+        data
+    }
+
+    fn framed(messages: &[Vec<u8>]) -> Vec<u8> {
+        let mut stream = Vec::new();
+        for message in messages {
+            stream.extend_from_slice(&(message.len() as u16).to_be_bytes());
+            stream.extend_from_slice(message);
+        }
+        stream
+    }
+
+    #[test]
+    fn test_decode_add_order() {
+        let message = ItchMessage::decode(&add(7, 42, b'S', 300, "AAPL", 1_905_000)).unwrap();
+        assert_eq!(
+            message,
+            ItchMessage::AddOrder {
+                stock_locate: 7,
+                timestamp: 2,
+                order_ref: 42,
+                side: Side::Sell,
+                shares: 300,
+                stock: "AAPL".to_string(),
+                price: 1_905_000,
+                attribution: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_replace_and_unknown_types() {
+        let message = ItchMessage::decode(&replace(7, 42, 43, 100, 1_900_000)).unwrap();
+        assert_eq!(
+            message,
+            ItchMessage::OrderReplace {
+                stock_locate: 7,
+                timestamp: 6,
+                original_ref: 42,
+                new_ref: 43,
+                shares: 100,
+                price: 1_900_000,
+            }
+        );
+
+        let mut system_event = header(b'S', 0, 9);
+        system_event.push(b'O');
+        assert_eq!(
+            ItchMessage::decode(&system_event).unwrap(),
+            ItchMessage::Other {
+                kind: b'S',
+                stock_locate: 0,
+                timestamp: 9,
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed_messages() {
+        let mut truncated = add(7, 42, b'B', 300, "AAPL", 100);
+        truncated.truncate(30);
+        assert!(ItchMessage::decode(&truncated).is_err());
+        assert!(ItchMessage::decode(&add(7, 42, b'Z', 300, "AAPL", 100)).is_err());
+        assert!(ItchMessage::decode(&[b'A', 0]).is_err());
+    }
+
+    #[test]
+    fn test_reader_splits_stream() {
+        let stream = framed(&[directory(7, "AAPL"), delete(7, 1)]);
+        let messages: Vec<_> = ItchReader::new(stream.as_slice())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].stock_locate(), 7);
+
+        let mut cut = framed(&[delete(7, 1)]);
+        cut.pop();
+        let mut reader = ItchReader::new(cut.as_slice());
+        assert!(reader.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_itch_book_replays_stock() {
+        let stream = framed(&[
+            directory(7, "AAPL"),
+            directory(8, "MSFT"),
+            add(7, 1, b'B', 100, "AAPL", 1_900_000),
+            add(8, 2, b'B', 500, "MSFT", 4_100_000),
+            add(7, 3, b'S', 200, "AAPL", 1_905_000),
+            add(7, 4, b'S', 50, "AAPL", 1_910_000),
+            executed(7, 3, 150),
+            cancel(7, 1, 40),
+            replace(7, 4, 5, 80, 1_908_000),
+            add(7, 6, b'B', 10, "AAPL", 1_899_000),
+            delete(7, 6),
+        ]);
+
+        let mut itch = ItchBook::new(OrderBook::<()>::new("AAPL"), "AAPL");
+        let applied = itch.replay(ItchReader::new(stream.as_slice())).unwrap();
+
+        assert_eq!(applied, 8);
+        assert_eq!(itch.stock_locate(), Some(7));
+        let (bids, asks) = itch.book().depth(usize::MAX);
+        assert_eq!(bids, vec![(1_900_000, 60, 1)]);
+        assert_eq!(asks, vec![(1_905_000, 50, 1), (1_908_000, 80, 1)]);
+    }
+
+    #[test]
+    fn test_itch_book_learns_locate_from_add_order() {
+        let mut itch = ItchBook::new(OrderBook::<()>::new("AAPL"), "AAPL");
+        let other = ItchMessage::decode(&delete(7, 1)).unwrap();
+        assert!(!itch.apply(&other).unwrap());
+
+        let first = ItchMessage::decode(&add(7, 1, b'B', 100, "AAPL", 100)).unwrap();
+        assert!(itch.apply(&first).unwrap());
+        assert_eq!(itch.stock_locate(), Some(7));
+        assert_eq!(itch.book().best_bid(), Some(100));
+    }
+
+    #[test]
+    fn test_itch_book_rejects_unknown_order() {
+        let mut itch = ItchBook::new(OrderBook::<()>::new("AAPL"), "AAPL");
+        let first = ItchMessage::decode(&add(7, 1, b'B', 100, "AAPL", 100)).unwrap();
+        itch.apply(&first).unwrap();
+
+        let unknown = ItchMessage::decode(&executed(7, 99, 10)).unwrap();
+        assert!(itch.apply(&unknown).is_err());
+    }
+}
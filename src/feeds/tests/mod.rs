@@ -0,0 +1,7 @@
+mod adapter;
+#[cfg(feature = "binance")]
+mod binance;
+#[cfg(feature = "fix")]
+mod fix;
+#[cfg(feature = "itch")]
+mod itch;
@@ -50,22 +50,45 @@
 //!
 //! The order book provides comprehensive market analysis capabilities:
 //!
-//! - **VWAP Calculation**: Volume-Weighted Average Price for analyzing true market price
-//! - **Spread Analysis**: Absolute and basis point spread calculations
-//! - **Micro Price**: Fair price estimation incorporating depth
+//! - **VWAP Calculation**: Volume-Weighted Average Price for analyzing true market price, targeting a quantity with `vwap()` or a notional with `vwap_for_notional()` and `quantity_for_notional()`
+//! - **Spread Analysis**: Absolute and basis point spread calculations, with `enable_time_weighted_metrics()` adding `avg_spread_bps(window)`, `avg_top_depth(window)` and top-of-book imbalance averaged by the time each state lasted
+//! - **Micro Price**: Fair price estimation incorporating depth, and `weighted_fair_price(levels, decay)` generalizing it over the top N levels with exponentially decaying weights and per-level contributions
 //! - **Order Book Imbalance**: Buy/sell pressure indicators
-//! - **Market Impact Simulation**: Pre-trade analysis for estimating slippage and execution costs
+//! - **Order Flow Imbalance**: `enable_order_flow_imbalance(window_ms)` maintains the rolling OFI of best bid and offer changes, a short-horizon price predictor
+//! - **Market Impact Simulation**: Pre-trade analysis for estimating slippage and execution costs, with `slippage_curve(sizes, side)` computing the impact of many sizes in one pass, and `cost_to_move(n_ticks, side)` giving the quantity and notional needed to move the best price; `simulate_limit_order(price, qty, side)` reports the matching portion, resting remainder, queue position and resulting top of book without touching the book
+//! - **What-If Sandbox**: `sandbox()` opens a copy-on-write overlay of the book where hypothetical orders and cancellations can be applied and spread, imbalance, VWAP and market impact queried, without cloning or touching the live book
+//! - **Forking**: `fork()` copies the book into an independent branch with its orders, rules, session state, transaction ID sequence and tracker history, for backtests that branch at decision points
+//! - **Price Impact Estimation**: `impact_estimate()` reports Kyle's lambda, regressed from mid-price moves on signed trade flow, alongside the per-unit impact of walking the resting depth
 //! - **Depth Analysis**: Cumulative depth and liquidity distribution
+//! - **Book Resiliency**: `enable_resiliency_tracking(ratio, timeout_ms, capacity)` records trades that sweep whole levels and how long the touch takes to hold the given share of its depth again, summarized by `resiliency_stats()`
+//! - **Liquidity Heatmap**: `LiquidityHeatmap` samples resting depth into a bounded (timestamp, price bucket, quantity) series for heatmap rendering, downsampling older samples once full
+//! - **Trade Tape**: `enable_trade_tape(capacity)` keeps a bounded ring buffer of executions, with `tape_stats()` reporting trade count, volume, VWAP, buy/sell aggressor volumes and the largest trade over the last N milliseconds or trades
+//! - **Realized Volatility**: close-to-close, Parkinson and Garman-Klass estimators over rolling bars of trade or sampled mid prices, annualized for comparison with implied volatility
+//! - **Volume Profile**: `VolumeProfile` accumulates executed volume per price bucket from the trade stream, reporting the point of control and the value area high and low
 //!
 //! ### Intelligent Order Placement
 //!
 //! Advanced utilities for market makers and algorithmic traders:
 //!
-//! - **Queue Analysis**: `queue_ahead_at_price()` - Check depth at specific price levels
+//! - **Queue Analysis**: `queue_ahead_at_price()` - Check depth at specific price levels, `estimated_queue_position()` - Orders and quantity ahead of a resting order
+//! - **Fill Probability**: `enable_fill_probability(window_ms)` tracks executions and cancellations per price level, and `fill_probability(order_id, horizon_ms)` combines them with the queue position into a fill probability and expected time to fill
 //! - **Tick-Based Pricing**: `price_n_ticks_inside()` - Calculate prices N ticks from best bid/ask
 //! - **Position Targeting**: `price_for_queue_position()` - Find prices for target queue positions
 //! - **Depth-Based Strategy**: `price_at_depth_adjusted()` - Optimal prices based on cumulative depth
 //!
+//! ### Market Controls
+//!
+//! Rules that govern when and how orders trade:
+//!
+//! - **Trading Sessions**: `set_session_state()` moves a book through pre-open (orders rest without matching), open, halted (aggressive orders rejected) and closed (DAY orders purged), reporting each change to a session listener
+//! - **Market Close**: once the clock passes `set_market_close_timestamp()`, resting DAY orders expire before the next submission or match, or through an explicit `expire_day_orders(now)` sweep, each reported as an `Expired` order event
+//! - **Auctions**: `indicative_uncross()` publishes the equilibrium price of a pre-open book (maximum volume, minimum imbalance, market pressure, reference price) and `open_with_auction()` / `close_with_auction()` uncross it at that single price
+//! - **Circuit Breaker**: `CircuitBreaker` enforces static bands and ±bps dynamic bands around a `ReferencePriceSource` (last trade, prior close, external), rejecting or halting on violating orders and sweeps and reporting each hit
+//! - **Pre-Trade Risk Checks**: `RiskChecker` implementations run before an order is accepted, with built-in `MaxOrderQuantity`, `MaxNotional` and fat-finger `PriceCollar` checks rejecting orders with `OrderBookError::RiskRejected`
+//! - **Kill Switch**: `set_trading_enabled(false)` rejects every new order with `OrderBookError::TradingDisabled`, while `disable_owner` blocks a single owner and cancels its resting orders
+//! - **Rate Limiting**: `set_rate_limit` applies a per-owner token bucket to order submissions and cancellations, rejecting excess requests with `OrderBookError::RateLimited` and a retry-after hint
+//! - **Surveillance**: `enable_surveillance` tracks per-owner order-to-trade ratios, fleeting cancellations and flickering quotes over a rolling window, emitting a `SurveillanceAlert` when a configured threshold is crossed
+//!
 //! ### Functional Iterators
 //!
 //! Memory-efficient, composable iterators for order book analysis:
@@ -86,14 +109,26 @@
 //! Centralized trade event routing and multi-book orchestration:
 //!
 //! - **BookManager**: Manage multiple order books with unified trade listener
+//! - **Sharded Manager**: `ShardedBookManager` hashes symbols onto N worker threads for commands and trade routing, preserving per-symbol order
+//! - **Symbol Registry**: Per-symbol instrument type (spot, future, option), tick size, lot size and price band, enforced by each book
+//! - **Position Tracking**: `PositionTracker` keeps each owner's net position, average price and realized PnL per symbol from owner-attributed trades, queryable through `BookManager::position()` on managers built `with_position_tracking()`
+//! - **Fees**: `FeeSchedule` charges maker/taker basis-point rates with minimum fees and volume tiers on every trade of a book or manager, reporting each transaction's fees in `TradeResult::fees`
 //! - **Standard & Tokio Support**: Synchronous and async variants
-//! - **Event Routing**: Centralized trade notifications across all books
+//! - **Async Manager**: `AsyncBookManager` runs each book on its own Tokio task behind a bounded command channel, with graceful shutdown
+//! - **Engine Mode**: `OrderBook::into_engine()` funnels every mutation through a bounded lock-free queue into one matching thread, with `EngineHandle` returning blocking `Receipt`s or futures, while readers query the shared book directly
+//! - **Event Routing**: Centralized trade notifications across all books, passed to a pluggable `TradeEventHandler`
+//! - **Runtime Subscriptions**: Attach and detach trade and price level listeners to one or all managed books at any time
+//! - **Aggregate Stats**: `aggregate_stats()` reports order, level, volume and trade-rate metrics per symbol and in total for monitoring
+//! - **Bulk Snapshot/Restore**: `snapshot_all()` and `restore_all()` move every book and its configuration in one checksum-protected `ManagerSnapshot`
+//! - **Graceful Shutdown**: `shutdown()` drains pending trade events, stops the processor and can snapshot every book
+//! - **Consolidated Book**: `ConsolidatedBook` merges one instrument's books across venues with per-venue attribution, NBBO and imbalance
+//! - **Spread Book**: `SpreadBook` implies calendar and inter-product spread prices from two leg books, and leg prices from an outright spread book, recalculating when a leg's top changes
 //!
 //! ### Aggregate Statistics
 //!
 //! Comprehensive statistical analysis for market condition detection:
 //!
-//! - **Depth Statistics**: `depth_statistics()` - Volume, average sizes, weighted prices, std dev
+//! - **Depth Statistics**: `depth_statistics()` - Volume, average sizes, weighted prices, std dev; `depth_statistics_both()` covers both sides in one pass with volume ratio and distance-from-mid skew
 //! - **Market Pressure**: `buy_sell_pressure()` - Total volume on each side
 //! - **Liquidity Health**: `is_thin_book()` - Detect insufficient liquidity
 //! - **Distribution Analysis**: `depth_distribution()` - Histogram of liquidity concentration
@@ -189,6 +224,40 @@
 //! | 75%                      | 8,834,677.82        |
 //! | 100%                     | 19,403,341.34       |
 //!
+//! ### Sharded Order Locations
+//!
+//! `BookConfig::with_location_shards` sets how many shards the map locating resting orders by ID is split into; every cancel, update and lookup by ID locks one of them. The contention test runs each hot spot case against the default map and against one split into 256 shards (12 threads, 3 s per case, release build):
+//!
+//! | % Operations on Hot Spot | Default (ops/s) | 256 Shards (ops/s) |
+//! |--------------------------|-----------------|--------------------|
+//! | 0%                       | 2,366,753.33    | 2,438,242.54       |
+//! | 25%                      | 3,049,905.46    | 3,037,949.47       |
+//! | 50%                      | 4,080,946.90    | 3,934,237.16       |
+//! | 75%                      | 6,422,931.80    | 6,167,969.56       |
+//! | 100%                     | 14,231,703.78   | 14,636,160.82      |
+//!
+//! These figures were taken on a single-CPU machine, where the threads never hold shard locks at the same time, so both modes perform alike. More shards pay off once cancels and lookups run in parallel on many cores; run `cargo run --release --bin orderbook_contention_test` to compare them on the target hardware.
+//!
+//! ### Vectorized Depth Sums
+//!
+//! `depth_statistics` and `depth_distribution` copy the levels they read into a per-thread scratch buffer of packed prices and quantities and sum over those slices. Building with `--features simd` sums runs of 32 levels or more over 8 lanes that stable rustc turns into vector instructions, falling back to the scalar loops when a sum could overflow. Median times of the depth analytics benchmarks (single CPU, release build):
+//!
+//! | Benchmark                       | Before      | Packed      | Packed + `simd` |
+//! |---------------------------------|-------------|-------------|-----------------|
+//! | `depth_statistics_10/100`       | 643 ns      | 597 ns      | 635 ns          |
+//! | `depth_statistics_all/100`      | 5.66 µs     | 5.65 µs     | 5.43 µs         |
+//! | `depth_distribution_20/100`     | 9.93 µs     | 5.97 µs     | 5.35 µs         |
+//! | `depth_statistics_10/10000`     | 794 ns      | 662 ns      | 639 ns          |
+//! | `depth_statistics_all/10000`    | 607 µs      | 610 µs      | 571 µs          |
+//! | `depth_distribution_20/10000`   | 1.07 ms     | 663 µs      | 548 µs          |
+//!
+//! Walking the skip list to pack the levels dominates the statistics, so the lanes mostly help the distribution, which sums every bin. To compare both builds on the target hardware:
+//!
+//! ```sh
+//! cargo bench --bench benches -- "Depth Analytics" --save-baseline scalar
+//! cargo bench --bench benches --features simd -- "Depth Analytics" --baseline scalar
+//! ```
+//!
 //! ### Performance Improvements and Deadlock Resolution
 //!
 //! The significant performance gains, especially in the "Hot Spot Contention Test," and the resolution of the previous deadlocks are a direct result of refactoring the internal concurrency model of the `PriceLevel`.
@@ -232,23 +301,104 @@
 //!
 //! This analysis confirms that the system design is highly scalable and appropriate for demanding financial applications requiring high-speed processing with data consistency.
 
+/// Export of book data for offline analysis.
+pub mod export;
+/// Exchange market data feeds and adapters.
+pub mod feeds;
 pub mod orderbook;
+/// Recording of trades and book states for research datasets.
+pub mod recording;
 
 pub mod prelude;
 mod utils;
+/// Zero-copy FlatBuffers wire format for snapshots and deltas.
+#[cfg(feature = "wire")]
+pub mod wire;
 
+pub use orderbook::MAX_LADDER_TICKS;
+#[cfg(feature = "tokio")]
+pub use orderbook::async_manager::{AsyncBookManager, BookHandle};
+pub use orderbook::auction::{AuctionResult, AuctionUncross};
+pub use orderbook::bbo::Bbo;
+pub use orderbook::book::DepthLevel;
+pub use orderbook::circuit_breaker::{
+    BandAction, BandTrigger, CircuitBreaker, PriceBandEvent, PriceBandLimits, PriceBandListener,
+    ReferencePriceSource,
+};
+pub use orderbook::config::{BookConfig, LevelEviction};
+pub use orderbook::consolidated::{ConsolidatedBook, ConsolidatedLevel, Nbbo, VenueQuantity};
+pub use orderbook::delta::{LevelChange, OrderBookDelta};
+pub use orderbook::engine::{EngineHandle, MatchingEngine, Receipt};
+pub use orderbook::fair_price::{FairPriceContribution, WeightedFairPrice};
+pub use orderbook::fees::{FeeModel, FeeSchedule, FeeTier, TransactionFee};
+pub use orderbook::fill_probability::{FillEstimate, LevelFlowTracker, LevelIntensity};
+pub use orderbook::full_state::OrderBookFullState;
+pub use orderbook::heatmap::{
+    HeatmapCell, HeatmapColumn, HeatmapConfig, HeatmapPoint, LiquidityHeatmap,
+};
 pub use orderbook::implied_volatility::{
-    BlackScholes, IVConfig, IVError, IVParams, IVQuality, IVResult, OptionType, PriceSource,
-    SolverConfig,
+    ArbitrageViolation, BatchVolatility, BinomialTree, BlackScholes, ExerciseStyle, GreeksResult,
+    IVConfig, IVDiagnostics, IVError, IVParams, IVQuality, IVResult, IVSmile, ImpliedForward,
+    OptionPosition, OptionType, ParityQuote, PortfolioConfig, PortfolioGreeks, PositionFailure,
+    PositionGreeks, PriceSource, SmileConfig, SmileFailure, SmilePoint, SolverConfig, SolverMethod,
+    SurfacePoint, SurfaceSlice, SviFit, SviParams, SviResidual, TermPoint, TermStructure,
+    VolSurface,
 };
+pub use orderbook::integrity::{CrossedBook, IntegrityReport};
 pub use orderbook::iterators::LevelInfo;
-pub use orderbook::manager::{BookManager, BookManagerStd, BookManagerTokio};
-pub use orderbook::market_impact::{MarketImpact, OrderSimulation};
-pub use orderbook::snapshot::{EnrichedSnapshot, MetricFlags};
-pub use orderbook::statistics::{DepthStats, DistributionBin};
+#[cfg(all(feature = "mmap", unix))]
+pub use orderbook::journal::MmapJournal;
+pub use orderbook::journal::{FileJournal, InMemoryJournal, Journal, JournalCommand, JournalEntry};
+pub use orderbook::l3_feed::{
+    L3ApplyOutcome, L3FeedApplier, L3Message, L3Order, L3Snapshot, L3SnapshotSource, L3Update,
+};
+#[cfg(feature = "tokio")]
+pub use orderbook::manager::BookManagerTokio;
+pub use orderbook::manager::{BookManager, BookManagerStd, LoggingTradeHandler, TradeEventHandler};
+pub use orderbook::manager_snapshot::{ManagedBookSnapshot, ManagerSnapshot};
+pub use orderbook::manager_stats::{BookStats, ManagerStats};
+pub use orderbook::market_impact::{
+    LimitOrderSimulation, MarketImpact, OrderSimulation, PriceMoveCost,
+};
+pub use orderbook::memory::MemoryStats;
+pub use orderbook::order_event::{OrderEvent, OrderEventListener};
+pub use orderbook::order_flow::{OfiSample, OrderFlowImbalance};
+pub use orderbook::owner::OwnerId;
+pub use orderbook::pool::{
+    DEFAULT_LEVEL_POOL_CAPACITY, DEFAULT_ORDER_POOL_CAPACITY, LevelPool, OrderPool, PoolStats,
+};
+pub use orderbook::positions::{Position, PositionTracker};
+pub use orderbook::quotes::QuotePair;
+pub use orderbook::rate_limit::{RateLimit, RateLimiter};
+pub use orderbook::replay::{ReplayEngine, ReplaySpeed, ReplayStatus};
+pub use orderbook::resiliency::{ResiliencyStats, ResiliencyTracker, SweepEvent};
+pub use orderbook::risk::{
+    MaxNotional, MaxOrderQuantity, PriceCollar, RiskChecker, RiskContext, RiskOrder,
+};
+pub use orderbook::sandbox::OrderBookSandbox;
+pub use orderbook::scheduler::{
+    FileSnapshotStore, InMemorySnapshotStore, SnapshotScheduler, SnapshotStore, SnapshotTrigger,
+};
+pub use orderbook::session::{SessionListener, SessionState, SessionStateChangedEvent};
+pub use orderbook::sharded_manager::ShardedBookManager;
+pub use orderbook::snapshot::{EnrichedSnapshot, MetricFlags, SnapshotFormat};
+pub use orderbook::spread_book::{ImpliedQuote, ImpliedQuotes, SpreadBook, SpreadLeg};
+pub use orderbook::statistics::{
+    BookDepthStats, DepthStats, DistributionBin, ImpactEstimate, KyleLambda, LambdaEstimate,
+    PriceBar, RealizedVolatility, VolatilityEstimator, VolatilitySource,
+};
+pub use orderbook::subscription::SubscriptionId;
+pub use orderbook::surveillance::{
+    OwnerActivity, Surveillance, SurveillanceAlert, SurveillanceConfig, SurveillanceListener,
+    SurveillanceMetric,
+};
+pub use orderbook::symbol_config::{InstrumentType, SymbolConfig, SymbolRegistry};
+pub use orderbook::time_weighted::{TimeWeightedLiquidity, TimeWeightedStats};
 pub use orderbook::trade::{TradeListener, TradeResult};
+pub use orderbook::trade_tape::{TapeEntry, TapeStats, TapeWindow, TradeTape};
+pub use orderbook::volume_profile::{VolumeProfile, VolumeProfileLevel, VolumeProfileSummary};
 pub use orderbook::{OrderBook, OrderBookError, OrderBookSnapshot};
-pub use utils::current_time_millis;
+pub use utils::{Clock, ManualClock, SystemClock, current_time_millis};
 
 /// Legacy type alias for `OrderBook<()>` to maintain backward compatibility.
 ///
@@ -0,0 +1,423 @@
+//! Async book manager running every book on its own Tokio task.
+//!
+//! Each book is owned by a task that handles commands from a bounded mpsc
+//! channel one at a time, so commands to one book are applied in the order they
+//! were sent and never contend for the book. Callers talk to a book through a
+//! [`BookHandle`], which awaits each result on a oneshot channel. Trades of every
+//! managed book are fanned out on a single broadcast channel.
+
+use crate::orderbook::OrderBook;
+use crate::orderbook::error::OrderBookError;
+use crate::orderbook::snapshot::OrderBookSnapshot;
+use crate::orderbook::symbol_config::{SymbolConfig, SymbolRegistry};
+use crate::orderbook::trade::{TradeEvent, TradeListener, TradeResult};
+use crate::utils::{Clock, SystemClock};
+use pricelevel::{MatchResult, OrderId, OrderType, Side, TimeInForce};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+/// Default number of commands a book buffers before senders wait.
+pub const BOOK_COMMAND_CAPACITY: usize = 1024;
+
+/// Number of trade events the trade channel buffers for its slowest receiver.
+pub const TRADE_CHANNEL_CAPACITY: usize = 1024;
+
+/// Work run by a book task against its book.
+type BookJob<T> = Box<dyn FnOnce(&mut OrderBook<T>) + Send>;
+
+/// A command sent to a book task.
+enum BookCommand<T> {
+    /// Run a job against the book
+    Execute(BookJob<T>),
+    /// Stop the task once the commands queued before this one were handled
+    Shutdown,
+}
+
+/// Cloneable handle sending commands to the task owning one book.
+///
+/// Every method waits for room in the book's command channel, so a busy book
+/// applies backpressure to its producers, then waits for the task to reply.
+/// Methods return `OrderBookError::BookStopped` once the book was removed or the
+/// manager shut down.
+pub struct BookHandle<T> {
+    symbol: Arc<str>,
+    sender: mpsc::Sender<BookCommand<T>>,
+}
+
+impl<T> Clone for BookHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            symbol: Arc::clone(&self.symbol),
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for BookHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BookHandle")
+            .field("symbol", &self.symbol)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> BookHandle<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Symbol of the book
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Whether the task owning the book is still accepting commands
+    pub fn is_running(&self) -> bool {
+        !self.sender.is_closed()
+    }
+
+    /// Runs `job` on the book task and returns its result
+    ///
+    /// The job has exclusive access to the book, so it must not block: a slow job
+    /// delays every command queued behind it.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::BookStopped` if the book task stopped.
+    pub async fn execute<R, F>(&self, job: F) -> Result<R, OrderBookError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut OrderBook<T>) -> R + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        let job: BookJob<T> = Box::new(move |book| {
+            // The caller may have stopped waiting for the result
+            let _ = reply.send(job(book));
+        });
+        self.sender
+            .send(BookCommand::Execute(job))
+            .await
+            .map_err(|_| self.stopped())?;
+        result.await.map_err(|_| self.stopped())
+    }
+
+    /// Adds a limit order to the book, see [`OrderBook::add_limit_order`]
+    ///
+    /// # Errors
+    /// Returns the error of the book, or `OrderBookError::BookStopped` if the book
+    /// task stopped.
+    pub async fn add_limit_order(
+        &self,
+        id: OrderId,
+        price: u64,
+        quantity: u64,
+        side: Side,
+        time_in_force: TimeInForce,
+        extra_fields: Option<T>,
+    ) -> Result<Arc<OrderType<T>>, OrderBookError> {
+        self.execute(move |book| {
+            book.add_limit_order(id, price, quantity, side, time_in_force, extra_fields)
+        })
+        .await?
+    }
+
+    /// Cancels an order of the book, see [`OrderBook::cancel_order`]
+    ///
+    /// # Errors
+    /// Returns the error of the book, or `OrderBookError::BookStopped` if the book
+    /// task stopped.
+    pub async fn cancel_order(
+        &self,
+        order_id: OrderId,
+    ) -> Result<Option<Arc<OrderType<T>>>, OrderBookError> {
+        self.execute(move |book| book.cancel_order(order_id))
+            .await?
+    }
+
+    /// Matches a market order against the book, see [`OrderBook::submit_market_order`]
+    ///
+    /// # Errors
+    /// Returns the error of the book, or `OrderBookError::BookStopped` if the book
+    /// task stopped.
+    pub async fn submit_market_order(
+        &self,
+        id: OrderId,
+        quantity: u64,
+        side: Side,
+    ) -> Result<MatchResult, OrderBookError> {
+        self.execute(move |book| book.submit_market_order(id, quantity, side))
+            .await?
+    }
+
+    /// Captures up to `depth` levels per side of the book
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::BookStopped` if the book task stopped.
+    pub async fn snapshot(&self, depth: usize) -> Result<OrderBookSnapshot, OrderBookError> {
+        self.execute(move |book| book.create_snapshot(depth)).await
+    }
+
+    fn stopped(&self) -> OrderBookError {
+        OrderBookError::BookStopped {
+            symbol: self.symbol.to_string(),
+        }
+    }
+}
+
+/// A managed book: its handle and the task owning it.
+struct ManagedBook<T> {
+    handle: BookHandle<T>,
+    task: JoinHandle<OrderBook<T>>,
+}
+
+/// Book manager running every book on its own Tokio task.
+///
+/// Books must be added from within a Tokio runtime. The trades of every book are
+/// published on one broadcast channel; a receiver that falls more than
+/// [`TRADE_CHANNEL_CAPACITY`] events behind observes `RecvError::Lagged`.
+///
+/// [`AsyncBookManager::shutdown`] stops the tasks gracefully: every command sent
+/// before the call is handled, then the books are handed back.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::orderbook::async_manager::AsyncBookManager;
+/// use pricelevel::{OrderId, Side, TimeInForce};
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// runtime.block_on(async {
+///     let mut manager = AsyncBookManager::<()>::new();
+///     let mut trades = manager.subscribe_trades();
+///     let book = manager.add_book("BTC/USD");
+///
+///     book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None)
+///         .await
+///         .unwrap();
+///     book.submit_market_order(OrderId::new(), 4, Side::Buy).await.unwrap();
+///
+///     let event = trades.recv().await.unwrap();
+///     assert_eq!(event.symbol, "BTC/USD");
+///
+///     let books = manager.shutdown().await;
+///     assert_eq!(books["BTC/USD"].best_ask(), Some(100));
+/// });
+/// ```
+pub struct AsyncBookManager<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Books indexed by symbol
+    books: HashMap<String, ManagedBook<T>>,
+    /// Sender of the trade events of every book
+    trade_sender: broadcast::Sender<TradeEvent>,
+    /// Clock shared by every managed book and used to timestamp trade events
+    clock: Arc<dyn Clock>,
+    /// Capacity of the command channel of books added from now on
+    command_capacity: usize,
+    /// Configurations of the managed symbols
+    registry: SymbolRegistry,
+}
+
+impl<T> AsyncBookManager<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Create a manager using the system clock
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Create a manager whose books and trade events use `clock` for time
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        let (trade_sender, _) = broadcast::channel(TRADE_CHANNEL_CAPACITY);
+        Self {
+            books: HashMap::new(),
+            trade_sender,
+            clock,
+            command_capacity: BOOK_COMMAND_CAPACITY,
+            registry: SymbolRegistry::new(),
+        }
+    }
+
+    /// Sets the number of commands each book buffers before senders wait
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero.
+    #[must_use]
+    pub fn with_command_capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "command capacity must be positive");
+        self.command_capacity = capacity;
+        self
+    }
+
+    /// Adds a book for `symbol` on a new task and returns its handle
+    ///
+    /// The symbol is registered as a spot instrument without trading rules.
+    /// Returns the existing handle if the manager already has a book for `symbol`.
+    ///
+    /// # Panics
+    /// Panics if called outside a Tokio runtime.
+    pub fn add_book(&mut self, symbol: &str) -> BookHandle<T> {
+        self.add_book_with_config(symbol, SymbolConfig::default())
+    }
+
+    /// Adds a book for `symbol` enforcing the trading rules of `config` and
+    /// returns its handle
+    ///
+    /// Returns the existing handle, keeping its configuration, if the manager
+    /// already has a book for `symbol`.
+    ///
+    /// # Panics
+    /// Panics if called outside a Tokio runtime.
+    pub fn add_book_with_config(&mut self, symbol: &str, config: SymbolConfig) -> BookHandle<T> {
+        if let Some(managed) = self.books.get(symbol) {
+            return managed.handle.clone();
+        }
+
+        let sender = self.trade_sender.clone();
+        let clock = Arc::clone(&self.clock);
+        let trade_listener: TradeListener = Arc::new(move |trade_result: &TradeResult| {
+            // Sending only fails while there are no receivers
+            let _ = sender.send(TradeEvent {
+                symbol: trade_result.symbol.clone(),
+                trade_result: trade_result.clone(),
+                timestamp: clock.now_millis(),
+            });
+        });
+        let mut book = OrderBook::with_trade_listener(symbol, trade_listener);
+        book.set_clock(Arc::clone(&self.clock));
+        book.apply_config(config.book);
+
+        let (sender, receiver) = mpsc::channel(self.command_capacity);
+        let handle = BookHandle {
+            symbol: Arc::from(symbol),
+            sender,
+        };
+        let task = tokio::spawn(Self::run(book, receiver));
+        self.books.insert(
+            symbol.to_string(),
+            ManagedBook {
+                handle: handle.clone(),
+                task,
+            },
+        );
+        self.registry.register(symbol, config);
+        info!("Added async order book for symbol: {}", symbol);
+        handle
+    }
+
+    /// Handle of the book for `symbol`
+    pub fn book(&self, symbol: &str) -> Option<BookHandle<T>> {
+        self.books.get(symbol).map(|managed| managed.handle.clone())
+    }
+
+    /// Configurations of the managed symbols
+    pub fn registry(&self) -> &SymbolRegistry {
+        &self.registry
+    }
+
+    /// Configuration of `symbol`
+    pub fn symbol_config(&self, symbol: &str) -> Option<&SymbolConfig> {
+        self.registry.get(symbol)
+    }
+
+    /// Symbols of the managed books
+    pub fn symbols(&self) -> Vec<String> {
+        self.books.keys().cloned().collect()
+    }
+
+    /// Whether the manager has a book for `symbol`
+    pub fn has_book(&self, symbol: &str) -> bool {
+        self.books.contains_key(symbol)
+    }
+
+    /// Number of managed books
+    pub fn book_count(&self) -> usize {
+        self.books.len()
+    }
+
+    /// Subscribe to the trades of every managed book
+    ///
+    /// Receivers only get the trades executed after they subscribed.
+    pub fn subscribe_trades(&self) -> broadcast::Receiver<TradeEvent> {
+        self.trade_sender.subscribe()
+    }
+
+    /// Stops the task of the book for `symbol` and returns the book
+    ///
+    /// Commands sent before the call are handled first; later commands fail with
+    /// `OrderBookError::BookStopped`. Returns `None` if there is no such book or
+    /// its task panicked.
+    pub async fn remove_book(&mut self, symbol: &str) -> Option<OrderBook<T>> {
+        let managed = self.books.remove(symbol)?;
+        self.registry.remove(symbol);
+        let book = Self::stop(symbol, managed).await;
+        if book.is_some() {
+            info!("Removed async order book for symbol: {}", symbol);
+        }
+        book
+    }
+
+    /// Stops every book task and returns the books by symbol
+    ///
+    /// Every command sent before the call is handled before the books are returned.
+    /// Books whose task panicked are left out.
+    pub async fn shutdown(mut self) -> HashMap<String, OrderBook<T>> {
+        let mut books = HashMap::with_capacity(self.books.len());
+        for (symbol, managed) in self.books.drain() {
+            if let Some(book) = Self::stop(&symbol, managed).await {
+                books.insert(symbol, book);
+            }
+        }
+        info!("Async book manager shut down");
+        books
+    }
+
+    async fn stop(symbol: &str, managed: ManagedBook<T>) -> Option<OrderBook<T>> {
+        // A closed channel means the task already ended
+        let _ = managed.handle.sender.send(BookCommand::Shutdown).await;
+        match managed.task.await {
+            Ok(book) => Some(book),
+            Err(e) => {
+                error!("Book task for {} failed: {}", symbol, e);
+                None
+            }
+        }
+    }
+
+    /// Handles the commands of one book until shutdown
+    async fn run(
+        mut book: OrderBook<T>,
+        mut receiver: mpsc::Receiver<BookCommand<T>>,
+    ) -> OrderBook<T> {
+        while let Some(command) = receiver.recv().await {
+            match command {
+                BookCommand::Execute(job) => job(&mut book),
+                BookCommand::Shutdown => break,
+            }
+        }
+        book
+    }
+}
+
+impl<T> Default for AsyncBookManager<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for AsyncBookManager<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    fn drop(&mut self) {
+        // Best effort: a task whose channel is full stops once every handle is dropped
+        for managed in self.books.values() {
+            let _ = managed.handle.sender.try_send(BookCommand::Shutdown);
+        }
+    }
+}
@@ -0,0 +1,387 @@
+//! Opening and closing auctions: equilibrium price discovery and uncrossing.
+
+use super::book::OrderBook;
+use super::error::OrderBookError;
+use super::journal::JournalCommand;
+use super::owner::OwnerId;
+use super::session::SessionState;
+use super::trade::TradeResult;
+use pricelevel::{MatchResult, OrderId, Side, Transaction};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::Ordering;
+use tracing::trace;
+
+/// Equilibrium of a crossed book: the single price at which an auction uncrosses it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuctionUncross {
+    /// Price every auction trade executes at (in price units)
+    pub price: u64,
+    /// Quantity executed at the price (in units)
+    pub matched_quantity: u64,
+    /// Quantity left unexecuted on the heavier side at the price (in units)
+    pub imbalance: u64,
+    /// Side with the unexecuted quantity, or `None` when both sides are balanced
+    pub imbalance_side: Option<Side>,
+}
+
+/// Trades executed by an auction uncross.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuctionResult {
+    /// Equilibrium the auction executed at
+    pub uncross: AuctionUncross,
+    /// Executions, each pairing a buy order (reported as taker) with a sell order
+    pub transactions: Vec<Transaction>,
+}
+
+/// Execution of part of a resting order during an uncross
+struct AuctionFill {
+    order_id: OrderId,
+    owner: Option<OwnerId>,
+    quantity: u64,
+    /// Whether the order has no quantity left after this fill
+    completes: bool,
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Equilibrium price an auction would uncross the book at right now
+    ///
+    /// Intended for continuous publication during the pre-open phase. Candidate
+    /// prices are the prices of the crossed levels, and the equilibrium is chosen by
+    /// the usual rules, in order:
+    ///
+    /// 1. the price executing the largest quantity;
+    /// 2. the price leaving the smallest imbalance;
+    /// 3. market pressure: the highest price if every remaining candidate leaves
+    ///    surplus demand, the lowest if every one leaves surplus supply;
+    /// 4. the price closest to the reference price, which is the last trade price
+    ///    or, before the first trade, the middle of the remaining candidates. The
+    ///    lower price wins a remaining tie.
+    ///
+    /// Hidden orders take part in the auction like visible ones.
+    ///
+    /// # Returns
+    /// `None` if the book is not crossed, so nothing would execute.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{OrderBook, SessionState};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// book.set_session_state(SessionState::PreOpen).unwrap();
+    /// book.add_limit_order(OrderId::new(), 102, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 100, 4, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 101, 4, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// let uncross = book.indicative_uncross().unwrap();
+    /// assert_eq!((uncross.price, uncross.matched_quantity), (102, 8));
+    /// assert_eq!((uncross.imbalance, uncross.imbalance_side), (2, Some(Side::Buy)));
+    /// ```
+    #[must_use]
+    pub fn indicative_uncross(&self) -> Option<AuctionUncross> {
+        let demand = self.auction_interest(Side::Buy);
+        let supply = self.auction_interest(Side::Sell);
+        let (&best_bid, &best_ask) = (demand.keys().next_back()?, supply.keys().next()?);
+        if best_bid < best_ask {
+            return None;
+        }
+
+        let candidates: Vec<AuctionUncross> = demand
+            .keys()
+            .chain(supply.keys())
+            .filter(|&&price| (best_ask..=best_bid).contains(&price))
+            .copied()
+            .collect::<std::collections::BTreeSet<u64>>()
+            .into_iter()
+            .map(|price| {
+                let bought: u64 = demand.range(price..).map(|(_, quantity)| quantity).sum();
+                let sold: u64 = supply.range(..=price).map(|(_, quantity)| quantity).sum();
+                AuctionUncross {
+                    price,
+                    matched_quantity: bought.min(sold),
+                    imbalance: bought.abs_diff(sold),
+                    imbalance_side: match bought.cmp(&sold) {
+                        std::cmp::Ordering::Greater => Some(Side::Buy),
+                        std::cmp::Ordering::Less => Some(Side::Sell),
+                        std::cmp::Ordering::Equal => None,
+                    },
+                }
+            })
+            .collect();
+
+        let volume = candidates.iter().map(|c| c.matched_quantity).max()?;
+        let candidates: Vec<_> = candidates
+            .into_iter()
+            .filter(|c| c.matched_quantity == volume)
+            .collect();
+        let imbalance = candidates.iter().map(|c| c.imbalance).min()?;
+        let candidates: Vec<_> = candidates
+            .into_iter()
+            .filter(|c| c.imbalance == imbalance)
+            .collect();
+
+        let all_on = |side| candidates.iter().all(|c| c.imbalance_side == Some(side));
+        if all_on(Side::Buy) {
+            return candidates.last().copied();
+        }
+        if all_on(Side::Sell) {
+            return candidates.first().copied();
+        }
+
+        let reference = if self.has_traded.load(Ordering::Relaxed) {
+            self.last_trade_price.load(Ordering::Relaxed)
+        } else {
+            let (low, high) = (candidates.first()?.price, candidates.last()?.price);
+            low + (high - low) / 2
+        };
+        candidates
+            .into_iter()
+            .min_by_key(|c| c.price.abs_diff(reference))
+    }
+
+    /// Uncrosses the book at its equilibrium price
+    ///
+    /// The book must be in [`SessionState::PreOpen`], the call phase in which orders
+    /// were collected without matching. Orders execute in price-time priority, all
+    /// at the price returned by [`OrderBook::indicative_uncross`]. Each buy order's
+    /// executions are published to the trade listeners as one trade with the buy
+    /// order as taker. Orders that do not execute keep resting.
+    ///
+    /// # Returns
+    /// The equilibrium and the executions, or `None` if the book was not crossed.
+    ///
+    /// # Errors
+    /// Returns [`OrderBookError::SessionRejected`] outside the pre-open phase, or an
+    /// error if the uncross cannot be journaled.
+    pub fn uncross(&self) -> Result<Option<AuctionResult>, OrderBookError> {
+        let _journaled = self.begin_journaled(|| JournalCommand::Uncross)?;
+        let state = self.session_state();
+        if state != SessionState::PreOpen {
+            return Err(OrderBookError::SessionRejected {
+                state,
+                reason: "an auction can only uncross a pre-open book".to_string(),
+            });
+        }
+
+        let Some(uncross) = self.indicative_uncross() else {
+            return Ok(None);
+        };
+        trace!(
+            "Order book {}: Uncrossing {} at {}",
+            self.symbol, uncross.matched_quantity, uncross.price
+        );
+
+        let transactions = self.with_batched_level_changes(|| {
+            let buys = self.fill_auction_side(Side::Buy, uncross);
+            let sells = self.fill_auction_side(Side::Sell, uncross);
+            self.last_trade_price
+                .store(uncross.price, Ordering::Relaxed);
+            self.has_traded.store(true, Ordering::Relaxed);
+            self.last_trade_millis
+                .store(self.clock.now_millis(), Ordering::Relaxed);
+            self.publish_auction_trades(uncross.price, &buys, &sells)
+        });
+
+        Ok(Some(AuctionResult {
+            uncross,
+            transactions,
+        }))
+    }
+
+    /// Runs the opening auction: uncrosses the pre-open book, then opens it for
+    /// continuous trading
+    ///
+    /// # Errors
+    /// Returns an error if the book is not in [`SessionState::PreOpen`].
+    pub fn open_with_auction(&self) -> Result<Option<AuctionResult>, OrderBookError> {
+        let result = self.uncross()?;
+        self.set_session_state(SessionState::Open)?;
+        Ok(result)
+    }
+
+    /// Runs the closing auction: uncrosses the book collected since moving from
+    /// [`SessionState::Open`] to [`SessionState::PreOpen`], then closes the session
+    ///
+    /// # Errors
+    /// Returns an error if the book is not in [`SessionState::PreOpen`].
+    pub fn close_with_auction(&self) -> Result<Option<AuctionResult>, OrderBookError> {
+        let result = self.uncross()?;
+        self.set_session_state(SessionState::Closed)?;
+        Ok(result)
+    }
+
+    /// Resting quantity per price on `side`, visible and hidden combined
+    fn auction_interest(&self, side: Side) -> BTreeMap<u64, u64> {
+        let mut interest = BTreeMap::new();
+        for hidden in [false, true] {
+            for entry in self.side_levels(side, hidden).iter() {
+                let quantity = entry.value().total_quantity();
+                if quantity > 0 {
+                    *interest.entry(*entry.key()).or_insert(0) += quantity;
+                }
+            }
+        }
+        interest
+    }
+
+    /// Executes the auction quantity of `side` in price-time priority, visible
+    /// orders ahead of hidden ones at the same price
+    fn fill_auction_side(&self, side: Side, uncross: AuctionUncross) -> Vec<AuctionFill> {
+        let eligible = match side {
+            Side::Buy => uncross.price..=u64::MAX,
+            Side::Sell => 0..=uncross.price,
+        };
+        let mut prices: Vec<u64> = self
+            .auction_interest(side)
+            .range(eligible)
+            .map(|(price, _)| *price)
+            .collect();
+        if side == Side::Buy {
+            prices.reverse();
+        }
+
+        let mut remaining = uncross.matched_quantity;
+        let mut fills = Vec::new();
+        for price in prices {
+            for hidden in [false, true] {
+                if remaining == 0 {
+                    return fills;
+                }
+                let price_levels = self.side_levels(side, hidden);
+                let Some(entry) = price_levels.get(&price) else {
+                    continue;
+                };
+                let level = entry.value();
+                let level_match =
+                    level.match_order(remaining, OrderId::new(), &self.transaction_id_generator);
+                if !hidden {
+                    self.totals
+                        .removed(side, remaining - level_match.remaining_quantity);
+                }
+                remaining = level_match.remaining_quantity;
+
+                let executions = level_match.transactions.as_vec();
+                // The level draws an ID for each execution even though auction
+                // transactions are reported with their own IDs
+                self.transaction_count
+                    .fetch_add(executions.len() as u64, Ordering::Relaxed);
+                for (index, execution) in executions.iter().enumerate() {
+                    let order_id = execution.maker_order_id;
+                    let completes = level_match.filled_order_ids.contains(&order_id)
+                        && !executions[index + 1..]
+                            .iter()
+                            .any(|later| later.maker_order_id == order_id);
+                    fills.push(AuctionFill {
+                        order_id,
+                        owner: self.order_owner(order_id),
+                        quantity: execution.quantity,
+                        completes,
+                    });
+                }
+                for order_id in &level_match.filled_order_ids {
+                    self.forget_order(order_id);
+                }
+
+                if !hidden && !executions.is_empty() {
+                    self.notify_price_level_changed(side, level);
+                }
+                if level.order_count() == 0 {
+                    self.prune_empty_level(price_levels, price);
+                }
+            }
+        }
+        fills
+    }
+
+    /// Pairs the buy and sell fills into transactions at `price`, reporting the
+    /// fills and publishing one trade per buy order
+    fn publish_auction_trades(
+        &self,
+        price: u64,
+        buys: &[AuctionFill],
+        sells: &[AuctionFill],
+    ) -> Vec<Transaction> {
+        for fill in buys.iter().chain(sells) {
+            self.emit_fill_event(fill.order_id, price, fill.quantity, fill.completes);
+        }
+
+        let timestamp = self.clock.now_millis();
+        let mut transactions = Vec::new();
+        let mut trade: Option<TradeResult> = None;
+        let (mut buy_left, mut sell_left) = (0, 0);
+        let (mut buy_iter, mut sell_iter) = (buys.iter(), sells.iter());
+        let (mut buy, mut sell) = (None::<&AuctionFill>, None::<&AuctionFill>);
+
+        loop {
+            if buy_left == 0 {
+                if let Some(next) = buy_iter.next() {
+                    if let Some(done) = trade.take() {
+                        self.deliver_trade(done);
+                    }
+                    let mut result = MatchResult::new(next.order_id, next.quantity);
+                    result.is_complete = next.completes;
+                    trade = Some(TradeResult {
+                        symbol: self.symbol.clone(),
+                        match_result: result,
+                        taker_owner: next.owner,
+                        maker_owners: Vec::new(),
+                        fees: Vec::new(),
+                    });
+                    buy = Some(next);
+                    buy_left = next.quantity;
+                } else {
+                    break;
+                }
+            }
+            if sell_left == 0 {
+                match sell_iter.next() {
+                    Some(next) => {
+                        sell = Some(next);
+                        sell_left = next.quantity;
+                    }
+                    None => break,
+                }
+            }
+            let (Some(buy_fill), Some(sell_fill)) = (buy, sell) else {
+                break;
+            };
+
+            let quantity = buy_left.min(sell_left);
+            let mut transaction = Transaction::new(
+                self.transaction_id_generator.next(),
+                buy_fill.order_id,
+                sell_fill.order_id,
+                price,
+                quantity,
+                Side::Buy,
+            );
+            transaction.timestamp = timestamp;
+            buy_left -= quantity;
+            sell_left -= quantity;
+
+            if let Some(result) = trade.as_mut() {
+                result.match_result.add_transaction(transaction);
+                if sell_left == 0 && sell_fill.completes {
+                    result.match_result.add_filled_order_id(sell_fill.order_id);
+                }
+                if let Some(owner) = sell_fill.owner
+                    && result.maker_owner(sell_fill.order_id).is_none()
+                {
+                    result.maker_owners.push((sell_fill.order_id, owner));
+                }
+            }
+            transactions.push(transaction);
+        }
+        if let Some(done) = trade {
+            self.deliver_trade(done);
+        }
+
+        self.transaction_count
+            .fetch_add(transactions.len() as u64, Ordering::Relaxed);
+        transactions
+    }
+}
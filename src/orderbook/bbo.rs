@@ -0,0 +1,59 @@
+//! Top-of-book accessor returning best prices and quantities together.
+
+use super::book::OrderBook;
+use serde::{Deserialize, Serialize};
+
+/// Best bid and offer of a book, captured at a single point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bbo {
+    /// Best bid price (in price units)
+    pub bid_px: u64,
+    /// Quantity resting at the best bid (in units)
+    pub bid_qty: u64,
+    /// Best ask price (in price units)
+    pub ask_px: u64,
+    /// Quantity resting at the best ask (in units)
+    pub ask_qty: u64,
+    /// Time the top of book was read (milliseconds since epoch)
+    pub ts: u64,
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Returns the best bid and ask prices together with their quantities
+    ///
+    /// Each side is read once from the top of its price map, so the price and the
+    /// quantity of a side always come from the same level.
+    ///
+    /// # Returns
+    /// `None` if either side of the book is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// book.add_limit_order(OrderId::new(), 100, 50, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 105, 30, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// let bbo = book.bbo().unwrap();
+    /// assert_eq!((bbo.bid_px, bbo.bid_qty), (100, 50));
+    /// assert_eq!((bbo.ask_px, bbo.ask_qty), (105, 30));
+    /// ```
+    #[must_use]
+    pub fn bbo(&self) -> Option<Bbo> {
+        let bid = self.bids.back()?;
+        let ask = self.asks.front()?;
+
+        Some(Bbo {
+            bid_px: *bid.key(),
+            bid_qty: bid.value().total_quantity(),
+            ask_px: *ask.key(),
+            ask_qty: ask.value().total_quantity(),
+            ts: self.clock.now_millis(),
+        })
+    }
+}
@@ -1,28 +1,68 @@
 //! Core OrderBook implementation for managing price levels and orders
 
-use super::cache::PriceLevelCache;
+use super::cache::{PriceLevelCache, SideTotals};
+use super::circuit_breaker::{CircuitBreaker, PriceBandListener, ReferencePrices};
+use super::config::BookConfig;
+use super::delta::DeltaTracker;
+use super::depth_kernels;
 use super::error::OrderBookError;
-use super::iterators::{LevelInfo, LevelsInRange, LevelsUntilDepth, LevelsWithCumulativeDepth};
-use super::market_impact::{MarketImpact, OrderSimulation};
-use super::snapshot::{EnrichedSnapshot, MetricFlags, OrderBookSnapshot, OrderBookSnapshotPackage};
-use super::statistics::{DepthStats, DistributionBin};
-use crate::orderbook::book_change_event::PriceLevelChangedListener;
-use crate::orderbook::trade::{TradeListener, TradeResult};
-use crate::utils::current_time_millis;
+use super::fees::FeeModel;
+use super::fill_probability::LevelFlowTracker;
+use super::iterators::{
+    LevelInfo, LevelIter, LevelsInRange, LevelsUntilDepth, LevelsWithCumulativeDepth,
+};
+use super::journal::{Journal, JournalCommand};
+use super::ladder::PriceLadder;
+use super::market_impact::{LimitOrderSimulation, MarketImpact, OrderSimulation, PriceMoveCost};
+use super::order_flow::OrderFlowImbalance;
+use super::owner::OwnerId;
+use super::pool::{LevelPool, OrderPool};
+use super::quotes::QuotePair;
+use super::rate_limit::RateLimiter;
+use super::resiliency::ResiliencyTracker;
+use super::risk::{RiskChecker, RiskOrder};
+use super::session::{SessionListener, SessionState};
+use super::snapshot::{
+    EnrichedSnapshot, MetricFlags, OrderBookSnapshot, OrderBookSnapshotPackage, SnapshotFormat,
+    capture_levels,
+};
+use super::statistics::{
+    BookDepthStats, DepthStats, DistributionBin, KyleLambda, RealizedVolatility, VolatilitySource,
+};
+use super::subscription::{ListenerRegistry, SubscriptionId};
+use super::surveillance::{Surveillance, SurveillanceListener};
+use super::time_weighted::TimeWeightedLiquidity;
+use super::trade_tape::TradeTape;
+#[cfg(feature = "tokio")]
+use crate::orderbook::book_change_event::PriceLevelChangedEvent;
+use crate::orderbook::book_change_event::{
+    BboChangedEvent, BboListener, BookChangedListener, PriceLevelChangedListener,
+};
+use crate::orderbook::order_event::OrderEventListener;
+#[cfg(feature = "tokio")]
+use crate::orderbook::trade::TradeEvent;
+use crate::orderbook::trade::TradeListener;
+use crate::utils::{Clock, SystemClock};
 use crossbeam_skiplist::SkipMap;
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use pricelevel::{MatchResult, OrderId, OrderType, PriceLevel, Side, UuidGenerator};
 use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::collections::{BTreeMap, HashMap};
 use std::marker::PhantomData;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use tracing::trace;
+#[cfg(feature = "tokio")]
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::{error, trace};
 use uuid::Uuid;
 
 /// Default basis points multiplier for spread calculations
 /// One basis point = 0.01% = 0.0001
-const DEFAULT_BASIS_POINTS_MULTIPLIER: f64 = 10_000.0;
+pub(super) const DEFAULT_BASIS_POINTS_MULTIPLIER: f64 = 10_000.0;
+
+/// One aggregated price level of a depth ladder: `(price, visible_quantity, order_count)`
+pub type DepthLevel = (u64, u64, usize);
 
 /// The OrderBook manages a collection of price levels for both bid and ask sides.
 /// It supports adding, cancelling, and matching orders with lock-free operations where possible.
@@ -42,6 +82,25 @@ pub struct OrderBook<T = ()> {
     /// the need to sort prices during matching (optimization from O(N log N) to O(M log N))
     pub(super) asks: SkipMap<u64, Arc<PriceLevel>>,
 
+    /// Bid side price levels holding fully hidden orders. These levels take part in
+    /// matching but are never exposed through snapshots, depth queries or level iterators
+    pub(super) hidden_bids: SkipMap<u64, Arc<PriceLevel>>,
+
+    /// Ask side price levels holding fully hidden orders
+    pub(super) hidden_asks: SkipMap<u64, Arc<PriceLevel>>,
+
+    /// IDs of the resting orders stored in the hidden price levels
+    pub(super) hidden_order_ids: DashSet<OrderId>,
+
+    /// Extra fields of each resting order, which the price levels cannot hold
+    pub(super) order_extra_fields: DashMap<OrderId, T>,
+
+    /// Owner of each resting order that was submitted on behalf of an owner
+    pub(super) order_owners: DashMap<OrderId, OwnerId>,
+
+    /// Secondary index from owner to the IDs of its resting orders
+    pub(super) owner_orders: DashMap<OwnerId, DashSet<OrderId>>,
+
     /// A concurrent map from order ID to (price, side) for fast lookups
     /// This avoids having to search through all price levels to find an order
     pub(super) order_locations: DashMap<OrderId, (u64, Side)>,
@@ -49,21 +108,64 @@ pub struct OrderBook<T = ()> {
     /// Generator for unique transaction IDs
     pub(super) transaction_id_generator: UuidGenerator,
 
+    /// Namespace of the transaction ID generator
+    pub(super) transaction_namespace: Uuid,
+
+    /// Number of transaction IDs drawn from the generator so far
+    pub(super) transaction_count: AtomicU64,
+
     /// The last price at which a trade occurred
     pub(super) last_trade_price: AtomicU64,
 
     /// Flag indicating if there was a trade
     pub(super) has_traded: AtomicBool,
 
+    /// Clock time of the last trade in milliseconds, `u64::MAX` if none
+    pub(super) last_trade_millis: AtomicU64,
+
+    /// Clock time of the last price level change in milliseconds, `u64::MAX` if none
+    pub(super) last_update_millis: AtomicU64,
+
     /// The timestamp of market close, if applicable (for DAY orders)
     pub(super) market_close_timestamp: AtomicU64,
 
     /// Flag indicating if market close is set
     pub(super) has_market_close: AtomicBool,
 
+    /// Whether the resting DAY orders were already expired for the current market close
+    pub(super) day_orders_expired: AtomicBool,
+
+    /// Tick size, lot size and minimum notional enforced on incoming orders
+    pub(super) config: BookConfig,
+
+    /// Current `SessionState`, stored as its numeric code
+    pub(super) session_state: AtomicU8,
+
+    /// Price bands enforced on new orders and sweeps, if any
+    pub(super) circuit_breaker: Option<CircuitBreaker>,
+
+    /// Prior close and external reference prices of the circuit breaker
+    pub(super) reference_prices: Mutex<ReferencePrices>,
+
+    /// When set, submitting an order whose ID is already resting replaces that order
+    /// instead of being rejected
+    pub(super) replace_on_duplicate: AtomicBool,
+
     /// A cache for storing best bid/ask prices to avoid recalculation
     pub(super) cache: PriceLevelCache,
 
+    /// Resting quantity of the visible levels of each side
+    pub(super) totals: SideTotals,
+
+    /// Emptied price levels kept for reuse when orders rest at their price again
+    pub(super) level_pool: LevelPool,
+
+    /// Handles of the orders returned to callers, reused once they are dropped
+    pub(super) order_pool: OrderPool<T>,
+
+    /// Dense store of the visible levels, if the configuration bounds prices to a tick grid
+    pub(super) ladder: Option<PriceLadder>,
+
     /// listens to possible trades when an order is added
     pub trade_listener: Option<TradeListener>,
 
@@ -72,6 +174,99 @@ pub struct OrderBook<T = ()> {
 
     /// listens to order book changes. This provides a point to update a corresponding external order book e.g. in the UI
     pub price_level_changed_listener: Option<PriceLevelChangedListener>,
+
+    /// listens to consolidated changes produced by compound operations such as quote updates
+    pub book_changed_listener: Option<BookChangedListener>,
+
+    /// listens to the lifecycle of every order (accepted, rested, filled, cancelled, ...)
+    pub order_event_listener: Option<OrderEventListener>,
+
+    /// listens to changes of the best bid and offer only
+    pub bbo_listener: Option<BboListener>,
+
+    /// listens to changes of the trading session state
+    pub session_listener: Option<SessionListener>,
+
+    /// listens to orders and sweeps hitting the circuit breaker's price bands
+    pub price_band_listener: Option<PriceBandListener>,
+
+    /// Additional trade listeners registered through `subscribe_trade_listener`
+    pub(super) trade_subscribers: ListenerRegistry<TradeListener>,
+
+    /// Additional price level listeners registered through `subscribe_price_level_listener`
+    pub(super) price_level_subscribers: ListenerRegistry<PriceLevelChangedListener>,
+
+    /// Broadcast channel behind `subscribe_trades`, created on first subscription
+    #[cfg(feature = "tokio")]
+    pub(super) trade_stream: OnceLock<tokio::sync::broadcast::Sender<TradeEvent>>,
+
+    /// Broadcast channel behind `subscribe_book_changes`, created on first subscription
+    #[cfg(feature = "tokio")]
+    pub(super) book_change_stream: OnceLock<tokio::sync::broadcast::Sender<PriceLevelChangedEvent>>,
+
+    /// The best bid and offer last reported to the BBO listener
+    pub(super) last_bbo: Mutex<BboChangedEvent>,
+
+    /// The quote pair each market maker currently has resting in the book
+    pub(super) quotes: Mutex<HashMap<OwnerId, QuotePair>>,
+
+    /// Source of the current time for timestamps and expiry checks
+    pub(super) clock: Arc<dyn Clock>,
+
+    /// Book-wide kill switch: new orders are rejected while false
+    pub(super) trading_enabled: AtomicBool,
+
+    /// Owners whose new orders are rejected
+    pub(super) disabled_owners: DashSet<OwnerId>,
+
+    /// Per-owner limit on the orders added and cancelled, if set
+    pub(super) rate_limiter: Option<RateLimiter>,
+
+    /// Rolling order flow imbalance fed by best bid and offer changes, if enabled
+    pub(super) order_flow_imbalance: Option<Mutex<OrderFlowImbalance>>,
+
+    /// Time-weighted spread, depth and imbalance fed by best bid and offer changes, if enabled
+    pub(super) time_weighted_liquidity: Option<Mutex<TimeWeightedLiquidity>>,
+
+    /// Kyle's lambda fed by executions and mid-price changes, if enabled
+    pub(super) kyle_lambda: Option<Mutex<KyleLambda>>,
+
+    /// Source and rolling bars of the realized volatility, if tracked
+    pub(super) realized_volatility: Option<(VolatilitySource, Mutex<RealizedVolatility>)>,
+
+    /// Recent executions, if the trade tape is enabled
+    pub(super) trade_tape: Option<TradeTape>,
+
+    /// Executions and cancellations per price level, if fill probabilities are tracked
+    pub(super) level_flow: Option<LevelFlowTracker>,
+
+    /// Replenishment of the touch after sweeps, if tracked
+    pub(super) resiliency: Option<ResiliencyTracker>,
+
+    /// Per-owner surveillance metrics, if tracked
+    pub(super) surveillance: Option<Surveillance>,
+
+    /// Listener for surveillance alerts
+    pub(super) surveillance_listener: Option<SurveillanceListener>,
+
+    /// Fee schedule and traded volumes charged on every trade, if set
+    pub(super) fee_model: Option<Arc<FeeModel>>,
+
+    /// Pre-trade risk checks run on every new order, in order
+    pub(super) risk_checkers: Vec<Arc<dyn RiskChecker>>,
+
+    /// Write-ahead journal recording every accepted command, if attached
+    pub(super) journal: Option<Arc<dyn Journal<T>>>,
+
+    /// Sequence number of the last journaled or replayed command
+    pub(super) journal_sequence: Mutex<u64>,
+
+    /// Held from the journaling of a command until it is applied, so journaled
+    /// commands are applied in sequence order
+    pub(super) journal_writer: Mutex<()>,
+
+    /// History of visible level changes behind `delta_since`, if tracking is enabled
+    pub(super) delta_tracker: Option<Mutex<DeltaTracker>>,
 }
 
 impl<T> Serialize for OrderBook<T>
@@ -86,7 +281,7 @@ where
         use std::collections::HashMap;
         use std::sync::atomic::Ordering;
 
-        let mut state = serializer.serialize_struct("OrderBook", 9)?;
+        let mut state = serializer.serialize_struct("OrderBook", 10)?;
 
         // Serialize symbol
         state.serialize_field("symbol", &self.symbol)?;
@@ -115,6 +310,25 @@ where
             .collect();
         state.serialize_field("order_locations", &order_locations)?;
 
+        // Serialize the extra fields of resting orders as a map keyed by order ID
+        struct ExtraFields<'a, T>(&'a DashMap<OrderId, T>);
+
+        impl<T: Serialize> Serialize for ExtraFields<'_, T> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                use serde::ser::SerializeMap;
+
+                let mut map = serializer.serialize_map(Some(self.0.len()))?;
+                for entry in self.0.iter() {
+                    map.serialize_entry(entry.key(), entry.value())?;
+                }
+                map.end()
+            }
+        }
+        state.serialize_field("extra_fields", &ExtraFields(&self.order_extra_fields))?;
+
         // Serialize atomic values by loading them
         state.serialize_field(
             "last_trade_price",
@@ -144,10 +358,19 @@ where
     T: Default + Clone + Send + Sync + 'static,
 {
     /// Convert OrderType<()> to `OrderType<T>` for return values
+    ///
+    /// The extra fields stored for the order are restored, falling back to
+    /// `T::default()` for orders the book holds no extra fields for.
     pub fn convert_from_unit_type(&self, order: &OrderType<()>) -> OrderType<T>
     where
         T: Default,
     {
+        let extra_fields = self
+            .order_extra_fields
+            .get(&order.id())
+            .map(|fields| fields.clone())
+            .unwrap_or_default();
+
         match order {
             OrderType::Standard {
                 id,
@@ -164,7 +387,7 @@ where
                 side: *side,
                 timestamp: *timestamp,
                 time_in_force: *time_in_force,
-                extra_fields: T::default(),
+                extra_fields,
             },
             OrderType::IcebergOrder {
                 id,
@@ -183,7 +406,7 @@ where
                 side: *side,
                 timestamp: *timestamp,
                 time_in_force: *time_in_force,
-                extra_fields: T::default(),
+                extra_fields,
             },
             OrderType::PostOnly {
                 id,
@@ -200,7 +423,7 @@ where
                 side: *side,
                 timestamp: *timestamp,
                 time_in_force: *time_in_force,
-                extra_fields: T::default(),
+                extra_fields,
             },
             OrderType::TrailingStop {
                 id,
@@ -221,7 +444,7 @@ where
                 time_in_force: *time_in_force,
                 trail_amount: *trail_amount,
                 last_reference_price: *last_reference_price,
-                extra_fields: T::default(),
+                extra_fields,
             },
             OrderType::PeggedOrder {
                 id,
@@ -242,7 +465,7 @@ where
                 time_in_force: *time_in_force,
                 reference_price_offset: *reference_price_offset,
                 reference_price_type: *reference_price_type,
-                extra_fields: T::default(),
+                extra_fields,
             },
             OrderType::MarketToLimit {
                 id,
@@ -259,7 +482,7 @@ where
                 side: *side,
                 timestamp: *timestamp,
                 time_in_force: *time_in_force,
-                extra_fields: T::default(),
+                extra_fields,
             },
             OrderType::ReserveOrder {
                 id,
@@ -284,10 +507,18 @@ where
                 replenish_threshold: *replenish_threshold,
                 replenish_amount: *replenish_amount,
                 auto_replenish: *auto_replenish,
-                extra_fields: T::default(),
+                extra_fields,
             },
         }
     }
+    /// Create a new order book that enforces the given trading rules on every
+    /// incoming order
+    pub fn new_with_config(symbol: &str, config: BookConfig) -> Self {
+        let mut book = Self::new(symbol);
+        book.apply_config(config);
+        book
+    }
+
     /// Create a new order book for the given symbol
     pub fn new(symbol: &str) -> Self {
         // Create a unique namespace for this order book's transaction IDs
@@ -297,38 +528,76 @@ where
             symbol: symbol.to_string(),
             bids: SkipMap::new(),
             asks: SkipMap::new(),
+            hidden_bids: SkipMap::new(),
+            hidden_asks: SkipMap::new(),
+            hidden_order_ids: DashSet::new(),
+            order_extra_fields: DashMap::new(),
+            order_owners: DashMap::new(),
+            owner_orders: DashMap::new(),
             order_locations: DashMap::new(),
             transaction_id_generator: UuidGenerator::new(namespace),
+            transaction_namespace: namespace,
+            transaction_count: AtomicU64::new(0),
             last_trade_price: AtomicU64::new(0),
             has_traded: AtomicBool::new(false),
+            last_trade_millis: AtomicU64::new(u64::MAX),
+            last_update_millis: AtomicU64::new(u64::MAX),
             market_close_timestamp: AtomicU64::new(0),
             has_market_close: AtomicBool::new(false),
+            day_orders_expired: AtomicBool::new(false),
+            replace_on_duplicate: AtomicBool::new(false),
+            config: BookConfig::default(),
+            session_state: AtomicU8::new(SessionState::Open.to_u8()),
+            circuit_breaker: None,
+            reference_prices: Mutex::new(ReferencePrices::default()),
             cache: PriceLevelCache::new(),
+            totals: SideTotals::new(),
+            level_pool: LevelPool::default(),
+            order_pool: OrderPool::default(),
+            ladder: None,
             trade_listener: None,
             _phantom: PhantomData,
             price_level_changed_listener: None,
+            book_changed_listener: None,
+            order_event_listener: None,
+            bbo_listener: None,
+            session_listener: None,
+            price_band_listener: None,
+            trade_subscribers: ListenerRegistry::new(),
+            price_level_subscribers: ListenerRegistry::new(),
+            #[cfg(feature = "tokio")]
+            trade_stream: OnceLock::new(),
+            #[cfg(feature = "tokio")]
+            book_change_stream: OnceLock::new(),
+            last_bbo: Mutex::new(BboChangedEvent::default()),
+            quotes: Mutex::new(HashMap::new()),
+            clock: Arc::new(SystemClock),
+            trading_enabled: AtomicBool::new(true),
+            disabled_owners: DashSet::new(),
+            rate_limiter: None,
+            order_flow_imbalance: None,
+            time_weighted_liquidity: None,
+            kyle_lambda: None,
+            realized_volatility: None,
+            trade_tape: None,
+            level_flow: None,
+            resiliency: None,
+            surveillance: None,
+            surveillance_listener: None,
+            fee_model: None,
+            risk_checkers: Vec::new(),
+            journal: None,
+            journal_sequence: Mutex::new(0),
+            journal_writer: Mutex::new(()),
+            delta_tracker: None,
         }
     }
 
     /// Create a new order book for the given symbol with a trade listener
     pub fn with_trade_listener(symbol: &str, trade_listener: TradeListener) -> Self {
-        let namespace = Uuid::new_v4();
-
-        Self {
-            symbol: symbol.to_string(),
-            bids: SkipMap::new(),
-            asks: SkipMap::new(),
-            order_locations: DashMap::new(),
-            transaction_id_generator: UuidGenerator::new(namespace),
-            last_trade_price: AtomicU64::new(0),
-            has_traded: AtomicBool::new(false),
-            market_close_timestamp: AtomicU64::new(0),
-            has_market_close: AtomicBool::new(false),
-            cache: PriceLevelCache::new(),
-            trade_listener: Some(trade_listener),
-            _phantom: PhantomData,
-            price_level_changed_listener: None,
-        }
+        let mut book = Self::new(symbol);
+        book.trade_listener = Some(trade_listener);
+        book
     }
 
     /// Creates a new order book with both a trade listener and a price level change listener.
@@ -345,23 +614,9 @@ where
         trade_listener: TradeListener,
         book_changed_listener: PriceLevelChangedListener,
     ) -> Self {
-        let namespace = Uuid::new_v4();
-
-        Self {
-            symbol: symbol.to_string(),
-            bids: SkipMap::new(),
-            asks: SkipMap::new(),
-            order_locations: DashMap::new(),
-            transaction_id_generator: UuidGenerator::new(namespace),
-            last_trade_price: AtomicU64::new(0),
-            has_traded: AtomicBool::new(false),
-            market_close_timestamp: AtomicU64::new(0),
-            has_market_close: AtomicBool::new(false),
-            cache: PriceLevelCache::new(),
-            trade_listener: Some(trade_listener),
-            _phantom: PhantomData,
-            price_level_changed_listener: Some(book_changed_listener),
-        }
+        let mut book = Self::with_trade_listener(symbol, trade_listener);
+        book.price_level_changed_listener = Some(book_changed_listener);
+        book
     }
 
     /// Set a trade listener for this order book
@@ -384,16 +639,173 @@ where
         self.price_level_changed_listener = None;
     }
 
+    /// set consolidated book change listener for this order book
+    pub fn set_book_changed_listener(&mut self, listener: BookChangedListener) {
+        self.book_changed_listener = Some(listener);
+    }
+
+    /// remove consolidated book change listener for this order book
+    pub fn remove_book_changed_listener(&mut self) {
+        self.book_changed_listener = None;
+    }
+
+    /// set order lifecycle event listener for this order book
+    pub fn set_order_event_listener(&mut self, listener: OrderEventListener) {
+        self.order_event_listener = Some(listener);
+    }
+
+    /// remove order lifecycle event listener for this order book
+    pub fn remove_order_event_listener(&mut self) {
+        self.order_event_listener = None;
+    }
+
+    /// set the clock used for order timestamps, snapshots and expiry checks
+    ///
+    /// Books use the system clock by default. Timestamps generated inside the
+    /// price levels themselves, such as transaction timestamps, are not affected.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{ManualClock, OrderBook};
+    /// use std::sync::Arc;
+    ///
+    /// let clock = Arc::new(ManualClock::new(1_000));
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// book.set_clock(clock.clone());
+    ///
+    /// clock.advance(250);
+    /// assert_eq!(book.create_snapshot(1).timestamp, 1_250);
+    /// ```
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Returns the clock used by this order book
+    #[must_use]
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// set best bid and offer listener for this order book
+    ///
+    /// The listener is only invoked when the best bid or ask price or its visible
+    /// quantity changes, starting from the state of the book when it is set.
+    pub fn set_bbo_listener(&mut self, listener: BboListener) {
+        *self.last_bbo.get_mut().unwrap_or_else(|e| e.into_inner()) = self.current_bbo();
+        self.bbo_listener = Some(listener);
+    }
+
+    /// remove best bid and offer listener for this order book
+    pub fn remove_bbo_listener(&mut self) {
+        self.bbo_listener = None;
+    }
+
+    /// Subscribe an additional trade listener to this order book
+    ///
+    /// Unlike [`OrderBook::set_trade_listener`], any number of listeners can be
+    /// subscribed, and subscribing only needs a shared reference, so it also works
+    /// once the book is shared behind an `Arc`. Subscribers are invoked after the
+    /// listener set through `set_trade_listener`, in subscription order.
+    ///
+    /// # Returns
+    /// The ID to pass to [`OrderBook::unsubscribe_trade_listener`] to remove the listener.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// let book = Arc::new(OrderBook::<()>::new("BTC/USD"));
+    /// let trades = Arc::new(AtomicUsize::new(0));
+    /// let counter = Arc::clone(&trades);
+    /// let id = book.subscribe_trade_listener(Arc::new(move |_| {
+    ///     counter.fetch_add(1, Ordering::SeqCst);
+    /// }));
+    ///
+    /// book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    /// book.submit_market_order(OrderId::new(), 5, Side::Buy).unwrap();
+    /// assert_eq!(trades.load(Ordering::SeqCst), 1);
+    ///
+    /// assert!(book.unsubscribe_trade_listener(id));
+    /// ```
+    pub fn subscribe_trade_listener(&self, listener: TradeListener) -> SubscriptionId {
+        self.trade_subscribers.subscribe(listener)
+    }
+
+    /// Remove a trade listener subscribed through [`OrderBook::subscribe_trade_listener`]
+    ///
+    /// # Returns
+    /// `true` if the subscription existed.
+    pub fn unsubscribe_trade_listener(&self, id: SubscriptionId) -> bool {
+        self.trade_subscribers.unsubscribe(id)
+    }
+
+    /// Subscribe an additional price level change listener to this order book
+    ///
+    /// Any number of listeners can be subscribed through a shared reference. They
+    /// receive the same events as the listener set through
+    /// [`OrderBook::set_price_level_listener`], after it, in subscription order.
+    ///
+    /// # Returns
+    /// The ID to pass to [`OrderBook::unsubscribe_price_level_listener`] to remove the listener.
+    pub fn subscribe_price_level_listener(
+        &self,
+        listener: PriceLevelChangedListener,
+    ) -> SubscriptionId {
+        self.price_level_subscribers.subscribe(listener)
+    }
+
+    /// Remove a price level change listener subscribed through
+    /// [`OrderBook::subscribe_price_level_listener`]
+    ///
+    /// # Returns
+    /// `true` if the subscription existed.
+    pub fn unsubscribe_price_level_listener(&self, id: SubscriptionId) -> bool {
+        self.price_level_subscribers.unsubscribe(id)
+    }
+
+    /// Number of listeners subscribed to trades and price level changes, in that order
+    ///
+    /// Listeners set through the single-listener setters are not counted.
+    #[must_use]
+    pub fn subscriber_counts(&self) -> (usize, usize) {
+        (
+            self.trade_subscribers.len(),
+            self.price_level_subscribers.len(),
+        )
+    }
+
     /// Get the symbol of this order book
     pub fn symbol(&self) -> &str {
         &self.symbol
     }
 
+    /// Get the trading rules enforced by this order book
+    pub fn config(&self) -> &BookConfig {
+        &self.config
+    }
+
     /// Set the market close timestamp for DAY orders
+    ///
+    /// Once the book clock reaches the close, new DAY orders are rejected as expired
+    /// and the resting ones are expired on the next submission or match, or by an
+    /// explicit [`OrderBook::expire_day_orders`] sweep.
     pub fn set_market_close_timestamp(&self, timestamp: u64) {
+        let _journaled = match self.begin_journaled(|| JournalCommand::SetMarketClose {
+            timestamp: Some(timestamp),
+        }) {
+            Ok(scope) => scope,
+            Err(e) => {
+                error!("Order book {}: {}", self.symbol, e);
+                return;
+            }
+        };
         self.market_close_timestamp
             .store(timestamp, Ordering::SeqCst);
         self.has_market_close.store(true, Ordering::SeqCst);
+        self.day_orders_expired.store(false, Ordering::SeqCst);
         trace!(
             "Order book {}: Set market close timestamp to {}",
             self.symbol, timestamp
@@ -402,41 +814,42 @@ where
 
     /// Clear the market close timestamp
     pub fn clear_market_close_timestamp(&self) {
+        let _journaled =
+            match self.begin_journaled(|| JournalCommand::SetMarketClose { timestamp: None }) {
+                Ok(scope) => scope,
+                Err(e) => {
+                    error!("Order book {}: {}", self.symbol, e);
+                    return;
+                }
+            };
         self.has_market_close.store(false, Ordering::SeqCst);
+        self.day_orders_expired.store(false, Ordering::SeqCst);
+    }
+
+    /// Choose how an order reusing the ID of a resting order is handled
+    ///
+    /// By default such orders are rejected with `OrderBookError::DuplicateOrderId`.
+    /// When enabled, the resting order is cancelled and the new one takes its place.
+    pub fn set_replace_on_duplicate(&self, enabled: bool) {
+        self.replace_on_duplicate.store(enabled, Ordering::SeqCst);
     }
 
     /// Get the best bid price, if any
     ///
     /// # Performance
-    /// O(1) operation using SkipMap's ordered structure (highest price is last)
+    /// O(1) operation reading the best price maintained as levels are added and
+    /// removed
     pub fn best_bid(&self) -> Option<u64> {
-        if let Some(cached_bid) = self.cache.get_cached_best_bid() {
-            return Some(cached_bid);
-        }
-
-        // SkipMap maintains sorted order, best bid (highest price) is last
-        let best_price = self.bids.iter().next_back().map(|entry| *entry.key());
-
-        self.cache.update_best_prices(best_price, None);
-
-        best_price
+        self.cache.best_bid()
     }
 
     /// Get the best ask price, if any
     ///
     /// # Performance
-    /// O(1) operation using SkipMap's ordered structure (lowest price is first)
+    /// O(1) operation reading the best price maintained as levels are added and
+    /// removed
     pub fn best_ask(&self) -> Option<u64> {
-        if let Some(cached_ask) = self.cache.get_cached_best_ask() {
-            return Some(cached_ask);
-        }
-
-        // SkipMap maintains sorted order, best ask (lowest price) is first
-        let best_price = self.asks.iter().next().map(|entry| *entry.key());
-
-        self.cache.update_best_prices(None, best_price);
-
-        best_price
+        self.cache.best_ask()
     }
 
     /// Get the mid price (average of best bid and best ask)
@@ -459,6 +872,22 @@ where
         }
     }
 
+    /// Get the clock time of the last trade in milliseconds, if any
+    pub fn last_trade_time(&self) -> Option<u64> {
+        match self.last_trade_millis.load(Ordering::Relaxed) {
+            u64::MAX => None,
+            millis => Some(millis),
+        }
+    }
+
+    /// Get the clock time of the last price level change in milliseconds, if any
+    pub fn last_update_time(&self) -> Option<u64> {
+        match self.last_update_millis.load(Ordering::Relaxed) {
+            u64::MAX => None,
+            millis => Some(millis),
+        }
+    }
+
     /// Get the spread (best ask - best bid)
     pub fn spread(&self) -> Option<u64> {
         match (
@@ -509,10 +938,7 @@ where
         let mut cumulative = 0u64;
 
         // Iterate in price-priority order
-        let iter: Box<dyn Iterator<Item = _>> = match side {
-            Side::Buy => Box::new(price_levels.iter().rev()), // Highest to lowest
-            Side::Sell => Box::new(price_levels.iter()),      // Lowest to highest
-        };
+        let iter = LevelIter::best_first(price_levels.iter(), side);
 
         for entry in iter {
             let price = *entry.key();
@@ -565,10 +991,7 @@ where
         let mut cumulative = 0u64;
 
         // Iterate in price-priority order
-        let iter: Box<dyn Iterator<Item = _>> = match side {
-            Side::Buy => Box::new(price_levels.iter().rev()), // Highest to lowest
-            Side::Sell => Box::new(price_levels.iter()),      // Lowest to highest
-        };
+        let iter = LevelIter::best_first(price_levels.iter(), side);
 
         for entry in iter {
             let price = *entry.key();
@@ -583,6 +1006,28 @@ where
         None
     }
 
+    /// Number of visible price levels on one side of the book
+    ///
+    /// # Performance
+    /// O(1), the level maps keep their length as levels are added and removed.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 99, 10, Side::Buy, TimeInForce::Gtc, None);
+    ///
+    /// assert_eq!(book.level_count(Side::Buy), 2);
+    /// assert_eq!(book.level_count(Side::Sell), 0);
+    /// ```
+    #[must_use]
+    pub fn level_count(&self, side: Side) -> usize {
+        self.side_levels(side, false).len()
+    }
+
     /// Calculates total depth available in the first N price levels
     ///
     /// # Arguments
@@ -594,7 +1039,8 @@ where
     /// Returns 0 if the side is empty or if levels is 0.
     ///
     /// # Performance
-    /// O(min(levels, N) * log N) where N is the total number of price levels.
+    /// O(1) when `levels` covers every level of the side, read from the
+    /// maintained side total, otherwise O(levels).
     ///
     /// # Examples
     /// ```
@@ -620,14 +1066,14 @@ where
         if price_levels.is_empty() {
             return 0;
         }
+        if levels >= price_levels.len() {
+            return self.totals.quantity(side);
+        }
 
         let mut total = 0u64;
 
         // Iterate in price-priority order
-        let iter: Box<dyn Iterator<Item = _>> = match side {
-            Side::Buy => Box::new(price_levels.iter().rev()), // Highest to lowest
-            Side::Sell => Box::new(price_levels.iter()),      // Lowest to highest
-        };
+        let iter = LevelIter::best_first(price_levels.iter(), side);
 
         for (count, entry) in iter.enumerate() {
             if count >= levels {
@@ -765,10 +1211,7 @@ where
         let mut total_filled = 0u64;
 
         // Iterate in price-priority order
-        let iter: Box<dyn Iterator<Item = _>> = match side {
-            Side::Buy => Box::new(price_levels.iter()), // Lowest to highest (asks)
-            Side::Sell => Box::new(price_levels.iter().rev()), // Highest to lowest (bids)
-        };
+        let iter = LevelIter::best_first(price_levels.iter(), side.opposite());
 
         for entry in iter {
             if remaining == 0 {
@@ -796,6 +1239,118 @@ where
         }
     }
 
+    /// Calculates the volume-weighted average price (VWAP) for a given notional
+    ///
+    /// Walks through price levels in order, accumulating `price * quantity`
+    /// until the target notional is reached, and returns the weighted average
+    /// price of that fill. The quantity taken at the last level is rounded up
+    /// to whole units, so the filled notional may exceed the target by less
+    /// than one unit at that price.
+    ///
+    /// # Arguments
+    /// - `notional`: The target notional to fill (price units times quantity units)
+    /// - `side`: The side to calculate VWAP for (Buy = execute against asks, Sell = execute against bids)
+    ///
+    /// # Returns
+    /// - `Some(vwap)` if sufficient liquidity exists to reach the notional
+    /// - `None` if insufficient liquidity or notional is zero
+    ///
+    /// # Performance
+    /// O(M log N) where M is the number of levels needed to reach the target notional.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 105, 15, Side::Sell, TimeInForce::Gtc, None);
+    ///
+    /// // 1000 at 100 plus 1050 at 105
+    /// assert_eq!(book.vwap_for_notional(2050, Side::Buy), Some(2050.0 / 20.0));
+    /// ```
+    #[must_use]
+    pub fn vwap_for_notional(&self, notional: u128, side: Side) -> Option<f64> {
+        self.fill_for_notional(notional, side)
+            .map(|(quantity, cost)| cost as f64 / quantity as f64)
+    }
+
+    /// Calculates the quantity needed to reach a given notional
+    ///
+    /// Walks through price levels in order, accumulating `price * quantity`
+    /// until the target notional is reached. The quantity taken at the last
+    /// level is rounded up to whole units.
+    ///
+    /// # Arguments
+    /// - `notional`: The target notional to fill (price units times quantity units)
+    /// - `side`: The side to fill (Buy = execute against asks, Sell = execute against bids)
+    ///
+    /// # Returns
+    /// - `Some(quantity)` the smallest quantity whose fill reaches the notional
+    /// - `None` if insufficient liquidity or notional is zero
+    ///
+    /// # Performance
+    /// O(M log N) where M is the number of levels needed to reach the target notional.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 105, 15, Side::Sell, TimeInForce::Gtc, None);
+    ///
+    /// assert_eq!(book.quantity_for_notional(1000, Side::Buy), Some(10));
+    /// // 1000 at 100, then 100 more needs one unit at 105
+    /// assert_eq!(book.quantity_for_notional(1100, Side::Buy), Some(11));
+    /// assert_eq!(book.quantity_for_notional(10_000, Side::Buy), None);
+    /// ```
+    #[must_use]
+    pub fn quantity_for_notional(&self, notional: u128, side: Side) -> Option<u64> {
+        self.fill_for_notional(notional, side)
+            .map(|(quantity, _)| quantity)
+    }
+
+    /// Quantity and cost of the smallest fill of `side` reaching `notional`
+    fn fill_for_notional(&self, notional: u128, side: Side) -> Option<(u64, u128)> {
+        if notional == 0 {
+            return None;
+        }
+
+        let price_levels = match side {
+            Side::Buy => &self.asks,
+            Side::Sell => &self.bids,
+        };
+
+        let iter = LevelIter::best_first(price_levels.iter(), side.opposite());
+
+        let mut total_cost = 0u128;
+        let mut total_filled = 0u64;
+
+        for entry in iter {
+            let price = *entry.key();
+            let available = entry.value().total_quantity();
+
+            if available == 0 || price == 0 {
+                continue;
+            }
+
+            let remaining = notional - total_cost;
+            let needed = remaining.div_ceil(price as u128);
+            let fill_qty = needed.min(available as u128) as u64;
+            total_cost = total_cost.saturating_add((price as u128) * (fill_qty as u128));
+            total_filled = total_filled.saturating_add(fill_qty);
+
+            if total_cost >= notional {
+                return Some((total_filled, total_cost));
+            }
+        }
+
+        None // Insufficient liquidity
+    }
+
     /// Calculates the micro price (weighted price by volume at best bid and ask)
     ///
     /// The micro price is calculated as:
@@ -823,12 +1378,9 @@ where
     /// ```
     #[must_use]
     pub fn micro_price(&self) -> Option<f64> {
-        let best_bid_price = self.best_bid()?;
-        let best_ask_price = self.best_ask()?;
-
-        // Get volumes at best levels
-        let bid_volume = self.bids.get(&best_bid_price)?.value().total_quantity();
-        let ask_volume = self.asks.get(&best_ask_price)?.value().total_quantity();
+        let bbo = self.bbo()?;
+        let (best_bid_price, bid_volume) = (bbo.bid_px, bbo.bid_qty);
+        let (best_ask_price, ask_volume) = (bbo.ask_px, bbo.ask_qty);
 
         let total_volume = bid_volume.saturating_add(ask_volume);
 
@@ -935,10 +1487,6 @@ where
     /// ```
     #[must_use]
     pub fn market_impact(&self, quantity: u64, side: Side) -> MarketImpact {
-        if quantity == 0 {
-            return MarketImpact::empty();
-        }
-
         // For Buy orders, we execute against asks (in ascending order)
         // For Sell orders, we execute against bids (in descending order)
         let price_levels = match side {
@@ -946,78 +1494,229 @@ where
             Side::Sell => &self.bids,
         };
 
-        if price_levels.is_empty() {
-            return MarketImpact::empty();
-        }
+        // Iterate in price-priority order
+        let iter = LevelIter::best_first(price_levels.iter(), side.opposite());
 
-        let best_price = match side {
-            Side::Buy => self.best_ask(),
-            Side::Sell => self.best_bid(),
-        };
+        MarketImpact::from_levels(
+            iter.map(|entry| (*entry.key(), entry.value().total_quantity())),
+            quantity,
+            side,
+        )
+    }
 
-        let best_price = match best_price {
-            Some(price) => price,
-            None => return MarketImpact::empty(),
+    /// Calculates the market impact of a range of order sizes
+    ///
+    /// Produces the same result as calling [`OrderBook::market_impact`] for
+    /// each size, but walks the price levels only once: the sizes are visited
+    /// in ascending order while the fills accumulate. Useful for plotting the
+    /// expected cost against size or calibrating execution algorithms.
+    ///
+    /// # Arguments
+    /// - `sizes`: The order quantities to analyze (in units), in any order
+    /// - `side`: The side of the orders (Buy = execute against asks, Sell = execute against bids)
+    ///
+    /// # Returns
+    /// One `MarketImpact` per entry of `sizes`, in the same order.
+    ///
+    /// # Performance
+    /// O(S log S + M log N) where S is the number of sizes and M the number of
+    /// levels needed by the largest size.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 105, 15, Side::Sell, TimeInForce::Gtc, None);
+    ///
+    /// let curve = book.slippage_curve(&[5, 10, 20], Side::Buy);
+    /// assert_eq!(curve[0].slippage, 0);
+    /// assert_eq!(curve[2].slippage, 5);
+    /// assert_eq!(curve[2], book.market_impact(20, Side::Buy));
+    /// ```
+    #[must_use]
+    pub fn slippage_curve(&self, sizes: &[u64], side: Side) -> Vec<MarketImpact> {
+        let mut curve = vec![MarketImpact::empty(); sizes.len()];
+
+        let price_levels = match side {
+            Side::Buy => &self.asks,
+            Side::Sell => &self.bids,
         };
 
-        let mut remaining = quantity;
+        let iter = LevelIter::best_first(price_levels.iter(), side.opposite());
+
+        // Sizes in ascending order, skipping zero which has no impact
+        let mut order: Vec<usize> = (0..sizes.len()).filter(|&i| sizes[i] > 0).collect();
+        order.sort_unstable_by_key(|&i| sizes[i]);
+        let mut pending = order.into_iter().peekable();
+
+        let mut best_price = None;
         let mut total_cost = 0u128;
         let mut total_filled = 0u64;
-        let mut worst_price = best_price;
-        let mut levels_consumed = 0;
-
-        // Iterate in price-priority order
-        let iter: Box<dyn Iterator<Item = _>> = match side {
-            Side::Buy => Box::new(price_levels.iter()), // Lowest to highest (asks)
-            Side::Sell => Box::new(price_levels.iter().rev()), // Highest to lowest (bids)
-        };
+        let mut last_price = 0u64;
+        let mut levels_consumed = 0usize;
+
+        let impact =
+            |best_price: u64, worst_price: u64, cost: u128, filled: u64, levels_consumed: usize| {
+                let slippage = match side {
+                    Side::Buy => worst_price.saturating_sub(best_price),
+                    Side::Sell => best_price.saturating_sub(worst_price),
+                };
+                MarketImpact {
+                    avg_price: cost as f64 / filled as f64,
+                    worst_price,
+                    slippage,
+                    slippage_bps: if best_price > 0 {
+                        (slippage as f64 / best_price as f64) * DEFAULT_BASIS_POINTS_MULTIPLIER
+                    } else {
+                        0.0
+                    },
+                    levels_consumed,
+                    total_quantity_available: filled,
+                }
+            };
 
         for entry in iter {
-            if remaining == 0 {
+            if pending.peek().is_none() {
                 break;
             }
 
             let price = *entry.key();
-            let price_level = entry.value();
-            let available = price_level.total_quantity();
+            let available = entry.value().total_quantity();
 
             if available == 0 {
                 continue;
             }
 
+            let best = *best_price.get_or_insert(price);
             levels_consumed += 1;
-            let fill_qty = remaining.min(available);
-            total_cost = total_cost.saturating_add((price as u128) * (fill_qty as u128));
-            total_filled = total_filled.saturating_add(fill_qty);
-            worst_price = price;
-            remaining = remaining.saturating_sub(fill_qty);
+            let level_end = total_filled.saturating_add(available);
+
+            // Every size ending within this level
+            while let Some(&i) = pending.peek() {
+                let size = sizes[i];
+                if size > level_end {
+                    break;
+                }
+                let fill_qty = size - total_filled;
+                let cost = total_cost.saturating_add((price as u128) * (fill_qty as u128));
+                curve[i] = impact(best, price, cost, size, levels_consumed);
+                pending.next();
+            }
+
+            total_cost = total_cost.saturating_add((price as u128) * (available as u128));
+            total_filled = level_end;
+            last_price = price;
         }
 
-        let avg_price = if total_filled > 0 {
-            total_cost as f64 / total_filled as f64
-        } else {
-            0.0
-        };
+        // Sizes larger than the available liquidity consume it all
+        if let Some(best) = best_price {
+            for i in pending {
+                curve[i] = impact(best, last_price, total_cost, total_filled, levels_consumed);
+            }
+        }
 
-        let slippage = match side {
-            Side::Buy => worst_price.saturating_sub(best_price),
-            Side::Sell => best_price.saturating_sub(worst_price),
-        };
+        curve
+    }
 
-        let slippage_bps = if best_price > 0 {
-            (slippage as f64 / best_price as f64) * DEFAULT_BASIS_POINTS_MULTIPLIER
-        } else {
-            0.0
+    /// Calculates the cost of moving the best price by a number of ticks
+    ///
+    /// The inverse of [`OrderBook::market_impact`]: walks the levels the
+    /// aggressor would execute against and sums the liquidity resting at
+    /// prices better than `n_ticks` ticks away from the best price. Trading
+    /// that quantity leaves the best price on the target or beyond it, when
+    /// the target price itself has no resting level. The tick is the one of
+    /// the book configuration, or 1 if none is set.
+    ///
+    /// # Arguments
+    /// - `n_ticks`: The number of ticks to move the best price by
+    /// - `side`: The side of the aggressor (Buy = lifts the asks up, Sell = hits the bids down)
+    ///
+    /// # Returns
+    /// - `Some(cost)` with the quantity and notional to trade
+    /// - `None` if `n_ticks` is zero, the side is empty, or no liquidity rests
+    ///   at or beyond the target price, since the move would empty the side
+    ///
+    /// # Performance
+    /// O(M log N) where M is the number of levels before the target price.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 101, 15, Side::Sell, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 103, 20, Side::Sell, TimeInForce::Gtc, None);
+    ///
+    /// // Lifting 100 and 101 moves the best ask to 103, beyond the 2 tick target
+    /// let cost = book.cost_to_move(2, Side::Buy).unwrap();
+    /// assert_eq!(cost.quantity, 25);
+    /// assert_eq!(cost.notional, 100 * 10 + 101 * 15);
+    /// assert_eq!(cost.resulting_price, 103);
+    /// ```
+    #[must_use]
+    pub fn cost_to_move(&self, n_ticks: u64, side: Side) -> Option<PriceMoveCost> {
+        if n_ticks == 0 {
+            return None;
+        }
+
+        let tick_size = self.config.tick_size.filter(|&tick| tick > 0).unwrap_or(1);
+        let distance = n_ticks.checked_mul(tick_size)?;
+
+        let price_levels = match side {
+            Side::Buy => &self.asks,
+            Side::Sell => &self.bids,
         };
 
-        MarketImpact {
-            avg_price,
-            worst_price,
-            slippage,
-            slippage_bps,
-            levels_consumed,
-            total_quantity_available: total_filled,
+        let iter = LevelIter::best_first(price_levels.iter(), side.opposite());
+
+        let mut target = None;
+        let mut quantity = 0u64;
+        let mut notional = 0u128;
+        let mut levels_consumed = 0usize;
+
+        for entry in iter {
+            let price = *entry.key();
+            let available = entry.value().total_quantity();
+
+            if available == 0 {
+                continue;
+            }
+
+            let (start_price, target_price) = *target.get_or_insert_with(|| {
+                let target_price = match side {
+                    Side::Buy => price.saturating_add(distance),
+                    Side::Sell => price.saturating_sub(distance),
+                };
+                (price, target_price)
+            });
+
+            let reached = match side {
+                Side::Buy => price >= target_price,
+                Side::Sell => price <= target_price,
+            };
+            if reached {
+                return Some(PriceMoveCost {
+                    ticks: n_ticks,
+                    start_price,
+                    target_price,
+                    resulting_price: price,
+                    quantity,
+                    notional,
+                    levels_consumed,
+                });
+            }
+
+            quantity = quantity.saturating_add(available);
+            notional = notional.saturating_add((price as u128) * (available as u128));
+            levels_consumed += 1;
         }
+
+        None
     }
 
     /// Simulates the execution of a market order
@@ -1079,10 +1778,7 @@ where
         let mut fills = Vec::new();
 
         // Iterate in price-priority order
-        let iter: Box<dyn Iterator<Item = _>> = match side {
-            Side::Buy => Box::new(price_levels.iter()), // Lowest to highest (asks)
-            Side::Sell => Box::new(price_levels.iter().rev()), // Highest to lowest (bids)
-        };
+        let iter = LevelIter::best_first(price_levels.iter(), side.opposite());
 
         for entry in iter {
             if remaining == 0 {
@@ -1098,10 +1794,116 @@ where
             }
 
             let fill_qty = remaining.min(available);
-            total_cost = total_cost.saturating_add((price as u128) * (fill_qty as u128));
+            total_cost = total_cost.saturating_add((price as u128) * (fill_qty as u128));
+            total_filled = total_filled.saturating_add(fill_qty);
+            fills.push((price, fill_qty));
+            remaining = remaining.saturating_sub(fill_qty);
+        }
+
+        let avg_price = if total_filled > 0 {
+            total_cost as f64 / total_filled as f64
+        } else {
+            0.0
+        };
+
+        OrderSimulation {
+            fills,
+            avg_price,
+            total_filled,
+            remaining_quantity: remaining,
+        }
+    }
+
+    /// Simulates the execution of a limit order
+    ///
+    /// Matches the order against the opposite side up to its limit price, as
+    /// [`OrderBook::simulate_market_order`] does without a limit, and places
+    /// the remainder in the queue of its price level, behind the orders
+    /// already resting there. The book is not modified.
+    ///
+    /// # Arguments
+    /// - `price`: The limit price of the order (in price units)
+    /// - `quantity`: The order quantity to simulate (in units)
+    /// - `side`: The side of the order (Buy = execute against asks, Sell = execute against bids)
+    ///
+    /// # Returns
+    /// A `LimitOrderSimulation` struct containing:
+    /// - `execution`: The fills that would happen immediately
+    /// - `resting_quantity`: The quantity that would rest at `price`
+    /// - `queue_position`: Orders and quantity ahead of the resting remainder
+    /// - `best_bid` and `best_ask`: The top of the book after the order
+    ///
+    /// # Performance
+    /// O(M log N) where M is the number of levels crossed by the order.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 102, 10, Side::Sell, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 99, 5, Side::Buy, TimeInForce::Gtc, None);
+    ///
+    /// // Takes the 10 at 100, then rests 10 at 101 ahead of the bid at 99
+    /// let simulation = book.simulate_limit_order(101, 20, Side::Buy);
+    /// assert_eq!(simulation.execution.fills, vec![(100, 10)]);
+    /// assert_eq!(simulation.resting_quantity, 10);
+    /// assert_eq!(simulation.queue_position, Some((0, 0)));
+    /// assert_eq!(simulation.best_bid, Some((101, 10)));
+    /// assert_eq!(simulation.best_ask, Some((102, 10)));
+    ///
+    /// // Nothing changed
+    /// assert_eq!(book.best_ask(), Some(100));
+    /// ```
+    #[must_use]
+    pub fn simulate_limit_order(
+        &self,
+        price: u64,
+        quantity: u64,
+        side: Side,
+    ) -> LimitOrderSimulation {
+        let (own_levels, opposite_levels) = match side {
+            Side::Buy => (&self.bids, &self.asks),
+            Side::Sell => (&self.asks, &self.bids),
+        };
+
+        let iter = LevelIter::best_first(opposite_levels.iter(), side.opposite());
+
+        let mut remaining = quantity;
+        let mut total_cost = 0u128;
+        let mut total_filled = 0u64;
+        let mut fills = Vec::new();
+        let mut opposite_top = None;
+
+        for entry in iter {
+            let level_price = *entry.key();
+            let available = entry.value().total_quantity();
+
+            if available == 0 {
+                continue;
+            }
+
+            let crosses = match side {
+                Side::Buy => level_price <= price,
+                Side::Sell => level_price >= price,
+            };
+            if remaining == 0 || !crosses {
+                opposite_top = Some((level_price, available));
+                break;
+            }
+
+            let fill_qty = remaining.min(available);
+            total_cost = total_cost.saturating_add((level_price as u128) * (fill_qty as u128));
             total_filled = total_filled.saturating_add(fill_qty);
-            fills.push((price, fill_qty));
+            fills.push((level_price, fill_qty));
             remaining = remaining.saturating_sub(fill_qty);
+
+            if fill_qty < available {
+                opposite_top = Some((level_price, available - fill_qty));
+                break;
+            }
         }
 
         let avg_price = if total_filled > 0 {
@@ -1110,11 +1912,51 @@ where
             0.0
         };
 
-        OrderSimulation {
-            fills,
-            avg_price,
-            total_filled,
-            remaining_quantity: remaining,
+        // The remainder queues behind the visible orders already at its price
+        let queue_position = (remaining > 0).then(|| {
+            own_levels.get(&price).map_or((0, 0), |entry| {
+                let level = entry.value();
+                (level.order_count(), level.total_quantity())
+            })
+        });
+
+        let own_best = {
+            let mut levels = LevelIter::best_first(own_levels.iter(), side);
+            levels.find_map(|entry| {
+                let available = entry.value().total_quantity();
+                (available > 0).then(|| (*entry.key(), available))
+            })
+        };
+        let own_top = match own_best {
+            _ if remaining == 0 => own_best,
+            Some((best, available)) if best == price => Some((best, available + remaining)),
+            Some((best, _))
+                if match side {
+                    Side::Buy => best > price,
+                    Side::Sell => best < price,
+                } =>
+            {
+                own_best
+            }
+            _ => Some((price, remaining)),
+        };
+
+        let (best_bid, best_ask) = match side {
+            Side::Buy => (own_top, opposite_top),
+            Side::Sell => (opposite_top, own_top),
+        };
+
+        LimitOrderSimulation {
+            execution: OrderSimulation {
+                fills,
+                avg_price,
+                total_filled,
+                remaining_quantity: remaining,
+            },
+            resting_quantity: remaining,
+            queue_position,
+            best_bid,
+            best_ask,
         }
     }
 
@@ -1225,6 +2067,72 @@ where
         }
     }
 
+    /// Estimates the position of a resting order in the queue of its price level
+    ///
+    /// Orders at the same price are matched by time priority, so only the orders
+    /// with an earlier timestamp count as ahead. Orders sharing the timestamp of
+    /// this one are counted as ahead too, so the estimate never understates the
+    /// queue. Visible orders are matched before fully hidden ones at the same
+    /// price, so a fully hidden order also has every visible order at its price
+    /// ahead of it.
+    ///
+    /// # Arguments
+    /// - `order_id`: The resting order to locate
+    ///
+    /// # Returns
+    /// - `Some((orders_ahead, quantity_ahead))` with the number of orders and their
+    ///   total quantity, hidden parts included, matched before this order
+    /// - `None` if the order is not resting in the book
+    ///
+    /// # Performance
+    /// O(1) for the order and price level lookup, O(N log N) over the N orders of
+    /// its level. Other levels are not visited.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{ManualClock, OrderBook};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    /// use std::sync::Arc;
+    ///
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// let clock = Arc::new(ManualClock::new(0));
+    /// book.set_clock(clock.clone());
+    /// let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+    /// clock.advance(1);
+    /// let mine = OrderId::new();
+    /// let _ = book.add_limit_order(mine, 100, 5, Side::Buy, TimeInForce::Gtc, None);
+    /// clock.advance(1);
+    /// let _ = book.add_limit_order(OrderId::new(), 100, 20, Side::Buy, TimeInForce::Gtc, None);
+    ///
+    /// assert_eq!(book.estimated_queue_position(mine), Some((1, 10)));
+    /// ```
+    #[must_use]
+    pub fn estimated_queue_position(&self, order_id: OrderId) -> Option<(usize, u64)> {
+        let (price, side) = *self.order_locations.get(&order_id)?;
+        let hidden = self.is_hidden_order(order_id);
+        let level = self.side_levels(side, hidden).get(&price)?;
+
+        let (mut orders_ahead, mut quantity_ahead) = (0, 0);
+        if hidden && let Some(visible) = self.side_levels(side, false).get(&price) {
+            orders_ahead += visible.value().order_count();
+            quantity_ahead += visible.value().total_quantity();
+        }
+
+        let orders = level.value().iter_orders();
+        let timestamp = orders
+            .iter()
+            .find(|order| order.id() == order_id)?
+            .timestamp();
+        for order in orders
+            .iter()
+            .filter(|order| order.id() != order_id && order.timestamp() <= timestamp)
+        {
+            orders_ahead += 1;
+            quantity_ahead += order.visible_quantity() + order.hidden_quantity();
+        }
+        Some((orders_ahead, quantity_ahead))
+    }
+
     /// Calculates the price N ticks inside the best price
     ///
     /// Useful for placing orders that are competitive but not at the best price.
@@ -1330,21 +2238,9 @@ where
 
         // For bids: iterate from highest to lowest (reverse)
         // For asks: iterate from lowest to highest (forward)
-        let mut current_position = 1;
-
-        let iter: Box<dyn Iterator<Item = _>> = match side {
-            Side::Buy => Box::new(price_levels.iter().rev()),
-            Side::Sell => Box::new(price_levels.iter()),
-        };
-
-        for entry in iter {
-            if current_position == position {
-                return Some(*entry.key());
-            }
-            current_position += 1;
-        }
+        let mut iter = LevelIter::best_first(price_levels.iter(), side);
 
-        None
+        iter.nth(position - 1).map(|entry| *entry.key())
     }
 
     /// Suggests optimal price to place an order just inside a target depth
@@ -1406,10 +2302,7 @@ where
 
         // For bids: iterate from highest to lowest (reverse)
         // For asks: iterate from lowest to highest (forward)
-        let iter: Box<dyn Iterator<Item = _>> = match side {
-            Side::Buy => Box::new(price_levels.iter().rev()),
-            Side::Sell => Box::new(price_levels.iter()),
-        };
+        let iter = LevelIter::best_first(price_levels.iter(), side);
 
         for entry in iter {
             let price = *entry.key();
@@ -1614,24 +2507,17 @@ where
             "Order book {}: Getting orders at price {} for side {:?}",
             self.symbol, price, side
         );
-        let price_levels = match side {
-            Side::Buy => &self.bids,
-            Side::Sell => &self.asks,
-        };
-
-        if let Some(entry) = price_levels.get(&price) {
-            entry
-                .value()
-                .iter_orders()
-                .into_iter()
-                .map(|order| Arc::new(self.convert_from_unit_type(&order)))
-                .collect()
-        } else {
-            Vec::new()
-        }
+        self.level_at(side, price, false)
+            .map_or_else(Vec::new, |level| {
+                level
+                    .iter_orders()
+                    .into_iter()
+                    .map(|order| Arc::new(self.convert_from_unit_type(&order)))
+                    .collect()
+            })
     }
 
-    /// Get all orders in the book
+    /// Get all orders in the book, including fully hidden orders
     pub fn get_all_orders(&self) -> Vec<Arc<OrderType<T>>>
     where
         T: Default,
@@ -1661,6 +2547,16 @@ where
             result.extend(converted_orders);
         }
 
+        // Get all hidden orders
+        for item in self.hidden_bids.iter().chain(self.hidden_asks.iter()) {
+            result.extend(
+                item.value()
+                    .iter_orders()
+                    .into_iter()
+                    .map(|order| Arc::new(self.convert_from_unit_type(&order))),
+            );
+        }
+
         result
     }
 
@@ -1672,11 +2568,7 @@ where
         // Get the order location without locking
         if let Some(location) = self.order_locations.get(&order_id) {
             let (price, side) = *location;
-
-            let price_levels = match side {
-                Side::Buy => &self.bids,
-                Side::Sell => &self.asks,
-            };
+            let price_levels = self.side_levels(side, self.is_hidden_order(order_id));
 
             // Get the price level
             if let Some(entry) = price_levels.get(&price) {
@@ -1693,6 +2585,23 @@ where
         None
     }
 
+    /// Returns `true` if the given order is resting in the book as a fully hidden order
+    #[must_use]
+    pub fn is_hidden_order(&self, order_id: OrderId) -> bool {
+        self.hidden_order_ids.contains(&order_id)
+    }
+
+    /// Returns the total quantity resting in fully hidden orders on the given side
+    ///
+    /// This quantity is matchable but is excluded from every depth and snapshot query.
+    #[must_use]
+    pub fn hidden_quantity(&self, side: Side) -> u64 {
+        self.side_levels(side, true)
+            .iter()
+            .map(|entry| entry.value().total_quantity())
+            .sum()
+    }
+
     /// Match a market order against the book
     pub fn match_market_order(
         &self,
@@ -1704,15 +2613,19 @@ where
             "Order book {}: Matching market order {} for {} at side {:?}",
             self.symbol, order_id, quantity, side
         );
+        self.check_trading_enabled(None)?;
+        self.config.validate_quantity(quantity)?;
+        self.check_risk(&RiskOrder {
+            order_id,
+            side,
+            price: None,
+            quantity,
+            owner: None,
+        })?;
         let match_result = OrderBook::<T>::match_order(self, order_id, side, quantity, None)?;
 
-        // Trigger trade listener if there are transactions
-        if !match_result.transactions.transactions.is_empty()
-            && let Some(ref listener) = self.trade_listener
-        {
-            let trade_result = TradeResult::new(self.symbol.clone(), match_result.clone());
-            listener(&trade_result);
-        }
+        // Trigger trade listeners if there are transactions
+        self.publish_trade(&match_result, None);
 
         Ok(match_result)
     }
@@ -1759,55 +2672,66 @@ where
             "Order book {}: Matching limit order {} for {} at side {:?} with limit price {}",
             self.symbol, order_id, quantity, side, limit_price
         );
+        self.check_trading_enabled(None)?;
+        self.config.validate(limit_price, quantity)?;
+        self.check_risk(&RiskOrder {
+            order_id,
+            side,
+            price: Some(limit_price),
+            quantity,
+            owner: None,
+        })?;
         let match_result =
             OrderBook::<T>::match_order(self, order_id, side, quantity, Some(limit_price))?;
 
-        // Trigger trade listener if there are transactions
-        if !match_result.transactions.transactions.is_empty()
-            && let Some(ref listener) = self.trade_listener
-        {
-            let trade_result = TradeResult::new(self.symbol.clone(), match_result.clone());
-            listener(&trade_result);
-        }
+        // Trigger trade listeners if there are transactions
+        self.publish_trade(&match_result, None);
 
         Ok(match_result)
     }
 
     /// Create a snapshot of the current order book state
     pub fn create_snapshot(&self, depth: usize) -> OrderBookSnapshot {
-        // Get all bid prices and sort them in descending order
-        let mut bid_prices: Vec<u64> = self.bids.iter().map(|item| *item.key()).collect();
-        bid_prices.sort_by(|a, b| b.cmp(a)); // Descending order
-        bid_prices.truncate(depth);
-
-        // Get all ask prices and sort them in ascending order
-        let mut ask_prices: Vec<u64> = self.asks.iter().map(|item| *item.key()).collect();
-        ask_prices.sort(); // Ascending order
-        ask_prices.truncate(depth);
-
-        let mut bid_levels = Vec::with_capacity(bid_prices.len());
-        let mut ask_levels = Vec::with_capacity(ask_prices.len());
-
-        // Create snapshots for each bid level
-        for price in bid_prices {
-            if let Some(entry) = self.bids.get(&price) {
-                bid_levels.push(entry.value().snapshot());
-            }
-        }
-
-        // Create snapshots for each ask level
-        for price in ask_prices {
-            if let Some(entry) = self.asks.get(&price) {
-                ask_levels.push(entry.value().snapshot());
-            }
-        }
+        let mut snapshot = OrderBookSnapshot {
+            symbol: String::new(),
+            timestamp: 0,
+            bids: Vec::new(),
+            asks: Vec::new(),
+            extra_fields: Default::default(),
+        };
+        self.create_snapshot_into(depth, &mut snapshot);
+        snapshot
+    }
 
-        OrderBookSnapshot {
-            symbol: self.symbol.clone(),
-            timestamp: current_time_millis(),
-            bids: bid_levels,
-            asks: ask_levels,
-        }
+    /// Captures the current order book state into an existing snapshot,
+    /// reusing its level vectors
+    ///
+    /// Levels are read in price order straight from the level maps, stopping
+    /// after `depth` levels per side. Publishing snapshots at a high rate
+    /// through one buffer avoids reallocating the level vectors on every call;
+    /// the orders of each level are still collected into a new vector, one
+    /// allocation per non-empty level. The snapshot's extra fields are cleared.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+    /// let mut snapshot = book.create_snapshot(5);
+    ///
+    /// let _ = book.add_limit_order(OrderId::new(), 101, 10, Side::Buy, TimeInForce::Gtc, None);
+    /// book.create_snapshot_into(5, &mut snapshot);
+    /// assert_eq!(snapshot.bids.len(), 2);
+    /// assert_eq!(snapshot.bids[0].price, 101);
+    /// ```
+    pub fn create_snapshot_into(&self, depth: usize, snapshot: &mut OrderBookSnapshot) {
+        snapshot.symbol.clone_from(&self.symbol);
+        snapshot.timestamp = self.clock.now_millis();
+        capture_levels(self.bids.iter().rev().take(depth), &mut snapshot.bids);
+        capture_levels(self.asks.iter().take(depth), &mut snapshot.asks);
+        snapshot.extra_fields.clear();
     }
 
     /// Create a checksum-protected snapshot package of the entire book.
@@ -1824,6 +2748,15 @@ where
         self.create_snapshot_package(depth)?.to_json()
     }
 
+    /// Serialize a checksum-protected snapshot package in the given format.
+    pub fn snapshot_to_bytes(
+        &self,
+        depth: usize,
+        format: SnapshotFormat,
+    ) -> Result<Vec<u8>, OrderBookError> {
+        self.create_snapshot_package(depth)?.encode(format)
+    }
+
     /// Restore the book state from a checksum-validated snapshot package.
     pub fn restore_from_snapshot_package(
         &self,
@@ -1838,6 +2771,17 @@ where
         self.restore_from_snapshot_package(package)
     }
 
+    /// Restore the book state from a checksum-protected snapshot package stored in
+    /// the given format.
+    pub fn restore_from_snapshot_bytes(
+        &self,
+        data: &[u8],
+        format: SnapshotFormat,
+    ) -> Result<(), OrderBookError> {
+        let package = OrderBookSnapshotPackage::decode(data, format)?;
+        self.restore_from_snapshot_package(package)
+    }
+
     /// Restore the book state from a snapshot, without checksum validation.
     pub fn restore_from_snapshot(&self, snapshot: OrderBookSnapshot) -> Result<(), OrderBookError> {
         if snapshot.symbol != self.symbol {
@@ -1849,8 +2793,6 @@ where
             });
         }
 
-        self.cache.invalidate();
-
         // Clear all existing data
         while let Some(entry) = self.bids.pop_front() {
             drop(entry);
@@ -1858,11 +2800,21 @@ where
         while let Some(entry) = self.asks.pop_front() {
             drop(entry);
         }
+        self.hidden_bids.clear();
+        self.hidden_asks.clear();
+        self.hidden_order_ids.clear();
+        self.order_extra_fields.clear();
+        self.order_owners.clear();
+        self.owner_orders.clear();
         self.order_locations.clear();
         self.has_traded.store(false, Ordering::Relaxed);
         self.last_trade_price.store(0, Ordering::Relaxed);
+        self.last_trade_millis.store(u64::MAX, Ordering::Relaxed);
+        self.last_update_millis
+            .store(self.clock.now_millis(), Ordering::Relaxed);
         self.has_market_close.store(false, Ordering::Relaxed);
         self.market_close_timestamp.store(0, Ordering::Relaxed);
+        self.day_orders_expired.store(false, Ordering::Relaxed);
 
         for level_snapshot in snapshot.bids {
             let price = level_snapshot.price;
@@ -1878,6 +2830,8 @@ where
             self.asks.insert(price, arc_level);
         }
 
+        self.reindex_levels();
+
         // Rebuild order location map with generic order types
         for item in self.bids.iter() {
             let price = *item.key();
@@ -1895,6 +2849,8 @@ where
             }
         }
 
+        self.reset_delta_tracking();
+        self.emit_book_reset(snapshot.timestamp);
         Ok(())
     }
 
@@ -2007,7 +2963,7 @@ where
         // Create enriched snapshot with pre-calculated metrics
         EnrichedSnapshot::with_metrics(
             self.symbol.clone(),
-            current_time_millis(),
+            self.clock.now_millis(),
             bid_levels,
             ask_levels,
             depth, // Use depth for VWAP calculation
@@ -2038,6 +2994,46 @@ where
         (bid_volumes, ask_volumes)
     }
 
+    /// Get the top `levels` price levels of each side as aggregated L2 data
+    ///
+    /// Each entry is `(price, visible_quantity, order_count)`. Bids are ordered from the
+    /// highest price down and asks from the lowest price up. Unlike
+    /// [`OrderBook::create_snapshot`], no per-order detail is copied, so this is suitable
+    /// for publishing depth updates at high frequency. Quantities match the ones reported
+    /// by price level change events, so hidden reserve quantity is not included.
+    ///
+    /// # Arguments
+    /// - `levels`: Maximum number of price levels to return per side
+    ///
+    /// # Returns
+    /// A tuple of `(bids, asks)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// book.add_limit_order(OrderId::new(), 99, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 99, 5, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 101, 7, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// let (bids, asks) = book.depth(5);
+    /// assert_eq!(bids, vec![(99, 15, 2)]);
+    /// assert_eq!(asks, vec![(101, 7, 1)]);
+    /// ```
+    #[must_use]
+    pub fn depth(&self, levels: usize) -> (Vec<DepthLevel>, Vec<DepthLevel>) {
+        let aggregate = |entry: crossbeam_skiplist::map::Entry<'_, u64, Arc<PriceLevel>>| {
+            let level = entry.value();
+            (*entry.key(), level.visible_quantity(), level.order_count())
+        };
+
+        let bids = self.bids.iter().rev().take(levels).map(aggregate).collect();
+        let asks = self.asks.iter().take(levels).map(aggregate).collect();
+        (bids, asks)
+    }
+
     /// Get an Arc reference to the bids as a DashMap
     ///
     /// # Note
@@ -2126,74 +3122,111 @@ where
     /// ```
     #[must_use]
     pub fn depth_statistics(&self, side: Side, levels: usize) -> DepthStats {
+        self.side_depth_statistics(side, levels).0
+    }
+
+    /// Computes depth statistics for both sides of the order book, with skew metrics
+    ///
+    /// Walks the top levels of each side once, instead of two separate
+    /// [`OrderBook::depth_statistics`] calls followed by a best bid and ask
+    /// lookup for the mid price. The distances from the mid are measured to
+    /// the weighted average price of each side, so a side whose liquidity sits
+    /// deeper in the book has a larger distance.
+    ///
+    /// # Arguments
+    /// - `levels`: Maximum number of top levels to analyze per side (0 = all levels)
+    ///
+    /// # Returns
+    /// `BookDepthStats` with the statistics of each side. The mid price and the
+    /// derived metrics are `None` when either side is empty.
+    ///
+    /// # Performance
+    /// O(N) where N is the number of levels analyzed.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 99, 30, Side::Buy, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 102, 20, Side::Sell, TimeInForce::Gtc, None);
+    ///
+    /// let stats = book.depth_statistics_both(10);
+    /// assert_eq!(stats.mid_price, Some(101.0));
+    /// assert_eq!(stats.volume_ratio, Some(2.0));
+    /// assert_eq!(stats.bid_distance_from_mid, Some(101.0 - 99.25));
+    /// assert_eq!(stats.ask_distance_from_mid, Some(1.0));
+    /// ```
+    #[must_use]
+    pub fn depth_statistics_both(&self, levels: usize) -> BookDepthStats {
+        let (bid, best_bid) = self.side_depth_statistics(Side::Buy, levels);
+        let (ask, best_ask) = self.side_depth_statistics(Side::Sell, levels);
+
+        let mid_price = match (best_bid, best_ask) {
+            (Some(bid), Some(ask)) => Some((bid as f64 + ask as f64) / 2.0),
+            _ => None,
+        };
+        let volume_ratio = mid_price.map(|_| bid.total_volume as f64 / ask.total_volume as f64);
+        let bid_distance_from_mid = mid_price.map(|mid| mid - bid.weighted_avg_price);
+        let ask_distance_from_mid = mid_price.map(|mid| ask.weighted_avg_price - mid);
+
+        BookDepthStats {
+            bid,
+            ask,
+            mid_price,
+            volume_ratio,
+            bid_distance_from_mid,
+            ask_distance_from_mid,
+        }
+    }
+
+    /// Depth statistics of the top `levels` of `side`, with the best price
+    /// found while walking them
+    fn side_depth_statistics(&self, side: Side, levels: usize) -> (DepthStats, Option<u64>) {
         let price_levels = match side {
             Side::Buy => &self.bids,
             Side::Sell => &self.asks,
         };
 
         if price_levels.is_empty() {
-            return DepthStats::zero();
+            return (DepthStats::zero(), None);
         }
 
-        let iter: Box<dyn Iterator<Item = _>> = match side {
-            Side::Buy => Box::new(price_levels.iter().rev()),
-            Side::Sell => Box::new(price_levels.iter()),
-        };
-
-        let mut total_volume = 0u64;
-        let mut weighted_price_sum = 0u64;
-        let mut sizes = Vec::new();
-        let mut min_size = u64::MAX;
-        let mut max_size = 0u64;
-        let mut count = 0usize;
-
-        for entry in iter {
-            if levels > 0 && count >= levels {
-                break;
+        depth_kernels::with_scratch(|packed| {
+            // Pack the top non-empty levels, best first
+            for entry in LevelIter::best_first(price_levels.iter(), side) {
+                if levels > 0 && packed.len() >= levels {
+                    break;
+                }
+                if entry.value().total_quantity() > 0 {
+                    packed.push(&entry);
+                }
             }
 
-            let price = *entry.key();
-            let quantity = entry.value().total_quantity();
-
-            if quantity == 0 {
-                continue;
+            let count = packed.len();
+            let sums = depth_kernels::depth_sums(&packed.prices, &packed.quantities);
+            if count == 0 || sums.volume == 0 {
+                return (DepthStats::zero(), None);
             }
 
-            total_volume = total_volume.saturating_add(quantity);
-            weighted_price_sum = weighted_price_sum.saturating_add(price.saturating_mul(quantity));
-            sizes.push(quantity);
-            min_size = min_size.min(quantity);
-            max_size = max_size.max(quantity);
-            count += 1;
-        }
-
-        if count == 0 || total_volume == 0 {
-            return DepthStats::zero();
-        }
-
-        let avg_level_size = total_volume as f64 / count as f64;
-        let weighted_avg_price = weighted_price_sum as f64 / total_volume as f64;
-
-        // Calculate standard deviation
-        let variance: f64 = sizes
-            .iter()
-            .map(|&size| {
-                let diff = size as f64 - avg_level_size;
-                diff * diff
-            })
-            .sum::<f64>()
-            / count as f64;
-        let std_dev = variance.sqrt();
-
-        DepthStats {
-            total_volume,
-            levels_count: count,
-            avg_level_size,
-            weighted_avg_price,
-            min_level_size: if min_size == u64::MAX { 0 } else { min_size },
-            max_level_size: max_size,
-            std_dev_level_size: std_dev,
-        }
+            let avg_level_size = sums.volume as f64 / count as f64;
+            let weighted_avg_price = sums.weighted_price as f64 / sums.volume as f64;
+            let variance =
+                depth_kernels::squared_deviation(&packed.quantities, avg_level_size) / count as f64;
+
+            let stats = DepthStats {
+                total_volume: sums.volume,
+                levels_count: count,
+                avg_level_size,
+                weighted_avg_price,
+                min_level_size: sums.min_quantity,
+                max_level_size: sums.max_quantity,
+                std_dev_level_size: variance.sqrt(),
+            };
+            (stats, packed.prices.first().copied())
+        })
     }
 
     /// Calculates buy and sell pressure based on total volume on each side
@@ -2206,7 +3239,8 @@ where
     /// quantity available on that side (in units).
     ///
     /// # Performance
-    /// O(N + M) where N is bid levels and M is ask levels.
+    /// O(1), read from the side totals maintained as orders rest, execute and
+    /// leave the book.
     ///
     /// # Examples
     /// ```
@@ -2226,19 +3260,10 @@ where
     /// ```
     #[must_use]
     pub fn buy_sell_pressure(&self) -> (u64, u64) {
-        let buy_pressure: u64 = self
-            .bids
-            .iter()
-            .map(|entry| entry.value().total_quantity())
-            .sum();
-
-        let sell_pressure: u64 = self
-            .asks
-            .iter()
-            .map(|entry| entry.value().total_quantity())
-            .sum();
-
-        (buy_pressure, sell_pressure)
+        (
+            self.totals.quantity(Side::Buy),
+            self.totals.quantity(Side::Sell),
+        )
     }
 
     /// Detects if the order book is thin (has low liquidity)
@@ -2327,67 +3352,117 @@ where
             return Vec::new();
         }
 
-        // Find min and max prices
-        let mut min_price = u64::MAX;
-        let mut max_price = 0u64;
-
-        for entry in price_levels.iter() {
-            let price = *entry.key();
-            min_price = min_price.min(price);
-            max_price = max_price.max(price);
-        }
-
-        if min_price == u64::MAX || max_price < min_price {
-            return Vec::new();
-        }
-
-        // Calculate bin width
-        let price_range = max_price - min_price;
-        let bin_width = if price_range == 0 {
-            1
-        } else {
-            price_range.div_ceil(bins as u64) // Ceiling division
-        };
+        depth_kernels::with_scratch(|packed| {
+            for entry in price_levels.iter() {
+                packed.push(&entry);
+            }
+            let (Some(&min_price), Some(&max_price)) =
+                (packed.prices.first(), packed.prices.last())
+            else {
+                return Vec::new();
+            };
 
-        // Initialize bins
-        let mut distribution = Vec::with_capacity(bins);
-        for i in 0..bins {
-            let bin_min = min_price + (i as u64 * bin_width);
-            let bin_max = if i == bins - 1 {
-                max_price + 1 // Make last bin inclusive
+            // Calculate bin width
+            let price_range = max_price - min_price;
+            let bin_width = if price_range == 0 {
+                1
             } else {
-                bin_min + bin_width
+                price_range.div_ceil(bins as u64) // Ceiling division
             };
 
-            distribution.push(DistributionBin {
-                min_price: bin_min,
-                max_price: bin_max,
-                volume: 0,
-                level_count: 0,
-            });
+            // Levels are packed in ascending price order, so each bin is a
+            // contiguous run of them; the last bin takes every remaining level
+            let mut distribution = Vec::with_capacity(bins);
+            let mut start = 0;
+            for i in 0..bins {
+                let bin_min = min_price + (i as u64 * bin_width);
+                let (bin_max, end) = if i == bins - 1 {
+                    (max_price + 1, packed.len()) // Make last bin inclusive
+                } else {
+                    let bin_max = bin_min + bin_width;
+                    (
+                        bin_max,
+                        start + packed.prices[start..].partition_point(|&p| p < bin_max),
+                    )
+                };
+                let quantities = &packed.quantities[start..end];
+                start = end;
+
+                distribution.push(DistributionBin {
+                    min_price: bin_min,
+                    max_price: bin_max,
+                    volume: depth_kernels::volume(quantities),
+                    level_count: depth_kernels::non_zero_count(quantities),
+                });
+            }
+            distribution
+        })
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + Serialize + DeserializeOwned + 'static,
+{
+    /// Create a snapshot that also carries the extra fields of every included order
+    ///
+    /// Restoring it with [`OrderBook::restore_from_snapshot_with_extra_fields`]
+    /// brings back the orders together with their extra fields.
+    pub fn create_snapshot_with_extra_fields(
+        &self,
+        depth: usize,
+    ) -> Result<OrderBookSnapshot, OrderBookError> {
+        let mut snapshot = self.create_snapshot(depth);
+
+        for level in snapshot.bids.iter().chain(snapshot.asks.iter()) {
+            for order in &level.orders {
+                if let Some(fields) = self.order_extra_fields.get(&order.id()) {
+                    let value = serde_json::to_value(fields.value()).map_err(|error| {
+                        OrderBookError::SerializationError {
+                            message: error.to_string(),
+                        }
+                    })?;
+                    snapshot.extra_fields.insert(order.id().to_string(), value);
+                }
+            }
         }
 
-        // Fill bins with data
-        for entry in price_levels.iter() {
-            let price = *entry.key();
-            let quantity = entry.value().total_quantity();
+        Ok(snapshot)
+    }
 
-            if quantity == 0 {
-                continue;
-            }
+    /// Restore the book state from a snapshot, including the extra fields it carries
+    ///
+    /// The extra fields are decoded before the book is touched, so a malformed
+    /// snapshot leaves the current state unchanged.
+    pub fn restore_from_snapshot_with_extra_fields(
+        &self,
+        mut snapshot: OrderBookSnapshot,
+    ) -> Result<(), OrderBookError> {
+        let extra_fields = std::mem::take(&mut snapshot.extra_fields)
+            .into_iter()
+            .map(|(order_id, value)| {
+                let order_id = order_id.parse::<OrderId>().map_err(|error| {
+                    OrderBookError::DeserializationError {
+                        message: format!("Invalid order ID {order_id}: {error}"),
+                    }
+                })?;
+                let fields = serde_json::from_value::<T>(value).map_err(|error| {
+                    OrderBookError::DeserializationError {
+                        message: error.to_string(),
+                    }
+                })?;
+                Ok((order_id, fields))
+            })
+            .collect::<Result<Vec<(OrderId, T)>, OrderBookError>>()?;
 
-            // Find which bin this price belongs to
-            let bin_index = if price >= max_price {
-                bins - 1
-            } else {
-                ((price - min_price) / bin_width).min((bins - 1) as u64) as usize
-            };
+        self.restore_from_snapshot(snapshot)?;
 
-            distribution[bin_index].volume =
-                distribution[bin_index].volume.saturating_add(quantity);
-            distribution[bin_index].level_count += 1;
+        for (order_id, fields) in extra_fields {
+            if self.order_locations.contains_key(&order_id) {
+                self.order_extra_fields.insert(order_id, fields);
+            }
         }
 
-        distribution
+        Ok(())
     }
 }
@@ -6,7 +6,7 @@ use std::sync::Arc;
 /// order book context so we are not adding symbol here.
 /// This event is sent on operations that update the order book price levels
 /// e.g. adding, cancelling, updating or matching order
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PriceLevelChangedEvent {
     /// the order book side of the price level
     pub side: Side,
@@ -24,3 +24,54 @@ pub struct PriceLevelChangedEvent {
 /// a price level in the order book changes (e.g., order added, cancelled,
 /// matched, or updated).
 pub type PriceLevelChangedListener = Arc<dyn Fn(PriceLevelChangedEvent) + Send + Sync>;
+
+/// Consolidated event emitted once per compound operation (e.g. a quote update)
+/// that touches several price levels at the same time.
+///
+/// Each price level appears at most once, carrying its final visible quantity
+/// after the whole operation completed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookChangedEvent {
+    /// Final state of every price level touched by the operation
+    pub changes: Vec<PriceLevelChangedEvent>,
+
+    /// Set when the whole book was replaced, e.g. by a snapshot restore. Listeners
+    /// must then discard their previous view; `changes` lists every visible level
+    /// of the new book.
+    pub reset: Option<BookReset>,
+}
+
+/// Describes a wholesale replacement of the book state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookReset {
+    /// Journal sequence number the restored state continues from
+    pub sequence: u64,
+
+    /// Timestamp of the snapshot the book was restored from (milliseconds since epoch)
+    pub snapshot_ts: u64,
+}
+
+/// A thread-safe listener callback for consolidated book change events.
+pub type BookChangedListener = Arc<dyn Fn(BookChangedEvent) + Send + Sync>;
+
+/// Event emitted when the best bid or best ask changes price or visible quantity.
+///
+/// Changes deeper in the book never produce this event. A side without any
+/// resting visible order is reported with a `None` price and a zero quantity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BboChangedEvent {
+    /// Best bid price, if any bid is resting
+    pub bid_price: Option<u64>,
+
+    /// Visible quantity at the best bid
+    pub bid_quantity: u64,
+
+    /// Best ask price, if any ask is resting
+    pub ask_price: Option<u64>,
+
+    /// Visible quantity at the best ask
+    pub ask_quantity: u64,
+}
+
+/// A thread-safe listener callback for best bid and offer changes.
+pub type BboListener = Arc<dyn Fn(BboChangedEvent) + Send + Sync>;
@@ -4,60 +4,351 @@
    Date: 15/7/25
 ******************************************************************************/
 
-use serde::Serialize;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use crossbeam_skiplist::SkipMap;
+use pricelevel::{PriceLevel, Side};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::sync::atomic::{AtomicU64, Ordering, fence};
+use std::sync::{Arc, Mutex, MutexGuard};
 
-#[derive(Debug, Serialize, Default)]
+/// Stored in place of a best price when the side has no level.
+const NO_PRICE: u64 = u64::MAX;
+
+/// Best bid and ask prices of a book, maintained as visible levels are added
+/// and removed.
+///
+/// Reads are a single atomic load. Writers change the level map first and
+/// then check the cached price: adding a level only takes the lock of its side
+/// when it improves the best price, and removing a level only when it was the
+/// best one. Under the lock the best price is read from the level map, stored,
+/// and read again until the two agree.
+///
+/// A writer skipping the lock may have checked a price that a writer holding
+/// it is about to replace. Both fence between their map access and their
+/// cache access, so either the skipping writer sees the new price and takes
+/// the lock itself, or the locked writer sees the skipping writer's level when
+/// it reads the map again. Once every writer is done the cached prices match
+/// the map.
+#[derive(Debug)]
 pub struct PriceLevelCache {
     best_bid_price: AtomicU64,
     best_ask_price: AtomicU64,
-    cache_valid: AtomicBool,
+    bid_lock: Mutex<()>,
+    ask_lock: Mutex<()>,
+}
+
+impl Default for PriceLevelCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Serialized in the shape of the lazily validated cache it replaced: absent
+/// prices as zero and an always valid flag.
+impl Serialize for PriceLevelCache {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PriceLevelCache", 3)?;
+        state.serialize_field("best_bid_price", &self.best_bid().unwrap_or(0))?;
+        state.serialize_field("best_ask_price", &self.best_ask().unwrap_or(0))?;
+        state.serialize_field("cache_valid", &true)?;
+        state.end()
+    }
 }
 
 impl PriceLevelCache {
     pub fn new() -> Self {
         Self {
-            best_bid_price: AtomicU64::new(0),
-            best_ask_price: AtomicU64::new(0),
-            cache_valid: AtomicBool::new(false),
+            best_bid_price: AtomicU64::new(NO_PRICE),
+            best_ask_price: AtomicU64::new(NO_PRICE),
+            bid_lock: Mutex::new(()),
+            ask_lock: Mutex::new(()),
         }
     }
 
-    pub fn invalidate(&self) {
-        self.cache_valid.store(false, Ordering::Relaxed);
+    fn side(&self, side: Side) -> (&AtomicU64, MutexGuard<'_, ()>) {
+        let (price, lock) = match side {
+            Side::Buy => (&self.best_bid_price, &self.bid_lock),
+            Side::Sell => (&self.best_ask_price, &self.ask_lock),
+        };
+        (price, lock.lock().unwrap_or_else(|e| e.into_inner()))
     }
 
-    pub fn get_cached_best_bid(&self) -> Option<u64> {
-        if self.cache_valid.load(Ordering::Relaxed) {
-            let price = self.best_bid_price.load(Ordering::Relaxed);
-            if price > 0 { Some(price) } else { None }
-        } else {
-            None
+    fn load(price: &AtomicU64) -> Option<u64> {
+        Some(price.load(Ordering::Acquire)).filter(|&price| price != NO_PRICE)
+    }
+
+    /// Whether `price` would be a better price than `best` on `side`.
+    fn improves(side: Side, price: u64, best: Option<u64>) -> bool {
+        match (side, best) {
+            (_, None) => true,
+            (Side::Buy, Some(best)) => price > best,
+            (Side::Sell, Some(best)) => price < best,
         }
     }
 
-    pub fn get_cached_best_ask(&self) -> Option<u64> {
-        if self.cache_valid.load(Ordering::Relaxed) {
-            let price = self.best_ask_price.load(Ordering::Relaxed);
-            if price > 0 { Some(price) } else { None }
-        } else {
-            None
+    pub fn best_bid(&self) -> Option<u64> {
+        Self::load(&self.best_bid_price)
+    }
+
+    pub fn best_ask(&self) -> Option<u64> {
+        Self::load(&self.best_ask_price)
+    }
+
+    /// Records that a level at `price`, already in `price_levels`, may be new.
+    pub fn level_added(
+        &self,
+        side: Side,
+        price: u64,
+        price_levels: &SkipMap<u64, Arc<PriceLevel>>,
+    ) {
+        fence(Ordering::SeqCst);
+        let best = match side {
+            Side::Buy => self.best_bid(),
+            Side::Sell => self.best_ask(),
+        };
+        if Self::improves(side, price, best) {
+            self.publish(side, price_levels);
+        }
+    }
+
+    /// Records that the level at `price` left `price_levels`, reading the best
+    /// price again from the map if it was the best one.
+    pub fn level_removed(
+        &self,
+        side: Side,
+        price: u64,
+        price_levels: &SkipMap<u64, Arc<PriceLevel>>,
+    ) {
+        fence(Ordering::SeqCst);
+        let best = match side {
+            Side::Buy => self.best_bid(),
+            Side::Sell => self.best_ask(),
+        };
+        if best == Some(price) {
+            self.publish(side, price_levels);
+        }
+    }
+
+    /// Stores the best price of `price_levels` under the lock of `side`, until
+    /// the map still holds it after the store.
+    fn publish(&self, side: Side, price_levels: &SkipMap<u64, Arc<PriceLevel>>) {
+        let (best, _guard) = self.side(side);
+        let mut price = Self::read_best(side, price_levels);
+        loop {
+            best.store(price, Ordering::Release);
+            fence(Ordering::SeqCst);
+            let current = Self::read_best(side, price_levels);
+            if current == price {
+                break;
+            }
+            price = current;
+        }
+    }
+
+    /// Reads both best prices from the level maps after they were replaced.
+    pub fn refresh(
+        &self,
+        bids: &SkipMap<u64, Arc<PriceLevel>>,
+        asks: &SkipMap<u64, Arc<PriceLevel>>,
+    ) {
+        for (side, price_levels) in [(Side::Buy, bids), (Side::Sell, asks)] {
+            self.publish(side, price_levels);
+        }
+    }
+
+    fn read_best(side: Side, price_levels: &SkipMap<u64, Arc<PriceLevel>>) -> u64 {
+        let entry = match side {
+            Side::Buy => price_levels.back(),
+            Side::Sell => price_levels.front(),
+        };
+        entry.map_or(NO_PRICE, |entry| *entry.key())
+    }
+}
+
+/// Resting quantity of the visible levels of each side of a book, adjusted as
+/// quantity enters and leaves them.
+///
+/// Every adjustment is the quantity a single operation moved, so concurrent
+/// writers never overwrite each other. A reader racing them may observe a
+/// removal before the matching addition; such a transient deficit reads as
+/// zero.
+#[derive(Debug, Default)]
+pub struct SideTotals {
+    bid_quantity: AtomicU64,
+    ask_quantity: AtomicU64,
+}
+
+impl SideTotals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn total(&self, side: Side) -> &AtomicU64 {
+        match side {
+            Side::Buy => &self.bid_quantity,
+            Side::Sell => &self.ask_quantity,
         }
     }
 
-    pub fn update_best_prices(&self, best_bid: Option<u64>, best_ask: Option<u64>) {
-        if let Some(bid) = best_bid {
-            self.best_bid_price.store(bid, Ordering::Relaxed);
+    /// Total quantity resting on `side`.
+    pub fn quantity(&self, side: Side) -> u64 {
+        let quantity = self.total(side).load(Ordering::Acquire);
+        if quantity > i64::MAX as u64 {
+            0
         } else {
-            self.best_bid_price.store(0, Ordering::Relaxed);
+            quantity
+        }
+    }
+
+    /// Records `quantity` added to a level of `side`.
+    pub fn added(&self, side: Side, quantity: u64) {
+        if quantity > 0 {
+            self.total(side).fetch_add(quantity, Ordering::AcqRel);
+        }
+    }
+
+    /// Records `quantity` taken from a level of `side`.
+    pub fn removed(&self, side: Side, quantity: u64) {
+        if quantity > 0 {
+            self.total(side).fetch_sub(quantity, Ordering::AcqRel);
         }
+    }
 
-        if let Some(ask) = best_ask {
-            self.best_ask_price.store(ask, Ordering::Relaxed);
+    /// Records a level of `side` going from `before` to `after`.
+    pub fn resized(&self, side: Side, before: u64, after: u64) {
+        if after >= before {
+            self.added(side, after - before);
         } else {
-            self.best_ask_price.store(0, Ordering::Relaxed);
+            self.removed(side, before - after);
+        }
+    }
+
+    /// Sums both sides again from the level maps after they were replaced.
+    pub fn refresh(
+        &self,
+        bids: &SkipMap<u64, Arc<PriceLevel>>,
+        asks: &SkipMap<u64, Arc<PriceLevel>>,
+    ) {
+        for (side, price_levels) in [(Side::Buy, bids), (Side::Sell, asks)] {
+            let quantity = price_levels
+                .iter()
+                .map(|entry| entry.value().total_quantity())
+                .fold(0u64, u64::saturating_add);
+            self.total(side).store(quantity, Ordering::Release);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levels(prices: &[u64]) -> SkipMap<u64, Arc<PriceLevel>> {
+        let map = SkipMap::new();
+        for &price in prices {
+            map.insert(price, Arc::new(PriceLevel::new(price)));
         }
+        map
+    }
+
+    #[test]
+    fn test_best_prices_follow_level_changes() {
+        let cache = PriceLevelCache::new();
+        assert_eq!(cache.best_bid(), None);
+
+        let bids = levels(&[100, 98]);
+        let asks = levels(&[101, 105]);
+        cache.level_added(Side::Buy, 98, &bids);
+        cache.level_added(Side::Buy, 100, &bids);
+        cache.level_added(Side::Sell, 105, &asks);
+        cache.level_added(Side::Sell, 101, &asks);
+        assert_eq!(cache.best_bid(), Some(100));
+        assert_eq!(cache.best_ask(), Some(101));
+
+        // Removing a level behind the best one keeps the best price
+        asks.remove(&105);
+        cache.level_removed(Side::Sell, 105, &asks);
+        assert_eq!(cache.best_ask(), Some(101));
+
+        bids.remove(&100);
+        cache.level_removed(Side::Buy, 100, &bids);
+        assert_eq!(cache.best_bid(), Some(98));
+
+        bids.remove(&98);
+        cache.level_removed(Side::Buy, 98, &bids);
+        assert_eq!(cache.best_bid(), None);
+
+        cache.refresh(&levels(&[90, 95]), &levels(&[]));
+        assert_eq!(cache.best_bid(), Some(95));
+        assert_eq!(cache.best_ask(), None);
+    }
+
+    #[test]
+    fn test_best_prices_match_levels_after_concurrent_changes() {
+        const THREADS: u64 = 4;
+        let cache = PriceLevelCache::new();
+        let bids = levels(&[]);
+        let asks = levels(&[]);
+
+        std::thread::scope(|scope| {
+            for thread in 0..THREADS {
+                let (cache, bids, asks) = (&cache, &bids, &asks);
+                scope.spawn(move || {
+                    // Each thread owns the prices congruent to its index, so a
+                    // level is only ever added and removed by one writer
+                    let mut seed = thread + 1;
+                    for _ in 0..20_000 {
+                        seed = seed
+                            .wrapping_mul(6_364_136_223_846_793_005)
+                            .wrapping_add(1_442_695_040_888_963_407);
+                        let price = 100 + (seed >> 59) * THREADS + thread;
+                        for (side, price_levels) in [(Side::Buy, bids), (Side::Sell, asks)] {
+                            if price_levels.remove(&price).is_some() {
+                                cache.level_removed(side, price, price_levels);
+                            } else {
+                                price_levels.insert(price, Arc::new(PriceLevel::new(price)));
+                                cache.level_added(side, price, price_levels);
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(cache.best_bid(), bids.back().map(|entry| *entry.key()));
+        assert_eq!(cache.best_ask(), asks.front().map(|entry| *entry.key()));
+    }
+
+    #[test]
+    fn test_side_totals_track_adjustments() {
+        let totals = SideTotals::new();
+        totals.added(Side::Buy, 30);
+        totals.added(Side::Sell, 10);
+        totals.resized(Side::Buy, 30, 20);
+        totals.resized(Side::Sell, 10, 15);
+        assert_eq!(totals.quantity(Side::Buy), 20);
+        assert_eq!(totals.quantity(Side::Sell), 15);
+
+        // A removal observed before its addition reads as zero
+        totals.removed(Side::Sell, 25);
+        assert_eq!(totals.quantity(Side::Sell), 0);
+        totals.added(Side::Sell, 25);
+        assert_eq!(totals.quantity(Side::Sell), 15);
 
-        self.cache_valid.store(true, Ordering::Relaxed);
+        let bids = levels(&[100]);
+        bids.get(&100)
+            .unwrap()
+            .value()
+            .add_order(pricelevel::OrderType::Standard {
+                id: pricelevel::OrderId::new(),
+                price: 100,
+                quantity: 7,
+                side: Side::Buy,
+                timestamp: 0,
+                time_in_force: pricelevel::TimeInForce::Gtc,
+                extra_fields: (),
+            });
+        totals.refresh(&bids, &levels(&[]));
+        assert_eq!(totals.quantity(Side::Buy), 7);
+        assert_eq!(totals.quantity(Side::Sell), 0);
     }
 }
@@ -0,0 +1,330 @@
+//! Static and dynamic price bands that reject or halt on violating orders and trades.
+
+use super::book::OrderBook;
+use super::error::OrderBookError;
+use super::journal::JournalCommand;
+use super::session::SessionState;
+use pricelevel::{OrderId, Side};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use tracing::{error, trace};
+
+/// Price the dynamic band of a circuit breaker is centered on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ReferencePriceSource {
+    /// Price of the last trade in the book
+    #[default]
+    LastTrade,
+    /// Closing price of the previous session, recorded when the session closes
+    /// or set through [`OrderBook::set_reference_price`]
+    PriorClose,
+    /// Price supplied by an external source through [`OrderBook::set_reference_price`]
+    External,
+}
+
+/// What a circuit breaker does when a price band is hit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BandAction {
+    /// Reject the violating order, or stop a sweep at the band
+    #[default]
+    Reject,
+    /// Reject as above and halt trading in the book
+    Halt,
+}
+
+/// What hit a price band.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BandTrigger {
+    /// An order was submitted with a price outside the band
+    Order,
+    /// A sweep reached a resting level outside the band
+    Trade,
+}
+
+/// Static and dynamic price bands enforced on the orders and trades of a book.
+///
+/// Orders priced outside the bands are rejected, and sweeps stop at the first
+/// level beyond them. Dynamic bands follow the reference price, so they only
+/// apply once the reference price is known.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::{BandAction, CircuitBreaker, OrderBook, ReferencePriceSource, SessionState};
+/// use pricelevel::{OrderId, Side, TimeInForce};
+///
+/// let mut book = OrderBook::<()>::new("BTC/USD");
+/// book.set_circuit_breaker(
+///     CircuitBreaker::new(ReferencePriceSource::External)
+///         .with_dynamic_band_bps(500)
+///         .with_action(BandAction::Halt),
+/// );
+/// book.set_reference_price(ReferencePriceSource::External, Some(1_000));
+///
+/// assert!(book.add_limit_order(OrderId::new(), 1_040, 1, Side::Buy, TimeInForce::Gtc, None).is_ok());
+/// assert!(book.add_limit_order(OrderId::new(), 1_060, 1, Side::Buy, TimeInForce::Gtc, None).is_err());
+/// assert_eq!(book.session_state(), SessionState::Halted);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitBreaker {
+    /// Price the dynamic band is centered on
+    pub reference_source: ReferencePriceSource,
+    /// Lowest and highest price ever allowed, if set
+    pub static_band: Option<(u64, u64)>,
+    /// Half-width of the dynamic band around the reference price, in basis points
+    pub dynamic_band_bps: Option<u64>,
+    /// What happens when a band is hit
+    pub action: BandAction,
+}
+
+impl CircuitBreaker {
+    /// Create a circuit breaker without bands, centering dynamic bands on `reference_source`
+    pub fn new(reference_source: ReferencePriceSource) -> Self {
+        Self {
+            reference_source,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the static band to `[min_price, max_price]`
+    #[must_use]
+    pub fn with_static_band(mut self, min_price: u64, max_price: u64) -> Self {
+        self.static_band = Some((min_price, max_price));
+        self
+    }
+
+    /// Sets the dynamic band to `±bps` basis points around the reference price
+    #[must_use]
+    pub fn with_dynamic_band_bps(mut self, bps: u64) -> Self {
+        self.dynamic_band_bps = Some(bps);
+        self
+    }
+
+    /// Sets what happens when a band is hit
+    #[must_use]
+    pub fn with_action(mut self, action: BandAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Limits for a reference price of `reference_price`, if any
+    pub fn limits(&self, reference_price: Option<u64>) -> PriceBandLimits {
+        let (mut lower, mut upper) = match self.static_band {
+            Some((min, max)) => (Some(min), Some(max)),
+            None => (None, None),
+        };
+        if let (Some(bps), Some(reference)) = (self.dynamic_band_bps, reference_price) {
+            let width = (u128::from(reference) * u128::from(bps) / 10_000) as u64;
+            let dynamic_lower = reference.saturating_sub(width);
+            let dynamic_upper = reference.saturating_add(width);
+            lower = Some(lower.map_or(dynamic_lower, |l| l.max(dynamic_lower)));
+            upper = Some(upper.map_or(dynamic_upper, |u| u.min(dynamic_upper)));
+        }
+        PriceBandLimits {
+            reference_price,
+            lower,
+            upper,
+        }
+    }
+}
+
+/// Prices a book accepts under its circuit breaker.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceBandLimits {
+    /// Reference price the dynamic band is centered on, if known
+    pub reference_price: Option<u64>,
+    /// Lowest accepted price, if bounded
+    pub lower: Option<u64>,
+    /// Highest accepted price, if bounded
+    pub upper: Option<u64>,
+}
+
+impl PriceBandLimits {
+    /// Whether `price` is within the limits
+    pub fn contains(&self, price: u64) -> bool {
+        self.lower.is_none_or(|lower| price >= lower)
+            && self.upper.is_none_or(|upper| price <= upper)
+    }
+}
+
+/// A price band was hit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceBandEvent {
+    /// Symbol of the book
+    pub symbol: String,
+    /// Order that hit the band
+    pub order_id: OrderId,
+    /// Side of the order
+    pub side: Side,
+    /// Violating price: the order price, or the first level beyond the band
+    pub price: u64,
+    /// Limits that were in force
+    pub limits: PriceBandLimits,
+    /// What hit the band
+    pub trigger: BandTrigger,
+    /// What the circuit breaker did
+    pub action: BandAction,
+    /// Time of the hit, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// A listener for price band hits.
+pub type PriceBandListener = Arc<dyn Fn(&PriceBandEvent) + Send + Sync>;
+
+/// Reference prices set from outside the trade flow
+#[derive(Debug, Default, Clone, Copy)]
+pub(super) struct ReferencePrices {
+    pub prior_close: Option<u64>,
+    pub external: Option<u64>,
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Enforce the price bands of `breaker` on every new order and sweep
+    pub fn set_circuit_breaker(&mut self, breaker: CircuitBreaker) {
+        self.circuit_breaker = Some(breaker);
+    }
+
+    /// Stop enforcing price bands
+    pub fn remove_circuit_breaker(&mut self) {
+        self.circuit_breaker = None;
+    }
+
+    /// The circuit breaker enforced by this book, if any
+    #[must_use]
+    pub fn circuit_breaker(&self) -> Option<&CircuitBreaker> {
+        self.circuit_breaker.as_ref()
+    }
+
+    /// set price band hit listener for this order book
+    pub fn set_price_band_listener(&mut self, listener: PriceBandListener) {
+        self.price_band_listener = Some(listener);
+    }
+
+    /// remove price band hit listener for this order book
+    pub fn remove_price_band_listener(&mut self) {
+        self.price_band_listener = None;
+    }
+
+    /// Sets the prior close or external reference price, or clears it with `None`
+    ///
+    /// The prior close is also recorded automatically from the last trade price
+    /// when the session closes. Setting [`ReferencePriceSource::LastTrade`] has no
+    /// effect, as it always follows the trades of the book.
+    pub fn set_reference_price(&self, source: ReferencePriceSource, price: Option<u64>) {
+        let _journaled =
+            match self.begin_journaled(|| JournalCommand::SetReferencePrice { source, price }) {
+                Ok(scope) => scope,
+                Err(e) => {
+                    error!("Order book {}: {}", self.symbol, e);
+                    return;
+                }
+            };
+        let mut prices = self
+            .reference_prices
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        match source {
+            ReferencePriceSource::LastTrade => {}
+            ReferencePriceSource::PriorClose => prices.prior_close = price,
+            ReferencePriceSource::External => prices.external = price,
+        }
+    }
+
+    /// Current price of `source`, if known
+    #[must_use]
+    pub fn reference_price(&self, source: ReferencePriceSource) -> Option<u64> {
+        match source {
+            ReferencePriceSource::LastTrade => self.last_trade_price(),
+            ReferencePriceSource::PriorClose | ReferencePriceSource::External => {
+                let prices = self
+                    .reference_prices
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner());
+                match source {
+                    ReferencePriceSource::PriorClose => prices.prior_close,
+                    _ => prices.external,
+                }
+            }
+        }
+    }
+
+    /// Price limits currently enforced by the circuit breaker, if one is set
+    #[must_use]
+    pub fn price_band_limits(&self) -> Option<PriceBandLimits> {
+        let breaker = self.circuit_breaker.as_ref()?;
+        Some(breaker.limits(self.reference_price(breaker.reference_source)))
+    }
+
+    /// Checks the price of a new order against the price bands
+    pub(super) fn check_price_band(
+        &self,
+        order_id: OrderId,
+        side: Side,
+        price: u64,
+    ) -> Result<(), OrderBookError> {
+        let Some(limits) = self.price_band_limits() else {
+            return Ok(());
+        };
+        if limits.contains(price) {
+            return Ok(());
+        }
+        self.hit_price_band(order_id, side, price, limits, BandTrigger::Order);
+        Err(OrderBookError::PriceOutOfBand {
+            price,
+            min_price: limits.lower,
+            max_price: limits.upper,
+        })
+    }
+
+    /// Reports a band hit and halts the book if the circuit breaker says so
+    pub(super) fn hit_price_band(
+        &self,
+        order_id: OrderId,
+        side: Side,
+        price: u64,
+        limits: PriceBandLimits,
+        trigger: BandTrigger,
+    ) {
+        let action = self
+            .circuit_breaker
+            .as_ref()
+            .map_or(BandAction::Reject, |breaker| breaker.action);
+        trace!(
+            "Order book {}: {:?} at {} hit the price band {:?}",
+            self.symbol, trigger, price, limits
+        );
+
+        if action == BandAction::Halt
+            && self.session_state() != SessionState::Halted
+            && let Err(e) = self.set_session_state(SessionState::Halted)
+        {
+            error!("Order book {}: {}", self.symbol, e);
+        }
+
+        if let Some(ref listener) = self.price_band_listener {
+            listener(&PriceBandEvent {
+                symbol: self.symbol.clone(),
+                order_id,
+                side,
+                price,
+                limits,
+                trigger,
+                action,
+                timestamp: self.clock.now_millis(),
+            });
+        }
+    }
+
+    /// Records the last trade price as the prior close, when the session closes
+    pub(super) fn record_prior_close(&self) {
+        if self.has_traded.load(Ordering::Relaxed) {
+            self.reference_prices
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .prior_close = Some(self.last_trade_price.load(Ordering::Relaxed));
+        }
+    }
+}
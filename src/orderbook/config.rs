@@ -0,0 +1,268 @@
+//! Per-book trading rules applied to every incoming order.
+
+use super::error::OrderBookError;
+use super::ladder::MAX_LADDER_TICKS;
+use serde::{Deserialize, Serialize};
+
+/// Price and quantity constraints enforced by an order book.
+///
+/// Every rule is optional; a book created with the default configuration accepts
+/// any price and quantity.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::{BookConfig, OrderBook};
+/// use pricelevel::{OrderId, Side, TimeInForce};
+///
+/// let config = BookConfig::default().with_tick_size(5).with_lot_size(10);
+/// let book = OrderBook::<()>::new_with_config("BTC/USD", config);
+///
+/// assert!(book.add_limit_order(OrderId::new(), 105, 20, Side::Buy, TimeInForce::Gtc, None).is_ok());
+/// assert!(book.add_limit_order(OrderId::new(), 103, 20, Side::Buy, TimeInForce::Gtc, None).is_err());
+/// assert!(book.add_limit_order(OrderId::new(), 100, 15, Side::Buy, TimeInForce::Gtc, None).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookConfig {
+    /// Prices must be a multiple of this value (in price units)
+    pub tick_size: Option<u64>,
+    /// Quantities must be a multiple of this value (in units)
+    pub lot_size: Option<u64>,
+    /// Minimum `price * quantity` of a priced order
+    pub min_notional: Option<u64>,
+    /// Lowest price a priced order may have
+    #[serde(default)]
+    pub min_price: Option<u64>,
+    /// Highest price a priced order may have
+    #[serde(default)]
+    pub max_price: Option<u64>,
+    /// Keep the visible levels in a dense array over the tick grid, see
+    /// [`BookConfig::with_dense_ladder`]
+    #[serde(default)]
+    pub dense_ladder: bool,
+    /// Number of shards of the map locating resting orders by ID, see
+    /// [`BookConfig::with_location_shards`]
+    #[serde(default)]
+    pub location_shards: Option<usize>,
+    /// Most visible price levels each side keeps, see
+    /// [`BookConfig::with_max_levels`]
+    #[serde(default)]
+    pub max_levels: Option<usize>,
+    /// What happens to an order that would rest beyond `max_levels`
+    #[serde(default)]
+    pub level_eviction: LevelEviction,
+}
+
+/// Policy of a book whose sides are capped to a number of price levels when
+/// an order would open a level on a full side.
+///
+/// Either way, the part of an order that cannot rest is cancelled and the
+/// order is answered with `OrderBookError::DepthLimitExceeded`; fills it made
+/// on the way in stand.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LevelEviction {
+    /// Keep the existing levels and reject the new one
+    #[default]
+    Reject,
+    /// Open the new level if it ranks better than the worst level, cancelling
+    /// the orders of the worst level to make room
+    DropWorst,
+}
+
+impl BookConfig {
+    /// Sets the minimum price increment
+    #[must_use]
+    pub fn with_tick_size(mut self, tick_size: u64) -> Self {
+        self.tick_size = Some(tick_size);
+        self
+    }
+
+    /// Sets the minimum quantity increment
+    #[must_use]
+    pub fn with_lot_size(mut self, lot_size: u64) -> Self {
+        self.lot_size = Some(lot_size);
+        self
+    }
+
+    /// Sets the minimum notional value of a priced order
+    #[must_use]
+    pub fn with_min_notional(mut self, min_notional: u64) -> Self {
+        self.min_notional = Some(min_notional);
+        self
+    }
+
+    /// Sets the inclusive range of prices priced orders may have
+    #[must_use]
+    pub fn with_price_band(mut self, min_price: u64, max_price: u64) -> Self {
+        self.min_price = Some(min_price);
+        self.max_price = Some(max_price);
+        self
+    }
+
+    /// Keeps the visible levels in a dense array with one slot per tick
+    ///
+    /// Adding, cancelling and amending orders find their level by indexing
+    /// its slot, and matching walks the levels through an occupancy bitmap
+    /// instead of the ordered level maps. Those maps are still kept for
+    /// depth queries and snapshots, so they are only touched when a level
+    /// opens or closes. Only takes effect when the tick size and the price band
+    /// bound the prices to at most [`MAX_LADDER_TICKS`] ticks, see
+    /// [`BookConfig::ladder_ticks`].
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{BookConfig, OrderBook};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let config = BookConfig::default()
+    ///     .with_tick_size(25)
+    ///     .with_price_band(400_000, 600_000)
+    ///     .with_dense_ladder();
+    /// assert_eq!(config.ladder_ticks(), Some(8_001));
+    ///
+    /// let book = OrderBook::<()>::new_with_config("ES", config);
+    /// book.add_limit_order(OrderId::new(), 500_000, 1, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 500_025, 1, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// assert_eq!(book.best_bid(), Some(500_025));
+    /// ```
+    #[must_use]
+    pub fn with_dense_ladder(mut self) -> Self {
+        self.dense_ladder = true;
+        self
+    }
+
+    /// Splits the map locating resting orders by ID into `shards` shards
+    ///
+    /// Every cancel, update and lookup by order ID locks one shard of this
+    /// map, so a book hit by many threads at once spreads them over more
+    /// locks with a higher count. The count is rounded up to a power of two
+    /// of at least 2; without it the map picks a count from the number of
+    /// CPUs.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{BookConfig, OrderBook};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let config = BookConfig::default().with_location_shards(100);
+    /// assert_eq!(config.location_shards, Some(128));
+    ///
+    /// let book = OrderBook::<()>::new_with_config("BTC/USD", config);
+    /// let id = OrderId::new();
+    /// book.add_limit_order(id, 100, 1, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// assert!(book.get_order(id).is_some());
+    /// ```
+    #[must_use]
+    pub fn with_location_shards(mut self, shards: usize) -> Self {
+        self.location_shards = Some(shards.max(2).next_power_of_two());
+        self
+    }
+
+    /// Caps each side to its best `max_levels` visible price levels
+    ///
+    /// Orders that would open a level on a full side are handled by
+    /// `eviction`, which keeps memory bounded and depth traversals short when
+    /// only the top of the book matters, as for mirrored external feeds.
+    /// Hidden levels are not capped.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{BookConfig, LevelEviction, OrderBook};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let config = BookConfig::default().with_max_levels(2, LevelEviction::DropWorst);
+    /// let book = OrderBook::<()>::new_with_config("BTC/USD", config);
+    /// for price in [98, 99] {
+    ///     book.add_limit_order(OrderId::new(), price, 1, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// }
+    ///
+    /// // A better bid pushes out the worst level, a worse one does not fit
+    /// book.add_limit_order(OrderId::new(), 100, 1, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// assert!(book.add_limit_order(OrderId::new(), 97, 1, Side::Buy, TimeInForce::Gtc, None).is_err());
+    /// let bids: Vec<u64> = book.levels_with_cumulative_depth(Side::Buy).map(|l| l.price).collect();
+    /// assert_eq!(bids, vec![100, 99]);
+    /// ```
+    #[must_use]
+    pub fn with_max_levels(mut self, max_levels: usize, eviction: LevelEviction) -> Self {
+        self.max_levels = Some(max_levels);
+        self.level_eviction = eviction;
+        self
+    }
+
+    /// Number of ticks between the price band limits, both included, if a
+    /// positive tick size and both limits are set and they span at most
+    /// [`MAX_LADDER_TICKS`] ticks
+    #[must_use]
+    pub fn ladder_ticks(&self) -> Option<usize> {
+        let tick_size = self.tick_size.filter(|&tick_size| tick_size > 0)?;
+        let span = self.max_price?.checked_sub(self.min_price?)?;
+        usize::try_from(span / tick_size + 1)
+            .ok()
+            .filter(|&ticks| ticks <= MAX_LADDER_TICKS)
+    }
+
+    /// Checks that `price` lies on the tick grid
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::InvalidTickSize` if it does not.
+    pub fn validate_price(&self, price: u64) -> Result<(), OrderBookError> {
+        match self.tick_size {
+            Some(tick_size) if tick_size > 0 && !price.is_multiple_of(tick_size) => {
+                Err(OrderBookError::InvalidTickSize { price, tick_size })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that `quantity` is a whole number of lots
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::InvalidLotSize` if it is not.
+    pub fn validate_quantity(&self, quantity: u64) -> Result<(), OrderBookError> {
+        match self.lot_size {
+            Some(lot_size) if lot_size > 0 && !quantity.is_multiple_of(lot_size) => {
+                Err(OrderBookError::InvalidLotSize { quantity, lot_size })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that `price` lies within the price band
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::PriceOutOfBand` if it does not.
+    pub fn validate_price_band(&self, price: u64) -> Result<(), OrderBookError> {
+        let below = self.min_price.is_some_and(|min| price < min);
+        let above = self.max_price.is_some_and(|max| price > max);
+        if below || above {
+            return Err(OrderBookError::PriceOutOfBand {
+                price,
+                min_price: self.min_price,
+                max_price: self.max_price,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks price, quantity and notional value of a priced order
+    ///
+    /// # Errors
+    /// Returns the first rule the order breaks: `InvalidTickSize`, `PriceOutOfBand`,
+    /// `InvalidLotSize` or `BelowMinNotional`.
+    pub fn validate(&self, price: u64, quantity: u64) -> Result<(), OrderBookError> {
+        self.validate_price(price)?;
+        self.validate_price_band(price)?;
+        self.validate_quantity(quantity)?;
+
+        if let Some(min_notional) = self.min_notional {
+            let notional = u128::from(price) * u128::from(quantity);
+            if notional < u128::from(min_notional) {
+                return Err(OrderBookError::BelowMinNotional {
+                    notional,
+                    min_notional,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
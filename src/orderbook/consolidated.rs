@@ -0,0 +1,261 @@
+//! Consolidated view of the books of one instrument across several venues.
+
+use super::book::{DepthLevel, OrderBook};
+use pricelevel::Side;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Quantity one venue contributes to a consolidated price level.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VenueQuantity {
+    /// Name of the venue
+    pub venue: String,
+    /// Visible quantity of the venue at the price (in units)
+    pub quantity: u64,
+    /// Number of orders of the venue at the price
+    pub order_count: usize,
+}
+
+/// A price level of the consolidated book.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsolidatedLevel {
+    /// Price of the level (in price units)
+    pub price: u64,
+    /// Visible quantity of every venue at the price (in units)
+    pub quantity: u64,
+    /// Number of orders of every venue at the price
+    pub order_count: usize,
+    /// Contribution of each venue, largest quantity first
+    pub venues: Vec<VenueQuantity>,
+}
+
+/// Best consolidated bid and offer, with the venues quoting them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Nbbo {
+    /// Best bid across venues
+    pub bid: Option<ConsolidatedLevel>,
+    /// Best ask across venues
+    pub ask: Option<ConsolidatedLevel>,
+}
+
+impl Nbbo {
+    /// Difference between the best ask and the best bid, if the book is not crossed
+    pub fn spread(&self) -> Option<u64> {
+        let (bid, ask) = (self.bid.as_ref()?, self.ask.as_ref()?);
+        ask.price.checked_sub(bid.price)
+    }
+
+    /// Whether the best bid of one venue equals the best ask of another
+    pub fn is_locked(&self) -> bool {
+        matches!((&self.bid, &self.ask), (Some(bid), Some(ask)) if bid.price == ask.price)
+    }
+
+    /// Whether the best bid of one venue is above the best ask of another
+    pub fn is_crossed(&self) -> bool {
+        matches!((&self.bid, &self.ask), (Some(bid), Some(ask)) if bid.price > ask.price)
+    }
+}
+
+/// Unified view of the same instrument traded on several venues.
+///
+/// Each venue is an [`OrderBook`] shared with whatever keeps it up to date, such
+/// as a feed adapter. Views are computed from the venue books on every call, so
+/// they always reflect their current state. Books of different venues are not
+/// matched against each other, so the consolidated book can be locked or crossed.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::OrderBook;
+/// use orderbook_rs::orderbook::consolidated::ConsolidatedBook;
+/// use pricelevel::{OrderId, Side, TimeInForce};
+/// use std::sync::Arc;
+///
+/// let binance = Arc::new(OrderBook::<()>::new("BTC/USDT"));
+/// let kraken = Arc::new(OrderBook::<()>::new("BTC/USDT"));
+/// binance.add_limit_order(OrderId::new(), 100, 5, Side::Buy, TimeInForce::Gtc, None).unwrap();
+/// kraken.add_limit_order(OrderId::new(), 100, 3, Side::Buy, TimeInForce::Gtc, None).unwrap();
+/// kraken.add_limit_order(OrderId::new(), 102, 4, Side::Sell, TimeInForce::Gtc, None).unwrap();
+///
+/// let mut book = ConsolidatedBook::new("BTC/USDT");
+/// book.add_venue("binance", binance);
+/// book.add_venue("kraken", kraken);
+///
+/// let nbbo = book.nbbo();
+/// let bid = nbbo.bid.unwrap();
+/// assert_eq!((bid.price, bid.quantity), (100, 8));
+/// assert_eq!(bid.venues[0].venue, "binance");
+/// assert_eq!(nbbo.ask.unwrap().venues[0].venue, "kraken");
+/// ```
+pub struct ConsolidatedBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    symbol: String,
+    venues: Vec<(String, Arc<OrderBook<T>>)>,
+}
+
+impl<T> ConsolidatedBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Create a consolidated book of `symbol` without venues
+    pub fn new(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            venues: Vec::new(),
+        }
+    }
+
+    /// Symbol of the instrument
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Adds the book of `venue`, replacing the venue's previous book
+    pub fn add_venue(&mut self, venue: &str, book: Arc<OrderBook<T>>) {
+        match self.venues.iter_mut().find(|(name, _)| name == venue) {
+            Some((_, existing)) => *existing = book,
+            None => self.venues.push((venue.to_string(), book)),
+        }
+    }
+
+    /// Removes the book of `venue`
+    pub fn remove_venue(&mut self, venue: &str) -> Option<Arc<OrderBook<T>>> {
+        let index = self.venues.iter().position(|(name, _)| name == venue)?;
+        Some(self.venues.remove(index).1)
+    }
+
+    /// Book of `venue`
+    pub fn venue(&self, venue: &str) -> Option<&Arc<OrderBook<T>>> {
+        self.venues
+            .iter()
+            .find(|(name, _)| name == venue)
+            .map(|(_, book)| book)
+    }
+
+    /// Names of the venues, in the order they were added
+    pub fn venues(&self) -> Vec<&str> {
+        self.venues.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Up to `levels` consolidated price levels per side, best first, as `(bids, asks)`
+    pub fn depth(&self, levels: usize) -> (Vec<ConsolidatedLevel>, Vec<ConsolidatedLevel>) {
+        let mut bids: BTreeMap<u64, ConsolidatedLevel> = BTreeMap::new();
+        let mut asks: BTreeMap<u64, ConsolidatedLevel> = BTreeMap::new();
+        for (venue, book) in &self.venues {
+            // The top `levels` of each venue hold every level of the consolidated top
+            let (venue_bids, venue_asks) = book.depth(levels);
+            merge(&mut bids, venue, &venue_bids);
+            merge(&mut asks, venue, &venue_asks);
+        }
+        (
+            finish(bids.into_values().rev(), levels),
+            finish(asks.into_values(), levels),
+        )
+    }
+
+    /// Best consolidated bid and offer
+    pub fn nbbo(&self) -> Nbbo {
+        let (mut bids, mut asks) = self.depth(1);
+        Nbbo {
+            bid: bids.pop(),
+            ask: asks.pop(),
+        }
+    }
+
+    /// Best bid price across venues
+    pub fn best_bid(&self) -> Option<u64> {
+        self.venues
+            .iter()
+            .filter_map(|(_, book)| book.best_bid())
+            .max()
+    }
+
+    /// Best ask price across venues
+    pub fn best_ask(&self) -> Option<u64> {
+        self.venues
+            .iter()
+            .filter_map(|(_, book)| book.best_ask())
+            .min()
+    }
+
+    /// Midpoint of the best bid and ask across venues
+    pub fn mid_price(&self) -> Option<f64> {
+        Some((self.best_bid()? as f64 + self.best_ask()? as f64) / 2.0)
+    }
+
+    /// Visible quantity of the top `levels` consolidated levels of `side`
+    pub fn total_depth(&self, levels: usize, side: Side) -> u64 {
+        let (bids, asks) = self.depth(levels);
+        let side_levels = match side {
+            Side::Buy => bids,
+            Side::Sell => asks,
+        };
+        side_levels.iter().map(|level| level.quantity).sum()
+    }
+
+    /// Imbalance of the top `levels` consolidated levels, as computed by
+    /// [`OrderBook::order_book_imbalance`] for a single book
+    pub fn imbalance(&self, levels: usize) -> f64 {
+        let bid_volume = self.total_depth(levels, Side::Buy) as f64;
+        let ask_volume = self.total_depth(levels, Side::Sell) as f64;
+        if bid_volume + ask_volume == 0.0 {
+            return 0.0;
+        }
+        (bid_volume - ask_volume) / (bid_volume + ask_volume)
+    }
+
+    /// Share of the visible quantity of the top `levels` levels of `side` quoted
+    /// by each venue, in the order the venues were added
+    pub fn venue_shares(&self, levels: usize, side: Side) -> Vec<(String, f64)> {
+        let quantities: Vec<(String, u64)> = self
+            .venues
+            .iter()
+            .map(|(venue, book)| (venue.clone(), book.total_depth_at_levels(levels, side)))
+            .collect();
+        let total: u64 = quantities.iter().map(|(_, quantity)| quantity).sum();
+        quantities
+            .into_iter()
+            .map(|(venue, quantity)| {
+                let share = if total == 0 {
+                    0.0
+                } else {
+                    quantity as f64 / total as f64
+                };
+                (venue, share)
+            })
+            .collect()
+    }
+}
+
+/// Adds the levels of `venue` to the consolidated levels of one side
+fn merge(side: &mut BTreeMap<u64, ConsolidatedLevel>, venue: &str, levels: &[DepthLevel]) {
+    for &(price, quantity, order_count) in levels {
+        let level = side.entry(price).or_insert_with(|| ConsolidatedLevel {
+            price,
+            quantity: 0,
+            order_count: 0,
+            venues: Vec::new(),
+        });
+        level.quantity += quantity;
+        level.order_count += order_count;
+        level.venues.push(VenueQuantity {
+            venue: venue.to_string(),
+            quantity,
+            order_count,
+        });
+    }
+}
+
+/// Keeps the first `levels` levels, ordering the venues of each by quantity
+fn finish(side: impl Iterator<Item = ConsolidatedLevel>, levels: usize) -> Vec<ConsolidatedLevel> {
+    side.take(levels)
+        .map(|mut level| {
+            level
+                .venues
+                .sort_by_key(|venue| std::cmp::Reverse(venue.quantity));
+            level
+        })
+        .collect()
+}
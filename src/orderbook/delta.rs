@@ -0,0 +1,470 @@
+//! Incremental price level deltas between full snapshots.
+
+use super::book::OrderBook;
+use super::book_change_event::PriceLevelChangedEvent;
+use super::error::OrderBookError;
+use super::modifications::OrderQuantity;
+use super::order_event::OrderEvent;
+use pricelevel::{OrderId, OrderType, OrderUpdate, Side, TimeInForce};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use tracing::trace;
+
+/// Change of a single visible price level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LevelChange {
+    /// A level that did not exist appeared
+    Added {
+        /// Side of the level
+        side: Side,
+        /// Price of the level
+        price: u64,
+        /// Visible quantity of the level
+        quantity: u64,
+    },
+    /// The visible quantity of an existing level changed
+    Updated {
+        /// Side of the level
+        side: Side,
+        /// Price of the level
+        price: u64,
+        /// New visible quantity of the level
+        quantity: u64,
+    },
+    /// A level disappeared
+    Removed {
+        /// Side of the level
+        side: Side,
+        /// Price of the level
+        price: u64,
+    },
+}
+
+/// Net changes of the visible price levels between two delta sequence numbers.
+///
+/// Each level appears at most once, describing how its state at `from_sequence`
+/// turned into its state at `to_sequence`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderBookDelta {
+    /// Symbol of the book the delta was taken from
+    pub symbol: String,
+    /// Sequence number the delta starts from (exclusive)
+    pub from_sequence: u64,
+    /// Sequence number the delta brings a replica up to (inclusive)
+    pub to_sequence: u64,
+    /// Net level changes, in the order the levels first changed
+    pub changes: Vec<LevelChange>,
+}
+
+impl OrderBookDelta {
+    /// Returns `true` if no level changed
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TrackedChange {
+    sequence: u64,
+    side: Side,
+    price: u64,
+    previous: u64,
+    quantity: u64,
+}
+
+/// Bounded history of visible level changes used to build deltas.
+#[derive(Debug)]
+pub(super) struct DeltaTracker {
+    capacity: usize,
+    sequence: u64,
+    /// Oldest sequence number a delta can still start from
+    base: u64,
+    history: VecDeque<TrackedChange>,
+    bids: HashMap<u64, u64>,
+    asks: HashMap<u64, u64>,
+}
+
+impl DeltaTracker {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            sequence: 0,
+            base: 0,
+            history: VecDeque::with_capacity(capacity),
+            bids: HashMap::new(),
+            asks: HashMap::new(),
+        }
+    }
+
+    fn levels(&mut self, side: Side) -> &mut HashMap<u64, u64> {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    /// Forgets the history and takes the given levels as the current state.
+    fn reset(&mut self, bids: HashMap<u64, u64>, asks: HashMap<u64, u64>) {
+        self.history.clear();
+        self.base = self.sequence;
+        self.bids = bids;
+        self.asks = asks;
+    }
+
+    pub(super) fn record(&mut self, event: PriceLevelChangedEvent) {
+        let levels = self.levels(event.side);
+        let previous = if event.quantity == 0 {
+            levels.remove(&event.price)
+        } else {
+            levels.insert(event.price, event.quantity)
+        }
+        .unwrap_or(0);
+        if previous == event.quantity {
+            return;
+        }
+
+        self.sequence += 1;
+        self.history.push_back(TrackedChange {
+            sequence: self.sequence,
+            side: event.side,
+            price: event.price,
+            previous,
+            quantity: event.quantity,
+        });
+        if self.history.len() > self.capacity
+            && let Some(evicted) = self.history.pop_front()
+        {
+            self.base = evicted.sequence;
+        }
+    }
+
+    fn delta_since(&self, sequence: u64) -> Result<Vec<LevelChange>, OrderBookError> {
+        if sequence < self.base || sequence > self.sequence {
+            return Err(OrderBookError::InvalidOperation {
+                message: format!(
+                    "Delta sequence {sequence} is outside the retained range {}..={}",
+                    self.base, self.sequence
+                ),
+            });
+        }
+
+        // (side, price, quantity at `sequence`, latest quantity)
+        let mut net: Vec<(Side, u64, u64, u64)> = Vec::new();
+        let mut index: HashMap<(bool, u64), usize> = HashMap::new();
+        for change in self.history.iter().filter(|c| c.sequence > sequence) {
+            let key = (change.side == Side::Buy, change.price);
+            match index.get(&key) {
+                Some(&i) => net[i].3 = change.quantity,
+                None => {
+                    index.insert(key, net.len());
+                    net.push((change.side, change.price, change.previous, change.quantity));
+                }
+            }
+        }
+
+        Ok(net
+            .into_iter()
+            .filter_map(|(side, price, before, after)| match (before, after) {
+                (before, after) if before == after => None,
+                (0, quantity) => Some(LevelChange::Added {
+                    side,
+                    price,
+                    quantity,
+                }),
+                (_, 0) => Some(LevelChange::Removed { side, price }),
+                (_, quantity) => Some(LevelChange::Updated {
+                    side,
+                    price,
+                    quantity,
+                }),
+            })
+            .collect())
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Start recording visible level changes so deltas can be produced
+    ///
+    /// Up to `capacity` level changes are retained. A delta can only be produced
+    /// from a sequence number whose changes are all still retained; older replicas
+    /// must be resynchronized with a full snapshot. Sequence numbers start at the
+    /// value returned by [`OrderBook::delta_sequence`] right after this call.
+    pub fn enable_delta_tracking(&mut self, capacity: usize) {
+        let mut tracker = DeltaTracker::new(capacity);
+        let (bids, asks) = self.visible_level_quantities();
+        tracker.reset(bids, asks);
+        self.delta_tracker = Some(std::sync::Mutex::new(tracker));
+    }
+
+    /// Stop recording visible level changes
+    pub fn disable_delta_tracking(&mut self) {
+        self.delta_tracker = None;
+    }
+
+    /// Sequence number of the latest recorded level change, if deltas are tracked
+    ///
+    /// Record it together with a full snapshot to know where the first delta starts.
+    #[must_use]
+    pub fn delta_sequence(&self) -> Option<u64> {
+        self.delta_tracker
+            .as_ref()
+            .map(|tracker| tracker.lock().unwrap_or_else(|e| e.into_inner()).sequence)
+    }
+
+    /// Net visible level changes since `sequence`
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::InvalidOperation` if deltas are not tracked, or if
+    /// `sequence` is older than the retained history or newer than the latest change.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{LevelChange, OrderBook};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// book.enable_delta_tracking(1_000);
+    /// let start = book.delta_sequence().unwrap();
+    ///
+    /// book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// let delta = book.delta_since(start).unwrap();
+    /// assert_eq!(
+    ///     delta.changes,
+    ///     vec![LevelChange::Added { side: Side::Buy, price: 100, quantity: 10 }]
+    /// );
+    /// ```
+    pub fn delta_since(&self, sequence: u64) -> Result<OrderBookDelta, OrderBookError> {
+        let tracker = self
+            .delta_tracker
+            .as_ref()
+            .ok_or_else(|| OrderBookError::InvalidOperation {
+                message: "Delta tracking is not enabled".to_string(),
+            })?
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        Ok(OrderBookDelta {
+            symbol: self.symbol.clone(),
+            from_sequence: sequence,
+            to_sequence: tracker.sequence,
+            changes: tracker.delta_since(sequence)?,
+        })
+    }
+
+    /// Applies a delta taken from another book to this replica
+    ///
+    /// Each changed level is replaced by a single order holding the level's visible
+    /// quantity, so the replica mirrors the aggregated depth of the source book but
+    /// not its individual orders. Levels are replaced without matching, and the
+    /// resulting level changes are reported as one consolidated batch.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::InvalidOperation` if the delta belongs to another symbol.
+    pub fn apply_delta(&self, delta: &OrderBookDelta) -> Result<(), OrderBookError> {
+        if delta.symbol != self.symbol {
+            return Err(OrderBookError::InvalidOperation {
+                message: format!(
+                    "Delta symbol {} does not match order book symbol {}",
+                    delta.symbol, self.symbol
+                ),
+            });
+        }
+
+        trace!(
+            "Order book {}: Applying delta {}..={} with {} changes",
+            self.symbol,
+            delta.from_sequence,
+            delta.to_sequence,
+            delta.changes.len()
+        );
+        self.with_batched_level_changes(|| {
+            for change in &delta.changes {
+                match *change {
+                    LevelChange::Added {
+                        side,
+                        price,
+                        quantity,
+                    }
+                    | LevelChange::Updated {
+                        side,
+                        price,
+                        quantity,
+                    } => self.replace_level(side, price, quantity),
+                    LevelChange::Removed { side, price } => self.replace_level(side, price, 0),
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Applies an aggregated depth update, e.g. from an exchange's market-by-price stream
+    ///
+    /// The visible level at `price` is replaced by a single synthetic order holding
+    /// `new_total_qty`, or removed when `new_total_qty` is zero, so analytics such as
+    /// VWAP, imbalance and market impact work on books mirrored from L2 data. The
+    /// level is replaced without matching, even if it crosses the opposite side.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::InvalidTickSize` if `price` is not a multiple of the
+    /// book's tick size.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::Side;
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// book.apply_l2_update(100, 25, Side::Buy).unwrap();
+    /// book.apply_l2_update(100, 15, Side::Buy).unwrap();
+    /// assert_eq!(book.best_bid(), Some(100));
+    /// assert_eq!(book.depth(1).0, vec![(100, 15, 1)]);
+    ///
+    /// book.apply_l2_update(100, 0, Side::Buy).unwrap();
+    /// assert_eq!(book.best_bid(), None);
+    /// ```
+    pub fn apply_l2_update(
+        &self,
+        price: u64,
+        new_total_qty: u64,
+        side: Side,
+    ) -> Result<(), OrderBookError> {
+        self.config.validate_price(price)?;
+        trace!(
+            "Order book {}: Applying L2 update {} {} -> {}",
+            self.symbol, side, price, new_total_qty
+        );
+        self.with_batched_level_changes(|| self.replace_level(side, price, new_total_qty));
+        Ok(())
+    }
+
+    /// Replaces every visible level with the given aggregated depth
+    ///
+    /// `bids` and `asks` hold `(price, quantity)` pairs; levels missing from them are
+    /// removed, and the resulting level changes are reported as one consolidated
+    /// batch. Like [`OrderBook::apply_l2_update`], levels are replaced without matching.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::InvalidTickSize` if a price is not a multiple of the
+    /// book's tick size, in which case the book is left untouched.
+    pub fn apply_l2_snapshot(
+        &self,
+        bids: &[(u64, u64)],
+        asks: &[(u64, u64)],
+    ) -> Result<(), OrderBookError> {
+        for (price, _) in bids.iter().chain(asks) {
+            self.config.validate_price(*price)?;
+        }
+
+        trace!(
+            "Order book {}: Applying L2 snapshot with {} bids and {} asks",
+            self.symbol,
+            bids.len(),
+            asks.len()
+        );
+        self.with_batched_level_changes(|| {
+            for (side, levels) in [(Side::Buy, bids), (Side::Sell, asks)] {
+                let kept: HashSet<u64> = levels.iter().map(|(price, _)| *price).collect();
+                let stale: Vec<u64> = self
+                    .side_levels(side, false)
+                    .iter()
+                    .map(|entry| *entry.key())
+                    .filter(|price| !kept.contains(price))
+                    .collect();
+                for price in stale {
+                    self.replace_level(side, price, 0);
+                }
+                for (price, quantity) in levels {
+                    self.replace_level(side, *price, *quantity);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Replaces the visible level at `price` with one order of `quantity`, or removes
+    /// it when `quantity` is zero or the level does not fit within the depth limit.
+    /// Orders resting at the level are reported as cancelled.
+    fn replace_level(&self, side: Side, price: u64, quantity: u64) {
+        let price_levels = self.side_levels(side, false);
+        if let Some(entry) = price_levels.remove(&price) {
+            let level = entry.value();
+            self.totals.removed(side, level.total_quantity());
+            for order in level.iter_orders() {
+                self.emit_order_event(OrderEvent::Cancelled {
+                    order_id: order.id(),
+                    quantity: order.total_quantity(),
+                });
+                let _ = level.update_order(OrderUpdate::Cancel {
+                    order_id: order.id(),
+                });
+                self.forget_order(&order.id());
+            }
+            self.unindex_level(side, price);
+            self.level_pool.release(Arc::clone(level));
+        }
+
+        if quantity == 0 || self.admit_level(side, price).is_err() {
+            self.publish_level_change(PriceLevelChangedEvent {
+                side,
+                price,
+                quantity: 0,
+            });
+            return;
+        }
+
+        let level = self.level_pool.acquire(price);
+        let order = level.add_order(OrderType::Standard {
+            id: OrderId::new(),
+            price,
+            quantity,
+            side,
+            timestamp: self.clock.now_millis(),
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        });
+        price_levels.insert(price, Arc::clone(&level));
+        self.totals.added(side, quantity);
+        self.index_level(side, price, &level);
+        self.order_locations.insert(order.id(), (price, side));
+        self.notify_price_level_changed(side, &level);
+        self.evict_excess_levels(side);
+    }
+
+    /// Records a delivered level change in the delta history, if deltas are tracked.
+    pub(super) fn track_level_change(&self, event: PriceLevelChangedEvent) {
+        if let Some(ref tracker) = self.delta_tracker {
+            tracker
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .record(event);
+        }
+    }
+
+    /// Takes the current visible levels as the base of the delta history, discarding
+    /// the changes recorded so far.
+    pub(super) fn reset_delta_tracking(&self) {
+        if let Some(ref tracker) = self.delta_tracker {
+            let (bids, asks) = self.visible_level_quantities();
+            tracker
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .reset(bids, asks);
+        }
+    }
+
+    fn visible_level_quantities(&self) -> (HashMap<u64, u64>, HashMap<u64, u64>) {
+        let quantities = |side: Side| {
+            self.side_levels(side, false)
+                .iter()
+                .map(|entry| (*entry.key(), entry.value().visible_quantity()))
+                .filter(|(_, quantity)| *quantity > 0)
+                .collect()
+        };
+        (quantities(Side::Buy), quantities(Side::Sell))
+    }
+}
@@ -0,0 +1,329 @@
+//! Sums over packed price level depth, shared by the depth statistics and the
+//! depth distribution.
+//!
+//! Levels are first copied into a per-thread scratch buffer of packed prices
+//! and quantities, so every sum runs over contiguous slices instead of the
+//! nodes of a skip list. With the `simd` feature the sums run over fixed-width
+//! lanes that stable rustc compiles to vector instructions, falling back to the
+//! scalar loops for short runs and whenever a lane sum could overflow. Both
+//! paths give the same integer results; the lane path adds the squared
+//! deviations in a different order, which may change the last bits of a
+//! standard deviation.
+
+use crossbeam_skiplist::map::Entry;
+use pricelevel::PriceLevel;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+thread_local! {
+    /// Scratch buffer reused by the depth analytics running on this thread.
+    static SCRATCH: RefCell<PackedLevels> = const { RefCell::new(PackedLevels::new()) };
+}
+
+/// Prices and quantities of a run of price levels, packed side by side.
+#[derive(Debug, Default)]
+pub(super) struct PackedLevels {
+    pub prices: Vec<u64>,
+    pub quantities: Vec<u64>,
+}
+
+impl PackedLevels {
+    const fn new() -> Self {
+        Self {
+            prices: Vec::new(),
+            quantities: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, entry: &Entry<'_, u64, Arc<PriceLevel>>) {
+        self.prices.push(*entry.key());
+        self.quantities.push(entry.value().total_quantity());
+    }
+
+    pub fn len(&self) -> usize {
+        self.prices.len()
+    }
+
+    fn clear(&mut self) {
+        self.prices.clear();
+        self.quantities.clear();
+    }
+}
+
+/// Runs `f` with the cleared scratch buffer of this thread, or a fresh one if
+/// it is already in use.
+pub(super) fn with_scratch<R>(f: impl FnOnce(&mut PackedLevels) -> R) -> R {
+    SCRATCH.with(|scratch| match scratch.try_borrow_mut() {
+        Ok(mut packed) => {
+            packed.clear();
+            f(&mut packed)
+        }
+        Err(_) => f(&mut PackedLevels::default()),
+    })
+}
+
+/// Sums of a run of price levels, saturating at `u64::MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct DepthSums {
+    pub volume: u64,
+    pub weighted_price: u64,
+    pub min_quantity: u64,
+    pub max_quantity: u64,
+}
+
+/// Volume, price-weighted volume and smallest and largest quantity of the
+/// levels in `prices` and `quantities`.
+pub(super) fn depth_sums(prices: &[u64], quantities: &[u64]) -> DepthSums {
+    #[cfg(feature = "simd")]
+    if quantities.len() >= lanes::MIN_LEN
+        && let Some(sums) = lanes::depth_sums(prices, quantities)
+    {
+        return sums;
+    }
+    scalar::depth_sums(prices, quantities)
+}
+
+/// Total of `quantities`, saturating at `u64::MAX`.
+pub(super) fn volume(quantities: &[u64]) -> u64 {
+    #[cfg(feature = "simd")]
+    if quantities.len() >= lanes::MIN_LEN
+        && let Some(volume) = lanes::volume(quantities)
+    {
+        return volume;
+    }
+    scalar::volume(quantities)
+}
+
+/// Number of non-zero `quantities`.
+pub(super) fn non_zero_count(quantities: &[u64]) -> usize {
+    #[cfg(feature = "simd")]
+    if quantities.len() >= lanes::MIN_LEN {
+        return lanes::non_zero_count(quantities);
+    }
+    scalar::non_zero_count(quantities)
+}
+
+/// Sum of the squared differences between `quantities` and `mean`.
+pub(super) fn squared_deviation(quantities: &[u64], mean: f64) -> f64 {
+    #[cfg(feature = "simd")]
+    if quantities.len() >= lanes::MIN_LEN {
+        return lanes::squared_deviation(quantities, mean);
+    }
+    scalar::squared_deviation(quantities, mean)
+}
+
+mod scalar {
+    use super::DepthSums;
+
+    pub fn depth_sums(prices: &[u64], quantities: &[u64]) -> DepthSums {
+        let mut sums = DepthSums {
+            volume: 0,
+            weighted_price: 0,
+            min_quantity: u64::MAX,
+            max_quantity: 0,
+        };
+        for (&price, &quantity) in prices.iter().zip(quantities) {
+            sums.volume = sums.volume.saturating_add(quantity);
+            sums.weighted_price = sums
+                .weighted_price
+                .saturating_add(price.saturating_mul(quantity));
+            sums.min_quantity = sums.min_quantity.min(quantity);
+            sums.max_quantity = sums.max_quantity.max(quantity);
+        }
+        sums
+    }
+
+    pub fn volume(quantities: &[u64]) -> u64 {
+        quantities
+            .iter()
+            .fold(0u64, |sum, &q| sum.saturating_add(q))
+    }
+
+    pub fn non_zero_count(quantities: &[u64]) -> usize {
+        quantities.iter().filter(|&&q| q > 0).count()
+    }
+
+    pub fn squared_deviation(quantities: &[u64], mean: f64) -> f64 {
+        quantities
+            .iter()
+            .map(|&q| {
+                let diff = q as f64 - mean;
+                diff * diff
+            })
+            .sum()
+    }
+}
+
+#[cfg(feature = "simd")]
+mod lanes {
+    use super::DepthSums;
+
+    /// Values summed side by side, enough to fill a 512-bit vector of `u64`.
+    const LANES: usize = 8;
+
+    /// Shortest run worth splitting into lanes; shorter runs cost more to
+    /// set up and reduce than the scalar loops.
+    pub const MIN_LEN: usize = 4 * LANES;
+
+    /// Adds the lanes of `lanes`, `None` if the total overflows.
+    fn reduce(lanes: [u64; LANES]) -> Option<u64> {
+        lanes
+            .iter()
+            .try_fold(0u64, |sum, &lane| sum.checked_add(lane))
+    }
+
+    /// Sums of the levels, `None` if a product or a sum could overflow and
+    /// the saturating scalar sums are needed.
+    pub fn depth_sums(prices: &[u64], quantities: &[u64]) -> Option<DepthSums> {
+        let mut volume = [0u64; LANES];
+        let mut weighted = [0u64; LANES];
+        let mut overflow = [false; LANES];
+        let mut min = [u64::MAX; LANES];
+        let mut max = [0u64; LANES];
+        let mut max_price = [0u64; LANES];
+
+        let price_chunks = prices.chunks_exact(LANES);
+        let quantity_chunks = quantities.chunks_exact(LANES);
+        let (price_rest, quantity_rest) = (price_chunks.remainder(), quantity_chunks.remainder());
+        for (p, q) in price_chunks.zip(quantity_chunks) {
+            for i in 0..LANES {
+                let (v, volume_overflow) = volume[i].overflowing_add(q[i]);
+                let (w, weighted_overflow) = weighted[i].overflowing_add(p[i].wrapping_mul(q[i]));
+                volume[i] = v;
+                weighted[i] = w;
+                overflow[i] |= volume_overflow | weighted_overflow;
+                min[i] = min[i].min(q[i]);
+                max[i] = max[i].max(q[i]);
+                max_price[i] = max_price[i].max(p[i]);
+            }
+        }
+        for (i, (&p, &q)) in price_rest.iter().zip(quantity_rest).enumerate() {
+            let (v, volume_overflow) = volume[i].overflowing_add(q);
+            let (w, weighted_overflow) = weighted[i].overflowing_add(p.wrapping_mul(q));
+            volume[i] = v;
+            weighted[i] = w;
+            overflow[i] |= volume_overflow | weighted_overflow;
+            min[i] = min[i].min(q);
+            max[i] = max[i].max(q);
+            max_price[i] = max_price[i].max(p);
+        }
+
+        let max_quantity = max.into_iter().max().unwrap_or(0);
+        let max_price = max_price.into_iter().max().unwrap_or(0);
+        // Every product fits in 64 bits when the largest price and quantity do
+        let products_fit = max_price.leading_zeros() + max_quantity.leading_zeros() >= 64;
+        if overflow.contains(&true) || !products_fit {
+            return None;
+        }
+        Some(DepthSums {
+            volume: reduce(volume)?,
+            weighted_price: reduce(weighted)?,
+            min_quantity: min.into_iter().min().unwrap_or(u64::MAX),
+            max_quantity,
+        })
+    }
+
+    pub fn volume(quantities: &[u64]) -> Option<u64> {
+        let mut volume = [0u64; LANES];
+        let mut overflow = [false; LANES];
+        let chunks = quantities.chunks_exact(LANES);
+        let rest = chunks.remainder();
+        for q in chunks {
+            for i in 0..LANES {
+                let (v, o) = volume[i].overflowing_add(q[i]);
+                volume[i] = v;
+                overflow[i] |= o;
+            }
+        }
+        for (i, &q) in rest.iter().enumerate() {
+            let (v, o) = volume[i].overflowing_add(q);
+            volume[i] = v;
+            overflow[i] |= o;
+        }
+        if overflow.contains(&true) {
+            return None;
+        }
+        reduce(volume)
+    }
+
+    pub fn non_zero_count(quantities: &[u64]) -> usize {
+        let mut count = [0usize; LANES];
+        let chunks = quantities.chunks_exact(LANES);
+        let rest = chunks.remainder();
+        for q in chunks {
+            for i in 0..LANES {
+                count[i] += usize::from(q[i] > 0);
+            }
+        }
+        count.iter().sum::<usize>() + rest.iter().filter(|&&q| q > 0).count()
+    }
+
+    pub fn squared_deviation(quantities: &[u64], mean: f64) -> f64 {
+        let mut sum = [0f64; LANES];
+        let chunks = quantities.chunks_exact(LANES);
+        let rest = chunks.remainder();
+        for q in chunks {
+            for i in 0..LANES {
+                let diff = q[i] as f64 - mean;
+                sum[i] += diff * diff;
+            }
+        }
+        for (i, &q) in rest.iter().enumerate() {
+            let diff = q as f64 - mean;
+            sum[i] += diff * diff;
+        }
+        sum.iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packed(levels: usize) -> (Vec<u64>, Vec<u64>) {
+        let prices = (0..levels as u64).map(|i| 10_000 + i * 5).collect();
+        let quantities = (0..levels as u64).map(|i| (i * 37) % 101).collect();
+        (prices, quantities)
+    }
+
+    #[test]
+    fn test_kernels_match_scalar_loops() {
+        for levels in [0, 1, 7, 8, 9, 100, 1_003] {
+            let (prices, quantities) = packed(levels);
+            assert_eq!(
+                depth_sums(&prices, &quantities),
+                scalar::depth_sums(&prices, &quantities)
+            );
+            assert_eq!(volume(&quantities), scalar::volume(&quantities));
+            assert_eq!(
+                non_zero_count(&quantities),
+                scalar::non_zero_count(&quantities)
+            );
+            let mean = 50.0;
+            let deviation = squared_deviation(&quantities, mean);
+            let expected = scalar::squared_deviation(&quantities, mean);
+            assert!((deviation - expected).abs() <= expected * 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_kernels_saturate_like_scalar_loops() {
+        let prices = vec![u64::MAX / 2; 40];
+        let quantities = vec![u64::MAX / 4; 40];
+        let sums = depth_sums(&prices, &quantities);
+        assert_eq!(sums.volume, u64::MAX);
+        assert_eq!(sums.weighted_price, u64::MAX);
+        assert_eq!(sums.min_quantity, u64::MAX / 4);
+        assert_eq!(volume(&quantities), u64::MAX);
+    }
+
+    #[test]
+    fn test_scratch_is_cleared_and_reentrant() {
+        with_scratch(|outer| {
+            outer.prices.push(1);
+            outer.quantities.push(1);
+            with_scratch(|inner| assert_eq!(inner.len(), 0));
+        });
+        with_scratch(|packed| assert_eq!(packed.len(), 0));
+    }
+}
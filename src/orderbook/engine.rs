@@ -0,0 +1,528 @@
+//! Engine mode funnelling every mutation of a book through one matching thread.
+//!
+//! Threads adding to, cancelling from and matching against the same hot price
+//! levels contend on the atomics and queues of those levels. A
+//! [`MatchingEngine`] instead owns a matching thread that applies the commands
+//! of every producer one at a time. Producers push commands through an
+//! [`EngineHandle`] into a bounded lock-free queue and get a [`Receipt`], or a
+//! future with the `tokio` feature, for the result. A command that panics is
+//! reported through its receipt and the matching thread moves on to the next
+//! one. Readers keep querying the shared book directly, without going through
+//! the queue.
+
+use crate::orderbook::OrderBook;
+use crate::orderbook::error::OrderBookError;
+use pricelevel::{MatchResult, OrderId, OrderType, OrderUpdate, Side, TimeInForce};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::JoinHandle;
+use tracing::{error, info};
+
+/// Default number of commands the engine queue buffers before producers wait.
+pub const ENGINE_QUEUE_CAPACITY: usize = 65_536;
+
+/// Work run by the matching thread against the book.
+type EngineJob<T> = Box<dyn FnOnce(&OrderBook<T>) + Send>;
+
+/// A command sent to the matching thread.
+enum EngineCommand<T> {
+    /// Run a job against the book
+    Execute(EngineJob<T>),
+    /// Stop once the commands queued before this one were handled
+    Stop,
+}
+
+/// Result of a command submitted to a [`MatchingEngine`], available once the
+/// matching thread applied it.
+#[derive(Debug)]
+pub struct Receipt<R> {
+    symbol: Arc<str>,
+    result: Receiver<Result<R, OrderBookError>>,
+}
+
+impl<R> Receipt<R> {
+    /// Blocks until the command was applied and returns its result
+    ///
+    /// # Errors
+    /// Returns the error of the command, `OrderBookError::CommandPanicked` if it
+    /// panicked, or `OrderBookError::BookStopped` if the engine stopped before
+    /// applying it.
+    pub fn wait(self) -> Result<R, OrderBookError> {
+        self.result
+            .recv()
+            .unwrap_or_else(|_| Err(stopped(&self.symbol)))
+    }
+}
+
+/// Cloneable handle submitting commands to the matching thread of a
+/// [`MatchingEngine`].
+///
+/// Submitting waits for room in the engine queue, so a busy engine applies
+/// backpressure to its producers. Commands submitted through one handle are
+/// applied in the order they were submitted.
+pub struct EngineHandle<T> {
+    symbol: Arc<str>,
+    sender: SyncSender<EngineCommand<T>>,
+}
+
+impl<T> Clone for EngineHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            symbol: Arc::clone(&self.symbol),
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for EngineHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EngineHandle")
+            .field("symbol", &self.symbol)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> EngineHandle<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Symbol of the book
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Queues `job` for the matching thread and returns a receipt for its result
+    ///
+    /// The job runs alone against the book, so it must not block: a slow job
+    /// delays every command queued behind it.
+    pub fn submit<R, F>(&self, job: F) -> Receipt<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&OrderBook<T>) -> R + Send + 'static,
+    {
+        self.request(move |book| Ok(job(book)))
+    }
+
+    /// Queues `job` for the matching thread without waiting for it to run
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::BookStopped` if the engine stopped.
+    pub fn dispatch<F>(&self, job: F) -> Result<(), OrderBookError>
+    where
+        F: FnOnce(&OrderBook<T>) + Send + 'static,
+    {
+        self.sender
+            .send(EngineCommand::Execute(Box::new(job)))
+            .map_err(|_| stopped(&self.symbol))
+    }
+
+    /// Adds a limit order to the book, see [`OrderBook::add_limit_order`]
+    pub fn add_limit_order(
+        &self,
+        id: OrderId,
+        price: u64,
+        quantity: u64,
+        side: Side,
+        time_in_force: TimeInForce,
+        extra_fields: Option<T>,
+    ) -> Receipt<Arc<OrderType<T>>> {
+        self.request(move |book| {
+            book.add_limit_order(id, price, quantity, side, time_in_force, extra_fields)
+        })
+    }
+
+    /// Matches a market order against the book, see [`OrderBook::submit_market_order`]
+    pub fn submit_market_order(
+        &self,
+        id: OrderId,
+        quantity: u64,
+        side: Side,
+    ) -> Receipt<MatchResult> {
+        self.request(move |book| book.submit_market_order(id, quantity, side))
+    }
+
+    /// Cancels an order of the book, see [`OrderBook::cancel_order`]
+    pub fn cancel_order(&self, order_id: OrderId) -> Receipt<Option<Arc<OrderType<T>>>> {
+        self.request(move |book| book.cancel_order(order_id))
+    }
+
+    /// Updates an order of the book, see [`OrderBook::update_order`]
+    pub fn update_order(&self, update: OrderUpdate) -> Receipt<Option<Arc<OrderType<T>>>> {
+        self.request(move |book| book.update_order(update))
+    }
+
+    /// Runs `job` on the matching thread and awaits its result
+    ///
+    /// Waiting for room in the queue yields to the runtime instead of blocking
+    /// its worker thread.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::CommandPanicked` if `job` panicked, or
+    /// `OrderBookError::BookStopped` if the engine stopped.
+    #[cfg(feature = "tokio")]
+    pub async fn submit_async<R, F>(&self, job: F) -> Result<R, OrderBookError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&OrderBook<T>) -> R + Send + 'static,
+    {
+        let (reply, result) = tokio::sync::oneshot::channel();
+        let symbol = Arc::clone(&self.symbol);
+        let mut command = EngineCommand::Execute(Box::new(move |book: &OrderBook<T>| {
+            // The caller may have stopped waiting for the result
+            let _ = reply.send(guarded(&symbol, || Ok(job(book))));
+        }));
+        loop {
+            match self.sender.try_send(command) {
+                Ok(()) => break,
+                Err(mpsc::TrySendError::Full(rejected)) => {
+                    command = rejected;
+                    tokio::task::yield_now().await;
+                }
+                Err(mpsc::TrySendError::Disconnected(_)) => return Err(stopped(&self.symbol)),
+            }
+        }
+        result.await.unwrap_or_else(|_| Err(stopped(&self.symbol)))
+    }
+
+    fn request<R, F>(&self, job: F) -> Receipt<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&OrderBook<T>) -> Result<R, OrderBookError> + Send + 'static,
+    {
+        let (reply, result) = mpsc::sync_channel(1);
+        let symbol = Arc::clone(&self.symbol);
+        // A stopped engine drops the job, and the receipt then reports it
+        let _ = self.dispatch(move |book| {
+            let _ = reply.send(guarded(&symbol, || job(book)));
+        });
+        Receipt {
+            symbol: Arc::clone(&self.symbol),
+            result,
+        }
+    }
+}
+
+/// A book whose mutations are all applied by a single matching thread.
+///
+/// The book is shared: [`MatchingEngine::book`] hands it to readers, whose
+/// queries never wait for the matching thread. Mutating it other than through
+/// an [`EngineHandle`] gives up the single writer and the ordering of commands.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::OrderBook;
+/// use pricelevel::{OrderId, Side, TimeInForce};
+///
+/// let engine = OrderBook::<()>::new("BTC/USD").into_engine();
+/// let handle = engine.handle();
+///
+/// let resting = handle.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+/// let taker = handle.submit_market_order(OrderId::new(), 4, Side::Buy);
+/// assert!(resting.wait().is_ok());
+/// assert_eq!(taker.wait().unwrap().executed_quantity(), 4);
+///
+/// // Readers query the book directly
+/// assert_eq!(engine.book().best_ask(), Some(100));
+/// let book = engine.shutdown();
+/// assert_eq!(book.total_depth_at_levels(1, Side::Sell), 6);
+/// ```
+pub struct MatchingEngine<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// The book, shared with readers
+    book: Arc<OrderBook<T>>,
+    /// Handle kept to stop the matching thread
+    handle: EngineHandle<T>,
+    /// Matching thread, until stopped
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<T> MatchingEngine<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Starts a matching thread for `book` with a queue of
+    /// [`ENGINE_QUEUE_CAPACITY`] commands
+    pub fn new(book: OrderBook<T>) -> Self {
+        Self::with_capacity(book, ENGINE_QUEUE_CAPACITY)
+    }
+
+    /// Starts a matching thread for `book` with a queue of `capacity` commands
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero.
+    pub fn with_capacity(book: OrderBook<T>, capacity: usize) -> Self {
+        assert!(capacity > 0, "engine queue capacity must be positive");
+        let book = Arc::new(book);
+        let symbol: Arc<str> = Arc::from(book.symbol());
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let matching_book = Arc::clone(&book);
+        let thread = std::thread::Builder::new()
+            .name(format!("orderbook-engine-{symbol}"))
+            .spawn(move || Self::run(matching_book, receiver))
+            .expect("failed to spawn matching thread");
+        info!("Started matching engine for symbol: {}", symbol);
+        Self {
+            book,
+            handle: EngineHandle { symbol, sender },
+            thread: Some(thread),
+        }
+    }
+
+    /// The book, for queries that need not go through the matching thread
+    pub fn book(&self) -> &Arc<OrderBook<T>> {
+        &self.book
+    }
+
+    /// A handle submitting commands to the matching thread
+    pub fn handle(&self) -> EngineHandle<T> {
+        self.handle.clone()
+    }
+
+    /// Stops the matching thread and returns the book
+    ///
+    /// Every command submitted before the call is applied first; later commands
+    /// fail with `OrderBookError::BookStopped`.
+    pub fn shutdown(mut self) -> Arc<OrderBook<T>> {
+        self.stop();
+        info!("Matching engine for {} shut down", self.handle.symbol);
+        Arc::clone(&self.book)
+    }
+
+    fn stop(&mut self) {
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+        // A closed queue means the thread already ended
+        let _ = self.handle.sender.send(EngineCommand::Stop);
+        if thread.join().is_err() {
+            error!("Matching engine for {} panicked", self.handle.symbol);
+        }
+    }
+
+    /// Applies commands until stopped
+    ///
+    /// Jobs with a receipt report their own panics; a panic of a dispatched
+    /// job is logged, and either way the next command is applied.
+    fn run(book: Arc<OrderBook<T>>, receiver: Receiver<EngineCommand<T>>) {
+        while let Ok(command) = receiver.recv() {
+            match command {
+                EngineCommand::Execute(job) => {
+                    if let Err(panic) = panic::catch_unwind(AssertUnwindSafe(|| job(&book))) {
+                        error!(
+                            "Command for {} panicked: {}",
+                            book.symbol(),
+                            panic_message(panic.as_ref())
+                        );
+                    }
+                }
+                EngineCommand::Stop => break,
+            }
+        }
+    }
+}
+
+impl<T> Drop for MatchingEngine<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Moves the book into engine mode, where a single matching thread applies
+    /// every mutation, see [`MatchingEngine`]
+    pub fn into_engine(self) -> MatchingEngine<T> {
+        MatchingEngine::new(self)
+    }
+}
+
+fn stopped(symbol: &str) -> OrderBookError {
+    OrderBookError::BookStopped {
+        symbol: symbol.to_string(),
+    }
+}
+
+/// Runs `job`, turning a panic into `OrderBookError::CommandPanicked`.
+fn guarded<R>(
+    symbol: &str,
+    job: impl FnOnce() -> Result<R, OrderBookError>,
+) -> Result<R, OrderBookError> {
+    panic::catch_unwind(AssertUnwindSafe(job)).unwrap_or_else(|panic| {
+        Err(OrderBookError::CommandPanicked {
+            symbol: symbol.to_string(),
+            message: panic_message(panic.as_ref()),
+        })
+    })
+}
+
+/// Text of a panic payload, when it carries one.
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engine_applies_commands_of_every_producer() {
+        let engine = MatchingEngine::with_capacity(OrderBook::<()>::new("TEST"), 16);
+        let producers: Vec<_> = (0..4u64)
+            .map(|producer| {
+                let handle = engine.handle();
+                std::thread::spawn(move || {
+                    (0..50u64)
+                        .map(|step| {
+                            let price = 100 + producer * 100 + step;
+                            handle.add_limit_order(
+                                OrderId::new(),
+                                price,
+                                1,
+                                Side::Buy,
+                                TimeInForce::Gtc,
+                                None,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for producer in producers {
+            for receipt in producer.join().unwrap() {
+                assert!(receipt.wait().is_ok());
+            }
+        }
+
+        assert_eq!(engine.book().level_count(Side::Buy), 200);
+        let taker = engine
+            .handle()
+            .submit_market_order(OrderId::new(), 3, Side::Sell)
+            .wait()
+            .unwrap();
+        assert_eq!(taker.transactions.as_vec()[0].price, 449);
+    }
+
+    #[test]
+    fn test_engine_preserves_submission_order() {
+        let engine = OrderBook::<()>::new("TEST").into_engine();
+        let handle = engine.handle();
+        let id = OrderId::new();
+
+        handle.add_limit_order(id, 100, 10, Side::Sell, TimeInForce::Gtc, None);
+        handle.update_order(OrderUpdate::UpdateQuantity {
+            order_id: id,
+            new_quantity: 4,
+        });
+        let depth = handle.submit(|book| book.total_depth_at_levels(1, Side::Sell));
+        let cancelled = handle.cancel_order(id);
+
+        assert_eq!(depth.wait().unwrap(), 4);
+        assert!(cancelled.wait().unwrap().is_some());
+        assert_eq!(engine.book().best_ask(), None);
+
+        let (sender, receiver) = mpsc::channel();
+        handle
+            .dispatch(move |book| {
+                let _ = sender.send(book.symbol().to_string());
+            })
+            .unwrap();
+        assert_eq!(receiver.recv().unwrap(), "TEST");
+    }
+
+    #[test]
+    fn test_engine_reports_errors_and_shutdown() {
+        let engine = OrderBook::<()>::new("TEST").into_engine();
+        let handle = engine.handle();
+
+        let rejected = handle.submit_market_order(OrderId::new(), 5, Side::Buy);
+        assert!(matches!(
+            rejected.wait(),
+            Err(OrderBookError::InsufficientLiquidity { .. })
+        ));
+
+        handle.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+        let book = engine.shutdown();
+        // Commands queued before the shutdown were applied
+        assert_eq!(book.best_bid(), Some(100));
+
+        assert!(matches!(
+            handle.cancel_order(OrderId::new()).wait(),
+            Err(OrderBookError::BookStopped { .. })
+        ));
+        assert!(handle.dispatch(|_| ()).is_err());
+    }
+
+    #[test]
+    fn test_engine_survives_panicking_commands() {
+        let engine = OrderBook::<()>::new("TEST").into_engine();
+        let handle = engine.handle();
+
+        let panicked = handle.submit(|_| -> u64 { panic!("job failed") });
+        handle
+            .dispatch(|_| panic!("dispatched job failed"))
+            .unwrap();
+        let resting =
+            handle.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+
+        assert!(matches!(
+            panicked.wait(),
+            Err(OrderBookError::CommandPanicked { message, .. }) if message == "job failed"
+        ));
+        assert!(resting.wait().is_ok());
+        assert_eq!(engine.book().best_ask(), Some(100));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_engine_async_submission() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let engine = MatchingEngine::with_capacity(OrderBook::<()>::new("TEST"), 1);
+        let handle = engine.handle();
+
+        runtime.block_on(async {
+            for price in [100, 101, 102] {
+                handle
+                    .submit_async(move |book| {
+                        book.add_limit_order(
+                            OrderId::new(),
+                            price,
+                            5,
+                            Side::Sell,
+                            TimeInForce::Gtc,
+                            None,
+                        )
+                    })
+                    .await
+                    .unwrap()
+                    .unwrap();
+            }
+            let best = handle.submit_async(|book| book.best_ask()).await.unwrap();
+            assert_eq!(best, Some(100));
+
+            let panicked = handle.submit_async(|_| -> u64 { panic!("job failed") });
+            assert!(matches!(
+                panicked.await,
+                Err(OrderBookError::CommandPanicked { .. })
+            ));
+        });
+
+        drop(engine);
+        let stopped = runtime.block_on(handle.submit_async(|book| book.best_ask()));
+        assert!(matches!(stopped, Err(OrderBookError::BookStopped { .. })));
+    }
+}
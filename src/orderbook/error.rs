@@ -1,6 +1,8 @@
 //! Order book error types
 
-use pricelevel::{PriceLevelError, Side};
+use super::owner::OwnerId;
+use super::session::SessionState;
+use pricelevel::{OrderId, PriceLevelError, Side};
 use std::fmt;
 
 /// Errors that can occur within the OrderBook
@@ -53,6 +55,134 @@ pub enum OrderBookError {
         message: String,
     },
 
+    /// An order with the same ID is already resting in the book
+    DuplicateOrderId {
+        /// The duplicated order ID
+        order_id: OrderId,
+    },
+
+    /// Price is not a multiple of the book's tick size
+    InvalidTickSize {
+        /// The rejected price
+        price: u64,
+        /// The book's tick size
+        tick_size: u64,
+    },
+
+    /// Price lies outside the book's price band
+    PriceOutOfBand {
+        /// The rejected price
+        price: u64,
+        /// Lowest accepted price, if bounded
+        min_price: Option<u64>,
+        /// Highest accepted price, if bounded
+        max_price: Option<u64>,
+    },
+
+    /// Quantity is not a multiple of the book's lot size
+    InvalidLotSize {
+        /// The rejected quantity
+        quantity: u64,
+        /// The book's lot size
+        lot_size: u64,
+    },
+
+    /// Order value (price * quantity) is below the book's minimum notional
+    BelowMinNotional {
+        /// Notional value of the rejected order
+        notional: u128,
+        /// The book's minimum notional
+        min_notional: u64,
+    },
+
+    /// The order would rest at a price level beyond the book's depth limit
+    DepthLimitExceeded {
+        /// Side of the order
+        side: Side,
+        /// Price of the level the order would open
+        price: u64,
+        /// Most visible price levels a side keeps
+        max_levels: usize,
+    },
+
+    /// Error while appending to or reading from a journal
+    JournalError {
+        /// Underlying error message
+        message: String,
+    },
+
+    /// Error while writing to or reading from a snapshot store
+    SnapshotStoreError {
+        /// Underlying error message
+        message: String,
+    },
+
+    /// Error while writing a recording of trades or book states
+    RecordingError {
+        /// Underlying error message
+        message: String,
+    },
+
+    /// The task owning a book stopped before handling a command
+    BookStopped {
+        /// Symbol of the book
+        symbol: String,
+    },
+
+    /// A command panicked while the thread owning a book applied it
+    CommandPanicked {
+        /// Symbol of the book
+        symbol: String,
+        /// Message of the panic
+        message: String,
+    },
+
+    /// The book cannot move between the two session states
+    InvalidSessionTransition {
+        /// Current session state
+        from: SessionState,
+        /// Requested session state
+        to: SessionState,
+    },
+
+    /// The order is not allowed in the current session state
+    SessionRejected {
+        /// Session state of the book
+        state: SessionState,
+        /// Why the order was rejected
+        reason: String,
+    },
+
+    /// Trading is disabled for the whole book, or for the owner of the order
+    TradingDisabled {
+        /// Owner whose trading is disabled, or `None` if the whole book is disabled
+        owner: Option<OwnerId>,
+    },
+
+    /// The owner sent more requests than its rate limit allows
+    RateLimited {
+        /// Owner whose request was rejected
+        owner: OwnerId,
+        /// Milliseconds to wait before the owner may send another request
+        retry_after_ms: u64,
+    },
+
+    /// A pre-trade risk check rejected the order
+    RiskRejected {
+        /// ID of the rejected order
+        order_id: OrderId,
+        /// Why the risk check rejected the order
+        reason: String,
+    },
+
+    /// A feed message skipped one or more sequence numbers
+    SequenceGap {
+        /// Sequence number that was expected next
+        expected: u64,
+        /// Sequence number that was received
+        received: u64,
+    },
+
     /// Snapshot integrity check failed
     ChecksumMismatch {
         /// Expected checksum value
@@ -97,6 +227,98 @@ impl fmt::Display for OrderBookError {
             OrderBookError::DeserializationError { message } => {
                 write!(f, "Deserialization error: {message}")
             }
+            OrderBookError::DuplicateOrderId { order_id } => {
+                write!(f, "Duplicate order ID: {order_id} is already in the book")
+            }
+            OrderBookError::InvalidTickSize { price, tick_size } => {
+                write!(
+                    f,
+                    "Invalid tick size: price {price} is not a multiple of {tick_size}"
+                )
+            }
+            OrderBookError::PriceOutOfBand {
+                price,
+                min_price,
+                max_price,
+            } => {
+                let bound = |limit: &Option<u64>| limit.map_or("-".to_string(), |l| l.to_string());
+                write!(
+                    f,
+                    "Price {price} is outside the price band [{}, {}]",
+                    bound(min_price),
+                    bound(max_price)
+                )
+            }
+            OrderBookError::InvalidLotSize { quantity, lot_size } => {
+                write!(
+                    f,
+                    "Invalid lot size: quantity {quantity} is not a multiple of {lot_size}"
+                )
+            }
+            OrderBookError::BelowMinNotional {
+                notional,
+                min_notional,
+            } => {
+                write!(
+                    f,
+                    "Order notional {notional} is below the minimum of {min_notional}"
+                )
+            }
+            OrderBookError::DepthLimitExceeded {
+                side,
+                price,
+                max_levels,
+            } => {
+                write!(
+                    f,
+                    "Price {price} would open a {side} level beyond the depth limit of {max_levels} levels"
+                )
+            }
+            OrderBookError::JournalError { message } => {
+                write!(f, "Journal error: {message}")
+            }
+            OrderBookError::SnapshotStoreError { message } => {
+                write!(f, "Snapshot store error: {message}")
+            }
+            OrderBookError::RecordingError { message } => {
+                write!(f, "Recording error: {message}")
+            }
+            OrderBookError::BookStopped { symbol } => {
+                write!(f, "Book task for {symbol} has stopped")
+            }
+            OrderBookError::CommandPanicked { symbol, message } => {
+                write!(f, "Command for {symbol} panicked: {message}")
+            }
+            OrderBookError::InvalidSessionTransition { from, to } => {
+                write!(f, "Invalid session transition from {from} to {to}")
+            }
+            OrderBookError::SessionRejected { state, reason } => {
+                write!(f, "Order rejected in {state} session: {reason}")
+            }
+            OrderBookError::TradingDisabled { owner: None } => {
+                write!(f, "Trading is disabled")
+            }
+            OrderBookError::TradingDisabled { owner: Some(owner) } => {
+                write!(f, "Trading is disabled for {owner}")
+            }
+            OrderBookError::RateLimited {
+                owner,
+                retry_after_ms,
+            } => {
+                write!(
+                    f,
+                    "Rate limit exceeded for {owner}, retry after {retry_after_ms} ms"
+                )
+            }
+            OrderBookError::RiskRejected { order_id, reason } => {
+                write!(f, "Order {order_id} rejected by risk check: {reason}")
+            }
+            OrderBookError::SequenceGap { expected, received } => {
+                write!(
+                    f,
+                    "Sequence gap: expected {expected}, but received {received}"
+                )
+            }
             OrderBookError::ChecksumMismatch { expected, actual } => {
                 write!(
                     f,
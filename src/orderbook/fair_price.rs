@@ -0,0 +1,134 @@
+//! Fair value of the book from the top N levels, weighted by imbalance with exponential decay.
+
+use super::book::OrderBook;
+use pricelevel::Side;
+use serde::{Deserialize, Serialize};
+
+/// Contribution of the N-th bid and ask levels to a [`WeightedFairPrice`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FairPriceContribution {
+    /// Position of the levels from the touch, starting at 0
+    pub depth: usize,
+    /// Decay weight of the levels, `exp(-decay * depth)`
+    pub weight: f64,
+    /// Price of the bid level
+    pub bid_price: u64,
+    /// Quantity of the bid level
+    pub bid_quantity: u64,
+    /// Price of the ask level
+    pub ask_price: u64,
+    /// Quantity of the ask level
+    pub ask_quantity: u64,
+    /// Micro price of the pair of levels alone
+    pub micro_price: f64,
+    /// Share of the fair price coming from these levels; the contributions sum
+    /// to [`WeightedFairPrice::value`]
+    pub contribution: f64,
+}
+
+/// Fair value of the book over its top levels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightedFairPrice {
+    /// Weighted fair price
+    pub value: f64,
+    /// Imbalance of the decay-weighted quantities, `(bids - asks) / (bids + asks)`
+    pub imbalance: f64,
+    /// Contribution of each pair of levels, from the touch outwards
+    pub levels: Vec<FairPriceContribution>,
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Calculates a fair price from the top `levels` bid and ask levels,
+    /// generalizing [`OrderBook::micro_price`]
+    ///
+    /// The N-th bid and ask levels are paired and each pair gives a micro price,
+    /// `(ask * bid_quantity + bid * ask_quantity) / (bid_quantity + ask_quantity)`.
+    /// The pairs are averaged weighted by their quantity times a decay weight of
+    /// `exp(-decay * depth)`, so deeper levels count less as `decay` grows. With
+    /// one level, or an infinite decay, this is the micro price.
+    ///
+    /// # Arguments
+    /// - `levels`: Number of levels per side to use; pairing stops at the shallower side
+    /// - `decay`: Decay rate of the level weights, 0 for equal weights
+    ///
+    /// # Returns
+    /// - `Some(fair_price)` with the value and the contribution of each pair of levels
+    /// - `None` if either side is empty, `levels` is zero or `decay` is negative or NaN
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 101, 10, Side::Sell, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 99, 30, Side::Buy, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 102, 10, Side::Sell, TimeInForce::Gtc, None);
+    ///
+    /// // The top level alone is balanced
+    /// let top = book.weighted_fair_price(1, 0.0).unwrap();
+    /// assert_eq!(top.value, book.micro_price().unwrap());
+    ///
+    /// // The bids deeper in the book push the fair price up
+    /// let fair = book.weighted_fair_price(2, 0.5).unwrap();
+    /// assert!(fair.value > top.value);
+    /// assert_eq!(fair.levels.len(), 2);
+    /// ```
+    #[must_use]
+    pub fn weighted_fair_price(&self, levels: usize, decay: f64) -> Option<WeightedFairPrice> {
+        if levels == 0 || decay.is_nan() || decay < 0.0 {
+            return None;
+        }
+        let side_levels = |side| {
+            self.levels_with_cumulative_depth(side)
+                .filter(|level| level.quantity > 0)
+                .take(levels)
+        };
+
+        let mut pairs = Vec::with_capacity(levels);
+        let (mut weighted_bids, mut weighted_asks) = (0.0, 0.0);
+        let (mut numerator, mut denominator) = (0.0, 0.0);
+        for (depth, (bid, ask)) in side_levels(Side::Buy)
+            .zip(side_levels(Side::Sell))
+            .enumerate()
+        {
+            let weight = (-decay * depth as f64).exp();
+            let (bid_quantity, ask_quantity) = (bid.quantity as f64, ask.quantity as f64);
+            let level_numerator = ask.price as f64 * bid_quantity + bid.price as f64 * ask_quantity;
+            weighted_bids += weight * bid_quantity;
+            weighted_asks += weight * ask_quantity;
+            numerator += weight * level_numerator;
+            denominator += weight * (bid_quantity + ask_quantity);
+            pairs.push((depth, weight, bid, ask, level_numerator));
+        }
+        if denominator <= 0.0 {
+            return None;
+        }
+
+        let levels = pairs
+            .into_iter()
+            .map(
+                |(depth, weight, bid, ask, level_numerator)| FairPriceContribution {
+                    depth,
+                    weight,
+                    bid_price: bid.price,
+                    bid_quantity: bid.quantity,
+                    ask_price: ask.price,
+                    ask_quantity: ask.quantity,
+                    micro_price: level_numerator / (bid.quantity + ask.quantity) as f64,
+                    contribution: weight * level_numerator / denominator,
+                },
+            )
+            .collect();
+
+        Some(WeightedFairPrice {
+            value: numerator / denominator,
+            imbalance: (weighted_bids - weighted_asks) / (weighted_bids + weighted_asks),
+            levels,
+        })
+    }
+}
@@ -0,0 +1,222 @@
+//! Maker/taker fee schedules and the fees charged on each transaction.
+
+use super::book::OrderBook;
+use super::owner::OwnerId;
+use super::trade::TradeResult;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Rates applying once an owner has traded a minimum notional volume.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeTier {
+    /// Notional volume, price times quantity, from which the tier applies
+    pub min_volume: u128,
+    /// Maker rate in basis points; negative rates are rebates
+    pub maker_bps: i64,
+    /// Taker rate in basis points
+    pub taker_bps: i64,
+}
+
+/// Maker and taker rates, minimum fees and volume tiers.
+///
+/// Fees are charged on the notional value of each transaction, truncated
+/// toward zero, and raised to the side's minimum when positive. Negative fees
+/// are rebates paid to the owner. The tier of an owner is chosen from the
+/// notional volume it traded before the transaction; orders without an owner
+/// always pay the base rates.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::FeeSchedule;
+///
+/// let schedule = FeeSchedule::new(-1, 5)
+///     .with_min_taker_fee(10)
+///     .with_tier(1_000_000, -2, 3);
+///
+/// // 100 x 50 notional at 5 bps is 2.5, raised to the minimum of 10
+/// assert_eq!(schedule.taker_fee(100, 50, 0), 10);
+/// assert_eq!(schedule.maker_fee(10_000, 100, 0), -100);
+/// assert_eq!(schedule.maker_fee(10_000, 100, 2_000_000), -200);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeSchedule {
+    /// Base maker rate in basis points; negative rates are rebates
+    pub maker_bps: i64,
+    /// Base taker rate in basis points
+    pub taker_bps: i64,
+    /// Smallest positive fee charged to a maker per transaction
+    pub min_maker_fee: i64,
+    /// Smallest positive fee charged to a taker per transaction
+    pub min_taker_fee: i64,
+    /// Volume tiers, sorted by minimum volume
+    pub tiers: Vec<FeeTier>,
+}
+
+impl FeeSchedule {
+    /// Create a schedule with the given base rates, without minimums or tiers
+    pub fn new(maker_bps: i64, taker_bps: i64) -> Self {
+        Self {
+            maker_bps,
+            taker_bps,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the smallest positive fee charged to a maker per transaction
+    #[must_use]
+    pub fn with_min_maker_fee(mut self, fee: i64) -> Self {
+        self.min_maker_fee = fee;
+        self
+    }
+
+    /// Sets the smallest positive fee charged to a taker per transaction
+    #[must_use]
+    pub fn with_min_taker_fee(mut self, fee: i64) -> Self {
+        self.min_taker_fee = fee;
+        self
+    }
+
+    /// Adds rates applying from `min_volume` of traded notional
+    #[must_use]
+    pub fn with_tier(mut self, min_volume: u128, maker_bps: i64, taker_bps: i64) -> Self {
+        self.tiers.push(FeeTier {
+            min_volume,
+            maker_bps,
+            taker_bps,
+        });
+        self.tiers.sort_by_key(|tier| tier.min_volume);
+        self
+    }
+
+    /// Maker and taker rates for an owner that traded `volume` so far
+    pub fn rates(&self, volume: u128) -> (i64, i64) {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| volume >= tier.min_volume)
+            .map_or((self.maker_bps, self.taker_bps), |tier| {
+                (tier.maker_bps, tier.taker_bps)
+            })
+    }
+
+    /// Fee charged to the maker of a transaction of `quantity` at `price`
+    pub fn maker_fee(&self, price: u64, quantity: u64, volume: u128) -> i64 {
+        Self::fee(price, quantity, self.rates(volume).0, self.min_maker_fee)
+    }
+
+    /// Fee charged to the taker of a transaction of `quantity` at `price`
+    pub fn taker_fee(&self, price: u64, quantity: u64, volume: u128) -> i64 {
+        Self::fee(price, quantity, self.rates(volume).1, self.min_taker_fee)
+    }
+
+    fn fee(price: u64, quantity: u64, bps: i64, minimum: i64) -> i64 {
+        let notional = i128::from(price) * i128::from(quantity);
+        let fee = (notional * i128::from(bps) / 10_000) as i64;
+        if bps > 0 { fee.max(minimum) } else { fee }
+    }
+}
+
+/// Fees charged on one transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionFee {
+    /// ID of the transaction
+    pub transaction_id: Uuid,
+    /// Fee charged to the maker; negative for a rebate
+    pub maker_fee: i64,
+    /// Fee charged to the taker; negative for a rebate
+    pub taker_fee: i64,
+}
+
+/// A fee schedule together with the traded volume of each owner.
+///
+/// One model can be shared by several books, so that volume traded in any of
+/// them counts toward the tiers.
+#[derive(Debug, Default)]
+pub struct FeeModel {
+    schedule: FeeSchedule,
+    volumes: DashMap<OwnerId, u128>,
+}
+
+impl FeeModel {
+    /// Create a model charging `schedule`, without traded volume
+    pub fn new(schedule: FeeSchedule) -> Self {
+        Self {
+            schedule,
+            volumes: DashMap::new(),
+        }
+    }
+
+    /// The fee schedule charged by this model
+    pub fn schedule(&self) -> &FeeSchedule {
+        &self.schedule
+    }
+
+    /// Notional volume traded by `owner` so far
+    pub fn volume(&self, owner: OwnerId) -> u128 {
+        self.volumes.get(&owner).map_or(0, |volume| *volume)
+    }
+
+    /// Computes the fees of every transaction of `trade`, then adds the traded
+    /// notional to the volume of its owners
+    pub fn charge(&self, trade: &TradeResult) -> Vec<TransactionFee> {
+        let transactions = trade.match_result.transactions.as_vec();
+        let mut fees = Vec::with_capacity(transactions.len());
+        for transaction in transactions {
+            let maker = trade.maker_owner(transaction.maker_order_id);
+            let volume_of = |owner: Option<OwnerId>| owner.map_or(0, |owner| self.volume(owner));
+            fees.push(TransactionFee {
+                transaction_id: transaction.transaction_id,
+                maker_fee: self.schedule.maker_fee(
+                    transaction.price,
+                    transaction.quantity,
+                    volume_of(maker),
+                ),
+                taker_fee: self.schedule.taker_fee(
+                    transaction.price,
+                    transaction.quantity,
+                    volume_of(trade.taker_owner),
+                ),
+            });
+
+            let notional = u128::from(transaction.price) * u128::from(transaction.quantity);
+            for owner in [maker, trade.taker_owner].into_iter().flatten() {
+                *self.volumes.entry(owner).or_insert(0) += notional;
+            }
+        }
+        fees
+    }
+
+    /// Forgets the traded volume of every owner
+    pub fn reset_volumes(&self) {
+        self.volumes.clear();
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Charge `schedule` on every trade of this book, reporting the fees in
+    /// [`TradeResult::fees`]
+    pub fn set_fee_schedule(&mut self, schedule: FeeSchedule) {
+        self.fee_model = Some(Arc::new(FeeModel::new(schedule)));
+    }
+
+    /// Charge the fees of a model shared with other books
+    pub fn set_fee_model(&mut self, model: Arc<FeeModel>) {
+        self.fee_model = Some(model);
+    }
+
+    /// Stop charging fees
+    pub fn remove_fee_schedule(&mut self) {
+        self.fee_model = None;
+    }
+
+    /// The fee model charged on the trades of this book, if any
+    #[must_use]
+    pub fn fee_model(&self) -> Option<&Arc<FeeModel>> {
+        self.fee_model.as_ref()
+    }
+}
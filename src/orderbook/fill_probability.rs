@@ -0,0 +1,342 @@
+//! Fill probability of resting orders from their queue position and the flow at their price.
+
+use super::book::OrderBook;
+use super::trade::TradeResult;
+use pricelevel::{OrderId, Side};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Executions and cancellations at one price level over a rolling window.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LevelIntensity {
+    /// Price of the level
+    pub price: u64,
+    /// Side of the resting orders
+    pub side: Side,
+    /// Length of the window, in milliseconds
+    pub window_ms: u64,
+    /// Resting quantity executed in the window
+    pub traded_quantity: u64,
+    /// Number of executions in the window
+    pub trade_count: u64,
+    /// Resting quantity cancelled in the window
+    pub cancelled_quantity: u64,
+    /// Number of cancellations in the window
+    pub cancel_count: u64,
+}
+
+impl LevelIntensity {
+    /// Executed quantity per second over the window
+    #[must_use]
+    pub fn trade_rate(&self) -> f64 {
+        self.per_second(self.traded_quantity)
+    }
+
+    /// Cancelled quantity per second over the window
+    #[must_use]
+    pub fn cancel_rate(&self) -> f64 {
+        self.per_second(self.cancelled_quantity)
+    }
+
+    fn per_second(&self, quantity: u64) -> f64 {
+        if self.window_ms == 0 {
+            return 0.0;
+        }
+        quantity as f64 * 1_000.0 / self.window_ms as f64
+    }
+}
+
+/// Estimated chances of a resting order being filled.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FillEstimate {
+    /// Probability of a complete fill within the horizon, between 0 and 1
+    pub probability: f64,
+    /// Expected time to a complete fill in milliseconds, or `None` without
+    /// executions at the price in the window
+    pub expected_time_to_fill_ms: Option<f64>,
+    /// Orders matched before this one
+    pub orders_ahead: usize,
+    /// Quantity matched before this one
+    pub quantity_ahead: u64,
+    /// Quantity of the order left to fill
+    pub remaining_quantity: u64,
+    /// Flow at the price of the order the estimate is built from
+    pub intensity: LevelIntensity,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FlowEvent {
+    timestamp: u64,
+    side: Side,
+    price: u64,
+    quantity: u64,
+    cancelled: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct FlowTotals {
+    traded_quantity: u64,
+    trade_count: u64,
+    cancelled_quantity: u64,
+    cancel_count: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct FlowState {
+    events: VecDeque<FlowEvent>,
+    /// Totals keyed by whether the resting side is the ask, and price
+    totals: HashMap<(bool, u64), FlowTotals>,
+}
+
+/// Rolling executions and cancellations of resting orders, per price level.
+///
+/// Events older than the window are dropped as new ones are recorded or the
+/// intensities are read, so memory is bounded by the activity of one window.
+#[derive(Debug)]
+pub struct LevelFlowTracker {
+    window_ms: u64,
+    state: Mutex<FlowState>,
+}
+
+impl LevelFlowTracker {
+    /// Create a tracker over the last `window_ms` milliseconds
+    #[must_use]
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            state: Mutex::new(FlowState::default()),
+        }
+    }
+
+    /// Length of the rolling window, in milliseconds
+    #[must_use]
+    pub fn window_ms(&self) -> u64 {
+        self.window_ms
+    }
+
+    /// Records the execution of `quantity` resting on `side` at `price`
+    pub fn record_execution(&self, timestamp: u64, side: Side, price: u64, quantity: u64) {
+        self.record(FlowEvent {
+            timestamp,
+            side,
+            price,
+            quantity,
+            cancelled: false,
+        });
+    }
+
+    /// Records the cancellation of `quantity` resting on `side` at `price`
+    pub fn record_cancel(&self, timestamp: u64, side: Side, price: u64, quantity: u64) {
+        self.record(FlowEvent {
+            timestamp,
+            side,
+            price,
+            quantity,
+            cancelled: true,
+        });
+    }
+
+    /// Records every transaction of `trade` against the resting orders it matched
+    pub fn record_trade(&self, trade: &TradeResult, timestamp: u64) {
+        for transaction in trade.match_result.transactions.as_vec() {
+            self.record_execution(
+                timestamp,
+                transaction.taker_side.opposite(),
+                transaction.price,
+                transaction.quantity,
+            );
+        }
+    }
+
+    /// Flow at `price` on `side` over the window ending at `now`
+    #[must_use]
+    pub fn intensity(&self, side: Side, price: u64, now: u64) -> LevelIntensity {
+        let mut state = self.lock();
+        self.prune(&mut state, now);
+        let totals = state
+            .totals
+            .get(&(side == Side::Sell, price))
+            .copied()
+            .unwrap_or_default();
+        LevelIntensity {
+            price,
+            side,
+            window_ms: self.window_ms,
+            traded_quantity: totals.traded_quantity,
+            trade_count: totals.trade_count,
+            cancelled_quantity: totals.cancelled_quantity,
+            cancel_count: totals.cancel_count,
+        }
+    }
+
+    /// Drops every recorded event
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.events.clear();
+        state.totals.clear();
+    }
+
+    fn record(&self, event: FlowEvent) {
+        let mut state = self.lock();
+        let totals = state
+            .totals
+            .entry((event.side == Side::Sell, event.price))
+            .or_default();
+        if event.cancelled {
+            totals.cancelled_quantity += event.quantity;
+            totals.cancel_count += 1;
+        } else {
+            totals.traded_quantity += event.quantity;
+            totals.trade_count += 1;
+        }
+        state.events.push_back(event);
+        self.prune(&mut state, event.timestamp);
+    }
+
+    fn prune(&self, state: &mut FlowState, now: u64) {
+        let since = now.saturating_sub(self.window_ms);
+        while let Some(event) = state.events.front().copied() {
+            if event.timestamp > since {
+                break;
+            }
+            state.events.pop_front();
+            let key = (event.side == Side::Sell, event.price);
+            if let Some(totals) = state.totals.get_mut(&key) {
+                if event.cancelled {
+                    totals.cancelled_quantity -= event.quantity;
+                    totals.cancel_count -= 1;
+                } else {
+                    totals.traded_quantity -= event.quantity;
+                    totals.trade_count -= 1;
+                }
+                if totals.trade_count == 0 && totals.cancel_count == 0 {
+                    state.totals.remove(&key);
+                }
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, FlowState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clone for LevelFlowTracker {
+    fn clone(&self) -> Self {
+        Self {
+            window_ms: self.window_ms,
+            state: Mutex::new(self.lock().clone()),
+        }
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Tracks executions and cancellations per price level over the last
+    /// `window_ms` milliseconds of the book clock, enabling
+    /// [`OrderBook::fill_probability`]
+    ///
+    /// Replaces any tracker enabled before, dropping its events.
+    pub fn enable_fill_probability(&mut self, window_ms: u64) {
+        self.level_flow = Some(LevelFlowTracker::new(window_ms));
+    }
+
+    /// Stop tracking the flow at each price level
+    pub fn disable_fill_probability(&mut self) {
+        self.level_flow = None;
+    }
+
+    /// The per-level flow tracker of the book, if enabled
+    #[must_use]
+    pub fn level_flow(&self) -> Option<&LevelFlowTracker> {
+        self.level_flow.as_ref()
+    }
+
+    /// Executions and cancellations at `price` on `side` over the window ending
+    /// at the current time of the book clock, if tracked
+    #[must_use]
+    pub fn level_intensity(&self, price: u64, side: Side) -> Option<LevelIntensity> {
+        self.level_flow
+            .as_ref()
+            .map(|flow| flow.intensity(side, price, self.clock.now_millis()))
+    }
+
+    /// Estimates the probability that a resting order is completely filled
+    /// within `horizon_ms` milliseconds, and its expected time to fill
+    ///
+    /// The quantity ahead of the order, from
+    /// [`OrderBook::estimated_queue_position`], is consumed by executions at its
+    /// price and by the share of cancellations falling ahead of it, in
+    /// proportion to the quantity ahead in the level. The rest of the order is
+    /// then filled by executions alone. Both rates are the averages over the
+    /// tracking window, and fills are modelled as a Poisson process, so the
+    /// probability is `1 - exp(-horizon / expected_time_to_fill)`.
+    ///
+    /// # Returns
+    /// `None` if the tracker is not enabled or the order is not resting in the book.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{ManualClock, OrderBook};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    /// use std::sync::Arc;
+    ///
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// let clock = Arc::new(ManualClock::new(10_000));
+    /// book.set_clock(clock.clone());
+    /// book.enable_fill_probability(10_000);
+    ///
+    /// book.add_limit_order(OrderId::new(), 100, 50, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// clock.advance(1);
+    /// let mine = OrderId::new();
+    /// book.add_limit_order(mine, 100, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// // 20 units traded at 100 in the last 10 seconds: 2 units per second
+    /// book.submit_market_order(OrderId::new(), 20, Side::Sell).unwrap();
+    ///
+    /// let estimate = book.fill_probability(mine, 5_000).unwrap();
+    /// assert_eq!(estimate.quantity_ahead, 30);
+    /// assert_eq!(estimate.expected_time_to_fill_ms, Some(20_000.0));
+    /// assert!(estimate.probability > 0.2 && estimate.probability < 0.25);
+    /// ```
+    #[must_use]
+    pub fn fill_probability(&self, order_id: OrderId, horizon_ms: u64) -> Option<FillEstimate> {
+        let flow = self.level_flow.as_ref()?;
+        let (price, side) = *self.order_locations.get(&order_id)?;
+        let (orders_ahead, quantity_ahead) = self.estimated_queue_position(order_id)?;
+        let order = self.get_order(order_id)?;
+        let remaining_quantity = order.visible_quantity() + order.hidden_quantity();
+        let level_quantity: u64 = [false, true]
+            .into_iter()
+            .filter_map(|hidden| self.side_levels(side, hidden).get(&price))
+            .map(|entry| entry.value().total_quantity())
+            .sum();
+        let intensity = flow.intensity(side, price, self.clock.now_millis());
+
+        let trade_rate = intensity.trade_rate();
+        let expected_time_to_fill_ms = (trade_rate > 0.0).then(|| {
+            let cancel_share = quantity_ahead as f64 / level_quantity.max(1) as f64;
+            let ahead_rate = trade_rate + intensity.cancel_rate() * cancel_share;
+            let seconds =
+                quantity_ahead as f64 / ahead_rate + remaining_quantity as f64 / trade_rate;
+            seconds * 1_000.0
+        });
+        let probability = match expected_time_to_fill_ms {
+            Some(expected) if expected > 0.0 => 1.0 - (-(horizon_ms as f64) / expected).exp(),
+            Some(_) => 1.0,
+            None => 0.0,
+        };
+
+        Some(FillEstimate {
+            probability,
+            expected_time_to_fill_ms,
+            orders_ahead,
+            quantity_ahead,
+            remaining_quantity,
+            intensity,
+        })
+    }
+}
@@ -0,0 +1,371 @@
+//! Complete, restorable image of an order book for hot-standby failover.
+
+use super::book::OrderBook;
+use super::config::BookConfig;
+use super::error::OrderBookError;
+use super::owner::OwnerId;
+use super::quotes::QuotePair;
+use crossbeam_skiplist::SkipMap;
+use pricelevel::{OrderId, PriceLevel, PriceLevelSnapshot, Side, UuidGenerator};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use tracing::trace;
+use uuid::Uuid;
+
+/// Every piece of state held by an `OrderBook`, as produced by
+/// [`OrderBook::create_full_state`].
+///
+/// Unlike `OrderBookSnapshot`, which only describes the visible levels, this includes
+/// hidden levels, extra fields, owners, trading rules, the last trade and market
+/// close information, the resting quotes, the journal sequence and the position of
+/// the transaction ID generator. Listeners, the clock and the attached journal are
+/// runtime wiring and are not part of the state. Per-level execution statistics are
+/// not captured either, because price levels cannot be restored with them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
+pub struct OrderBookFullState<T> {
+    /// The symbol of the book
+    pub symbol: String,
+    /// Time the state was captured, from the book's clock (milliseconds since epoch)
+    pub timestamp: u64,
+    /// Visible bid levels, best first, with their orders in time priority
+    pub bids: Vec<PriceLevelSnapshot>,
+    /// Visible ask levels, best first, with their orders in time priority
+    pub asks: Vec<PriceLevelSnapshot>,
+    /// Bid levels holding fully hidden orders
+    pub hidden_bids: Vec<PriceLevelSnapshot>,
+    /// Ask levels holding fully hidden orders
+    pub hidden_asks: Vec<PriceLevelSnapshot>,
+    /// Extra fields of the resting orders
+    pub extra_fields: Vec<(OrderId, T)>,
+    /// Owners of the resting orders submitted on behalf of an owner
+    pub owners: Vec<(OrderId, OwnerId)>,
+    /// Price of the last trade, if the book has traded
+    pub last_trade_price: Option<u64>,
+    /// Market close timestamp used to expire DAY orders, if set
+    pub market_close_timestamp: Option<u64>,
+    /// Trading rules of the book
+    pub config: BookConfig,
+    /// Whether duplicate order IDs replace the resting order
+    pub replace_on_duplicate: bool,
+    /// The quote pairs of the market makers resting in the book, by owner
+    pub quotes: Vec<(OwnerId, QuotePair)>,
+    /// Sequence number of the last journaled command
+    pub journal_sequence: u64,
+    /// Namespace of the transaction ID generator
+    pub transaction_namespace: Uuid,
+    /// Number of transaction IDs the generator has produced
+    pub transaction_count: u64,
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Captures the complete state of the book
+    ///
+    /// Restoring the result with [`OrderBook::restore_full_state`] yields a book with
+    /// the same state, including the transaction IDs of future trades. Orders within a
+    /// level are captured in time priority, as reported by the price level, so orders
+    /// sharing a timestamp may be restored in either order.
+    pub fn create_full_state(&self) -> OrderBookFullState<T> {
+        let levels = |map: &SkipMap<u64, Arc<PriceLevel>>, side: Side| {
+            let snapshots = map.iter().map(|entry| entry.value().snapshot());
+            match side {
+                Side::Buy => snapshots.rev().collect::<Vec<_>>(),
+                Side::Sell => snapshots.collect(),
+            }
+        };
+
+        let mut extra_fields: Vec<(OrderId, T)> = self
+            .order_extra_fields
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect();
+        extra_fields.sort_by_cached_key(|(order_id, _)| order_id.to_string());
+
+        let mut owners: Vec<(OrderId, OwnerId)> = self
+            .order_owners
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect();
+        owners.sort_by_cached_key(|(order_id, _)| order_id.to_string());
+
+        let mut quotes: Vec<(OwnerId, QuotePair)> = self
+            .quotes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(owner, pair)| (*owner, *pair))
+            .collect();
+        quotes.sort_unstable_by_key(|(owner, _)| *owner);
+
+        OrderBookFullState {
+            symbol: self.symbol.clone(),
+            timestamp: self.clock.now_millis(),
+            bids: levels(&self.bids, Side::Buy),
+            asks: levels(&self.asks, Side::Sell),
+            hidden_bids: levels(&self.hidden_bids, Side::Buy),
+            hidden_asks: levels(&self.hidden_asks, Side::Sell),
+            extra_fields,
+            owners,
+            last_trade_price: self.last_trade_price(),
+            market_close_timestamp: self
+                .has_market_close
+                .load(Ordering::Relaxed)
+                .then(|| self.market_close_timestamp.load(Ordering::Relaxed)),
+            config: self.config,
+            replace_on_duplicate: self.replace_on_duplicate.load(Ordering::Relaxed),
+            quotes,
+            journal_sequence: self.journal_sequence(),
+            transaction_namespace: self.transaction_namespace,
+            transaction_count: self.transaction_count.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Replaces the complete state of the book with `state`
+    ///
+    /// Listeners, the clock and the attached journal are kept. Restoring the
+    /// transaction ID generator replays it to its captured position, which takes
+    /// time proportional to the number of transactions the source book produced.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::InvalidOperation` if `state` belongs to another symbol.
+    pub fn restore_full_state(
+        &mut self,
+        state: OrderBookFullState<T>,
+    ) -> Result<(), OrderBookError> {
+        if state.symbol != self.symbol {
+            return Err(OrderBookError::InvalidOperation {
+                message: format!(
+                    "State symbol {} does not match order book symbol {}",
+                    state.symbol, self.symbol
+                ),
+            });
+        }
+
+        trace!(
+            "Order book {}: Restoring full state captured at {}",
+            self.symbol, state.timestamp
+        );
+
+        for map in [&self.bids, &self.asks, &self.hidden_bids, &self.hidden_asks] {
+            map.clear();
+        }
+        self.hidden_order_ids.clear();
+        self.order_extra_fields.clear();
+        self.order_owners.clear();
+        self.owner_orders.clear();
+        self.order_locations.clear();
+
+        let levels = [
+            (state.bids, Side::Buy, false),
+            (state.asks, Side::Sell, false),
+            (state.hidden_bids, Side::Buy, true),
+            (state.hidden_asks, Side::Sell, true),
+        ];
+        for (snapshots, side, hidden) in levels {
+            for snapshot in snapshots {
+                let price = snapshot.price;
+                for order in &snapshot.orders {
+                    self.order_locations.insert(order.id(), (price, side));
+                    if hidden {
+                        self.hidden_order_ids.insert(order.id());
+                    }
+                }
+                self.side_levels(side, hidden)
+                    .insert(price, Arc::new(PriceLevel::from(&snapshot)));
+            }
+        }
+
+        for (order_id, fields) in state.extra_fields {
+            self.order_extra_fields.insert(order_id, fields);
+        }
+        for (order_id, owner) in state.owners {
+            self.order_owners.insert(order_id, owner);
+            self.owner_orders.entry(owner).or_default().insert(order_id);
+        }
+
+        self.last_trade_price
+            .store(state.last_trade_price.unwrap_or(0), Ordering::Relaxed);
+        self.has_traded
+            .store(state.last_trade_price.is_some(), Ordering::Relaxed);
+        self.last_trade_millis.store(u64::MAX, Ordering::Relaxed);
+        self.last_update_millis
+            .store(self.clock.now_millis(), Ordering::Relaxed);
+        self.market_close_timestamp
+            .store(state.market_close_timestamp.unwrap_or(0), Ordering::Relaxed);
+        self.has_market_close
+            .store(state.market_close_timestamp.is_some(), Ordering::Relaxed);
+        self.day_orders_expired.store(false, Ordering::Relaxed);
+        self.apply_config(state.config);
+        self.replace_on_duplicate
+            .store(state.replace_on_duplicate, Ordering::Relaxed);
+        *self.quotes.lock().unwrap_or_else(|e| e.into_inner()) = state.quotes.into_iter().collect();
+        *self
+            .journal_sequence
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = state.journal_sequence;
+
+        let generator = UuidGenerator::new(state.transaction_namespace);
+        for _ in 0..state.transaction_count {
+            generator.next();
+        }
+        self.transaction_id_generator = generator;
+        self.transaction_namespace = state.transaction_namespace;
+        self.transaction_count = state.transaction_count.into();
+
+        self.reset_delta_tracking();
+        self.emit_book_reset(state.timestamp);
+        Ok(())
+    }
+
+    /// Creates an independent copy of the book, to branch its state
+    ///
+    /// The fork holds the same orders, extra fields, owners, trading rules,
+    /// session and kill switch state, last trade, quotes and journal sequence,
+    /// and produces the same transaction IDs for its future trades. Its price
+    /// levels are copied one by one, since levels are mutated in place by
+    /// matching and cannot be shared, without going through a serialized
+    /// state. The order flow, time-weighted, Kyle's lambda, realized
+    /// volatility, trade tape, fill probability, resiliency and surveillance
+    /// trackers are copied with their history, and the clock and risk
+    /// checkers are shared.
+    ///
+    /// Listeners, subscribers and the attached journal are not carried over,
+    /// so the fork never reports to the consumers of this book. Neither are
+    /// the rate limiter, the fee model with its traded volumes, nor delta
+    /// tracking, which can be set up again on the fork.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// book.add_limit_order(OrderId::new(), 101, 10, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// let fork = book.fork();
+    /// fork.submit_market_order(OrderId::new(), 10, Side::Buy).unwrap();
+    ///
+    /// assert_eq!(fork.best_ask(), None);
+    /// assert_eq!(book.best_ask(), Some(101));
+    /// ```
+    #[must_use]
+    pub fn fork(&self) -> Self {
+        let mut fork = Self::new(&self.symbol);
+
+        for (side, hidden) in [
+            (Side::Buy, false),
+            (Side::Sell, false),
+            (Side::Buy, true),
+            (Side::Sell, true),
+        ] {
+            let target = fork.side_levels(side, hidden);
+            for entry in self.side_levels(side, hidden).iter() {
+                let level = PriceLevel::from(&entry.value().snapshot());
+                target.insert(*entry.key(), Arc::new(level));
+            }
+        }
+        for entry in self.order_locations.iter() {
+            fork.order_locations.insert(*entry.key(), *entry.value());
+        }
+        for order_id in self.hidden_order_ids.iter() {
+            fork.hidden_order_ids.insert(*order_id);
+        }
+        for entry in self.order_extra_fields.iter() {
+            fork.order_extra_fields
+                .insert(*entry.key(), entry.value().clone());
+        }
+        for entry in self.order_owners.iter() {
+            fork.order_owners.insert(*entry.key(), *entry.value());
+        }
+        for entry in self.owner_orders.iter() {
+            let orders = entry.value().iter().map(|order_id| *order_id).collect();
+            fork.owner_orders.insert(*entry.key(), orders);
+        }
+
+        let transaction_count = self.transaction_count.load(Ordering::Relaxed);
+        fork.transaction_id_generator = generator_at(self.transaction_namespace, transaction_count);
+        fork.transaction_namespace = self.transaction_namespace;
+        fork.transaction_count = transaction_count.into();
+
+        for (target, source) in [
+            (&fork.last_trade_price, &self.last_trade_price),
+            (&fork.last_trade_millis, &self.last_trade_millis),
+            (&fork.last_update_millis, &self.last_update_millis),
+            (&fork.market_close_timestamp, &self.market_close_timestamp),
+        ] {
+            target.store(source.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        for (target, source) in [
+            (&fork.has_traded, &self.has_traded),
+            (&fork.has_market_close, &self.has_market_close),
+            (&fork.day_orders_expired, &self.day_orders_expired),
+            (&fork.replace_on_duplicate, &self.replace_on_duplicate),
+            (&fork.trading_enabled, &self.trading_enabled),
+        ] {
+            target.store(source.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        fork.session_state.store(
+            self.session_state.load(Ordering::Relaxed),
+            Ordering::Relaxed,
+        );
+        for owner in self.disabled_owners.iter() {
+            fork.disabled_owners.insert(*owner);
+        }
+
+        fork.apply_config(self.config);
+        fork.circuit_breaker = self.circuit_breaker;
+        fork.reference_prices = Mutex::new(*lock(&self.reference_prices));
+        fork.last_bbo = Mutex::new(*lock(&self.last_bbo));
+        fork.quotes = Mutex::new(lock(&self.quotes).clone());
+        fork.journal_sequence = Mutex::new(*lock(&self.journal_sequence));
+        fork.clock = Arc::clone(&self.clock);
+        fork.risk_checkers = self.risk_checkers.clone();
+
+        fork.order_flow_imbalance = self
+            .order_flow_imbalance
+            .as_ref()
+            .map(|tracker| Mutex::new(lock(tracker).clone()));
+        fork.time_weighted_liquidity = self
+            .time_weighted_liquidity
+            .as_ref()
+            .map(|tracker| Mutex::new(lock(tracker).clone()));
+        fork.kyle_lambda = self
+            .kyle_lambda
+            .as_ref()
+            .map(|tracker| Mutex::new(lock(tracker).clone()));
+        fork.realized_volatility = self
+            .realized_volatility
+            .as_ref()
+            .map(|(source, tracker)| (*source, Mutex::new(lock(tracker).clone())));
+        fork.trade_tape = self.trade_tape.clone();
+        fork.level_flow = self.level_flow.clone();
+        fork.resiliency = self.resiliency.clone();
+        fork.surveillance = self.surveillance.clone();
+
+        fork
+    }
+}
+
+/// Locks `mutex`, recovering the value of a poisoned lock
+fn lock<V>(mutex: &Mutex<V>) -> std::sync::MutexGuard<'_, V> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A transaction ID generator of `namespace` that already produced `count` IDs
+///
+/// The generator has no way to set its position, but its serialized form holds
+/// it, so it is rebuilt from that form instead of drawing `count` IDs.
+fn generator_at(namespace: Uuid, count: u64) -> UuidGenerator {
+    serde_json::from_value(serde_json::json!({ "namespace": namespace, "counter": count }))
+        .unwrap_or_else(|_| {
+            let generator = UuidGenerator::new(namespace);
+            for _ in 0..count {
+                generator.next();
+            }
+            generator
+        })
+}
@@ -0,0 +1,283 @@
+//! Liquidity heatmap recorder sampling resting depth into a bounded time series.
+
+use super::book::OrderBook;
+use super::iterators::LevelInfo;
+use pricelevel::Side;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+/// Sampling, bucketing and memory settings of a [`LiquidityHeatmap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeatmapConfig {
+    /// Minimum time between two samples, in milliseconds
+    pub sampling_interval_ms: u64,
+    /// Width of each price bucket
+    pub bucket_size: u64,
+    /// Number of price levels sampled on each side, from the best price
+    pub levels: usize,
+    /// Maximum number of samples kept before the series is downsampled
+    pub max_columns: usize,
+}
+
+impl HeatmapConfig {
+    /// Create a configuration sampling `levels` levels per side every
+    /// `sampling_interval_ms` milliseconds into buckets of `bucket_size`
+    #[must_use]
+    pub fn new(sampling_interval_ms: u64, bucket_size: u64, levels: usize) -> Self {
+        Self {
+            sampling_interval_ms,
+            bucket_size: bucket_size.max(1),
+            levels,
+            max_columns: 1_024,
+        }
+    }
+
+    /// Sets the maximum number of samples kept
+    #[must_use]
+    pub fn with_max_columns(mut self, max_columns: usize) -> Self {
+        self.max_columns = max_columns.max(2);
+        self
+    }
+}
+
+/// Resting quantity of one price bucket and side in a sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeatmapCell {
+    /// Lowest price of the bucket
+    pub price: u64,
+    /// Side of the resting quantity
+    pub side: Side,
+    /// Resting quantity in the bucket
+    pub quantity: u64,
+}
+
+/// Depth of the book at one point in time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeatmapColumn {
+    /// Time of the sample, in milliseconds
+    pub timestamp: u64,
+    /// Non-empty buckets, bids first, each side by ascending price
+    pub cells: Vec<HeatmapCell>,
+}
+
+/// One cell of the heatmap series, flattened for rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeatmapPoint {
+    /// Time of the sample, in milliseconds
+    pub timestamp: u64,
+    /// Lowest price of the bucket
+    pub price: u64,
+    /// Side of the resting quantity
+    pub side: Side,
+    /// Resting quantity in the bucket
+    pub quantity: u64,
+}
+
+#[derive(Debug)]
+struct HeatmapState {
+    columns: VecDeque<HeatmapColumn>,
+    interval_ms: u64,
+    last_sample: Option<u64>,
+}
+
+/// Bounded time series of the resting depth of a book, bucketed by price.
+///
+/// Call [`LiquidityHeatmap::sample`] as often as convenient: a sample is only
+/// taken once the sampling interval elapsed on the book clock. When
+/// `max_columns` samples are stored, pairs of consecutive samples are merged by
+/// averaging their quantities, and the sampling interval doubles, so the series
+/// keeps covering the whole recording with a bounded amount of memory.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::{HeatmapConfig, LiquidityHeatmap, ManualClock, OrderBook};
+/// use pricelevel::{OrderId, Side, TimeInForce};
+/// use std::sync::Arc;
+///
+/// let mut book = OrderBook::<()>::new("BTC/USD");
+/// let clock = Arc::new(ManualClock::new(0));
+/// book.set_clock(clock.clone());
+/// book.add_limit_order(OrderId::new(), 101, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+/// book.add_limit_order(OrderId::new(), 104, 20, Side::Buy, TimeInForce::Gtc, None).unwrap();
+/// book.add_limit_order(OrderId::new(), 111, 5, Side::Sell, TimeInForce::Gtc, None).unwrap();
+///
+/// let heatmap = LiquidityHeatmap::new(HeatmapConfig::new(1_000, 10, 20));
+/// assert!(heatmap.sample(&book));
+/// assert!(!heatmap.sample(&book));
+///
+/// let column = &heatmap.columns()[0];
+/// assert_eq!(column.cells.len(), 2);
+/// assert_eq!((column.cells[0].price, column.cells[0].quantity), (100, 30));
+/// assert_eq!((column.cells[1].price, column.cells[1].quantity), (110, 5));
+/// ```
+#[derive(Debug)]
+pub struct LiquidityHeatmap {
+    config: HeatmapConfig,
+    state: Mutex<HeatmapState>,
+}
+
+impl LiquidityHeatmap {
+    /// Create an empty heatmap
+    #[must_use]
+    pub fn new(config: HeatmapConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(HeatmapState {
+                columns: VecDeque::new(),
+                interval_ms: config.sampling_interval_ms,
+                last_sample: None,
+            }),
+        }
+    }
+
+    /// Settings of the heatmap
+    #[must_use]
+    pub fn config(&self) -> HeatmapConfig {
+        self.config
+    }
+
+    /// Current time between samples, doubled by each downsampling
+    #[must_use]
+    pub fn interval_ms(&self) -> u64 {
+        self.lock().interval_ms
+    }
+
+    /// Samples the depth of `book` if the interval elapsed since the last sample
+    ///
+    /// # Returns
+    /// Whether a sample was taken.
+    pub fn sample<T>(&self, book: &OrderBook<T>) -> bool
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let now = book.clock().now_millis();
+        if !self.is_due(now) {
+            return false;
+        }
+        let levels = self.config.levels;
+        self.record(
+            now,
+            book.levels_with_cumulative_depth(Side::Buy).take(levels),
+            book.levels_with_cumulative_depth(Side::Sell).take(levels),
+        );
+        true
+    }
+
+    /// Records a sample of the given bid and ask levels at `timestamp`,
+    /// regardless of the sampling interval
+    pub fn record(
+        &self,
+        timestamp: u64,
+        bids: impl IntoIterator<Item = LevelInfo>,
+        asks: impl IntoIterator<Item = LevelInfo>,
+    ) {
+        let mut cells = self.bucket(Side::Buy, bids);
+        cells.extend(self.bucket(Side::Sell, asks));
+
+        let mut state = self.lock();
+        if state.columns.len() >= self.config.max_columns {
+            Self::downsample(&mut state);
+        }
+        state.columns.push_back(HeatmapColumn { timestamp, cells });
+        state.last_sample = Some(timestamp);
+    }
+
+    /// Every sample, oldest first
+    #[must_use]
+    pub fn columns(&self) -> Vec<HeatmapColumn> {
+        self.lock().columns.iter().cloned().collect()
+    }
+
+    /// Every non-empty cell of every sample, flattened for rendering
+    #[must_use]
+    pub fn points(&self) -> Vec<HeatmapPoint> {
+        self.lock()
+            .columns
+            .iter()
+            .flat_map(|column| {
+                column.cells.iter().map(|cell| HeatmapPoint {
+                    timestamp: column.timestamp,
+                    price: cell.price,
+                    side: cell.side,
+                    quantity: cell.quantity,
+                })
+            })
+            .collect()
+    }
+
+    /// Number of samples stored
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().columns.len()
+    }
+
+    /// Whether no sample was stored
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().columns.is_empty()
+    }
+
+    /// Drops every sample and restores the configured sampling interval
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.columns.clear();
+        state.interval_ms = self.config.sampling_interval_ms;
+        state.last_sample = None;
+    }
+
+    fn is_due(&self, now: u64) -> bool {
+        let state = self.lock();
+        state
+            .last_sample
+            .is_none_or(|last| now >= last.saturating_add(state.interval_ms))
+    }
+
+    fn bucket(&self, side: Side, levels: impl IntoIterator<Item = LevelInfo>) -> Vec<HeatmapCell> {
+        let mut buckets: BTreeMap<u64, u64> = BTreeMap::new();
+        for level in levels {
+            let bucket = level.price - level.price % self.config.bucket_size;
+            *buckets.entry(bucket).or_insert(0) += level.quantity;
+        }
+        buckets
+            .into_iter()
+            .filter(|(_, quantity)| *quantity > 0)
+            .map(|(price, quantity)| HeatmapCell {
+                price,
+                side,
+                quantity,
+            })
+            .collect()
+    }
+
+    /// Merges consecutive pairs of samples, averaging the quantity of each bucket
+    fn downsample(state: &mut HeatmapState) {
+        let columns: Vec<HeatmapColumn> = state.columns.drain(..).collect();
+        for pair in columns.chunks(2) {
+            let mut merged: BTreeMap<(bool, u64), u64> = BTreeMap::new();
+            for cell in pair.iter().flat_map(|column| &column.cells) {
+                *merged
+                    .entry((cell.side == Side::Sell, cell.price))
+                    .or_insert(0) += cell.quantity;
+            }
+            let count = pair.len() as u64;
+            state.columns.push_back(HeatmapColumn {
+                timestamp: pair[0].timestamp,
+                cells: merged
+                    .into_iter()
+                    .map(|((is_ask, price), quantity)| HeatmapCell {
+                        price,
+                        side: if is_ask { Side::Sell } else { Side::Buy },
+                        quantity: quantity / count,
+                    })
+                    .filter(|cell| cell.quantity > 0)
+                    .collect(),
+            });
+        }
+        state.interval_ms = state.interval_ms.saturating_mul(2).max(1);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HeatmapState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,156 @@
+//! Cox-Ross-Rubinstein binomial tree pricing for American options.
+//!
+//! American options can be exercised at any time before expiry, so there is
+//! no closed form like Black-Scholes. The binomial tree discretizes the life
+//! of the option into steps and checks early exercise at every node.
+
+use super::black_scholes::BlackScholes;
+use super::types::{ExerciseStyle, IVParams, OptionType};
+
+/// Volatility bump used for the finite-difference vega.
+const VEGA_BUMP: f64 = 1e-4;
+
+/// Cox-Ross-Rubinstein binomial tree pricing model.
+pub struct BinomialTree;
+
+impl BinomialTree {
+    /// Calculates the price of an American option on a CRR binomial tree.
+    ///
+    /// Each step moves the underlying up by `u = e^(σ√dt)` or down by `1/u`,
+    /// with risk-neutral up probability `p = (e^((r-q)dt) - d) / (u - d)`.
+    /// Option values are rolled back from expiry taking the maximum of the
+    /// continuation value and immediate exercise at every node.
+    ///
+    /// # Arguments
+    /// - `params`: Option parameters (spot, strike, time, rate, yield, type)
+    /// - `vol`: Volatility (σ)
+    /// - `steps`: Number of time steps (at least 1)
+    ///
+    /// # Returns
+    /// Theoretical option price
+    #[must_use]
+    pub fn price(params: &IVParams, vol: f64, steps: u32) -> f64 {
+        if params.time_to_expiry <= 0.0 {
+            return params.intrinsic_value();
+        }
+
+        let steps = steps.max(1);
+        let dt = params.time_to_expiry / f64::from(steps);
+        let up = (vol.max(0.0) * dt.sqrt()).exp();
+        let down = 1.0 / up;
+        let growth = (params.cost_of_carry() * dt).exp();
+        // Outside [0, 1] only when σ√dt is below the carry per step
+        let probability = if up > down {
+            ((growth - down) / (up - down)).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        let discount = (-params.risk_free_rate * dt).exp();
+
+        let exercise = |spot: f64| match params.option_type {
+            OptionType::Call => (spot - params.strike).max(0.0),
+            OptionType::Put => (params.strike - spot).max(0.0),
+        };
+
+        // Node i at step n has seen i up moves: spot · u^i · d^(n - i)
+        let steps = steps as usize;
+        let mut values: Vec<f64> = (0..=steps)
+            .map(|i| exercise(params.spot * up.powi(i as i32) * down.powi((steps - i) as i32)))
+            .collect();
+
+        for step in (0..steps).rev() {
+            for i in 0..=step {
+                let continuation =
+                    discount * (probability * values[i + 1] + (1.0 - probability) * values[i]);
+                let spot = params.spot * up.powi(i as i32) * down.powi((step - i) as i32);
+                values[i] = continuation.max(exercise(spot));
+            }
+        }
+
+        values[0]
+    }
+
+    /// Calculates vega of an American option by central finite difference.
+    ///
+    /// # Arguments
+    /// - `params`: Option parameters
+    /// - `vol`: Volatility (σ)
+    /// - `steps`: Number of time steps of the tree
+    ///
+    /// # Returns
+    /// Vega value (change in price per unit change in volatility)
+    #[must_use]
+    pub fn vega(params: &IVParams, vol: f64, steps: u32) -> f64 {
+        if params.time_to_expiry <= 0.0 {
+            return 0.0;
+        }
+
+        let low = (vol - VEGA_BUMP).max(0.0);
+        let high = vol + VEGA_BUMP;
+        (Self::price(params, high, steps) - Self::price(params, low, steps)) / (high - low)
+    }
+}
+
+/// Prices an option with the model matching its exercise style.
+pub(super) fn model_price(params: &IVParams, vol: f64) -> f64 {
+    match params.exercise_style {
+        ExerciseStyle::European => BlackScholes::price(params, vol),
+        ExerciseStyle::American { steps } => BinomialTree::price(params, vol, steps),
+    }
+}
+
+/// Vega of an option with the model matching its exercise style.
+pub(super) fn model_vega(params: &IVParams, vol: f64) -> f64 {
+    match params.exercise_style {
+        ExerciseStyle::European => BlackScholes::vega(params, vol),
+        ExerciseStyle::American { steps } => BinomialTree::vega(params, vol, steps),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn american(params: IVParams) -> IVParams {
+        params.with_exercise_style(ExerciseStyle::american())
+    }
+
+    #[test]
+    fn test_american_call_without_dividend_matches_european() {
+        // Early exercise of a call never pays without a dividend
+        let params = IVParams::call(100.0, 100.0, 0.5, 0.05);
+        let european = BlackScholes::price(&params, 0.3);
+        let tree = BinomialTree::price(&params, 0.3, 500);
+        assert!((tree - european).abs() < 0.02);
+        assert!((model_price(&american(params), 0.3) - european).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_american_put_early_exercise_premium() {
+        let params = IVParams::put(100.0, 110.0, 1.0, 0.08);
+        let european = BlackScholes::price(&params, 0.2);
+        let american = BinomialTree::price(&params, 0.2, 300);
+        assert!(american > european + 0.1);
+        assert!(american >= params.intrinsic_value());
+    }
+
+    #[test]
+    fn test_american_call_with_dividend_exceeds_european() {
+        let params = IVParams::call(100.0, 80.0, 1.0, 0.02).with_dividend_yield(0.1);
+        let european = BlackScholes::price(&params, 0.2);
+        let american = BinomialTree::price(&params, 0.2, 300);
+        assert!(american > european);
+        assert!(american >= params.intrinsic_value());
+    }
+
+    #[test]
+    fn test_binomial_edge_cases() {
+        let expired = IVParams::put(90.0, 100.0, 0.0, 0.05);
+        assert_eq!(BinomialTree::price(&expired, 0.2, 100), 10.0);
+        assert_eq!(BinomialTree::vega(&expired, 0.2, 100), 0.0);
+
+        let params = IVParams::put(100.0, 100.0, 0.25, 0.05);
+        assert!(BinomialTree::price(&params, 0.2, 0) > 0.0);
+        assert!(BinomialTree::vega(&params, 0.2, 200) > 0.0);
+    }
+}
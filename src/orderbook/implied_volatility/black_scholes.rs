@@ -3,12 +3,65 @@
 //! This module provides a lightweight implementation of the Black-Scholes
 //! option pricing model for use in implied volatility calculations.
 
-use super::types::{IVParams, OptionType};
+use super::error::IVError;
+use super::types::{IVDiagnostics, IVParams, OptionType};
 use std::f64::consts::PI;
 
 /// Square root of 2, precomputed for efficiency.
 const SQRT_2: f64 = std::f64::consts::SQRT_2;
 
+/// |x| below which the normal CDF uses Hart's rational approximation.
+const HART_CUTOFF: f64 = 3.0;
+
+/// Terms of the continued fraction of the normal CDF tails.
+const TAIL_FRACTION_TERMS: u32 = 40;
+
+/// Volatility input of the batch pricing functions of [`BlackScholes`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BatchVolatility<'a> {
+    /// One volatility for every option.
+    Single(f64),
+    /// One volatility per option, in the order of the options.
+    PerOption(&'a [f64]),
+}
+
+impl From<f64> for BatchVolatility<'_> {
+    fn from(vol: f64) -> Self {
+        Self::Single(vol)
+    }
+}
+
+impl<'a> From<&'a [f64]> for BatchVolatility<'a> {
+    fn from(vols: &'a [f64]) -> Self {
+        Self::PerOption(vols)
+    }
+}
+
+impl<'a> From<&'a Vec<f64>> for BatchVolatility<'a> {
+    fn from(vols: &'a Vec<f64>) -> Self {
+        Self::PerOption(vols)
+    }
+}
+
+impl BatchVolatility<'_> {
+    /// Checks that per-option volatilities match `count` options.
+    fn validate(&self, count: usize) -> Result<(), IVError> {
+        match self {
+            Self::PerOption(vols) if vols.len() != count => Err(IVError::InvalidParams {
+                message: format!("{} volatilities for {count} options", vols.len()),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn get(&self, index: usize) -> f64 {
+        match self {
+            Self::Single(vol) => *vol,
+            Self::PerOption(vols) => vols[index],
+        }
+    }
+}
+
 /// Black-Scholes pricing model implementation.
 ///
 /// Provides methods for calculating option prices and Greeks
@@ -16,39 +69,32 @@ const SQRT_2: f64 = std::f64::consts::SQRT_2;
 pub struct BlackScholes;
 
 impl BlackScholes {
-    /// Approximation of the error function (erf).
+    /// Error function (erf).
     ///
-    /// Uses Abramowitz and Stegun approximation (formula 7.1.26)
-    /// with maximum error of 1.5×10⁻⁷.
+    /// Derived from [`BlackScholes::norm_cdf`] as `erf(x) = 1 - 2·N(-|x|·√2)`
+    /// with the sign of `x`, so it shares its double precision accuracy.
     ///
     /// # Arguments
     /// - `x`: Input value
     ///
     /// # Returns
-    /// Approximation of erf(x)
+    /// erf(x)
     #[must_use]
     pub fn erf(x: f64) -> f64 {
-        // Constants for the approximation
-        const A1: f64 = 0.254829592;
-        const A2: f64 = -0.284496736;
-        const A3: f64 = 1.421413741;
-        const A4: f64 = -1.453152027;
-        const A5: f64 = 1.061405429;
-        const P: f64 = 0.3275911;
-
-        let sign = if x < 0.0 { -1.0 } else { 1.0 };
-        let x = x.abs();
-
-        let t = 1.0 / (1.0 + P * x);
-        let y = 1.0 - (((((A5 * t + A4) * t) + A3) * t + A2) * t + A1) * t * (-x * x).exp();
-
-        sign * y
+        let y = 1.0 - 2.0 * Self::norm_cdf(-x.abs() * SQRT_2);
+        if x < 0.0 { -y } else { y }
     }
 
     /// Standard normal cumulative distribution function (CDF).
     ///
     /// Calculates P(Z ≤ x) where Z is a standard normal random variable.
     ///
+    /// Uses Hart's double precision algorithm 5666 as given by West (2005)
+    /// for |x| < 3, and Laplace's continued fraction of the Mills ratio in the
+    /// tails, for a relative error of about 10⁻¹⁴ on the tail probability
+    /// everywhere. The tails stay accurate far out, which matters for deep
+    /// out-of-the-money wings where `N(d)` is tiny.
+    ///
     /// # Arguments
     /// - `x`: Input value
     ///
@@ -56,7 +102,43 @@ impl BlackScholes {
     /// Probability that a standard normal variable is less than or equal to x
     #[must_use]
     pub fn norm_cdf(x: f64) -> f64 {
-        0.5 * (1.0 + Self::erf(x / SQRT_2))
+        let z = x.abs();
+        let tail = if z > 38.0 {
+            0.0
+        } else if z < HART_CUTOFF {
+            let e = (-0.5 * z * z).exp();
+            let numerator = (((((0.035_262_496_599_891_1 * z + 0.700_383_064_443_688) * z
+                + 6.373_962_203_531_65)
+                * z
+                + 33.912_866_078_383)
+                * z
+                + 112.079_291_497_871)
+                * z
+                + 221.213_596_169_931)
+                * z
+                + 220.206_867_912_376;
+            let denominator = ((((((0.088_388_347_648_318_4 * z + 1.755_667_163_182_64) * z
+                + 16.064_177_579_207)
+                * z
+                + 86.780_732_202_946_1)
+                * z
+                + 296.564_248_779_674)
+                * z
+                + 637.333_633_378_831)
+                * z
+                + 793.826_512_519_948)
+                * z
+                + 440.413_735_824_752;
+            e * numerator / denominator
+        } else {
+            // N(-z) = n(z) / (z + 1/(z + 2/(z + 3/(z + ...))))
+            let mut fraction = z;
+            for k in (1..=TAIL_FRACTION_TERMS).rev() {
+                fraction = z + f64::from(k) / fraction;
+            }
+            Self::norm_pdf(z) / fraction
+        };
+        if x > 0.0 { 1.0 - tail } else { tail }
     }
 
     /// Standard normal probability density function (PDF).
@@ -84,6 +166,8 @@ impl BlackScholes {
     /// - `time`: Time to expiration in years (T)
     /// - `vol`: Volatility (σ)
     ///
+    /// With a dividend yield q, pass the cost of carry `r - q` as `rate`.
+    ///
     /// # Returns
     /// The d1 parameter value
     #[must_use]
@@ -110,8 +194,8 @@ impl BlackScholes {
 
     /// Calculates the theoretical option price using Black-Scholes formula.
     ///
-    /// For calls: C = S·N(d1) - K·e^(-rT)·N(d2)
-    /// For puts:  P = K·e^(-rT)·N(-d2) - S·N(-d1)
+    /// For calls: C = S·e^(-qT)·N(d1) - K·e^(-rT)·N(d2)
+    /// For puts:  P = K·e^(-rT)·N(-d2) - S·e^(-qT)·N(-d1)
     ///
     /// # Arguments
     /// - `params`: Option parameters (spot, strike, time, rate, type)
@@ -129,35 +213,101 @@ impl BlackScholes {
         if vol <= 0.0 {
             // With zero volatility, option is worth intrinsic value
             let discount = (-params.risk_free_rate * params.time_to_expiry).exp();
+            let spot = params.spot * params.dividend_discount();
             return match params.option_type {
-                OptionType::Call => (params.spot - params.strike * discount).max(0.0),
-                OptionType::Put => (params.strike * discount - params.spot).max(0.0),
+                OptionType::Call => (spot - params.strike * discount).max(0.0),
+                OptionType::Put => (params.strike * discount - spot).max(0.0),
             };
         }
 
         let d1 = Self::d1(
             params.spot,
             params.strike,
-            params.risk_free_rate,
+            params.cost_of_carry(),
             params.time_to_expiry,
             vol,
         );
         let d2 = Self::d2(d1, vol, params.time_to_expiry);
         let discount = (-params.risk_free_rate * params.time_to_expiry).exp();
+        let spot = params.spot * params.dividend_discount();
 
         match params.option_type {
             OptionType::Call => {
-                params.spot * Self::norm_cdf(d1) - params.strike * discount * Self::norm_cdf(d2)
+                spot * Self::norm_cdf(d1) - params.strike * discount * Self::norm_cdf(d2)
             }
             OptionType::Put => {
-                params.strike * discount * Self::norm_cdf(-d2) - params.spot * Self::norm_cdf(-d1)
+                params.strike * discount * Self::norm_cdf(-d2) - spot * Self::norm_cdf(-d1)
             }
         }
     }
 
+    /// Prices a batch of options, for bulk revaluation.
+    ///
+    /// # Arguments
+    /// - `params`: Option parameters of each option
+    /// - `vols`: One volatility for all options, or one per option
+    ///
+    /// # Returns
+    /// - `Ok(prices)` in the order of `params`
+    /// - `Err(IVError::InvalidParams)` if the number of volatilities does not
+    ///   match the number of options
+    ///
+    /// # Example
+    /// ```
+    /// use orderbook_rs::orderbook::implied_volatility::{BlackScholes, IVParams};
+    ///
+    /// let options = [
+    ///     IVParams::call(100.0, 95.0, 0.25, 0.05),
+    ///     IVParams::put(100.0, 105.0, 0.25, 0.05),
+    /// ];
+    /// let prices = BlackScholes::price_batch(&options, 0.2).unwrap();
+    /// assert_eq!(prices[1], BlackScholes::price(&options[1], 0.2));
+    ///
+    /// let vols = vec![0.2, 0.3];
+    /// let prices = BlackScholes::price_batch(&options, &vols).unwrap();
+    /// assert_eq!(prices[1], BlackScholes::price(&options[1], 0.3));
+    /// ```
+    pub fn price_batch<'a>(
+        params: &[IVParams],
+        vols: impl Into<BatchVolatility<'a>>,
+    ) -> Result<Vec<f64>, IVError> {
+        let vols = vols.into();
+        vols.validate(params.len())?;
+        Ok(params
+            .iter()
+            .enumerate()
+            .map(|(index, params)| Self::price(params, vols.get(index)))
+            .collect())
+    }
+
+    /// Prices a batch of options on the rayon thread pool.
+    ///
+    /// Same results as [`BlackScholes::price_batch`], split across threads,
+    /// which pays off from a few thousand options.
+    ///
+    /// # Returns
+    /// - `Ok(prices)` in the order of `params`
+    /// - `Err(IVError::InvalidParams)` if the number of volatilities does not
+    ///   match the number of options
+    #[cfg(feature = "rayon")]
+    pub fn price_batch_parallel<'a>(
+        params: &[IVParams],
+        vols: impl Into<BatchVolatility<'a>>,
+    ) -> Result<Vec<f64>, IVError> {
+        use rayon::prelude::*;
+
+        let vols = vols.into();
+        vols.validate(params.len())?;
+        Ok(params
+            .par_iter()
+            .enumerate()
+            .map(|(index, params)| Self::price(params, vols.get(index)))
+            .collect())
+    }
+
     /// Calculates vega (∂price/∂σ) - sensitivity to volatility.
     ///
-    /// Vega = S · N'(d1) · √T
+    /// Vega = S · e^(-qT) · N'(d1) · √T
     ///
     /// Vega is always positive for both calls and puts.
     ///
@@ -176,17 +326,17 @@ impl BlackScholes {
         let d1 = Self::d1(
             params.spot,
             params.strike,
-            params.risk_free_rate,
+            params.cost_of_carry(),
             params.time_to_expiry,
             vol,
         );
-        params.spot * Self::norm_pdf(d1) * params.time_to_expiry.sqrt()
+        params.spot * params.dividend_discount() * Self::norm_pdf(d1) * params.time_to_expiry.sqrt()
     }
 
     /// Calculates delta (∂price/∂S) - sensitivity to underlying price.
     ///
-    /// For calls: Δ = N(d1)
-    /// For puts:  Δ = N(d1) - 1
+    /// For calls: Δ = e^(-qT) · N(d1)
+    /// For puts:  Δ = e^(-qT) · (N(d1) - 1)
     ///
     /// # Arguments
     /// - `params`: Option parameters
@@ -218,20 +368,22 @@ impl BlackScholes {
         let d1 = Self::d1(
             params.spot,
             params.strike,
-            params.risk_free_rate,
+            params.cost_of_carry(),
             params.time_to_expiry,
             vol,
         );
 
+        let dividend_discount = params.dividend_discount();
+
         match params.option_type {
-            OptionType::Call => Self::norm_cdf(d1),
-            OptionType::Put => Self::norm_cdf(d1) - 1.0,
+            OptionType::Call => dividend_discount * Self::norm_cdf(d1),
+            OptionType::Put => dividend_discount * (Self::norm_cdf(d1) - 1.0),
         }
     }
 
     /// Calculates gamma (∂²price/∂S²) - rate of change of delta.
     ///
-    /// Γ = N'(d1) / (S · σ · √T)
+    /// Γ = e^(-qT) · N'(d1) / (S · σ · √T)
     ///
     /// Gamma is always positive for both calls and puts.
     ///
@@ -250,11 +402,12 @@ impl BlackScholes {
         let d1 = Self::d1(
             params.spot,
             params.strike,
-            params.risk_free_rate,
+            params.cost_of_carry(),
             params.time_to_expiry,
             vol,
         );
-        Self::norm_pdf(d1) / (params.spot * vol * params.time_to_expiry.sqrt())
+        params.dividend_discount() * Self::norm_pdf(d1)
+            / (params.spot * vol * params.time_to_expiry.sqrt())
     }
 
     /// Calculates theta (∂price/∂T) - time decay.
@@ -276,28 +429,109 @@ impl BlackScholes {
         let d1 = Self::d1(
             params.spot,
             params.strike,
-            params.risk_free_rate,
+            params.cost_of_carry(),
             params.time_to_expiry,
             vol,
         );
         let d2 = Self::d2(d1, vol, params.time_to_expiry);
         let discount = (-params.risk_free_rate * params.time_to_expiry).exp();
         let sqrt_time = params.time_to_expiry.sqrt();
+        let spot = params.spot * params.dividend_discount();
 
-        let term1 = -params.spot * Self::norm_pdf(d1) * vol / (2.0 * sqrt_time);
+        let term1 = -spot * Self::norm_pdf(d1) * vol / (2.0 * sqrt_time);
 
         let theta_annual = match params.option_type {
             OptionType::Call => {
                 term1 - params.risk_free_rate * params.strike * discount * Self::norm_cdf(d2)
+                    + params.dividend_yield * spot * Self::norm_cdf(d1)
             }
             OptionType::Put => {
                 term1 + params.risk_free_rate * params.strike * discount * Self::norm_cdf(-d2)
+                    - params.dividend_yield * spot * Self::norm_cdf(-d1)
             }
         };
 
         // Convert to daily theta
         theta_annual / 365.0
     }
+
+    /// Calculates rho (∂price/∂r) - sensitivity to the risk-free rate.
+    ///
+    /// For calls: ρ = K · T · e^(-rT) · N(d2)
+    /// For puts:  ρ = -K · T · e^(-rT) · N(-d2)
+    ///
+    /// # Arguments
+    /// - `params`: Option parameters
+    /// - `vol`: Volatility
+    ///
+    /// # Returns
+    /// Rho value (change in price per unit change in the rate)
+    #[must_use]
+    pub fn rho(params: &IVParams, vol: f64) -> f64 {
+        if params.time_to_expiry <= 0.0 {
+            return 0.0;
+        }
+
+        let discounted_strike = params.strike
+            * params.time_to_expiry
+            * (-params.risk_free_rate * params.time_to_expiry).exp();
+
+        if vol <= 0.0 {
+            // With zero volatility, the strike is paid whenever the option is exercised
+            let forward_itm = params.spot
+                > params.strike * (-params.risk_free_rate * params.time_to_expiry).exp();
+            return match (params.option_type, forward_itm) {
+                (OptionType::Call, true) => discounted_strike,
+                (OptionType::Put, false) => -discounted_strike,
+                _ => 0.0,
+            };
+        }
+
+        let d1 = Self::d1(
+            params.spot,
+            params.strike,
+            params.cost_of_carry(),
+            params.time_to_expiry,
+            vol,
+        );
+        let d2 = Self::d2(d1, vol, params.time_to_expiry);
+
+        match params.option_type {
+            OptionType::Call => discounted_strike * Self::norm_cdf(d2),
+            OptionType::Put => -discounted_strike * Self::norm_cdf(-d2),
+        }
+    }
+
+    /// Calculates d1, d2 and all Greeks of an option at one volatility.
+    ///
+    /// d1 and d2 are only finite for a positive time to expiry and
+    /// volatility; the Greeks follow the conventions of their own functions.
+    ///
+    /// # Arguments
+    /// - `params`: Option parameters
+    /// - `vol`: Volatility
+    ///
+    /// # Returns
+    /// The diagnostics of the option at `vol`
+    #[must_use]
+    pub fn diagnostics(params: &IVParams, vol: f64) -> IVDiagnostics {
+        let d1 = Self::d1(
+            params.spot,
+            params.strike,
+            params.cost_of_carry(),
+            params.time_to_expiry,
+            vol,
+        );
+        IVDiagnostics {
+            d1,
+            d2: Self::d2(d1, vol, params.time_to_expiry),
+            delta: Self::delta(params, vol),
+            gamma: Self::gamma(params, vol),
+            vega: Self::vega(params, vol),
+            theta: Self::theta(params, vol),
+            rho: Self::rho(params, vol),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -310,8 +544,9 @@ mod tests {
     fn test_erf() {
         // Test known values
         assert!((BlackScholes::erf(0.0) - 0.0).abs() < TOLERANCE);
-        assert!((BlackScholes::erf(1.0) - 0.8427007929).abs() < 1e-5);
-        assert!((BlackScholes::erf(-1.0) + 0.8427007929).abs() < 1e-5);
+        assert!((BlackScholes::erf(1.0) - 0.842_700_792_949_715).abs() < 1e-14);
+        assert!((BlackScholes::erf(-1.0) + 0.842_700_792_949_715).abs() < 1e-14);
+        assert!((BlackScholes::erf(0.1) - 0.112_462_916_018_285).abs() < 1e-14);
     }
 
     #[test]
@@ -323,6 +558,30 @@ mod tests {
         assert!(BlackScholes::norm_cdf(10.0) > 1.0 - 1e-10);
     }
 
+    #[test]
+    fn test_norm_cdf_precision() {
+        // Reference values of the standard normal CDF
+        for (x, expected) in [
+            (-1.0, 0.158_655_253_931_457_05),
+            (-3.0, 1.349_898_031_630_094_6e-3),
+            (-6.0, 9.865_876_450_376_982e-10),
+            (-10.0, 7.619_853_024_160_527e-24),
+            (-20.0, 2.753_624_118_606_232e-89),
+            (1.5, 0.933_192_798_731_141_9),
+        ] {
+            let value = BlackScholes::norm_cdf(x);
+            assert!(
+                ((value - expected) / expected).abs() < 1e-13,
+                "N({x}) = {value}, expected {expected}"
+            );
+        }
+        assert_eq!(BlackScholes::norm_cdf(-40.0), 0.0);
+        assert!(
+            (BlackScholes::norm_cdf(-3.0) - BlackScholes::norm_cdf(-2.999_999_999)).abs() < 1e-11
+        );
+        assert_eq!(BlackScholes::norm_cdf(40.0), 1.0);
+    }
+
     #[test]
     fn test_norm_pdf() {
         // PDF at 0 = 1/√(2π) ≈ 0.3989
@@ -415,6 +674,140 @@ mod tests {
         assert!(theta < 0.0);
     }
 
+    #[test]
+    fn test_rho_matches_rate_bump() {
+        let vol = 0.3;
+        for params in [
+            IVParams::call(100.0, 105.0, 0.5, 0.04),
+            IVParams::put(100.0, 105.0, 0.5, 0.04),
+        ] {
+            let bump = 1e-5;
+            let mut up = params.clone();
+            up.risk_free_rate += bump;
+            let mut down = params.clone();
+            down.risk_free_rate -= bump;
+            let numerical =
+                (BlackScholes::price(&up, vol) - BlackScholes::price(&down, vol)) / (2.0 * bump);
+            assert!((BlackScholes::rho(&params, vol) - numerical).abs() < 1e-3);
+        }
+
+        let call = IVParams::call(100.0, 100.0, 0.25, 0.05);
+        let put = IVParams::put(100.0, 100.0, 0.25, 0.05);
+        assert!(BlackScholes::rho(&call, vol) > 0.0);
+        assert!(BlackScholes::rho(&put, vol) < 0.0);
+        assert_eq!(
+            BlackScholes::rho(&IVParams::call(100.0, 100.0, 0.0, 0.05), vol),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_dividend_yield_put_call_parity() {
+        // C - P = S·e^(-qT) - K·e^(-rT)
+        let vol = 0.2;
+        let call = IVParams::call(100.0, 95.0, 0.5, 0.05).with_dividend_yield(0.03);
+        let put = IVParams::put(100.0, 95.0, 0.5, 0.05).with_dividend_yield(0.03);
+        let parity = 100.0 * (-0.03f64 * 0.5).exp() - 95.0 * (-0.05f64 * 0.5).exp();
+        let diff = BlackScholes::price(&call, vol) - BlackScholes::price(&put, vol);
+        assert!((diff - parity).abs() < 1e-6);
+
+        // The dividend lowers calls and raises puts
+        let no_dividend = IVParams::call(100.0, 95.0, 0.5, 0.05);
+        assert!(BlackScholes::price(&call, vol) < BlackScholes::price(&no_dividend, vol));
+    }
+
+    #[test]
+    fn test_dividend_yield_greeks_match_finite_differences() {
+        let vol = 0.25;
+        for params in [
+            IVParams::call(100.0, 105.0, 0.75, 0.04).with_dividend_yield(0.02),
+            IVParams::put(100.0, 105.0, 0.75, 0.04).with_dividend_yield(0.02),
+        ] {
+            let h = 1e-4;
+            let price = |p: &IVParams, v: f64| BlackScholes::price(p, v);
+            let bumped = |f: &dyn Fn(&mut IVParams)| {
+                let mut p = params.clone();
+                f(&mut p);
+                p
+            };
+
+            let up = bumped(&|p| p.spot += h);
+            let down = bumped(&|p| p.spot -= h);
+            let delta = (price(&up, vol) - price(&down, vol)) / (2.0 * h);
+            assert!((BlackScholes::delta(&params, vol) - delta).abs() < 1e-5);
+
+            let gamma = (price(&up, vol) - 2.0 * price(&params, vol) + price(&down, vol)) / (h * h);
+            assert!((BlackScholes::gamma(&params, vol) - gamma).abs() < 1e-3);
+
+            let vega = (price(&params, vol + h) - price(&params, vol - h)) / (2.0 * h);
+            assert!((BlackScholes::vega(&params, vol) - vega).abs() < 1e-4);
+
+            let later = bumped(&|p| p.time_to_expiry -= h);
+            let earlier = bumped(&|p| p.time_to_expiry += h);
+            let theta = (price(&later, vol) - price(&earlier, vol)) / (2.0 * h) / 365.0;
+            assert!((BlackScholes::theta(&params, vol) - theta).abs() < 1e-5);
+
+            let up = bumped(&|p| p.risk_free_rate += h);
+            let down = bumped(&|p| p.risk_free_rate -= h);
+            let rho = (price(&up, vol) - price(&down, vol)) / (2.0 * h);
+            assert!((BlackScholes::rho(&params, vol) - rho).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_diagnostics_match_greeks() {
+        let params = IVParams::put(100.0, 95.0, 0.5, 0.04).with_dividend_yield(0.01);
+        let vol = 0.3;
+        let diagnostics = BlackScholes::diagnostics(&params, vol);
+
+        let d1 = ((100.0f64 / 95.0).ln() + (0.03 + 0.5 * vol * vol) * 0.5) / (vol * 0.5f64.sqrt());
+        assert!((diagnostics.d1 - d1).abs() < 1e-12);
+        assert!((diagnostics.d2 - (d1 - vol * 0.5f64.sqrt())).abs() < 1e-12);
+        assert_eq!(diagnostics.delta, BlackScholes::delta(&params, vol));
+        assert_eq!(diagnostics.gamma, BlackScholes::gamma(&params, vol));
+        assert_eq!(diagnostics.vega, BlackScholes::vega(&params, vol));
+        assert_eq!(diagnostics.theta, BlackScholes::theta(&params, vol));
+        assert_eq!(diagnostics.rho, BlackScholes::rho(&params, vol));
+    }
+
+    #[test]
+    fn test_price_batch() {
+        let options: Vec<IVParams> = (0..50)
+            .map(|i| {
+                let strike = 80.0 + f64::from(i);
+                if i % 2 == 0 {
+                    IVParams::call(100.0, strike, 0.5, 0.03)
+                } else {
+                    IVParams::put(100.0, strike, 0.5, 0.03)
+                }
+            })
+            .collect();
+        let vols: Vec<f64> = (0..50).map(|i| 0.15 + f64::from(i) * 0.005).collect();
+
+        let single = BlackScholes::price_batch(&options, 0.25).unwrap();
+        let per_option = BlackScholes::price_batch(&options, &vols).unwrap();
+        for (i, params) in options.iter().enumerate() {
+            assert_eq!(single[i], BlackScholes::price(params, 0.25));
+            assert_eq!(per_option[i], BlackScholes::price(params, vols[i]));
+        }
+
+        assert!(matches!(
+            BlackScholes::price_batch(&options, &vols[1..]),
+            Err(IVError::InvalidParams { .. })
+        ));
+        assert!(BlackScholes::price_batch(&[], 0.2).unwrap().is_empty());
+
+        #[cfg(feature = "rayon")]
+        {
+            assert_eq!(
+                BlackScholes::price_batch_parallel(&options, &vols).unwrap(),
+                per_option
+            );
+            assert!(BlackScholes::price_batch_parallel(&options, vols.as_slice()).is_ok());
+            assert!(BlackScholes::price_batch_parallel(&options[1..], &vols).is_err());
+        }
+    }
+
     #[test]
     fn test_price_at_expiry() {
         // At expiry, option is worth intrinsic value
@@ -8,6 +8,12 @@ pub enum IVError {
     /// No valid price available (empty book or no bid/ask).
     NoPriceAvailable,
 
+    /// No order book exists for the option symbol.
+    BookNotFound {
+        /// Symbol of the missing book.
+        symbol: String,
+    },
+
     /// Spread too wide for reliable calculation.
     SpreadTooWide {
         /// Current spread in basis points.
@@ -63,6 +69,9 @@ impl fmt::Display for IVError {
             IVError::NoPriceAvailable => {
                 write!(f, "no valid price available from order book")
             }
+            IVError::BookNotFound { symbol } => {
+                write!(f, "no order book for option symbol {symbol}")
+            }
             IVError::SpreadTooWide {
                 spread_bps,
                 threshold_bps,
@@ -124,6 +133,11 @@ mod tests {
         let err = IVError::NoPriceAvailable;
         assert_eq!(err.to_string(), "no valid price available from order book");
 
+        let err = IVError::BookNotFound {
+            symbol: "BTC-C-100".to_string(),
+        };
+        assert!(err.to_string().contains("BTC-C-100"));
+
         let err = IVError::SpreadTooWide {
             spread_bps: 600.0,
             threshold_bps: 500.0,
@@ -3,11 +3,12 @@
 //! This module provides methods on the OrderBook struct to calculate
 //! implied volatility from order book prices.
 
+use super::binomial::{model_price, model_vega};
 use super::black_scholes::BlackScholes;
 use super::error::IVError;
-use super::solver::{SolverConfig, solve_iv};
-use super::types::{IVParams, IVQuality, IVResult, PriceSource};
-use crate::orderbook::book::OrderBook;
+use super::solver::{SolverConfig, solve_iv, solve_iv_bisection};
+use super::types::{GreeksResult, IVParams, IVQuality, IVResult, PriceSource};
+use crate::orderbook::book::{DepthLevel, OrderBook};
 use pricelevel::Side;
 
 /// Threshold for high quality IV calculation (spread < 100 bps = 1%).
@@ -26,6 +27,22 @@ pub struct IVConfig {
     /// Price scale factor to convert u64 prices to f64.
     /// For example, if prices are in cents, use 100.0 to get dollars.
     pub price_scale: f64,
+    /// Whether to retry with bisection when Newton-Raphson fails to converge
+    /// or leaves the IV bounds (default: true).
+    pub bisection_fallback: bool,
+    /// Minimum quantity at both the best bid and the best ask for the quality
+    /// not to be downgraded (default: none).
+    pub min_top_of_book_quantity: Option<u64>,
+    /// Maximum age in milliseconds of the last price level change of the book
+    /// for the quality not to be downgraded (default: none).
+    pub max_book_age_ms: Option<u64>,
+    /// Maximum age in milliseconds of the last trade when pricing from
+    /// [`PriceSource::LastTrade`] for the quality not to be downgraded
+    /// (default: none).
+    pub max_last_trade_age_ms: Option<u64>,
+    /// Whether to attach the d1/d2 terms and Greeks at the solved volatility
+    /// to the result (default: false).
+    pub diagnostics: bool,
 }
 
 impl Default for IVConfig {
@@ -34,6 +51,11 @@ impl Default for IVConfig {
             solver: SolverConfig::default(),
             max_spread_bps: 1000.0,
             price_scale: 1.0,
+            bisection_fallback: true,
+            min_top_of_book_quantity: None,
+            max_book_age_ms: None,
+            max_last_trade_age_ms: None,
+            diagnostics: false,
         }
     }
 }
@@ -65,6 +87,41 @@ impl IVConfig {
         self.solver = solver;
         self
     }
+
+    /// Enables or disables the bisection fallback.
+    #[must_use]
+    pub fn with_bisection_fallback(mut self, bisection_fallback: bool) -> Self {
+        self.bisection_fallback = bisection_fallback;
+        self
+    }
+
+    /// Sets the minimum quantity expected at the best bid and ask.
+    #[must_use]
+    pub fn with_min_top_of_book_quantity(mut self, quantity: u64) -> Self {
+        self.min_top_of_book_quantity = Some(quantity);
+        self
+    }
+
+    /// Sets the maximum age of the last book update in milliseconds.
+    #[must_use]
+    pub fn with_max_book_age(mut self, max_age_ms: u64) -> Self {
+        self.max_book_age_ms = Some(max_age_ms);
+        self
+    }
+
+    /// Sets the maximum age of the last trade in milliseconds.
+    #[must_use]
+    pub fn with_max_last_trade_age(mut self, max_age_ms: u64) -> Self {
+        self.max_last_trade_age_ms = Some(max_age_ms);
+        self
+    }
+
+    /// Enables or disables the diagnostics of [`IVResult`].
+    #[must_use]
+    pub fn with_diagnostics(mut self, diagnostics: bool) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
 }
 
 impl<T> OrderBook<T>
@@ -76,6 +133,7 @@ where
     /// This method extracts the market price from the order book based on the
     /// specified price source, then uses Newton-Raphson to find the implied
     /// volatility that makes the Black-Scholes price equal to the market price.
+    /// If Newton-Raphson does not converge, the bisection solver is used instead.
     ///
     /// # Arguments
     /// - `params`: Option parameters (spot, strike, time, rate, type)
@@ -86,25 +144,29 @@ where
     /// - `Err(IVError)` if calculation fails
     ///
     /// # Example
-    /// ```ignore
+    /// ```
     /// use orderbook_rs::OrderBook;
-    /// use orderbook_rs::implied_volatility::{IVParams, OptionType, PriceSource};
+    /// use orderbook_rs::orderbook::implied_volatility::{
+    ///     ExerciseStyle, IVParams, OptionType, PriceSource,
+    /// };
+    /// use pricelevel::{OrderId, Side, TimeInForce};
     ///
     /// let book = OrderBook::<()>::new("AAPL-C-150");
-    /// // Add orders to the book...
+    /// let _ = book.add_limit_order(OrderId::new(), 2, 10, Side::Buy, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 3, 10, Side::Sell, TimeInForce::Gtc, None);
     ///
     /// let params = IVParams {
     ///     spot: 150.0,
     ///     strike: 155.0,
     ///     time_to_expiry: 30.0 / 365.0,
     ///     risk_free_rate: 0.05,
+    ///     dividend_yield: 0.0,
     ///     option_type: OptionType::Call,
+    ///     exercise_style: ExerciseStyle::European,
     /// };
     ///
-    /// match book.implied_volatility(&params, PriceSource::MidPrice) {
-    ///     Ok(result) => println!("IV: {:.2}%", result.iv_percent()),
-    ///     Err(e) => eprintln!("Failed to calculate IV: {}", e),
-    /// }
+    /// // The default configuration rejects the 40% spread of this book
+    /// assert!(book.implied_volatility(&params, PriceSource::MidPrice).is_err());
     /// ```
     pub fn implied_volatility(
         &self,
@@ -116,6 +178,18 @@ where
 
     /// Calculates implied volatility with custom configuration.
     ///
+    /// The spread of the book is checked against `config.max_spread_bps` and
+    /// graded into an [`IVQuality`], which is then downgraded one level for
+    /// each configured threshold the book misses: too little quantity at the
+    /// best bid or ask, no book update within `max_book_age_ms`, and, when
+    /// pricing from the last trade, no trade within `max_last_trade_age_ms`.
+    /// When `config.bisection_fallback` is set and
+    /// Newton-Raphson fails to converge or leaves the IV bounds, the bisection
+    /// solver is run with the same solver configuration, and the reported
+    /// iterations include those of both solvers. With `config.diagnostics`
+    /// the Black-Scholes d1/d2 terms and Greeks at the solved volatility are
+    /// attached to the result.
+    ///
     /// # Arguments
     /// - `params`: Option parameters
     /// - `price_source`: Price extraction method
@@ -124,6 +198,27 @@ where
     /// # Returns
     /// - `Ok(IVResult)` with calculated IV and metadata
     /// - `Err(IVError)` if calculation fails
+    ///
+    /// # Example
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use orderbook_rs::orderbook::implied_volatility::{IVConfig, IVParams, IVQuality, PriceSource};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// // Prices in cents: 5.40 / 5.50
+    /// let book = OrderBook::<()>::new("TEST-C-100");
+    /// let _ = book.add_limit_order(OrderId::new(), 540, 10, Side::Buy, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 550, 10, Side::Sell, TimeInForce::Gtc, None);
+    ///
+    /// let params = IVParams::call(100.0, 100.0, 0.25, 0.05);
+    /// let config = IVConfig::default().with_price_scale(100.0);
+    ///
+    /// let result = book
+    ///     .implied_volatility_with_config(&params, PriceSource::MidPrice, &config)
+    ///     .unwrap();
+    /// assert!(result.iv > 0.2 && result.iv < 0.3);
+    /// assert_eq!(result.quality, IVQuality::Medium);
+    /// ```
     pub fn implied_volatility_with_config(
         &self,
         params: &IVParams,
@@ -131,7 +226,8 @@ where
         config: &IVConfig,
     ) -> Result<IVResult, IVError> {
         // Extract price from order book
-        let (price, spread_bps) = self.extract_price_for_iv(price_source, config.price_scale)?;
+        let (mut price, spread_bps) =
+            self.extract_price_for_iv(price_source, config.price_scale)?;
 
         // Check spread threshold
         if spread_bps > config.max_spread_bps {
@@ -142,22 +238,116 @@ where
         }
 
         // Check if price is below intrinsic value
-        let intrinsic = params.intrinsic_value();
+        let intrinsic = params.carry_adjusted_intrinsic_value();
+        if price_source == PriceSource::ClampedMid {
+            price = price.max(intrinsic);
+        }
         if price < intrinsic - config.solver.tolerance {
             return Err(IVError::PriceBelowIntrinsic { price, intrinsic });
         }
 
-        // Determine quality based on spread
-        let quality = spread_to_quality(spread_bps);
+        // Determine quality based on spread, depth and staleness
+        let quality = self.grade_iv_quality(spread_bps, price_source, config);
+
+        // Solve for IV using Newton-Raphson, falling back to bisection
+        let (iv, iterations) = match solve_iv(params, price, &config.solver) {
+            Ok(solution) => solution,
+            Err(err) if config.bisection_fallback => {
+                let newton_iterations = match err {
+                    IVError::ConvergenceFailure { iterations, .. } => iterations,
+                    IVError::VolatilityOutOfBounds { .. } => 0,
+                    _ => return Err(err),
+                };
+                let (iv, bisection_iterations) = solve_iv_bisection(params, price, &config.solver)?;
+                (iv, newton_iterations + bisection_iterations)
+            }
+            Err(err) => return Err(err),
+        };
+
+        let result = IVResult::new(iv, price, spread_bps, iterations, quality);
+        if config.diagnostics {
+            return Ok(result.with_diagnostics(BlackScholes::diagnostics(params, iv)));
+        }
+        Ok(result)
+    }
 
-        // Solve for IV using Newton-Raphson
-        let (iv, iterations) = solve_iv(params, price, &config.solver)?;
+    /// Calculates the implied volatility of an option from order book prices
+    /// along with all of its Greeks at that volatility.
+    ///
+    /// The volatility is solved once, as in [`OrderBook::implied_volatility`],
+    /// and delta, gamma, vega, theta and rho are evaluated at it with the
+    /// Black-Scholes formulas, also for American options.
+    ///
+    /// # Arguments
+    /// - `params`: Option parameters (spot, strike, time, rate, type)
+    /// - `price_source`: How to derive price from bid/ask (MidPrice, WeightedMid, LastTrade)
+    ///
+    /// # Returns
+    /// - `Ok(GreeksResult)` with the IV result and the Greeks
+    /// - `Err(IVError)` if the IV calculation fails
+    ///
+    /// # Example
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use orderbook_rs::orderbook::implied_volatility::{IVConfig, IVParams, PriceSource};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("TEST-C-100");
+    /// let _ = book.add_limit_order(OrderId::new(), 540, 10, Side::Buy, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 550, 10, Side::Sell, TimeInForce::Gtc, None);
+    ///
+    /// let params = IVParams::call(100.0, 100.0, 0.25, 0.05);
+    /// let config = IVConfig::default().with_price_scale(100.0);
+    /// let greeks = book
+    ///     .option_greeks_with_config(&params, PriceSource::MidPrice, &config)
+    ///     .unwrap();
+    ///
+    /// assert!(greeks.delta > 0.5 && greeks.delta < 0.6);
+    /// assert!(greeks.rho > 0.0);
+    /// ```
+    pub fn option_greeks(
+        &self,
+        params: &IVParams,
+        price_source: PriceSource,
+    ) -> Result<GreeksResult, IVError> {
+        self.option_greeks_with_config(params, price_source, &IVConfig::default())
+    }
 
-        Ok(IVResult::new(iv, price, spread_bps, iterations, quality))
+    /// Calculates the implied volatility and Greeks with custom configuration.
+    ///
+    /// # Arguments
+    /// - `params`: Option parameters
+    /// - `price_source`: Price extraction method
+    /// - `config`: Custom IV calculation configuration
+    ///
+    /// # Returns
+    /// - `Ok(GreeksResult)` with the IV result and the Greeks
+    /// - `Err(IVError)` if the IV calculation fails
+    pub fn option_greeks_with_config(
+        &self,
+        params: &IVParams,
+        price_source: PriceSource,
+        config: &IVConfig,
+    ) -> Result<GreeksResult, IVError> {
+        let iv = self.implied_volatility_with_config(params, price_source, config)?;
+        let vol = iv.iv;
+
+        Ok(GreeksResult {
+            delta: BlackScholes::delta(params, vol),
+            gamma: BlackScholes::gamma(params, vol),
+            vega: BlackScholes::vega(params, vol),
+            theta: BlackScholes::theta(params, vol),
+            rho: BlackScholes::rho(params, vol),
+            iv,
+        })
     }
 
     /// Extracts the market price from the order book.
     ///
+    /// [`PriceSource::ClampedMid`] yields the plain mid here, since clamping
+    /// needs the option parameters. [`PriceSource::BidOnly`] and
+    /// [`PriceSource::AskOnly`] fail without a quote on their side.
+    ///
     /// # Arguments
     /// - `source`: Price extraction method
     /// - `price_scale`: Scale factor to convert u64 to f64
@@ -165,7 +355,7 @@ where
     /// # Returns
     /// - `Ok((price, spread_bps))`: Extracted price and spread in basis points
     /// - `Err(IVError::NoPriceAvailable)`: If no valid price can be extracted
-    fn extract_price_for_iv(
+    pub(super) fn extract_price_for_iv(
         &self,
         source: PriceSource,
         price_scale: f64,
@@ -187,7 +377,7 @@ where
                 };
 
                 let price = match source {
-                    PriceSource::MidPrice => mid,
+                    PriceSource::MidPrice | PriceSource::ClampedMid => mid,
                     PriceSource::WeightedMid => {
                         self.weighted_mid_price_for_iv(bid, ask, price_scale)
                     }
@@ -195,10 +385,17 @@ where
                         .last_trade_price()
                         .map(|p| p as f64 / price_scale)
                         .unwrap_or(mid),
+                    PriceSource::DepthWeighted { levels } => self
+                        .depth_weighted_mid_price_for_iv(levels, price_scale)
+                        .unwrap_or(mid),
+                    PriceSource::BidOnly => bid_f,
+                    PriceSource::AskOnly => ask_f,
                 };
 
                 Ok((price, spread_bps))
             }
+            (Some(_), None) if source == PriceSource::AskOnly => Err(IVError::NoPriceAvailable),
+            (None, Some(_)) if source == PriceSource::BidOnly => Err(IVError::NoPriceAvailable),
             (Some(bid), None) => {
                 // Only bid available - use bid price with high spread indicator
                 let price = bid as f64 / price_scale;
@@ -213,6 +410,46 @@ where
         }
     }
 
+    /// Grades the spread into an [`IVQuality`] and downgrades it once per
+    /// depth or staleness threshold of `config` that the book misses.
+    fn grade_iv_quality(
+        &self,
+        spread_bps: f64,
+        price_source: PriceSource,
+        config: &IVConfig,
+    ) -> IVQuality {
+        let now = self.clock.now_millis();
+        let is_stale = |last: Option<u64>, max_age: u64| {
+            last.is_none_or(|last| now.saturating_sub(last) > max_age)
+        };
+
+        let thin = config.min_top_of_book_quantity.is_some_and(|min_quantity| {
+            let bid_qty = self
+                .best_bid()
+                .map_or(0, |bid| self.quantity_at_price(bid, Side::Buy));
+            let ask_qty = self
+                .best_ask()
+                .map_or(0, |ask| self.quantity_at_price(ask, Side::Sell));
+            bid_qty.min(ask_qty) < min_quantity
+        });
+        let stale_book = config
+            .max_book_age_ms
+            .is_some_and(|max_age| is_stale(self.last_update_time(), max_age));
+        // Without trades the last trade source prices from the mid
+        let stale_trade = price_source == PriceSource::LastTrade
+            && self.last_trade_price().is_some()
+            && config
+                .max_last_trade_age_ms
+                .is_some_and(|max_age| is_stale(self.last_trade_time(), max_age));
+
+        [thin, stale_book, stale_trade]
+            .into_iter()
+            .filter(|&missed| missed)
+            .fold(spread_to_quality(spread_bps), |quality, _| {
+                downgrade_quality(quality)
+            })
+    }
+
     /// Calculates volume-weighted mid price.
     ///
     /// Weights the mid price by the quantities available at best bid and ask.
@@ -238,6 +475,28 @@ where
         }
     }
 
+    /// Calculates the depth-weighted mid price over the best `levels` levels.
+    ///
+    /// The volume-weighted average prices of both sides are weighted by the
+    /// depth of the opposite side, as in the weighted mid. Returns `None` when
+    /// either side has no quantity.
+    fn depth_weighted_mid_price_for_iv(&self, levels: usize, price_scale: f64) -> Option<f64> {
+        let (bids, asks) = self.depth(levels.max(1));
+        let side_vwap = |side: &[DepthLevel]| {
+            let quantity: u64 = side.iter().map(|&(_, quantity, _)| quantity).sum();
+            let notional: f64 = side
+                .iter()
+                .map(|&(price, quantity, _)| price as f64 * quantity as f64)
+                .sum();
+            (quantity > 0).then(|| (notional / quantity as f64 / price_scale, quantity))
+        };
+
+        let (bid_vwap, bid_qty) = side_vwap(&bids)?;
+        let (ask_vwap, ask_qty) = side_vwap(&asks)?;
+        let total_qty = (bid_qty + ask_qty) as f64;
+        Some(bid_vwap * (ask_qty as f64 / total_qty) + ask_vwap * (bid_qty as f64 / total_qty))
+    }
+
     /// Gets the total quantity at a specific price level.
     fn quantity_at_price(&self, price: u64, side: Side) -> u64 {
         let price_levels = match side {
@@ -251,10 +510,11 @@ where
             .unwrap_or(0)
     }
 
-    /// Calculates the theoretical option price using Black-Scholes.
+    /// Calculates the theoretical option price.
     ///
-    /// This is a convenience method that uses the Black-Scholes model
-    /// to price an option given the parameters and volatility.
+    /// This is a convenience method that uses the Black-Scholes model, or the
+    /// binomial tree for American options, to price an option given the
+    /// parameters and volatility.
     ///
     /// # Arguments
     /// - `params`: Option parameters
@@ -264,11 +524,13 @@ where
     /// Theoretical option price
     #[must_use]
     pub fn theoretical_price(params: &IVParams, volatility: f64) -> f64 {
-        BlackScholes::price(params, volatility)
+        model_price(params, volatility)
     }
 
     /// Calculates vega (sensitivity to volatility) for an option.
     ///
+    /// American options use the finite-difference vega of the binomial tree.
+    ///
     /// # Arguments
     /// - `params`: Option parameters
     /// - `volatility`: Current volatility estimate
@@ -277,7 +539,7 @@ where
     /// Vega value (change in price per unit change in volatility)
     #[must_use]
     pub fn option_vega(params: &IVParams, volatility: f64) -> f64 {
-        BlackScholes::vega(params, volatility)
+        model_vega(params, volatility)
     }
 
     /// Calculates delta (sensitivity to underlying price) for an option.
@@ -318,6 +580,19 @@ where
     pub fn option_theta(params: &IVParams, volatility: f64) -> f64 {
         BlackScholes::theta(params, volatility)
     }
+
+    /// Calculates rho (sensitivity to the risk-free rate) for an option.
+    ///
+    /// # Arguments
+    /// - `params`: Option parameters
+    /// - `volatility`: Current volatility estimate
+    ///
+    /// # Returns
+    /// Rho value
+    #[must_use]
+    pub fn option_rho(params: &IVParams, volatility: f64) -> f64 {
+        BlackScholes::rho(params, volatility)
+    }
 }
 
 /// Converts spread in basis points to IV quality indicator.
@@ -331,11 +606,22 @@ fn spread_to_quality(spread_bps: f64) -> IVQuality {
     }
 }
 
+/// Lowers an IV quality indicator by one level.
+fn downgrade_quality(quality: IVQuality) -> IVQuality {
+    match quality {
+        IVQuality::High => IVQuality::Medium,
+        IVQuality::Medium | IVQuality::Low => IVQuality::Low,
+        IVQuality::Interpolated => IVQuality::Interpolated,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::ManualClock;
     use pricelevel::{OrderId, TimeInForce};
+    use std::sync::Arc;
 
     fn create_test_book() -> OrderBook<()> {
         let book = OrderBook::<()>::new("TEST-OPT");
@@ -466,6 +752,68 @@ mod tests {
         assert!(matches!(result, Err(IVError::SpreadTooWide { .. })));
     }
 
+    #[test]
+    fn test_implied_volatility_falls_back_to_bisection() {
+        let book = OrderBook::<()>::new("TEST-OPT");
+        let _ = book.add_limit_order(OrderId::new(), 540, 100, Side::Buy, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 550, 100, Side::Sell, TimeInForce::Gtc, None);
+
+        let params = IVParams::call(100.0, 100.0, 0.25, 0.05);
+        // An unreachable vega threshold keeps Newton-Raphson on its coarse
+        // multiplicative steps, so it cannot reach the tolerance
+        let mut solver = SolverConfig::default().with_max_iterations(60);
+        solver.min_vega = f64::MAX;
+        assert!(matches!(
+            solve_iv(&params, 5.45, &solver),
+            Err(IVError::ConvergenceFailure { .. })
+        ));
+
+        let config = IVConfig::default()
+            .with_price_scale(100.0)
+            .with_solver(solver);
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::MidPrice, &config)
+            .unwrap();
+        let expected = book
+            .implied_volatility_with_config(
+                &params,
+                PriceSource::MidPrice,
+                &IVConfig::default().with_price_scale(100.0),
+            )
+            .unwrap();
+        assert!((result.iv - expected.iv).abs() < 1e-6);
+        assert!(result.iterations > 60);
+        assert_eq!(result.quality, IVQuality::Medium);
+
+        let no_fallback = config.with_bisection_fallback(false);
+        let result =
+            book.implied_volatility_with_config(&params, PriceSource::MidPrice, &no_fallback);
+        assert!(matches!(result, Err(IVError::ConvergenceFailure { .. })));
+    }
+
+    #[test]
+    fn test_implied_volatility_one_sided_book_is_too_wide() {
+        let book = OrderBook::<()>::new("TEST-OPT");
+        let _ = book.add_limit_order(OrderId::new(), 545, 100, Side::Buy, TimeInForce::Gtc, None);
+
+        let params = IVParams::call(100.0, 100.0, 0.25, 0.05);
+        let config = IVConfig::default().with_price_scale(100.0);
+        let result = book.implied_volatility_with_config(&params, PriceSource::MidPrice, &config);
+        assert!(matches!(
+            result,
+            Err(IVError::SpreadTooWide { threshold_bps, .. }) if threshold_bps == 1000.0
+        ));
+
+        let result = book
+            .implied_volatility_with_config(
+                &params,
+                PriceSource::MidPrice,
+                &config.with_max_spread(f64::INFINITY),
+            )
+            .unwrap();
+        assert_eq!(result.quality, IVQuality::Low);
+    }
+
     #[test]
     fn test_spread_to_quality() {
         assert_eq!(spread_to_quality(50.0), IVQuality::High);
@@ -482,8 +830,11 @@ mod tests {
         let config = IVConfig::new()
             .with_max_spread(2000.0)
             .with_price_scale(100.0)
-            .with_solver(SolverConfig::default().with_max_iterations(50));
+            .with_solver(SolverConfig::default().with_max_iterations(50))
+            .with_bisection_fallback(false);
 
+        assert!(IVConfig::default().bisection_fallback);
+        assert!(!config.bisection_fallback);
         assert!((config.max_spread_bps - 2000.0).abs() < 1e-10);
         assert!((config.price_scale - 100.0).abs() < 1e-10);
         assert_eq!(config.solver.max_iterations, 50);
@@ -518,6 +869,65 @@ mod tests {
         assert!(theta < 0.0);
     }
 
+    #[test]
+    fn test_option_greeks_from_book() {
+        let book = OrderBook::<()>::new("TEST-OPT");
+        let _ = book.add_limit_order(OrderId::new(), 540, 100, Side::Buy, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 550, 100, Side::Sell, TimeInForce::Gtc, None);
+
+        let params = IVParams::put(100.0, 100.0, 0.25, 0.05);
+        let config = IVConfig::default().with_price_scale(100.0);
+        let greeks = book
+            .option_greeks_with_config(&params, PriceSource::MidPrice, &config)
+            .unwrap();
+        let iv = book
+            .implied_volatility_with_config(&params, PriceSource::MidPrice, &config)
+            .unwrap();
+
+        assert_eq!(greeks.iv.iv, iv.iv);
+        assert_eq!(greeks.iv.quality, iv.quality);
+        assert_eq!(greeks.delta, OrderBook::<()>::option_delta(&params, iv.iv));
+        assert_eq!(greeks.gamma, OrderBook::<()>::option_gamma(&params, iv.iv));
+        assert_eq!(greeks.vega, OrderBook::<()>::option_vega(&params, iv.iv));
+        assert_eq!(greeks.theta, OrderBook::<()>::option_theta(&params, iv.iv));
+        assert_eq!(greeks.rho, OrderBook::<()>::option_rho(&params, iv.iv));
+        assert!(greeks.delta < 0.0);
+        assert!(greeks.rho < 0.0);
+
+        let empty = OrderBook::<()>::new("EMPTY");
+        assert!(matches!(
+            empty.option_greeks(&params, PriceSource::MidPrice),
+            Err(IVError::NoPriceAvailable)
+        ));
+    }
+
+    #[test]
+    fn test_implied_volatility_diagnostics() {
+        let book = OrderBook::<()>::new("TEST-OPT");
+        let _ = book.add_limit_order(OrderId::new(), 540, 100, Side::Buy, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 550, 100, Side::Sell, TimeInForce::Gtc, None);
+
+        let params = IVParams::call(100.0, 100.0, 0.25, 0.05);
+        let config = IVConfig::default().with_price_scale(100.0);
+        let plain = book
+            .implied_volatility_with_config(&params, PriceSource::MidPrice, &config)
+            .unwrap();
+        assert!(plain.diagnostics.is_none());
+
+        let config = config.with_diagnostics(true);
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::MidPrice, &config)
+            .unwrap();
+        let diagnostics = result.diagnostics.unwrap();
+        assert_eq!(result.iv, plain.iv);
+        assert_eq!(diagnostics, BlackScholes::diagnostics(&params, result.iv));
+        assert_eq!(
+            diagnostics.delta,
+            OrderBook::<()>::option_delta(&params, result.iv)
+        );
+        assert!(diagnostics.d1 > diagnostics.d2);
+    }
+
     #[test]
     fn test_one_sided_market_bid_only() {
         let book = OrderBook::<()>::new("TEST-OPT");
@@ -547,4 +957,246 @@ mod tests {
         assert!((price - 4.70).abs() < 0.01);
         assert!((spread_bps - 10_000.0).abs() < 1.0); // 100% spread indicator
     }
+
+    fn clocked_book(clock: &Arc<ManualClock>) -> OrderBook<()> {
+        let mut book = OrderBook::<()>::new("TEST-OPT");
+        book.set_clock(clock.clone());
+        // 5.40 / 5.44, about 74 bps wide
+        let _ = book.add_limit_order(OrderId::new(), 540, 20, Side::Buy, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 544, 5, Side::Sell, TimeInForce::Gtc, None);
+        book
+    }
+
+    #[test]
+    fn test_quality_downgraded_by_thin_top_of_book() {
+        let clock = Arc::new(ManualClock::new(1_000));
+        let book = clocked_book(&clock);
+        let params = IVParams::call(100.0, 100.0, 0.25, 0.05);
+        let config = IVConfig::default().with_price_scale(100.0);
+
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::MidPrice, &config)
+            .unwrap();
+        assert_eq!(result.quality, IVQuality::High);
+
+        let deep = config.clone().with_min_top_of_book_quantity(5);
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::MidPrice, &deep)
+            .unwrap();
+        assert_eq!(result.quality, IVQuality::High);
+
+        // The ask only shows 5 contracts
+        let thin = config.with_min_top_of_book_quantity(10);
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::MidPrice, &thin)
+            .unwrap();
+        assert_eq!(result.quality, IVQuality::Medium);
+    }
+
+    #[test]
+    fn test_quality_downgraded_by_stale_book() {
+        let clock = Arc::new(ManualClock::new(1_000));
+        let book = clocked_book(&clock);
+        assert_eq!(book.last_update_time(), Some(1_000));
+
+        let params = IVParams::call(100.0, 100.0, 0.25, 0.05);
+        let config = IVConfig::default()
+            .with_price_scale(100.0)
+            .with_max_book_age(5_000);
+
+        clock.advance(5_000);
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::MidPrice, &config)
+            .unwrap();
+        assert_eq!(result.quality, IVQuality::High);
+
+        clock.advance(1);
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::MidPrice, &config)
+            .unwrap();
+        assert_eq!(result.quality, IVQuality::Medium);
+
+        // Any level change refreshes the book
+        let _ = book.add_limit_order(OrderId::new(), 539, 1, Side::Buy, TimeInForce::Gtc, None);
+        assert_eq!(book.last_update_time(), Some(6_001));
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::MidPrice, &config)
+            .unwrap();
+        assert_eq!(result.quality, IVQuality::High);
+    }
+
+    #[test]
+    fn test_quality_downgraded_by_stale_last_trade() {
+        let clock = Arc::new(ManualClock::new(1_000));
+        let book = clocked_book(&clock);
+        let params = IVParams::call(100.0, 100.0, 0.25, 0.05);
+        let config = IVConfig::default()
+            .with_price_scale(100.0)
+            .with_max_last_trade_age(60_000)
+            .with_min_top_of_book_quantity(1);
+
+        // Without trades the mid is used and staleness does not apply
+        assert_eq!(book.last_trade_time(), None);
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::LastTrade, &config)
+            .unwrap();
+        assert_eq!(result.quality, IVQuality::High);
+
+        clock.advance(500);
+        let _ = book.submit_market_order(OrderId::new(), 1, Side::Buy);
+        assert_eq!(book.last_trade_time(), Some(1_500));
+
+        clock.advance(60_001);
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::LastTrade, &config)
+            .unwrap();
+        assert_eq!(result.quality, IVQuality::Medium);
+
+        // Other price sources ignore the last trade
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::MidPrice, &config)
+            .unwrap();
+        assert_eq!(result.quality, IVQuality::High);
+
+        // Thin and stale: downgraded twice
+        let thin = config.with_min_top_of_book_quantity(100);
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::LastTrade, &thin)
+            .unwrap();
+        assert_eq!(result.quality, IVQuality::Low);
+    }
+
+    #[test]
+    fn test_downgrade_quality() {
+        assert_eq!(downgrade_quality(IVQuality::High), IVQuality::Medium);
+        assert_eq!(downgrade_quality(IVQuality::Medium), IVQuality::Low);
+        assert_eq!(downgrade_quality(IVQuality::Low), IVQuality::Low);
+        assert_eq!(
+            downgrade_quality(IVQuality::Interpolated),
+            IVQuality::Interpolated
+        );
+    }
+
+    fn ladder_book() -> OrderBook<()> {
+        let book = OrderBook::<()>::new("TEST-OPT");
+        for (price, quantity, side) in [
+            (450, 10, Side::Buy),
+            (440, 30, Side::Buy),
+            (430, 100, Side::Buy),
+            (470, 20, Side::Sell),
+            (490, 20, Side::Sell),
+        ] {
+            let _ = book.add_limit_order(
+                OrderId::new(),
+                price,
+                quantity,
+                side,
+                TimeInForce::Gtc,
+                None,
+            );
+        }
+        book
+    }
+
+    #[test]
+    fn test_extract_price_depth_weighted() {
+        let book = ladder_book();
+
+        // One level matches the weighted mid
+        let (one_level, _) = book
+            .extract_price_for_iv(PriceSource::DepthWeighted { levels: 1 }, 100.0)
+            .unwrap();
+        let (weighted, _) = book
+            .extract_price_for_iv(PriceSource::WeightedMid, 100.0)
+            .unwrap();
+        assert!((one_level - weighted).abs() < 1e-12);
+        let (no_levels, _) = book
+            .extract_price_for_iv(PriceSource::DepthWeighted { levels: 0 }, 100.0)
+            .unwrap();
+        assert!((no_levels - weighted).abs() < 1e-12);
+
+        // Bids: 40 @ 4.425, asks: 40 @ 4.80, equal weights
+        let (price, spread_bps) = book
+            .extract_price_for_iv(PriceSource::DepthWeighted { levels: 2 }, 100.0)
+            .unwrap();
+        assert!((price - 4.6125).abs() < 1e-9);
+        assert!((spread_bps - 20.0 / 460.0 * 10_000.0).abs() < 1e-6);
+
+        // Bids: 140 @ 4.3357, asks: 40 @ 4.80, weighted towards the ask
+        let (price, _) = book
+            .extract_price_for_iv(PriceSource::DepthWeighted { levels: 5 }, 100.0)
+            .unwrap();
+        let bid_vwap = (450.0 * 10.0 + 440.0 * 30.0 + 430.0 * 100.0) / 140.0 / 100.0;
+        let expected = bid_vwap * (40.0 / 180.0) + 4.80 * (140.0 / 180.0);
+        assert!((price - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_extract_price_bid_and_ask_only() {
+        let book = create_test_book();
+        let (bid, _) = book
+            .extract_price_for_iv(PriceSource::BidOnly, 100.0)
+            .unwrap();
+        let (ask, _) = book
+            .extract_price_for_iv(PriceSource::AskOnly, 100.0)
+            .unwrap();
+        assert!((bid - 4.50).abs() < 1e-12);
+        assert!((ask - 4.70).abs() < 1e-12);
+
+        let bids_only = OrderBook::<()>::new("TEST-OPT");
+        let _ =
+            bids_only.add_limit_order(OrderId::new(), 450, 10, Side::Buy, TimeInForce::Gtc, None);
+        assert!(
+            bids_only
+                .extract_price_for_iv(PriceSource::BidOnly, 100.0)
+                .is_ok()
+        );
+        assert!(matches!(
+            bids_only.extract_price_for_iv(PriceSource::AskOnly, 100.0),
+            Err(IVError::NoPriceAvailable)
+        ));
+    }
+
+    #[test]
+    fn test_bid_and_ask_only_bracket_mid_iv() {
+        let book = create_test_book();
+        let params = IVParams::call(100.0, 100.0, 0.25, 0.05);
+        let config = IVConfig::default().with_price_scale(100.0);
+        let iv = |source| {
+            book.implied_volatility_with_config(&params, source, &config)
+                .unwrap()
+                .iv
+        };
+
+        assert!(iv(PriceSource::BidOnly) < iv(PriceSource::MidPrice));
+        assert!(iv(PriceSource::MidPrice) < iv(PriceSource::AskOnly));
+    }
+
+    #[test]
+    fn test_clamped_mid_never_below_intrinsic() {
+        // Mid of 4.60 below the intrinsic 5.00 of a deep put
+        let book = create_test_book();
+        let params = IVParams::put(95.0, 100.0, 0.1, 0.0);
+        let config = IVConfig::default().with_price_scale(100.0);
+
+        assert!(matches!(
+            book.implied_volatility_with_config(&params, PriceSource::MidPrice, &config),
+            Err(IVError::PriceBelowIntrinsic { .. })
+        ));
+        let result = book
+            .implied_volatility_with_config(&params, PriceSource::ClampedMid, &config)
+            .unwrap();
+        assert!((result.price_used - 5.0).abs() < 1e-12);
+
+        // Above intrinsic the clamp is a plain mid
+        let params = IVParams::call(100.0, 100.0, 0.25, 0.05);
+        let clamped = book
+            .implied_volatility_with_config(&params, PriceSource::ClampedMid, &config)
+            .unwrap();
+        let mid = book
+            .implied_volatility_with_config(&params, PriceSource::MidPrice, &config)
+            .unwrap();
+        assert_eq!(clamped.iv, mid.iv);
+        assert_eq!(clamped.price_used, mid.price_used);
+    }
 }
@@ -2,7 +2,8 @@
 //!
 //! This module provides functionality to calculate implied volatility (IV)
 //! from option prices extracted from the order book using Black-Scholes
-//! model inversion via Newton-Raphson numerical method.
+//! model inversion via Newton-Raphson numerical method, with bisection as
+//! a fallback.
 //!
 //! # Overview
 //!
@@ -21,33 +22,90 @@
 //!
 //! Since there's no analytical solution to invert Black-Scholes, we use
 //! Newton-Raphson root finding which converges quickly (3-5 iterations)
-//! because vega (∂price/∂σ) is always positive.
+//! because vega (∂price/∂σ) is always positive. Third-order Householder
+//! steps, Jäckel's non-iterative "Let's Be Rational" inversion or plain
+//! bisection can be selected instead with [`SolverMethod`].
+//!
+//! # American Options
+//!
+//! Options with [`ExerciseStyle::American`] are priced on a Cox-Ross-Rubinstein
+//! binomial tree ([`BinomialTree`]) instead, and the solvers use a
+//! finite-difference vega for them.
+//!
+//! # Rates from Put-Call Parity
+//!
+//! The risk-free rate and the forward of an expiry can be implied from the
+//! call and put books of its strikes with [`ImpliedForward`], then applied to
+//! the [`IVParams`] of the options.
+//!
+//! # Volatility Surface
+//!
+//! Smiles solved across the strike ladders of several expiries assemble into
+//! a [`VolSurface`], which interpolates in total variance, finds strikes by
+//! delta and checks the quotes for butterfly and calendar arbitrage. Each
+//! expiry can be smoothed with a raw SVI fit ([`SviParams::fit`]).
 //!
 //! # Example
 //!
-//! ```ignore
-//! use orderbook_rs::implied_volatility::{IVParams, OptionType, PriceSource};
+//! ```
+//! use orderbook_rs::OrderBook;
+//! use orderbook_rs::orderbook::implied_volatility::{
+//!     ExerciseStyle, IVParams, OptionType, PriceSource,
+//! };
+//! use pricelevel::{OrderId, Side, TimeInForce};
+//!
+//! let book = OrderBook::<()>::new("ETH-C-3000");
+//! let _ = book.add_limit_order(OrderId::new(), 136, 10, Side::Buy, TimeInForce::Gtc, None);
+//! let _ = book.add_limit_order(OrderId::new(), 138, 10, Side::Sell, TimeInForce::Gtc, None);
 //!
 //! let params = IVParams {
 //!     spot: 3000.0,
 //!     strike: 3000.0,
 //!     time_to_expiry: 30.0 / 365.0,
 //!     risk_free_rate: 0.0,
+//!     dividend_yield: 0.0,
 //!     option_type: OptionType::Call,
+//!     exercise_style: ExerciseStyle::European,
 //! };
 //!
-//! let result = book.implied_volatility(&params, PriceSource::MidPrice)?;
-//! println!("IV: {:.2}%", result.iv * 100.0);
+//! let result = book.implied_volatility(&params, PriceSource::MidPrice).unwrap();
+//! assert!(result.iv > 0.35 && result.iv < 0.45);
 //! ```
+//!
+//! If Newton-Raphson fails to converge, [`OrderBook::implied_volatility`]
+//! falls back to bisection automatically (see [`IVConfig`]).
+//!
+//! [`OrderBook::implied_volatility`]: crate::OrderBook::implied_volatility
 
+mod binomial;
 mod black_scholes;
 mod error;
 mod integration;
+mod parity;
+mod portfolio;
+mod rational;
+mod smile;
 mod solver;
+mod svi;
+mod term_structure;
 mod types;
+mod vol_surface;
 
-pub use black_scholes::BlackScholes;
+pub use binomial::BinomialTree;
+pub use black_scholes::{BatchVolatility, BlackScholes};
 pub use error::IVError;
 pub use integration::IVConfig;
-pub use solver::{SolverConfig, solve_iv, solve_iv_bisection};
-pub use types::{IVParams, IVQuality, IVResult, OptionType, PriceSource};
+pub use parity::{ImpliedForward, ParityQuote};
+pub use portfolio::{
+    OptionPosition, PortfolioConfig, PortfolioGreeks, PositionFailure, PositionGreeks,
+    portfolio_greeks,
+};
+pub use smile::{IVSmile, SmileConfig, SmileFailure, SmilePoint};
+pub use solver::{SolverConfig, SolverMethod, solve_iv, solve_iv_bisection};
+pub use svi::{SVI_MIN_POINTS, SviFit, SviParams, SviResidual};
+pub use term_structure::{TermPoint, TermStructure};
+pub use types::{
+    DEFAULT_BINOMIAL_STEPS, ExerciseStyle, GreeksResult, IVDiagnostics, IVParams, IVQuality,
+    IVResult, OptionType, PriceSource,
+};
+pub use vol_surface::{ArbitrageViolation, SurfacePoint, SurfaceSlice, VolSurface};
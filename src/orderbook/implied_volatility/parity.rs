@@ -0,0 +1,346 @@
+//! Implied forward and financing rate from put-call parity.
+//!
+//! For European options of one expiry, put-call parity gives
+//! `C - P = D·(F - K)` at every strike, with `D = e^(-rT)` the discount
+//! factor and `F` the forward of the underlying. Regressing `C - P` on the
+//! strike across the quoted strikes recovers `D` from the slope and `F` from
+//! the intercept, so the rate and carry no longer have to be guessed.
+
+use super::error::IVError;
+use super::integration::IVConfig;
+use super::types::{IVParams, PriceSource};
+use crate::orderbook::book::OrderBook;
+use serde::{Deserialize, Serialize};
+
+/// Call and put prices quoted at one strike.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ParityQuote {
+    /// Strike price in price units.
+    pub strike: f64,
+    /// Call price in price units.
+    pub call_price: f64,
+    /// Put price in price units.
+    pub put_price: f64,
+}
+
+impl ParityQuote {
+    /// Creates a quote from call and put prices.
+    #[must_use]
+    pub fn new(strike: f64, call_price: f64, put_price: f64) -> Self {
+        Self {
+            strike,
+            call_price,
+            put_price,
+        }
+    }
+
+    /// Takes the call and put prices of a strike from their order books.
+    ///
+    /// Prices are extracted as for implied volatility, so `config` sets the
+    /// price scale and the widest spread accepted on either book.
+    ///
+    /// # Errors
+    /// [`IVError::NoPriceAvailable`] if a book is empty, or
+    /// [`IVError::SpreadTooWide`] if a spread exceeds `config.max_spread_bps`
+    pub fn from_books<T>(
+        strike: f64,
+        call: &OrderBook<T>,
+        put: &OrderBook<T>,
+        price_source: PriceSource,
+        config: &IVConfig,
+    ) -> Result<Self, IVError>
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let price = |book: &OrderBook<T>| {
+            let (price, spread_bps) =
+                book.extract_price_for_iv(price_source, config.price_scale)?;
+            if spread_bps > config.max_spread_bps {
+                return Err(IVError::SpreadTooWide {
+                    spread_bps,
+                    threshold_bps: config.max_spread_bps,
+                });
+            }
+            Ok(price)
+        };
+        Ok(Self::new(strike, price(call)?, price(put)?))
+    }
+
+    /// Call minus put price.
+    #[must_use]
+    pub fn parity_value(&self) -> f64 {
+        self.call_price - self.put_price
+    }
+}
+
+/// Forward and financing rate implied by put-call parity at one expiry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImpliedForward {
+    /// Time to expiration in years.
+    pub expiry: f64,
+    /// Implied forward price of the underlying in price units.
+    pub forward: f64,
+    /// Implied discount factor `e^(-rT)`.
+    pub discount_factor: f64,
+    /// Implied continuously compounded risk-free rate.
+    pub risk_free_rate: f64,
+    /// Coefficient of determination of the regression.
+    pub r_squared: f64,
+    /// Quoted minus fitted `C - P` at each strike, in quote order.
+    pub residuals: Vec<(f64, f64)>,
+}
+
+impl ImpliedForward {
+    /// Regresses `C - P` on the strike across `quotes`.
+    ///
+    /// # Returns
+    /// `None` with fewer than two distinct strikes, a non-positive expiry, or
+    /// quotes that imply a non-positive discount factor or forward
+    ///
+    /// # Example
+    /// ```
+    /// use orderbook_rs::orderbook::implied_volatility::{ImpliedForward, ParityQuote};
+    ///
+    /// // Quotes generated with F = 102 and r = 4% over one year
+    /// let discount = (-0.04f64).exp();
+    /// let quotes: Vec<ParityQuote> = [90.0, 100.0, 110.0]
+    ///     .iter()
+    ///     .map(|&strike| ParityQuote::new(strike, 10.0 + discount * (102.0 - strike), 10.0))
+    ///     .collect();
+    ///
+    /// let implied = ImpliedForward::from_quotes(&quotes, 1.0).unwrap();
+    /// assert!((implied.forward - 102.0).abs() < 1e-9);
+    /// assert!((implied.risk_free_rate - 0.04).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn from_quotes(quotes: &[ParityQuote], expiry: f64) -> Option<Self> {
+        if quotes.len() < 2 || expiry <= 0.0 || expiry.is_nan() {
+            return None;
+        }
+        let n = quotes.len() as f64;
+        let mean_strike = quotes.iter().map(|quote| quote.strike).sum::<f64>() / n;
+        let mean_value = quotes.iter().map(ParityQuote::parity_value).sum::<f64>() / n;
+        let (mut covariance, mut strike_variance, mut value_variance) = (0.0, 0.0, 0.0);
+        for quote in quotes {
+            let dk = quote.strike - mean_strike;
+            let dv = quote.parity_value() - mean_value;
+            covariance += dk * dv;
+            strike_variance += dk * dk;
+            value_variance += dv * dv;
+        }
+        if strike_variance <= 0.0 {
+            return None;
+        }
+
+        let slope = covariance / strike_variance;
+        let intercept = mean_value - slope * mean_strike;
+        let discount_factor = -slope;
+        if discount_factor <= 0.0 || !discount_factor.is_finite() {
+            return None;
+        }
+        let forward = intercept / discount_factor;
+        if forward <= 0.0 || !forward.is_finite() {
+            return None;
+        }
+
+        let residuals: Vec<(f64, f64)> = quotes
+            .iter()
+            .map(|quote| {
+                let fitted = intercept + slope * quote.strike;
+                (quote.strike, quote.parity_value() - fitted)
+            })
+            .collect();
+        let residual_variance: f64 = residuals.iter().map(|(_, r)| r * r).sum();
+        let r_squared = if value_variance > 0.0 {
+            1.0 - residual_variance / value_variance
+        } else {
+            1.0
+        };
+
+        Some(Self {
+            expiry,
+            forward,
+            discount_factor,
+            risk_free_rate: -discount_factor.ln() / expiry,
+            r_squared,
+            residuals,
+        })
+    }
+
+    /// Continuous dividend or borrow yield implied by the forward against `spot`,
+    /// `r - ln(F / S) / T`.
+    #[must_use]
+    pub fn dividend_yield(&self, spot: f64) -> f64 {
+        self.risk_free_rate - (self.forward / spot).ln() / self.expiry
+    }
+
+    /// Sets the risk-free rate of `params` to the implied rate, and its
+    /// dividend yield so that its spot carries to the implied forward.
+    #[must_use]
+    pub fn apply_to(&self, params: IVParams) -> IVParams {
+        let dividend_yield = self.dividend_yield(params.spot);
+        IVParams {
+            risk_free_rate: self.risk_free_rate,
+            ..params
+        }
+        .with_dividend_yield(dividend_yield)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::implied_volatility::BlackScholes;
+    use crate::orderbook::manager::{BookManager, BookManagerStd};
+    use pricelevel::{OrderId, Side, TimeInForce};
+
+    fn quotes(spot: f64, rate: f64, dividend_yield: f64, expiry: f64) -> Vec<ParityQuote> {
+        [80.0, 90.0, 100.0, 110.0, 120.0]
+            .iter()
+            .map(|&strike| {
+                let vol = 0.3 - strike * 0.001;
+                let call =
+                    IVParams::call(spot, strike, expiry, rate).with_dividend_yield(dividend_yield);
+                let put =
+                    IVParams::put(spot, strike, expiry, rate).with_dividend_yield(dividend_yield);
+                ParityQuote::new(
+                    strike,
+                    BlackScholes::price(&call, vol),
+                    BlackScholes::price(&put, vol),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_recovers_rate_and_forward() {
+        let implied = ImpliedForward::from_quotes(&quotes(100.0, 0.05, 0.02, 0.5), 0.5).unwrap();
+        let forward = 100.0 * (0.03f64 * 0.5).exp();
+
+        assert!((implied.forward - forward).abs() < 1e-6);
+        assert!((implied.risk_free_rate - 0.05).abs() < 1e-6);
+        assert!((implied.discount_factor - (-0.025f64).exp()).abs() < 1e-8);
+        assert!((implied.dividend_yield(100.0) - 0.02).abs() < 1e-6);
+        assert!(implied.r_squared > 0.999_999);
+        assert!(implied.residuals.iter().all(|(_, r)| r.abs() < 1e-6));
+
+        let params = implied.apply_to(IVParams::call(100.0, 100.0, 0.5, 0.0));
+        assert!((params.risk_free_rate - 0.05).abs() < 1e-6);
+        assert!((params.dividend_yield - 0.02).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_noisy_quotes_report_residuals() {
+        let mut quotes = quotes(100.0, 0.03, 0.0, 1.0);
+        quotes[2].call_price += 0.05;
+        let implied = ImpliedForward::from_quotes(&quotes, 1.0).unwrap();
+
+        assert!((implied.risk_free_rate - 0.03).abs() < 1e-3);
+        assert!(implied.r_squared < 1.0);
+        let (strike, worst) = implied
+            .residuals
+            .iter()
+            .copied()
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .unwrap();
+        assert_eq!(strike, 100.0);
+        assert!(worst > 0.0);
+    }
+
+    #[test]
+    fn test_degenerate_quotes() {
+        let quote = ParityQuote::new(100.0, 5.0, 4.0);
+        assert!(ImpliedForward::from_quotes(&[quote], 1.0).is_none());
+        assert!(ImpliedForward::from_quotes(&[quote, quote], 1.0).is_none());
+        let quotes = quotes(100.0, 0.03, 0.0, 1.0);
+        assert!(ImpliedForward::from_quotes(&quotes, 0.0).is_none());
+
+        // C - P rising with the strike implies a negative discount factor
+        let inverted = [
+            ParityQuote::new(90.0, 1.0, 10.0),
+            ParityQuote::new(110.0, 10.0, 1.0),
+        ];
+        assert!(ImpliedForward::from_quotes(&inverted, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_quote_from_books() {
+        let call = OrderBook::<()>::new("C-100");
+        let put = OrderBook::<()>::new("P-100");
+        let config = IVConfig::default().with_price_scale(100.0);
+        assert!(matches!(
+            ParityQuote::from_books(100.0, &call, &put, PriceSource::MidPrice, &config),
+            Err(IVError::NoPriceAvailable)
+        ));
+
+        let _ = call.add_limit_order(OrderId::new(), 540, 10, Side::Buy, TimeInForce::Gtc, None);
+        let _ = call.add_limit_order(OrderId::new(), 550, 10, Side::Sell, TimeInForce::Gtc, None);
+        let _ = put.add_limit_order(OrderId::new(), 300, 10, Side::Buy, TimeInForce::Gtc, None);
+        let _ = put.add_limit_order(OrderId::new(), 310, 10, Side::Sell, TimeInForce::Gtc, None);
+
+        let quote =
+            ParityQuote::from_books(100.0, &call, &put, PriceSource::MidPrice, &config).unwrap();
+        assert!((quote.call_price - 5.45).abs() < 1e-12);
+        assert!((quote.put_price - 3.05).abs() < 1e-12);
+        assert!((quote.parity_value() - 2.4).abs() < 1e-12);
+
+        let tight = config.with_max_spread(100.0);
+        assert!(matches!(
+            ParityQuote::from_books(100.0, &call, &put, PriceSource::MidPrice, &tight),
+            Err(IVError::SpreadTooWide { .. })
+        ));
+    }
+
+    #[test]
+    fn test_manager_implied_forward() {
+        let mut manager = BookManagerStd::<()>::new();
+        for quote in quotes(100.0, 0.04, 0.0, 0.5) {
+            for (kind, price) in [("C", quote.call_price), ("P", quote.put_price)] {
+                let symbol = format!("{kind}-{}", quote.strike);
+                let cents = (price * 100.0).round() as u64;
+                manager.add_book(&symbol);
+                let book = manager.get_book(&symbol).unwrap();
+                let _ = book.add_limit_order(
+                    OrderId::new(),
+                    cents - 1,
+                    10,
+                    Side::Buy,
+                    TimeInForce::Gtc,
+                    None,
+                );
+                let _ = book.add_limit_order(
+                    OrderId::new(),
+                    cents + 1,
+                    10,
+                    Side::Sell,
+                    TimeInForce::Gtc,
+                    None,
+                );
+            }
+        }
+        let strikes = [
+            ("C-80", "P-80", 80.0),
+            ("C-90", "P-90", 90.0),
+            ("C-100", "P-100", 100.0),
+            ("C-110", "P-110", 110.0),
+            ("C-120", "P-120", 120.0),
+            ("C-130", "P-130", 130.0),
+        ];
+        let config = IVConfig::default()
+            .with_price_scale(100.0)
+            .with_max_spread(f64::INFINITY);
+
+        let implied = manager
+            .implied_forward(0.5, &strikes, PriceSource::MidPrice, &config)
+            .unwrap();
+        assert_eq!(implied.residuals.len(), 5);
+        assert!((implied.risk_free_rate - 0.04).abs() < 2e-3);
+        assert!((implied.forward - 100.0 * (0.02f64).exp()).abs() < 0.05);
+
+        assert!(
+            manager
+                .implied_forward(0.5, &strikes[4..], PriceSource::MidPrice, &config)
+                .is_none()
+        );
+    }
+}
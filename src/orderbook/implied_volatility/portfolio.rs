@@ -0,0 +1,520 @@
+//! Net Greeks of an option portfolio solved from order books.
+//!
+//! Every option position is priced from its own order book and the spot of
+//! its underlying from the underlying's book. The IV and Greeks of each
+//! position are solved, scaled by the signed position size and summed into
+//! the net exposures a desk hedges against.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::error::IVError;
+use super::integration::IVConfig;
+use super::types::{ExerciseStyle, GreeksResult, IVParams, IVQuality, OptionType, PriceSource};
+use crate::orderbook::book::OrderBook;
+
+/// Position in one option contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionPosition {
+    /// Symbol of the option book.
+    pub symbol: String,
+    /// Symbol of the book of the underlying.
+    pub underlying: String,
+    /// Signed number of contracts, negative for short positions.
+    pub quantity: f64,
+    /// Strike price in price units.
+    pub strike: f64,
+    /// Time to expiration in years.
+    pub time_to_expiry: f64,
+    /// Option type (Call or Put).
+    pub option_type: OptionType,
+}
+
+impl OptionPosition {
+    /// Creates a new option position.
+    #[must_use]
+    pub fn new(
+        symbol: impl Into<String>,
+        underlying: impl Into<String>,
+        quantity: f64,
+        strike: f64,
+        time_to_expiry: f64,
+        option_type: OptionType,
+    ) -> Self {
+        Self {
+            symbol: symbol.into(),
+            underlying: underlying.into(),
+            quantity,
+            strike,
+            time_to_expiry,
+            option_type,
+        }
+    }
+}
+
+/// Configuration of a portfolio Greeks calculation.
+#[derive(Debug, Clone)]
+pub struct PortfolioConfig {
+    /// IV calculation configuration applied to every option book.
+    pub iv: IVConfig,
+    /// How to derive the option price of each book.
+    pub price_source: PriceSource,
+    /// Scale factor converting the prices of the underlying books to price
+    /// units (default: 1.0).
+    pub spot_price_scale: f64,
+    /// Risk-free interest rate (annualized).
+    pub risk_free_rate: f64,
+    /// Continuous dividend or borrow yield of the underlyings (annualized).
+    pub dividend_yield: f64,
+    /// Exercise style of the options.
+    pub exercise_style: ExerciseStyle,
+    /// Units of the underlying per contract (default: 1.0).
+    pub contract_multiplier: f64,
+}
+
+impl Default for PortfolioConfig {
+    fn default() -> Self {
+        Self {
+            iv: IVConfig::default(),
+            price_source: PriceSource::default(),
+            spot_price_scale: 1.0,
+            risk_free_rate: 0.0,
+            dividend_yield: 0.0,
+            exercise_style: ExerciseStyle::European,
+            contract_multiplier: 1.0,
+        }
+    }
+}
+
+impl PortfolioConfig {
+    /// Creates a new portfolio configuration with default values.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the IV calculation configuration.
+    #[must_use]
+    pub fn with_iv_config(mut self, iv: IVConfig) -> Self {
+        self.iv = iv;
+        self
+    }
+
+    /// Sets the price source of the option books.
+    #[must_use]
+    pub fn with_price_source(mut self, price_source: PriceSource) -> Self {
+        self.price_source = price_source;
+        self
+    }
+
+    /// Sets the price scale factor of the underlying books.
+    #[must_use]
+    pub fn with_spot_price_scale(mut self, spot_price_scale: f64) -> Self {
+        self.spot_price_scale = spot_price_scale;
+        self
+    }
+
+    /// Sets the risk-free interest rate.
+    #[must_use]
+    pub fn with_risk_free_rate(mut self, risk_free_rate: f64) -> Self {
+        self.risk_free_rate = risk_free_rate;
+        self
+    }
+
+    /// Sets the continuous dividend or borrow yield.
+    #[must_use]
+    pub fn with_dividend_yield(mut self, dividend_yield: f64) -> Self {
+        self.dividend_yield = dividend_yield;
+        self
+    }
+
+    /// Sets the exercise style.
+    #[must_use]
+    pub fn with_exercise_style(mut self, exercise_style: ExerciseStyle) -> Self {
+        self.exercise_style = exercise_style;
+        self
+    }
+
+    /// Sets the contract multiplier.
+    #[must_use]
+    pub fn with_contract_multiplier(mut self, contract_multiplier: f64) -> Self {
+        self.contract_multiplier = contract_multiplier;
+        self
+    }
+
+    /// Option parameters of a position at a spot price.
+    #[must_use]
+    pub fn params(&self, position: &OptionPosition, spot: f64) -> IVParams {
+        IVParams::new(
+            spot,
+            position.strike,
+            position.time_to_expiry,
+            self.risk_free_rate,
+            position.option_type,
+        )
+        .with_dividend_yield(self.dividend_yield)
+        .with_exercise_style(self.exercise_style)
+    }
+}
+
+/// Greeks solved for one position, per contract.
+#[derive(Debug, Clone)]
+pub struct PositionGreeks {
+    /// The position.
+    pub position: OptionPosition,
+    /// Spot price of the underlying in price units.
+    pub spot: f64,
+    /// Signed size in units of the underlying, the quantity times the
+    /// contract multiplier.
+    pub size: f64,
+    /// IV and Greeks of one unit of the underlying.
+    pub greeks: GreeksResult,
+}
+
+/// Position whose Greeks could not be solved.
+#[derive(Debug, Clone)]
+pub struct PositionFailure {
+    /// The position.
+    pub position: OptionPosition,
+    /// Why the Greeks could not be solved.
+    pub error: IVError,
+}
+
+/// Net Greeks of an option portfolio.
+///
+/// The net Greeks sum `quantity × contract_multiplier × greek` over the
+/// solved positions, so failed positions are missing from them.
+#[derive(Debug, Clone, Default)]
+pub struct PortfolioGreeks {
+    /// Net delta, in units of the underlying.
+    pub delta: f64,
+    /// Net gamma.
+    pub gamma: f64,
+    /// Net vega, per unit of volatility.
+    pub vega: f64,
+    /// Net daily theta.
+    pub theta: f64,
+    /// Net rho, per unit of rate.
+    pub rho: f64,
+    /// Solved positions, in the order they were given.
+    pub positions: Vec<PositionGreeks>,
+    /// Positions that could not be solved, in the order they were given.
+    pub failures: Vec<PositionFailure>,
+    /// Symbols of the solved positions whose IV quality is not
+    /// [`IVQuality::High`].
+    pub degraded: Vec<String>,
+}
+
+impl PortfolioGreeks {
+    /// Solves the Greeks of every position and aggregates them.
+    ///
+    /// `books` looks up the option and underlying books by symbol. Missing
+    /// books fail with [`IVError::BookNotFound`], and underlying books
+    /// without a mid or last trade price with [`IVError::NoPriceAvailable`].
+    #[must_use]
+    pub fn solve<'a, T>(
+        positions: &[OptionPosition],
+        books: impl Fn(&str) -> Option<&'a OrderBook<T>>,
+        config: &PortfolioConfig,
+    ) -> Self
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let book = |symbol: &str| {
+            books(symbol).ok_or_else(|| IVError::BookNotFound {
+                symbol: symbol.to_string(),
+            })
+        };
+        let mut spots: HashMap<&str, Result<f64, IVError>> = HashMap::new();
+        let mut portfolio = Self::default();
+
+        for position in positions {
+            let spot = spots
+                .entry(position.underlying.as_str())
+                .or_insert_with(|| {
+                    let underlying = book(&position.underlying)?;
+                    underlying
+                        .mid_price()
+                        .or_else(|| underlying.last_trade_price().map(|price| price as f64))
+                        .map(|price| price / config.spot_price_scale)
+                        .ok_or(IVError::NoPriceAvailable)
+                })
+                .clone();
+            let solved = spot.and_then(|spot| {
+                let greeks = book(&position.symbol)?.option_greeks_with_config(
+                    &config.params(position, spot),
+                    config.price_source,
+                    &config.iv,
+                )?;
+                Ok((spot, greeks))
+            });
+
+            match solved {
+                Ok((spot, greeks)) => portfolio.add(position.clone(), spot, greeks, config),
+                Err(error) => portfolio.failures.push(PositionFailure {
+                    position: position.clone(),
+                    error,
+                }),
+            }
+        }
+        portfolio
+    }
+
+    fn add(
+        &mut self,
+        position: OptionPosition,
+        spot: f64,
+        greeks: GreeksResult,
+        config: &PortfolioConfig,
+    ) {
+        let size = position.quantity * config.contract_multiplier;
+        self.delta += size * greeks.delta;
+        self.gamma += size * greeks.gamma;
+        self.vega += size * greeks.vega;
+        self.theta += size * greeks.theta;
+        self.rho += size * greeks.rho;
+        if greeks.iv.quality != IVQuality::High {
+            self.degraded.push(position.symbol.clone());
+        }
+        self.positions.push(PositionGreeks {
+            position,
+            spot,
+            size,
+            greeks,
+        });
+    }
+
+    /// Net delta per underlying symbol, the quantity of each underlying to
+    /// sell to be delta neutral.
+    #[must_use]
+    pub fn delta_by_underlying(&self) -> BTreeMap<String, f64> {
+        let mut deltas = BTreeMap::new();
+        for solved in &self.positions {
+            *deltas
+                .entry(solved.position.underlying.clone())
+                .or_insert(0.0) += solved.size * solved.greeks.delta;
+        }
+        deltas
+    }
+
+    /// Whether every position was solved.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Whether any solved position has an IV quality below
+    /// [`IVQuality::High`].
+    #[must_use]
+    pub fn is_degraded(&self) -> bool {
+        !self.degraded.is_empty()
+    }
+}
+
+/// Solves the Greeks of an option portfolio from its option and underlying
+/// books, see [`PortfolioGreeks::solve`].
+///
+/// # Example
+/// ```
+/// use orderbook_rs::OrderBook;
+/// use orderbook_rs::orderbook::implied_volatility::{
+///     IVConfig, OptionPosition, OptionType, PortfolioConfig, portfolio_greeks,
+/// };
+/// use pricelevel::{OrderId, Side, TimeInForce};
+///
+/// let spot = OrderBook::<()>::new("XYZ");
+/// let _ = spot.add_limit_order(OrderId::new(), 9_999, 10, Side::Buy, TimeInForce::Gtc, None);
+/// let _ = spot.add_limit_order(OrderId::new(), 10_001, 10, Side::Sell, TimeInForce::Gtc, None);
+/// let call = OrderBook::<()>::new("XYZ-C-100");
+/// let _ = call.add_limit_order(OrderId::new(), 540, 10, Side::Buy, TimeInForce::Gtc, None);
+/// let _ = call.add_limit_order(OrderId::new(), 544, 10, Side::Sell, TimeInForce::Gtc, None);
+///
+/// let positions = [OptionPosition::new("XYZ-C-100", "XYZ", -10.0, 100.0, 0.25, OptionType::Call)];
+/// let config = PortfolioConfig::new()
+///     .with_iv_config(IVConfig::default().with_price_scale(100.0))
+///     .with_spot_price_scale(100.0)
+///     .with_risk_free_rate(0.05);
+/// let books = |symbol: &str| [&spot, &call].into_iter().find(|book| book.symbol() == symbol);
+/// let greeks = portfolio_greeks(&positions, books, &config);
+///
+/// // Short calls: buy the underlying to hedge
+/// assert!(greeks.is_complete());
+/// assert!(greeks.delta < -5.0 && greeks.delta > -6.0);
+/// ```
+#[must_use]
+pub fn portfolio_greeks<'a, T>(
+    positions: &[OptionPosition],
+    books: impl Fn(&str) -> Option<&'a OrderBook<T>>,
+    config: &PortfolioConfig,
+) -> PortfolioGreeks
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    PortfolioGreeks::solve(positions, books, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::implied_volatility::BlackScholes;
+    use crate::orderbook::manager::{BookManager, BookManagerStd};
+    use pricelevel::{OrderId, Side, TimeInForce};
+
+    const VOL: f64 = 0.25;
+    const EXPIRY: f64 = 0.5;
+
+    fn quote(manager: &BookManagerStd<()>, symbol: &str, bid: u64, ask: u64) {
+        let book = manager.get_book(symbol).unwrap();
+        let _ = book.add_limit_order(OrderId::new(), bid, 10, Side::Buy, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), ask, 10, Side::Sell, TimeInForce::Gtc, None);
+    }
+
+    fn config() -> PortfolioConfig {
+        PortfolioConfig::new()
+            .with_iv_config(IVConfig::default().with_price_scale(100.0))
+            .with_spot_price_scale(100.0)
+            .with_risk_free_rate(0.03)
+    }
+
+    /// Underlyings quoted in cents around their spot, with one-tick wide
+    /// option books around the Black-Scholes price of each position
+    fn setup_manager(positions: &[OptionPosition], spots: &[(&str, f64)]) -> BookManagerStd<()> {
+        let mut manager = BookManagerStd::new();
+        for &(symbol, spot) in spots {
+            manager.add_book(symbol);
+            let cents = (spot * 100.0) as u64;
+            quote(&manager, symbol, cents - 1, cents + 1);
+        }
+        for position in positions {
+            let spot = spots
+                .iter()
+                .find(|(symbol, _)| *symbol == position.underlying)
+                .unwrap()
+                .1;
+            let price = BlackScholes::price(&config().params(position, spot), VOL);
+            let cents = (price * 100.0).round() as u64;
+            manager.add_book(&position.symbol);
+            quote(&manager, &position.symbol, cents, cents + 1);
+        }
+        manager
+    }
+
+    fn positions() -> Vec<OptionPosition> {
+        vec![
+            OptionPosition::new("XYZ-C-100", "XYZ", 10.0, 100.0, EXPIRY, OptionType::Call),
+            OptionPosition::new("XYZ-P-95", "XYZ", -20.0, 95.0, EXPIRY, OptionType::Put),
+            OptionPosition::new("ABC-C-50", "ABC", -5.0, 50.0, EXPIRY, OptionType::Call),
+        ]
+    }
+
+    #[test]
+    fn test_portfolio_greeks_aggregate_positions() {
+        let positions = positions();
+        let manager = setup_manager(&positions, &[("XYZ", 100.0), ("ABC", 50.0)]);
+        let greeks = manager.portfolio_greeks(&positions, &config());
+
+        assert!(greeks.is_complete());
+        assert!(!greeks.is_degraded());
+        assert_eq!(greeks.positions.len(), 3);
+
+        let mut expected_delta = 0.0;
+        let mut expected_vega = 0.0;
+        for solved in &greeks.positions {
+            assert!((solved.greeks.iv.iv - VOL).abs() < 0.01);
+            let params = config().params(&solved.position, solved.spot);
+            expected_delta += solved.position.quantity * BlackScholes::delta(&params, VOL);
+            expected_vega += solved.position.quantity * BlackScholes::vega(&params, VOL);
+        }
+        assert!((greeks.delta - expected_delta).abs() < 0.05);
+        assert!((greeks.vega - expected_vega).abs() / expected_vega.abs() < 0.05);
+
+        let sum: f64 = greeks
+            .positions
+            .iter()
+            .map(|solved| solved.size * solved.greeks.gamma)
+            .sum();
+        assert!((greeks.gamma - sum).abs() < 1e-12);
+
+        let deltas = greeks.delta_by_underlying();
+        assert_eq!(deltas.len(), 2);
+        assert!(deltas["ABC"] < 0.0);
+        // Long calls and short puts are both long the underlying
+        assert!(deltas["XYZ"] > 0.0);
+        assert!((deltas.values().sum::<f64>() - greeks.delta).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_portfolio_greeks_contract_multiplier() {
+        let positions = positions();
+        let manager = setup_manager(&positions, &[("XYZ", 100.0), ("ABC", 50.0)]);
+        let single = manager.portfolio_greeks(&positions, &config());
+        let hundred =
+            manager.portfolio_greeks(&positions, &config().with_contract_multiplier(100.0));
+
+        assert!((hundred.delta - single.delta * 100.0).abs() < 1e-9);
+        assert!((hundred.theta - single.theta * 100.0).abs() < 1e-9);
+        assert!((hundred.rho - single.rho * 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_portfolio_greeks_failures_and_degraded() {
+        let mut positions = positions();
+        let mut manager = setup_manager(&positions, &[("XYZ", 100.0), ("ABC", 50.0)]);
+        // Wide but within the spread limit: solved with a degraded quality
+        manager.add_book("XYZ-C-110");
+        quote(&manager, "XYZ-C-110", 300, 320);
+        positions.push(OptionPosition::new(
+            "XYZ-C-110",
+            "XYZ",
+            1.0,
+            110.0,
+            EXPIRY,
+            OptionType::Call,
+        ));
+        // Missing option book and underlying without quotes
+        positions.push(OptionPosition::new(
+            "XYZ-C-120",
+            "XYZ",
+            1.0,
+            120.0,
+            EXPIRY,
+            OptionType::Call,
+        ));
+        manager.add_book("DEF");
+        positions.push(OptionPosition::new(
+            "ABC-C-50",
+            "DEF",
+            1.0,
+            50.0,
+            EXPIRY,
+            OptionType::Call,
+        ));
+
+        let greeks = manager.portfolio_greeks(&positions, &config());
+
+        assert_eq!(greeks.positions.len(), 4);
+        assert_eq!(greeks.degraded, vec!["XYZ-C-110".to_string()]);
+        assert!(greeks.is_degraded());
+        assert!(!greeks.is_complete());
+        assert_eq!(greeks.failures.len(), 2);
+        assert!(matches!(
+            &greeks.failures[0].error,
+            IVError::BookNotFound { symbol } if symbol == "XYZ-C-120"
+        ));
+        assert!(matches!(
+            greeks.failures[1].error,
+            IVError::NoPriceAvailable
+        ));
+    }
+
+    #[test]
+    fn test_portfolio_greeks_free_function_matches_manager() {
+        let positions = positions();
+        let manager = setup_manager(&positions, &[("XYZ", 100.0), ("ABC", 50.0)]);
+        let from_manager = manager.portfolio_greeks(&positions, &config());
+        let from_fn = portfolio_greeks(&positions, |symbol| manager.get_book(symbol), &config());
+
+        assert_eq!(from_manager.delta, from_fn.delta);
+        assert_eq!(from_manager.vega, from_fn.vega);
+        assert!(portfolio_greeks::<()>(&[], |_| None, &config()).is_complete());
+    }
+}
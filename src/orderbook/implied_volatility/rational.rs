@@ -0,0 +1,649 @@
+//! Peter Jäckel's "Let's Be Rational" implied volatility inversion.
+//!
+//! The price is normalised to `β = B / √(F·K)` at log-moneyness
+//! `x = ln(F/K)` and reduced to an out-of-the-money call, whose total
+//! volatility `s = σ·√T` is found without a convergence loop: a rational
+//! cubic interpolation over four branches of `β` gives an initial guess,
+//! and two third-order Householder steps on an objective chosen per branch
+//! bring it to close to machine precision.
+//!
+//! See P. Jäckel, "Let's Be Rational", Wilmott (2015), pp. 40-53.
+
+use super::black_scholes::BlackScholes;
+use std::f64::consts::FRAC_PI_6;
+
+/// Householder steps taken after the rational initial guess.
+pub(super) const RATIONAL_STEPS: u32 = 2;
+
+const ONE_OVER_SQRT_TWO_PI: f64 = 0.398_942_280_401_432_7;
+const SQRT_PI_OVER_TWO: f64 = 1.253_314_137_315_500_3;
+const SQRT_THREE: f64 = 1.732_050_807_568_877_2;
+const SQRT_ONE_OVER_THREE: f64 = 0.577_350_269_189_625_8;
+const TWO_PI_OVER_SQRT_TWENTY_SEVEN: f64 = 1.209_199_576_156_145_2;
+
+/// `h + t` at or below which the normalised call uses the asymptotic
+/// expansion of the Mills ratio.
+const ASYMPTOTIC_EXPANSION_THRESHOLD: f64 = -10.0;
+
+/// Half the total volatility below which the normalised call uses its
+/// Taylor expansion in `t`, about `2·ε^(1/16)`.
+const SMALL_T_EXPANSION_THRESHOLD: f64 = 0.21;
+
+/// Most terms summed by either expansion of the normalised call.
+const MAX_EXPANSION_TERMS: u32 = 40;
+
+/// Bounds of the rational cubic control parameter; the upper one makes the
+/// interpolation linear.
+const MIN_CONTROL_PARAMETER: f64 = -(1.0 - 1.490_116_119_384_765_6e-8);
+const MAX_CONTROL_PARAMETER: f64 = 2.0 / (f64::EPSILON * f64::EPSILON);
+
+/// Total volatility `s = σ·√T` of the normalised Black price `beta` at
+/// log-moneyness `x`, of a call when `theta` is `1.0` and of a put when it
+/// is `-1.0`, with the number of Householder steps taken.
+///
+/// Prices at or below the intrinsic value give zero. Returns `None` for
+/// prices at or above the upper bound `e^(θ·x/2)` of the option.
+pub(super) fn normalised_implied_volatility(beta: f64, x: f64, theta: f64) -> Option<(f64, u32)> {
+    let (mut beta, mut x, mut theta) = (beta, x, theta);
+    // An in-the-money option is worth its intrinsic value plus the
+    // out-of-the-money option of the other type
+    if theta * x > 0.0 {
+        beta = (beta - normalised_intrinsic(x, theta)).max(0.0);
+        theta = -theta;
+    }
+    // A put at x is a call at -x
+    if theta < 0.0 {
+        x = -x;
+    }
+    if beta <= 0.0 {
+        return Some((0.0, 0));
+    }
+    let b_max = (0.5 * x).exp();
+    if beta >= b_max {
+        return None;
+    }
+
+    let s_c = (2.0 * x.abs()).sqrt();
+    let b_c = normalised_black_call(x, s_c);
+    let v_c = normalised_vega(x, s_c);
+
+    if beta < b_c {
+        let s_l = s_c - b_c / v_c;
+        let b_l = normalised_black_call(x, s_l);
+        if beta < b_l {
+            let (f_l, fp_l, fpp_l) = lower_map(x, s_l);
+            let r = convex_control_parameter_at_right(0.0, b_l, 0.0, f_l, 1.0, fp_l, fpp_l, true);
+            let mut f = rational_cubic(beta, 0.0, b_l, 0.0, f_l, 1.0, fp_l, r);
+            if f.is_nan() || f <= 0.0 {
+                // Round-off for extreme |x|: quadratic through f(0) = 0,
+                // f'(0) = 1 and f(b_l)
+                let u = beta / b_l;
+                f = (f_l * u + b_l * (1.0 - u)) * u;
+            }
+            let s = inverse_lower_map(x, f);
+            return Some(refine(
+                x,
+                beta,
+                s,
+                (f64::MIN_POSITIVE, s_l),
+                Objective::Lower,
+            ));
+        }
+        let v_l = normalised_vega(x, s_l);
+        let r =
+            convex_control_parameter_at_right(b_l, b_c, s_l, s_c, 1.0 / v_l, 1.0 / v_c, 0.0, false);
+        let s = rational_cubic(beta, b_l, b_c, s_l, s_c, 1.0 / v_l, 1.0 / v_c, r);
+        return Some(refine(x, beta, s, (s_l, s_c), Objective::Price));
+    }
+
+    let s_h = if v_c > f64::MIN_POSITIVE {
+        s_c + (b_max - b_c) / v_c
+    } else {
+        s_c
+    };
+    let b_h = normalised_black_call(x, s_h);
+    if beta <= b_h {
+        let v_h = normalised_vega(x, s_h);
+        let r =
+            convex_control_parameter_at_left(b_c, b_h, s_c, s_h, 1.0 / v_c, 1.0 / v_h, 0.0, false);
+        let s = rational_cubic(beta, b_c, b_h, s_c, s_h, 1.0 / v_c, 1.0 / v_h, r);
+        return Some(refine(x, beta, s, (s_c, s_h), Objective::Price));
+    }
+
+    let (f_h, fp_h, fpp_h) = upper_map(x, s_h);
+    let mut f = if fpp_h.abs() < f64::MAX.sqrt() {
+        let r = convex_control_parameter_at_left(b_h, b_max, f_h, 0.0, fp_h, -0.5, fpp_h, true);
+        rational_cubic(beta, b_h, b_max, f_h, 0.0, fp_h, -0.5, r)
+    } else {
+        f64::NAN
+    };
+    if f.is_nan() || f <= 0.0 {
+        // Quadratic through f(b_h), f(b_max) = 0 and f'(b_max) = -1/2
+        let h = b_max - b_h;
+        let u = (beta - b_h) / h;
+        f = (f_h * (1.0 - u) + 0.5 * h * u) * (1.0 - u);
+    }
+    let s = -2.0 * inverse_norm_cdf(f);
+    let objective = if beta > 0.5 * b_max {
+        Objective::Upper { b_max }
+    } else {
+        Objective::Price
+    };
+    Some(refine(x, beta, s, (s_h, f64::MAX), objective))
+}
+
+/// Normalised Black price `b(x, s)` of a call.
+pub(super) fn normalised_black_call(x: f64, s: f64) -> f64 {
+    if x > 0.0 {
+        return normalised_intrinsic(x, 1.0) + normalised_black_call(-x, s);
+    }
+    if s <= 0.0 {
+        return 0.0;
+    }
+    let h = x / s;
+    let t = 0.5 * s;
+    // b = e^(x/2)·N(h + t) - e^(-x/2)·N(h - t), which loses digits to
+    // cancellation unless written as n(h + t)·e^(x/2) times the difference
+    // of the Mills ratios m(z) = N(z)/n(z) at h + t and h - t
+    let mills_difference = if h + t <= ASYMPTOTIC_EXPANSION_THRESHOLD {
+        asymptotic_mills_difference(h, t)
+    } else if t < SMALL_T_EXPANSION_THRESHOLD {
+        small_t_mills_difference(h, t)
+    } else {
+        let b = (0.5 * x).exp() * BlackScholes::norm_cdf(h + t)
+            - (-0.5 * x).exp() * BlackScholes::norm_cdf(h - t);
+        return b.max(0.0);
+    };
+    (ONE_OVER_SQRT_TWO_PI * (-0.5 * (h * h + t * t)).exp() * mills_difference).max(0.0)
+}
+
+/// Normalised vega `∂b/∂s`.
+fn normalised_vega(x: f64, s: f64) -> f64 {
+    if x == 0.0 {
+        ONE_OVER_SQRT_TWO_PI * (-0.125 * s * s).exp()
+    } else if s <= 0.0 {
+        0.0
+    } else {
+        let h = x / s;
+        ONE_OVER_SQRT_TWO_PI * (-0.5 * (h * h + 0.25 * s * s)).exp()
+    }
+}
+
+/// Normalised intrinsic value `max(θ·(e^(x/2) - e^(-x/2)), 0)`.
+fn normalised_intrinsic(x: f64, theta: f64) -> f64 {
+    if theta * x <= 0.0 {
+        0.0
+    } else {
+        (2.0 * (0.5 * x).sinh()).abs()
+    }
+}
+
+/// `m(h + t) - m(h - t)` from the asymptotic series
+/// `m(-a) ~ Σ (-1)^k·(2k-1)!!/a^(2k+1)`, for `h + t` far below zero.
+///
+/// The differences `a^-n - c^-n` of the series are built up without
+/// cancellation from `a^-1 - c^-1 = 2t/(a·c)`.
+fn asymptotic_mills_difference(h: f64, t: f64) -> f64 {
+    let p = -1.0 / (h + t);
+    let q = -1.0 / (h - t);
+    let p2 = p * p;
+    let q2 = q * q;
+    let first = 2.0 * t * p * q;
+    let spread = first * (p + q);
+    let mut difference = first;
+    let mut q_power = q;
+    let mut coefficient = 1.0;
+    let mut sum = difference;
+    for k in 1..MAX_EXPANSION_TERMS {
+        // p^(n+2) - q^(n+2) = p²·(p^n - q^n) + q^n·(p² - q²)
+        difference = p2 * difference + q_power * spread;
+        q_power *= q2;
+        coefficient *= -f64::from(2 * k - 1);
+        let term = coefficient * difference;
+        sum += term;
+        if term.abs() <= f64::EPSILON * sum.abs() {
+            break;
+        }
+    }
+    sum
+}
+
+/// `m(h + t) - m(h - t)` from the Taylor series in `t`, whose odd
+/// derivatives `M_n` of the Mills ratio follow `M_(n+1) = h·M_n + n·M_(n-1)`.
+fn small_t_mills_difference(h: f64, t: f64) -> f64 {
+    let t2 = t * t;
+    let mut previous = BlackScholes::norm_cdf(h) / BlackScholes::norm_pdf(h);
+    let mut derivative = h * previous + 1.0;
+    let mut weight = t;
+    let mut sum = weight * derivative;
+    let mut n = 1.0;
+    for _ in 1..MAX_EXPANSION_TERMS {
+        let next = h * derivative + n * previous;
+        previous = next;
+        derivative = h * next + (n + 1.0) * derivative;
+        weight *= t2 / ((n + 1.0) * (n + 2.0));
+        n += 2.0;
+        let term = weight * derivative;
+        sum += term;
+        if term.abs() <= f64::EPSILON * sum.abs() {
+            break;
+        }
+    }
+    2.0 * sum
+}
+
+/// Transformation of the lowest branch, `f = 2π·|x|/√27·N(-|x|/(√3·s))³`,
+/// with its first two derivatives with respect to `β`.
+fn lower_map(x: f64, s: f64) -> (f64, f64, f64) {
+    let ax = x.abs();
+    let z = SQRT_ONE_OVER_THREE * ax / s;
+    let y = z * z;
+    let s2 = s * s;
+    let cdf = BlackScholes::norm_cdf(-z);
+    let pdf = BlackScholes::norm_pdf(z);
+    let fpp = FRAC_PI_6 * y / (s2 * s)
+        * cdf
+        * (8.0 * SQRT_THREE * s * ax + (3.0 * s2 * (s2 - 8.0) - 8.0 * x * x) * cdf / pdf)
+        * (2.0 * y + 0.25 * s2).exp();
+    let cdf2 = cdf * cdf;
+    let fp = 2.0 * std::f64::consts::PI * y * cdf2 * (y + 0.125 * s2).exp();
+    let f = TWO_PI_OVER_SQRT_TWENTY_SEVEN * ax * cdf2 * cdf;
+    (f, fp, fpp)
+}
+
+fn inverse_lower_map(x: f64, f: f64) -> f64 {
+    let u = (f / (TWO_PI_OVER_SQRT_TWENTY_SEVEN * x.abs())).cbrt();
+    (x / (SQRT_THREE * inverse_norm_cdf(u))).abs()
+}
+
+/// Transformation of the highest branch, `f = N(-s/2)`, with its first two
+/// derivatives with respect to `β`.
+fn upper_map(x: f64, s: f64) -> (f64, f64, f64) {
+    let f = BlackScholes::norm_cdf(-0.5 * s);
+    let w = (x / s) * (x / s);
+    let fp = -0.5 * (0.5 * w).exp();
+    let fpp = SQRT_PI_OVER_TWO * (w + 0.125 * s * s).exp() * w / s;
+    (f, fp, fpp)
+}
+
+/// Objective function of the Householder steps.
+#[derive(Clone, Copy)]
+enum Objective {
+    /// `1/ln(b(s)) - 1/ln(β)`, for the lowest branch.
+    Lower,
+    /// `b(s) - β`, for the two middle branches.
+    Price,
+    /// `ln(b_max - β) - ln(b_max - b(s))`, for the highest branch.
+    Upper { b_max: f64 },
+}
+
+impl Objective {
+    /// Householder step from `s`, or `None` when the objective cannot be
+    /// evaluated there.
+    fn step(self, x: f64, s: f64, beta: f64, b: f64, vega: f64) -> Option<f64> {
+        // b''/b' and b'''/b' of the normalised price
+        let b_halley = (x / s) * (x / s) / s - 0.25 * s;
+        let b_hh3 = b_halley * b_halley - 3.0 * (x / (s * s)) * (x / (s * s)) - 0.25;
+        let (newton, halley, hh3) = match self {
+            Objective::Lower => {
+                if b <= 0.0 || vega <= 0.0 {
+                    return None;
+                }
+                let ln_b = b.ln();
+                let ln_beta = beta.ln();
+                let vega_over_b = vega / b;
+                let log_factor = 1.0 + 2.0 / ln_b;
+                (
+                    (ln_beta - ln_b) * ln_b / ln_beta / vega_over_b,
+                    b_halley - vega_over_b * log_factor,
+                    b_hh3
+                        + 2.0 * vega_over_b * vega_over_b * (1.0 + 3.0 / ln_b * (1.0 + 1.0 / ln_b))
+                        - 3.0 * b_halley * vega_over_b * log_factor,
+                )
+            }
+            Objective::Price => ((beta - b) / vega, b_halley, b_hh3),
+            Objective::Upper { b_max } => {
+                if b >= b_max || vega <= f64::MIN_POSITIVE {
+                    return None;
+                }
+                let headroom = b_max - b;
+                let g = ((b_max - beta) / headroom).ln();
+                let gp = vega / headroom;
+                (
+                    -g / gp,
+                    b_halley + gp,
+                    b_hh3 + gp * (2.0 * gp + 3.0 * b_halley),
+                )
+            }
+        };
+        Some(
+            newton * (1.0 + 0.5 * halley * newton) / (1.0 + newton * (halley + hh3 * newton / 6.0)),
+        )
+    }
+}
+
+/// Takes at most [`RATIONAL_STEPS`] Householder steps from the initial
+/// guess `s`, bisecting the bracket instead when a step leaves it.
+fn refine(x: f64, beta: f64, mut s: f64, bracket: (f64, f64), objective: Objective) -> (f64, u32) {
+    let (mut s_left, mut s_right) = bracket;
+    let mut ds = -f64::MAX;
+    let mut ds_previous = 0.0;
+    let mut reversals = 0;
+    let mut steps = 0;
+    while steps < RATIONAL_STEPS && ds.abs() > f64::EPSILON * s {
+        if ds * ds_previous < 0.0 {
+            reversals += 1;
+        }
+        if steps > 0 && (reversals == 3 || !(s > s_left && s < s_right)) {
+            s = 0.5 * (s_left + s_right);
+            if s_right - s_left <= f64::EPSILON * s {
+                break;
+            }
+            reversals = 0;
+            ds = 0.0;
+        }
+        ds_previous = ds;
+        let b = normalised_black_call(x, s);
+        let vega = normalised_vega(x, s);
+        if b > beta && s < s_right {
+            s_right = s;
+        } else if b < beta && s > s_left {
+            s_left = s;
+        }
+        ds = objective
+            .step(x, s, beta, b, vega)
+            .unwrap_or(0.5 * (s_left + s_right) - s)
+            .max(-0.5 * s);
+        s += ds;
+        steps += 1;
+    }
+    (s, steps)
+}
+
+/// Rational cubic through `(x_l, y_l)` and `(x_r, y_r)` with slopes `d_l`
+/// and `d_r`, evaluated at `x`, for the control parameter `r`.
+#[allow(clippy::too_many_arguments)]
+fn rational_cubic(
+    x: f64,
+    x_l: f64,
+    x_r: f64,
+    y_l: f64,
+    y_r: f64,
+    d_l: f64,
+    d_r: f64,
+    r: f64,
+) -> f64 {
+    let h = x_r - x_l;
+    if h.abs() <= 0.0 {
+        return 0.5 * (y_l + y_r);
+    }
+    let t = (x - x_l) / h;
+    if r >= MAX_CONTROL_PARAMETER {
+        return y_r * t + y_l * (1.0 - t);
+    }
+    let omt = 1.0 - t;
+    let t2 = t * t;
+    let omt2 = omt * omt;
+    (y_r * t2 * t
+        + (r * y_r - h * d_r) * t2 * omt
+        + (r * y_l + h * d_l) * t * omt2
+        + y_l * omt2 * omt)
+        / (1.0 + (r - 3.0) * t * omt)
+}
+
+/// Smallest control parameter keeping the interpolation monotonic, convex
+/// or concave wherever the slopes `d_l`, `s` and `d_r` allow it.
+fn minimum_control_parameter(d_l: f64, d_r: f64, s: f64, prefer_shape: bool) -> f64 {
+    let monotonic = d_l * s >= 0.0 && d_r * s >= 0.0;
+    let convex = d_l <= s && s <= d_r;
+    let concave = d_l >= s && s >= d_r;
+    if !monotonic && !convex && !concave {
+        return MIN_CONTROL_PARAMETER;
+    }
+    let mut r1 = -f64::MAX;
+    let mut r2 = -f64::MAX;
+    if monotonic {
+        if !is_zero(s) {
+            r1 = (d_r + d_l) / s;
+        } else if prefer_shape {
+            r1 = MAX_CONTROL_PARAMETER;
+        }
+    }
+    if convex || concave {
+        let (s_minus_d_l, d_r_minus_s) = (s - d_l, d_r - s);
+        if !(is_zero(s_minus_d_l) || is_zero(d_r_minus_s)) {
+            let d_r_minus_d_l = d_r - d_l;
+            r2 = (d_r_minus_d_l / d_r_minus_s)
+                .abs()
+                .max((d_r_minus_d_l / s_minus_d_l).abs());
+        } else if prefer_shape {
+            r2 = MAX_CONTROL_PARAMETER;
+        }
+    } else if monotonic && prefer_shape {
+        r2 = MAX_CONTROL_PARAMETER;
+    }
+    MIN_CONTROL_PARAMETER.max(r1).max(r2)
+}
+
+/// Control parameter matching the second derivative at `x_l`, raised to
+/// keep the shape of the data.
+#[allow(clippy::too_many_arguments)]
+fn convex_control_parameter_at_left(
+    x_l: f64,
+    x_r: f64,
+    y_l: f64,
+    y_r: f64,
+    d_l: f64,
+    d_r: f64,
+    second_derivative: f64,
+    prefer_shape: bool,
+) -> f64 {
+    let h = x_r - x_l;
+    let slope = (y_r - y_l) / h;
+    let numerator = 0.5 * h * second_derivative + (d_r - d_l);
+    let denominator = slope - d_l;
+    let r = control_parameter(numerator, denominator);
+    r.max(minimum_control_parameter(d_l, d_r, slope, prefer_shape))
+}
+
+/// Control parameter matching the second derivative at `x_r`, raised to
+/// keep the shape of the data.
+#[allow(clippy::too_many_arguments)]
+fn convex_control_parameter_at_right(
+    x_l: f64,
+    x_r: f64,
+    y_l: f64,
+    y_r: f64,
+    d_l: f64,
+    d_r: f64,
+    second_derivative: f64,
+    prefer_shape: bool,
+) -> f64 {
+    let h = x_r - x_l;
+    let slope = (y_r - y_l) / h;
+    let numerator = 0.5 * h * second_derivative + (d_r - d_l);
+    let denominator = d_r - slope;
+    let r = control_parameter(numerator, denominator);
+    r.max(minimum_control_parameter(d_l, d_r, slope, prefer_shape))
+}
+
+fn control_parameter(numerator: f64, denominator: f64) -> f64 {
+    if is_zero(numerator) {
+        0.0
+    } else if is_zero(denominator) {
+        if numerator > 0.0 {
+            MAX_CONTROL_PARAMETER
+        } else {
+            MIN_CONTROL_PARAMETER
+        }
+    } else {
+        numerator / denominator
+    }
+}
+
+fn is_zero(x: f64) -> bool {
+    x.abs() < f64::MIN_POSITIVE
+}
+
+/// Inverse of the standard normal CDF.
+///
+/// Acklam's rational approximation, accurate to about 10⁻⁹, polished by one
+/// Halley step on [`BlackScholes::norm_cdf`].
+fn inverse_norm_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.024_25;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    if p > 0.5 {
+        return -inverse_norm_cdf(1.0 - p);
+    }
+    let x = if p < P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    };
+    let u = (BlackScholes::norm_cdf(x) - p) / BlackScholes::norm_pdf(x);
+    x - u / (1.0 + 0.5 * x * u)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `b(x, s)` straight from the normal CDF, for moderate arguments.
+    fn direct_black_call(x: f64, s: f64) -> f64 {
+        (0.5 * x).exp() * BlackScholes::norm_cdf(x / s + 0.5 * s)
+            - (-0.5 * x).exp() * BlackScholes::norm_cdf(x / s - 0.5 * s)
+    }
+
+    #[test]
+    fn test_inverse_norm_cdf() {
+        for x in [-30.0, -8.0, -2.5, -1.0, -1e-3, 0.0, 0.7, 2.0] {
+            let p = BlackScholes::norm_cdf(x);
+            let inverse = inverse_norm_cdf(p);
+            assert!(
+                (inverse - x).abs() < 1e-12 * x.abs().max(1.0),
+                "{x}: {inverse}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalised_black_call_expansions_match_direct_price() {
+        // Points on either side of both expansion thresholds
+        for (x, s) in [
+            (-0.5, 0.05),
+            (-0.5, 0.45),
+            (-2.0, 0.41),
+            (-2.0, 0.43),
+            (-1.0, 0.1),
+            (0.0, 0.2),
+            (-4.0, 0.4),
+            (-4.0, 0.41),
+            (-30.0, 3.2),
+            (-30.0, 3.4),
+            (0.8, 0.3),
+        ] {
+            let expected = direct_black_call(x, s);
+            let value = normalised_black_call(x, s);
+            assert!(
+                ((value - expected) / expected).abs() < 1e-10,
+                "b({x}, {s}) = {value}, expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalised_black_call_deep_wing_is_positive_and_increasing() {
+        // Far below the point where the direct formula cancels to zero
+        let x = -5.0;
+        let mut previous = 0.0;
+        for s in [0.2, 0.3, 0.4, 0.45] {
+            let b = normalised_black_call(x, s);
+            assert!(b > previous, "b({x}, {s}) = {b}");
+            previous = b;
+        }
+        assert!(normalised_black_call(x, 0.2) < 1e-50);
+    }
+
+    #[test]
+    fn test_round_trip_in_two_steps() {
+        for x in [-12.0, -3.0, -0.8, -0.05, 0.0, 0.05, 0.8, 3.0, 12.0] {
+            for s in [0.01, 0.08, 0.3, 1.0, 2.5, 6.0] {
+                for theta in [1.0, -1.0] {
+                    // A put at x is worth a call at -x
+                    let beta = normalised_black_call(theta * x, s);
+                    let time_value = beta - normalised_intrinsic(x, theta);
+                    if beta <= f64::MIN_POSITIVE || time_value <= 1e-6 * beta {
+                        // Underflows, or the time value is lost to rounding
+                        continue;
+                    }
+                    let (implied, steps) = normalised_implied_volatility(beta, x, theta).unwrap();
+                    assert!(steps <= RATIONAL_STEPS);
+                    assert!(
+                        ((implied - s) / s).abs() < 1e-9,
+                        "x = {x}, s = {s}, θ = {theta}: {implied}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(
+            normalised_implied_volatility(0.0, -1.0, 1.0),
+            Some((0.0, 0))
+        );
+        // At the intrinsic value of an in-the-money call
+        let intrinsic = normalised_intrinsic(1.0, 1.0);
+        assert_eq!(
+            normalised_implied_volatility(intrinsic, 1.0, 1.0),
+            Some((0.0, 0))
+        );
+        // A call is worth less than the forward
+        assert_eq!(normalised_implied_volatility(1.0, 0.0, 1.0), None);
+        assert_eq!(
+            normalised_implied_volatility((0.5f64).exp(), 1.0, 1.0),
+            None
+        );
+    }
+}
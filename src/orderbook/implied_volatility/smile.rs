@@ -0,0 +1,391 @@
+//! Implied volatility smiles across a strike ladder of option books.
+//!
+//! Each strike of an expiry trades in its own order book. A smile solves the
+//! IV of every book of the ladder in one call, collecting the strikes that
+//! could not be solved next to the ones that could.
+
+use super::error::IVError;
+use super::integration::IVConfig;
+use super::types::{ExerciseStyle, IVParams, IVResult, OptionType, PriceSource};
+use crate::orderbook::book::OrderBook;
+
+/// Configuration of an implied volatility smile.
+#[derive(Debug, Clone)]
+pub struct SmileConfig {
+    /// IV calculation configuration applied to every book.
+    pub iv: IVConfig,
+    /// How to derive the option price of each book.
+    pub price_source: PriceSource,
+    /// Option type of the books of the ladder (default: Call).
+    pub option_type: OptionType,
+    /// Risk-free interest rate (annualized).
+    pub risk_free_rate: f64,
+    /// Continuous dividend or borrow yield of the underlying (annualized).
+    pub dividend_yield: f64,
+    /// Exercise style of the options.
+    pub exercise_style: ExerciseStyle,
+    /// Whether to solve the strikes in parallel. Requires the `rayon` feature,
+    /// strikes are solved sequentially without it.
+    pub parallel: bool,
+}
+
+impl Default for SmileConfig {
+    fn default() -> Self {
+        Self {
+            iv: IVConfig::default(),
+            price_source: PriceSource::default(),
+            option_type: OptionType::Call,
+            risk_free_rate: 0.0,
+            dividend_yield: 0.0,
+            exercise_style: ExerciseStyle::European,
+            parallel: false,
+        }
+    }
+}
+
+impl SmileConfig {
+    /// Creates a new smile configuration with default values.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the IV calculation configuration.
+    #[must_use]
+    pub fn with_iv_config(mut self, iv: IVConfig) -> Self {
+        self.iv = iv;
+        self
+    }
+
+    /// Sets the price source.
+    #[must_use]
+    pub fn with_price_source(mut self, price_source: PriceSource) -> Self {
+        self.price_source = price_source;
+        self
+    }
+
+    /// Sets the option type.
+    #[must_use]
+    pub fn with_option_type(mut self, option_type: OptionType) -> Self {
+        self.option_type = option_type;
+        self
+    }
+
+    /// Sets the risk-free interest rate.
+    #[must_use]
+    pub fn with_risk_free_rate(mut self, risk_free_rate: f64) -> Self {
+        self.risk_free_rate = risk_free_rate;
+        self
+    }
+
+    /// Sets the continuous dividend or borrow yield.
+    #[must_use]
+    pub fn with_dividend_yield(mut self, dividend_yield: f64) -> Self {
+        self.dividend_yield = dividend_yield;
+        self
+    }
+
+    /// Sets the exercise style.
+    #[must_use]
+    pub fn with_exercise_style(mut self, exercise_style: ExerciseStyle) -> Self {
+        self.exercise_style = exercise_style;
+        self
+    }
+
+    /// Enables or disables parallel solving.
+    #[must_use]
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Option parameters of one strike of the smile.
+    #[must_use]
+    pub fn params(&self, spot: f64, strike: f64, expiry: f64) -> IVParams {
+        IVParams::new(spot, strike, expiry, self.risk_free_rate, self.option_type)
+            .with_dividend_yield(self.dividend_yield)
+            .with_exercise_style(self.exercise_style)
+    }
+}
+
+/// Implied volatility solved for one strike of a smile.
+#[derive(Debug, Clone)]
+pub struct SmilePoint {
+    /// Symbol of the option book.
+    pub symbol: String,
+    /// Strike price in price units.
+    pub strike: f64,
+    /// IV result of the book.
+    pub result: IVResult,
+}
+
+/// Strike of a smile whose implied volatility could not be solved.
+#[derive(Debug, Clone)]
+pub struct SmileFailure {
+    /// Symbol of the option book.
+    pub symbol: String,
+    /// Strike price in price units.
+    pub strike: f64,
+    /// Why the IV could not be solved.
+    pub error: IVError,
+}
+
+/// Implied volatilities of a strike ladder at one expiry.
+#[derive(Debug, Clone)]
+pub struct IVSmile {
+    /// Time to expiration in years.
+    pub expiry: f64,
+    /// Underlying spot price in price units.
+    pub spot: f64,
+    /// Solved strikes, in ascending strike order.
+    pub points: Vec<SmilePoint>,
+    /// Strikes that could not be solved, in the order they were requested.
+    pub failures: Vec<SmileFailure>,
+}
+
+impl IVSmile {
+    /// Solves the IV of every `(symbol, strike, book)` of a ladder.
+    ///
+    /// Books that are `None` fail with [`IVError::BookNotFound`].
+    #[must_use]
+    pub fn solve<T>(
+        expiry: f64,
+        spot: f64,
+        ladder: &[(&str, f64, Option<&OrderBook<T>>)],
+        config: &SmileConfig,
+    ) -> Self
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let solve = |&(symbol, strike, book): &(&str, f64, Option<&OrderBook<T>>)| match book {
+            Some(book) => book.implied_volatility_with_config(
+                &config.params(spot, strike, expiry),
+                config.price_source,
+                &config.iv,
+            ),
+            None => Err(IVError::BookNotFound {
+                symbol: symbol.to_string(),
+            }),
+        };
+
+        #[cfg(feature = "rayon")]
+        let results: Vec<_> = if config.parallel {
+            use rayon::prelude::*;
+            ladder.par_iter().map(solve).collect()
+        } else {
+            ladder.iter().map(solve).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let results: Vec<_> = ladder.iter().map(solve).collect();
+
+        let mut smile = Self {
+            expiry,
+            spot,
+            points: Vec::with_capacity(results.len()),
+            failures: Vec::new(),
+        };
+        for (&(symbol, strike, _), result) in ladder.iter().zip(results) {
+            let symbol = symbol.to_string();
+            match result {
+                Ok(result) => smile.points.push(SmilePoint {
+                    symbol,
+                    strike,
+                    result,
+                }),
+                Err(error) => smile.failures.push(SmileFailure {
+                    symbol,
+                    strike,
+                    error,
+                }),
+            }
+        }
+        smile.points.sort_by(|a, b| a.strike.total_cmp(&b.strike));
+        smile
+    }
+
+    /// Solved `(strike, iv)` pairs, in ascending strike order.
+    #[must_use]
+    pub fn strike_ivs(&self) -> Vec<(f64, f64)> {
+        self.points
+            .iter()
+            .map(|point| (point.strike, point.result.iv))
+            .collect()
+    }
+
+    /// Whether every requested strike was solved.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Implied volatility of the smile at `strike`.
+    ///
+    /// Variance is interpolated linearly in log-strike between the solved
+    /// strikes, and extended flat beyond them.
+    ///
+    /// # Returns
+    /// `None` if no strike was solved or `strike` is not positive
+    #[must_use]
+    pub fn iv_at_strike(&self, strike: f64) -> Option<f64> {
+        if strike <= 0.0 || !strike.is_finite() {
+            return None;
+        }
+        let first = self.points.first()?;
+        let last = self.points.last()?;
+        if strike <= first.strike {
+            return Some(first.result.iv);
+        }
+        if strike >= last.strike {
+            return Some(last.result.iv);
+        }
+
+        let upper = self.points.partition_point(|point| point.strike < strike);
+        let (low, high) = (&self.points[upper - 1], &self.points[upper]);
+        let weight = (strike / low.strike).ln() / (high.strike / low.strike).ln();
+        let variance = low.result.iv.powi(2) * (1.0 - weight) + high.result.iv.powi(2) * weight;
+        Some(variance.sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::implied_volatility::BlackScholes;
+    use crate::orderbook::manager::{BookManager, BookManagerStd};
+    use pricelevel::{OrderId, Side, TimeInForce};
+
+    const SPOT: f64 = 100.0;
+    const EXPIRY: f64 = 0.5;
+
+    /// Manager with a one-tick wide book in cents per strike, quoted around
+    /// the Black-Scholes price of a put at a volatility skewed by strike
+    fn setup_manager(config: &SmileConfig) -> BookManagerStd<()> {
+        let mut manager = BookManagerStd::new();
+        for strike in [80.0, 90.0, 100.0, 110.0, 120.0] {
+            let symbol = format!("P-{strike}");
+            let vol = 0.2 + (100.0 - strike) * 0.004;
+            let price = BlackScholes::price(&config.params(SPOT, strike, EXPIRY), vol);
+            let cents = (price * 100.0).round() as u64;
+            manager.add_book(&symbol);
+            let book = manager.get_book(&symbol).unwrap();
+            let _ =
+                book.add_limit_order(OrderId::new(), cents, 10, Side::Buy, TimeInForce::Gtc, None);
+            let _ = book.add_limit_order(
+                OrderId::new(),
+                cents + 1,
+                10,
+                Side::Sell,
+                TimeInForce::Gtc,
+                None,
+            );
+        }
+        manager
+    }
+
+    fn smile_config() -> SmileConfig {
+        SmileConfig::new()
+            .with_iv_config(IVConfig::default().with_price_scale(100.0))
+            .with_option_type(OptionType::Put)
+            // Deep ITM European puts trade below intrinsic with a positive rate
+            .with_risk_free_rate(0.0)
+    }
+
+    #[test]
+    fn test_smile_recovers_skew() {
+        let config = smile_config();
+        let manager = setup_manager(&config);
+        let strikes = [
+            ("P-120", 120.0),
+            ("P-80", 80.0),
+            ("P-100", 100.0),
+            ("P-90", 90.0),
+            ("P-110", 110.0),
+        ];
+
+        let smile = manager.iv_smile(EXPIRY, &strikes, SPOT, &config);
+        assert!(smile.is_complete());
+        assert_eq!(smile.expiry, EXPIRY);
+        assert_eq!(smile.spot, SPOT);
+
+        let ivs = smile.strike_ivs();
+        let sorted: Vec<f64> = ivs.iter().map(|&(strike, _)| strike).collect();
+        assert_eq!(sorted, vec![80.0, 90.0, 100.0, 110.0, 120.0]);
+        for (strike, iv) in ivs {
+            let expected = 0.2 + (100.0 - strike) * 0.004;
+            assert!((iv - expected).abs() < 0.01, "strike {strike}: {iv}");
+        }
+        assert!(ivs_decreasing(&smile));
+    }
+
+    #[test]
+    fn test_smile_iv_at_strike() {
+        let config = smile_config();
+        let manager = setup_manager(&config);
+        let strikes = [("P-90", 90.0), ("P-100", 100.0), ("P-110", 110.0)];
+        let smile = manager.iv_smile(EXPIRY, &strikes, SPOT, &config);
+        let iv = |strike| smile.iv_at_strike(strike).unwrap();
+
+        assert_eq!(iv(100.0), smile.points[1].result.iv);
+        assert_eq!(iv(50.0), smile.points[0].result.iv);
+        assert_eq!(iv(200.0), smile.points[2].result.iv);
+        assert!(iv(95.0) < iv(90.0) && iv(95.0) > iv(100.0));
+        assert!(smile.iv_at_strike(0.0).is_none());
+
+        let empty = manager.iv_smile(EXPIRY, &[("P-1", 1.0)], SPOT, &config);
+        assert!(empty.iv_at_strike(100.0).is_none());
+    }
+
+    fn ivs_decreasing(smile: &IVSmile) -> bool {
+        smile
+            .points
+            .windows(2)
+            .all(|pair| pair[0].result.iv > pair[1].result.iv)
+    }
+
+    #[test]
+    fn test_smile_reports_failures() {
+        let config = smile_config();
+        let manager = setup_manager(&config);
+        let strikes = [("P-100", 100.0), ("P-130", 130.0), ("P-90", 90.0)];
+
+        let tight = config.clone().with_iv_config(
+            IVConfig::default()
+                .with_price_scale(100.0)
+                .with_max_spread(1.0),
+        );
+        let smile = manager.iv_smile(EXPIRY, &strikes, SPOT, &tight);
+        assert!(!smile.is_complete());
+        assert!(smile.points.is_empty());
+        assert_eq!(smile.failures.len(), 3);
+        assert!(matches!(
+            &smile.failures[1].error,
+            IVError::BookNotFound { symbol } if symbol == "P-130"
+        ));
+        assert!(matches!(
+            smile.failures[0].error,
+            IVError::SpreadTooWide { .. }
+        ));
+        assert_eq!(smile.failures[2].strike, 90.0);
+    }
+
+    #[test]
+    fn test_parallel_smile_matches_sequential() {
+        let config = smile_config();
+        let manager = setup_manager(&config);
+        let strikes: Vec<(String, f64)> = [80.0, 90.0, 100.0, 110.0, 120.0, 125.0]
+            .iter()
+            .map(|&strike| (format!("P-{strike}"), strike))
+            .collect();
+        let strikes: Vec<(&str, f64)> = strikes
+            .iter()
+            .map(|(symbol, strike)| (symbol.as_str(), *strike))
+            .collect();
+
+        let sequential = manager.iv_smile(EXPIRY, &strikes, SPOT, &config);
+        let parallel =
+            manager.iv_smile(EXPIRY, &strikes, SPOT, &config.clone().with_parallel(true));
+        assert_eq!(sequential.strike_ivs(), parallel.strike_ivs());
+        assert_eq!(parallel.failures.len(), 1);
+        assert_eq!(parallel.failures[0].symbol, "P-125");
+    }
+}
@@ -3,12 +3,40 @@
 //! This module provides a numerical solver to find the implied volatility
 //! that makes the Black-Scholes price equal to the observed market price.
 
+use super::binomial::{model_price, model_vega};
 use super::black_scholes::BlackScholes;
 use super::error::IVError;
-use super::types::IVParams;
+use super::rational::normalised_implied_volatility;
+use super::types::{ExerciseStyle, IVParams, OptionType};
+
+/// Root-finding method used by [`solve_iv`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SolverMethod {
+    /// Damped Newton-Raphson steps on vega.
+    #[default]
+    NewtonRaphson,
+    /// Third-order Householder steps using vega, vomma and the third
+    /// derivative of the price, as in the refinement stage of Jäckel's
+    /// "Let's Be Rational". Typically converges in two or three steps.
+    /// American options use Newton-Raphson instead.
+    Householder,
+    /// Peter Jäckel's "Let's Be Rational" inversion: a rational cubic
+    /// initial guess refined by exactly two Householder steps, with no
+    /// convergence loop, accurate to close to machine precision. The
+    /// tolerance, iteration limit and initial guess are not used. American
+    /// options use Newton-Raphson instead.
+    Rational,
+    /// Bisection between the IV bounds, see [`solve_iv_bisection`].
+    Bisection,
+}
 
 /// Configuration for the Newton-Raphson solver.
+///
+/// Built with [`SolverConfig::new`] and the `with_*` methods, so that new
+/// settings can be added without breaking callers.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct SolverConfig {
     /// Maximum iterations before giving up.
     pub max_iterations: u32,
@@ -22,6 +50,8 @@ pub struct SolverConfig {
     pub max_iv: f64,
     /// Minimum vega threshold to avoid division by near-zero.
     pub min_vega: f64,
+    /// Root-finding method (default: Newton-Raphson).
+    pub method: SolverMethod,
 }
 
 impl Default for SolverConfig {
@@ -33,6 +63,7 @@ impl Default for SolverConfig {
             min_iv: 0.001,
             max_iv: 5.0,
             min_vega: 1e-10,
+            method: SolverMethod::NewtonRaphson,
         }
     }
 }
@@ -65,6 +96,13 @@ impl SolverConfig {
         self
     }
 
+    /// Sets the root-finding method.
+    #[must_use]
+    pub fn with_method(mut self, method: SolverMethod) -> Self {
+        self.method = method;
+        self
+    }
+
     /// Sets the IV bounds.
     #[must_use]
     pub fn with_bounds(mut self, min_iv: f64, max_iv: f64) -> Self {
@@ -130,14 +168,15 @@ fn validate_params(params: &IVParams) -> Result<(), IVError> {
 fn smart_initial_guess(params: &IVParams, market_price: f64) -> f64 {
     let sqrt_time = params.time_to_expiry.sqrt();
 
-    // Brenner-Subrahmanyam approximation for ATM: σ ≈ price / (0.4 * S * √T)
-    let bs_approx = market_price / (0.4 * params.spot * sqrt_time);
+    // Brenner-Subrahmanyam approximation for ATM: σ ≈ price / (0.4 * S·e^(-qT) * √T)
+    let bs_approx = market_price / (0.4 * params.spot * params.dividend_discount() * sqrt_time);
 
     // Clamp to reasonable bounds
     bs_approx.clamp(0.05, 2.0)
 }
 
-/// Solves for implied volatility using Newton-Raphson method.
+/// Solves for implied volatility using Newton-Raphson method, or the
+/// method selected in `config.method`.
 ///
 /// The Newton-Raphson method iteratively refines the IV estimate using:
 /// σ_{n+1} = σ_n - (BS(σ_n) - market_price) / vega(σ_n)
@@ -179,7 +218,7 @@ pub fn solve_iv(
     }
 
     // Check if price is below intrinsic value
-    let intrinsic = params.intrinsic_value();
+    let intrinsic = params.carry_adjusted_intrinsic_value();
     if market_price < intrinsic - config.tolerance {
         return Err(IVError::PriceBelowIntrinsic {
             price: market_price,
@@ -187,6 +226,13 @@ pub fn solve_iv(
         });
     }
 
+    if config.method == SolverMethod::Bisection {
+        return solve_iv_bisection(params, market_price, config);
+    }
+    if config.method == SolverMethod::Rational && params.exercise_style == ExerciseStyle::European {
+        return solve_rational(params, market_price, config);
+    }
+
     // Use smart initial guess if default is used
     let mut iv = if (config.initial_guess - 0.25).abs() < 1e-10 {
         smart_initial_guess(params, market_price)
@@ -197,9 +243,15 @@ pub fn solve_iv(
     // Clamp initial guess to bounds
     iv = iv.clamp(config.min_iv, config.max_iv);
 
+    if config.method == SolverMethod::Householder
+        && params.exercise_style == ExerciseStyle::European
+    {
+        return solve_householder(params, market_price, iv, config);
+    }
+
     // Newton-Raphson iteration
     for iteration in 0..config.max_iterations {
-        let price = BlackScholes::price(params, iv);
+        let price = model_price(params, iv);
         let diff = price - market_price;
 
         // Check convergence
@@ -215,7 +267,7 @@ pub fn solve_iv(
             return Ok((iv, iteration + 1));
         }
 
-        let vega = BlackScholes::vega(params, iv);
+        let vega = model_vega(params, iv);
 
         // Handle near-zero vega (can happen for deep ITM/OTM or near expiry)
         if vega.abs() < config.min_vega {
@@ -250,6 +302,95 @@ pub fn solve_iv(
     })
 }
 
+/// Third-order Householder iteration from `iv` for European options.
+///
+/// Each step scales the Newton step `ν = -f/f'` by
+/// `(1 + ν·h₂/2) / (1 + ν·(h₂ + ν·h₃/6))`, with `h₂ = f''/f'` and
+/// `h₃ = f'''/f'` the vomma and third derivative of the price over vega.
+/// Steps that overshoot or flip sign are replaced by damped Newton steps.
+fn solve_householder(
+    params: &IVParams,
+    market_price: f64,
+    mut iv: f64,
+    config: &SolverConfig,
+) -> Result<(f64, u32), IVError> {
+    for iteration in 0..config.max_iterations {
+        let diff = BlackScholes::price(params, iv) - market_price;
+        if diff.abs() < config.tolerance {
+            return Ok((iv, iteration + 1));
+        }
+
+        let vega = BlackScholes::vega(params, iv);
+        if vega.abs() < config.min_vega {
+            iv *= if diff > 0.0 { 0.9 } else { 1.1 };
+        } else {
+            let newton = -diff / vega;
+            let d1 = BlackScholes::d1(
+                params.spot,
+                params.strike,
+                params.cost_of_carry(),
+                params.time_to_expiry,
+                iv,
+            );
+            let d2 = BlackScholes::d2(d1, iv, params.time_to_expiry);
+            let h2 = d1 * d2 / iv;
+            let h3 = -(d1 * d2 * (1.0 - d1 * d2) + d1 * d1 + d2 * d2) / (iv * iv);
+            let step =
+                newton * (1.0 + 0.5 * h2 * newton) / (1.0 + newton * (h2 + h3 * newton / 6.0));
+            let step =
+                if step.is_finite() && step * newton > 0.0 && step.abs() <= 2.0 * newton.abs() {
+                    step
+                } else {
+                    newton
+                };
+            iv += step.clamp(-0.5, 0.5);
+        }
+
+        iv = iv.clamp(config.min_iv, config.max_iv);
+    }
+
+    Err(IVError::ConvergenceFailure {
+        iterations: config.max_iterations,
+        last_iv: iv,
+    })
+}
+
+/// Jäckel's "Let's Be Rational" inversion for European options.
+///
+/// The price is undiscounted and normalised by `√(F·K)` before inverting,
+/// and the total volatility found is scaled back by `√T`. The number of
+/// Householder steps taken is returned as the iteration count.
+fn solve_rational(
+    params: &IVParams,
+    market_price: f64,
+    config: &SolverConfig,
+) -> Result<(f64, u32), IVError> {
+    let time = params.time_to_expiry;
+    let forward = params.spot * (params.cost_of_carry() * time).exp();
+    let undiscounted = market_price * (params.risk_free_rate * time).exp();
+    let theta = match params.option_type {
+        OptionType::Call => 1.0,
+        OptionType::Put => -1.0,
+    };
+    let beta = undiscounted / (forward * params.strike).sqrt();
+    let x = (forward / params.strike).ln();
+
+    // Prices at or above the forward (calls) or the strike (puts) have no
+    // finite volatility
+    let (iv, steps) = normalised_implied_volatility(beta, x, theta)
+        .map_or((f64::INFINITY, 0), |(total, steps)| {
+            (total / time.sqrt(), steps)
+        });
+    if iv < config.min_iv || iv > config.max_iv {
+        return Err(IVError::VolatilityOutOfBounds {
+            volatility: iv,
+            min_bound: config.min_iv,
+            max_bound: config.max_iv,
+        });
+    }
+    Ok((iv, steps))
+}
+
 /// Solves for IV using bisection method as a fallback.
 ///
 /// Slower than Newton-Raphson but guaranteed to converge if a solution exists.
@@ -275,7 +416,7 @@ pub fn solve_iv_bisection(
         });
     }
 
-    let intrinsic = params.intrinsic_value();
+    let intrinsic = params.carry_adjusted_intrinsic_value();
     if market_price < intrinsic - config.tolerance {
         return Err(IVError::PriceBelowIntrinsic {
             price: market_price,
@@ -287,8 +428,8 @@ pub fn solve_iv_bisection(
     let mut high = config.max_iv;
 
     // Verify solution exists in bounds
-    let price_low = BlackScholes::price(params, low);
-    let price_high = BlackScholes::price(params, high);
+    let price_low = model_price(params, low);
+    let price_high = model_price(params, high);
 
     if market_price < price_low || market_price > price_high {
         return Err(IVError::VolatilityOutOfBounds {
@@ -304,7 +445,7 @@ pub fn solve_iv_bisection(
 
     for iteration in 0..config.max_iterations {
         let mid = (low + high) / 2.0;
-        let price = BlackScholes::price(params, mid);
+        let price = model_price(params, mid);
         let diff = price - market_price;
 
         if diff.abs() < config.tolerance || (high - low) < config.tolerance {
@@ -327,6 +468,7 @@ pub fn solve_iv_bisection(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::orderbook::implied_volatility::types::ExerciseStyle;
 
     const TOLERANCE: f64 = 1e-4;
 
@@ -453,6 +595,135 @@ mod tests {
         assert!((iv - target_vol).abs() < TOLERANCE);
     }
 
+    #[test]
+    fn test_solve_iv_with_dividend_yield() {
+        let config = SolverConfig::default();
+        let vol = 0.35;
+        for params in [
+            IVParams::call(100.0, 90.0, 0.5, 0.03).with_dividend_yield(0.06),
+            IVParams::put(100.0, 110.0, 0.5, 0.03).with_dividend_yield(0.06),
+        ] {
+            let price = BlackScholes::price(&params, vol);
+            let (iv, _) = solve_iv(&params, price, &config).unwrap();
+            assert!((iv - vol).abs() < 1e-6);
+            let (iv, _) = solve_iv_bisection(&params, price, &config).unwrap();
+            assert!((iv - vol).abs() < 1e-6);
+        }
+
+        // A deep ITM call on a high-yield underlying trades below S - K
+        let params = IVParams::call(100.0, 60.0, 1.0, 0.0).with_dividend_yield(0.1);
+        let price = BlackScholes::price(&params, 0.2);
+        assert!(price < params.intrinsic_value());
+        let (iv, _) = solve_iv(&params, price, &config).unwrap();
+        assert!((iv - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_solve_iv_american() {
+        let config = SolverConfig::default();
+        let vol = 0.3;
+        for params in [
+            IVParams::put(100.0, 110.0, 0.5, 0.06),
+            IVParams::call(100.0, 90.0, 0.5, 0.02).with_dividend_yield(0.08),
+        ] {
+            let params = params.with_exercise_style(ExerciseStyle::American { steps: 150 });
+            let price = model_price(&params, vol);
+            assert!(price > BlackScholes::price(&params, vol));
+
+            let (iv, _) = solve_iv(&params, price, &config).unwrap();
+            assert!((iv - vol).abs() < 1e-5);
+            let (iv, _) = solve_iv_bisection(&params, price, &config).unwrap();
+            assert!((iv - vol).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_solve_iv_householder() {
+        let newton = SolverConfig::default();
+        let householder = SolverConfig::default().with_method(SolverMethod::Householder);
+        for (params, vol) in [
+            (IVParams::call(100.0, 100.0, 0.25, 0.05), 0.25),
+            (
+                IVParams::put(100.0, 80.0, 0.5, 0.02).with_dividend_yield(0.01),
+                0.45,
+            ),
+            (IVParams::call(100.0, 130.0, 1.0, 0.03), 0.15),
+            (IVParams::call(100.0, 70.0, 0.1, 0.0), 0.6),
+        ] {
+            let price = BlackScholes::price(&params, vol);
+            let (iv, steps) = solve_iv(&params, price, &householder).unwrap();
+            let (_, newton_steps) = solve_iv(&params, price, &newton).unwrap();
+            assert!((iv - vol).abs() < 1e-7, "{params:?}: {iv}");
+            assert!(steps <= newton_steps);
+        }
+    }
+
+    #[test]
+    fn test_solve_iv_deep_otm_wing() {
+        // Far out-of-the-money prices depend on the far tails of N(d)
+        let params = IVParams::call(100.0, 250.0, 0.25, 0.0);
+        let vol = 0.4;
+        let price = BlackScholes::price(&params, vol);
+        assert!(price > 0.0 && price < 1e-3);
+
+        let config = SolverConfig::default()
+            .with_tolerance(price * 1e-10)
+            .with_method(SolverMethod::Householder);
+        let (iv, _) = solve_iv(&params, price, &config).unwrap();
+        assert!((iv - vol).abs() < 1e-8, "{iv}");
+        let (iv, _) = solve_iv_bisection(&params, price, &config).unwrap();
+        assert!((iv - vol).abs() < 1e-6, "{iv}");
+    }
+
+    #[test]
+    fn test_solve_iv_rational() {
+        let rational = SolverConfig::default().with_method(SolverMethod::Rational);
+        for (params, vol) in [
+            (IVParams::call(100.0, 100.0, 0.25, 0.05), 0.25),
+            (
+                IVParams::put(100.0, 80.0, 0.5, 0.02).with_dividend_yield(0.01),
+                0.45,
+            ),
+            (IVParams::call(100.0, 130.0, 1.0, 0.03), 0.15),
+            (IVParams::call(100.0, 70.0, 0.1, 0.0), 0.6),
+            (IVParams::put(100.0, 120.0, 2.0, 0.0), 0.3),
+            (IVParams::call(100.0, 100.0, 0.01, 0.0), 0.02),
+            (IVParams::put(100.0, 60.0, 0.5, 0.0), 2.5),
+        ] {
+            let price = BlackScholes::price(&params, vol);
+            let (iv, steps) = solve_iv(&params, price, &rational).unwrap();
+            assert!(((iv - vol) / vol).abs() < 1e-10, "{params:?}: {iv}");
+            assert!(steps <= 2);
+        }
+    }
+
+    #[test]
+    fn test_solve_iv_rational_deep_otm_wing() {
+        let params = IVParams::call(100.0, 250.0, 0.25, 0.0);
+        let price = BlackScholes::price(&params, 0.4);
+        let config = SolverConfig::default().with_method(SolverMethod::Rational);
+        let (iv, _) = solve_iv(&params, price, &config).unwrap();
+        assert!((iv - 0.4).abs() < 1e-10, "{iv}");
+
+        // Above the discounted forward no volatility matches the price
+        let params = IVParams::call(100.0, 90.0, 0.25, 0.0);
+        assert!(matches!(
+            solve_iv(&params, 100.5, &config),
+            Err(IVError::VolatilityOutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn test_solve_iv_bisection_method() {
+        let params = IVParams::put(100.0, 95.0, 0.5, 0.03);
+        let price = BlackScholes::price(&params, 0.3);
+        let config = SolverConfig::default().with_method(SolverMethod::Bisection);
+        assert_eq!(
+            solve_iv(&params, price, &config).unwrap(),
+            solve_iv_bisection(&params, price, &config).unwrap()
+        );
+    }
+
     #[test]
     fn test_solver_config_builder() {
         let config = SolverConfig::new()
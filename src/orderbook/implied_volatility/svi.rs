@@ -0,0 +1,446 @@
+//! Raw SVI parameterization of per-expiry volatility smiles.
+//!
+//! The raw SVI (stochastic volatility inspired) parameterization models the
+//! total implied variance of one expiry as a function of forward
+//! log-moneyness `k`:
+//!
+//! `w(k) = a + b·(ρ·(k - m) + √((k - m)² + σ²))`
+//!
+//! Fitting it by least squares turns the noisy IVs solved from option books
+//! into a smooth curve that can be evaluated at any strike.
+
+use serde::{Deserialize, Serialize};
+
+/// Largest wing slope `b·(1 + |ρ|)` allowed by Roger Lee's moment formula.
+const MAX_WING_SLOPE: f64 = 2.0;
+
+/// Bound on `|ρ|` keeping the correlation strictly inside (-1, 1).
+const MAX_RHO: f64 = 0.999;
+
+/// Number of log-moneyness points on which the density is checked.
+const DENSITY_GRID_POINTS: usize = 101;
+
+/// Weight of negative densities in the fit objective.
+const DENSITY_PENALTY: f64 = 1e3;
+
+/// Nelder-Mead iterations per start.
+const MAX_ITERATIONS: usize = 4000;
+
+/// Minimum number of points to fit the five parameters.
+pub const SVI_MIN_POINTS: usize = 5;
+
+/// Parameters of a raw SVI total variance slice.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SviParams {
+    /// Vertical level of the variance.
+    pub a: f64,
+    /// Slope of the wings.
+    pub b: f64,
+    /// Rotation of the smile, between -1 and 1.
+    pub rho: f64,
+    /// Horizontal translation of the smile.
+    pub m: f64,
+    /// Curvature at the minimum.
+    pub sigma: f64,
+}
+
+impl SviParams {
+    /// Total implied variance `w(k)` at forward log-moneyness `k`.
+    #[must_use]
+    pub fn total_variance(&self, k: f64) -> f64 {
+        let x = k - self.m;
+        self.a + self.b * (self.rho * x + (x * x + self.sigma * self.sigma).sqrt())
+    }
+
+    /// Implied volatility at forward log-moneyness `k` for an expiry in years.
+    #[must_use]
+    pub fn iv(&self, k: f64, expiry: f64) -> f64 {
+        (self.total_variance(k).max(0.0) / expiry).sqrt()
+    }
+
+    /// Gatheral's risk-neutral density function `g(k)`.
+    ///
+    /// The slice is free of butterfly arbitrage where `g(k) ≥ 0`.
+    #[must_use]
+    pub fn density(&self, k: f64) -> f64 {
+        let x = k - self.m;
+        let root = (x * x + self.sigma * self.sigma).sqrt();
+        let w = self.total_variance(k);
+        if w <= 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        let w1 = self.b * (self.rho + x / root);
+        let w2 = self.b * self.sigma * self.sigma / (root * root * root);
+        let term = 1.0 - k * w1 / (2.0 * w);
+        term * term - w1 * w1 / 4.0 * (1.0 / w + 0.25) + w2 / 2.0
+    }
+
+    /// Whether the density is non-negative over `[k_min, k_max]`, checked on
+    /// an evenly spaced grid.
+    #[must_use]
+    pub fn is_butterfly_free(&self, k_min: f64, k_max: f64) -> bool {
+        density_grid(k_min, k_max).all(|k| self.density(k) >= 0.0)
+    }
+
+    /// Fits raw SVI parameters to `(log_moneyness, iv)` points of one expiry.
+    ///
+    /// Minimizes the squared errors in total variance with Nelder-Mead. The
+    /// parameterization keeps `b ≥ 0`, `|ρ| < 1`, `σ > 0`, a non-negative
+    /// minimum variance and wing slopes within Lee's bound, and negative
+    /// densities over the quoted range are penalized so the fitted slice is
+    /// free of butterfly arbitrage.
+    ///
+    /// # Arguments
+    /// - `points`: Quoted `(log_moneyness, iv)` pairs, with `k = ln(K / F)`
+    /// - `expiry`: Time to expiration in years
+    ///
+    /// # Returns
+    /// - `Some(fit)` with the parameters and the residuals in IV terms
+    /// - `None` with fewer than [`SVI_MIN_POINTS`] valid points or a non-positive expiry
+    ///
+    /// # Example
+    /// ```
+    /// use orderbook_rs::orderbook::implied_volatility::SviParams;
+    ///
+    /// let truth = SviParams { a: 0.01, b: 0.1, rho: -0.4, m: 0.05, sigma: 0.2 };
+    /// let points: Vec<(f64, f64)> = (-6..=6)
+    ///     .map(|i| {
+    ///         let k = f64::from(i) * 0.05;
+    ///         (k, truth.iv(k, 0.5))
+    ///     })
+    ///     .collect();
+    ///
+    /// let fit = SviParams::fit(&points, 0.5).unwrap();
+    /// assert!(fit.rmse < 1e-3);
+    /// assert!(fit.butterfly_free);
+    /// ```
+    #[must_use]
+    pub fn fit(points: &[(f64, f64)], expiry: f64) -> Option<SviFit> {
+        let points: Vec<(f64, f64)> = points
+            .iter()
+            .copied()
+            .filter(|&(k, iv)| k.is_finite() && iv.is_finite() && iv > 0.0)
+            .collect();
+        if points.len() < SVI_MIN_POINTS || expiry <= 0.0 || expiry.is_nan() {
+            return None;
+        }
+        let variances: Vec<(f64, f64)> = points
+            .iter()
+            .map(|&(k, iv)| (k, iv * iv * expiry))
+            .collect();
+        let k_min = points.iter().map(|&(k, _)| k).fold(f64::INFINITY, f64::min);
+        let k_max = points
+            .iter()
+            .map(|&(k, _)| k)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let w_min = variances
+            .iter()
+            .map(|&(_, w)| w)
+            .fold(f64::INFINITY, f64::min);
+        let width = (k_max - k_min).max(1e-4);
+
+        let objective = |x: &[f64; 5]| {
+            let params = Self::from_unconstrained(x);
+            let error: f64 = variances
+                .iter()
+                .map(|&(k, w)| (params.total_variance(k) - w).powi(2))
+                .sum();
+            let negative_density: f64 = density_grid(k_min, k_max)
+                .map(|k| params.density(k).min(0.0).powi(2))
+                .sum();
+            error + DENSITY_PENALTY * w_min * w_min * negative_density
+        };
+
+        let mut best: Option<([f64; 5], f64)> = None;
+        for (rho, m) in [(-0.5, 0.0), (0.0, 0.0), (-0.5, k_min), (-0.5, k_max)] {
+            let start = Self::to_unconstrained(&SviParams {
+                a: 0.5 * w_min,
+                b: 0.1,
+                rho,
+                m,
+                sigma: 0.25 * width,
+            });
+            let (x, value) = nelder_mead(&objective, start, MAX_ITERATIONS);
+            if best.is_none_or(|(_, best_value)| value < best_value) {
+                best = Some((x, value));
+            }
+        }
+        let (x, _) = best?;
+        let params = Self::from_unconstrained(&x);
+
+        let residuals: Vec<SviResidual> = points
+            .iter()
+            .map(|&(log_moneyness, market_iv)| {
+                let fitted_iv = params.iv(log_moneyness, expiry);
+                SviResidual {
+                    log_moneyness,
+                    market_iv,
+                    fitted_iv,
+                    residual: fitted_iv - market_iv,
+                }
+            })
+            .collect();
+        let rmse = (residuals
+            .iter()
+            .map(|residual| residual.residual * residual.residual)
+            .sum::<f64>()
+            / residuals.len() as f64)
+            .sqrt();
+
+        Some(SviFit {
+            params,
+            expiry,
+            rmse,
+            butterfly_free: params.is_butterfly_free(k_min, k_max),
+            residuals,
+        })
+    }
+
+    /// Maps unconstrained optimizer coordinates to admissible parameters.
+    fn from_unconstrained(x: &[f64; 5]) -> Self {
+        let rho = MAX_RHO * x[2].tanh();
+        let b = MAX_WING_SLOPE / (1.0 + rho.abs()) / (1.0 + (-x[1]).exp());
+        let sigma = x[4].exp();
+        // Minimum variance a + b·σ·√(1-ρ²) is kept non-negative
+        let a = x[0].exp() - b * sigma * (1.0 - rho * rho).sqrt();
+        Self {
+            a,
+            b,
+            rho,
+            m: x[3],
+            sigma,
+        }
+    }
+
+    /// Inverse of [`SviParams::from_unconstrained`] for admissible parameters.
+    fn to_unconstrained(params: &Self) -> [f64; 5] {
+        let rho = params.rho.clamp(-MAX_RHO * 0.99, MAX_RHO * 0.99);
+        let bound = MAX_WING_SLOPE / (1.0 + rho.abs());
+        let share = (params.b / bound).clamp(1e-6, 1.0 - 1e-6);
+        let sigma = params.sigma.max(1e-6);
+        let min_variance = params.a + params.b * sigma * (1.0 - rho * rho).sqrt();
+        [
+            min_variance.max(1e-12).ln(),
+            (share / (1.0 - share)).ln(),
+            (rho / MAX_RHO).atanh(),
+            params.m,
+            sigma.ln(),
+        ]
+    }
+}
+
+/// Difference between the fitted and the quoted IV of one point.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SviResidual {
+    /// Forward log-moneyness `ln(K / F)` of the point.
+    pub log_moneyness: f64,
+    /// Quoted implied volatility.
+    pub market_iv: f64,
+    /// Implied volatility of the fitted slice.
+    pub fitted_iv: f64,
+    /// `fitted_iv - market_iv`.
+    pub residual: f64,
+}
+
+/// Raw SVI slice fitted to the quoted IVs of one expiry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SviFit {
+    /// Fitted parameters.
+    pub params: SviParams,
+    /// Time to expiration in years.
+    pub expiry: f64,
+    /// Root mean square of the IV residuals.
+    pub rmse: f64,
+    /// Whether the fitted density is non-negative over the quoted range.
+    pub butterfly_free: bool,
+    /// Residual of each quoted point.
+    pub residuals: Vec<SviResidual>,
+}
+
+impl SviFit {
+    /// Implied volatility of the fitted slice at forward log-moneyness `k`.
+    #[must_use]
+    pub fn iv(&self, k: f64) -> f64 {
+        self.params.iv(k, self.expiry)
+    }
+}
+
+/// Evenly spaced log-moneyness points over `[k_min, k_max]`.
+fn density_grid(k_min: f64, k_max: f64) -> impl Iterator<Item = f64> {
+    let step = (k_max - k_min) / (DENSITY_GRID_POINTS - 1) as f64;
+    (0..DENSITY_GRID_POINTS).map(move |i| k_min + step * i as f64)
+}
+
+/// Minimizes `f` with the Nelder-Mead simplex method from `start`.
+fn nelder_mead<F>(f: &F, start: [f64; 5], max_iterations: usize) -> ([f64; 5], f64)
+where
+    F: Fn(&[f64; 5]) -> f64,
+{
+    const N: usize = 5;
+    let mut simplex: Vec<([f64; N], f64)> = Vec::with_capacity(N + 1);
+    simplex.push((start, f(&start)));
+    for i in 0..N {
+        let mut vertex = start;
+        vertex[i] += if vertex[i].abs() > 1e-3 {
+            0.1 * vertex[i].abs()
+        } else {
+            0.1
+        };
+        simplex.push((vertex, f(&vertex)));
+    }
+
+    for _ in 0..max_iterations {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (best, worst) = (simplex[0].1, simplex[N].1);
+        if (worst - best).abs() <= 1e-16 * (1.0 + best.abs()) {
+            break;
+        }
+
+        let mut centroid = [0.0; N];
+        for (vertex, _) in &simplex[..N] {
+            for i in 0..N {
+                centroid[i] += vertex[i] / N as f64;
+            }
+        }
+        let towards = |coefficient: f64| {
+            let mut point = [0.0; N];
+            for i in 0..N {
+                point[i] = centroid[i] + coefficient * (simplex[N].0[i] - centroid[i]);
+            }
+            point
+        };
+
+        let reflected = towards(-1.0);
+        let reflected_value = f(&reflected);
+        if reflected_value < best {
+            let expanded = towards(-2.0);
+            let expanded_value = f(&expanded);
+            simplex[N] = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+        } else if reflected_value < simplex[N - 1].1 {
+            simplex[N] = (reflected, reflected_value);
+        } else {
+            let contracted = if reflected_value < worst {
+                towards(-0.5)
+            } else {
+                towards(0.5)
+            };
+            let contracted_value = f(&contracted);
+            if contracted_value < worst.min(reflected_value) {
+                simplex[N] = (contracted, contracted_value);
+            } else {
+                let best_vertex = simplex[0].0;
+                for (vertex, value) in simplex.iter_mut().skip(1) {
+                    for i in 0..N {
+                        vertex[i] = best_vertex[i] + 0.5 * (vertex[i] - best_vertex[i]);
+                    }
+                    *value = f(vertex);
+                }
+            }
+        }
+    }
+
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    simplex.swap_remove(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(params: &SviParams, expiry: f64) -> Vec<(f64, f64)> {
+        (-8..=8)
+            .map(|i| {
+                let k = f64::from(i) * 0.04;
+                (k, params.iv(k, expiry))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_total_variance_and_density() {
+        let params = SviParams {
+            a: 0.02,
+            b: 0.1,
+            rho: -0.3,
+            m: 0.0,
+            sigma: 0.1,
+        };
+        // At k = m: a + b·σ
+        assert!((params.total_variance(0.0) - 0.03).abs() < 1e-12);
+        assert!((params.iv(0.0, 0.75) - (0.03f64 / 0.75).sqrt()).abs() < 1e-12);
+        assert!(params.is_butterfly_free(-1.0, 1.0));
+
+        // A steep, tightly curved smile with little variance has negative density
+        let arbitrage = SviParams {
+            a: -0.05,
+            b: 1.0,
+            rho: -0.9,
+            m: 0.0,
+            sigma: 0.06,
+        };
+        assert!(!arbitrage.is_butterfly_free(-0.5, 0.5));
+    }
+
+    #[test]
+    fn test_fit_recovers_smile() {
+        let truth = SviParams {
+            a: 0.015,
+            b: 0.12,
+            rho: -0.35,
+            m: 0.02,
+            sigma: 0.15,
+        };
+        let fit = SviParams::fit(&sample(&truth, 0.5), 0.5).unwrap();
+
+        assert!(fit.rmse < 5e-4, "rmse {}", fit.rmse);
+        assert!(fit.butterfly_free);
+        assert_eq!(fit.residuals.len(), 17);
+        assert!(fit.residuals.iter().all(|r| r.residual.abs() < 2e-3));
+        assert!((fit.iv(0.1) - truth.iv(0.1, 0.5)).abs() < 2e-3);
+    }
+
+    #[test]
+    fn test_fit_noisy_smile_is_arbitrage_free() {
+        let truth = SviParams {
+            a: 0.03,
+            b: 0.2,
+            rho: -0.5,
+            m: 0.0,
+            sigma: 0.2,
+        };
+        let noisy: Vec<(f64, f64)> = sample(&truth, 1.0)
+            .into_iter()
+            .enumerate()
+            .map(|(i, (k, iv))| (k, iv + if i % 2 == 0 { 0.004 } else { -0.004 }))
+            .collect();
+        let fit = SviParams::fit(&noisy, 1.0).unwrap();
+
+        assert!(fit.butterfly_free);
+        assert!(fit.rmse < 0.006);
+        let params = fit.params;
+        assert!(params.b >= 0.0);
+        assert!(params.rho.abs() < 1.0);
+        assert!(params.sigma > 0.0);
+        assert!(params.b * (1.0 + params.rho.abs()) <= MAX_WING_SLOPE + 1e-12);
+        assert!(params.a + params.b * params.sigma * (1.0 - params.rho.powi(2)).sqrt() >= 0.0);
+    }
+
+    #[test]
+    fn test_fit_rejects_too_few_points() {
+        let points = [(-0.1, 0.25), (0.0, 0.2), (0.1, 0.22), (0.2, f64::NAN)];
+        assert!(SviParams::fit(&points, 0.5).is_none());
+        let points = [
+            (-0.2, 0.3),
+            (-0.1, 0.25),
+            (0.0, 0.2),
+            (0.1, 0.22),
+            (0.2, 0.24),
+        ];
+        assert!(SviParams::fit(&points, 0.0).is_none());
+        assert!(SviParams::fit(&points, 0.5).is_some());
+    }
+}
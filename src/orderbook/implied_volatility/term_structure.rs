@@ -0,0 +1,307 @@
+//! At-the-money implied volatility term structure of one underlying.
+//!
+//! The ATM implied volatilities of several expiries are collected into a
+//! term structure. Between expiries total variance `w = σ²·T` is
+//! interpolated linearly in time, which also gives the forward volatility
+//! implied between any two expiries.
+
+use super::smile::IVSmile;
+use serde::{Deserialize, Serialize};
+
+/// Relative tolerance of the calendar arbitrage check.
+const CALENDAR_TOLERANCE: f64 = 1e-9;
+
+/// At-the-money implied volatility of one expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TermPoint {
+    /// Time to expiration in years.
+    pub expiry: f64,
+    /// At-the-money implied volatility.
+    pub atm_iv: f64,
+}
+
+impl TermPoint {
+    /// Total implied variance `σ²·T` of the expiry.
+    #[must_use]
+    pub fn total_variance(&self) -> f64 {
+        self.atm_iv * self.atm_iv * self.expiry
+    }
+}
+
+/// At-the-money implied volatilities across expiries.
+///
+/// # Example
+/// ```
+/// use orderbook_rs::orderbook::implied_volatility::TermStructure;
+///
+/// let term = TermStructure::from_points([(0.25, 0.30), (1.0, 0.20)]);
+///
+/// // Total variance is linear in time between the expiries
+/// let w = 0.30_f64.powi(2) * 0.25 + (0.20_f64.powi(2) - 0.30_f64.powi(2) * 0.25) / 0.75 * 0.25;
+/// assert!((term.iv(0.5).unwrap() - (w / 0.5).sqrt()).abs() < 1e-12);
+///
+/// // Forward volatility between the two expiries
+/// let forward = term.forward_vol(0.25, 1.0).unwrap();
+/// assert!((forward - ((0.04 - 0.0225) / 0.75_f64).sqrt()).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TermStructure {
+    points: Vec<TermPoint>,
+}
+
+impl TermStructure {
+    /// Creates an empty term structure.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a term structure from `(expiry, atm_iv)` pairs, see
+    /// [`TermStructure::add_point`].
+    #[must_use]
+    pub fn from_points(points: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        let mut term = Self::new();
+        for (expiry, atm_iv) in points {
+            term.add_point(expiry, atm_iv);
+        }
+        term
+    }
+
+    /// Adds the ATM implied volatility of an expiry, replacing any point at
+    /// the same expiry.
+    ///
+    /// # Returns
+    /// `false`, leaving the term structure unchanged, if `expiry` or `atm_iv`
+    /// is not positive and finite
+    pub fn add_point(&mut self, expiry: f64, atm_iv: f64) -> bool {
+        let valid = |value: f64| value > 0.0 && value.is_finite();
+        if !valid(expiry) || !valid(atm_iv) {
+            return false;
+        }
+
+        let point = TermPoint { expiry, atm_iv };
+        match self
+            .points
+            .binary_search_by(|existing| existing.expiry.total_cmp(&expiry))
+        {
+            Ok(index) => self.points[index] = point,
+            Err(index) => self.points.insert(index, point),
+        }
+        true
+    }
+
+    /// Adds the implied volatility of a smile at `atm_strike`, usually the
+    /// forward of its expiry, see [`IVSmile::iv_at_strike`].
+    ///
+    /// # Returns
+    /// `false` if the smile has no solved strike
+    pub fn add_smile(&mut self, smile: &IVSmile, atm_strike: f64) -> bool {
+        smile
+            .iv_at_strike(atm_strike)
+            .is_some_and(|atm_iv| self.add_point(smile.expiry, atm_iv))
+    }
+
+    /// Points of the term structure, in ascending expiry order.
+    #[must_use]
+    pub fn points(&self) -> &[TermPoint] {
+        &self.points
+    }
+
+    /// Number of expiries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether the term structure has no expiry.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Total implied variance `σ²·T` at `expiry`.
+    ///
+    /// Total variance is interpolated linearly in time between expiries.
+    /// Before the first and after the last expiry the volatility is extended
+    /// flat.
+    ///
+    /// # Returns
+    /// `None` if the term structure is empty or `expiry` is not positive
+    #[must_use]
+    pub fn total_variance(&self, expiry: f64) -> Option<f64> {
+        if expiry <= 0.0 || !expiry.is_finite() {
+            return None;
+        }
+        let first = self.points.first()?;
+        let last = self.points.last()?;
+        if expiry <= first.expiry {
+            return Some(first.atm_iv * first.atm_iv * expiry);
+        }
+        if expiry >= last.expiry {
+            return Some(last.atm_iv * last.atm_iv * expiry);
+        }
+
+        let upper = self.points.partition_point(|point| point.expiry < expiry);
+        let (short, long) = (&self.points[upper - 1], &self.points[upper]);
+        let weight = (expiry - short.expiry) / (long.expiry - short.expiry);
+        Some(short.total_variance() * (1.0 - weight) + long.total_variance() * weight)
+    }
+
+    /// At-the-money implied volatility at `expiry`, see
+    /// [`TermStructure::total_variance`].
+    #[must_use]
+    pub fn iv(&self, expiry: f64) -> Option<f64> {
+        self.total_variance(expiry)
+            .map(|variance| (variance / expiry).sqrt())
+    }
+
+    /// Forward volatility implied between the expiries `start` and `end`:
+    /// `√((w(end) − w(start)) / (end − start))`.
+    ///
+    /// # Returns
+    /// `None` if the term structure is empty, `start` is not positive,
+    /// `end` is not after `start`, or total variance decreases between them
+    #[must_use]
+    pub fn forward_vol(&self, start: f64, end: f64) -> Option<f64> {
+        if end <= start {
+            return None;
+        }
+        let forward_variance = self.total_variance(end)? - self.total_variance(start)?;
+        (forward_variance >= 0.0).then(|| (forward_variance / (end - start)).sqrt())
+    }
+
+    /// Forward volatilities between consecutive expiries, as
+    /// `(start, end, forward_vol)`. The forward volatility is `None` where
+    /// total variance decreases.
+    #[must_use]
+    pub fn forward_vols(&self) -> Vec<(f64, f64, Option<f64>)> {
+        self.points
+            .windows(2)
+            .map(|pair| {
+                let (start, end) = (pair[0].expiry, pair[1].expiry);
+                (start, end, self.forward_vol(start, end))
+            })
+            .collect()
+    }
+
+    /// Whether total variance never decreases with expiry, so that every
+    /// forward volatility exists.
+    #[must_use]
+    pub fn is_arbitrage_free(&self) -> bool {
+        self.points.windows(2).all(|pair| {
+            let (short, long) = (pair[0].total_variance(), pair[1].total_variance());
+            long >= short * (1.0 - CALENDAR_TOLERANCE)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::implied_volatility::{BlackScholes, IVConfig, OptionType, SmileConfig};
+    use crate::orderbook::manager::{BookManager, BookManagerStd};
+    use pricelevel::{OrderId, Side, TimeInForce};
+
+    #[test]
+    fn test_points_sorted_and_replaced() {
+        let mut term = TermStructure::from_points([(1.0, 0.2), (0.25, 0.3), (0.5, 0.25)]);
+        let expiries: Vec<f64> = term.points().iter().map(|point| point.expiry).collect();
+        assert_eq!(expiries, vec![0.25, 0.5, 1.0]);
+
+        assert!(term.add_point(0.5, 0.22));
+        assert_eq!(term.len(), 3);
+        assert_eq!(term.points()[1].atm_iv, 0.22);
+
+        assert!(!term.add_point(0.0, 0.2));
+        assert!(!term.add_point(2.0, -0.2));
+        assert!(!term.add_point(f64::NAN, 0.2));
+        assert_eq!(term.len(), 3);
+    }
+
+    #[test]
+    fn test_interpolation_in_total_variance() {
+        let term = TermStructure::from_points([(0.25, 0.3), (1.0, 0.2)]);
+
+        assert_eq!(term.iv(0.25), Some(0.3));
+        assert!((term.iv(1.0).unwrap() - 0.2).abs() < 1e-15);
+        // Flat beyond the quoted expiries
+        assert!((term.iv(0.1).unwrap() - 0.3).abs() < 1e-15);
+        assert!((term.iv(2.0).unwrap() - 0.2).abs() < 1e-15);
+
+        let midpoint = (0.3f64.powi(2) * 0.25 + 0.2f64.powi(2)) / 2.0;
+        assert!((term.total_variance(0.625).unwrap() - midpoint).abs() < 1e-15);
+
+        assert!(term.iv(0.0).is_none());
+        assert!(TermStructure::new().iv(1.0).is_none());
+    }
+
+    #[test]
+    fn test_forward_vol() {
+        let term = TermStructure::from_points([(0.5, 0.2), (1.0, 0.25)]);
+        let forward = term.forward_vol(0.5, 1.0).unwrap();
+        let expected = ((0.0625 - 0.02) / 0.5f64).sqrt();
+        assert!((forward - expected).abs() < 1e-12);
+        assert!(forward > 0.25);
+
+        // Beyond the last expiry the forward volatility is the flat one
+        assert!((term.forward_vol(1.0, 2.0).unwrap() - 0.25).abs() < 1e-12);
+        assert!(term.forward_vol(1.0, 0.5).is_none());
+        assert!(term.is_arbitrage_free());
+
+        let inverted = TermStructure::from_points([(0.5, 0.4), (1.0, 0.2)]);
+        assert!(!inverted.is_arbitrage_free());
+        assert!(inverted.forward_vol(0.5, 1.0).is_none());
+        assert_eq!(inverted.forward_vols(), vec![(0.5, 1.0, None)]);
+    }
+
+    #[test]
+    fn test_term_structure_from_manager() {
+        let spot = 100.0;
+        let config = SmileConfig::new()
+            .with_iv_config(IVConfig::default().with_price_scale(100.0))
+            .with_option_type(OptionType::Call);
+        let expiries = [30.0 / 365.0, 90.0 / 365.0, 180.0 / 365.0];
+        let strikes = [90.0, 95.0, 100.0, 105.0, 110.0];
+        let symbol =
+            |expiry: f64, strike: f64| format!("XYZ-{}D-C-{strike}", (expiry * 365.0).round());
+
+        let mut manager = BookManagerStd::<()>::new();
+        for expiry in expiries {
+            let vol = 0.3 - expiry * 0.1;
+            for strike in strikes {
+                let price = BlackScholes::price(&config.params(spot, strike, expiry), vol);
+                let cents = (price * 100.0).round() as u64;
+                let name = symbol(expiry, strike);
+                manager.add_book(&name);
+                let book = manager.get_book(&name).unwrap();
+                let _ = book.add_limit_order(
+                    OrderId::new(),
+                    cents,
+                    10,
+                    Side::Buy,
+                    TimeInForce::Gtc,
+                    None,
+                );
+                let _ = book.add_limit_order(
+                    OrderId::new(),
+                    cents + 1,
+                    10,
+                    Side::Sell,
+                    TimeInForce::Gtc,
+                    None,
+                );
+            }
+        }
+
+        // A fourth expiry without books is left out
+        let with_missing = [expiries[0], expiries[1], expiries[2], 1.0];
+        let term = manager.term_structure(spot, &with_missing, &strikes, &symbol, &config);
+
+        assert_eq!(term.len(), 3);
+        for (point, expiry) in term.points().iter().zip(expiries) {
+            assert_eq!(point.expiry, expiry);
+            assert!((point.atm_iv - (0.3 - expiry * 0.1)).abs() < 0.01);
+        }
+        assert!(term.is_arbitrage_free());
+    }
+}
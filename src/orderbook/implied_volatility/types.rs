@@ -11,6 +11,32 @@ pub enum OptionType {
     Put,
 }
 
+/// Default number of binomial tree steps for American options.
+pub const DEFAULT_BINOMIAL_STEPS: u32 = 200;
+
+/// Exercise style of an option, which selects the pricing model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExerciseStyle {
+    /// Exercisable only at expiry, priced with Black-Scholes.
+    #[default]
+    European,
+    /// Exercisable at any time, priced on a Cox-Ross-Rubinstein binomial tree.
+    American {
+        /// Number of time steps of the tree.
+        steps: u32,
+    },
+}
+
+impl ExerciseStyle {
+    /// American exercise with [`DEFAULT_BINOMIAL_STEPS`] tree steps.
+    #[must_use]
+    pub fn american() -> Self {
+        Self::American {
+            steps: DEFAULT_BINOMIAL_STEPS,
+        }
+    }
+}
+
 /// Price source for IV calculation from order book.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PriceSource {
@@ -21,11 +47,27 @@ pub enum PriceSource {
     WeightedMid,
     /// Last traded price from the order book.
     LastTrade,
+    /// Mid of the volume-weighted average bid and ask over the best `levels`
+    /// levels of each side (at least one), each weighted by the depth of the
+    /// opposite side. With one level this is [`PriceSource::WeightedMid`].
+    DepthWeighted {
+        /// Number of price levels per side.
+        levels: usize,
+    },
+    /// Best bid, a conservative mark for long positions.
+    BidOnly,
+    /// Best ask, a conservative mark for short positions.
+    AskOnly,
+    /// Simple mid price, raised to the intrinsic value of the option when
+    /// below it.
+    ClampedMid,
 }
 
 /// IV calculation quality indicator based on liquidity.
 ///
-/// Quality is determined by the bid-ask spread at calculation time.
+/// Quality is determined by the bid-ask spread at calculation time, and
+/// lowered one level per depth or staleness threshold of the `IVConfig` that
+/// the book misses.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IVQuality {
     /// Spread < 100 bps (1%), high liquidity.
@@ -52,8 +94,15 @@ pub struct IVParams {
     pub time_to_expiry: f64,
     /// Risk-free interest rate (annualized, e.g., 0.05 for 5%).
     pub risk_free_rate: f64,
+    /// Continuous dividend or borrow yield of the underlying (annualized,
+    /// e.g., 0.02 for 2%). For FX options this is the foreign interest rate.
+    #[serde(default)]
+    pub dividend_yield: f64,
     /// Option type (Call or Put).
     pub option_type: OptionType,
+    /// Exercise style, European unless set.
+    #[serde(default)]
+    pub exercise_style: ExerciseStyle,
 }
 
 impl IVParams {
@@ -78,7 +127,9 @@ impl IVParams {
             strike,
             time_to_expiry,
             risk_free_rate,
+            dividend_yield: 0.0,
             option_type,
+            exercise_style: ExerciseStyle::European,
         }
     }
 
@@ -106,6 +157,52 @@ impl IVParams {
         )
     }
 
+    /// Sets the continuous dividend or borrow yield.
+    #[must_use]
+    pub fn with_dividend_yield(mut self, dividend_yield: f64) -> Self {
+        self.dividend_yield = dividend_yield;
+        self
+    }
+
+    /// Sets the exercise style.
+    #[must_use]
+    pub fn with_exercise_style(mut self, exercise_style: ExerciseStyle) -> Self {
+        self.exercise_style = exercise_style;
+        self
+    }
+
+    /// Cost of carry of the underlying, `r - q`.
+    #[must_use]
+    pub fn cost_of_carry(&self) -> f64 {
+        self.risk_free_rate - self.dividend_yield
+    }
+
+    /// Discount factor applied to the spot for the dividend yield, `e^(-qT)`.
+    #[must_use]
+    pub fn dividend_discount(&self) -> f64 {
+        (-self.dividend_yield * self.time_to_expiry).exp()
+    }
+
+    /// Calculates the intrinsic value against the dividend-discounted spot.
+    ///
+    /// For calls: max(0, spot·e^(-qT) - strike)
+    /// For puts: max(0, strike - spot·e^(-qT))
+    ///
+    /// Equal to [`IVParams::intrinsic_value`] without a dividend yield, and for
+    /// American options, which can always be exercised immediately. The
+    /// solvers use it as the lowest acceptable market price.
+    #[must_use]
+    pub fn carry_adjusted_intrinsic_value(&self) -> f64 {
+        if let ExerciseStyle::American { .. } = self.exercise_style {
+            return self.intrinsic_value();
+        }
+        let spot = self.spot * self.dividend_discount();
+        match self.option_type {
+            OptionType::Call => (spot - self.strike).max(0.0),
+            OptionType::Put => (self.strike - spot).max(0.0),
+        }
+    }
+
     /// Calculates the intrinsic value of the option.
     ///
     /// For calls: max(0, spot - strike)
@@ -153,6 +250,10 @@ pub struct IVResult {
     pub iterations: u32,
     /// Calculation quality based on liquidity.
     pub quality: IVQuality,
+    /// Greeks and d1/d2 at the solved volatility, when requested with
+    /// `IVConfig::with_diagnostics`.
+    #[serde(default)]
+    pub diagnostics: Option<IVDiagnostics>,
 }
 
 impl IVResult {
@@ -171,9 +272,17 @@ impl IVResult {
             spread_bps,
             iterations,
             quality,
+            diagnostics: None,
         }
     }
 
+    /// Attaches the diagnostics computed at the solved volatility.
+    #[must_use]
+    pub fn with_diagnostics(mut self, diagnostics: IVDiagnostics) -> Self {
+        self.diagnostics = Some(diagnostics);
+        self
+    }
+
     /// Returns the IV as a percentage (e.g., 25.0 for 25%).
     #[must_use]
     pub fn iv_percent(&self) -> f64 {
@@ -193,6 +302,43 @@ impl IVResult {
     }
 }
 
+/// Black-Scholes terms and Greeks of an option at its solved implied
+/// volatility.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IVDiagnostics {
+    /// The d1 term of the Black-Scholes formula, with the cost of carry.
+    pub d1: f64,
+    /// The d2 term of the Black-Scholes formula, `d1 - σ√T`.
+    pub d2: f64,
+    /// Sensitivity of the price to the underlying price.
+    pub delta: f64,
+    /// Rate of change of delta with the underlying price.
+    pub gamma: f64,
+    /// Sensitivity of the price to volatility, per unit of volatility.
+    pub vega: f64,
+    /// Daily time decay of the price.
+    pub theta: f64,
+    /// Sensitivity of the price to the risk-free rate, per unit of rate.
+    pub rho: f64,
+}
+
+/// Implied volatility of an option together with its Greeks at that volatility.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GreeksResult {
+    /// Implied volatility solved from the order book, with its metadata.
+    pub iv: IVResult,
+    /// Sensitivity of the price to the underlying price.
+    pub delta: f64,
+    /// Rate of change of delta with the underlying price.
+    pub gamma: f64,
+    /// Sensitivity of the price to volatility, per unit of volatility.
+    pub vega: f64,
+    /// Daily time decay of the price.
+    pub theta: f64,
+    /// Sensitivity of the price to the risk-free rate, per unit of rate.
+    pub rho: f64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,6 +354,29 @@ mod tests {
         assert_eq!(json, "\"Put\"");
     }
 
+    #[test]
+    fn test_exercise_style() {
+        let params = IVParams::put(100.0, 110.0, 1.0, 0.05).with_dividend_yield(0.1);
+        assert_eq!(params.exercise_style, ExerciseStyle::European);
+        assert!(params.carry_adjusted_intrinsic_value() > params.intrinsic_value());
+
+        let params = params.with_exercise_style(ExerciseStyle::american());
+        assert_eq!(
+            params.exercise_style,
+            ExerciseStyle::American {
+                steps: DEFAULT_BINOMIAL_STEPS
+            }
+        );
+        assert_eq!(
+            params.carry_adjusted_intrinsic_value(),
+            params.intrinsic_value()
+        );
+
+        let json = serde_json::to_string(&params).unwrap();
+        let parsed: IVParams = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.exercise_style, params.exercise_style);
+    }
+
     #[test]
     fn test_price_source_default() {
         let source = PriceSource::default();
@@ -237,6 +406,26 @@ mod tests {
         assert!(params.is_otm());
     }
 
+    #[test]
+    fn test_iv_params_dividend_yield() {
+        let params = IVParams::call(110.0, 100.0, 1.0, 0.05);
+        assert_eq!(params.dividend_yield, 0.0);
+        assert_eq!(
+            params.carry_adjusted_intrinsic_value(),
+            params.intrinsic_value()
+        );
+
+        let params = params.with_dividend_yield(0.03);
+        assert!((params.cost_of_carry() - 0.02).abs() < 1e-12);
+        assert!((params.dividend_discount() - (-0.03f64).exp()).abs() < 1e-12);
+        assert!(params.carry_adjusted_intrinsic_value() < params.intrinsic_value());
+
+        // Parameters serialized before the field existed default to no yield
+        let json = r#"{"spot":100.0,"strike":100.0,"time_to_expiry":0.25,"risk_free_rate":0.05,"option_type":"Put"}"#;
+        let params: IVParams = serde_json::from_str(json).unwrap();
+        assert_eq!(params.dividend_yield, 0.0);
+    }
+
     #[test]
     fn test_iv_params_atm() {
         let params = IVParams::call(100.0, 100.0, 0.25, 0.05);
@@ -265,4 +454,30 @@ mod tests {
         assert!(!low.is_high_quality());
         assert!(!low.is_acceptable_quality());
     }
+
+    #[test]
+    fn test_iv_result_diagnostics_serialization() {
+        let result = IVResult::new(0.25, 10.0, 50.0, 5, IVQuality::High);
+        assert!(result.diagnostics.is_none());
+
+        // Results serialized before the field existed have no diagnostics
+        let json =
+            r#"{"iv":0.25,"price_used":10.0,"spread_bps":50.0,"iterations":5,"quality":"High"}"#;
+        let parsed: IVResult = serde_json::from_str(json).unwrap();
+        assert!(parsed.diagnostics.is_none());
+
+        let diagnostics = IVDiagnostics {
+            d1: 0.1,
+            d2: -0.025,
+            delta: 0.54,
+            gamma: 0.03,
+            vega: 19.7,
+            theta: -0.03,
+            rho: 12.0,
+        };
+        let result = result.with_diagnostics(diagnostics);
+        let json = serde_json::to_string(&result).unwrap();
+        let parsed: IVResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.diagnostics, Some(diagnostics));
+    }
 }
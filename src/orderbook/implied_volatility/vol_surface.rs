@@ -0,0 +1,544 @@
+//! Volatility surface assembled from implied volatilities across strikes and expiries.
+//!
+//! Implied volatilities solved from the option books of each expiry are
+//! collected into slices. Queries between quoted points interpolate total
+//! variance `w = σ²·T` linearly in forward log-moneyness `k = ln(K / F)`
+//! within a slice, and linearly in time between slices at the same `k`,
+//! which keeps interpolated calendars free of arbitrage.
+
+use super::black_scholes::BlackScholes;
+use super::smile::IVSmile;
+use super::svi::{SviFit, SviParams};
+use super::types::{IVParams, IVResult};
+use serde::{Deserialize, Serialize};
+
+/// Relative tolerance of the arbitrage checks.
+const ARBITRAGE_TOLERANCE: f64 = 1e-9;
+
+/// Bisection iterations of the delta queries.
+const DELTA_SEARCH_ITERATIONS: u32 = 100;
+
+/// Log-moneyness range searched by the delta queries.
+const DELTA_SEARCH_LOG_MONEYNESS: f64 = 10.0;
+
+/// Implied volatility quoted at one strike of a slice.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SurfacePoint {
+    /// Strike price in price units.
+    pub strike: f64,
+    /// Implied volatility.
+    pub iv: f64,
+}
+
+/// Implied volatilities of one expiry, in ascending strike order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurfaceSlice {
+    /// Time to expiration in years.
+    pub expiry: f64,
+    /// Quoted points, in ascending strike order.
+    pub points: Vec<SurfacePoint>,
+}
+
+/// Static arbitrage found between the quoted points of a surface.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ArbitrageViolation {
+    /// Call prices are not convex around `strike`: buying the butterfly of
+    /// the neighbouring strikes costs less than nothing.
+    Butterfly {
+        /// Expiry of the slice.
+        expiry: f64,
+        /// Middle strike of the butterfly.
+        strike: f64,
+    },
+    /// Total variance at the moneyness of `strike` is lower at `long_expiry`
+    /// than at `short_expiry`.
+    Calendar {
+        /// Strike quoted at the shorter expiry.
+        strike: f64,
+        /// Shorter expiry.
+        short_expiry: f64,
+        /// Longer expiry.
+        long_expiry: f64,
+    },
+}
+
+/// Implied volatility surface across strikes and expiries of one underlying.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolSurface {
+    /// Underlying spot price in price units.
+    pub spot: f64,
+    /// Risk-free interest rate (annualized).
+    pub risk_free_rate: f64,
+    /// Continuous dividend or borrow yield of the underlying (annualized).
+    pub dividend_yield: f64,
+    /// Slices in ascending expiry order.
+    slices: Vec<SurfaceSlice>,
+}
+
+impl VolSurface {
+    /// Creates an empty surface.
+    ///
+    /// # Arguments
+    /// - `spot`: Underlying spot price in price units
+    /// - `risk_free_rate`: Risk-free interest rate (annualized)
+    /// - `dividend_yield`: Continuous dividend or borrow yield (annualized)
+    #[must_use]
+    pub fn new(spot: f64, risk_free_rate: f64, dividend_yield: f64) -> Self {
+        Self {
+            spot,
+            risk_free_rate,
+            dividend_yield,
+            slices: Vec::new(),
+        }
+    }
+
+    /// Creates a surface from the solved points of smiles.
+    #[must_use]
+    pub fn from_smiles(
+        spot: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        smiles: &[IVSmile],
+    ) -> Self {
+        let mut surface = Self::new(spot, risk_free_rate, dividend_yield);
+        for smile in smiles {
+            surface.add_smile(smile);
+        }
+        surface
+    }
+
+    /// Adds the solved points of a smile at its expiry.
+    pub fn add_smile(&mut self, smile: &IVSmile) {
+        for point in &smile.points {
+            self.add_result(smile.expiry, point.strike, &point.result);
+        }
+    }
+
+    /// Adds the IV result of one option book.
+    pub fn add_result(&mut self, expiry: f64, strike: f64, result: &IVResult) {
+        self.add_point(expiry, strike, result.iv);
+    }
+
+    /// Adds an implied volatility at `(strike, expiry)`, replacing any IV
+    /// already quoted there.
+    ///
+    /// Points with a non-positive expiry, strike or IV, or NaN values, are ignored.
+    pub fn add_point(&mut self, expiry: f64, strike: f64, iv: f64) {
+        if !(expiry > 0.0 && strike > 0.0 && iv > 0.0 && iv.is_finite()) {
+            return;
+        }
+        let slice = match self
+            .slices
+            .binary_search_by(|slice| slice.expiry.total_cmp(&expiry))
+        {
+            Ok(index) => &mut self.slices[index],
+            Err(index) => {
+                self.slices.insert(
+                    index,
+                    SurfaceSlice {
+                        expiry,
+                        points: Vec::new(),
+                    },
+                );
+                &mut self.slices[index]
+            }
+        };
+        let point = SurfacePoint { strike, iv };
+        match slice
+            .points
+            .binary_search_by(|point| point.strike.total_cmp(&strike))
+        {
+            Ok(index) => slice.points[index] = point,
+            Err(index) => slice.points.insert(index, point),
+        }
+    }
+
+    /// Slices of the surface, in ascending expiry order.
+    #[must_use]
+    pub fn slices(&self) -> &[SurfaceSlice] {
+        &self.slices
+    }
+
+    /// Whether the surface has no quoted point.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.slices.is_empty()
+    }
+
+    /// Forward price of the underlying at `expiry`, `S·e^((r-q)T)`.
+    #[must_use]
+    pub fn forward(&self, expiry: f64) -> f64 {
+        self.spot * ((self.risk_free_rate - self.dividend_yield) * expiry).exp()
+    }
+
+    /// Implied volatility at `(strike, expiry)`.
+    ///
+    /// Between quoted points total variance is interpolated linearly in
+    /// forward log-moneyness and in time. Outside the quoted strikes of a
+    /// slice, and before the first or after the last expiry, the volatility
+    /// is extended flat.
+    ///
+    /// # Returns
+    /// `None` if the surface is empty or `strike` or `expiry` is not positive
+    #[must_use]
+    pub fn iv(&self, strike: f64, expiry: f64) -> Option<f64> {
+        if self.slices.is_empty() || !(strike > 0.0 && expiry > 0.0) {
+            return None;
+        }
+        let moneyness = (strike / self.forward(expiry)).ln();
+
+        let after = self.slices.partition_point(|slice| slice.expiry < expiry);
+        if after == 0 {
+            return Some(self.slice_iv(&self.slices[0], moneyness));
+        }
+        if after == self.slices.len() {
+            return Some(self.slice_iv(&self.slices[after - 1], moneyness));
+        }
+
+        let (short, long) = (&self.slices[after - 1], &self.slices[after]);
+        let short_variance = self.slice_iv(short, moneyness).powi(2) * short.expiry;
+        let long_variance = self.slice_iv(long, moneyness).powi(2) * long.expiry;
+        let weight = (expiry - short.expiry) / (long.expiry - short.expiry);
+        let variance = short_variance + weight * (long_variance - short_variance);
+        Some((variance.max(0.0) / expiry).sqrt())
+    }
+
+    /// Strike and implied volatility at which an option of `expiry` has `delta`.
+    ///
+    /// Positive deltas are calls and negative deltas puts, so `-0.25` is the
+    /// 25-delta put. The strike is searched so that the Black-Scholes delta at
+    /// the surface volatility of that strike equals `delta`.
+    ///
+    /// # Returns
+    /// - `Some((strike, iv))` at the requested delta
+    /// - `None` if the surface is empty, `expiry` is not positive or no strike
+    ///   has that delta
+    #[must_use]
+    pub fn iv_at_delta(&self, delta: f64, expiry: f64) -> Option<(f64, f64)> {
+        if self.slices.is_empty()
+            || expiry <= 0.0
+            || expiry.is_nan()
+            || delta == 0.0
+            || !delta.is_finite()
+        {
+            return None;
+        }
+        let forward = self.forward(expiry);
+        let delta_at = |log_moneyness: f64| {
+            let strike = forward * log_moneyness.exp();
+            let iv = self.iv(strike, expiry).unwrap_or(0.0);
+            let params = self.params(strike, expiry, delta > 0.0);
+            (BlackScholes::delta(&params, iv), strike, iv)
+        };
+
+        // Deltas of both calls and puts decrease as the strike grows
+        let (mut low, mut high) = (-DELTA_SEARCH_LOG_MONEYNESS, DELTA_SEARCH_LOG_MONEYNESS);
+        let (low_delta, ..) = delta_at(low);
+        let (high_delta, ..) = delta_at(high);
+        if !(high_delta..=low_delta).contains(&delta) {
+            return None;
+        }
+        for _ in 0..DELTA_SEARCH_ITERATIONS {
+            let mid = (low + high) / 2.0;
+            let (mid_delta, ..) = delta_at(mid);
+            if mid_delta > delta {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        let (_, strike, iv) = delta_at((low + high) / 2.0);
+        Some((strike, iv))
+    }
+
+    /// Static arbitrage between the quoted points of the surface.
+    ///
+    /// Butterfly arbitrage is checked on the convexity of the call prices of
+    /// each slice across its quoted strikes. Calendar arbitrage is checked on
+    /// the total variance of each quoted point against the next expiry at the
+    /// same forward moneyness.
+    #[must_use]
+    pub fn arbitrage_violations(&self) -> Vec<ArbitrageViolation> {
+        let mut violations = Vec::new();
+
+        for slice in &self.slices {
+            let prices: Vec<(f64, f64)> = slice
+                .points
+                .iter()
+                .map(|point| {
+                    let params = self.params(point.strike, slice.expiry, true);
+                    (point.strike, BlackScholes::price(&params, point.iv))
+                })
+                .collect();
+            for window in prices.windows(3) {
+                let [(k1, c1), (k2, c2), (k3, c3)] = [window[0], window[1], window[2]];
+                let left_slope = (c2 - c1) / (k2 - k1);
+                let right_slope = (c3 - c2) / (k3 - k2);
+                if right_slope < left_slope - ARBITRAGE_TOLERANCE {
+                    violations.push(ArbitrageViolation::Butterfly {
+                        expiry: slice.expiry,
+                        strike: k2,
+                    });
+                }
+            }
+        }
+
+        for pair in self.slices.windows(2) {
+            let (short, long) = (&pair[0], &pair[1]);
+            for point in &short.points {
+                let moneyness = (point.strike / self.forward(short.expiry)).ln();
+                let short_variance = point.iv.powi(2) * short.expiry;
+                let long_variance = self.slice_iv(long, moneyness).powi(2) * long.expiry;
+                if long_variance < short_variance * (1.0 - ARBITRAGE_TOLERANCE) {
+                    violations.push(ArbitrageViolation::Calendar {
+                        strike: point.strike,
+                        short_expiry: short.expiry,
+                        long_expiry: long.expiry,
+                    });
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// Whether the quoted points are free of butterfly and calendar arbitrage.
+    #[must_use]
+    pub fn is_arbitrage_free(&self) -> bool {
+        self.arbitrage_violations().is_empty()
+    }
+
+    /// Fits a raw SVI slice to the quoted points of `expiry`.
+    ///
+    /// The points are converted to forward log-moneyness with the forward of
+    /// the expiry before fitting, see [`SviParams::fit`].
+    ///
+    /// # Returns
+    /// `None` if no slice is quoted at `expiry` or it has too few points
+    #[must_use]
+    pub fn fit_svi(&self, expiry: f64) -> Option<SviFit> {
+        let slice = self.slices.iter().find(|slice| slice.expiry == expiry)?;
+        let forward = self.forward(expiry);
+        let points: Vec<(f64, f64)> = slice
+            .points
+            .iter()
+            .map(|point| ((point.strike / forward).ln(), point.iv))
+            .collect();
+        SviParams::fit(&points, expiry)
+    }
+
+    /// Fits a raw SVI slice to every expiry with enough quoted points.
+    #[must_use]
+    pub fn fit_svi_slices(&self) -> Vec<SviFit> {
+        self.slices
+            .iter()
+            .filter_map(|slice| self.fit_svi(slice.expiry))
+            .collect()
+    }
+
+    /// Volatility of a slice at forward log-moneyness `moneyness`, linear in
+    /// total variance between its points and flat outside them.
+    fn slice_iv(&self, slice: &SurfaceSlice, moneyness: f64) -> f64 {
+        let forward = self.forward(slice.expiry);
+        let log_moneyness = |point: &SurfacePoint| (point.strike / forward).ln();
+
+        let after = slice
+            .points
+            .partition_point(|point| log_moneyness(point) < moneyness);
+        if after == 0 {
+            return slice.points[0].iv;
+        }
+        if after == slice.points.len() {
+            return slice.points[after - 1].iv;
+        }
+
+        let (left, right) = (&slice.points[after - 1], &slice.points[after]);
+        let (left_k, right_k) = (log_moneyness(left), log_moneyness(right));
+        let weight = (moneyness - left_k) / (right_k - left_k);
+        let variance = left.iv.powi(2) + weight * (right.iv.powi(2) - left.iv.powi(2));
+        variance.max(0.0).sqrt()
+    }
+
+    fn params(&self, strike: f64, expiry: f64, call: bool) -> IVParams {
+        let params = if call {
+            IVParams::call(self.spot, strike, expiry, self.risk_free_rate)
+        } else {
+            IVParams::put(self.spot, strike, expiry, self.risk_free_rate)
+        };
+        params.with_dividend_yield(self.dividend_yield)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::implied_volatility::IVQuality;
+
+    /// Surface with a linear skew per expiry and a rising term structure
+    fn setup_surface() -> VolSurface {
+        let mut surface = VolSurface::new(100.0, 0.02, 0.0);
+        for (expiry, atm) in [(0.25, 0.20), (0.5, 0.22), (1.0, 0.24)] {
+            for strike in [80.0, 90.0, 100.0, 110.0, 120.0] {
+                surface.add_point(expiry, strike, atm + (100.0 - strike) * 0.002);
+            }
+        }
+        surface
+    }
+
+    #[test]
+    fn test_add_points_sorted_and_replaced() {
+        let mut surface = VolSurface::new(100.0, 0.0, 0.0);
+        assert!(surface.is_empty());
+        assert_eq!(surface.iv(100.0, 0.5), None);
+
+        surface.add_point(1.0, 110.0, 0.3);
+        surface.add_point(0.5, 100.0, 0.2);
+        surface.add_point(1.0, 90.0, 0.35);
+        surface.add_point(1.0, 110.0, 0.32);
+        surface.add_point(1.0, 100.0, f64::NAN);
+        surface.add_point(-1.0, 100.0, 0.2);
+
+        let expiries: Vec<f64> = surface.slices().iter().map(|slice| slice.expiry).collect();
+        assert_eq!(expiries, vec![0.5, 1.0]);
+        assert_eq!(
+            surface.slices()[1].points,
+            vec![
+                SurfacePoint {
+                    strike: 90.0,
+                    iv: 0.35
+                },
+                SurfacePoint {
+                    strike: 110.0,
+                    iv: 0.32
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_iv_interpolation() {
+        let surface = setup_surface();
+
+        // Quoted points are returned as they are
+        assert!((surface.iv(90.0, 0.5).unwrap() - 0.24).abs() < 1e-12);
+
+        // Within a slice, linear in variance between neighbouring strikes
+        let iv = surface.iv(95.0, 1.0).unwrap();
+        assert!(iv < 0.26 && iv > 0.24);
+
+        // Flat outside the quoted strikes and expiries
+        assert!((surface.iv(50.0, 0.25).unwrap() - 0.24).abs() < 1e-12);
+        assert!((surface.iv(200.0, 1.0).unwrap() - 0.20).abs() < 1e-12);
+        let first_slice_atm = surface.iv(100.0 * (0.02f64 * 0.25).exp(), 0.25).unwrap();
+        let before = surface.iv(100.0 * (0.02f64 * 0.1).exp(), 0.1).unwrap();
+        assert!((before - first_slice_atm).abs() < 1e-12);
+
+        // Between expiries, total variance is linear in time
+        let t = 0.75;
+        let strike = surface.forward(t);
+        let short = surface.iv(surface.forward(0.5), 0.5).unwrap();
+        let long = surface.iv(surface.forward(1.0), 1.0).unwrap();
+        let expected = ((short * short * 0.5 + long * long * 1.0) / 2.0 / t).sqrt();
+        assert!((surface.iv(strike, t).unwrap() - expected).abs() < 1e-12);
+
+        assert_eq!(surface.iv(0.0, 0.5), None);
+        assert_eq!(surface.iv(100.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_iv_at_delta() {
+        let surface = setup_surface();
+
+        let (call_strike, call_iv) = surface.iv_at_delta(0.25, 0.5).unwrap();
+        let params = surface.params(call_strike, 0.5, true);
+        assert!((BlackScholes::delta(&params, call_iv) - 0.25).abs() < 1e-9);
+        assert!(call_strike > 100.0);
+        assert!((surface.iv(call_strike, 0.5).unwrap() - call_iv).abs() < 1e-12);
+
+        let (put_strike, put_iv) = surface.iv_at_delta(-0.25, 0.5).unwrap();
+        let params = surface.params(put_strike, 0.5, false);
+        assert!((BlackScholes::delta(&params, put_iv) + 0.25).abs() < 1e-9);
+        assert!(put_strike < 100.0);
+        // The skew makes the 25-delta put richer than the 25-delta call
+        assert!(put_iv > call_iv);
+
+        assert_eq!(surface.iv_at_delta(1.5, 0.5), None);
+        assert_eq!(surface.iv_at_delta(0.0, 0.5), None);
+        assert_eq!(VolSurface::new(100.0, 0.0, 0.0).iv_at_delta(0.5, 0.5), None);
+    }
+
+    #[test]
+    fn test_arbitrage_checks() {
+        let surface = setup_surface();
+        assert!(surface.is_arbitrage_free());
+
+        // A spike at one strike breaks the convexity of call prices
+        let mut butterfly = surface.clone();
+        butterfly.add_point(0.5, 100.0, 0.6);
+        assert!(
+            butterfly
+                .arbitrage_violations()
+                .contains(&ArbitrageViolation::Butterfly {
+                    expiry: 0.5,
+                    strike: 100.0
+                })
+        );
+
+        // Total variance falling with the expiry
+        let mut calendar = surface.clone();
+        calendar.add_point(1.0, 120.0, 0.05);
+        assert!(
+            calendar
+                .arbitrage_violations()
+                .iter()
+                .any(|violation| matches!(
+                    violation,
+                    ArbitrageViolation::Calendar {
+                        short_expiry,
+                        long_expiry,
+                        ..
+                    } if *short_expiry == 0.5 && *long_expiry == 1.0
+                ))
+        );
+    }
+
+    #[test]
+    fn test_fit_svi_slices() {
+        let surface = setup_surface();
+        let fits = surface.fit_svi_slices();
+        assert_eq!(fits.len(), 3);
+        for fit in &fits {
+            assert!(fit.butterfly_free);
+            assert!(fit.rmse < 0.01);
+            let forward = surface.forward(fit.expiry);
+            let atm = surface.iv(forward, fit.expiry).unwrap();
+            assert!((fit.iv(0.0) - atm).abs() < 0.01);
+        }
+
+        assert!(surface.fit_svi(0.3).is_none());
+        let mut sparse = VolSurface::new(100.0, 0.0, 0.0);
+        sparse.add_point(0.5, 100.0, 0.2);
+        assert!(sparse.fit_svi(0.5).is_none());
+    }
+
+    #[test]
+    fn test_from_smiles() {
+        let result = |iv| IVResult::new(iv, 1.0, 50.0, 3, IVQuality::High);
+        let smile = IVSmile {
+            expiry: 0.5,
+            spot: 100.0,
+            points: vec![crate::orderbook::implied_volatility::SmilePoint {
+                symbol: "C-100".to_string(),
+                strike: 100.0,
+                result: result(0.3),
+            }],
+            failures: Vec::new(),
+        };
+        let mut surface = VolSurface::from_smiles(100.0, 0.0, 0.0, &[smile]);
+        surface.add_result(1.0, 100.0, &result(0.25));
+
+        assert_eq!(surface.slices().len(), 2);
+        assert_eq!(surface.iv(100.0, 0.5), Some(0.3));
+        assert_eq!(surface.iv(100.0, 1.0), Some(0.25));
+    }
+}
@@ -0,0 +1,144 @@
+//! Structural invariant checks for the order book.
+
+use super::book::OrderBook;
+use pricelevel::{OrderId, Side};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A crossed book: the best bid is at or above the best ask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrossedBook {
+    /// Highest bid price, including hidden orders
+    pub best_bid: u64,
+    /// Lowest ask price, including hidden orders
+    pub best_ask: u64,
+}
+
+/// Result of [`OrderBook::verify_integrity`].
+///
+/// A healthy book produces an empty report.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    /// Set when matchable bids and asks overlap
+    pub crossed: Option<CrossedBook>,
+    /// Orders tracked in `order_locations` that are not resting at the recorded
+    /// price and side
+    pub orphaned_locations: Vec<OrderId>,
+    /// Orders resting in a price level without a matching `order_locations` entry
+    pub untracked_orders: Vec<OrderId>,
+    /// Price levels left in the book without any order
+    pub empty_levels: Vec<(Side, u64)>,
+}
+
+impl IntegrityReport {
+    /// Returns `true` if no invariant violation was found
+    #[must_use]
+    pub fn is_consistent(&self) -> bool {
+        self.crossed.is_none()
+            && self.orphaned_locations.is_empty()
+            && self.untracked_orders.is_empty()
+            && self.empty_levels.is_empty()
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Checks the structural invariants of the book
+    ///
+    /// Detects crossed books, `order_locations` entries that no longer point at a
+    /// resting order, resting orders missing from `order_locations`, and empty price
+    /// levels that were never removed. Visible and hidden levels are both inspected.
+    ///
+    /// The check walks every resting order, so it is meant for diagnostics, tests
+    /// and post-restore validation rather than the hot path.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// assert!(book.verify_integrity().is_consistent());
+    /// ```
+    #[must_use]
+    pub fn verify_integrity(&self) -> IntegrityReport {
+        let mut report = IntegrityReport::default();
+        let mut resting: HashMap<OrderId, (u64, Side, bool)> = HashMap::new();
+
+        for side in [Side::Buy, Side::Sell] {
+            for hidden in [false, true] {
+                for entry in self.side_levels(side, hidden).iter() {
+                    let price = *entry.key();
+                    let orders = entry.value().iter_orders();
+                    if orders.is_empty() {
+                        report.empty_levels.push((side, price));
+                    }
+                    for order in orders {
+                        resting.insert(order.id(), (price, side, hidden));
+                    }
+                }
+            }
+        }
+
+        for entry in self.order_locations.iter() {
+            let (price, side) = *entry.value();
+            let hidden = self.hidden_order_ids.contains(entry.key());
+            if resting.get(entry.key()) != Some(&(price, side, hidden)) {
+                report.orphaned_locations.push(*entry.key());
+            }
+        }
+
+        report.untracked_orders = resting
+            .keys()
+            .filter(|order_id| !self.order_locations.contains_key(*order_id))
+            .copied()
+            .collect();
+
+        let best_bid = self.matchable_best(Side::Buy);
+        let best_ask = self.matchable_best(Side::Sell);
+        if let (Some(best_bid), Some(best_ask)) = (best_bid, best_ask)
+            && best_bid >= best_ask
+        {
+            report.crossed = Some(CrossedBook { best_bid, best_ask });
+        }
+
+        report
+    }
+
+    /// Panics with the integrity report if the book is inconsistent
+    ///
+    /// Only active in debug builds, so it can be sprinkled through tests and fuzz
+    /// targets at no cost in release builds.
+    pub fn debug_assert_integrity(&self) {
+        if cfg!(debug_assertions) {
+            let report = self.verify_integrity();
+            assert!(
+                report.is_consistent(),
+                "Order book {} failed integrity check: {report:?}",
+                self.symbol
+            );
+        }
+    }
+
+    /// Best non-empty price on `side` across visible and hidden levels.
+    fn matchable_best(&self, side: Side) -> Option<u64> {
+        [false, true]
+            .into_iter()
+            .filter_map(|hidden| {
+                let levels = self.side_levels(side, hidden).iter();
+                let mut non_empty = levels.filter(|entry| entry.value().order_count() > 0);
+                match side {
+                    Side::Buy => non_empty.next_back(),
+                    Side::Sell => non_empty.next(),
+                }
+                .map(|entry| *entry.key())
+            })
+            .reduce(|a, b| match side {
+                Side::Buy => a.max(b),
+                Side::Sell => a.min(b),
+            })
+    }
+}
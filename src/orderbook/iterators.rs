@@ -5,9 +5,55 @@
 //! iterator combinators and can short-circuit early.
 
 use crossbeam_skiplist::SkipMap;
+use crossbeam_skiplist::map::Iter;
 use pricelevel::{PriceLevel, Side};
+use std::iter::Rev;
 use std::sync::Arc;
 
+/// An iterator walked either front to back or back to front
+///
+/// Both directions share a single concrete type, so code that walks the bids
+/// highest first and the asks lowest first needs no boxed iterator.
+pub(crate) enum Directed<I> {
+    /// Front to back, lowest price first for price maps
+    Ascending(I),
+    /// Back to front, highest price first for price maps
+    Descending(Rev<I>),
+}
+
+impl<I: DoubleEndedIterator> Directed<I> {
+    /// Walks `iter` over the prices of `side` from the best price outwards:
+    /// highest first for bids, lowest first for asks
+    pub(crate) fn best_first(iter: I, side: Side) -> Self {
+        match side {
+            Side::Buy => Self::Descending(iter.rev()),
+            Side::Sell => Self::Ascending(iter),
+        }
+    }
+}
+
+impl<I: DoubleEndedIterator> Iterator for Directed<I> {
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Ascending(iter) => iter.next(),
+            Self::Descending(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Ascending(iter) => iter.size_hint(),
+            Self::Descending(iter) => iter.size_hint(),
+        }
+    }
+}
+
+/// Price levels of a book side in price-priority order
+pub(crate) type LevelIter<'a> = Directed<Iter<'a, u64, Arc<PriceLevel>>>;
+
 /// Information about a price level including price, quantity, and cumulative depth
 #[derive(Debug, Clone)]
 pub struct LevelInfo {
@@ -27,7 +73,7 @@ pub struct LevelInfo {
 /// maintaining cumulative depth as it goes. This is useful for analyzing
 /// market depth distribution and finding liquidity thresholds.
 pub struct LevelsWithCumulativeDepth<'a> {
-    iter: Box<dyn Iterator<Item = crossbeam_skiplist::map::Entry<'a, u64, Arc<PriceLevel>>> + 'a>,
+    iter: LevelIter<'a>,
     cumulative_depth: u64,
 }
 
@@ -38,10 +84,7 @@ impl<'a> LevelsWithCumulativeDepth<'a> {
     /// - `price_levels`: Reference to the SkipMap of price levels
     /// - `side`: Side to iterate (Buy for bids, Sell for asks)
     pub fn new(price_levels: &'a SkipMap<u64, Arc<PriceLevel>>, side: Side) -> Self {
-        let iter: Box<dyn Iterator<Item = _> + 'a> = match side {
-            Side::Buy => Box::new(price_levels.iter().rev()), // Highest to lowest
-            Side::Sell => Box::new(price_levels.iter()),      // Lowest to highest
-        };
+        let iter = LevelIter::best_first(price_levels.iter(), side);
 
         Self {
             iter,
@@ -73,7 +116,7 @@ impl<'a> Iterator for LevelsWithCumulativeDepth<'a> {
 /// Stops automatically when the cumulative depth reaches or exceeds the target.
 /// Useful for analyzing how many levels are needed to fill a specific quantity.
 pub struct LevelsUntilDepth<'a> {
-    iter: Box<dyn Iterator<Item = crossbeam_skiplist::map::Entry<'a, u64, Arc<PriceLevel>>> + 'a>,
+    iter: LevelIter<'a>,
     target_depth: u64,
     cumulative_depth: u64,
     finished: bool,
@@ -91,10 +134,7 @@ impl<'a> LevelsUntilDepth<'a> {
         side: Side,
         target_depth: u64,
     ) -> Self {
-        let iter: Box<dyn Iterator<Item = _> + 'a> = match side {
-            Side::Buy => Box::new(price_levels.iter().rev()),
-            Side::Sell => Box::new(price_levels.iter()),
-        };
+        let iter = LevelIter::best_first(price_levels.iter(), side);
 
         Self {
             iter,
@@ -139,7 +179,7 @@ impl<'a> Iterator for LevelsUntilDepth<'a> {
 /// Only yields levels where the price falls within [min_price, max_price] inclusive.
 /// Useful for analyzing liquidity in specific price bands.
 pub struct LevelsInRange<'a> {
-    iter: Box<dyn Iterator<Item = crossbeam_skiplist::map::Entry<'a, u64, Arc<PriceLevel>>> + 'a>,
+    iter: LevelIter<'a>,
     min_price: u64,
     max_price: u64,
     finished: bool,
@@ -159,10 +199,7 @@ impl<'a> LevelsInRange<'a> {
         min_price: u64,
         max_price: u64,
     ) -> Self {
-        let iter: Box<dyn Iterator<Item = _> + 'a> = match side {
-            Side::Buy => Box::new(price_levels.iter().rev()),
-            Side::Sell => Box::new(price_levels.iter()),
-        };
+        let iter = LevelIter::best_first(price_levels.iter(), side);
 
         Self {
             iter,
@@ -0,0 +1,758 @@
+//! Write-ahead journal of book commands for crash recovery.
+//!
+//! Every command accepted by a book with an attached [`Journal`] is appended to it,
+//! with a gapless sequence number, before it is applied. Restoring the last snapshot
+//! and replaying the entries recorded after it reconstructs the book state between
+//! snapshot intervals. The journaled commands of a book are applied one at a time,
+//! in the order of their sequence numbers, even when issued from several threads.
+
+use super::book::OrderBook;
+use super::circuit_breaker::ReferencePriceSource;
+use super::error::OrderBookError;
+use super::modifications::OrderPlacement;
+use super::owner::OwnerId;
+use super::session::SessionState;
+use crate::utils::ManualClock;
+use pricelevel::{OrderId, OrderType, OrderUpdate, Side};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tracing::trace;
+
+thread_local! {
+    /// Address of the book whose journaled command is running on this thread, so
+    /// the operations it issues internally are not journaled a second time.
+    static ACTIVE_COMMAND: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// A state-changing command issued to an order book.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum JournalCommand<T> {
+    /// An order submitted through any of the `add_*` methods
+    AddOrder {
+        /// The order as submitted
+        order: OrderType<T>,
+        /// Owner the order was submitted on behalf of
+        owner: Option<OwnerId>,
+        /// Whether the order was submitted as a hidden order
+        hidden: bool,
+    },
+    /// A cancellation of a single order
+    CancelOrder {
+        /// ID of the order to cancel
+        order_id: OrderId,
+    },
+    /// A price, quantity or replace update of a resting order
+    UpdateOrder {
+        /// The update as submitted
+        update: OrderUpdate,
+    },
+    /// A replacement of the extra fields of a resting order
+    UpdateExtraFields {
+        /// ID of the order to update
+        order_id: OrderId,
+        /// The new extra fields
+        extra_fields: T,
+    },
+    /// A taker order matched directly against the book
+    MatchOrder {
+        /// ID of the taker order
+        order_id: OrderId,
+        /// Side of the taker order
+        side: Side,
+        /// Quantity to match
+        quantity: u64,
+        /// Worst acceptable price, if any
+        limit_price: Option<u64>,
+    },
+    /// A cancellation of every resting order
+    CancelAll,
+    /// A cancellation of every resting order on a side within a price range
+    CancelRange {
+        /// Lowest price to cancel (inclusive)
+        min_price: u64,
+        /// Highest price to cancel (inclusive)
+        max_price: u64,
+        /// Side to cancel orders on
+        side: Side,
+    },
+    /// A change of the market close timestamp used for DAY orders
+    SetMarketClose {
+        /// The new market close timestamp, or `None` to clear it
+        timestamp: Option<u64>,
+    },
+    /// A change of the trading session state
+    SetSessionState {
+        /// The new session state
+        state: SessionState,
+    },
+    /// An auction uncross of the pre-open book
+    Uncross,
+    /// A change of a reference price of the circuit breaker
+    SetReferencePrice {
+        /// Reference price that changed
+        source: ReferencePriceSource,
+        /// The new price, or `None` to clear it
+        price: Option<u64>,
+    },
+    /// A change of the book-wide kill switch
+    SetTradingEnabled {
+        /// Whether trading is enabled
+        enabled: bool,
+    },
+    /// A change of the kill switch of one owner
+    SetOwnerEnabled {
+        /// Owner whose trading changed
+        owner: OwnerId,
+        /// Whether trading is enabled for the owner
+        enabled: bool,
+    },
+    /// An expiry sweep of the resting DAY orders
+    ExpireDayOrders {
+        /// Time the sweep was run for, in milliseconds since the Unix epoch
+        now: u64,
+    },
+}
+
+/// A command recorded in a journal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry<T> {
+    /// Position of the entry in the journal, starting at 1 without gaps
+    pub sequence: u64,
+    /// Time the command was issued (milliseconds since epoch, from the book's clock)
+    pub timestamp: u64,
+    /// The command
+    pub command: JournalCommand<T>,
+}
+
+/// Destination for journal entries.
+///
+/// Implementations must return entries in the order they were appended.
+pub trait Journal<T>: Send + Sync {
+    /// Durably records an entry
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::JournalError` if the entry could not be recorded. The
+    /// command is then not applied to the book.
+    fn append(&self, entry: &JournalEntry<T>) -> Result<(), OrderBookError>;
+
+    /// Returns every entry whose sequence number is greater than `after_sequence`
+    fn entries_after(&self, after_sequence: u64) -> Result<Vec<JournalEntry<T>>, OrderBookError>;
+}
+
+/// Journal keeping its entries in memory, mostly useful for tests and replication.
+#[derive(Debug, Default)]
+pub struct InMemoryJournal<T> {
+    entries: Mutex<Vec<JournalEntry<T>>>,
+}
+
+impl<T> InMemoryJournal<T> {
+    /// Create an empty in-memory journal
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Number of entries recorded so far
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Returns `true` if no entry was recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Journal<T> for InMemoryJournal<T>
+where
+    T: Clone + Send + Sync,
+{
+    fn append(&self, entry: &JournalEntry<T>) -> Result<(), OrderBookError> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(entry.clone());
+        Ok(())
+    }
+
+    fn entries_after(&self, after_sequence: u64) -> Result<Vec<JournalEntry<T>>, OrderBookError> {
+        Ok(self
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|entry| entry.sequence > after_sequence)
+            .cloned()
+            .collect())
+    }
+}
+
+/// Journal appending one JSON document per line to a file.
+///
+/// Each append is flushed to the operating system before the command is applied.
+/// Call [`FileJournal::sync`] to force the entries to the storage device. A torn
+/// last line left by a crash in the middle of an append is ignored when reading.
+#[derive(Debug)]
+pub struct FileJournal {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl FileJournal {
+    /// Open the journal at `path`, creating the file if needed and appending to it
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::JournalError` if the file cannot be opened.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, OrderBookError> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(journal_error)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Path of the journal file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Flushes every appended entry to the storage device
+    pub fn sync(&self) -> Result<(), OrderBookError> {
+        self.file
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .sync_data()
+            .map_err(journal_error)
+    }
+}
+
+impl<T> Journal<T> for FileJournal
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn append(&self, entry: &JournalEntry<T>) -> Result<(), OrderBookError> {
+        let mut line =
+            serde_json::to_vec(entry).map_err(|e| OrderBookError::SerializationError {
+                message: e.to_string(),
+            })?;
+        line.push(b'\n');
+
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.write_all(&line).map_err(journal_error)?;
+        file.flush().map_err(journal_error)
+    }
+
+    fn entries_after(&self, after_sequence: u64) -> Result<Vec<JournalEntry<T>>, OrderBookError> {
+        let file = File::open(&self.path).map_err(journal_error)?;
+        let mut lines = BufReader::new(file).lines().peekable();
+        let mut entries = Vec::new();
+
+        while let Some(line) = lines.next() {
+            let line = line.map_err(journal_error)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<JournalEntry<T>>(&line) {
+                Ok(entry) if entry.sequence > after_sequence => entries.push(entry),
+                Ok(_) => {}
+                // Only the last line can be incomplete
+                Err(_) if lines.peek().is_none() => break,
+                Err(e) => {
+                    return Err(OrderBookError::DeserializationError {
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Journal appending one JSON document per line to a memory-mapped file of fixed
+/// capacity.
+///
+/// Appends are plain copies into a shared mapping of the file, so they reach the
+/// operating system without a system call. Call [`MmapJournal::sync`] to force the
+/// entries to the storage device. The unused tail of the file stays zeroed, and a
+/// torn last line left by a crash in the middle of an append is erased when the
+/// file is reopened. The file must not be truncated while it is mapped.
+#[cfg(all(feature = "mmap", unix))]
+#[derive(Debug)]
+pub struct MmapJournal {
+    path: PathBuf,
+    log: Mutex<mapped::MappedLog>,
+}
+
+#[cfg(all(feature = "mmap", unix))]
+impl MmapJournal {
+    /// Open the journal at `path`, creating the file if needed and growing it to
+    /// `capacity` bytes if it is smaller, then continue after its last entry
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::JournalError` if the file cannot be opened, grown
+    /// or mapped.
+    pub fn open(path: impl AsRef<Path>, capacity: usize) -> Result<Self, OrderBookError> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(journal_error)?;
+        let log = mapped::MappedLog::open(file, capacity).map_err(journal_error)?;
+        Ok(Self {
+            path,
+            log: Mutex::new(log),
+        })
+    }
+
+    /// Path of the journal file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Bytes left for new entries
+    pub fn remaining(&self) -> usize {
+        self.log
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remaining()
+    }
+
+    /// Flushes every appended entry to the storage device
+    pub fn sync(&self) -> Result<(), OrderBookError> {
+        self.log
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .sync()
+            .map_err(journal_error)
+    }
+}
+
+#[cfg(all(feature = "mmap", unix))]
+impl<T> Journal<T> for MmapJournal
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn append(&self, entry: &JournalEntry<T>) -> Result<(), OrderBookError> {
+        let mut line =
+            serde_json::to_vec(entry).map_err(|e| OrderBookError::SerializationError {
+                message: e.to_string(),
+            })?;
+        line.push(b'\n');
+
+        self.log
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .append(&line)
+            .map_err(journal_error)
+    }
+
+    fn entries_after(&self, after_sequence: u64) -> Result<Vec<JournalEntry<T>>, OrderBookError> {
+        let log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries = Vec::new();
+        for line in log.written().split(|&byte| byte == b'\n') {
+            if line.is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_slice::<JournalEntry<T>>(line).map_err(|e| {
+                OrderBookError::DeserializationError {
+                    message: e.to_string(),
+                }
+            })?;
+            if entry.sequence > after_sequence {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Shared memory mapping of a journal file, keeping every unsafe call of
+/// [`MmapJournal`] in one place.
+#[cfg(all(feature = "mmap", unix))]
+mod mapped {
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::AsRawFd;
+    use std::ptr::{self, NonNull};
+    use std::slice;
+
+    /// A file mapped read-write and shared, holding complete lines up to `end`
+    /// followed by zeroes.
+    #[derive(Debug)]
+    pub(super) struct MappedLog {
+        ptr: NonNull<u8>,
+        capacity: usize,
+        end: usize,
+        _file: File,
+    }
+
+    // SAFETY: the mapping is owned by the log and only reached through its
+    // methods, which take `&mut self` to write.
+    unsafe impl Send for MappedLog {}
+
+    impl MappedLog {
+        /// Maps `file`, grown to at least `capacity` bytes, and finds the end of
+        /// its last complete line.
+        pub fn open(file: File, capacity: usize) -> io::Result<Self> {
+            let current = file.metadata()?.len();
+            let capacity = usize::try_from(current)
+                .map_err(io::Error::other)?
+                .max(capacity);
+            if capacity as u64 > current {
+                file.set_len(capacity as u64)?;
+            }
+
+            // SAFETY: maps `capacity` bytes of an open file that is at least that
+            // long; the result is checked before use.
+            let mapping = unsafe {
+                libc::mmap(
+                    ptr::null_mut(),
+                    capacity,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    file.as_raw_fd(),
+                    0,
+                )
+            };
+            if mapping == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+            let ptr = NonNull::new(mapping.cast::<u8>())
+                .ok_or_else(|| io::Error::other("mmap returned a null mapping"))?;
+            let mut log = Self {
+                ptr,
+                capacity,
+                end: 0,
+                _file: file,
+            };
+
+            // Entries stop at the first zero byte; a line cut short is erased
+            let bytes = log.bytes_mut();
+            let written = bytes
+                .iter()
+                .position(|&byte| byte == 0)
+                .unwrap_or(bytes.len());
+            let end = bytes[..written]
+                .iter()
+                .rposition(|&byte| byte == b'\n')
+                .map_or(0, |newline| newline + 1);
+            bytes[end..written].fill(0);
+            log.end = end;
+            Ok(log)
+        }
+
+        fn bytes_mut(&mut self) -> &mut [u8] {
+            // SAFETY: the mapping spans `capacity` bytes for the life of `self`,
+            // and `&mut self` makes this the only reference into it.
+            unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.capacity) }
+        }
+
+        /// The complete lines appended so far
+        pub fn written(&self) -> &[u8] {
+            // SAFETY: `end` never exceeds the `capacity` bytes of the mapping,
+            // and writes need `&mut self`.
+            unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.end) }
+        }
+
+        pub fn remaining(&self) -> usize {
+            self.capacity - self.end
+        }
+
+        /// Copies `line` after the last one, failing if it does not fit
+        pub fn append(&mut self, line: &[u8]) -> io::Result<()> {
+            if line.len() > self.remaining() {
+                return Err(io::Error::new(
+                    io::ErrorKind::StorageFull,
+                    "memory-mapped journal is full",
+                ));
+            }
+            let end = self.end;
+            self.bytes_mut()[end..end + line.len()].copy_from_slice(line);
+            self.end += line.len();
+            Ok(())
+        }
+
+        pub fn sync(&self) -> io::Result<()> {
+            // SAFETY: flushes the whole live mapping
+            let result =
+                unsafe { libc::msync(self.ptr.as_ptr().cast(), self.capacity, libc::MS_SYNC) };
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        }
+    }
+
+    impl Drop for MappedLog {
+        fn drop(&mut self) {
+            // SAFETY: unmaps the mapping created in `open`, which no reference
+            // outlives.
+            unsafe {
+                libc::munmap(self.ptr.as_ptr().cast(), self.capacity);
+            }
+        }
+    }
+}
+
+fn journal_error(error: std::io::Error) -> OrderBookError {
+    OrderBookError::JournalError {
+        message: error.to_string(),
+    }
+}
+
+/// Marks a journaled command as running on the current thread until dropped, and
+/// keeps other journaled commands of the book waiting until then.
+#[must_use]
+pub(super) struct JournalScope<'a> {
+    previous: Option<Option<usize>>,
+    _writer: Option<MutexGuard<'a, ()>>,
+}
+
+impl Drop for JournalScope<'_> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            ACTIVE_COMMAND.with(|active| active.set(previous));
+        }
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Attach a journal that records every command accepted by this book
+    ///
+    /// Sequence numbers continue from [`OrderBook::journal_sequence`], so a journal
+    /// can be reattached after [`OrderBook::replay_journal`].
+    pub fn set_journal(&mut self, journal: Arc<dyn Journal<T>>) {
+        self.journal = Some(journal);
+    }
+
+    /// Detach the journal from this book
+    pub fn remove_journal(&mut self) {
+        self.journal = None;
+    }
+
+    /// Sequence number of the last command recorded in, or replayed from, a journal
+    ///
+    /// Record it next to each snapshot to know where replay has to resume.
+    #[must_use]
+    pub fn journal_sequence(&self) -> u64 {
+        *self
+            .journal_sequence
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Re-applies the journal entries recorded after `after_sequence`
+    ///
+    /// To recover a book, restore the last snapshot and replay from the journal
+    /// sequence recorded with it, or replay an empty book from sequence 0. While
+    /// replaying, the book's clock reads the timestamp of each entry, so orders
+    /// expire exactly as they originally did. Commands that were rejected when
+    /// first issued are rejected again and skipped. No entry is appended to the
+    /// attached journal during replay.
+    ///
+    /// Book settings are not journaled, so the book must be configured like the
+    /// original one (trading rules, duplicate handling) before replaying.
+    ///
+    /// # Returns
+    /// The sequence number of the last replayed entry.
+    ///
+    /// # Errors
+    /// Returns an error if the journal cannot be read.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{InMemoryJournal, OrderBook};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    /// use std::sync::Arc;
+    ///
+    /// let journal = Arc::new(InMemoryJournal::new());
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// book.set_journal(journal.clone());
+    /// book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// let mut recovered = OrderBook::<()>::new("BTC/USD");
+    /// assert_eq!(recovered.replay_journal(journal.as_ref(), 0).unwrap(), 1);
+    /// assert_eq!(recovered.best_bid(), Some(100));
+    /// ```
+    pub fn replay_journal(
+        &mut self,
+        journal: &dyn Journal<T>,
+        after_sequence: u64,
+    ) -> Result<u64, OrderBookError> {
+        let entries = journal.entries_after(after_sequence)?;
+        trace!(
+            "Order book {}: Replaying {} journal entries after {}",
+            self.symbol,
+            entries.len(),
+            after_sequence
+        );
+
+        let attached = self.journal.take();
+        // Requests over the rate limit were rejected before being journaled
+        let rate_limiter = self.rate_limiter.take();
+        let clock = Arc::new(ManualClock::default());
+        let original_clock = std::mem::replace(&mut self.clock, clock.clone());
+
+        let mut last_sequence = after_sequence;
+        for entry in entries {
+            clock.set(entry.timestamp);
+            // Commands rejected when first issued are rejected again
+            let _ = self.apply_journal_command(entry.command);
+            last_sequence = entry.sequence;
+        }
+
+        self.clock = original_clock;
+        self.journal = attached;
+        self.rate_limiter = rate_limiter;
+        *self
+            .journal_sequence
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner()) = last_sequence;
+        Ok(last_sequence)
+    }
+
+    pub(super) fn apply_journal_command(
+        &self,
+        command: JournalCommand<T>,
+    ) -> Result<(), OrderBookError> {
+        match command {
+            JournalCommand::AddOrder {
+                order,
+                owner,
+                hidden,
+            } => self
+                .add_order_with_placement(
+                    order,
+                    OrderPlacement {
+                        hidden,
+                        owner,
+                        replacing: false,
+                    },
+                )
+                .map(drop),
+            JournalCommand::CancelOrder { order_id } => {
+                self.cancel_resting_order(order_id).map(drop)
+            }
+            JournalCommand::UpdateOrder { update } => self.update_order(update).map(drop),
+            JournalCommand::UpdateExtraFields {
+                order_id,
+                extra_fields,
+            } => {
+                self.update_order_extra_fields(order_id, extra_fields);
+                Ok(())
+            }
+            JournalCommand::MatchOrder {
+                order_id,
+                side,
+                quantity,
+                limit_price,
+            } => self
+                .match_order(order_id, side, quantity, limit_price)
+                .map(drop),
+            JournalCommand::CancelAll => self.cancel_all().map(drop),
+            JournalCommand::CancelRange {
+                min_price,
+                max_price,
+                side,
+            } => self.cancel_range(min_price, max_price, side).map(drop),
+            JournalCommand::SetMarketClose { timestamp } => {
+                match timestamp {
+                    Some(timestamp) => self.set_market_close_timestamp(timestamp),
+                    None => self.clear_market_close_timestamp(),
+                }
+                Ok(())
+            }
+            JournalCommand::SetSessionState { state } => self.set_session_state(state).map(drop),
+            JournalCommand::Uncross => self.uncross().map(drop),
+            JournalCommand::SetReferencePrice { source, price } => {
+                self.set_reference_price(source, price);
+                Ok(())
+            }
+            JournalCommand::SetTradingEnabled { enabled } => {
+                self.set_trading_enabled(enabled);
+                Ok(())
+            }
+            JournalCommand::SetOwnerEnabled { owner, enabled } => {
+                if enabled {
+                    self.enable_owner(owner);
+                    Ok(())
+                } else {
+                    self.disable_owner(owner).map(drop)
+                }
+            }
+            JournalCommand::ExpireDayOrders { now } => {
+                self.expire_day_orders(now);
+                Ok(())
+            }
+        }
+    }
+
+    /// Appends the command built by `command` to the attached journal, unless it is
+    /// issued from within another journaled command of this book on the same thread.
+    ///
+    /// The returned scope must be held while the command is applied. It keeps the
+    /// journaled commands of other threads waiting, so commands are applied in the
+    /// order of their sequence numbers and replaying the journal rebuilds the same
+    /// book.
+    pub(super) fn begin_journaled(
+        &self,
+        command: impl FnOnce() -> JournalCommand<T>,
+    ) -> Result<JournalScope<'_>, OrderBookError> {
+        let inactive = || JournalScope {
+            previous: None,
+            _writer: None,
+        };
+        let Some(ref journal) = self.journal else {
+            return Ok(inactive());
+        };
+
+        let key = self.batch_key();
+        let previous = ACTIVE_COMMAND.with(Cell::get);
+        if previous == Some(key) {
+            return Ok(inactive());
+        }
+
+        let writer = self
+            .journal_writer
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        {
+            let mut sequence = self
+                .journal_sequence
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            let entry = JournalEntry {
+                sequence: *sequence + 1,
+                timestamp: self.clock.now_millis(),
+                command: command(),
+            };
+            journal.append(&entry)?;
+            *sequence = entry.sequence;
+        }
+
+        ACTIVE_COMMAND.with(|active| active.set(Some(key)));
+        Ok(JournalScope {
+            previous: Some(previous),
+            _writer: Some(writer),
+        })
+    }
+}
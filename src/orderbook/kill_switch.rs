@@ -0,0 +1,138 @@
+//! Kill switch disabling trading for the whole book or for single owners.
+
+use super::book::OrderBook;
+use super::error::OrderBookError;
+use super::journal::JournalCommand;
+use super::owner::OwnerId;
+use pricelevel::OrderId;
+use std::sync::atomic::Ordering;
+use tracing::{error, info};
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Enables or disables trading for every participant
+    ///
+    /// While trading is disabled new orders are rejected with
+    /// [`OrderBookError::TradingDisabled`]. Resting orders stay in the book and
+    /// can still be cancelled.
+    pub fn set_trading_enabled(&self, enabled: bool) {
+        let _journaled =
+            match self.begin_journaled(|| JournalCommand::SetTradingEnabled { enabled }) {
+                Ok(scope) => scope,
+                Err(e) => {
+                    error!("Order book {}: {}", self.symbol, e);
+                    return;
+                }
+            };
+        self.trading_enabled.store(enabled, Ordering::Release);
+        info!(
+            "Order book {}: Trading {}",
+            self.symbol,
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+
+    /// Whether the book accepts new orders from participants that are not disabled
+    #[must_use]
+    pub fn is_trading_enabled(&self) -> bool {
+        self.trading_enabled.load(Ordering::Acquire)
+    }
+
+    /// Disables trading for `owner` and cancels its resting orders
+    ///
+    /// New orders of the owner are rejected with [`OrderBookError::TradingDisabled`]
+    /// until [`OrderBook::enable_owner`] is called.
+    ///
+    /// # Returns
+    /// The IDs of the cancelled orders.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::JournalError` if the command cannot be journaled,
+    /// in which case the owner stays enabled and no order is cancelled, or if one
+    /// of the cancellations cannot be journaled.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{OrderBook, OrderBookError, OwnerId};
+    /// use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let order = || OrderType::Standard {
+    ///     id: OrderId::new(),
+    ///     price: 100,
+    ///     quantity: 10,
+    ///     side: Side::Buy,
+    ///     timestamp: 0,
+    ///     time_in_force: TimeInForce::Gtc,
+    ///     extra_fields: (),
+    /// };
+    /// book.add_order_with_owner(order(), OwnerId(7)).unwrap();
+    ///
+    /// assert_eq!(book.disable_owner(OwnerId(7)).unwrap().len(), 1);
+    /// assert!(matches!(
+    ///     book.add_order_with_owner(order(), OwnerId(7)),
+    ///     Err(OrderBookError::TradingDisabled { owner: Some(OwnerId(7)) })
+    /// ));
+    /// assert!(book.add_order_with_owner(order(), OwnerId(8)).is_ok());
+    /// ```
+    pub fn disable_owner(&self, owner: OwnerId) -> Result<Vec<OrderId>, OrderBookError> {
+        let _journaled = self.begin_journaled(|| JournalCommand::SetOwnerEnabled {
+            owner,
+            enabled: false,
+        })?;
+        self.disabled_owners.insert(owner);
+        info!("Order book {}: Trading disabled for {}", self.symbol, owner);
+        self.cancel_by_owner(owner)
+    }
+
+    /// Enables trading again for `owner`, returning whether it was disabled
+    pub fn enable_owner(&self, owner: OwnerId) -> bool {
+        let _journaled = match self.begin_journaled(|| JournalCommand::SetOwnerEnabled {
+            owner,
+            enabled: true,
+        }) {
+            Ok(scope) => scope,
+            Err(e) => {
+                error!("Order book {}: {}", self.symbol, e);
+                return false;
+            }
+        };
+        let was_disabled = self.disabled_owners.remove(&owner).is_some();
+        if was_disabled {
+            info!("Order book {}: Trading enabled for {}", self.symbol, owner);
+        }
+        was_disabled
+    }
+
+    /// Whether `owner` may submit new orders, ignoring the book-wide switch
+    #[must_use]
+    pub fn is_owner_enabled(&self, owner: OwnerId) -> bool {
+        !self.disabled_owners.contains(&owner)
+    }
+
+    /// Owners whose trading is disabled, in ascending order
+    #[must_use]
+    pub fn disabled_owners(&self) -> Vec<OwnerId> {
+        let mut owners: Vec<OwnerId> = self.disabled_owners.iter().map(|owner| *owner).collect();
+        owners.sort();
+        owners
+    }
+
+    /// Checks a new order against the book-wide and per-owner kill switches
+    pub(super) fn check_trading_enabled(
+        &self,
+        owner: Option<OwnerId>,
+    ) -> Result<(), OrderBookError> {
+        if !self.is_trading_enabled() {
+            return Err(OrderBookError::TradingDisabled { owner: None });
+        }
+        match owner {
+            Some(owner) if self.disabled_owners.contains(&owner) => {
+                Err(OrderBookError::TradingDisabled { owner: Some(owner) })
+            }
+            _ => Ok(()),
+        }
+    }
+}
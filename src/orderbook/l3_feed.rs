@@ -0,0 +1,430 @@
+//! Market-by-order (L3) feed applier mirroring an external exchange book.
+//!
+//! An [`L3FeedApplier`] consumes normalized add, modify, delete and execute
+//! messages keyed by the exchange's order IDs, maps them to local `OrderId`s and
+//! keeps an order book in step with the exchange. Sequence numbers are checked on
+//! every message, and a gap triggers a resync from an [`L3SnapshotSource`].
+
+use super::book::OrderBook;
+use super::error::OrderBookError;
+use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{trace, warn};
+
+/// A normalized market-by-order message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum L3Message {
+    /// A new order rests in the book
+    Add {
+        /// Exchange order ID
+        exchange_id: String,
+        /// Side of the order
+        side: Side,
+        /// Limit price of the order
+        price: u64,
+        /// Resting quantity of the order
+        quantity: u64,
+    },
+    /// A resting order changed its price and/or quantity
+    Modify {
+        /// Exchange order ID
+        exchange_id: String,
+        /// New limit price of the order
+        price: u64,
+        /// New resting quantity of the order
+        quantity: u64,
+    },
+    /// A resting order left the book without trading
+    Delete {
+        /// Exchange order ID
+        exchange_id: String,
+    },
+    /// A resting order traded
+    Execute {
+        /// Exchange order ID
+        exchange_id: String,
+        /// Quantity executed against the order
+        quantity: u64,
+    },
+    /// Part of a resting order was cancelled
+    Reduce {
+        /// Exchange order ID
+        exchange_id: String,
+        /// Quantity cancelled
+        quantity: u64,
+    },
+    /// A resting order was replaced by a new order on the same side
+    Replace {
+        /// Exchange order ID of the replaced order
+        exchange_id: String,
+        /// Exchange order ID of the new order
+        new_exchange_id: String,
+        /// Limit price of the new order
+        price: u64,
+        /// Resting quantity of the new order
+        quantity: u64,
+    },
+}
+
+/// An [`L3Message`] together with its feed sequence number.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct L3Update {
+    /// Feed sequence number, incremented by one for every message
+    pub sequence: u64,
+    /// The message
+    pub message: L3Message,
+}
+
+/// A resting order in an [`L3Snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct L3Order {
+    /// Exchange order ID
+    pub exchange_id: String,
+    /// Side of the order
+    pub side: Side,
+    /// Limit price of the order
+    pub price: u64,
+    /// Resting quantity of the order
+    pub quantity: u64,
+}
+
+/// Every resting order of the exchange book as of a feed sequence number.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct L3Snapshot {
+    /// Sequence number of the last message reflected in the snapshot
+    pub sequence: u64,
+    /// Resting orders, in queue priority within each level
+    pub orders: Vec<L3Order>,
+}
+
+/// Provides exchange snapshots used to resynchronize an [`L3FeedApplier`].
+///
+/// Implemented for closures taking the symbol, so a source can be as simple as a
+/// request to the exchange's snapshot endpoint.
+pub trait L3SnapshotSource: Send + Sync {
+    /// Fetches a snapshot of the exchange book for `symbol`
+    ///
+    /// # Errors
+    /// Returns an error if the snapshot cannot be obtained.
+    fn fetch_snapshot(&self, symbol: &str) -> Result<L3Snapshot, OrderBookError>;
+}
+
+impl<F> L3SnapshotSource for F
+where
+    F: Fn(&str) -> Result<L3Snapshot, OrderBookError> + Send + Sync,
+{
+    fn fetch_snapshot(&self, symbol: &str) -> Result<L3Snapshot, OrderBookError> {
+        self(symbol)
+    }
+}
+
+/// What [`L3FeedApplier::apply`] did with an update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum L3ApplyOutcome {
+    /// The message was applied to the book
+    Applied,
+    /// The sequence number was already applied, so the message was ignored
+    Stale,
+    /// A gap was detected and the book was rebuilt from a snapshot taken at this
+    /// sequence number; the update itself was applied if the snapshot predates it
+    Resynced(u64),
+}
+
+/// Local state of an exchange order mirrored in the book.
+#[derive(Debug, Clone, Copy)]
+struct MirroredOrder {
+    order_id: OrderId,
+    side: Side,
+    price: u64,
+    quantity: u64,
+}
+
+/// Maintains an order book from a market-by-order feed.
+///
+/// Orders are placed in the book without matching, since the exchange has already
+/// matched them, and without being journaled. A modification or partial execution
+/// re-queues the order at the back of its level, as the price level does for any
+/// quantity change. Until the first snapshot is loaded the book state is unknown, so
+/// the first update always triggers a resync.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::{L3FeedApplier, L3Message, L3Snapshot, L3Update, OrderBook, OrderBookError};
+/// use pricelevel::Side;
+///
+/// let source = |_: &str| -> Result<L3Snapshot, OrderBookError> { Ok(L3Snapshot::default()) };
+/// let mut feed = L3FeedApplier::new(OrderBook::<()>::new("BTC/USD"), source);
+///
+/// feed.apply(L3Update {
+///     sequence: 1,
+///     message: L3Message::Add {
+///         exchange_id: "a1".to_string(),
+///         side: Side::Buy,
+///         price: 100,
+///         quantity: 10,
+///     },
+/// })
+/// .unwrap();
+/// assert_eq!(feed.book().best_bid(), Some(100));
+/// assert_eq!(feed.sequence(), Some(1));
+/// ```
+pub struct L3FeedApplier<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    book: OrderBook<T>,
+    source: Box<dyn L3SnapshotSource>,
+    orders: HashMap<String, MirroredOrder>,
+    sequence: Option<u64>,
+    resyncs: usize,
+}
+
+impl<T> L3FeedApplier<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Create an applier maintaining `book`, resynchronizing from `source`
+    pub fn new(book: OrderBook<T>, source: impl L3SnapshotSource + 'static) -> Self {
+        Self {
+            book,
+            source: Box::new(source),
+            orders: HashMap::new(),
+            sequence: None,
+            resyncs: 0,
+        }
+    }
+
+    /// Applies a feed update, resynchronizing first if a gap is detected
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::SequenceGap` if the update is still ahead of the
+    /// book after a resync, the snapshot source's error if the resync fails,
+    /// `OrderBookError::OrderNotFound` if the message refers to an unknown exchange
+    /// order, and `OrderBookError::InvalidOperation` for an add of a known order or a
+    /// zero quantity. After an error the book may be out of step; call
+    /// [`L3FeedApplier::resync`] to recover.
+    pub fn apply(&mut self, update: L3Update) -> Result<L3ApplyOutcome, OrderBookError> {
+        match self.sequence {
+            Some(sequence) if update.sequence <= sequence => {
+                trace!(
+                    "L3 feed {}: Ignoring stale update {}",
+                    self.book.symbol(),
+                    update.sequence
+                );
+                return Ok(L3ApplyOutcome::Stale);
+            }
+            Some(sequence) if update.sequence == sequence + 1 => {
+                self.apply_message(update.message)?;
+                self.sequence = Some(update.sequence);
+                return Ok(L3ApplyOutcome::Applied);
+            }
+            Some(sequence) => warn!(
+                "L3 feed {}: Sequence gap, expected {} but received {}",
+                self.book.symbol(),
+                sequence + 1,
+                update.sequence
+            ),
+            None => {}
+        }
+
+        let snapshot_sequence = self.resync()?;
+        if update.sequence > snapshot_sequence + 1 {
+            return Err(OrderBookError::SequenceGap {
+                expected: snapshot_sequence + 1,
+                received: update.sequence,
+            });
+        }
+        if update.sequence == snapshot_sequence + 1 {
+            self.apply_message(update.message)?;
+            self.sequence = Some(update.sequence);
+        }
+        Ok(L3ApplyOutcome::Resynced(snapshot_sequence))
+    }
+
+    /// Rebuilds the book from a fresh snapshot, returning the snapshot's sequence number
+    ///
+    /// # Errors
+    /// Returns the snapshot source's error, in which case the book is left untouched,
+    /// or `OrderBookError::InvalidOperation` if the snapshot repeats an exchange order
+    /// ID or holds an order with zero quantity.
+    pub fn resync(&mut self) -> Result<u64, OrderBookError> {
+        let snapshot = self.source.fetch_snapshot(self.book.symbol())?;
+        trace!(
+            "L3 feed {}: Resyncing from snapshot at {} with {} orders",
+            self.book.symbol(),
+            snapshot.sequence,
+            snapshot.orders.len()
+        );
+        self.resyncs += 1;
+        self.sequence = None;
+
+        let (book, orders) = (&self.book, &mut self.orders);
+        book.with_batched_level_changes(|| {
+            let resting: Vec<OrderId> = book.order_locations.iter().map(|e| *e.key()).collect();
+            for order_id in resting {
+                book.remove_order(order_id)?;
+            }
+            orders.clear();
+            snapshot.orders.into_iter().try_for_each(|order| {
+                place(
+                    book,
+                    orders,
+                    order.exchange_id,
+                    order.side,
+                    order.price,
+                    order.quantity,
+                )
+            })
+        })?;
+
+        self.sequence = Some(snapshot.sequence);
+        Ok(snapshot.sequence)
+    }
+
+    fn apply_message(&mut self, message: L3Message) -> Result<(), OrderBookError> {
+        let (book, orders) = (&self.book, &mut self.orders);
+        match message {
+            L3Message::Add {
+                exchange_id,
+                side,
+                price,
+                quantity,
+            } => place(book, orders, exchange_id, side, price, quantity),
+            L3Message::Modify {
+                exchange_id,
+                price,
+                quantity,
+            } => {
+                let order = take(orders, &exchange_id)?;
+                book.with_batched_level_changes(|| {
+                    book.remove_order(order.order_id)?;
+                    place(book, orders, exchange_id, order.side, price, quantity)
+                })
+            }
+            L3Message::Delete { exchange_id } => {
+                let order = take(orders, &exchange_id)?;
+                book.remove_order(order.order_id)?;
+                Ok(())
+            }
+            L3Message::Replace {
+                exchange_id,
+                new_exchange_id,
+                price,
+                quantity,
+            } => {
+                let order = take(orders, &exchange_id)?;
+                book.with_batched_level_changes(|| {
+                    book.remove_order(order.order_id)?;
+                    place(book, orders, new_exchange_id, order.side, price, quantity)
+                })
+            }
+            L3Message::Execute {
+                exchange_id,
+                quantity,
+            }
+            | L3Message::Reduce {
+                exchange_id,
+                quantity,
+            } => {
+                let order = take(orders, &exchange_id)?;
+                let remaining = order.quantity.saturating_sub(quantity);
+                book.with_batched_level_changes(|| {
+                    book.remove_order(order.order_id)?;
+                    if remaining == 0 {
+                        return Ok(());
+                    }
+                    place(
+                        book,
+                        orders,
+                        exchange_id,
+                        order.side,
+                        order.price,
+                        remaining,
+                    )
+                })
+            }
+        }
+    }
+
+    /// Local order ID of a resting exchange order
+    pub fn local_order_id(&self, exchange_id: &str) -> Option<OrderId> {
+        self.orders.get(exchange_id).map(|order| order.order_id)
+    }
+
+    /// Sequence number of the last applied update, or `None` before the first snapshot
+    pub fn sequence(&self) -> Option<u64> {
+        self.sequence
+    }
+
+    /// Number of times the book was rebuilt from a snapshot
+    pub fn resyncs(&self) -> usize {
+        self.resyncs
+    }
+
+    /// The book maintained from the feed
+    pub fn book(&self) -> &OrderBook<T> {
+        &self.book
+    }
+
+    /// Stops applying the feed and returns the book
+    pub fn into_book(self) -> OrderBook<T> {
+        self.book
+    }
+}
+
+/// Places an exchange order in the book without matching and records its mapping.
+fn place<T>(
+    book: &OrderBook<T>,
+    orders: &mut HashMap<String, MirroredOrder>,
+    exchange_id: String,
+    side: Side,
+    price: u64,
+    quantity: u64,
+) -> Result<(), OrderBookError>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    if quantity == 0 {
+        return Err(OrderBookError::InvalidOperation {
+            message: format!("Exchange order {exchange_id} has zero quantity"),
+        });
+    }
+    if orders.contains_key(&exchange_id) {
+        return Err(OrderBookError::InvalidOperation {
+            message: format!("Exchange order {exchange_id} is already in the book"),
+        });
+    }
+
+    let order_id = OrderId::new();
+    book.place_order_in_book(Arc::new(OrderType::Standard {
+        id: order_id,
+        price,
+        quantity,
+        side,
+        timestamp: book.clock().now_millis(),
+        time_in_force: TimeInForce::Gtc,
+        extra_fields: T::default(),
+    }))?;
+    orders.insert(
+        exchange_id,
+        MirroredOrder {
+            order_id,
+            side,
+            price,
+            quantity,
+        },
+    );
+    Ok(())
+}
+
+/// Removes the mapping of an exchange order, failing if it is unknown.
+fn take(
+    orders: &mut HashMap<String, MirroredOrder>,
+    exchange_id: &str,
+) -> Result<MirroredOrder, OrderBookError> {
+    orders
+        .remove(exchange_id)
+        .ok_or_else(|| OrderBookError::OrderNotFound(exchange_id.to_string()))
+}
@@ -0,0 +1,461 @@
+//! Dense price ladder holding the visible levels of tick-bounded books.
+//!
+//! Books whose prices are confined to a bounded grid of ticks keep one slot
+//! per tick and a hierarchical occupancy bitmap per side. Adds, cancels,
+//! amends and matching resolve their levels through the ladder: a level is
+//! found by indexing its slot, and the next level to match by descending one
+//! word per bitmap layer. The ordered level maps are still kept for depth
+//! iteration and snapshots, so only opening and closing a level touches them.
+
+use super::config::BookConfig;
+use crossbeam_skiplist::SkipMap;
+use pricelevel::{PriceLevel, Side};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Largest number of ticks a dense ladder is built for.
+pub const MAX_LADDER_TICKS: usize = 1 << 20;
+
+/// Bitmap of occupied ticks, with one summary bit per non-empty word in the
+/// layer above until a single word remains.
+#[derive(Debug)]
+struct Occupancy {
+    layers: Vec<Vec<u64>>,
+}
+
+impl Occupancy {
+    fn new(bits: usize) -> Self {
+        let mut layers = Vec::new();
+        let mut bits = bits;
+        loop {
+            let words = bits.div_ceil(64).max(1);
+            layers.push(vec![0; words]);
+            if words == 1 {
+                return Self { layers };
+            }
+            bits = words;
+        }
+    }
+
+    fn set(&mut self, mut index: usize) {
+        for layer in &mut self.layers {
+            let word = &mut layer[index / 64];
+            let was_empty = *word == 0;
+            *word |= 1 << (index % 64);
+            if !was_empty {
+                return;
+            }
+            index /= 64;
+        }
+    }
+
+    fn clear(&mut self, mut index: usize) {
+        for layer in &mut self.layers {
+            let word = &mut layer[index / 64];
+            *word &= !(1 << (index % 64));
+            if *word != 0 {
+                return;
+            }
+            index /= 64;
+        }
+    }
+
+    fn reset(&mut self) {
+        for layer in &mut self.layers {
+            layer.fill(0);
+        }
+    }
+
+    /// Lowest set bit at or above `index`.
+    fn next_from(&self, index: usize) -> Option<usize> {
+        let (mut layer, mut index) = (0, index);
+        loop {
+            let word = *self.layers[layer].get(index / 64)? & (!0 << (index % 64));
+            if word != 0 {
+                index = index / 64 * 64 + word.trailing_zeros() as usize;
+                break;
+            }
+            // Continue with the next word, through the summary bits above
+            layer += 1;
+            if layer == self.layers.len() {
+                return None;
+            }
+            index = index / 64 + 1;
+        }
+        Some(self.descend_from(layer, index, |word| word.trailing_zeros() as usize))
+    }
+
+    /// Highest set bit at or below `index`.
+    fn previous_from(&self, index: usize) -> Option<usize> {
+        let (mut layer, mut index) = (0, index);
+        loop {
+            let word = self.layers[layer][index / 64] & (!0 >> (63 - index % 64));
+            if word != 0 {
+                index = index / 64 * 64 + 63 - word.leading_zeros() as usize;
+                break;
+            }
+            // Continue with the previous word, through the summary bits above
+            layer += 1;
+            if layer == self.layers.len() || index < 64 {
+                return None;
+            }
+            index = index / 64 - 1;
+        }
+        Some(self.descend_from(layer, index, |word| 63 - word.leading_zeros() as usize))
+    }
+
+    /// Follows the set bit `index` of `layer` down to the bottom layer.
+    fn descend_from(&self, layer: usize, mut index: usize, pick: impl Fn(u64) -> usize) -> usize {
+        for layer in self.layers[..layer].iter().rev() {
+            index = index * 64 + pick(layer[index]);
+        }
+        index
+    }
+}
+
+/// Levels of one side of the ladder.
+#[derive(Debug)]
+struct LadderSide {
+    levels: Vec<Option<Arc<PriceLevel>>>,
+    occupancy: Occupancy,
+    /// Levels off the tick grid, kept so every visible level is found here
+    off_grid: BTreeMap<u64, Arc<PriceLevel>>,
+}
+
+impl LadderSide {
+    fn new(ticks: usize) -> Self {
+        Self {
+            levels: vec![None; ticks],
+            occupancy: Occupancy::new(ticks),
+            off_grid: BTreeMap::new(),
+        }
+    }
+}
+
+/// Dense array of the visible price levels of a book, indexed by tick offset
+/// from the lowest price of its band.
+#[derive(Debug)]
+pub struct PriceLadder {
+    min_price: u64,
+    tick_size: u64,
+    ticks: usize,
+    bids: RwLock<LadderSide>,
+    asks: RwLock<LadderSide>,
+}
+
+impl PriceLadder {
+    /// Builds an empty ladder over the tick grid of `config`, if it asks for a
+    /// dense ladder and bounds its prices, see [`BookConfig::ladder_ticks`].
+    pub fn for_config(config: &BookConfig) -> Option<Self> {
+        if !config.dense_ladder {
+            return None;
+        }
+        let ticks = config.ladder_ticks()?;
+        Some(Self {
+            min_price: config.min_price?,
+            tick_size: config.tick_size?,
+            ticks,
+            bids: RwLock::new(LadderSide::new(ticks)),
+            asks: RwLock::new(LadderSide::new(ticks)),
+        })
+    }
+
+    /// Slot of `price`, if it lies on the grid.
+    fn index(&self, price: u64) -> Option<usize> {
+        let offset = price.checked_sub(self.min_price)?;
+        if !offset.is_multiple_of(self.tick_size) {
+            return None;
+        }
+        usize::try_from(offset / self.tick_size)
+            .ok()
+            .filter(|&index| index < self.ticks)
+    }
+
+    fn price(&self, index: usize) -> u64 {
+        self.min_price + index as u64 * self.tick_size
+    }
+
+    fn read(&self, side: Side) -> RwLockReadGuard<'_, LadderSide> {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self, side: Side) -> RwLockWriteGuard<'_, LadderSide> {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records `level` as the level at `price`.
+    ///
+    /// The slot is only written when it does not hold `level` already, so
+    /// recording a level again takes a read lock alone.
+    pub fn insert(&self, side: Side, price: u64, level: &Arc<PriceLevel>) {
+        {
+            let ladder = self.read(side);
+            let current = match self.index(price) {
+                Some(index) => ladder.levels[index].as_ref(),
+                None => ladder.off_grid.get(&price),
+            };
+            if current.is_some_and(|current| Arc::ptr_eq(current, level)) {
+                return;
+            }
+        }
+
+        let mut ladder = self.write(side);
+        match self.index(price) {
+            Some(index) => {
+                ladder.levels[index] = Some(Arc::clone(level));
+                ladder.occupancy.set(index);
+            }
+            None => {
+                ladder.off_grid.insert(price, Arc::clone(level));
+            }
+        }
+    }
+
+    /// Forgets the level at `price`.
+    pub fn remove(&self, side: Side, price: u64) {
+        let mut ladder = self.write(side);
+        match self.index(price) {
+            Some(index) => {
+                ladder.levels[index] = None;
+                ladder.occupancy.clear(index);
+            }
+            None => {
+                ladder.off_grid.remove(&price);
+            }
+        }
+    }
+
+    /// Level resting at `price`, if any.
+    pub fn level(&self, side: Side, price: u64) -> Option<Arc<PriceLevel>> {
+        let ladder = self.read(side);
+        match self.index(price) {
+            Some(index) => ladder.levels[index].clone(),
+            None => ladder.off_grid.get(&price).cloned(),
+        }
+    }
+
+    /// First level of `side` at or above `from` when `ascending`, at or below
+    /// it otherwise.
+    pub fn next_level(
+        &self,
+        side: Side,
+        from: u64,
+        ascending: bool,
+    ) -> Option<(u64, Arc<PriceLevel>)> {
+        let ladder = self.read(side);
+        let on_grid = if ascending {
+            let offset = from.saturating_sub(self.min_price).div_ceil(self.tick_size);
+            usize::try_from(offset)
+                .ok()
+                .and_then(|index| ladder.occupancy.next_from(index))
+        } else {
+            from.checked_sub(self.min_price).and_then(|offset| {
+                let index = usize::try_from(offset / self.tick_size).unwrap_or(usize::MAX);
+                ladder.occupancy.previous_from(index.min(self.ticks - 1))
+            })
+        }
+        .filter(|&index| index < self.ticks)
+        .map(|index| self.price(index));
+        let off_grid = if ascending {
+            ladder.off_grid.range(from..).next()
+        } else {
+            ladder.off_grid.range(..=from).next_back()
+        };
+
+        let off_grid_first = match (on_grid, off_grid) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(on_grid), Some((&off_grid, _))) => (off_grid < on_grid) == ascending,
+        };
+        if off_grid_first {
+            off_grid.map(|(&price, level)| (price, Arc::clone(level)))
+        } else {
+            let price = on_grid?;
+            let level = ladder.levels[self.index(price)?].clone()?;
+            Some((price, level))
+        }
+    }
+
+    /// Levels of `side` from the best price for an incoming order on the
+    /// other side.
+    pub fn levels_best_first(&self, side: Side) -> LadderLevels<'_> {
+        let ascending = side == Side::Sell;
+        LadderLevels {
+            ladder: self,
+            side,
+            ascending,
+            from: Some(if ascending { 0 } else { u64::MAX }),
+        }
+    }
+
+    /// Rebuilds one side from the level map of the book.
+    pub fn sync(&self, side: Side, price_levels: &SkipMap<u64, Arc<PriceLevel>>) {
+        let mut ladder = self.write(side);
+        ladder.levels.fill(None);
+        ladder.occupancy.reset();
+        ladder.off_grid.clear();
+        for entry in price_levels.iter() {
+            let price = *entry.key();
+            match self.index(price) {
+                Some(index) => {
+                    ladder.levels[index] = Some(Arc::clone(entry.value()));
+                    ladder.occupancy.set(index);
+                }
+                None => {
+                    ladder.off_grid.insert(price, Arc::clone(entry.value()));
+                }
+            }
+        }
+    }
+}
+
+/// Levels of one side of a [`PriceLadder`] in price priority, each looked up
+/// as the iteration reaches it so levels opened or closed meanwhile are seen.
+pub struct LadderLevels<'a> {
+    ladder: &'a PriceLadder,
+    side: Side,
+    ascending: bool,
+    from: Option<u64>,
+}
+
+impl Iterator for LadderLevels<'_> {
+    type Item = (u64, Arc<PriceLevel>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (price, level) = self
+            .ladder
+            .next_level(self.side, self.from?, self.ascending)?;
+        self.from = if self.ascending {
+            price.checked_add(1)
+        } else {
+            price.checked_sub(1)
+        };
+        Some((price, level))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ladder(min_price: u64, max_price: u64, tick_size: u64) -> PriceLadder {
+        let config = BookConfig::default()
+            .with_tick_size(tick_size)
+            .with_price_band(min_price, max_price)
+            .with_dense_ladder();
+        PriceLadder::for_config(&config).unwrap()
+    }
+
+    fn best(ladder: &PriceLadder, side: Side) -> Option<u64> {
+        ladder
+            .levels_best_first(side)
+            .next()
+            .map(|(price, _)| price)
+    }
+
+    #[test]
+    fn test_occupancy_across_layers() {
+        let mut occupancy = Occupancy::new(300_000);
+        assert_eq!(occupancy.layers.len(), 4);
+        assert_eq!(occupancy.previous_from(299_999), None);
+
+        for index in [5, 4_100, 299_999] {
+            occupancy.set(index);
+        }
+        assert_eq!(occupancy.previous_from(299_999), Some(299_999));
+        assert_eq!(occupancy.next_from(0), Some(5));
+
+        occupancy.clear(299_999);
+        occupancy.clear(5);
+        assert_eq!(occupancy.previous_from(299_999), Some(4_100));
+        assert_eq!(occupancy.next_from(0), Some(4_100));
+
+        occupancy.set(70);
+        assert_eq!(occupancy.next_from(71), Some(4_100));
+        assert_eq!(occupancy.next_from(4_101), None);
+        assert_eq!(occupancy.previous_from(4_099), Some(70));
+        assert_eq!(occupancy.previous_from(299_999), Some(4_100));
+        assert_eq!(occupancy.previous_from(69), None);
+        occupancy.clear(70);
+
+        occupancy.clear(4_100);
+        assert_eq!(occupancy.next_from(0), None);
+        assert!(occupancy.layers.iter().flatten().all(|&word| word == 0));
+    }
+
+    #[test]
+    fn test_ladder_best_prices_and_levels() {
+        let ladder = ladder(1_000, 2_000, 5);
+        let level = |price| Arc::new(PriceLevel::new(price));
+
+        for price in [1_100, 1_200, 1_150] {
+            ladder.insert(Side::Buy, price, &level(price));
+        }
+        for price in [1_300, 1_250] {
+            ladder.insert(Side::Sell, price, &level(price));
+        }
+        assert_eq!(best(&ladder, Side::Buy), Some(1_200));
+        assert_eq!(best(&ladder, Side::Sell), Some(1_250));
+        assert_eq!(ladder.level(Side::Buy, 1_150).unwrap().price(), 1_150);
+        assert!(ladder.level(Side::Sell, 1_150).is_none());
+
+        ladder.remove(Side::Buy, 1_200);
+        assert_eq!(best(&ladder, Side::Buy), Some(1_150));
+
+        // Levels off the grid keep best prices exact
+        ladder.insert(Side::Buy, 1_153, &level(1_153));
+        assert_eq!(ladder.level(Side::Buy, 1_153).unwrap().price(), 1_153);
+        assert_eq!(best(&ladder, Side::Buy), Some(1_153));
+        ladder.remove(Side::Buy, 1_153);
+        ladder.insert(Side::Sell, 999, &level(999));
+        assert_eq!(best(&ladder, Side::Sell), Some(999));
+
+        // Levels walk in price priority across the grid and off it
+        ladder.insert(Side::Sell, 1_252, &level(1_252));
+        let asks: Vec<_> = ladder
+            .levels_best_first(Side::Sell)
+            .map(|(price, _)| price)
+            .collect();
+        assert_eq!(asks, [999, 1_250, 1_252, 1_300]);
+        let bids: Vec<_> = ladder
+            .levels_best_first(Side::Buy)
+            .map(|(price, _)| price)
+            .collect();
+        assert_eq!(bids, [1_150, 1_100]);
+        assert_eq!(ladder.next_level(Side::Sell, 1_251, true).unwrap().0, 1_252);
+        assert_eq!(
+            ladder.next_level(Side::Sell, 1_251, false).unwrap().0,
+            1_250
+        );
+
+        let price_levels = SkipMap::new();
+        price_levels.insert(1_005, level(1_005));
+        ladder.sync(Side::Sell, &price_levels);
+        assert_eq!(best(&ladder, Side::Sell), Some(1_005));
+        assert!(ladder.level(Side::Sell, 1_250).is_none());
+    }
+
+    #[test]
+    fn test_ladder_needs_bounded_grid() {
+        let unbounded = BookConfig::default().with_tick_size(1).with_dense_ladder();
+        assert!(PriceLadder::for_config(&unbounded).is_none());
+
+        let too_wide = unbounded.with_price_band(0, MAX_LADDER_TICKS as u64);
+        assert!(PriceLadder::for_config(&too_wide).is_none());
+
+        let not_requested = BookConfig::default()
+            .with_tick_size(1)
+            .with_price_band(0, 100);
+        assert!(PriceLadder::for_config(&not_requested).is_none());
+    }
+}
@@ -8,9 +8,31 @@
 //!
 //! This module provides book management through a trait-based design, with implementations
 //! for both standard library (`BookManagerStd`) and Tokio (`BookManagerTokio`) channels.
+//! `BookManagerTokio` requires the `tokio` feature, which is enabled by default.
+//! Trade events are passed to a [`TradeEventHandler`], which logs them unless
+//! another handler is set with `with_handler`.
+//!
+//! Both managers shut down gracefully: `shutdown` drops the books, lets the trade
+//! processor handle every trade event sent before the call, stops it and can hand
+//! back a final snapshot of every book.
 
 use crate::orderbook::OrderBook;
+use crate::orderbook::book_change_event::PriceLevelChangedListener;
+use crate::orderbook::error::OrderBookError;
+use crate::orderbook::fees::{FeeModel, FeeSchedule};
+use crate::orderbook::implied_volatility::{
+    IVConfig, IVSmile, ImpliedForward, OptionPosition, ParityQuote, PortfolioConfig,
+    PortfolioGreeks, PriceSource, SmileConfig, TermStructure,
+};
+use crate::orderbook::manager_snapshot::{ManagedBookSnapshot, ManagerSnapshot};
+use crate::orderbook::manager_stats::{BookActivity, ManagerStats};
+use crate::orderbook::owner::OwnerId;
+use crate::orderbook::positions::{Position, PositionTracker};
+use crate::orderbook::snapshot::OrderBookSnapshot;
+use crate::orderbook::subscription::{ListenerRegistry, SubscriptionId};
+use crate::orderbook::symbol_config::{SymbolConfig, SymbolRegistry};
 use crate::orderbook::trade::{TradeEvent, TradeListener, TradeResult};
+use crate::utils::{Clock, SystemClock, current_time_millis};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{error, info};
@@ -24,7 +46,25 @@ where
     T: Clone + Send + Sync + Default + 'static,
 {
     /// Add a new order book for a symbol with an automatically configured trade listener.
-    fn add_book(&mut self, symbol: &str);
+    ///
+    /// The symbol is registered as a spot instrument without trading rules.
+    fn add_book(&mut self, symbol: &str) {
+        self.add_book_with_config(symbol, SymbolConfig::default());
+    }
+
+    /// Add a new order book for a symbol that enforces the trading rules of `config`.
+    ///
+    /// `config` is registered in the manager's symbol registry, replacing any
+    /// previous configuration of the symbol.
+    fn add_book_with_config(&mut self, symbol: &str, config: SymbolConfig);
+
+    /// Get the configurations of the symbols with order books in this manager.
+    fn registry(&self) -> &SymbolRegistry;
+
+    /// Get the configuration of a symbol.
+    fn symbol_config(&self, symbol: &str) -> Option<&SymbolConfig> {
+        self.registry().get(symbol)
+    }
 
     /// Get a reference to an order book by symbol.
     fn get_book(&self, symbol: &str) -> Option<&OrderBook<T>>;
@@ -43,6 +83,344 @@ where
 
     /// Get the number of order books in this manager.
     fn book_count(&self) -> usize;
+
+    /// Collect the order, depth and trade activity metrics of every book and their totals.
+    fn aggregate_stats(&self) -> ManagerStats;
+
+    /// Subscribe a listener to the trades of every book, including books added later.
+    ///
+    /// Listeners run on the thread executing the trade, after the trade event was
+    /// queued for the trade processor, and can be subscribed and unsubscribed at
+    /// any time through a shared reference.
+    fn subscribe_trades(&self, listener: TradeListener) -> SubscriptionId;
+
+    /// Remove a listener subscribed through [`BookManager::subscribe_trades`],
+    /// returning whether the subscription existed.
+    fn unsubscribe_trades(&self, id: SubscriptionId) -> bool;
+
+    /// Subscribe a listener to the trades of the book of `symbol`.
+    ///
+    /// Returns `None` if there is no such book. The subscription ends with the book.
+    fn subscribe_book_trades(
+        &self,
+        symbol: &str,
+        listener: TradeListener,
+    ) -> Option<SubscriptionId> {
+        self.get_book(symbol)
+            .map(|book| book.subscribe_trade_listener(listener))
+    }
+
+    /// Remove a listener subscribed through [`BookManager::subscribe_book_trades`],
+    /// returning whether the subscription existed.
+    fn unsubscribe_book_trades(&self, symbol: &str, id: SubscriptionId) -> bool {
+        self.get_book(symbol)
+            .is_some_and(|book| book.unsubscribe_trade_listener(id))
+    }
+
+    /// Subscribe a listener to the price level changes of the book of `symbol`.
+    ///
+    /// Returns `None` if there is no such book. The subscription ends with the book.
+    fn subscribe_book_price_levels(
+        &self,
+        symbol: &str,
+        listener: PriceLevelChangedListener,
+    ) -> Option<SubscriptionId> {
+        self.get_book(symbol)
+            .map(|book| book.subscribe_price_level_listener(listener))
+    }
+
+    /// Remove a listener subscribed through [`BookManager::subscribe_book_price_levels`],
+    /// returning whether the subscription existed.
+    fn unsubscribe_book_price_levels(&self, symbol: &str, id: SubscriptionId) -> bool {
+        self.get_book(symbol)
+            .is_some_and(|book| book.unsubscribe_price_level_listener(id))
+    }
+
+    /// Positions of every owner, if the manager was built with position tracking.
+    fn positions(&self) -> Option<&PositionTracker>;
+
+    /// Position of `owner` in `symbol`, if positions are tracked and the owner traded it.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::orderbook::manager::{BookManager, BookManagerStd};
+    /// use orderbook_rs::OwnerId;
+    /// use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+    ///
+    /// let mut manager = BookManagerStd::<()>::new().with_position_tracking();
+    /// manager.add_book("BTC/USD");
+    /// let book = manager.get_book("BTC/USD").unwrap();
+    /// let order = |side, quantity| OrderType::Standard {
+    ///     id: OrderId::new(),
+    ///     price: 100,
+    ///     quantity,
+    ///     side,
+    ///     timestamp: 0,
+    ///     time_in_force: TimeInForce::Gtc,
+    ///     extra_fields: (),
+    /// };
+    /// book.add_order_with_owner(order(Side::Sell, 10), OwnerId(1)).unwrap();
+    /// book.add_order_with_owner(order(Side::Buy, 4), OwnerId(2)).unwrap();
+    ///
+    /// let position = manager.position(OwnerId(2), "BTC/USD").unwrap();
+    /// assert_eq!(position.net_quantity, 4);
+    /// assert_eq!(position.average_price, 100.0);
+    /// ```
+    fn position(&self, owner: OwnerId, symbol: &str) -> Option<Position> {
+        self.positions()?.position(owner, symbol)
+    }
+
+    /// Solve the implied volatility smile of a strike ladder of option books.
+    ///
+    /// Every `(symbol, strike)` of `strikes` is the book of one strike of the
+    /// expiry, priced against `spot` with the option type, rates and IV
+    /// configuration of `config`. Strikes are solved in parallel when
+    /// `config.parallel` is set and the `rayon` feature is enabled. Strikes
+    /// without a book or whose IV cannot be solved are reported as failures.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::orderbook::manager::{BookManager, BookManagerStd};
+    /// use orderbook_rs::{IVConfig, SmileConfig};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let mut manager = BookManagerStd::<()>::new();
+    /// for (symbol, bid, ask) in [("C-95", 770, 780), ("C-100", 450, 460), ("C-105", 230, 240)] {
+    ///     manager.add_book(symbol);
+    ///     let book = manager.get_book(symbol).unwrap();
+    ///     let _ = book.add_limit_order(OrderId::new(), bid, 10, Side::Buy, TimeInForce::Gtc, None);
+    ///     let _ = book.add_limit_order(OrderId::new(), ask, 10, Side::Sell, TimeInForce::Gtc, None);
+    /// }
+    ///
+    /// let config = SmileConfig::new()
+    ///     .with_iv_config(IVConfig::default().with_price_scale(100.0))
+    ///     .with_risk_free_rate(0.05);
+    /// let strikes = [("C-95", 95.0), ("C-100", 100.0), ("C-105", 105.0), ("C-110", 110.0)];
+    /// let smile = manager.iv_smile(0.25, &strikes, 100.0, &config);
+    ///
+    /// assert_eq!(smile.points.len(), 3);
+    /// assert_eq!(smile.failures[0].symbol, "C-110");
+    /// ```
+    fn iv_smile(
+        &self,
+        expiry: f64,
+        strikes: &[(&str, f64)],
+        spot: f64,
+        config: &SmileConfig,
+    ) -> IVSmile {
+        let ladder: Vec<_> = strikes
+            .iter()
+            .map(|&(symbol, strike)| (symbol, strike, self.get_book(symbol)))
+            .collect();
+        IVSmile::solve(expiry, spot, &ladder, config)
+    }
+
+    /// Imply the forward and risk-free rate of an expiry from put-call parity.
+    ///
+    /// Every `(call_symbol, put_symbol, strike)` of `strikes` names the call
+    /// and put books of one strike. Strikes whose books are missing, empty or
+    /// wider than `config.max_spread_bps` are left out of the regression.
+    ///
+    /// # Returns
+    /// `None` if fewer than two strikes could be quoted, see
+    /// [`ImpliedForward::from_quotes`]
+    fn implied_forward(
+        &self,
+        expiry: f64,
+        strikes: &[(&str, &str, f64)],
+        price_source: PriceSource,
+        config: &IVConfig,
+    ) -> Option<ImpliedForward> {
+        let quotes: Vec<ParityQuote> = strikes
+            .iter()
+            .filter_map(|&(call, put, strike)| {
+                let (call, put) = (self.get_book(call)?, self.get_book(put)?);
+                ParityQuote::from_books(strike, call, put, price_source, config).ok()
+            })
+            .collect();
+        ImpliedForward::from_quotes(&quotes, expiry)
+    }
+
+    /// Build the at-the-money implied volatility term structure of an underlying.
+    ///
+    /// The option book of every `(expiry, strike)` is named by `symbol`, the
+    /// naming convention of the venue. The smile of each expiry is solved as
+    /// in [`BookManager::iv_smile`] and read at the forward
+    /// `spot · e^((r − q)·T)`. Expiries without any solved strike are left out.
+    fn term_structure(
+        &self,
+        spot: f64,
+        expiries: &[f64],
+        strikes: &[f64],
+        symbol: &dyn Fn(f64, f64) -> String,
+        config: &SmileConfig,
+    ) -> TermStructure {
+        let mut term = TermStructure::new();
+        for &expiry in expiries {
+            let symbols: Vec<String> = strikes
+                .iter()
+                .map(|&strike| symbol(expiry, strike))
+                .collect();
+            let ladder: Vec<(&str, f64)> = symbols
+                .iter()
+                .zip(strikes)
+                .map(|(symbol, &strike)| (symbol.as_str(), strike))
+                .collect();
+            let smile = self.iv_smile(expiry, &ladder, spot, config);
+            let forward = spot * ((config.risk_free_rate - config.dividend_yield) * expiry).exp();
+            term.add_smile(&smile, forward);
+        }
+        term
+    }
+
+    /// Solve the net Greeks of option positions from the books of the options
+    /// and of their underlyings, see [`PortfolioGreeks::solve`].
+    fn portfolio_greeks(
+        &self,
+        positions: &[OptionPosition],
+        config: &PortfolioConfig,
+    ) -> PortfolioGreeks {
+        PortfolioGreeks::solve(positions, |symbol| self.get_book(symbol), config)
+    }
+
+    /// Snapshot up to `depth` levels per side of every book, with its symbol
+    /// configuration, into one checksum-protected container.
+    fn snapshot_all(&self, depth: usize) -> Result<ManagerSnapshot, OrderBookError> {
+        let books: Vec<ManagedBookSnapshot> = self
+            .symbols()
+            .iter()
+            .filter_map(|symbol| {
+                let book = self.get_book(symbol)?;
+                Some(ManagedBookSnapshot {
+                    config: self.symbol_config(symbol).copied().unwrap_or_default(),
+                    snapshot: book.create_snapshot(depth),
+                })
+            })
+            .collect();
+        let timestamp = books
+            .iter()
+            .map(|book| book.snapshot.timestamp)
+            .max()
+            .unwrap_or_else(current_time_millis);
+        ManagerSnapshot::new(timestamp, books)
+    }
+
+    /// Restore every book of `snapshot`, returning the number of books restored.
+    ///
+    /// The checksum is validated before any book is touched. Each book is replaced
+    /// by a new one with the snapshot's configuration and contents; books of
+    /// symbols missing from the snapshot are left as they are.
+    fn restore_all(&mut self, snapshot: ManagerSnapshot) -> Result<usize, OrderBookError> {
+        snapshot.validate()?;
+        let count = snapshot.books.len();
+        for book in snapshot.books {
+            let symbol = book.snapshot.symbol.clone();
+            self.add_book_with_config(&symbol, book.config);
+            if let Some(restored) = self.get_book(&symbol) {
+                restored.restore_from_snapshot(book.snapshot)?;
+            }
+        }
+        info!("Restored {} order books", count);
+        Ok(count)
+    }
+}
+
+/// Handler of the trade events routed by a book manager.
+///
+/// The trade processor of a manager passes every trade of every managed book to
+/// its handler, on the processor's thread or task, so handlers can route trades
+/// to risk checks, persistence or market data publication. Closures taking a
+/// `&TradeEvent` are handlers.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::orderbook::manager::{BookManager, BookManagerStd};
+/// use orderbook_rs::orderbook::trade::TradeEvent;
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicU64, Ordering};
+///
+/// let volume = Arc::new(AtomicU64::new(0));
+/// let counter = Arc::clone(&volume);
+/// let mut manager = BookManagerStd::<()>::new().with_handler(Arc::new(move |event: &TradeEvent| {
+///     counter.fetch_add(event.trade_result.match_result.executed_quantity(), Ordering::Relaxed);
+/// }));
+/// manager.add_book("BTC/USD");
+/// manager.start_trade_processor();
+/// manager.shutdown(None);
+/// assert_eq!(volume.load(Ordering::Relaxed), 0);
+/// ```
+pub trait TradeEventHandler: Send + Sync {
+    /// Handle a trade executed by one of the managed books
+    fn handle(&self, event: &TradeEvent);
+}
+
+impl<F> TradeEventHandler for F
+where
+    F: Fn(&TradeEvent) + Send + Sync,
+{
+    fn handle(&self, event: &TradeEvent) {
+        self(event)
+    }
+}
+
+/// Handler logging every trade and its transactions, used by default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoggingTradeHandler;
+
+impl TradeEventHandler for LoggingTradeHandler {
+    fn handle(&self, event: &TradeEvent) {
+        info!(
+            "Processing trade for {}: {} transactions, executed quantity: {}",
+            event.symbol,
+            event
+                .trade_result
+                .match_result
+                .transactions
+                .transactions
+                .len(),
+            event.trade_result.match_result.executed_quantity()
+        );
+
+        for transaction in event.trade_result.match_result.transactions.as_vec() {
+            info!(
+                "  Transaction: {} units at price {} (ID: {})",
+                transaction.quantity, transaction.price, transaction.transaction_id
+            );
+        }
+    }
+}
+
+/// Message consumed by a trade processor.
+enum ProcessorMessage {
+    /// A trade executed by one of the books
+    Trade(Box<TradeEvent>),
+    /// Stop once the messages sent before this one were handled
+    Stop,
+}
+
+/// Creates a position tracker fed by every trade reaching `subscribers`
+fn track_positions(subscribers: &ListenerRegistry<TradeListener>) -> Arc<PositionTracker> {
+    let tracker = Arc::new(PositionTracker::new());
+    let sink = Arc::clone(&tracker);
+    subscribers.subscribe(Arc::new(move |trade| sink.record_trade(trade)));
+    tracker
+}
+
+/// Snapshots `depth` levels of every book, if a depth was requested
+fn snapshot_books<T>(
+    books: &HashMap<String, OrderBook<T>>,
+    depth: Option<usize>,
+) -> HashMap<String, OrderBookSnapshot>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    match depth {
+        Some(depth) => books
+            .iter()
+            .map(|(symbol, book)| (symbol.clone(), book.create_snapshot(depth)))
+            .collect(),
+        None => HashMap::new(),
+    }
 }
 
 /// BookManager implementation using standard library mpsc channels.
@@ -53,9 +431,25 @@ where
     /// Collection of order books indexed by symbol
     books: HashMap<String, OrderBook<T>>,
     /// Sender for trade events
-    trade_sender: std::sync::mpsc::Sender<TradeEvent>,
+    trade_sender: std::sync::mpsc::Sender<ProcessorMessage>,
     /// Receiver for trade events (taken when processor starts)
-    trade_receiver: Option<std::sync::mpsc::Receiver<TradeEvent>>,
+    trade_receiver: Option<std::sync::mpsc::Receiver<ProcessorMessage>>,
+    /// Thread of the trade processor, once started
+    processor: Option<std::thread::JoinHandle<()>>,
+    /// Clock shared by every managed book and used to timestamp trade events
+    clock: Arc<dyn Clock>,
+    /// Handler of the trade events, used by the processor once started
+    handler: Arc<dyn TradeEventHandler>,
+    /// Configurations of the managed symbols
+    registry: SymbolRegistry,
+    /// Trade and update activity of each book
+    activity: HashMap<String, Arc<BookActivity>>,
+    /// Listeners to the trades of every book
+    trade_subscribers: Arc<ListenerRegistry<TradeListener>>,
+    /// Positions of every owner, if tracking is enabled
+    positions: Option<Arc<PositionTracker>>,
+    /// Fees charged on the trades of every book, if set
+    fee_model: Option<Arc<FeeModel>>,
 }
 
 impl<T> BookManagerStd<T>
@@ -64,53 +458,129 @@ where
 {
     /// Create a new BookManagerStd with a standard library mpsc channel.
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Create a new BookManagerStd whose books and trade events use `clock` for time.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
         let (sender, receiver) = std::sync::mpsc::channel();
 
         Self {
             books: HashMap::new(),
             trade_sender: sender,
             trade_receiver: Some(receiver),
+            processor: None,
+            clock,
+            handler: Arc::new(LoggingTradeHandler),
+            registry: SymbolRegistry::new(),
+            activity: HashMap::new(),
+            trade_subscribers: Arc::new(ListenerRegistry::new()),
+            positions: None,
+            fee_model: None,
         }
     }
 
+    /// Route trade events to `handler` instead of logging them.
+    ///
+    /// Set the handler before starting the trade processor: a running processor
+    /// keeps the handler it was started with.
+    #[must_use]
+    pub fn with_handler(mut self, handler: Arc<dyn TradeEventHandler>) -> Self {
+        self.handler = handler;
+        self
+    }
+
+    /// Track the position of every owner from the trades of every book.
+    ///
+    /// Positions are updated on the thread executing each trade, so they are
+    /// current as soon as the order that traded returns.
+    #[must_use]
+    pub fn with_position_tracking(mut self) -> Self {
+        self.positions = Some(track_positions(&self.trade_subscribers));
+        self
+    }
+
+    /// Charge `schedule` on the trades of every book added afterwards.
+    ///
+    /// The books share one [`FeeModel`], so volume traded in any of them counts
+    /// toward the volume tiers.
+    #[must_use]
+    pub fn with_fee_schedule(mut self, schedule: FeeSchedule) -> Self {
+        self.fee_model = Some(Arc::new(FeeModel::new(schedule)));
+        self
+    }
+
+    /// The fee model shared by the books of this manager, if any
+    #[must_use]
+    pub fn fee_model(&self) -> Option<&Arc<FeeModel>> {
+        self.fee_model.as_ref()
+    }
+
     /// Start the trade event processor in a separate thread.
-    pub fn start_trade_processor(&mut self) -> std::thread::JoinHandle<()> {
+    ///
+    /// The thread runs until [`BookManagerStd::shutdown`] or until the manager and
+    /// all of its books are dropped.
+    ///
+    /// # Panics
+    /// Panics if the processor was already started.
+    pub fn start_trade_processor(&mut self) {
         let receiver = self
             .trade_receiver
             .take()
             .expect("Trade processor already started");
 
-        std::thread::spawn(move || {
+        let handler = Arc::clone(&self.handler);
+        self.processor = Some(std::thread::spawn(move || {
             info!("Trade processor started");
 
-            while let Ok(trade_event) = receiver.recv() {
-                Self::process_trade_event(trade_event);
+            while let Ok(message) = receiver.recv() {
+                match message {
+                    ProcessorMessage::Trade(trade_event) => handler.handle(&trade_event),
+                    ProcessorMessage::Stop => break,
+                }
             }
 
             info!("Trade processor stopped");
-        })
+        }));
     }
 
-    /// Process a single trade event.
-    fn process_trade_event(event: TradeEvent) {
-        info!(
-            "Processing trade for {}: {} transactions, executed quantity: {}",
-            event.symbol,
-            event
-                .trade_result
-                .match_result
-                .transactions
-                .transactions
-                .len(),
-            event.trade_result.match_result.executed_quantity()
-        );
+    /// Shut the manager down, returning a snapshot of every book if requested.
+    ///
+    /// The books are dropped, so they stop accepting orders, and every trade event
+    /// sent before the call is processed: by the processor thread, which is then
+    /// joined, or on the calling thread if the processor was never started.
+    /// With `snapshot_depth` set, each book is snapshotted with that many levels
+    /// per side first; otherwise the returned map is empty.
+    pub fn shutdown(self, snapshot_depth: Option<usize>) -> HashMap<String, OrderBookSnapshot> {
+        let snapshots = snapshot_books(&self.books, snapshot_depth);
+        let Self {
+            books,
+            trade_sender,
+            trade_receiver,
+            processor,
+            handler,
+            ..
+        } = self;
+        drop(books);
+
+        if let Err(e) = trade_sender.send(ProcessorMessage::Stop) {
+            error!("Failed to stop trade processor: {}", e);
+        }
+        drop(trade_sender);
 
-        for transaction in event.trade_result.match_result.transactions.as_vec() {
-            info!(
-                "  Transaction: {} units at price {} (ID: {})",
-                transaction.quantity, transaction.price, transaction.transaction_id
-            );
+        if let Some(receiver) = trade_receiver {
+            while let Ok(ProcessorMessage::Trade(trade_event)) = receiver.try_recv() {
+                handler.handle(&trade_event);
+            }
+        }
+        if let Some(processor) = processor
+            && processor.join().is_err()
+        {
+            error!("Trade processor panicked");
         }
+
+        info!("Book manager shut down");
+        snapshots
     }
 }
 
@@ -118,30 +588,50 @@ impl<T> BookManager<T> for BookManagerStd<T>
 where
     T: Clone + Send + Sync + Default + 'static,
 {
-    fn add_book(&mut self, symbol: &str) {
+    fn add_book_with_config(&mut self, symbol: &str, config: SymbolConfig) {
         let sender = self.trade_sender.clone();
         let symbol_clone = symbol.to_string();
+        let clock = Arc::clone(&self.clock);
+        let activity = Arc::new(BookActivity::new(self.clock.now_millis()));
+        let trade_activity = Arc::clone(&activity);
+        let subscribers = Arc::clone(&self.trade_subscribers);
 
         let trade_listener: TradeListener = Arc::new(move |trade_result: &TradeResult| {
+            let timestamp = clock.now_millis();
+            trade_activity.record_trade(timestamp, trade_result.match_result.executed_quantity());
             let trade_event = TradeEvent {
                 symbol: trade_result.symbol.clone(),
                 trade_result: trade_result.clone(),
-                timestamp: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as u64,
+                timestamp,
             };
 
-            if let Err(e) = sender.send(trade_event) {
+            if let Err(e) = sender.send(ProcessorMessage::Trade(Box::new(trade_event))) {
                 error!("Failed to send trade event for {}: {}", symbol_clone, e);
             }
+            subscribers.notify(|listener| listener(trade_result));
         });
 
-        let book = OrderBook::with_trade_listener(symbol, trade_listener);
+        let mut book = OrderBook::with_trade_listener(symbol, trade_listener);
+        book.set_clock(Arc::clone(&self.clock));
+        book.apply_config(config.book);
+        if let Some(ref model) = self.fee_model {
+            book.set_fee_model(Arc::clone(model));
+        }
+        let clock = Arc::clone(&self.clock);
+        let update_activity = Arc::clone(&activity);
+        book.subscribe_price_level_listener(Arc::new(move |_| {
+            update_activity.record_update(clock.now_millis());
+        }));
         self.books.insert(symbol.to_string(), book);
+        self.registry.register(symbol, config);
+        self.activity.insert(symbol.to_string(), activity);
         info!("Added order book for symbol: {}", symbol);
     }
 
+    fn registry(&self) -> &SymbolRegistry {
+        &self.registry
+    }
+
     fn get_book(&self, symbol: &str) -> Option<&OrderBook<T>> {
         self.books.get(symbol)
     }
@@ -156,6 +646,8 @@ where
 
     fn remove_book(&mut self, symbol: &str) -> Option<OrderBook<T>> {
         let result = self.books.remove(symbol);
+        self.registry.remove(symbol);
+        self.activity.remove(symbol);
         if result.is_some() {
             info!("Removed order book for symbol: {}", symbol);
         }
@@ -169,6 +661,22 @@ where
     fn book_count(&self) -> usize {
         self.books.len()
     }
+
+    fn aggregate_stats(&self) -> ManagerStats {
+        ManagerStats::collect(self.books.iter(), &self.activity, self.clock.now_millis())
+    }
+
+    fn subscribe_trades(&self, listener: TradeListener) -> SubscriptionId {
+        self.trade_subscribers.subscribe(listener)
+    }
+
+    fn unsubscribe_trades(&self, id: SubscriptionId) -> bool {
+        self.trade_subscribers.unsubscribe(id)
+    }
+
+    fn positions(&self) -> Option<&PositionTracker> {
+        self.positions.as_deref()
+    }
 }
 
 impl<T> Default for BookManagerStd<T>
@@ -181,6 +689,7 @@ where
 }
 
 /// BookManager implementation using Tokio mpsc channels.
+#[cfg(feature = "tokio")]
 pub struct BookManagerTokio<T>
 where
     T: Clone + Send + Sync + Default + 'static,
@@ -188,97 +697,210 @@ where
     /// Collection of order books indexed by symbol
     books: HashMap<String, OrderBook<T>>,
     /// Sender for trade events
-    trade_sender: tokio::sync::mpsc::UnboundedSender<TradeEvent>,
+    trade_sender: tokio::sync::mpsc::UnboundedSender<ProcessorMessage>,
     /// Receiver for trade events (taken when processor starts)
-    trade_receiver: Option<tokio::sync::mpsc::UnboundedReceiver<TradeEvent>>,
+    trade_receiver: Option<tokio::sync::mpsc::UnboundedReceiver<ProcessorMessage>>,
+    /// Task of the trade processor, once started
+    processor: Option<tokio::task::JoinHandle<()>>,
+    /// Clock shared by every managed book and used to timestamp trade events
+    clock: Arc<dyn Clock>,
+    /// Handler of the trade events, used by the processor once started
+    handler: Arc<dyn TradeEventHandler>,
+    /// Configurations of the managed symbols
+    registry: SymbolRegistry,
+    /// Trade and update activity of each book
+    activity: HashMap<String, Arc<BookActivity>>,
+    /// Listeners to the trades of every book
+    trade_subscribers: Arc<ListenerRegistry<TradeListener>>,
+    /// Positions of every owner, if tracking is enabled
+    positions: Option<Arc<PositionTracker>>,
+    /// Fees charged on the trades of every book, if set
+    fee_model: Option<Arc<FeeModel>>,
 }
 
+#[cfg(feature = "tokio")]
 impl<T> BookManagerTokio<T>
 where
     T: Clone + Send + Sync + Default + 'static,
 {
     /// Create a new BookManagerTokio with a Tokio unbounded mpsc channel.
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Create a new BookManagerTokio whose books and trade events use `clock` for time.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
         let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
 
         Self {
             books: HashMap::new(),
             trade_sender: sender,
             trade_receiver: Some(receiver),
+            processor: None,
+            clock,
+            handler: Arc::new(LoggingTradeHandler),
+            registry: SymbolRegistry::new(),
+            activity: HashMap::new(),
+            trade_subscribers: Arc::new(ListenerRegistry::new()),
+            positions: None,
+            fee_model: None,
         }
     }
 
+    /// Route trade events to `handler` instead of logging them.
+    ///
+    /// Set the handler before starting the trade processor: a running processor
+    /// keeps the handler it was started with.
+    #[must_use]
+    pub fn with_handler(mut self, handler: Arc<dyn TradeEventHandler>) -> Self {
+        self.handler = handler;
+        self
+    }
+
+    /// Track the position of every owner from the trades of every book.
+    ///
+    /// Positions are updated on the thread executing each trade, so they are
+    /// current as soon as the order that traded returns.
+    #[must_use]
+    pub fn with_position_tracking(mut self) -> Self {
+        self.positions = Some(track_positions(&self.trade_subscribers));
+        self
+    }
+
+    /// Charge `schedule` on the trades of every book added afterwards.
+    ///
+    /// The books share one [`FeeModel`], so volume traded in any of them counts
+    /// toward the volume tiers.
+    #[must_use]
+    pub fn with_fee_schedule(mut self, schedule: FeeSchedule) -> Self {
+        self.fee_model = Some(Arc::new(FeeModel::new(schedule)));
+        self
+    }
+
+    /// The fee model shared by the books of this manager, if any
+    #[must_use]
+    pub fn fee_model(&self) -> Option<&Arc<FeeModel>> {
+        self.fee_model.as_ref()
+    }
+
     /// Start the trade event processor as an async task.
     ///
-    /// Returns a JoinHandle for the spawned task.
-    pub fn start_trade_processor(&mut self) -> tokio::task::JoinHandle<()> {
+    /// The task runs until [`BookManagerTokio::shutdown`] or until the manager and
+    /// all of its books are dropped.
+    ///
+    /// # Panics
+    /// Panics if the processor was already started, or if called outside a Tokio
+    /// runtime.
+    pub fn start_trade_processor(&mut self) {
         let mut receiver = self
             .trade_receiver
             .take()
             .expect("Trade processor already started");
 
-        tokio::spawn(async move {
+        let handler = Arc::clone(&self.handler);
+        self.processor = Some(tokio::spawn(async move {
             info!("Trade processor started (Tokio)");
 
-            while let Some(trade_event) = receiver.recv().await {
-                Self::process_trade_event(trade_event);
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    ProcessorMessage::Trade(trade_event) => handler.handle(&trade_event),
+                    ProcessorMessage::Stop => break,
+                }
             }
 
             info!("Trade processor stopped (Tokio)");
-        })
+        }));
     }
 
-    /// Process a single trade event.
-    fn process_trade_event(event: TradeEvent) {
-        info!(
-            "Processing trade for {}: {} transactions, executed quantity: {}",
-            event.symbol,
-            event
-                .trade_result
-                .match_result
-                .transactions
-                .transactions
-                .len(),
-            event.trade_result.match_result.executed_quantity()
-        );
+    /// Shut the manager down, returning a snapshot of every book if requested.
+    ///
+    /// Behaves like [`BookManagerStd::shutdown`], awaiting the processor task
+    /// instead of joining a thread.
+    pub async fn shutdown(
+        self,
+        snapshot_depth: Option<usize>,
+    ) -> HashMap<String, OrderBookSnapshot> {
+        let snapshots = snapshot_books(&self.books, snapshot_depth);
+        let Self {
+            books,
+            trade_sender,
+            trade_receiver,
+            processor,
+            handler,
+            ..
+        } = self;
+        drop(books);
+
+        if let Err(e) = trade_sender.send(ProcessorMessage::Stop) {
+            error!("Failed to stop trade processor: {}", e);
+        }
+        drop(trade_sender);
 
-        for transaction in event.trade_result.match_result.transactions.as_vec() {
-            info!(
-                "  Transaction: {} units at price {} (ID: {})",
-                transaction.quantity, transaction.price, transaction.transaction_id
-            );
+        if let Some(mut receiver) = trade_receiver {
+            while let Ok(ProcessorMessage::Trade(trade_event)) = receiver.try_recv() {
+                handler.handle(&trade_event);
+            }
         }
+        if let Some(processor) = processor
+            && processor.await.is_err()
+        {
+            error!("Trade processor panicked (Tokio)");
+        }
+
+        info!("Book manager shut down (Tokio)");
+        snapshots
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<T> BookManager<T> for BookManagerTokio<T>
 where
     T: Clone + Send + Sync + Default + 'static,
 {
-    fn add_book(&mut self, symbol: &str) {
+    fn add_book_with_config(&mut self, symbol: &str, config: SymbolConfig) {
         let sender = self.trade_sender.clone();
         let symbol_clone = symbol.to_string();
+        let clock = Arc::clone(&self.clock);
+        let activity = Arc::new(BookActivity::new(self.clock.now_millis()));
+        let trade_activity = Arc::clone(&activity);
+        let subscribers = Arc::clone(&self.trade_subscribers);
 
         let trade_listener: TradeListener = Arc::new(move |trade_result: &TradeResult| {
+            let timestamp = clock.now_millis();
+            trade_activity.record_trade(timestamp, trade_result.match_result.executed_quantity());
             let trade_event = TradeEvent {
                 symbol: trade_result.symbol.clone(),
                 trade_result: trade_result.clone(),
-                timestamp: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as u64,
+                timestamp,
             };
 
-            if let Err(e) = sender.send(trade_event) {
+            if let Err(e) = sender.send(ProcessorMessage::Trade(Box::new(trade_event))) {
                 error!("Failed to send trade event for {}: {}", symbol_clone, e);
             }
+            subscribers.notify(|listener| listener(trade_result));
         });
 
-        let book = OrderBook::with_trade_listener(symbol, trade_listener);
+        let mut book = OrderBook::with_trade_listener(symbol, trade_listener);
+        book.set_clock(Arc::clone(&self.clock));
+        book.apply_config(config.book);
+        if let Some(ref model) = self.fee_model {
+            book.set_fee_model(Arc::clone(model));
+        }
+        let clock = Arc::clone(&self.clock);
+        let update_activity = Arc::clone(&activity);
+        book.subscribe_price_level_listener(Arc::new(move |_| {
+            update_activity.record_update(clock.now_millis());
+        }));
         self.books.insert(symbol.to_string(), book);
+        self.registry.register(symbol, config);
+        self.activity.insert(symbol.to_string(), activity);
         info!("Added order book for symbol: {}", symbol);
     }
 
+    fn registry(&self) -> &SymbolRegistry {
+        &self.registry
+    }
+
     fn get_book(&self, symbol: &str) -> Option<&OrderBook<T>> {
         self.books.get(symbol)
     }
@@ -293,6 +915,8 @@ where
 
     fn remove_book(&mut self, symbol: &str) -> Option<OrderBook<T>> {
         let result = self.books.remove(symbol);
+        self.registry.remove(symbol);
+        self.activity.remove(symbol);
         if result.is_some() {
             info!("Removed order book for symbol: {}", symbol);
         }
@@ -306,8 +930,25 @@ where
     fn book_count(&self) -> usize {
         self.books.len()
     }
+
+    fn aggregate_stats(&self) -> ManagerStats {
+        ManagerStats::collect(self.books.iter(), &self.activity, self.clock.now_millis())
+    }
+
+    fn subscribe_trades(&self, listener: TradeListener) -> SubscriptionId {
+        self.trade_subscribers.subscribe(listener)
+    }
+
+    fn unsubscribe_trades(&self, id: SubscriptionId) -> bool {
+        self.trade_subscribers.unsubscribe(id)
+    }
+
+    fn positions(&self) -> Option<&PositionTracker> {
+        self.positions.as_deref()
+    }
 }
 
+#[cfg(feature = "tokio")]
 impl<T> Default for BookManagerTokio<T>
 where
     T: Clone + Send + Sync + Default + 'static,
@@ -0,0 +1,115 @@
+//! Checksum-protected snapshot of every book of a manager.
+
+use super::error::OrderBookError;
+use super::snapshot::OrderBookSnapshot;
+use super::symbol_config::SymbolConfig;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Format version of manager snapshots.
+pub const MANAGER_SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Snapshot of one managed book together with its symbol configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedBookSnapshot {
+    /// Configuration the book is restored with
+    pub config: SymbolConfig,
+    /// Levels and orders of the book
+    pub snapshot: OrderBookSnapshot,
+}
+
+/// Snapshot of every book of a manager, protected by a single checksum.
+///
+/// Created by [`BookManager::snapshot_all`](super::manager::BookManager::snapshot_all)
+/// and restored by [`BookManager::restore_all`](super::manager::BookManager::restore_all),
+/// so failing over a multi-symbol engine takes one call on each side. Like
+/// `OrderBook::create_snapshot`, book snapshots do not carry extra order fields.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagerSnapshot {
+    /// Format version of the snapshot
+    pub version: u32,
+    /// Time the snapshot was taken, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// Snapshots of the books, sorted by symbol
+    pub books: Vec<ManagedBookSnapshot>,
+    /// Hex-encoded SHA-256 checksum of the timestamp and books
+    pub checksum: String,
+}
+
+impl ManagerSnapshot {
+    /// Creates a snapshot of `books` taken at `timestamp`, computing its checksum
+    pub fn new(
+        timestamp: u64,
+        mut books: Vec<ManagedBookSnapshot>,
+    ) -> Result<Self, OrderBookError> {
+        books.sort_by(|a, b| a.snapshot.symbol.cmp(&b.snapshot.symbol));
+        for book in &mut books {
+            book.snapshot.refresh_aggregates();
+        }
+        let checksum = Self::compute_checksum(timestamp, &books)?;
+        Ok(Self {
+            version: MANAGER_SNAPSHOT_FORMAT_VERSION,
+            timestamp,
+            books,
+            checksum,
+        })
+    }
+
+    /// Snapshot of the book of `symbol`
+    pub fn book(&self, symbol: &str) -> Option<&ManagedBookSnapshot> {
+        self.books
+            .iter()
+            .find(|book| book.snapshot.symbol == symbol)
+    }
+
+    /// Validates the checksum and version.
+    pub fn validate(&self) -> Result<(), OrderBookError> {
+        if self.version != MANAGER_SNAPSHOT_FORMAT_VERSION {
+            return Err(OrderBookError::InvalidOperation {
+                message: format!(
+                    "Unsupported manager snapshot version: {} (expected {})",
+                    self.version, MANAGER_SNAPSHOT_FORMAT_VERSION
+                ),
+            });
+        }
+
+        let computed = Self::compute_checksum(self.timestamp, &self.books)?;
+        if computed != self.checksum {
+            return Err(OrderBookError::ChecksumMismatch {
+                expected: self.checksum.clone(),
+                actual: computed,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Serializes the snapshot to JSON.
+    pub fn to_json(&self) -> Result<String, OrderBookError> {
+        serde_json::to_string(self).map_err(|error| OrderBookError::SerializationError {
+            message: error.to_string(),
+        })
+    }
+
+    /// Deserializes the snapshot from JSON.
+    pub fn from_json(data: &str) -> Result<Self, OrderBookError> {
+        serde_json::from_str(data).map_err(|error| OrderBookError::DeserializationError {
+            message: error.to_string(),
+        })
+    }
+
+    fn compute_checksum(
+        timestamp: u64,
+        books: &[ManagedBookSnapshot],
+    ) -> Result<String, OrderBookError> {
+        let payload = serde_json::to_vec(&(timestamp, books)).map_err(|error| {
+            OrderBookError::SerializationError {
+                message: error.to_string(),
+            }
+        })?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(payload);
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+}
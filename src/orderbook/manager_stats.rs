@@ -0,0 +1,188 @@
+//! Aggregated activity and depth metrics of the books of a manager.
+
+use crate::orderbook::OrderBook;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Number of seconds over which trade rates are averaged.
+pub const TRADE_RATE_WINDOW_SECS: u64 = 60;
+
+/// Metrics of one managed book.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookStats {
+    /// Symbol of the book
+    pub symbol: String,
+    /// Number of resting orders
+    pub order_count: usize,
+    /// Number of bid price levels
+    pub bid_levels: usize,
+    /// Number of ask price levels
+    pub ask_levels: usize,
+    /// Total resting bid quantity
+    pub bid_volume: u64,
+    /// Total resting ask quantity
+    pub ask_volume: u64,
+    /// Number of trades since the book was added
+    pub trade_count: u64,
+    /// Quantity traded since the book was added
+    pub traded_volume: u64,
+    /// Trades per second over the last [`TRADE_RATE_WINDOW_SECS`] seconds, or
+    /// since the book was added if that is more recent
+    pub trades_per_second: f64,
+    /// Time of the last trade, in milliseconds since the Unix epoch
+    pub last_trade_timestamp: Option<u64>,
+    /// Time of the last trade or price level change, in milliseconds since the
+    /// Unix epoch
+    pub last_event_timestamp: Option<u64>,
+}
+
+/// Metrics of every managed book and their totals, for monitoring endpoints.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ManagerStats {
+    /// Time the metrics were collected, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// Metrics of each book, sorted by symbol
+    pub books: Vec<BookStats>,
+    /// Number of resting orders of all books
+    pub total_orders: usize,
+    /// Number of price levels of all books, both sides
+    pub total_levels: usize,
+    /// Resting quantity of all books, both sides
+    pub total_volume: u64,
+    /// Number of trades of all books
+    pub total_trades: u64,
+    /// Quantity traded in all books
+    pub total_traded_volume: u64,
+    /// Trades per second of all books
+    pub trades_per_second: f64,
+    /// Time of the last event of any book, in milliseconds since the Unix epoch
+    pub last_event_timestamp: Option<u64>,
+}
+
+impl ManagerStats {
+    /// Metrics of the book of `symbol`
+    pub fn book(&self, symbol: &str) -> Option<&BookStats> {
+        self.books.iter().find(|stats| stats.symbol == symbol)
+    }
+
+    /// Collects the metrics of `books` at `now`, using the activity of each symbol
+    pub(crate) fn collect<'a, T>(
+        books: impl Iterator<Item = (&'a String, &'a OrderBook<T>)>,
+        activity: &HashMap<String, Arc<BookActivity>>,
+        now: u64,
+    ) -> Self
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let mut stats = ManagerStats {
+            timestamp: now,
+            ..Default::default()
+        };
+        for (symbol, book) in books {
+            let (bid_volume, ask_volume) = book.buy_sell_pressure();
+            let mut book_stats = BookStats {
+                symbol: symbol.clone(),
+                order_count: book.order_locations.len(),
+                bid_levels: book.bids.len(),
+                ask_levels: book.asks.len(),
+                bid_volume,
+                ask_volume,
+                ..Default::default()
+            };
+            if let Some(activity) = activity.get(symbol) {
+                activity.fill(&mut book_stats, now);
+            }
+
+            stats.total_orders += book_stats.order_count;
+            stats.total_levels += book_stats.bid_levels + book_stats.ask_levels;
+            stats.total_volume += book_stats.bid_volume + book_stats.ask_volume;
+            stats.total_trades += book_stats.trade_count;
+            stats.total_traded_volume += book_stats.traded_volume;
+            stats.trades_per_second += book_stats.trades_per_second;
+            stats.last_event_timestamp = stats
+                .last_event_timestamp
+                .max(book_stats.last_event_timestamp);
+            stats.books.push(book_stats);
+        }
+        stats.books.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        stats
+    }
+}
+
+/// Trade and update activity of one book, fed by the manager's listeners.
+#[derive(Debug)]
+pub(crate) struct BookActivity {
+    /// Time the book was added
+    added_at: u64,
+    trade_count: AtomicU64,
+    traded_volume: AtomicU64,
+    /// Time of the last trade, zero before the first one
+    last_trade: AtomicU64,
+    /// Time of the last trade or price level change, zero before the first one
+    last_event: AtomicU64,
+    /// Trade counts per second of the rate window, oldest first
+    window: Mutex<VecDeque<(u64, u64)>>,
+}
+
+impl BookActivity {
+    pub(crate) fn new(added_at: u64) -> Self {
+        Self {
+            added_at,
+            trade_count: AtomicU64::new(0),
+            traded_volume: AtomicU64::new(0),
+            last_trade: AtomicU64::new(0),
+            last_event: AtomicU64::new(0),
+            window: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Records a trade of `quantity` at `now`
+    pub(crate) fn record_trade(&self, now: u64, quantity: u64) {
+        self.trade_count.fetch_add(1, Ordering::Relaxed);
+        self.traded_volume.fetch_add(quantity, Ordering::Relaxed);
+        self.last_trade.fetch_max(now, Ordering::Relaxed);
+        self.last_event.fetch_max(now, Ordering::Relaxed);
+
+        let second = now / 1000;
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        match window.back_mut() {
+            Some((last, count)) if *last >= second => *count += 1,
+            _ => window.push_back((second, 1)),
+        }
+        Self::prune(&mut window, second);
+    }
+
+    /// Records a price level change at `now`
+    pub(crate) fn record_update(&self, now: u64) {
+        self.last_event.fetch_max(now, Ordering::Relaxed);
+    }
+
+    fn prune(window: &mut VecDeque<(u64, u64)>, second: u64) {
+        while window
+            .front()
+            .is_some_and(|(start, _)| start + TRADE_RATE_WINDOW_SECS <= second)
+        {
+            window.pop_front();
+        }
+    }
+
+    fn fill(&self, stats: &mut BookStats, now: u64) {
+        let nonzero = |value: u64| (value > 0).then_some(value);
+        stats.trade_count = self.trade_count.load(Ordering::Relaxed);
+        stats.traded_volume = self.traded_volume.load(Ordering::Relaxed);
+        stats.last_trade_timestamp = nonzero(self.last_trade.load(Ordering::Relaxed));
+        stats.last_event_timestamp = nonzero(self.last_event.load(Ordering::Relaxed));
+
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        Self::prune(&mut window, now / 1000);
+        let trades: u64 = window.iter().map(|(_, count)| count).sum();
+        let elapsed = now
+            .saturating_sub(self.added_at)
+            .div_ceil(1000)
+            .clamp(1, TRADE_RATE_WINDOW_SECS);
+        stats.trades_per_second = trades as f64 / elapsed as f64;
+    }
+}
@@ -7,6 +7,8 @@
 //! - Number of price levels consumed
 //! - Available liquidity in price ranges
 
+use super::book::DEFAULT_BASIS_POINTS_MULTIPLIER;
+use pricelevel::Side;
 use serde::{Deserialize, Serialize};
 
 /// Represents the market impact analysis of an order
@@ -53,6 +55,69 @@ pub struct OrderSimulation {
     pub remaining_quantity: u64,
 }
 
+/// Represents a simulated limit order execution
+///
+/// Splits the order into the portion that would match immediately and the
+/// remainder that would rest in the book, and describes the book after both.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LimitOrderSimulation {
+    /// Immediate fills against the opposite side, with the unmatched quantity
+    /// in `remaining_quantity`
+    pub execution: OrderSimulation,
+
+    /// Quantity that would rest in the book at the limit price (in units)
+    pub resting_quantity: u64,
+
+    /// Orders and quantity ahead of the resting remainder at its price level,
+    /// `None` if nothing would rest
+    pub queue_position: Option<(usize, u64)>,
+
+    /// Best bid after the order, as (price, quantity)
+    pub best_bid: Option<(u64, u64)>,
+
+    /// Best ask after the order, as (price, quantity)
+    pub best_ask: Option<(u64, u64)>,
+}
+
+impl LimitOrderSimulation {
+    /// Checks if the order would be filled entirely on arrival
+    ///
+    /// # Returns
+    /// `true` if nothing would rest in the book
+    #[must_use]
+    pub fn is_fully_filled(&self) -> bool {
+        self.resting_quantity == 0
+    }
+}
+
+/// Represents the liquidity an aggressor must take to move the best price
+///
+/// The inverse of a [`MarketImpact`]: instead of the price reached by a given
+/// quantity, the quantity needed to reach a given price.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceMoveCost {
+    /// Number of ticks the best price moves
+    pub ticks: u64,
+
+    /// Best price before the move (in price units)
+    pub start_price: u64,
+
+    /// Price the best price moves to or beyond (in price units)
+    pub target_price: u64,
+
+    /// Best price left once the quantity is traded (in price units)
+    pub resulting_price: u64,
+
+    /// Quantity that must be traded (in units)
+    pub quantity: u64,
+
+    /// Notional that must be traded (price units times quantity units)
+    pub notional: u128,
+
+    /// Number of price levels that must be consumed entirely
+    pub levels_consumed: usize,
+}
+
 impl MarketImpact {
     /// Creates a new MarketImpact with all fields set to zero/empty
     ///
@@ -70,6 +135,67 @@ impl MarketImpact {
         }
     }
 
+    /// Walks `levels`, as (price, quantity) pairs in price-priority order, to
+    /// calculate the impact of an order of `quantity` on `side`
+    pub(super) fn from_levels(
+        levels: impl IntoIterator<Item = (u64, u64)>,
+        quantity: u64,
+        side: Side,
+    ) -> Self {
+        if quantity == 0 {
+            return Self::empty();
+        }
+
+        let mut best_price = None;
+        let mut remaining = quantity;
+        let mut total_cost = 0u128;
+        let mut total_filled = 0u64;
+        let mut worst_price = 0;
+        let mut levels_consumed = 0;
+
+        for (price, available) in levels {
+            if remaining == 0 {
+                break;
+            }
+
+            if available == 0 {
+                continue;
+            }
+
+            best_price.get_or_insert(price);
+            levels_consumed += 1;
+            let fill_qty = remaining.min(available);
+            total_cost = total_cost.saturating_add((price as u128) * (fill_qty as u128));
+            total_filled = total_filled.saturating_add(fill_qty);
+            worst_price = price;
+            remaining = remaining.saturating_sub(fill_qty);
+        }
+
+        let Some(best_price) = best_price else {
+            return Self::empty();
+        };
+
+        let slippage = match side {
+            Side::Buy => worst_price.saturating_sub(best_price),
+            Side::Sell => best_price.saturating_sub(worst_price),
+        };
+
+        let slippage_bps = if best_price > 0 {
+            (slippage as f64 / best_price as f64) * DEFAULT_BASIS_POINTS_MULTIPLIER
+        } else {
+            0.0
+        };
+
+        Self {
+            avg_price: total_cost as f64 / total_filled as f64,
+            worst_price,
+            slippage,
+            slippage_bps,
+            levels_consumed,
+            total_quantity_available: total_filled,
+        }
+    }
+
     /// Checks if the order can be fully filled
     ///
     /// # Arguments
@@ -0,0 +1,316 @@
+//! Bulk cancellation of resting orders, including the eviction of levels
+//! beyond the depth limit of a book.
+
+use super::book::OrderBook;
+use super::book_change_event::PriceLevelChangedEvent;
+use super::config::LevelEviction;
+use super::error::OrderBookError;
+use super::journal::JournalCommand;
+use super::modifications::OrderQuantity;
+use super::order_event::OrderEvent;
+use super::owner::OwnerId;
+use crossbeam_skiplist::map::Entry;
+use pricelevel::{OrderId, OrderUpdate, PriceLevel, Side};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use tracing::trace;
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Cancels every resting order in the book, including hidden orders
+    ///
+    /// Whole price levels are dropped at once and only the cancelled orders are
+    /// removed from the order tracking maps, so orders added concurrently stay
+    /// tracked. Listeners receive each removed visible level once, followed
+    /// by a single consolidated `BookChangedEvent`.
+    ///
+    /// # Returns
+    /// The IDs of the cancelled orders.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::JournalError` if the cancellation cannot be
+    /// journaled, in which case no order is cancelled.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// book.add_limit_order(OrderId::new(), 99, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 101, 10, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// assert_eq!(book.cancel_all().unwrap().len(), 2);
+    /// assert_eq!(book.best_bid(), None);
+    /// assert_eq!(book.best_ask(), None);
+    /// ```
+    pub fn cancel_all(&self) -> Result<Vec<OrderId>, OrderBookError> {
+        let _journaled = self.begin_journaled(|| JournalCommand::CancelAll)?;
+        trace!("Order book {}: Cancelling all orders", self.symbol);
+        self.with_batched_level_changes(|| {
+            let mut cancelled = Vec::with_capacity(self.order_locations.len());
+            for side in [Side::Buy, Side::Sell] {
+                self.drop_levels(side, 0..=u64::MAX, &mut cancelled);
+            }
+
+            for order_id in &cancelled {
+                self.forget_order(order_id);
+            }
+            Ok(cancelled)
+        })
+    }
+
+    /// Cancels every resting order on one side of the book, including hidden orders
+    ///
+    /// # Returns
+    /// The IDs of the cancelled orders.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::JournalError` if the cancellation cannot be
+    /// journaled, in which case no order is cancelled.
+    pub fn cancel_side(&self, side: Side) -> Result<Vec<OrderId>, OrderBookError> {
+        self.cancel_range(0, u64::MAX, side)
+    }
+
+    /// Cancels every resting order on `side` priced within `min_price..=max_price`,
+    /// including hidden orders
+    ///
+    /// # Arguments
+    /// - `min_price`: Lowest price to cancel (inclusive, in price units)
+    /// - `max_price`: Highest price to cancel (inclusive, in price units)
+    /// - `side`: The side to cancel orders on
+    ///
+    /// # Returns
+    /// The IDs of the cancelled orders. An empty range cancels nothing.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::JournalError` if the cancellation cannot be
+    /// journaled, in which case no order is cancelled.
+    pub fn cancel_range(
+        &self,
+        min_price: u64,
+        max_price: u64,
+        side: Side,
+    ) -> Result<Vec<OrderId>, OrderBookError> {
+        if min_price > max_price {
+            return Ok(Vec::new());
+        }
+        let _journaled = self.begin_journaled(|| JournalCommand::CancelRange {
+            min_price,
+            max_price,
+            side,
+        })?;
+
+        trace!(
+            "Order book {}: Cancelling {} orders in range {}..={}",
+            self.symbol, side, min_price, max_price
+        );
+        self.with_batched_level_changes(|| {
+            let mut cancelled = Vec::new();
+            self.drop_levels(side, min_price..=max_price, &mut cancelled);
+            for order_id in &cancelled {
+                self.forget_order(order_id);
+            }
+            Ok(cancelled)
+        })
+    }
+
+    /// Cancels every resting order submitted on behalf of `owner`
+    ///
+    /// Each order is cancelled, and journaled, on its own.
+    ///
+    /// # Returns
+    /// The IDs of the cancelled orders.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::JournalError` if a cancellation cannot be
+    /// journaled. The orders cancelled before it stay cancelled.
+    pub fn cancel_by_owner(&self, owner: OwnerId) -> Result<Vec<OrderId>, OrderBookError> {
+        trace!("Order book {}: Cancelling orders of {}", self.symbol, owner);
+        let owned = self.owned_order_ids(owner);
+
+        self.with_batched_level_changes(|| {
+            let mut cancelled = Vec::with_capacity(owned.len());
+            for order_id in owned {
+                if self.recycle(self.cancel_resting_order(order_id)?) {
+                    cancelled.push(order_id);
+                }
+            }
+            Ok(cancelled)
+        })
+    }
+
+    /// Removes the visible and hidden levels of `side` within `prices`, collecting the
+    /// IDs of the orders they held. Order tracking state is left to the caller.
+    fn drop_levels(&self, side: Side, prices: RangeInclusive<u64>, cancelled: &mut Vec<OrderId>) {
+        for hidden in [false, true] {
+            let price_levels = self.side_levels(side, hidden);
+            let level_prices: Vec<u64> = price_levels
+                .range(prices.clone())
+                .map(|entry| *entry.key())
+                .collect();
+
+            for price in level_prices {
+                if let Some(entry) = price_levels.remove(&price) {
+                    self.drop_level(side, hidden, &entry, cancelled);
+                }
+            }
+        }
+    }
+
+    /// Cancels the orders of a level already taken out of the visible or hidden
+    /// levels of `side`, collecting their IDs, and parks the emptied level for
+    /// reuse. Order tracking state is left to the caller.
+    fn drop_level(
+        &self,
+        side: Side,
+        hidden: bool,
+        entry: &Entry<'_, u64, Arc<PriceLevel>>,
+        cancelled: &mut Vec<OrderId>,
+    ) {
+        let price = *entry.key();
+        if !hidden {
+            self.totals.removed(side, entry.value().total_quantity());
+            self.unindex_level(side, price);
+        }
+        let level = entry.value();
+        for order in level.iter_orders() {
+            self.emit_order_event(OrderEvent::Cancelled {
+                order_id: order.id(),
+                quantity: order.total_quantity(),
+            });
+            let _ = level.update_order(OrderUpdate::Cancel {
+                order_id: order.id(),
+            });
+            cancelled.push(order.id());
+        }
+        self.level_pool.release(Arc::clone(level));
+
+        if !hidden {
+            self.publish_level_change(PriceLevelChangedEvent {
+                side,
+                price,
+                quantity: 0,
+            });
+        }
+    }
+
+    /// Checks that an order may rest at `price` on `side` under the depth limit
+    /// of the book
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::DepthLimitExceeded` if the side is full and the
+    /// level at `price` neither exists nor, when the policy drops the worst
+    /// level, ranks better than it.
+    pub(super) fn admit_level(&self, side: Side, price: u64) -> Result<(), OrderBookError> {
+        self.admit_level_vacating(side, price, None)
+    }
+
+    /// Checks that an order may rest at `price` on `side` once the visible level
+    /// at `vacated`, if any, has been removed from that side
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::DepthLimitExceeded` as [`OrderBook::admit_level`]
+    /// does.
+    pub(super) fn admit_level_vacating(
+        &self,
+        side: Side,
+        price: u64,
+        vacated: Option<u64>,
+    ) -> Result<(), OrderBookError> {
+        let Some(max_levels) = self.config.max_levels else {
+            return Ok(());
+        };
+        let price_levels = self.side_levels(side, false);
+        let freed =
+            vacated.is_some_and(|vacated| vacated != price && price_levels.contains_key(&vacated));
+        if price_levels.len() - usize::from(freed) < max_levels || price_levels.contains_key(&price)
+        {
+            return Ok(());
+        }
+        let worst = match side {
+            Side::Buy => price_levels.front(),
+            Side::Sell => price_levels.back(),
+        };
+        let fits = match (self.config.level_eviction, worst) {
+            (LevelEviction::DropWorst, Some(worst)) => match side {
+                Side::Buy => price > *worst.key(),
+                Side::Sell => price < *worst.key(),
+            },
+            _ => false,
+        };
+        if fits {
+            Ok(())
+        } else {
+            Err(OrderBookError::DepthLimitExceeded {
+                side,
+                price,
+                max_levels,
+            })
+        }
+    }
+
+    /// Checks that the visible level just opened at `price` did not take `side`
+    /// beyond the depth limit of a book rejecting new levels
+    ///
+    /// Concurrent orders may all be admitted while one slot is left; each
+    /// checks again once its level is in the map so that the side never ends
+    /// up deeper than the limit.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::DepthLimitExceeded` if the policy rejects new
+    /// levels and the side holds more than `max_levels` of them.
+    pub(super) fn confirm_opened_level(
+        &self,
+        side: Side,
+        price: u64,
+    ) -> Result<(), OrderBookError> {
+        match self.config.max_levels {
+            Some(max_levels)
+                if self.config.level_eviction == LevelEviction::Reject
+                    && self.side_levels(side, false).len() > max_levels =>
+            {
+                Err(OrderBookError::DepthLimitExceeded {
+                    side,
+                    price,
+                    max_levels,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Cancels the orders of the worst visible levels of `side` until it is back
+    /// within the depth limit of a book dropping its worst levels
+    pub(super) fn evict_excess_levels(&self, side: Side) {
+        let Some(max_levels) = self.config.max_levels else {
+            return;
+        };
+        if self.config.level_eviction != LevelEviction::DropWorst {
+            return;
+        }
+        let price_levels = self.side_levels(side, false);
+        let mut evicted = Vec::new();
+        while price_levels.len() > max_levels {
+            let worst = match side {
+                Side::Buy => price_levels.pop_front(),
+                Side::Sell => price_levels.pop_back(),
+            };
+            let Some(entry) = worst else {
+                break;
+            };
+            trace!(
+                "Order book {}: Evicting {} level {} beyond the depth limit",
+                self.symbol,
+                side,
+                entry.key()
+            );
+            self.drop_level(side, false, &entry, &mut evicted);
+        }
+        for order_id in &evicted {
+            self.forget_order(order_id);
+        }
+    }
+}
@@ -1,9 +1,16 @@
 //! Contains the core matching engine logic for the order book.
 
-use crate::orderbook::book_change_event::PriceLevelChangedEvent;
+use crate::orderbook::circuit_breaker::BandTrigger;
+use crate::orderbook::iterators::LevelIter;
+use crate::orderbook::journal::JournalCommand;
+use crate::orderbook::ladder::LadderLevels;
+use crate::orderbook::order_event::OrderEvent;
 use crate::orderbook::pool::MatchingPool;
 use crate::{OrderBook, OrderBookError};
-use pricelevel::{MatchResult, OrderId, Side};
+use crossbeam_skiplist::SkipMap;
+use pricelevel::{MatchResult, OrderId, OrderType, PriceLevel, Side};
+use std::iter::Peekable;
+use std::sync::Arc;
 use std::sync::atomic::Ordering;
 
 impl<T> OrderBook<T>
@@ -27,18 +34,30 @@ where
         quantity: u64,
         limit_price: Option<u64>,
     ) -> Result<MatchResult, OrderBookError> {
-        self.cache.invalidate();
+        let _journaled = self.begin_journaled(|| JournalCommand::MatchOrder {
+            order_id,
+            side,
+            quantity,
+            limit_price,
+        })?;
+        let state = self.session_state();
+        if !state.is_matching() {
+            return Err(OrderBookError::SessionRejected {
+                state,
+                reason: "matching is suspended".to_string(),
+            });
+        }
+        self.expire_day_orders_if_closed();
+        self.begin_owner_capture();
         let mut match_result = MatchResult::new(order_id, quantity);
         let mut remaining_quantity = quantity;
 
         // Choose the appropriate side for matching
-        let match_side = match side {
-            Side::Buy => &self.asks,
-            Side::Sell => &self.bids,
-        };
+        let match_side = self.side_levels(side.opposite(), false);
+        let hidden_side = self.side_levels(side.opposite(), true);
 
         // Early exit if the opposite side is empty
-        if match_side.is_empty() {
+        if match_side.is_empty() && hidden_side.is_empty() {
             if limit_price.is_none() {
                 return Err(OrderBookError::InsufficientLiquidity {
                     side,
@@ -56,23 +75,38 @@ where
         }
 
         // Get reusable vectors from pool
-        let (mut filled_orders, mut empty_price_levels) = MATCHING_POOL.with(|pool| {
-            let filled = pool.get_filled_orders_vec();
-            let empty = pool.get_price_vec();
-            (filled, empty)
-        });
+        let (mut filled_orders, mut empty_price_levels, mut empty_hidden_levels) = MATCHING_POOL
+            .with(|pool| {
+                let filled = pool.get_filled_orders_vec();
+                let empty = pool.get_price_vec();
+                let empty_hidden = pool.get_price_vec();
+                (filled, empty, empty_hidden)
+            });
 
         // Iterate through prices in optimal order (already sorted by SkipMap)
         // For buy orders: iterate asks in ascending order (best ask first)
         // For sell orders: iterate bids in descending order (best bid first)
-        let price_iter: Box<dyn Iterator<Item = _>> = match side {
-            Side::Buy => Box::new(match_side.iter()),
-            Side::Sell => Box::new(match_side.iter().rev()),
-        };
+        let mut price_iter = self.match_levels(side, false);
+        let mut hidden_iter = self.match_levels(side, true);
+
+        // Sweeps stop at the first level outside the circuit breaker's bands
+        let band_limits = self.price_band_limits();
+        let mut band_hit = None;
+
+        // Process each price level. At the same price, visible orders take
+        // priority over hidden ones.
+        while remaining_quantity > 0 {
+            let visible_price = price_iter.peek().map(|&(price, _)| price);
+            let hidden_price = hidden_iter.peek().map(|&(price, _)| price);
+            let price = match (visible_price, hidden_price) {
+                (None, None) => break,
+                (Some(price), None) | (None, Some(price)) => price,
+                (Some(visible), Some(hidden)) => match side {
+                    Side::Buy => visible.min(hidden),
+                    Side::Sell => visible.max(hidden),
+                },
+            };
 
-        // Process each price level
-        for entry in price_iter {
-            let price = *entry.key();
             // Check price limit constraint early
             if let Some(limit) = limit_price {
                 match side {
@@ -81,74 +115,73 @@ where
                     _ => {}
                 }
             }
-
-            // Get price level value from the entry
-            let price_level = entry.value();
-
-            // Perform the match at this price level
-            let price_level_match = price_level.match_order(
-                remaining_quantity,
-                order_id,
-                &self.transaction_id_generator,
-            );
-
-            // Process transactions if any occurred
-            if !price_level_match.transactions.as_vec().is_empty() {
-                // Update last trade price atomically
-                self.last_trade_price.store(price, Ordering::Relaxed);
-                self.has_traded.store(true, Ordering::Relaxed);
-
-                // Add transactions to result
-                for transaction in price_level_match.transactions.as_vec() {
-                    match_result.add_transaction(*transaction);
-                }
-
-                // notify price level changes
-                if let Some(ref listener) = self.price_level_changed_listener {
-                    listener(PriceLevelChangedEvent {
-                        side: side.opposite(),
-                        price: price_level.price(),
-                        quantity: price_level.visible_quantity(),
-                    });
-                }
-            }
-
-            // Collect filled orders for batch removal
-            for &filled_order_id in &price_level_match.filled_order_ids {
-                match_result.add_filled_order_id(filled_order_id);
-                filled_orders.push(filled_order_id);
+            if let Some(limits) = band_limits
+                && !limits.contains(price)
+            {
+                band_hit = Some((price, limits));
+                break;
             }
 
-            // Update remaining quantity
-            remaining_quantity = price_level_match.remaining_quantity;
-
-            // Check if price level is empty and mark for removal
-            if price_level.order_count() == 0 {
-                empty_price_levels.push(price);
+            if visible_price == Some(price)
+                && let Some((_, level)) = price_iter.next()
+            {
+                remaining_quantity = self.match_price_level(
+                    &level,
+                    order_id,
+                    remaining_quantity,
+                    side,
+                    false,
+                    &mut match_result,
+                    &mut filled_orders,
+                );
+                if level.order_count() == 0 {
+                    empty_price_levels.push(price);
+                }
             }
 
-            // Early exit if order is fully matched
-            if remaining_quantity == 0 {
-                break;
+            if remaining_quantity > 0
+                && hidden_price == Some(price)
+                && let Some((_, level)) = hidden_iter.next()
+            {
+                remaining_quantity = self.match_price_level(
+                    &level,
+                    order_id,
+                    remaining_quantity,
+                    side,
+                    true,
+                    &mut match_result,
+                    &mut filled_orders,
+                );
+                if level.order_count() == 0 {
+                    empty_hidden_levels.push(price);
+                }
             }
         }
 
         // Batch remove empty price levels
         for price in &empty_price_levels {
-            match_side.remove(price);
+            self.prune_empty_level(match_side, *price);
+        }
+        for price in &empty_hidden_levels {
+            self.prune_empty_level(hidden_side, *price);
         }
 
         // Batch remove filled orders from tracking
         for order_id in &filled_orders {
-            self.order_locations.remove(order_id);
+            self.forget_order(order_id);
         }
 
         // Return vectors to pool for reuse
         MATCHING_POOL.with(|pool| {
             pool.return_filled_orders_vec(filled_orders);
             pool.return_price_vec(empty_price_levels);
+            pool.return_price_vec(empty_hidden_levels);
         });
 
+        if let Some((price, limits)) = band_hit {
+            self.hit_price_band(order_id, side, price, limits, BandTrigger::Trade);
+        }
+
         // Check for insufficient liquidity in market orders
         if limit_price.is_none() && remaining_quantity == quantity {
             return Err(OrderBookError::InsufficientLiquidity {
@@ -162,34 +195,230 @@ where
         match_result.remaining_quantity = remaining_quantity;
         match_result.is_complete = remaining_quantity == 0;
 
+        // Report each execution to the incoming order
+        if self.order_event_listener.is_some() {
+            let transactions = match_result.transactions.as_vec();
+            for (index, transaction) in transactions.iter().enumerate() {
+                let completes = match_result.is_complete && index + 1 == transactions.len();
+                self.emit_fill_event(order_id, transaction.price, transaction.quantity, completes);
+            }
+        }
+
         Ok(match_result)
     }
 
+    /// Iterates the given levels from the best price for an incoming order on `side`.
+    fn levels_in_match_order(
+        price_levels: &SkipMap<u64, Arc<PriceLevel>>,
+        side: Side,
+    ) -> Peekable<LevelIter<'_>> {
+        LevelIter::best_first(price_levels.iter(), side.opposite()).peekable()
+    }
+
+    /// Iterates the visible or hidden levels an incoming order on `side` matches
+    /// against from the best price, walking the dense ladder for the visible
+    /// levels of books that have one.
+    fn match_levels(&self, side: Side, hidden: bool) -> Peekable<MatchLevels<'_>> {
+        match self.ladder {
+            Some(ref ladder) if !hidden => {
+                MatchLevels::Ladder(ladder.levels_best_first(side.opposite()))
+            }
+            _ => MatchLevels::Map(LevelIter::best_first(
+                self.side_levels(side.opposite(), hidden).iter(),
+                side.opposite(),
+            )),
+        }
+        .peekable()
+    }
+
+    /// Matches the incoming quantity against a single price level, returning the
+    /// quantity still left to match.
+    ///
+    /// Trades against hidden levels are reported like any other trade, but the
+    /// level itself is never published through the price level listeners.
+    #[allow(clippy::too_many_arguments)]
+    fn match_price_level(
+        &self,
+        price_level: &PriceLevel,
+        order_id: OrderId,
+        remaining_quantity: u64,
+        side: Side,
+        hidden: bool,
+        match_result: &mut MatchResult,
+        filled_orders: &mut Vec<OrderId>,
+    ) -> u64 {
+        // Perform the match at this price level
+        let price_level_match =
+            price_level.match_order(remaining_quantity, order_id, &self.transaction_id_generator);
+        if !hidden {
+            self.totals.removed(
+                side.opposite(),
+                remaining_quantity - price_level_match.remaining_quantity,
+            );
+        }
+
+        // Process transactions if any occurred
+        if !price_level_match.transactions.as_vec().is_empty() {
+            // Update last trade price atomically
+            self.last_trade_price
+                .store(price_level.price(), Ordering::Relaxed);
+            self.has_traded.store(true, Ordering::Relaxed);
+            self.last_trade_millis
+                .store(self.clock.now_millis(), Ordering::Relaxed);
+            self.transaction_count.fetch_add(
+                price_level_match.transactions.as_vec().len() as u64,
+                Ordering::Relaxed,
+            );
+
+            self.capture_owners(
+                price_level_match
+                    .transactions
+                    .as_vec()
+                    .iter()
+                    .map(|transaction| transaction.maker_order_id),
+            );
+
+            // Add transactions to result
+            for transaction in price_level_match.transactions.as_vec() {
+                match_result.add_transaction(*transaction);
+            }
+
+            // notify price level changes
+            if !hidden {
+                self.notify_price_level_changed(side.opposite(), price_level);
+            }
+        }
+
+        // Report each execution to the resting orders
+        if self.order_event_listener.is_some() {
+            let transactions = price_level_match.transactions.as_vec();
+            for (index, transaction) in transactions.iter().enumerate() {
+                let maker = transaction.maker_order_id;
+                let completes = price_level_match.filled_order_ids.contains(&maker)
+                    && !transactions[index + 1..]
+                        .iter()
+                        .any(|later| later.maker_order_id == maker);
+                self.emit_fill_event(maker, transaction.price, transaction.quantity, completes);
+            }
+        }
+
+        // Collect filled orders for batch removal
+        for &filled_order_id in &price_level_match.filled_order_ids {
+            match_result.add_filled_order_id(filled_order_id);
+            filled_orders.push(filled_order_id);
+        }
+
+        price_level_match.remaining_quantity
+    }
+
+    /// Reports a single execution of an order as a partial or completing fill.
+    pub(super) fn emit_fill_event(
+        &self,
+        order_id: OrderId,
+        price: u64,
+        quantity: u64,
+        completes: bool,
+    ) {
+        self.emit_order_event(if completes {
+            OrderEvent::Filled {
+                order_id,
+                price,
+                quantity,
+            }
+        } else {
+            OrderEvent::PartiallyFilled {
+                order_id,
+                price,
+                quantity,
+            }
+        });
+    }
+
+    /// Converts a market-to-limit order into the limit order it becomes on entry
+    ///
+    /// The order takes the best opposite price as its limit, so it only executes at
+    /// that level and any remainder rests as a standard limit order at the execution
+    /// price. Fully hidden levels count towards the best price, since the order would
+    /// execute against them first. When the opposite side is empty there is no price
+    /// to anchor on, and the order's own price is used as the limit instead.
+    ///
+    /// Orders of any other type are returned unchanged.
+    pub fn convert_market_to_limit(&self, order: OrderType<T>) -> OrderType<T> {
+        match order {
+            OrderType::MarketToLimit {
+                id,
+                price,
+                quantity,
+                side,
+                timestamp,
+                time_in_force,
+                extra_fields,
+            } => {
+                let execution_price = match side {
+                    Side::Buy => self
+                        .best_ask()
+                        .into_iter()
+                        .chain(self.hidden_asks.front().map(|entry| *entry.key()))
+                        .min(),
+                    Side::Sell => self
+                        .best_bid()
+                        .into_iter()
+                        .chain(self.hidden_bids.back().map(|entry| *entry.key()))
+                        .max(),
+                }
+                .unwrap_or(price);
+
+                OrderType::Standard {
+                    id,
+                    price: execution_price,
+                    quantity,
+                    side,
+                    timestamp,
+                    time_in_force,
+                    extra_fields,
+                }
+            }
+            other => other,
+        }
+    }
+
     /// Optimized peek match without memory pooling or sorting
     ///
     /// # Performance Optimization
     /// Uses SkipMap's natural ordering to eliminate sorting overhead.
     /// Time complexity: O(M log N) where M = price levels inspected.
     pub fn peek_match(&self, side: Side, quantity: u64, price_limit: Option<u64>) -> u64 {
-        let price_levels = match side {
-            Side::Buy => &self.asks,
-            Side::Sell => &self.bids,
-        };
+        // Hidden orders are matchable, so they count towards the available quantity
+        let matched_quantity = self.peek_levels(
+            self.side_levels(side.opposite(), false),
+            side,
+            quantity,
+            price_limit,
+        );
+        matched_quantity.saturating_add(self.peek_levels(
+            self.side_levels(side.opposite(), true),
+            side,
+            quantity.saturating_sub(matched_quantity),
+            price_limit,
+        ))
+    }
 
+    /// Sums the quantity an order could take from the given levels, up to `quantity`.
+    fn peek_levels(
+        &self,
+        price_levels: &SkipMap<u64, Arc<PriceLevel>>,
+        side: Side,
+        quantity: u64,
+        price_limit: Option<u64>,
+    ) -> u64 {
         if price_levels.is_empty() {
             return 0;
         }
 
         let mut matched_quantity = 0u64;
 
-        // Iterate through prices in optimal order (already sorted by SkipMap)
-        let price_iter: Box<dyn Iterator<Item = _>> = match side {
-            Side::Buy => Box::new(price_levels.iter()),
-            Side::Sell => Box::new(price_levels.iter().rev()),
-        };
-
-        // Process each price level
-        for entry in price_iter {
+        // Process each price level in optimal order (already sorted by SkipMap)
+        for entry in Self::levels_in_match_order(price_levels, side) {
             // Early termination when we have enough quantity
             if matched_quantity >= quantity {
                 break;
@@ -232,3 +461,23 @@ where
         results
     }
 }
+
+/// Levels an incoming order matches against, read from a level map or from
+/// the dense ladder.
+enum MatchLevels<'a> {
+    Map(LevelIter<'a>),
+    Ladder(LadderLevels<'a>),
+}
+
+impl Iterator for MatchLevels<'_> {
+    type Item = (u64, Arc<PriceLevel>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Map(levels) => levels
+                .next()
+                .map(|entry| (*entry.key(), Arc::clone(entry.value()))),
+            Self::Ladder(levels) => levels.next(),
+        }
+    }
+}
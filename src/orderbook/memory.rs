@@ -0,0 +1,229 @@
+//! Approximate memory accounting of an order book.
+
+use super::book::OrderBook;
+use crossbeam_skiplist::SkipMap;
+use pricelevel::{OrderId, OrderType, PriceLevel, Side};
+use serde::{Deserialize, Serialize};
+use std::mem::{size_of, size_of_val};
+use std::sync::Arc;
+
+/// Bytes of one shard of a `DashMap`, padded to keep shards on separate
+/// cache lines.
+const SHARD_BYTES: usize = 128;
+
+/// Bytes of a reference counted allocation on top of its value.
+const ARC_BYTES: usize = 2 * size_of::<usize>();
+
+/// Bytes of a skip list node on top of its key and value: reference count,
+/// height and the average tower of two links.
+const SKIPLIST_NODE_BYTES: usize = 4 * size_of::<usize>();
+
+/// Bytes of an empty price level held in a skip list node, excluding its
+/// statistics and the shard table of its order map.
+const LEVEL_BYTES: usize =
+    SKIPLIST_NODE_BYTES + size_of::<(u64, Arc<PriceLevel>)>() + ARC_BYTES + size_of::<PriceLevel>();
+
+/// Bytes of a resting order inside its price level: the shared order, its
+/// entry in the order map of the level, with one control byte, and its slot
+/// in the time priority queue.
+const ORDER_BYTES: usize = ARC_BYTES
+    + size_of::<OrderType<()>>()
+    + size_of::<(OrderId, Arc<OrderType<()>>)>()
+    + 1
+    + size_of::<OrderId>()
+    + size_of::<usize>();
+
+/// Approximate memory used by an order book, see [`OrderBook::memory_stats`].
+///
+/// Byte figures are estimates built from the sizes of the stored types and the
+/// number of entries and reserved slots, meant to follow the growth of a book
+/// rather than to match the allocator to the byte.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    /// Bytes of the bid price levels and the orders resting in them
+    pub bids_bytes: usize,
+    /// Bytes of the ask price levels and the orders resting in them
+    pub asks_bytes: usize,
+    /// Bytes of the map locating resting orders by ID
+    pub order_locations_bytes: usize,
+    /// Bytes of the emptied price levels and returned order handles kept
+    /// for reuse
+    pub pools_bytes: usize,
+    /// Bid price levels, visible and hidden
+    pub bid_levels: usize,
+    /// Ask price levels, visible and hidden
+    pub ask_levels: usize,
+    /// Price levels parked for reuse
+    pub pooled_levels: usize,
+    /// Returned order handles tracked for reuse
+    pub pooled_orders: usize,
+    /// Resting orders on both sides
+    pub order_count: usize,
+}
+
+impl MemoryStats {
+    /// Bytes of every structure accounted for
+    #[must_use]
+    pub fn total_bytes(&self) -> usize {
+        self.bids_bytes + self.asks_bytes + self.order_locations_bytes + self.pools_bytes
+    }
+
+    /// Price levels on both sides, visible and hidden
+    #[must_use]
+    pub fn level_count(&self) -> usize {
+        self.bid_levels + self.ask_levels
+    }
+}
+
+/// Number of shards a `DashMap` gets when none is asked for.
+fn default_shard_amount() -> usize {
+    let cpus = std::thread::available_parallelism().map_or(1, usize::from);
+    (cpus * 4).next_power_of_two()
+}
+
+/// Bytes of a hash map of `capacity` slots of `entry` bytes each, split into
+/// `shards` shards.
+fn hash_map_bytes(capacity: usize, entry: usize, shards: usize) -> usize {
+    capacity * (entry + 1) + shards * SHARD_BYTES
+}
+
+/// Bytes of `level` and the orders resting in it.
+fn level_bytes(level: &PriceLevel) -> usize {
+    LEVEL_BYTES
+        + ARC_BYTES
+        + size_of_val(level.stats().as_ref())
+        + default_shard_amount() * SHARD_BYTES
+        + level.order_count() * ORDER_BYTES
+}
+
+/// Level count, order count and bytes of the levels in `price_levels`.
+fn levels_usage(price_levels: &SkipMap<u64, Arc<PriceLevel>>) -> (usize, usize, usize) {
+    price_levels
+        .iter()
+        .fold((0, 0, 0), |(levels, orders, bytes), entry| {
+            let level = entry.value();
+            (
+                levels + 1,
+                orders + level.order_count(),
+                bytes + level_bytes(level),
+            )
+        })
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Approximate memory used by the price levels, resting orders, order
+    /// location map and level pool of the book
+    ///
+    /// Walks every price level once, so long-running services can poll it to
+    /// watch the book grow and decide when to compact it or rotate snapshots.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let empty = book.memory_stats();
+    /// book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// let stats = book.memory_stats();
+    /// assert_eq!((stats.bid_levels, stats.order_count), (1, 2));
+    /// assert!(stats.bids_bytes > empty.bids_bytes);
+    /// assert_eq!(stats.asks_bytes, 0);
+    /// ```
+    #[must_use]
+    pub fn memory_stats(&self) -> MemoryStats {
+        let mut stats = MemoryStats::default();
+        for side in [Side::Buy, Side::Sell] {
+            for hidden in [false, true] {
+                let (levels, orders, bytes) = levels_usage(self.side_levels(side, hidden));
+                let (side_levels, side_bytes) = match side {
+                    Side::Buy => (&mut stats.bid_levels, &mut stats.bids_bytes),
+                    Side::Sell => (&mut stats.ask_levels, &mut stats.asks_bytes),
+                };
+                *side_levels += levels;
+                *side_bytes += bytes;
+                stats.order_count += orders;
+            }
+        }
+
+        let location_shards = self
+            .config
+            .location_shards
+            .map_or_else(default_shard_amount, |shards| {
+                shards.max(2).next_power_of_two()
+            });
+        stats.order_locations_bytes = hash_map_bytes(
+            self.order_locations.capacity(),
+            size_of::<(OrderId, (u64, Side))>(),
+            location_shards,
+        );
+
+        stats.pooled_levels = self.level_pool.len();
+        stats.pools_bytes = hash_map_bytes(
+            self.level_pool.map_capacity(),
+            size_of::<(u64, Arc<PriceLevel>)>(),
+            default_shard_amount(),
+        ) + stats.pooled_levels
+            * (LEVEL_BYTES + default_shard_amount() * SHARD_BYTES);
+
+        stats.pooled_orders = self.order_pool.len();
+        stats.pools_bytes += self.order_pool.queue_capacity() * size_of::<Arc<OrderType<T>>>()
+            + stats.pooled_orders * (ARC_BYTES + size_of::<OrderType<T>>());
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pricelevel::TimeInForce;
+
+    #[test]
+    fn test_memory_stats_follow_book_growth() {
+        let book = OrderBook::<()>::new("TEST");
+        let empty = book.memory_stats();
+        assert_eq!(empty.level_count(), 0);
+        assert_eq!(empty.order_count, 0);
+        assert_eq!(empty.bids_bytes + empty.asks_bytes, 0);
+
+        let ids: Vec<OrderId> = (0..10).map(|_| OrderId::new()).collect();
+        for (i, &id) in ids.iter().enumerate() {
+            let (price, side) = if i % 2 == 0 {
+                (100 - i as u64, Side::Buy)
+            } else {
+                (200 + i as u64, Side::Sell)
+            };
+            book.add_limit_order(id, price, 5, side, TimeInForce::Gtc, None)
+                .unwrap();
+        }
+        book.add_hidden_order(OrderId::new(), 90, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        let full = book.memory_stats();
+        assert_eq!(full.bid_levels, 6);
+        assert_eq!(full.ask_levels, 5);
+        assert_eq!(full.order_count, 11);
+        let empty_level = level_bytes(&PriceLevel::new(0));
+        assert_eq!(full.bids_bytes, 6 * empty_level + 6 * ORDER_BYTES);
+        assert!(full.order_locations_bytes >= empty.order_locations_bytes);
+        assert_eq!(
+            full.total_bytes(),
+            full.bids_bytes + full.asks_bytes + full.order_locations_bytes + full.pools_bytes
+        );
+
+        // Emptied levels move to the pool
+        for &id in &ids {
+            book.cancel_order(id).unwrap();
+        }
+        let drained = book.memory_stats();
+        assert_eq!(drained.level_count(), 1);
+        assert_eq!(drained.pooled_levels, 10);
+        assert!(drained.pools_bytes > full.pools_bytes);
+        assert!(drained.asks_bytes < full.asks_bytes);
+    }
+}
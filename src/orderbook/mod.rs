@@ -1,42 +1,193 @@
 //! OrderBook implementation for managing multiple price levels and order matching.
 
+/// Async book manager running every book on its own Tokio task.
+#[cfg(feature = "tokio")]
+pub mod async_manager;
+/// Opening and closing auction uncross.
+pub mod auction;
+/// Top-of-book accessor returning best prices and quantities together.
+pub mod bbo;
 pub mod book;
+/// Incremental price level deltas between full snapshots.
+pub mod delta;
+/// Engine mode applying every mutation of a book on a single matching thread.
+pub mod engine;
 pub mod error;
+/// Maker/taker fee schedules with volume tiers.
+pub mod fees;
+/// Complete, restorable image of an order book for hot-standby failover.
+pub mod full_state;
+/// Liquidity heatmap recorder sampling resting depth over time.
+pub mod heatmap;
 /// Implied volatility calculation from order book prices.
 pub mod implied_volatility;
+/// Structural invariant checks and diagnostics.
+pub mod integrity;
 /// Functional-style iterators for order book analysis.
 pub mod iterators;
+/// Write-ahead journal of book commands for crash recovery.
+pub mod journal;
+/// Book-wide and per-owner kill switches.
+pub mod kill_switch;
+/// Market-by-order (L3) feed applier mirroring an external exchange book.
+pub mod l3_feed;
+mod ladder;
 /// Multi-book management with centralized trade event routing.
 pub mod manager;
+/// Checksum-protected snapshot of every managed book for failover.
+pub mod manager_snapshot;
+/// Aggregated activity and depth metrics of managed books.
+pub mod manager_stats;
 /// Market impact simulation and liquidity analysis.
 pub mod market_impact;
+/// Bulk cancellation by side, price range and owner, and eviction beyond the depth limit.
+pub mod mass_cancel;
 pub mod matching;
+/// Approximate memory usage of the levels, orders and pools of a book.
+pub mod memory;
+/// Event-sourced replay of recorded commands for backtesting and debugging.
+pub mod replay;
+/// What-if sandbox applying hypothetical orders to an overlay of a live book.
+pub mod sandbox;
+/// Periodic snapshots of managed books with a retention policy.
+pub mod scheduler;
+/// Trading session states and their order rules.
+pub mod session;
+/// Book manager spreading books over a fixed pool of worker threads.
+pub mod sharded_manager;
 /// Aggregate statistics for order book analysis.
 pub mod statistics;
 
 /// Price level change events for real-time order book updates.
 pub mod book_change_event;
 mod cache;
+/// Static and dynamic price bands with reject or halt actions.
+pub mod circuit_breaker;
+/// Per-book tick size, lot size and minimum notional rules.
+pub mod config;
+/// Consolidated view of one instrument across several venues.
+pub mod consolidated;
+mod depth_kernels;
+/// Decay-weighted fair price over the top levels of the book.
+pub mod fair_price;
+/// Fill probability of resting orders from their queue position and price level flow.
+pub mod fill_probability;
 /// Contains the core logic for modifying the order book state, such as adding, canceling, or updating orders.
 pub mod modifications;
 pub mod operations;
-mod pool;
+/// Order lifecycle events for tracking every order from submission to completion.
+pub mod order_event;
+/// Order flow imbalance computed from best bid and offer changes.
+pub mod order_flow;
+/// Order ownership tracking.
+pub mod owner;
+/// Object pools reusing vectors and price levels in hot paths.
+pub mod pool;
+/// Per-owner positions and realized PnL built from executed trades.
+pub mod positions;
 mod private;
+/// Two-sided quote management for market makers.
+pub mod quotes;
+/// Per-owner rate limiting of order requests.
+pub mod rate_limit;
+/// Replenishment of the touch after trades that sweep price levels.
+pub mod resiliency;
+/// Pre-trade risk checks on order size, notional and price.
+pub mod risk;
 pub mod snapshot;
+/// Implied prices of spread instruments from their leg books.
+pub mod spread_book;
+/// Async broadcast streams of trades and price level changes.
+#[cfg(feature = "tokio")]
+pub mod stream;
+/// Multi-subscriber listener registration.
+pub mod subscription;
+/// Per-owner surveillance metrics and alerts for market-abuse monitoring.
+pub mod surveillance;
+/// Instrument metadata and trading rules of managed symbols.
+pub mod symbol_config;
 mod tests;
+/// Time-weighted averages of the spread, top-of-book depth and imbalance.
+pub mod time_weighted;
 /// Trade-related types including TradeResult and TradeListener for monitoring order executions.
 pub mod trade;
+/// Bounded tape of recent executions with rolling-window statistics.
+pub mod trade_tape;
+/// Executed volume per price bucket with point of control and value area.
+pub mod volume_profile;
 
-pub use book::OrderBook;
+pub use auction::{AuctionResult, AuctionUncross};
+pub use bbo::Bbo;
+pub use book::{DepthLevel, OrderBook};
+pub use circuit_breaker::{
+    BandAction, BandTrigger, CircuitBreaker, PriceBandEvent, PriceBandLimits, PriceBandListener,
+    ReferencePriceSource,
+};
+pub use config::{BookConfig, LevelEviction};
+pub use consolidated::{ConsolidatedBook, ConsolidatedLevel, Nbbo, VenueQuantity};
+pub use delta::{LevelChange, OrderBookDelta};
+pub use engine::{ENGINE_QUEUE_CAPACITY, EngineHandle, MatchingEngine, Receipt};
 pub use error::OrderBookError;
+pub use fair_price::{FairPriceContribution, WeightedFairPrice};
+pub use fees::{FeeModel, FeeSchedule, FeeTier, TransactionFee};
+pub use fill_probability::{FillEstimate, LevelFlowTracker, LevelIntensity};
+pub use full_state::OrderBookFullState;
+pub use heatmap::{HeatmapCell, HeatmapColumn, HeatmapConfig, HeatmapPoint, LiquidityHeatmap};
 pub use implied_volatility::{
-    BlackScholes, IVConfig, IVError, IVParams, IVQuality, IVResult, OptionType, PriceSource,
-    SolverConfig,
+    ArbitrageViolation, BatchVolatility, BinomialTree, BlackScholes, ExerciseStyle, GreeksResult,
+    IVConfig, IVDiagnostics, IVError, IVParams, IVQuality, IVResult, IVSmile, ImpliedForward,
+    OptionPosition, OptionType, ParityQuote, PortfolioConfig, PortfolioGreeks, PositionFailure,
+    PositionGreeks, PriceSource, SmileConfig, SmileFailure, SmilePoint, SolverConfig, SolverMethod,
+    SurfacePoint, SurfaceSlice, SviFit, SviParams, SviResidual, TermPoint, TermStructure,
+    VolSurface,
 };
+pub use integrity::{CrossedBook, IntegrityReport};
 pub use iterators::LevelInfo;
-pub use market_impact::{MarketImpact, OrderSimulation};
+#[cfg(all(feature = "mmap", unix))]
+pub use journal::MmapJournal;
+pub use journal::{FileJournal, InMemoryJournal, Journal, JournalCommand, JournalEntry};
+pub use l3_feed::{
+    L3ApplyOutcome, L3FeedApplier, L3Message, L3Order, L3Snapshot, L3SnapshotSource, L3Update,
+};
+pub use ladder::MAX_LADDER_TICKS;
+pub use manager_snapshot::{ManagedBookSnapshot, ManagerSnapshot};
+pub use manager_stats::{BookStats, ManagerStats};
+pub use market_impact::{LimitOrderSimulation, MarketImpact, OrderSimulation, PriceMoveCost};
+pub use memory::MemoryStats;
+pub use order_event::{OrderEvent, OrderEventListener};
+pub use order_flow::{OfiSample, OrderFlowImbalance};
+pub use owner::OwnerId;
+pub use pool::{
+    DEFAULT_LEVEL_POOL_CAPACITY, DEFAULT_ORDER_POOL_CAPACITY, LevelPool, OrderPool, PoolStats,
+};
+pub use positions::{Position, PositionTracker};
+pub use quotes::QuotePair;
+pub use rate_limit::{RateLimit, RateLimiter};
+pub use replay::{ReplayEngine, ReplaySpeed, ReplayStatus};
+pub use resiliency::{ResiliencyStats, ResiliencyTracker, SweepEvent};
+pub use risk::{MaxNotional, MaxOrderQuantity, PriceCollar, RiskChecker, RiskContext, RiskOrder};
+pub use sandbox::OrderBookSandbox;
+pub use scheduler::{
+    FileSnapshotStore, InMemorySnapshotStore, SnapshotScheduler, SnapshotStore, SnapshotTrigger,
+};
+pub use session::{SessionListener, SessionState, SessionStateChangedEvent};
+pub use sharded_manager::ShardedBookManager;
 pub use snapshot::{
-    EnrichedSnapshot, MetricFlags, ORDERBOOK_SNAPSHOT_FORMAT_VERSION, OrderBookSnapshot,
-    OrderBookSnapshotPackage,
+    EnrichedSnapshot, MetricFlags, ORDERBOOK_SNAPSHOT_FORMAT_VERSION,
+    ORDERBOOK_SNAPSHOT_MIN_SUPPORTED_VERSION, OrderBookSnapshot, OrderBookSnapshotPackage,
+    SnapshotFormat,
+};
+pub use spread_book::{ImpliedQuote, ImpliedQuotes, SpreadBook, SpreadLeg};
+pub use statistics::{
+    BookDepthStats, DepthStats, DistributionBin, ImpactEstimate, KyleLambda, LambdaEstimate,
+    PriceBar, RealizedVolatility, VolatilityEstimator, VolatilitySource,
+};
+pub use subscription::SubscriptionId;
+pub use surveillance::{
+    OwnerActivity, Surveillance, SurveillanceAlert, SurveillanceConfig, SurveillanceListener,
+    SurveillanceMetric,
 };
-pub use statistics::{DepthStats, DistributionBin};
+pub use symbol_config::{InstrumentType, SymbolConfig, SymbolRegistry};
+pub use time_weighted::{TimeWeightedLiquidity, TimeWeightedStats};
+pub use trade_tape::{TapeEntry, TapeStats, TapeWindow, TradeTape};
+pub use volume_profile::{VolumeProfile, VolumeProfileLevel, VolumeProfileSummary};
@@ -1,10 +1,13 @@
 use crate::orderbook::book::OrderBook;
-use crate::orderbook::book_change_event::PriceLevelChangedEvent;
 use crate::orderbook::error::OrderBookError;
-use crate::orderbook::trade::TradeResult;
-use pricelevel::{OrderId, OrderType, OrderUpdate, PriceLevel, Side};
+use crate::orderbook::journal::JournalCommand;
+use crate::orderbook::order_event::OrderEvent;
+use crate::orderbook::owner::OwnerId;
+use crate::orderbook::risk::RiskOrder;
+use pricelevel::{MatchResult, OrderId, OrderType, OrderUpdate, Side};
 use std::sync::Arc;
-use tracing::trace;
+use std::sync::atomic::Ordering;
+use tracing::{error, trace};
 
 /// A trait to abstract quantity access and modification for different order types.
 pub trait OrderQuantity<T = ()> {
@@ -103,11 +106,22 @@ where
     T: Clone + Send + Sync + Default + 'static,
 {
     /// Update an order's price and/or quantity
+    ///
+    /// Amendments of owned orders count against the owner's rate limit, if set.
+    /// [`OrderUpdate::Cancel`] goes through [`OrderBook::cancel_order`].
     pub fn update_order(
         &self,
         update: OrderUpdate,
     ) -> Result<Option<Arc<OrderType<T>>>, OrderBookError> {
-        self.cache.invalidate();
+        let order_id = match update {
+            OrderUpdate::Cancel { order_id } => return self.cancel_order(order_id),
+            OrderUpdate::UpdatePrice { order_id, .. }
+            | OrderUpdate::UpdateQuantity { order_id, .. }
+            | OrderUpdate::UpdatePriceAndQuantity { order_id, .. }
+            | OrderUpdate::Replace { order_id, .. } => order_id,
+        };
+        self.check_rate_limit(self.order_owner(order_id))?;
+        let _journaled = self.begin_journaled(|| JournalCommand::UpdateOrder { update })?;
         trace!("Order book {}: Updating order {:?}", self.symbol, update);
         match update {
             OrderUpdate::UpdatePrice {
@@ -133,10 +147,19 @@ where
                         return Ok(None); // Order not found
                     };
 
-                    // Cancel the original order
-                    self.cancel_order(order_id)?;
+                    // Validate against the book rules before touching the resting order
+                    self.config
+                        .validate(new_price, original_order.total_quantity())?;
+
+                    self.check_session(
+                        new_price,
+                        original_order.side(),
+                        original_order.is_immediate(),
+                    )?;
+                    self.check_price_band(order_id, original_order.side(), new_price)?;
 
                     // Create a new order with the updated price
+                    let original_location = (original_order.price(), original_order.side());
                     let mut new_order = original_order;
 
                     // Update the price based on order type
@@ -150,8 +173,22 @@ where
                         OrderType::ReserveOrder { price, .. } => *price = new_price,
                     }
 
+                    // Cancel the original order, keeping track of its visibility and owner
+                    let hidden = self.is_hidden_order(order_id);
+                    let owner = self.order_owner(order_id);
+                    self.check_replacement(&new_order, owner, hidden, original_location)?;
+                    self.recycle(self.remove_order(order_id)?);
+
                     // Add the updated order
-                    let result = self.add_order(new_order)?;
+                    let result = self.add_order_with_placement(
+                        new_order,
+                        OrderPlacement {
+                            hidden,
+                            owner,
+                            replacing: true,
+                        },
+                    )?;
+                    self.notify_modified(&result);
                     Ok(Some(result))
                 } else {
                     Ok(None) // Order not found
@@ -166,48 +203,65 @@ where
                 let location = self.order_locations.get(&order_id).map(|val| *val);
 
                 if let Some((price, side)) = location {
-                    // Get the appropriate price levels map
-                    let price_levels = match side {
-                        Side::Buy => &self.bids,
-                        Side::Sell => &self.asks,
+                    self.config.validate(price, new_quantity)?;
+
+                    // Run the submission checks on the amended order before touching
+                    // the resting one
+                    let Some(original_order) = self.get_order(order_id) else {
+                        return Ok(None);
                     };
+                    let mut amended = (*original_order).clone();
+                    amended.set_quantity(new_quantity);
+                    let owner = self.order_owner(order_id);
+                    self.check_trading_enabled(owner)?;
+                    self.check_session(price, side, amended.is_immediate())?;
+                    self.check_risk(&RiskOrder {
+                        order_id,
+                        side,
+                        price: Some(price),
+                        quantity: amended.total_quantity(),
+                        owner,
+                    })?;
+
+                    // Get the appropriate price levels map
+                    let hidden = self.is_hidden_order(order_id);
+                    let price_levels = self.side_levels(side, hidden);
 
                     // Attempt to update the order within the price level
                     let mut result = None;
                     let mut is_empty = false;
 
                     // Get the price level and update it
-                    if let Some(entry) = price_levels.get(&price) {
-                        let price_level = entry.value();
+                    if let Some(price_level) = self.level_at(side, price, hidden) {
                         let update = OrderUpdate::UpdateQuantity {
                             order_id,
                             new_quantity,
                         };
 
+                        let before = price_level.total_quantity();
                         if let Ok(updated_order) = price_level.update_order(update)
                             && let Some(order) = updated_order
                         {
                             // notify price level changes
-                            if let Some(ref listener) = self.price_level_changed_listener {
-                                listener(PriceLevelChangedEvent {
-                                    side,
-                                    price: price_level.price(),
-                                    quantity: price_level.visible_quantity(),
-                                })
+                            if !hidden {
+                                self.totals
+                                    .resized(side, before, price_level.total_quantity());
+                                self.notify_price_level_changed(side, &price_level);
                             }
-                            result = Some(Arc::new(self.convert_from_unit_type(&order)));
+                            let order =
+                                self.order_pool.acquire(self.convert_from_unit_type(&order));
+                            self.notify_modified(&order);
+                            result = Some(order);
                         }
 
                         is_empty = price_level.order_count() == 0;
                     }
 
                     // If the price level is now empty, remove it
-                    if is_empty {
-                        price_levels.remove(&price);
-                        self.order_locations.remove(&order_id);
+                    if is_empty && self.prune_empty_level(price_levels, price) {
+                        self.forget_order(&order_id);
                     }
 
-                    self.cache.invalidate();
                     Ok(result)
                 } else {
                     Ok(None) // Order not found
@@ -231,10 +285,18 @@ where
                         return Ok(None); // Order not found
                     };
 
-                    // Cancel the original order
-                    self.cancel_order(order_id)?;
+                    // Validate against the book rules before touching the resting order
+                    self.config.validate(new_price, new_quantity)?;
+
+                    self.check_session(
+                        new_price,
+                        original_order.side(),
+                        original_order.is_immediate(),
+                    )?;
+                    self.check_price_band(order_id, original_order.side(), new_price)?;
 
                     // Create a new order with the updated price and quantity
+                    let original_location = (original_order.price(), original_order.side());
                     let mut new_order = original_order;
 
                     // Update the price based on order type
@@ -251,66 +313,30 @@ where
                     // Update the quantity using the trait method
                     new_order.set_quantity(new_quantity);
 
+                    // Cancel the original order, keeping track of its visibility and owner
+                    let hidden = self.is_hidden_order(order_id);
+                    let owner = self.order_owner(order_id);
+                    self.check_replacement(&new_order, owner, hidden, original_location)?;
+                    self.recycle(self.remove_order(order_id)?);
+
                     // Add the updated order
-                    let result = self.add_order(new_order)?;
+                    let result = self.add_order_with_placement(
+                        new_order,
+                        OrderPlacement {
+                            hidden,
+                            owner,
+                            replacing: true,
+                        },
+                    )?;
+                    self.notify_modified(&result);
                     Ok(Some(result))
                 } else {
                     Ok(None) // Order not found
                 }
             }
 
-            OrderUpdate::Cancel { order_id } => {
-                // Get order location without locking
-                let location = self.order_locations.get(&order_id).map(|val| *val);
-
-                if let Some((price, side)) = location {
-                    // Get the appropriate price levels map
-                    let price_levels = match side {
-                        Side::Buy => &self.bids,
-                        Side::Sell => &self.asks,
-                    };
-
-                    // Attempt to cancel the order
-                    let mut result = None;
-                    let mut is_empty = false;
-
-                    // Get the current order first
-                    if let Some(current_order) = self.get_order(order_id) {
-                        result = Some(current_order);
-
-                        // Remove the order directly from the price level
-                        if let Some(entry) = price_levels.get(&price) {
-                            let price_level = entry.value();
-                            let cancel_update = OrderUpdate::Cancel { order_id };
-                            let result = price_level.update_order(cancel_update);
-                            // notify price level changes
-                            if let Some(ref listener) = self.price_level_changed_listener
-                                && let Ok(updated_order) = result
-                                && updated_order.is_some()
-                            {
-                                listener(PriceLevelChangedEvent {
-                                    side,
-                                    price: price_level.price(),
-                                    quantity: price_level.visible_quantity(),
-                                })
-                            }
-                            is_empty = price_level.order_count() == 0;
-                        }
-
-                        // Remove from order locations tracking
-                        self.order_locations.remove(&order_id);
-                    }
-
-                    // If price level is empty, remove it
-                    if is_empty {
-                        price_levels.remove(&price);
-                    }
-
-                    Ok(result)
-                } else {
-                    Ok(None) // Order not found
-                }
-            }
+            // Cancellations were handed to `cancel_order` above
+            OrderUpdate::Cancel { .. } => Ok(None),
 
             OrderUpdate::Replace {
                 order_id,
@@ -413,11 +439,33 @@ where
                         }
                     }
 
-                    // Cancel the original order
-                    self.cancel_order(order_id)?;
+                    // Validate against the book rules before touching the resting order
+                    self.config.validate(price, quantity)?;
+
+                    self.check_session(price, side, new_order.is_immediate())?;
+                    self.check_price_band(order_id, side, price)?;
+
+                    // Cancel the original order, keeping track of its visibility and owner
+                    let hidden = self.is_hidden_order(order_id);
+                    let owner = self.order_owner(order_id);
+                    self.check_replacement(
+                        &new_order,
+                        owner,
+                        hidden,
+                        (original.price(), original.side()),
+                    )?;
+                    self.recycle(self.remove_order(order_id)?);
 
                     // Add the new order
-                    let result = self.add_order(new_order)?;
+                    let result = self.add_order_with_placement(
+                        new_order,
+                        OrderPlacement {
+                            hidden,
+                            owner,
+                            replacing: true,
+                        },
+                    )?;
+                    self.notify_modified(&result);
                     Ok(Some(result))
                 } else {
                     Ok(None) // Original order not found
@@ -427,71 +475,214 @@ where
     }
 
     /// Cancel an order by ID
+    ///
+    /// Cancellations of owned orders count against the owner's rate limit, if set.
     pub fn cancel_order(
         &self,
         order_id: OrderId,
     ) -> Result<Option<Arc<OrderType<T>>>, OrderBookError> {
-        self.cache.invalidate();
+        self.check_rate_limit(self.order_owner(order_id))?;
+        self.cancel_resting_order(order_id)
+    }
+
+    /// Cancels an order without counting it against the owner's rate limit.
+    pub(super) fn cancel_resting_order(
+        &self,
+        order_id: OrderId,
+    ) -> Result<Option<Arc<OrderType<T>>>, OrderBookError> {
+        let _journaled = self.begin_journaled(|| JournalCommand::CancelOrder { order_id })?;
+        let owner = self
+            .surveillance
+            .is_some()
+            .then(|| self.order_owner(order_id))
+            .flatten();
+        let cancelled = self.remove_order(order_id)?;
+        if let Some(ref order) = cancelled {
+            self.surveil_cancel(owner, order_id);
+            if let Some(ref flow) = self.level_flow {
+                flow.record_cancel(
+                    self.clock.now_millis(),
+                    order.side(),
+                    order.price(),
+                    order.total_quantity(),
+                );
+            }
+            self.emit_order_event(OrderEvent::Cancelled {
+                order_id,
+                quantity: order.total_quantity(),
+            });
+        }
+        Ok(cancelled)
+    }
+
+    /// Removes a resting order from the book without reporting it as cancelled.
+    pub(super) fn remove_order(
+        &self,
+        order_id: OrderId,
+    ) -> Result<Option<Arc<OrderType<T>>>, OrderBookError> {
         // First, we find the order's location (price and side) without locking
         let location = self.order_locations.get(&order_id).map(|val| *val);
 
         if let Some((price, side)) = location {
             // Obtener el mapa de niveles de precio apropiado
-            let price_levels = match side {
-                Side::Buy => &self.bids,
-                Side::Sell => &self.asks,
-            };
+            let hidden = self.is_hidden_order(order_id);
+            let price_levels = self.side_levels(side, hidden);
 
             // Create the update to cancel
             let update = OrderUpdate::Cancel { order_id };
 
             // Attempt to cancel the order from the price level
             let mut result = None;
-            let mut empty_level = false;
 
-            if let Some(entry) = price_levels.get(&price) {
-                let price_level = entry.value();
+            if let Some(price_level) = self.level_at(side, price, hidden) {
                 // Try to cancel the order
                 if let Ok(cancelled) = price_level.update_order(update) {
                     result = cancelled;
 
                     // notify price level changes
-                    if result.is_some()
-                        && let Some(ref listener) = self.price_level_changed_listener
+                    if let Some(ref cancelled) = result
+                        && !hidden
                     {
-                        listener(PriceLevelChangedEvent {
-                            side,
-                            price: price_level.price(),
-                            quantity: price_level.visible_quantity(),
-                        })
+                        self.totals.removed(side, cancelled.total_quantity());
+                        self.notify_price_level_changed(side, &price_level);
                     }
-
-                    // Check if the level became empty
-                    empty_level = price_level.order_count() == 0;
                 }
             }
 
-            self.cache.invalidate();
+            // If the level became empty, remove it
+            self.prune_empty_level(price_levels, price);
+
+            // Convert while the extra fields are still tracked
+            let result =
+                result.map(|order| self.order_pool.acquire(self.convert_from_unit_type(&order)));
             // If we got a result and the order was canceled
             if result.is_some() {
                 // Remove the order from the locations map
-                self.order_locations.remove(&order_id);
-
-                // If the level became empty, remove it
-                if empty_level {
-                    price_levels.remove(&price);
-                }
+                self.forget_order(&order_id);
             }
 
-            Ok(result.map(|order| Arc::new(self.convert_from_unit_type(&order))))
+            Ok(result)
         } else {
             Ok(None)
         }
     }
 
+    /// Replaces the extra fields of a resting order
+    ///
+    /// The order keeps its price, quantity and queue position.
+    ///
+    /// # Returns
+    /// The updated order, or `None` if no order with this ID is resting in the book
+    /// or the update could not be recorded in the attached journal.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<String>::new("BTC/USD");
+    /// let id = OrderId::new();
+    /// book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, Some("desk-a".to_string()))
+    ///     .unwrap();
+    ///
+    /// book.update_order_extra_fields(id, "desk-b".to_string()).unwrap();
+    /// assert_eq!(book.get_order(id).unwrap().extra_fields(), "desk-b");
+    /// ```
+    pub fn update_order_extra_fields(
+        &self,
+        order_id: OrderId,
+        extra_fields: T,
+    ) -> Option<Arc<OrderType<T>>> {
+        if !self.order_locations.contains_key(&order_id) {
+            return None;
+        }
+        let _journaled = match self.begin_journaled(|| JournalCommand::UpdateExtraFields {
+            order_id,
+            extra_fields: extra_fields.clone(),
+        }) {
+            Ok(scope) => scope,
+            Err(e) => {
+                error!("Order book {}: {}", self.symbol, e);
+                return None;
+            }
+        };
+
+        trace!(
+            "Order book {}: Updating extra fields of order {}",
+            self.symbol, order_id
+        );
+        self.order_extra_fields.insert(order_id, extra_fields);
+        let order = self.get_order(order_id);
+        if order.is_none() {
+            // The order left the book while the fields were being replaced
+            self.order_extra_fields.remove(&order_id);
+        }
+        order
+    }
+
+    /// Removes every empty price level left in the book
+    ///
+    /// Cancels and fills already drop the levels they empty, so this only finds
+    /// levels left behind by concurrent activity or by direct manipulation of the
+    /// book. Both visible and hidden levels are compacted.
+    ///
+    /// # Returns
+    /// The number of price levels removed.
+    pub fn compact(&self) -> usize {
+        let mut removed = 0;
+        for side in [Side::Buy, Side::Sell] {
+            for hidden in [false, true] {
+                let price_levels = self.side_levels(side, hidden);
+                let empty_prices: Vec<u64> = price_levels
+                    .iter()
+                    .filter(|entry| entry.value().order_count() == 0)
+                    .map(|entry| *entry.key())
+                    .collect();
+                removed += empty_prices
+                    .into_iter()
+                    .filter(|price| self.prune_empty_level(price_levels, *price))
+                    .count();
+            }
+        }
+
+        if removed > 0 {
+            trace!(
+                "Order book {}: Compacted {} empty price levels",
+                self.symbol, removed
+            );
+        }
+        removed
+    }
+
     /// Add a new order to the book, automatically matching it if it's aggressive.
-    pub fn add_order(&self, mut order: OrderType<T>) -> Result<Arc<OrderType<T>>, OrderBookError> {
-        self.cache.invalidate();
+    ///
+    /// Market-to-limit orders are converted into limit orders at the best opposite
+    /// price before matching (see [`OrderBook::convert_market_to_limit`]).
+    pub fn add_order(&self, order: OrderType<T>) -> Result<Arc<OrderType<T>>, OrderBookError> {
+        self.add_order_with_placement(order, OrderPlacement::default())
+    }
+
+    /// Add a new order to the book, placing any remainder as described by `placement`.
+    pub(super) fn add_order_with_placement(
+        &self,
+        order: OrderType<T>,
+        placement: OrderPlacement,
+    ) -> Result<Arc<OrderType<T>>, OrderBookError> {
+        if !placement.replacing
+            && let Err(error) = self.check_rate_limit(placement.owner)
+        {
+            self.emit_order_event(OrderEvent::Rejected {
+                order_id: order.id(),
+                reason: error.to_string(),
+            });
+            return Err(error);
+        }
+        let _journaled = self.begin_journaled(|| JournalCommand::AddOrder {
+            order: order.clone(),
+            owner: placement.owner,
+            hidden: placement.hidden,
+        })?;
+        let mut order = self.convert_market_to_limit(order);
 
         trace!(
             "Order book {}: Adding order {} at price {}",
@@ -501,51 +692,58 @@ where
         );
 
         if self.has_expired(&order) {
+            self.emit_order_event(OrderEvent::Expired {
+                order_id: order.id(),
+            });
             return Err(OrderBookError::InvalidOperation {
                 message: "Order has already expired".to_string(),
             });
         }
+        self.expire_day_orders_if_closed();
 
-        if order.is_post_only() && self.will_cross_market(order.price(), order.side()) {
-            return Err(OrderBookError::PriceCrossing {
-                price: order.price(),
-                side: order.side(),
-                opposite_price: if order.side() == Side::Buy {
-                    self.best_ask().unwrap_or(0)
-                } else {
-                    self.best_bid().unwrap_or(0)
-                },
+        if self.replace_on_duplicate.load(Ordering::Relaxed)
+            && self.order_locations.contains_key(&order.id())
+        {
+            self.recycle(self.cancel_resting_order(order.id())?);
+        }
+
+        if let Err(error) = self.validate_submission(&order, placement.owner) {
+            self.emit_order_event(OrderEvent::Rejected {
+                order_id: order.id(),
+                reason: error.to_string(),
             });
+            return Err(error);
         }
 
-        // For FOK orders, first check if the entire quantity can be matched without altering the book.
-        if order.is_fill_or_kill() {
-            let potential_match =
-                self.peek_match(order.side(), order.total_quantity(), Some(order.price()));
-            if potential_match < order.total_quantity() {
-                return Err(OrderBookError::InsufficientLiquidity {
-                    side: order.side(),
-                    requested: order.total_quantity(),
-                    available: potential_match,
-                });
-            }
+        if !placement.replacing {
+            self.emit_order_event(OrderEvent::Accepted {
+                order_id: order.id(),
+                side: order.side(),
+                price: order.price(),
+                quantity: order.total_quantity(),
+            });
+            self.surveil_order(
+                placement.owner,
+                order.id(),
+                order.side(),
+                order.price(),
+                placement.hidden,
+            );
         }
 
-        self.cache.invalidate();
-        // Attempt to match the order immediately
-        let match_result = self.match_order(
-            order.id(),
-            order.side(),
-            order.total_quantity(), // Use total quantity for matching
-            Some(order.price()),
-        )?;
+        // Attempt to match the order immediately, unless the session does not match
+        let match_result = if self.session_state().is_matching() {
+            self.match_order(
+                order.id(),
+                order.side(),
+                order.total_quantity(), // Use total quantity for matching
+                Some(order.price()),
+            )?
+        } else {
+            MatchResult::new(order.id(), order.total_quantity())
+        };
 
-        if !match_result.transactions.transactions.is_empty()
-            && let Some(ref listener) = self.trade_listener
-        {
-            let trade_result = TradeResult::new(self.symbol.clone(), match_result.clone());
-            listener(&trade_result) // emit trade events to listener
-        }
+        self.publish_trade(&match_result, placement.owner); // emit trade events to listeners
 
         // If the order was not fully filled, add the remainder to the book
         if match_result.remaining_quantity > 0 {
@@ -553,6 +751,10 @@ where
                 // IOC/FOK orders should not have a resting part.
                 // If FOK, it should have been fully filled or cancelled before this point.
                 // If IOC, this is the remaining part that couldn't be filled, so we just drop it.
+                self.emit_order_event(OrderEvent::Cancelled {
+                    order_id: order.id(),
+                    quantity: match_result.remaining_quantity,
+                });
                 return Err(OrderBookError::InsufficientLiquidity {
                     side: order.side(),
                     requested: order.quantity(), // Now uses the trait method
@@ -571,36 +773,219 @@ where
             let price = order.price();
             let side = order.side();
 
-            let price_levels = match side {
-                Side::Buy => &self.bids,
-                Side::Sell => &self.asks,
-            };
+            if !placement.hidden
+                && let Err(error) = self.admit_level(side, price)
+            {
+                self.emit_order_event(OrderEvent::Cancelled {
+                    order_id: order.id(),
+                    quantity: match_result.remaining_quantity,
+                });
+                return Err(error);
+            }
 
-            let price_level = price_levels.get_or_insert(price, Arc::new(PriceLevel::new(price)));
-            let level = price_level.value();
+            // Books with a dense ladder join a resting visible level through its
+            // slot, leaving the level map alone
+            let resting = match self.ladder {
+                Some(ref ladder) if !placement.hidden => ladder.level(side, price),
+                _ => None,
+            };
+            let level = match resting {
+                Some(level) => level,
+                None => {
+                    let price_levels = self.side_levels(side, placement.hidden);
+                    let mut opened = None;
+                    let level = Arc::clone(
+                        price_levels
+                            .get_or_insert_with(price, || {
+                                let level = self.level_pool.acquire(price);
+                                opened = Some(Arc::clone(&level));
+                                level
+                            })
+                            .value(),
+                    );
+                    if !placement.hidden {
+                        // Only the order whose level made it into the map opened it,
+                        // and it backs off while that level is still empty
+                        let opened = opened.is_some_and(|opened| Arc::ptr_eq(&opened, &level));
+                        if opened
+                            && let Err(error) = self.confirm_opened_level(side, price)
+                            && self.prune_empty_level(price_levels, price)
+                        {
+                            self.emit_order_event(OrderEvent::Cancelled {
+                                order_id: order.id(),
+                                quantity: match_result.remaining_quantity,
+                            });
+                            return Err(error);
+                        }
+                        self.index_level(side, price, &level);
+                    }
+                    level
+                }
+            };
 
-            // Convert to unit type for PriceLevel compatibility
+            // Convert to unit type for PriceLevel compatibility, keeping the extra
+            // fields on the side. Zero-sized extra fields always equal their default.
             let unit_order = self.convert_to_unit_type(&order);
-            let unit_order_arc = price_level.value().add_order(unit_order);
-            // notify price level changes
-            if let Some(ref listener) = self.price_level_changed_listener {
-                listener(PriceLevelChangedEvent {
-                    side,
-                    price: level.price(),
-                    quantity: level.visible_quantity(),
-                })
+            if !placement.hidden {
+                self.totals.added(side, unit_order.total_quantity());
+            }
+            if std::mem::size_of::<T>() > 0 {
+                self.order_extra_fields
+                    .insert(order.id(), order.extra_fields().clone());
+            }
+            let unit_order_arc = level.add_order(unit_order);
+            // notify price level changes; hidden levels are never published
+            if let Some(owner) = placement.owner {
+                self.order_owners.insert(unit_order_arc.id(), owner);
+                self.owner_orders
+                    .entry(owner)
+                    .or_default()
+                    .insert(unit_order_arc.id());
+            }
+
+            if placement.hidden {
+                self.hidden_order_ids.insert(unit_order_arc.id());
+            } else {
+                self.notify_price_level_changed(side, &level);
             }
             self.order_locations
                 .insert(unit_order_arc.id(), (price, side));
+            if !placement.replacing {
+                self.emit_order_event(OrderEvent::Rested {
+                    order_id: unit_order_arc.id(),
+                    side,
+                    price,
+                    quantity: unit_order_arc.total_quantity(),
+                });
+            }
+            if !placement.hidden {
+                self.evict_excess_levels(side);
+            }
 
             // Convert back to generic type for return
             let generic_order = self.convert_from_unit_type(&unit_order_arc);
-            Ok(Arc::new(generic_order))
+            Ok(self.order_pool.acquire(generic_order))
         } else {
             // The order was fully matched, create an Arc from the matched result
             // Note: The original order object is consumed, but we can reconstruct its essence if needed.
             // For now, we return a representation of the completed order.
-            Ok(Arc::new(order))
+            Ok(self.order_pool.acquire(order))
+        }
+    }
+
+    /// Checks the submission rules that can reject an order before it is matched.
+    pub(super) fn validate_submission(
+        &self,
+        order: &OrderType<T>,
+        owner: Option<OwnerId>,
+    ) -> Result<(), OrderBookError> {
+        self.check_trading_enabled(owner)?;
+        self.check_session(order.price(), order.side(), order.is_immediate())?;
+        self.check_price_band(order.id(), order.side(), order.price())?;
+
+        if self.order_locations.contains_key(&order.id()) {
+            return Err(OrderBookError::DuplicateOrderId {
+                order_id: order.id(),
+            });
+        }
+
+        self.config
+            .validate(order.price(), order.total_quantity())?;
+        if order.quantity() != order.total_quantity() {
+            self.config.validate_quantity(order.quantity())?;
+        }
+
+        self.check_risk(&RiskOrder {
+            order_id: order.id(),
+            side: order.side(),
+            price: Some(order.price()),
+            quantity: order.total_quantity(),
+            owner,
+        })?;
+
+        if order.is_post_only() && self.will_cross_market(order.price(), order.side()) {
+            return Err(OrderBookError::PriceCrossing {
+                price: order.price(),
+                side: order.side(),
+                opposite_price: if order.side() == Side::Buy {
+                    self.best_ask().unwrap_or(0)
+                } else {
+                    self.best_bid().unwrap_or(0)
+                },
+            });
         }
+
+        // For FOK orders, first check if the entire quantity can be matched without altering the book.
+        if order.is_fill_or_kill() {
+            let potential_match =
+                self.peek_match(order.side(), order.total_quantity(), Some(order.price()));
+            if potential_match < order.total_quantity() {
+                return Err(OrderBookError::InsufficientLiquidity {
+                    side: order.side(),
+                    requested: order.total_quantity(),
+                    available: potential_match,
+                });
+            }
+        }
+
+        Ok(())
     }
+
+    /// Checks the rules an amended order must pass to re-enter the book before the
+    /// order it replaces, resting at `original`, is taken out, so a rejected amend
+    /// leaves the original order in place.
+    fn check_replacement(
+        &self,
+        order: &OrderType<T>,
+        owner: Option<OwnerId>,
+        hidden: bool,
+        original: (u64, Side),
+    ) -> Result<(), OrderBookError> {
+        self.check_trading_enabled(owner)?;
+        self.check_risk(&RiskOrder {
+            order_id: order.id(),
+            side: order.side(),
+            price: Some(order.price()),
+            quantity: order.total_quantity(),
+            owner,
+        })?;
+        if hidden {
+            return Ok(());
+        }
+
+        // Taking out the last order of its level frees a slot on that side
+        let (original_price, original_side) = original;
+        let vacated = (original_side == order.side())
+            .then_some(original_price)
+            .filter(|price| {
+                self.side_levels(original_side, false)
+                    .get(price)
+                    .is_some_and(|level| level.value().order_count() == 1)
+            });
+        self.admit_level_vacating(order.side(), order.price(), vacated)
+    }
+
+    /// Reports a resting order whose price or quantity was updated.
+    fn notify_modified(&self, order: &OrderType<T>) {
+        if self.order_locations.contains_key(&order.id()) {
+            self.emit_order_event(OrderEvent::Modified {
+                order_id: order.id(),
+                price: order.price(),
+                quantity: order.total_quantity(),
+            });
+        }
+    }
+}
+
+/// How an order submitted through [`OrderBook::add_order_with_placement`] is placed
+/// in the book.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct OrderPlacement {
+    /// Rest any remainder in the hidden price levels
+    pub hidden: bool,
+    /// Owner the resting remainder is attributed to
+    pub owner: Option<OwnerId>,
+    /// The order re-enters the book as part of a modification and is not reported
+    /// as newly accepted or rested
+    pub replacing: bool,
 }
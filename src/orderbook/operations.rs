@@ -2,6 +2,7 @@
 
 use super::book::OrderBook;
 use super::error::OrderBookError;
+use super::modifications::OrderPlacement;
 use pricelevel::{MatchResult, OrderId, OrderType, Side, TimeInForce};
 use std::sync::Arc;
 use tracing::trace;
@@ -26,7 +27,7 @@ where
             price,
             quantity,
             side,
-            timestamp: crate::utils::current_time_millis(),
+            timestamp: self.clock.now_millis(),
             time_in_force,
             extra_fields,
         };
@@ -56,7 +57,7 @@ where
             visible_quantity,
             hidden_quantity,
             side,
-            timestamp: crate::utils::current_time_millis(),
+            timestamp: self.clock.now_millis(),
             time_in_force,
             extra_fields,
         };
@@ -67,6 +68,43 @@ where
         self.add_order(order)
     }
 
+    /// Add a fully hidden order to the book
+    ///
+    /// Hidden orders match like standard limit orders, with visible orders at the
+    /// same price taking priority, but they never appear in snapshots, depth
+    /// queries, level iterators or price level change notifications.
+    pub fn add_hidden_order(
+        &self,
+        id: OrderId,
+        price: u64,
+        quantity: u64,
+        side: Side,
+        time_in_force: TimeInForce,
+        extra_fields: Option<T>,
+    ) -> Result<Arc<OrderType<T>>, OrderBookError> {
+        let extra_fields: T = extra_fields.unwrap_or_default();
+        let order = OrderType::Standard {
+            id,
+            price,
+            quantity,
+            side,
+            timestamp: self.clock.now_millis(),
+            time_in_force,
+            extra_fields,
+        };
+        trace!(
+            "Adding hidden order {} {} {} {} {}",
+            id, price, quantity, side, time_in_force
+        );
+        self.add_order_with_placement(
+            order,
+            OrderPlacement {
+                hidden: true,
+                ..OrderPlacement::default()
+            },
+        )
+    }
+
     /// Add a post-only order to the book
     pub fn add_post_only_order(
         &self,
@@ -83,7 +121,7 @@ where
             price,
             quantity,
             side,
-            timestamp: crate::utils::current_time_millis(),
+            timestamp: self.clock.now_millis(),
             time_in_force,
             extra_fields,
         };
@@ -0,0 +1,110 @@
+//! Order lifecycle events.
+
+use pricelevel::{OrderId, Side};
+use std::sync::Arc;
+
+/// A step in the lifecycle of a single order.
+///
+/// Every order submitted through the book is reported as `Accepted`, `Rejected` or
+/// `Expired`. Accepted orders then report each execution, and finish either fully
+/// `Filled`, `Cancelled`, or `Rested` in the book until a later event.
+/// Quantities are in units and prices in price units.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderEvent {
+    /// The order passed validation and entered the matching engine
+    Accepted {
+        /// The order's identifier
+        order_id: OrderId,
+        /// Side of the order
+        side: Side,
+        /// Limit price of the order
+        price: u64,
+        /// Total quantity submitted
+        quantity: u64,
+    },
+
+    /// The order, or its unfilled remainder, is now resting in the book
+    Rested {
+        /// The order's identifier
+        order_id: OrderId,
+        /// Side of the order
+        side: Side,
+        /// Price level the order rests at
+        price: u64,
+        /// Quantity left resting
+        quantity: u64,
+    },
+
+    /// Part of the order was executed and some quantity remains
+    PartiallyFilled {
+        /// The order's identifier
+        order_id: OrderId,
+        /// Execution price
+        price: u64,
+        /// Quantity executed in this fill
+        quantity: u64,
+    },
+
+    /// The execution that completed the order
+    Filled {
+        /// The order's identifier
+        order_id: OrderId,
+        /// Execution price
+        price: u64,
+        /// Quantity executed in this fill
+        quantity: u64,
+    },
+
+    /// A resting order had its price or quantity updated
+    Modified {
+        /// The order's identifier
+        order_id: OrderId,
+        /// Price after the update
+        price: u64,
+        /// Quantity after the update
+        quantity: u64,
+    },
+
+    /// The unfilled quantity of the order was removed from the book
+    Cancelled {
+        /// The order's identifier
+        order_id: OrderId,
+        /// Quantity that was still open when the order was cancelled
+        quantity: u64,
+    },
+
+    /// The order's time in force elapsed, either before it was submitted or while
+    /// it was resting in the book
+    Expired {
+        /// The order's identifier
+        order_id: OrderId,
+    },
+
+    /// The order was refused at submission and never entered the book
+    Rejected {
+        /// The order's identifier
+        order_id: OrderId,
+        /// Human readable reason for the rejection
+        reason: String,
+    },
+}
+
+impl OrderEvent {
+    /// Returns the ID of the order this event refers to
+    #[must_use]
+    pub fn order_id(&self) -> OrderId {
+        match self {
+            OrderEvent::Accepted { order_id, .. }
+            | OrderEvent::Rested { order_id, .. }
+            | OrderEvent::PartiallyFilled { order_id, .. }
+            | OrderEvent::Filled { order_id, .. }
+            | OrderEvent::Modified { order_id, .. }
+            | OrderEvent::Cancelled { order_id, .. }
+            | OrderEvent::Expired { order_id }
+            | OrderEvent::Rejected { order_id, .. } => *order_id,
+        }
+    }
+}
+
+/// A thread-safe listener callback for order lifecycle events.
+pub type OrderEventListener = Arc<dyn Fn(&OrderEvent) + Send + Sync>;
@@ -0,0 +1,209 @@
+//! Order flow imbalance (OFI) computed incrementally from best bid and offer changes.
+
+use super::book::OrderBook;
+use super::book_change_event::BboChangedEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Contribution of one best bid and offer change to the order flow imbalance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfiSample {
+    /// Time of the change, in milliseconds of the book clock
+    pub timestamp: u64,
+    /// Signed contribution: positive for buying pressure, negative for selling pressure
+    pub value: i64,
+}
+
+/// Rolling order flow imbalance over a time window.
+///
+/// Each change of the best bid or offer contributes, following Cont, Kukanov
+/// and Stoikov, the quantity added to the bid minus the quantity removed from
+/// it, minus the same for the ask:
+///
+/// - a bid at a higher or equal price adds its quantity, a bid at a lower or
+///   equal price removes the previous quantity;
+/// - an ask at a lower or equal price adds its quantity, an ask at a higher or
+///   equal price removes the previous quantity.
+///
+/// An empty side is treated as a bid at minus infinity or an ask at plus infinity.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::OrderFlowImbalance;
+/// use orderbook_rs::orderbook::book_change_event::BboChangedEvent;
+///
+/// let bbo = |bid_quantity, ask_quantity| BboChangedEvent {
+///     bid_price: Some(100),
+///     bid_quantity,
+///     ask_price: Some(101),
+///     ask_quantity,
+/// };
+/// let mut ofi = OrderFlowImbalance::new(1_000, bbo(10, 10));
+/// ofi.observe(100, bbo(15, 10));
+/// ofi.observe(200, bbo(15, 4));
+/// assert_eq!(ofi.value(200), 11);
+/// assert_eq!(ofi.value(1_150), 6);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderFlowImbalance {
+    window_ms: u64,
+    previous: BboChangedEvent,
+    samples: VecDeque<OfiSample>,
+    cumulative: i64,
+}
+
+impl OrderFlowImbalance {
+    /// Create a calculator over the last `window_ms` milliseconds, starting from `initial`
+    #[must_use]
+    pub fn new(window_ms: u64, initial: BboChangedEvent) -> Self {
+        Self {
+            window_ms,
+            previous: initial,
+            samples: VecDeque::new(),
+            cumulative: 0,
+        }
+    }
+
+    /// Length of the rolling window, in milliseconds
+    #[must_use]
+    pub fn window_ms(&self) -> u64 {
+        self.window_ms
+    }
+
+    /// Contribution of a change from `previous` to `current`
+    #[must_use]
+    pub fn contribution(previous: &BboChangedEvent, current: &BboChangedEvent) -> i64 {
+        let quantity = |quantity: u64| i64::try_from(quantity).unwrap_or(i64::MAX);
+        let mut value = 0;
+
+        // A missing bid sorts below every price
+        let (bid, previous_bid) = (current.bid_price, previous.bid_price);
+        if bid.is_some() && bid >= previous_bid {
+            value += quantity(current.bid_quantity);
+        }
+        if previous_bid.is_some() && bid <= previous_bid {
+            value -= quantity(previous.bid_quantity);
+        }
+
+        // A missing ask sorts above every price
+        let above = |price: Option<u64>| price.map_or(u128::MAX, u128::from);
+        let (ask, previous_ask) = (above(current.ask_price), above(previous.ask_price));
+        if current.ask_price.is_some() && ask <= previous_ask {
+            value -= quantity(current.ask_quantity);
+        }
+        if previous.ask_price.is_some() && ask >= previous_ask {
+            value += quantity(previous.ask_quantity);
+        }
+        value
+    }
+
+    /// Records a change of the best bid and offer at `timestamp`, returning its contribution
+    pub fn observe(&mut self, timestamp: u64, current: BboChangedEvent) -> i64 {
+        let value = Self::contribution(&self.previous, &current);
+        self.previous = current;
+        if value != 0 {
+            self.cumulative = self.cumulative.saturating_add(value);
+            self.samples.push_back(OfiSample { timestamp, value });
+        }
+        self.prune(timestamp);
+        value
+    }
+
+    /// Order flow imbalance over the window ending at `now`
+    #[must_use]
+    pub fn value(&self, now: u64) -> i64 {
+        let since = now.saturating_sub(self.window_ms);
+        self.samples
+            .iter()
+            .rev()
+            .take_while(|sample| sample.timestamp > since)
+            .map(|sample| sample.value)
+            .sum()
+    }
+
+    /// Order flow imbalance accumulated since the calculator was created
+    #[must_use]
+    pub fn cumulative(&self) -> i64 {
+        self.cumulative
+    }
+
+    /// Non-zero contributions in the window ending at `now`, oldest first
+    #[must_use]
+    pub fn samples(&self, now: u64) -> Vec<OfiSample> {
+        let since = now.saturating_sub(self.window_ms);
+        self.samples
+            .iter()
+            .filter(|sample| sample.timestamp > since)
+            .copied()
+            .collect()
+    }
+
+    fn prune(&mut self, now: u64) {
+        let since = now.saturating_sub(self.window_ms);
+        while self
+            .samples
+            .front()
+            .is_some_and(|sample| sample.timestamp <= since)
+        {
+            self.samples.pop_front();
+        }
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Computes the order flow imbalance of the book over the last `window_ms`
+    /// milliseconds of the book clock, starting from the current best bid and offer
+    ///
+    /// Replaces any calculator enabled before.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 101, 10, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    /// book.enable_order_flow_imbalance(60_000);
+    ///
+    /// // Bids joining the best bid are buying pressure
+    /// book.add_limit_order(OrderId::new(), 100, 5, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// assert_eq!(book.order_flow_imbalance(), Some(5));
+    /// ```
+    pub fn enable_order_flow_imbalance(&mut self, window_ms: u64) {
+        let current = self.current_bbo();
+        *self.last_bbo.get_mut().unwrap_or_else(|e| e.into_inner()) = current;
+        self.order_flow_imbalance = Some(Mutex::new(OrderFlowImbalance::new(window_ms, current)));
+    }
+
+    /// Stop computing the order flow imbalance
+    pub fn disable_order_flow_imbalance(&mut self) {
+        self.order_flow_imbalance = None;
+    }
+
+    /// Order flow imbalance over the window ending at the current time of the
+    /// book clock, if enabled
+    #[must_use]
+    pub fn order_flow_imbalance(&self) -> Option<i64> {
+        self.order_flow_imbalance.as_ref().map(|ofi| {
+            ofi.lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .value(self.clock.now_millis())
+        })
+    }
+
+    /// Non-zero order flow imbalance contributions in the window ending at the
+    /// current time of the book clock, if enabled
+    #[must_use]
+    pub fn order_flow_imbalance_series(&self) -> Option<Vec<OfiSample>> {
+        self.order_flow_imbalance.as_ref().map(|ofi| {
+            ofi.lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .samples(self.clock.now_millis())
+        })
+    }
+}
@@ -0,0 +1,131 @@
+//! Order ownership tracking.
+
+use super::book::OrderBook;
+use super::error::OrderBookError;
+use super::modifications::{OrderPlacement, OrderQuantity};
+use pricelevel::{OrderId, OrderType, Side};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// Identifier of the participant (account, trader or session) that owns an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct OwnerId(pub u64);
+
+impl From<u64> for OwnerId {
+    fn from(value: u64) -> Self {
+        OwnerId(value)
+    }
+}
+
+impl fmt::Display for OwnerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Add a new order to the book on behalf of `owner`
+    ///
+    /// The order is processed exactly like [`OrderBook::add_order`]. If any quantity
+    /// is left resting, it stays attributed to `owner` until it leaves the book,
+    /// including across price and quantity updates.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{OrderBook, OwnerId};
+    /// use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let order = OrderType::Standard {
+    ///     id: OrderId::new(),
+    ///     price: 100,
+    ///     quantity: 10,
+    ///     side: Side::Buy,
+    ///     timestamp: 0,
+    ///     time_in_force: TimeInForce::Gtc,
+    ///     extra_fields: (),
+    /// };
+    /// let resting = book.add_order_with_owner(order, OwnerId(7)).unwrap();
+    /// assert_eq!(book.order_owner(resting.id()), Some(OwnerId(7)));
+    /// ```
+    pub fn add_order_with_owner(
+        &self,
+        order: OrderType<T>,
+        owner: OwnerId,
+    ) -> Result<Arc<OrderType<T>>, OrderBookError> {
+        self.add_order_with_placement(
+            order,
+            OrderPlacement {
+                owner: Some(owner),
+                ..OrderPlacement::default()
+            },
+        )
+    }
+
+    /// Returns the owner of a resting order, if it was submitted with one
+    #[must_use]
+    pub fn order_owner(&self, order_id: OrderId) -> Option<OwnerId> {
+        self.order_owners.get(&order_id).map(|owner| *owner)
+    }
+
+    /// Returns every resting order submitted on behalf of `owner`, including hidden orders
+    ///
+    /// The lookup goes through a per-owner index, so its cost depends on the number
+    /// of orders the owner has resting rather than on the size of the book.
+    #[must_use]
+    pub fn get_orders_by_owner(&self, owner: OwnerId) -> Vec<Arc<OrderType<T>>> {
+        self.owned_order_ids(owner)
+            .into_iter()
+            .filter_map(|order_id| self.get_order(order_id))
+            .collect()
+    }
+
+    /// Returns the total open quantity `owner` has resting on `side`
+    ///
+    /// Both the visible and the hidden quantity of each order are counted.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{OrderBook, OwnerId};
+    /// use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let order = OrderType::Standard {
+    ///     id: OrderId::new(),
+    ///     price: 100,
+    ///     quantity: 10,
+    ///     side: Side::Buy,
+    ///     timestamp: 0,
+    ///     time_in_force: TimeInForce::Gtc,
+    ///     extra_fields: (),
+    /// };
+    /// book.add_order_with_owner(order, OwnerId(7)).unwrap();
+    /// assert_eq!(book.open_quantity_by_owner(OwnerId(7), Side::Buy), 10);
+    /// assert_eq!(book.open_quantity_by_owner(OwnerId(7), Side::Sell), 0);
+    /// ```
+    #[must_use]
+    pub fn open_quantity_by_owner(&self, owner: OwnerId, side: Side) -> u64 {
+        self.owned_order_ids(owner)
+            .into_iter()
+            .filter(|order_id| {
+                self.order_locations
+                    .get(order_id)
+                    .is_some_and(|location| location.1 == side)
+            })
+            .filter_map(|order_id| self.get_order(order_id))
+            .map(|order| order.total_quantity())
+            .sum()
+    }
+
+    /// Returns the IDs of the resting orders submitted on behalf of `owner`.
+    pub(super) fn owned_order_ids(&self, owner: OwnerId) -> Vec<OrderId> {
+        self.owner_orders
+            .get(&owner)
+            .map(|ids| ids.iter().map(|id| *id).collect())
+            .unwrap_or_default()
+    }
+}
@@ -1,5 +1,18 @@
-use pricelevel::OrderId;
+//! Object pools reusing vectors, price levels and order handles in hot paths.
+//!
+//! Resting orders themselves are allocated by the `pricelevel` crate when
+//! added to a level; the pools here cover what the book allocates around them,
+//! including the handle of the order returned by every add, cancel and
+//! amendment.
+
+use super::book::OrderBook;
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use pricelevel::{OrderId, OrderType, PriceLevel};
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
 /// A memory pool for reusing vectors to reduce allocations in hot paths.
 #[derive(Debug)]
@@ -51,3 +64,502 @@ impl Default for MatchingPool {
         Self::new()
     }
 }
+
+/// Default number of emptied price levels an order book keeps for reuse.
+pub const DEFAULT_LEVEL_POOL_CAPACITY: usize = 256;
+
+/// Default number of returned order handles an order book tracks for reuse.
+pub const DEFAULT_ORDER_POOL_CAPACITY: usize = 1024;
+
+/// Allocation metrics of a [`LevelPool`] or an [`OrderPool`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolStats {
+    /// Items allocated because none could be reused.
+    pub allocated: u64,
+    /// Items taken back from the pool instead of being allocated.
+    pub reused: u64,
+    /// Items handed to the pool.
+    pub released: u64,
+    /// Items currently held by the pool.
+    pub pooled: usize,
+    /// Largest number of items held by the pool at once.
+    pub high_water_mark: usize,
+    /// Maximum number of items the pool holds.
+    pub capacity: usize,
+}
+
+impl PoolStats {
+    /// Share of the items needed that were reused, `None` before any was
+    /// needed.
+    #[must_use]
+    pub fn reuse_ratio(&self) -> Option<f64> {
+        let acquired = self.allocated + self.reused;
+        (acquired > 0).then(|| self.reused as f64 / acquired as f64)
+    }
+}
+
+/// A pool of emptied price levels, handed out again when an order rests at
+/// the same price.
+///
+/// Levels are parked per price, at most one per price and `capacity` in
+/// total. A parked level is only reused once nothing else references it,
+/// so a level still held by a reader or by the book's lock-free maps is
+/// never shared; it is dropped instead and a fresh level is allocated.
+#[derive(Debug)]
+pub struct LevelPool {
+    capacity: usize,
+    levels: DashMap<u64, Arc<PriceLevel>>,
+    allocated: AtomicU64,
+    reused: AtomicU64,
+    released: AtomicU64,
+    high_water_mark: AtomicUsize,
+}
+
+impl LevelPool {
+    /// Creates an empty pool parking at most `capacity` levels.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            levels: DashMap::new(),
+            allocated: AtomicU64::new(0),
+            reused: AtomicU64::new(0),
+            released: AtomicU64::new(0),
+            high_water_mark: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns an empty price level at `price`, reusing a parked one when
+    /// possible.
+    pub fn acquire(&self, price: u64) -> Arc<PriceLevel> {
+        if let Some((_, level)) = self.levels.remove(&price)
+            && Arc::strong_count(&level) == 1
+            && level.order_count() == 0
+        {
+            level.stats().reset();
+            self.reused.fetch_add(1, Ordering::Relaxed);
+            return level;
+        }
+        self.allocated.fetch_add(1, Ordering::Relaxed);
+        Arc::new(PriceLevel::new(price))
+    }
+
+    /// Parks an emptied price level for reuse.
+    ///
+    /// # Returns
+    /// `false`, dropping the level, if it still holds orders, the pool is
+    /// full, or a level at the same price is already parked
+    pub fn release(&self, level: Arc<PriceLevel>) -> bool {
+        if level.order_count() > 0 || self.levels.len() >= self.capacity {
+            return false;
+        }
+        match self.levels.entry(level.price()) {
+            Entry::Occupied(_) => return false,
+            Entry::Vacant(entry) => {
+                entry.insert(level);
+            }
+        }
+        self.released.fetch_add(1, Ordering::Relaxed);
+        self.high_water_mark
+            .fetch_max(self.levels.len(), Ordering::Relaxed);
+        true
+    }
+
+    /// Number of price levels currently parked.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Whether no price level is parked.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    /// Number of price levels the map of parked levels holds without
+    /// growing.
+    pub(super) fn map_capacity(&self) -> usize {
+        self.levels.capacity()
+    }
+
+    /// Drops every parked price level, keeping the metrics.
+    pub fn clear(&self) {
+        self.levels.clear();
+    }
+
+    /// Allocation metrics of the pool.
+    #[must_use]
+    pub fn stats(&self) -> PoolStats {
+        PoolStats {
+            allocated: self.allocated.load(Ordering::Relaxed),
+            reused: self.reused.load(Ordering::Relaxed),
+            released: self.released.load(Ordering::Relaxed),
+            pooled: self.levels.len(),
+            high_water_mark: self.high_water_mark.load(Ordering::Relaxed),
+            capacity: self.capacity,
+        }
+    }
+}
+
+impl Default for LevelPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_LEVEL_POOL_CAPACITY)
+    }
+}
+
+/// A pool recycling the handles of the orders an order book returns.
+///
+/// Every add, cancel and amendment hands back an `Arc<OrderType<T>>`. Handles
+/// released to the pool, by the book when it drops one itself or by a caller
+/// done with one, are parked if nothing else references them, and the next
+/// order is written into a parked handle instead of allocating. A parked
+/// handle keeps its last order, extra fields included, until it is reused or
+/// the pool is cleared.
+#[derive(Debug)]
+pub struct OrderPool<T> {
+    capacity: usize,
+    handles: Mutex<Vec<Arc<OrderType<T>>>>,
+    allocated: AtomicU64,
+    reused: AtomicU64,
+    released: AtomicU64,
+    high_water_mark: AtomicUsize,
+}
+
+impl<T> OrderPool<T> {
+    /// Creates an empty pool parking at most `capacity` handles.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            handles: Mutex::new(Vec::new()),
+            allocated: AtomicU64::new(0),
+            reused: AtomicU64::new(0),
+            released: AtomicU64::new(0),
+            high_water_mark: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns a handle holding `order`, reusing a parked one when possible.
+    ///
+    /// Never waits: while another thread uses the pool, a new handle is
+    /// allocated.
+    pub fn acquire(&self, order: OrderType<T>) -> Arc<OrderType<T>> {
+        let parked = self
+            .handles
+            .try_lock()
+            .ok()
+            .and_then(|mut handles| handles.pop());
+        if let Some(mut handle) = parked
+            && let Some(slot) = Arc::get_mut(&mut handle)
+        {
+            *slot = order;
+            self.reused.fetch_add(1, Ordering::Relaxed);
+            return handle;
+        }
+        self.allocated.fetch_add(1, Ordering::Relaxed);
+        Arc::new(order)
+    }
+
+    /// Parks a handle for reuse.
+    ///
+    /// # Returns
+    /// `false`, dropping the handle, if anything else still references it,
+    /// the pool is full or another thread is using it.
+    pub fn release(&self, mut handle: Arc<OrderType<T>>) -> bool {
+        if Arc::get_mut(&mut handle).is_none() {
+            return false;
+        }
+        let Ok(mut handles) = self.handles.try_lock() else {
+            return false;
+        };
+        if handles.len() >= self.capacity {
+            return false;
+        }
+        handles.push(handle);
+        self.released.fetch_add(1, Ordering::Relaxed);
+        self.high_water_mark
+            .fetch_max(handles.len(), Ordering::Relaxed);
+        true
+    }
+
+    /// Number of handles currently parked.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.handles.lock().map_or(0, |handles| handles.len())
+    }
+
+    /// Whether no handle is parked.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of handles the vector of parked handles holds without growing.
+    pub(super) fn queue_capacity(&self) -> usize {
+        self.handles.lock().map_or(0, |handles| handles.capacity())
+    }
+
+    /// Drops every parked handle, keeping the metrics.
+    pub fn clear(&self) {
+        if let Ok(mut handles) = self.handles.lock() {
+            handles.clear();
+        }
+    }
+
+    /// Allocation metrics of the pool.
+    #[must_use]
+    pub fn stats(&self) -> PoolStats {
+        PoolStats {
+            allocated: self.allocated.load(Ordering::Relaxed),
+            reused: self.reused.load(Ordering::Relaxed),
+            released: self.released.load(Ordering::Relaxed),
+            pooled: self.len(),
+            high_water_mark: self.high_water_mark.load(Ordering::Relaxed),
+            capacity: self.capacity,
+        }
+    }
+}
+
+impl<T> Default for OrderPool<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_ORDER_POOL_CAPACITY)
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Sets how many emptied price levels the book keeps for reuse, zero to
+    /// disable reuse
+    ///
+    /// Replaces the pool, dropping its parked levels and resetting its
+    /// metrics.
+    pub fn set_level_pool_capacity(&mut self, capacity: usize) {
+        self.level_pool = LevelPool::new(capacity);
+    }
+
+    /// Allocation metrics of the price levels of the book
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// for _ in 0..3 {
+    ///     book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// }
+    ///
+    /// // Orders resting at an existing level allocate no new level
+    /// assert_eq!(book.level_pool_stats().allocated, 1);
+    /// ```
+    #[must_use]
+    pub fn level_pool_stats(&self) -> PoolStats {
+        self.level_pool.stats()
+    }
+
+    /// Sets how many order handles the book parks for reuse, zero to allocate
+    /// a new handle for every returned order
+    ///
+    /// Replaces the pool, dropping its parked handles and resetting its
+    /// metrics.
+    pub fn set_order_pool_capacity(&mut self, capacity: usize) {
+        self.order_pool = OrderPool::new(capacity);
+    }
+
+    /// Hands back an order handle returned by the book once the caller is
+    /// done with it, so a later add, cancel or amendment can reuse it
+    ///
+    /// # Returns
+    /// `false` if the handle was dropped instead, because it is still
+    /// referenced elsewhere or the pool is full.
+    pub fn recycle_order_handle(&self, order: Arc<OrderType<T>>) -> bool {
+        self.order_pool.release(order)
+    }
+
+    /// Allocation metrics of the order handles returned by adds, cancels
+    /// and amendments
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// for _ in 0..3 {
+    ///     let id = OrderId::new();
+    ///     let added = book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    ///     book.recycle_order_handle(added);
+    ///     let cancelled = book.cancel_order(id).unwrap().unwrap();
+    ///     book.recycle_order_handle(cancelled);
+    /// }
+    ///
+    /// // Recycled handles are written over instead of allocating new ones
+    /// let stats = book.order_pool_stats();
+    /// assert_eq!((stats.allocated, stats.reused, stats.released), (1, 5, 6));
+    /// ```
+    #[must_use]
+    pub fn order_pool_stats(&self) -> PoolStats {
+        self.order_pool.stats()
+    }
+
+    /// Parks the handle of an order the book took out and drops itself,
+    /// returning whether there was one.
+    pub(super) fn recycle(&self, order: Option<Arc<OrderType<T>>>) -> bool {
+        order.map(|order| self.order_pool.release(order)).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pricelevel::{OrderType, Side, TimeInForce};
+
+    fn order(price: u64) -> OrderType<()> {
+        OrderType::Standard {
+            id: OrderId::new(),
+            price,
+            quantity: 10,
+            side: Side::Buy,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        }
+    }
+
+    #[test]
+    fn test_level_pool_reuses_released_levels() {
+        let pool = LevelPool::new(2);
+        let level = pool.acquire(100);
+        assert!(pool.release(level));
+        assert_eq!(pool.len(), 1);
+
+        let reused = pool.acquire(100);
+        assert_eq!(reused.price(), 100);
+        assert!(pool.is_empty());
+        // No parked level at another price
+        let _ = pool.acquire(101);
+
+        let stats = pool.stats();
+        assert_eq!(stats.allocated, 2);
+        assert_eq!(stats.reused, 1);
+        assert_eq!(stats.released, 1);
+        assert_eq!(stats.high_water_mark, 1);
+        assert_eq!(stats.reuse_ratio(), Some(1.0 / 3.0));
+    }
+
+    #[test]
+    fn test_level_pool_rejects_busy_levels() {
+        let pool = LevelPool::new(2);
+
+        let busy = pool.acquire(100);
+        busy.add_order(order(100));
+        assert!(!pool.release(busy));
+
+        // A level still referenced elsewhere is parked but never handed out
+        let shared = pool.acquire(101);
+        let reader = Arc::clone(&shared);
+        assert!(pool.release(shared));
+        let fresh = pool.acquire(101);
+        assert!(!Arc::ptr_eq(&fresh, &reader));
+        assert_eq!(pool.stats().reused, 0);
+
+        assert!(pool.release(pool.acquire(102)));
+        assert!(!pool.release(Arc::new(PriceLevel::new(102))));
+        assert!(pool.release(pool.acquire(103)));
+        assert!(!pool.release(pool.acquire(104)));
+        assert_eq!(pool.stats().high_water_mark, 2);
+
+        pool.clear();
+        assert!(pool.is_empty());
+        assert!(!LevelPool::new(0).release(Arc::new(PriceLevel::new(1))));
+    }
+
+    #[test]
+    fn test_order_pool_reuses_released_handles() {
+        let pool = OrderPool::new(2);
+        let first = pool.acquire(order(100));
+        let address = Arc::as_ptr(&first);
+        assert!(pool.release(first));
+
+        let second = pool.acquire(order(101));
+        assert_eq!(Arc::as_ptr(&second), address);
+        assert_eq!(second.price(), 101);
+
+        // A handle still referenced elsewhere is not parked
+        let shared = Arc::clone(&second);
+        assert!(!pool.release(second));
+        assert_eq!(shared.price(), 101);
+        assert!(Arc::try_unwrap(shared).is_ok());
+
+        assert!(pool.release(pool.acquire(order(102))));
+        assert!(pool.release(Arc::new(order(103))));
+        assert!(!pool.release(Arc::new(order(104))));
+        assert_eq!(pool.len(), 2);
+
+        let stats = pool.stats();
+        assert_eq!(stats.allocated, 2);
+        assert_eq!(stats.reused, 1);
+        assert_eq!(stats.released, 3);
+        assert_eq!(stats.high_water_mark, 2);
+        assert_eq!(stats.capacity, 2);
+
+        pool.clear();
+        assert!(pool.is_empty());
+        assert!(!OrderPool::new(0).release(Arc::new(order(100))));
+    }
+
+    #[test]
+    fn test_book_reuses_order_handles() {
+        let book = OrderBook::<()>::new("TEST");
+        for _ in 0..10 {
+            let id = OrderId::new();
+            book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+                .unwrap();
+            book.cancel_order(id).unwrap();
+        }
+        // Handles dropped by the caller are not tracked
+        assert_eq!(book.order_pool_stats().allocated, 20);
+        assert_eq!(book.order_pool_stats().reused, 0);
+
+        // The book recycles the handles it drops itself, as amendments do
+        let id = OrderId::new();
+        let added = book
+            .add_limit_order(id, 99, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        assert!(Arc::try_unwrap(added).is_ok());
+        for price in [98, 97] {
+            book.update_order(pricelevel::OrderUpdate::UpdatePrice {
+                order_id: id,
+                new_price: price,
+            })
+            .unwrap();
+        }
+        let stats = book.order_pool_stats();
+        assert_eq!(stats.released, 2);
+        assert_eq!(stats.reused, 2);
+        assert_eq!(book.get_order(id).unwrap().price(), 97);
+    }
+
+    #[test]
+    fn test_book_parks_levels_dropped_in_bulk() {
+        let book = OrderBook::<()>::new("TEST");
+        for price in [99, 100, 101] {
+            book.add_limit_order(OrderId::new(), price, 10, Side::Buy, TimeInForce::Gtc, None)
+                .unwrap();
+        }
+        book.cancel_all().unwrap();
+        assert_eq!(book.level_pool_stats().released, 3);
+        assert_eq!(book.level_pool_stats().pooled, 3);
+
+        // Mirrored levels come from and go back to the pool too
+        book.apply_l2_update(200, 5, Side::Sell).unwrap();
+        book.apply_l2_update(200, 0, Side::Sell).unwrap();
+        let stats = book.level_pool_stats();
+        assert_eq!(stats.allocated, 4);
+        assert_eq!(stats.released, 4);
+        assert_eq!(book.best_ask(), None);
+    }
+}
@@ -0,0 +1,234 @@
+//! Per-owner positions, average prices and realized PnL built from executed trades.
+
+use super::owner::OwnerId;
+use super::trade::TradeResult;
+use dashmap::DashMap;
+use pricelevel::Side;
+use serde::{Deserialize, Serialize};
+
+/// Position of one owner in one symbol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    /// Owner holding the position
+    pub owner: OwnerId,
+    /// Symbol of the position
+    pub symbol: String,
+    /// Net quantity: positive when long, negative when short
+    pub net_quantity: i64,
+    /// Average entry price of the open quantity, or 0 when flat
+    pub average_price: f64,
+    /// PnL realized by closing quantity, in price units times quantity
+    pub realized_pnl: f64,
+    /// Total quantity bought
+    pub bought_quantity: u64,
+    /// Total quantity sold
+    pub sold_quantity: u64,
+    /// Number of fills applied to the position
+    pub fill_count: u64,
+}
+
+impl Position {
+    /// Create a flat position
+    pub fn new(owner: OwnerId, symbol: &str) -> Self {
+        Self {
+            owner,
+            symbol: symbol.to_string(),
+            net_quantity: 0,
+            average_price: 0.0,
+            realized_pnl: 0.0,
+            bought_quantity: 0,
+            sold_quantity: 0,
+            fill_count: 0,
+        }
+    }
+
+    /// Whether the position has no open quantity
+    pub fn is_flat(&self) -> bool {
+        self.net_quantity == 0
+    }
+
+    /// Value of the open quantity at its average price, regardless of direction
+    pub fn exposure(&self) -> f64 {
+        self.net_quantity.unsigned_abs() as f64 * self.average_price
+    }
+
+    /// PnL of the open quantity if it were closed at `mark_price`
+    pub fn unrealized_pnl(&self, mark_price: u64) -> f64 {
+        self.net_quantity as f64 * (mark_price as f64 - self.average_price)
+    }
+
+    /// Applies a fill of `quantity` at `price` on `side`
+    ///
+    /// Fills in the direction of the position move the average price; fills
+    /// against it realize PnL on the closed quantity, and any excess opens a new
+    /// position at `price`.
+    pub fn apply_fill(&mut self, side: Side, price: u64, quantity: u64) {
+        if quantity == 0 {
+            return;
+        }
+        let price = price as f64;
+        let signed = match side {
+            Side::Buy => {
+                self.bought_quantity += quantity;
+                quantity as i64
+            }
+            Side::Sell => {
+                self.sold_quantity += quantity;
+                -(quantity as i64)
+            }
+        };
+        self.fill_count += 1;
+
+        let open = self.net_quantity;
+        if open == 0 || open.signum() == signed.signum() {
+            let open_abs = open.unsigned_abs() as f64;
+            self.average_price = (open_abs * self.average_price + quantity as f64 * price)
+                / (open_abs + quantity as f64);
+            self.net_quantity += signed;
+            return;
+        }
+
+        let closed = open.unsigned_abs().min(quantity);
+        self.realized_pnl += closed as f64 * (price - self.average_price) * open.signum() as f64;
+        self.net_quantity += signed;
+        if self.net_quantity == 0 {
+            self.average_price = 0.0;
+        } else if self.net_quantity.signum() != open.signum() {
+            self.average_price = price;
+        }
+    }
+}
+
+/// Positions of every owner, fed by the trades of one or more books.
+///
+/// Each transaction is applied to the owner of the incoming order on the taker
+/// side and to the owner of the resting order on the opposite side. Orders
+/// submitted without an owner are not tracked.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::{OrderBook, OwnerId, PositionTracker};
+/// use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+/// use std::sync::Arc;
+///
+/// let tracker = Arc::new(PositionTracker::new());
+/// let book = OrderBook::<()>::new("BTC/USD");
+/// let sink = Arc::clone(&tracker);
+/// book.subscribe_trade_listener(Arc::new(move |trade| sink.record_trade(trade)));
+///
+/// let order = |side, price| OrderType::Standard {
+///     id: OrderId::new(),
+///     price,
+///     quantity: 10,
+///     side,
+///     timestamp: 0,
+///     time_in_force: TimeInForce::Gtc,
+///     extra_fields: (),
+/// };
+/// book.add_order_with_owner(order(Side::Sell, 100), OwnerId(1)).unwrap();
+/// book.add_order_with_owner(order(Side::Buy, 100), OwnerId(2)).unwrap();
+///
+/// assert_eq!(tracker.position(OwnerId(1), "BTC/USD").unwrap().net_quantity, -10);
+/// assert_eq!(tracker.position(OwnerId(2), "BTC/USD").unwrap().net_quantity, 10);
+/// ```
+#[derive(Debug, Default)]
+pub struct PositionTracker {
+    positions: DashMap<(OwnerId, String), Position>,
+}
+
+impl PositionTracker {
+    /// Create a tracker without positions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies every transaction of `trade` to the positions of its owners
+    pub fn record_trade(&self, trade: &TradeResult) {
+        if trade.taker_owner.is_none() && trade.maker_owners.is_empty() {
+            return;
+        }
+        for transaction in trade.match_result.transactions.as_vec() {
+            if let Some(owner) = trade.taker_owner {
+                self.record_fill(
+                    owner,
+                    &trade.symbol,
+                    transaction.taker_side,
+                    transaction.price,
+                    transaction.quantity,
+                );
+            }
+            if let Some(owner) = trade.maker_owner(transaction.maker_order_id) {
+                self.record_fill(
+                    owner,
+                    &trade.symbol,
+                    transaction.taker_side.opposite(),
+                    transaction.price,
+                    transaction.quantity,
+                );
+            }
+        }
+    }
+
+    /// Applies a single fill to the position of `owner` in `symbol`
+    pub fn record_fill(&self, owner: OwnerId, symbol: &str, side: Side, price: u64, quantity: u64) {
+        self.positions
+            .entry((owner, symbol.to_string()))
+            .or_insert_with(|| Position::new(owner, symbol))
+            .apply_fill(side, price, quantity);
+    }
+
+    /// Position of `owner` in `symbol`, if the owner has traded it
+    #[must_use]
+    pub fn position(&self, owner: OwnerId, symbol: &str) -> Option<Position> {
+        self.positions
+            .get(&(owner, symbol.to_string()))
+            .map(|position| position.clone())
+    }
+
+    /// Positions of `owner` in every symbol it has traded
+    #[must_use]
+    pub fn positions_of(&self, owner: OwnerId) -> Vec<Position> {
+        let mut positions: Vec<Position> = self
+            .positions
+            .iter()
+            .filter(|entry| entry.key().0 == owner)
+            .map(|entry| entry.value().clone())
+            .collect();
+        positions.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        positions
+    }
+
+    /// Every tracked position
+    #[must_use]
+    pub fn all_positions(&self) -> Vec<Position> {
+        self.positions
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect()
+    }
+
+    /// Sum of the exposures of `owner` across every symbol
+    #[must_use]
+    pub fn gross_exposure(&self, owner: OwnerId) -> f64 {
+        self.positions
+            .iter()
+            .filter(|entry| entry.key().0 == owner)
+            .map(|entry| entry.value().exposure())
+            .sum()
+    }
+
+    /// Sum of the PnL realized by `owner` across every symbol
+    #[must_use]
+    pub fn realized_pnl(&self, owner: OwnerId) -> f64 {
+        self.positions
+            .iter()
+            .filter(|entry| entry.key().0 == owner)
+            .map(|entry| entry.value().realized_pnl)
+            .sum()
+    }
+
+    /// Forgets every position
+    pub fn clear(&self) {
+        self.positions.clear();
+    }
+}
@@ -1,9 +1,63 @@
-use crate::orderbook::book_change_event::PriceLevelChangedEvent;
-use crate::{OrderBook, OrderBookError, current_time_millis};
-use pricelevel::{OrderType, PriceLevel, Side};
+use crate::orderbook::book_change_event::{
+    BboChangedEvent, BookChangedEvent, BookReset, PriceLevelChangedEvent,
+};
+use crate::orderbook::config::BookConfig;
+use crate::orderbook::ladder::PriceLadder;
+use crate::orderbook::modifications::OrderQuantity;
+use crate::orderbook::order_event::OrderEvent;
+use crate::orderbook::owner::OwnerId;
+use crate::orderbook::trade::TradeResult;
+use crate::{OrderBook, OrderBookError};
+use crossbeam_skiplist::SkipMap;
+use dashmap::DashMap;
+use pricelevel::{MatchResult, OrderId, OrderType, PriceLevel, Side};
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 
+thread_local! {
+    /// Price level changes buffered by the book currently running a batched operation
+    /// on this thread, keyed by the address of that book.
+    static LEVEL_CHANGE_BATCH: RefCell<Option<(usize, Vec<PriceLevelChangedEvent>)>> =
+        const { RefCell::new(None) };
+
+    /// Owners of the resting orders matched by the last match on this thread, keyed
+    /// by the address of the book that ran it.
+    static MATCHED_OWNERS: RefCell<(usize, Vec<(OrderId, OwnerId)>)> =
+        const { RefCell::new((0, Vec::new())) };
+}
+
+/// Ends the level change batch started on this thread and emits what it
+/// buffered when dropped, so a batched operation that panics does not leave
+/// the thread buffering changes forever.
+struct LevelChangeBatch<'a, T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    book: &'a OrderBook<T>,
+}
+
+impl<T> Drop for LevelChangeBatch<'_, T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    fn drop(&mut self) {
+        let events = LEVEL_CHANGE_BATCH
+            .with(|batch| batch.borrow_mut().take())
+            .map(|(_, events)| events)
+            .unwrap_or_default();
+        if std::thread::panicking() {
+            // A listener panicking again while unwinding would abort the process
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+                self.book.emit_level_changes(events);
+            }));
+        } else {
+            self.book.emit_level_changes(events);
+        }
+    }
+}
+
 impl<T> OrderBook<T>
 where
     T: Clone + Send + Sync + Default + 'static,
@@ -11,7 +65,7 @@ where
     /// Check if an order has expired
     pub fn has_expired(&self, order: &OrderType<T>) -> bool {
         let time_in_force = order.time_in_force();
-        let current_time = current_time_millis();
+        let current_time = self.clock.now_millis();
 
         // Only check market close timestamp if we have one set
         let market_close = if self.has_market_close.load(Ordering::Relaxed) {
@@ -23,11 +77,438 @@ where
         time_in_force.is_expired(current_time, market_close)
     }
 
+    /// Notifies the price level listener about the current state of a level.
+    ///
+    /// While a batched operation is running on this thread for this book, the event
+    /// is buffered instead and emitted once the batch completes.
+    pub(super) fn notify_price_level_changed(&self, side: Side, price_level: &PriceLevel) {
+        self.publish_level_change(PriceLevelChangedEvent {
+            side,
+            price: price_level.price(),
+            quantity: price_level.visible_quantity(),
+        });
+    }
+
+    /// Delivers a price level change to the listener, or buffers it while a batched
+    /// operation is running on this thread for this book.
+    pub(super) fn publish_level_change(&self, event: PriceLevelChangedEvent) {
+        let key = self.batch_key();
+        let buffered = LEVEL_CHANGE_BATCH.with(|batch| match batch.borrow_mut().as_mut() {
+            Some((owner, events)) if *owner == key => {
+                events.push(event);
+                true
+            }
+            _ => false,
+        });
+
+        if !buffered {
+            self.last_update_millis
+                .store(self.clock.now_millis(), Ordering::Relaxed);
+            self.track_level_change(event);
+            if let Some(ref listener) = self.price_level_changed_listener {
+                listener(event);
+            }
+            self.price_level_subscribers
+                .notify(|listener| listener(event));
+            self.notify_bbo_changed();
+        }
+    }
+
+    /// Reports the best bid and offer to the order flow imbalance, the
+    /// time-weighted metrics, the resiliency tracker, Kyle's lambda and the BBO
+    /// listener if it moved since the last report.
+    pub(super) fn notify_bbo_changed(&self) {
+        if self.bbo_listener.is_none()
+            && self.order_flow_imbalance.is_none()
+            && self.time_weighted_liquidity.is_none()
+            && self.resiliency.is_none()
+            && self.kyle_lambda.is_none()
+        {
+            return;
+        }
+
+        let current = self.current_bbo();
+        {
+            let mut last = self.last_bbo.lock().unwrap_or_else(|e| e.into_inner());
+            if *last == current {
+                return;
+            }
+            *last = current;
+        }
+        if let Some(ref ofi) = self.order_flow_imbalance {
+            ofi.lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .observe(self.clock.now_millis(), current);
+        }
+        if let Some(ref tracker) = self.time_weighted_liquidity {
+            tracker
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .observe(self.clock.now_millis(), current);
+        }
+        if let Some(ref tracker) = self.resiliency {
+            tracker.observe(self.clock.now_millis(), &current);
+        }
+        if let Some(ref kyle) = self.kyle_lambda
+            && let (Some(bid), Some(ask)) = (current.bid_price, current.ask_price)
+        {
+            kyle.lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .record_mid(self.clock.now_millis(), (bid as f64 + ask as f64) / 2.0);
+        }
+        if let Some(ref listener) = self.bbo_listener {
+            listener(current);
+        }
+    }
+
+    /// Reads the best visible bid and ask, skipping levels that were emptied but
+    /// not yet removed.
+    pub(super) fn current_bbo(&self) -> BboChangedEvent {
+        let best = |level: &PriceLevel| {
+            let quantity = level.visible_quantity();
+            (quantity > 0).then_some((level.price(), quantity))
+        };
+        let bid = self.bids.iter().rev().find_map(|entry| best(entry.value()));
+        let ask = self.asks.iter().find_map(|entry| best(entry.value()));
+
+        BboChangedEvent {
+            bid_price: bid.map(|(price, _)| price),
+            bid_quantity: bid.map_or(0, |(_, quantity)| quantity),
+            ask_price: ask.map(|(price, _)| price),
+            ask_quantity: ask.map_or(0, |(_, quantity)| quantity),
+        }
+    }
+
+    /// Runs `operation` while buffering price level change notifications, then emits
+    /// the final state of every touched level once, followed by a single consolidated
+    /// `BookChangedEvent`.
+    ///
+    /// Nested calls join the outermost batch already running on this thread.
+    pub(super) fn with_batched_level_changes<R>(&self, operation: impl FnOnce() -> R) -> R {
+        let key = self.batch_key();
+        let started = LEVEL_CHANGE_BATCH.with(|batch| {
+            let mut batch = batch.borrow_mut();
+            if batch.is_some() {
+                return false;
+            }
+            *batch = Some((key, Vec::new()));
+            true
+        });
+
+        let _batch = started.then_some(LevelChangeBatch { book: self });
+        operation()
+    }
+
+    /// Collapses buffered events so each level is reported once with its final quantity.
+    fn emit_level_changes(&self, events: Vec<PriceLevelChangedEvent>) {
+        if events.is_empty() {
+            return;
+        }
+
+        let mut changes: Vec<PriceLevelChangedEvent> = Vec::with_capacity(events.len());
+        for event in events {
+            match changes
+                .iter_mut()
+                .find(|change| change.side == event.side && change.price == event.price)
+            {
+                Some(change) => change.quantity = event.quantity,
+                None => changes.push(event),
+            }
+        }
+
+        self.last_update_millis
+            .store(self.clock.now_millis(), Ordering::Relaxed);
+        for change in &changes {
+            self.track_level_change(*change);
+        }
+        if let Some(ref listener) = self.price_level_changed_listener {
+            for change in &changes {
+                listener(*change);
+            }
+        }
+        self.price_level_subscribers.notify(|listener| {
+            for change in &changes {
+                listener(*change);
+            }
+        });
+
+        if let Some(ref listener) = self.book_changed_listener {
+            listener(BookChangedEvent {
+                changes,
+                reset: None,
+            });
+        }
+
+        self.notify_bbo_changed();
+    }
+
+    /// Reports the reset of the book state, with every visible level of the new
+    /// book, to the book change listener.
+    pub(super) fn emit_book_reset(&self, snapshot_ts: u64) {
+        if let Some(ref listener) = self.book_changed_listener {
+            let levels = |side: Side| {
+                self.side_levels(side, false)
+                    .iter()
+                    .map(move |entry| PriceLevelChangedEvent {
+                        side,
+                        price: *entry.key(),
+                        quantity: entry.value().visible_quantity(),
+                    })
+                    .filter(|change| change.quantity > 0)
+            };
+            listener(BookChangedEvent {
+                changes: levels(Side::Buy).chain(levels(Side::Sell)).collect(),
+                reset: Some(BookReset {
+                    sequence: self.journal_sequence(),
+                    snapshot_ts,
+                }),
+            });
+        }
+        self.notify_bbo_changed();
+    }
+
+    pub(super) fn batch_key(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    /// Starts recording the owners of the resting orders matched on this thread.
+    pub(super) fn begin_owner_capture(&self) {
+        let key = self.batch_key();
+        MATCHED_OWNERS.with(|captured| {
+            let mut captured = captured.borrow_mut();
+            captured.0 = key;
+            captured.1.clear();
+        });
+    }
+
+    /// Records the owners of the resting orders `order_ids`, while they are still
+    /// tracked by the book.
+    pub(super) fn capture_owners(&self, order_ids: impl Iterator<Item = OrderId>) {
+        if self.order_owners.is_empty() {
+            return;
+        }
+        let key = self.batch_key();
+        MATCHED_OWNERS.with(|captured| {
+            let mut captured = captured.borrow_mut();
+            if captured.0 != key {
+                return;
+            }
+            for order_id in order_ids {
+                if let Some(owner) = self.order_owners.get(&order_id)
+                    && !captured.1.iter().any(|(id, _)| *id == order_id)
+                {
+                    captured.1.push((order_id, *owner));
+                }
+            }
+        });
+    }
+
+    /// Takes the owners recorded since the last match of this book on this thread.
+    fn take_captured_owners(&self) -> Vec<(OrderId, OwnerId)> {
+        let key = self.batch_key();
+        MATCHED_OWNERS.with(|captured| {
+            let mut captured = captured.borrow_mut();
+            if captured.0 == key {
+                std::mem::take(&mut captured.1)
+            } else {
+                Vec::new()
+            }
+        })
+    }
+
+    /// Delivers the trades of a match to the trade listener and every trade subscriber,
+    /// attributing them to `taker_owner` and the owners of the matched resting orders.
+    pub(super) fn publish_trade(&self, match_result: &MatchResult, taker_owner: Option<OwnerId>) {
+        let maker_owners = self.take_captured_owners();
+        if match_result.transactions.transactions.is_empty()
+            || (self.trade_listener.is_none()
+                && self.trade_subscribers.is_empty()
+                && self.fee_model.is_none()
+                && self.trade_tape.is_none()
+                && self.level_flow.is_none()
+                && self.resiliency.is_none()
+                && self.surveillance.is_none()
+                && self.realized_volatility.is_none()
+                && self.kyle_lambda.is_none())
+        {
+            return;
+        }
+
+        self.deliver_trade(TradeResult {
+            symbol: self.symbol.clone(),
+            match_result: match_result.clone(),
+            taker_owner,
+            maker_owners,
+            fees: Vec::new(),
+        });
+    }
+
+    /// Charges the fees of a trade and records it on the tape, the per-level flow,
+    /// the resiliency and surveillance trackers and the trade statistics, then
+    /// delivers it to the trade listener and every trade subscriber.
+    pub(super) fn deliver_trade(&self, mut trade_result: TradeResult) {
+        if let Some(ref model) = self.fee_model {
+            trade_result.fees = model.charge(&trade_result);
+        }
+        if let Some(ref tape) = self.trade_tape {
+            tape.record_trade(&trade_result, self.clock.now_millis());
+        }
+        if let Some(ref flow) = self.level_flow {
+            flow.record_trade(&trade_result, self.clock.now_millis());
+        }
+        self.record_sweep(&trade_result);
+        self.surveil_trade(&trade_result);
+        self.record_trade_statistics(&trade_result);
+        if let Some(ref listener) = self.trade_listener {
+            listener(&trade_result);
+        }
+        self.trade_subscribers
+            .notify(|listener| listener(&trade_result));
+    }
+
+    /// Delivers an order lifecycle event to the order event listener, if any.
+    pub(super) fn emit_order_event(&self, event: OrderEvent) {
+        if let Some(ref listener) = self.order_event_listener {
+            listener(&event);
+        }
+    }
+
+    /// Drops every piece of tracking state kept for an order that left the book.
+    pub(super) fn forget_order(&self, order_id: &OrderId) {
+        self.order_locations.remove(order_id);
+        self.hidden_order_ids.remove(order_id);
+        if let Some((_, owner)) = self.order_owners.remove(order_id) {
+            if let Some(ids) = self.owner_orders.get(&owner) {
+                ids.remove(order_id);
+            }
+            self.owner_orders.remove_if(&owner, |_, ids| ids.is_empty());
+        }
+        self.order_extra_fields.remove(order_id);
+    }
+
+    /// Removes the level at `price` if it no longer holds any order, returning
+    /// whether a level was removed. The removed level is parked for reuse.
+    pub(super) fn prune_empty_level(
+        &self,
+        price_levels: &SkipMap<u64, Arc<PriceLevel>>,
+        price: u64,
+    ) -> bool {
+        match price_levels.get(&price) {
+            Some(entry) if entry.value().order_count() == 0 && entry.remove() => {
+                if let Some(side) = self.visible_side_of(price_levels) {
+                    self.unindex_level(side, price);
+                }
+                self.level_pool.release(Arc::clone(entry.value()));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the side whose visible levels `price_levels` holds, `None` for
+    /// hidden levels.
+    pub(super) fn visible_side_of(
+        &self,
+        price_levels: &SkipMap<u64, Arc<PriceLevel>>,
+    ) -> Option<Side> {
+        if std::ptr::eq(price_levels, &self.bids) {
+            Some(Side::Buy)
+        } else if std::ptr::eq(price_levels, &self.asks) {
+            Some(Side::Sell)
+        } else {
+            None
+        }
+    }
+
+    /// Records a visible level, already in its level map, in the best price
+    /// cache and the dense ladder, if the book has one.
+    pub(super) fn index_level(&self, side: Side, price: u64, level: &Arc<PriceLevel>) {
+        self.cache
+            .level_added(side, price, self.side_levels(side, false));
+        if let Some(ref ladder) = self.ladder {
+            ladder.insert(side, price, level);
+        }
+    }
+
+    /// Forgets a visible level, already removed from its level map, in the
+    /// best price cache and the dense ladder, if the book has one.
+    pub(super) fn unindex_level(&self, side: Side, price: u64) {
+        self.cache
+            .level_removed(side, price, self.side_levels(side, false));
+        if let Some(ref ladder) = self.ladder {
+            ladder.remove(side, price);
+        }
+    }
+
+    /// Rebuilds the best price cache, the side totals and the dense ladder, if
+    /// the book has one, from the visible level maps after they were replaced
+    /// wholesale.
+    pub(super) fn reindex_levels(&self) {
+        self.cache.refresh(&self.bids, &self.asks);
+        self.totals.refresh(&self.bids, &self.asks);
+        if let Some(ref ladder) = self.ladder {
+            ladder.sync(Side::Buy, &self.bids);
+            ladder.sync(Side::Sell, &self.asks);
+        }
+    }
+
+    /// Replaces the trading rules of the book, building the dense ladder and
+    /// the order location shards they ask for.
+    pub(super) fn apply_config(&mut self, config: BookConfig) {
+        if config.location_shards != self.config.location_shards {
+            let locations =
+                std::mem::replace(&mut self.order_locations, Self::location_map(&config));
+            self.order_locations.extend(locations);
+        }
+        self.config = config;
+        self.ladder = PriceLadder::for_config(&config);
+        self.reindex_levels();
+    }
+
+    /// Empty map locating resting orders, split into the shards `config` asks for.
+    pub(super) fn location_map(config: &BookConfig) -> DashMap<OrderId, (u64, Side)> {
+        match config.location_shards {
+            Some(shards) => DashMap::with_shard_amount(shards.max(2).next_power_of_two()),
+            None => DashMap::new(),
+        }
+    }
+
+    /// Returns the price level map holding the given side's visible or hidden orders.
+    pub(super) fn side_levels(&self, side: Side, hidden: bool) -> &SkipMap<u64, Arc<PriceLevel>> {
+        match (side, hidden) {
+            (Side::Buy, false) => &self.bids,
+            (Side::Sell, false) => &self.asks,
+            (Side::Buy, true) => &self.hidden_bids,
+            (Side::Sell, true) => &self.hidden_asks,
+        }
+    }
+
+    /// Returns the visible or hidden level of `side` at `price`, found in its
+    /// slot of the dense ladder for visible levels of books that have one.
+    pub(super) fn level_at(&self, side: Side, price: u64, hidden: bool) -> Option<Arc<PriceLevel>> {
+        match self.ladder {
+            Some(ref ladder) if !hidden => ladder.level(side, price),
+            _ => self
+                .side_levels(side, hidden)
+                .get(&price)
+                .map(|entry| Arc::clone(entry.value())),
+        }
+    }
+
     /// Check if there would be a price crossing
+    ///
+    /// Fully hidden orders are taken into account, since an order priced through
+    /// them would execute against them.
     pub fn will_cross_market(&self, price: u64, side: Side) -> bool {
         match side {
-            Side::Buy => OrderBook::<T>::best_ask(self).is_some_and(|best_ask| price >= best_ask),
-            Side::Sell => OrderBook::<T>::best_bid(self).is_some_and(|best_bid| price <= best_bid),
+            Side::Buy => OrderBook::<T>::best_ask(self)
+                .into_iter()
+                .chain(self.hidden_asks.front().map(|entry| *entry.key()))
+                .any(|best_ask| price >= best_ask),
+            Side::Sell => OrderBook::<T>::best_bid(self)
+                .into_iter()
+                .chain(self.hidden_bids.back().map(|entry| *entry.key()))
+                .any(|best_bid| price <= best_bid),
         }
     }
 
@@ -38,6 +519,7 @@ where
         order: Arc<OrderType<T>>,
     ) -> Result<Arc<OrderType<T>>, OrderBookError> {
         let (side, price, order_id) = (order.side(), order.price(), order.id());
+        self.admit_level(side, price)?;
 
         let book_side = match side {
             Side::Buy => &self.bids,
@@ -45,25 +527,33 @@ where
         };
 
         // Get or create the price level
+        let mut opened = None;
         let price_level = book_side
-            .get_or_insert(price, Arc::new(PriceLevel::new(price)))
+            .get_or_insert_with(price, || {
+                let level = self.level_pool.acquire(price);
+                opened = Some(Arc::clone(&level));
+                level
+            })
             .value()
             .clone();
+        if opened.is_some_and(|opened| Arc::ptr_eq(&opened, &price_level))
+            && let Err(error) = self.confirm_opened_level(side, price)
+            && self.prune_empty_level(book_side, price)
+        {
+            return Err(error);
+        }
+        self.index_level(side, price, &price_level);
 
         // Convert OrderType<T> to OrderType<()> for compatibility with current PriceLevel API
         let unit_order = self.convert_to_unit_type(&*order);
+        self.totals.added(side, unit_order.total_quantity());
         let _added_order = price_level.add_order(unit_order);
 
         // notify price level changes
-        if let Some(ref listener) = self.price_level_changed_listener {
-            listener(PriceLevelChangedEvent {
-                side,
-                price: price_level.price(),
-                quantity: price_level.visible_quantity(),
-            })
-        }
+        self.notify_price_level_changed(side, &price_level);
         // The location is stored as (price, side) for efficient retrieval in cancel_order
         self.order_locations.insert(order_id, (price, side));
+        self.evict_excess_levels(side);
 
         Ok(order)
     }
@@ -0,0 +1,202 @@
+//! Two-sided quote management for market makers.
+
+use super::book::OrderBook;
+use super::error::OrderBookError;
+use super::owner::OwnerId;
+use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+/// Identifiers of the bid and ask quotes of one owner currently resting in the
+/// book.
+///
+/// A side is `None` when no quote is posted on it, or when the previous quote
+/// was fully executed before it could rest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotePair {
+    /// Order ID of the resting bid quote
+    pub bid_order_id: Option<OrderId>,
+    /// Order ID of the resting ask quote
+    pub ask_order_id: Option<OrderId>,
+}
+
+impl QuotePair {
+    /// Returns `true` if neither side has a resting quote
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bid_order_id.is_none() && self.ask_order_id.is_none()
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Replaces the quote pair of `owner` with new bid and ask quotes in one step
+    ///
+    /// Both new quotes are checked against the trading rules, session, price bands,
+    /// kill switches and risk checks before the previous quotes of `owner` are
+    /// cancelled, and they are cancelled before the new ones are posted, so the new
+    /// quotes can never trade against the old ones. Quotes are posted on behalf of
+    /// `owner`, so its rate limit, positions and self-trade rules apply to them, and
+    /// the quotes of other owners are left alone. Concurrent calls are serialized,
+    /// and all price level changes produced by the update are reported through a
+    /// single consolidated `BookChangedEvent`.
+    ///
+    /// A zero quantity withdraws the quote on that side.
+    ///
+    /// # Arguments
+    /// - `owner`: Market maker the quotes belong to
+    /// - `bid_price`: Price of the new bid quote (in price units)
+    /// - `bid_quantity`: Quantity of the new bid quote (in units, 0 = no bid)
+    /// - `ask_price`: Price of the new ask quote (in price units)
+    /// - `ask_quantity`: Quantity of the new ask quote (in units, 0 = no ask)
+    ///
+    /// # Returns
+    /// The `QuotePair` of `owner` left resting in the book after the update.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::PriceCrossing` if both sides are quoted and the bid is
+    /// not strictly below the ask, or the error of the first check a new quote fails.
+    /// In that case the previous quotes are left untouched. If posting a quote still
+    /// fails once the previous quotes are gone, the new quotes already posted are
+    /// cancelled too and `owner` is left without quotes.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{OrderBook, OwnerId};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let maker = OwnerId(1);
+    /// let quotes = book.update_quotes(maker, 99, 10, 101, 10).unwrap();
+    /// assert_eq!(book.best_bid(), Some(99));
+    /// assert_eq!(book.best_ask(), Some(101));
+    ///
+    /// // Requote: the old pair is replaced
+    /// let requoted = book.update_quotes(maker, 100, 5, 102, 5).unwrap();
+    /// assert_ne!(quotes, requoted);
+    /// assert_eq!(book.best_bid(), Some(100));
+    /// ```
+    pub fn update_quotes(
+        &self,
+        owner: OwnerId,
+        bid_price: u64,
+        bid_quantity: u64,
+        ask_price: u64,
+        ask_quantity: u64,
+    ) -> Result<QuotePair, OrderBookError> {
+        if bid_quantity > 0 && ask_quantity > 0 && bid_price >= ask_price {
+            return Err(OrderBookError::PriceCrossing {
+                price: bid_price,
+                side: Side::Buy,
+                opposite_price: ask_price,
+            });
+        }
+
+        trace!(
+            "Order book {}: Updating quotes of {} {}@{} / {}@{}",
+            self.symbol, owner, bid_quantity, bid_price, ask_quantity, ask_price
+        );
+
+        let mut quotes = self.quotes.lock().unwrap_or_else(|e| e.into_inner());
+        let bid = (bid_quantity > 0).then(|| self.quote(bid_price, bid_quantity, Side::Buy));
+        let ask = (ask_quantity > 0).then(|| self.quote(ask_price, ask_quantity, Side::Sell));
+        for quote in bid.iter().chain(&ask) {
+            self.validate_submission(quote, Some(owner))?;
+        }
+
+        self.with_batched_level_changes(|| {
+            let previous = quotes.remove(&owner).unwrap_or_default();
+            self.cancel_quote_pair(&previous)?;
+
+            let mut pair = QuotePair::default();
+            for quote in [bid, ask].into_iter().flatten() {
+                let side = quote.side();
+                let posted = match self.post_quote(quote, owner) {
+                    Ok(posted) => posted,
+                    Err(error) => {
+                        self.cancel_quote_pair(&pair)?;
+                        return Err(error);
+                    }
+                };
+                match side {
+                    Side::Buy => pair.bid_order_id = posted,
+                    Side::Sell => pair.ask_order_id = posted,
+                }
+            }
+            if !pair.is_empty() {
+                quotes.insert(owner, pair);
+            }
+            Ok(pair)
+        })
+    }
+
+    /// Cancels both sides of the quote pair of `owner`
+    ///
+    /// # Returns
+    /// The quote pair that was active before cancellation.
+    pub fn cancel_quotes(&self, owner: OwnerId) -> Result<QuotePair, OrderBookError> {
+        let mut quotes = self.quotes.lock().unwrap_or_else(|e| e.into_inner());
+        let previous = quotes.get(&owner).copied().unwrap_or_default();
+
+        self.with_batched_level_changes(|| self.cancel_quote_pair(&previous))?;
+        quotes.remove(&owner);
+
+        Ok(previous)
+    }
+
+    /// Returns the quote pair of `owner` currently resting in the book
+    ///
+    /// Sides whose quote has since been fully executed or cancelled are reported as
+    /// `None`.
+    #[must_use]
+    pub fn active_quotes(&self, owner: OwnerId) -> QuotePair {
+        let quotes = self.quotes.lock().unwrap_or_else(|e| e.into_inner());
+        let pair = quotes.get(&owner).copied().unwrap_or_default();
+        QuotePair {
+            bid_order_id: pair
+                .bid_order_id
+                .filter(|id| self.order_locations.contains_key(id)),
+            ask_order_id: pair
+                .ask_order_id
+                .filter(|id| self.order_locations.contains_key(id)),
+        }
+    }
+
+    fn cancel_quote_pair(&self, quotes: &QuotePair) -> Result<(), OrderBookError> {
+        for order_id in [quotes.bid_order_id, quotes.ask_order_id]
+            .into_iter()
+            .flatten()
+        {
+            self.recycle(self.cancel_resting_order(order_id)?);
+        }
+        Ok(())
+    }
+
+    /// A new good-till-cancelled quote.
+    fn quote(&self, price: u64, quantity: u64, side: Side) -> OrderType<T> {
+        OrderType::Standard {
+            id: OrderId::new(),
+            price,
+            quantity,
+            side,
+            timestamp: self.clock.now_millis(),
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: T::default(),
+        }
+    }
+
+    /// Posts a single quote, returning its ID only if some quantity is left resting.
+    fn post_quote(
+        &self,
+        quote: OrderType<T>,
+        owner: OwnerId,
+    ) -> Result<Option<OrderId>, OrderBookError> {
+        let order_id = quote.id();
+        self.add_order_with_owner(quote, owner)?;
+        Ok(self
+            .order_locations
+            .contains_key(&order_id)
+            .then_some(order_id))
+    }
+}
@@ -0,0 +1,174 @@
+//! Token-bucket rate limiting of order submissions and cancellations per owner.
+
+use super::book::OrderBook;
+use super::error::OrderBookError;
+use super::owner::OwnerId;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+/// Burst size and sustained rate of the requests accepted from one owner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Requests accepted in a burst from a full bucket
+    pub capacity: u32,
+    /// Tokens added back to the bucket every second
+    pub refill_per_second: u32,
+}
+
+impl RateLimit {
+    /// Create a limit of `refill_per_second` requests per second with bursts of `capacity`
+    pub fn new(capacity: u32, refill_per_second: u32) -> Self {
+        Self {
+            capacity,
+            refill_per_second,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: u64,
+}
+
+/// Token buckets of every owner that sent requests to a book.
+///
+/// Each owner starts with a full bucket of [`RateLimit::capacity`] tokens. Every
+/// accepted request takes one token, and tokens are added back continuously at
+/// [`RateLimit::refill_per_second`] up to the capacity.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    buckets: DashMap<OwnerId, TokenBucket>,
+}
+
+impl RateLimiter {
+    /// Create a limiter applying `limit` to every owner
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: DashMap::new(),
+        }
+    }
+
+    /// The limit applied to every owner
+    pub fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    /// Takes a token from the bucket of `owner` at time `now`, in milliseconds
+    ///
+    /// # Returns
+    /// The milliseconds to wait before a token is available, if the bucket is empty.
+    pub fn try_acquire(&self, owner: OwnerId, now: u64) -> Result<(), u64> {
+        let mut bucket = self.buckets.entry(owner).or_insert_with(|| TokenBucket {
+            tokens: f64::from(self.limit.capacity),
+            updated_at: now,
+        });
+        self.refill(&mut bucket, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        if self.limit.refill_per_second == 0 {
+            return Err(u64::MAX);
+        }
+        let missing = 1.0 - bucket.tokens;
+        Err((missing * 1_000.0 / f64::from(self.limit.refill_per_second)).ceil() as u64)
+    }
+
+    /// Whole tokens left to `owner` at time `now`
+    pub fn available(&self, owner: OwnerId, now: u64) -> u32 {
+        match self.buckets.get_mut(&owner) {
+            Some(mut bucket) => {
+                self.refill(&mut bucket, now);
+                bucket.tokens as u32
+            }
+            None => self.limit.capacity,
+        }
+    }
+
+    /// Refills the bucket of `owner`
+    pub fn reset(&self, owner: OwnerId) {
+        self.buckets.remove(&owner);
+    }
+
+    /// Refills the bucket of every owner
+    pub fn clear(&self) {
+        self.buckets.clear();
+    }
+
+    fn refill(&self, bucket: &mut TokenBucket, now: u64) {
+        let elapsed = now.saturating_sub(bucket.updated_at);
+        if elapsed == 0 {
+            return;
+        }
+        let refilled = elapsed as f64 * f64::from(self.limit.refill_per_second) / 1_000.0;
+        bucket.tokens = (bucket.tokens + refilled).min(f64::from(self.limit.capacity));
+        bucket.updated_at = now;
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Limits the orders added and cancelled on behalf of each owner
+    ///
+    /// Requests over the limit are rejected with [`OrderBookError::RateLimited`]
+    /// before they are journaled. Orders without an owner, and cancellations made
+    /// by the book itself such as mass cancels, are not limited.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{OrderBook, OrderBookError, OwnerId, RateLimit};
+    /// use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+    ///
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// book.set_rate_limit(RateLimit::new(2, 1));
+    /// let order = || OrderType::Standard {
+    ///     id: OrderId::new(),
+    ///     price: 100,
+    ///     quantity: 10,
+    ///     side: Side::Buy,
+    ///     timestamp: 0,
+    ///     time_in_force: TimeInForce::Gtc,
+    ///     extra_fields: (),
+    /// };
+    ///
+    /// assert!(book.add_order_with_owner(order(), OwnerId(1)).is_ok());
+    /// assert!(book.add_order_with_owner(order(), OwnerId(1)).is_ok());
+    /// assert!(matches!(
+    ///     book.add_order_with_owner(order(), OwnerId(1)),
+    ///     Err(OrderBookError::RateLimited { .. })
+    /// ));
+    /// ```
+    pub fn set_rate_limit(&mut self, limit: RateLimit) {
+        self.rate_limiter = Some(RateLimiter::new(limit));
+    }
+
+    /// Stop limiting the requests of owners
+    pub fn remove_rate_limit(&mut self) {
+        self.rate_limiter = None;
+    }
+
+    /// The rate limiter of the book, if a limit is set
+    #[must_use]
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Takes a token from the bucket of `owner`, if the book has a rate limit
+    pub(super) fn check_rate_limit(&self, owner: Option<OwnerId>) -> Result<(), OrderBookError> {
+        let (Some(limiter), Some(owner)) = (&self.rate_limiter, owner) else {
+            return Ok(());
+        };
+        limiter
+            .try_acquire(owner, self.clock.now_millis())
+            .map_err(|retry_after_ms| OrderBookError::RateLimited {
+                owner,
+                retry_after_ms,
+            })
+    }
+}
@@ -0,0 +1,231 @@
+//! Event-sourced replay of recorded commands for backtesting and debugging.
+//!
+//! A [`ReplayEngine`] feeds journal entries into an order book one by one, pacing
+//! them like the original session (or faster), and can pause at chosen sequence
+//! numbers so the book can be inspected exactly as it was at that point.
+
+use super::book::OrderBook;
+use super::error::OrderBookError;
+use super::journal::{Journal, JournalEntry};
+use crate::utils::ManualClock;
+use std::collections::{BTreeSet, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::trace;
+
+/// How fast recorded commands are replayed.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ReplaySpeed {
+    /// Apply every command immediately
+    #[default]
+    AsFastAsPossible,
+    /// Wait between commands as long as the original session did
+    RealTime,
+    /// Wait between commands the original delay divided by this factor
+    Accelerated(f64),
+}
+
+/// Why [`ReplayEngine::run`] returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayStatus {
+    /// Every recorded command has been replayed
+    Finished,
+    /// The command with this sequence number was replayed and is a breakpoint
+    Breakpoint(u64),
+}
+
+/// Replays recorded journal entries into an order book.
+///
+/// The book's clock is replaced by a manual clock that reads the timestamp of the
+/// command being replayed, so orders expire exactly as they originally did, and
+/// any attached journal is detached so replayed commands are not recorded again.
+/// Commands that were rejected when first issued are rejected again and skipped.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::{InMemoryJournal, OrderBook, ReplayEngine, ReplayStatus};
+/// use pricelevel::{OrderId, Side, TimeInForce};
+/// use std::sync::Arc;
+///
+/// let journal = Arc::new(InMemoryJournal::new());
+/// let mut book = OrderBook::<()>::new("BTC/USD");
+/// book.set_journal(journal.clone());
+/// book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+/// book.add_limit_order(OrderId::new(), 101, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+///
+/// let mut replay = ReplayEngine::from_journal(OrderBook::new("BTC/USD"), journal.as_ref(), 0)
+///     .unwrap()
+///     .with_breakpoint(1);
+///
+/// assert_eq!(replay.run(), ReplayStatus::Breakpoint(1));
+/// assert_eq!(replay.book().best_bid(), Some(100));
+/// assert_eq!(replay.run(), ReplayStatus::Finished);
+/// assert_eq!(replay.book().best_bid(), Some(101));
+/// ```
+pub struct ReplayEngine<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    book: OrderBook<T>,
+    clock: Arc<ManualClock>,
+    entries: VecDeque<JournalEntry<T>>,
+    speed: ReplaySpeed,
+    breakpoints: BTreeSet<u64>,
+    position: u64,
+    rejected: usize,
+}
+
+impl<T> ReplayEngine<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Create an engine replaying `entries`, in order, into `book`
+    ///
+    /// `book` is usually a fresh book configured like the original one, or a book
+    /// restored from a snapshot taken at the first entry's predecessor.
+    pub fn new(mut book: OrderBook<T>, entries: impl IntoIterator<Item = JournalEntry<T>>) -> Self {
+        let clock = Arc::new(ManualClock::new(book.clock().now_millis()));
+        book.set_clock(clock.clone());
+        book.remove_journal();
+        let position = book.journal_sequence();
+
+        Self {
+            book,
+            clock,
+            entries: entries.into_iter().collect(),
+            speed: ReplaySpeed::default(),
+            breakpoints: BTreeSet::new(),
+            position,
+            rejected: 0,
+        }
+    }
+
+    /// Create an engine replaying the entries of `journal` recorded after `after_sequence`
+    ///
+    /// # Errors
+    /// Returns an error if the journal cannot be read.
+    pub fn from_journal(
+        book: OrderBook<T>,
+        journal: &dyn Journal<T>,
+        after_sequence: u64,
+    ) -> Result<Self, OrderBookError> {
+        Ok(Self::new(book, journal.entries_after(after_sequence)?))
+    }
+
+    /// Sets the replay speed
+    #[must_use]
+    pub fn with_speed(mut self, speed: ReplaySpeed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Pauses [`ReplayEngine::run`] right after the command with this sequence number
+    #[must_use]
+    pub fn with_breakpoint(mut self, sequence: u64) -> Self {
+        self.breakpoints.insert(sequence);
+        self
+    }
+
+    /// Changes the replay speed, e.g. while paused at a breakpoint
+    pub fn set_speed(&mut self, speed: ReplaySpeed) {
+        self.speed = speed;
+    }
+
+    /// Adds a breakpoint after the command with this sequence number
+    pub fn add_breakpoint(&mut self, sequence: u64) {
+        self.breakpoints.insert(sequence);
+    }
+
+    /// Removes a breakpoint, returning `true` if it was set
+    pub fn remove_breakpoint(&mut self, sequence: u64) -> bool {
+        self.breakpoints.remove(&sequence)
+    }
+
+    /// Replays commands, paced according to the speed, until a breakpoint or the end
+    ///
+    /// Time spent paused between calls is not made up for: pacing restarts from the
+    /// first command replayed by each call.
+    pub fn run(&mut self) -> ReplayStatus {
+        let started = Instant::now();
+        let first_timestamp = self.entries.front().map(|entry| entry.timestamp);
+
+        while let Some(entry) = self.entries.front() {
+            if let Some(delay) = self.delay(started, first_timestamp, entry.timestamp) {
+                std::thread::sleep(delay);
+            }
+
+            let Some(sequence) = self.step() else {
+                break;
+            };
+            if self.breakpoints.contains(&sequence) {
+                trace!("Replay of {} paused at {}", self.book.symbol(), sequence);
+                return ReplayStatus::Breakpoint(sequence);
+            }
+        }
+
+        ReplayStatus::Finished
+    }
+
+    /// Replays the next command immediately, ignoring speed and breakpoints
+    ///
+    /// Returns its sequence number, or `None` if every command has been replayed.
+    pub fn step(&mut self) -> Option<u64> {
+        let entry = self.entries.pop_front()?;
+        self.clock.set(entry.timestamp);
+        if self.book.apply_journal_command(entry.command).is_err() {
+            self.rejected += 1;
+        }
+
+        self.position = entry.sequence;
+        *self
+            .book
+            .journal_sequence
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = entry.sequence;
+        Some(entry.sequence)
+    }
+
+    /// How long to wait before replaying a command recorded at `timestamp`
+    fn delay(
+        &self,
+        started: Instant,
+        first_timestamp: Option<u64>,
+        timestamp: u64,
+    ) -> Option<Duration> {
+        let factor = match self.speed {
+            ReplaySpeed::AsFastAsPossible => return None,
+            ReplaySpeed::RealTime => 1.0,
+            ReplaySpeed::Accelerated(factor) if factor > 0.0 => factor,
+            ReplaySpeed::Accelerated(_) => return None,
+        };
+
+        let recorded = timestamp.saturating_sub(first_timestamp?);
+        let target = Duration::from_secs_f64(recorded as f64 / 1_000.0 / factor);
+        target.checked_sub(started.elapsed())
+    }
+
+    /// The book commands are replayed into
+    pub fn book(&self) -> &OrderBook<T> {
+        &self.book
+    }
+
+    /// Ends the replay and returns the book
+    pub fn into_book(self) -> OrderBook<T> {
+        self.book
+    }
+
+    /// Sequence number of the last replayed command
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Number of commands still to be replayed
+    pub fn remaining(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Number of replayed commands that were rejected by the book
+    pub fn rejected(&self) -> usize {
+        self.rejected
+    }
+}
@@ -0,0 +1,356 @@
+//! Book resiliency: how quickly depth at the touch is replenished after sweeps.
+
+use super::book::OrderBook;
+use super::book_change_event::BboChangedEvent;
+use super::trade::TradeResult;
+use pricelevel::Side;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// A trade that consumed one or more price levels, and the recovery of the touch after it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SweepEvent {
+    /// Time of the sweep, in milliseconds of the book clock
+    pub timestamp: u64,
+    /// Side of the resting orders that were swept
+    pub side: Side,
+    /// Number of price levels the trade consumed entirely
+    pub levels_consumed: usize,
+    /// Quantity resting at the touch before the sweep, executed by the trade
+    pub depth_before: u64,
+    /// Visible quantity at the touch right after the sweep
+    pub depth_after: u64,
+    /// Time at which the touch held the replenishment ratio of `depth_before`
+    /// again, or `None` while pending or if it never did
+    pub recovered_at: Option<u64>,
+}
+
+impl SweepEvent {
+    /// Milliseconds it took the touch to be replenished, if it was
+    #[must_use]
+    pub fn recovery_ms(&self) -> Option<u64> {
+        self.recovered_at
+            .map(|recovered| recovered.saturating_sub(self.timestamp))
+    }
+}
+
+/// Summary of the sweeps recorded by a [`ResiliencyTracker`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResiliencyStats {
+    /// Completed sweeps, recovered or not
+    pub sweep_count: usize,
+    /// Sweeps after which the touch was replenished within the timeout
+    pub recovered_count: usize,
+    /// Average time to replenishment of the recovered sweeps, in milliseconds
+    pub avg_recovery_ms: Option<f64>,
+    /// Median time to replenishment of the recovered sweeps, in milliseconds
+    pub median_recovery_ms: Option<u64>,
+    /// Longest time to replenishment of the recovered sweeps, in milliseconds
+    pub max_recovery_ms: Option<u64>,
+}
+
+impl ResiliencyStats {
+    /// Share of the sweeps that were replenished within the timeout, between 0 and 1
+    #[must_use]
+    pub fn recovery_ratio(&self) -> Option<f64> {
+        (self.sweep_count > 0).then(|| self.recovered_count as f64 / self.sweep_count as f64)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ResiliencyState {
+    /// Sweep waiting for replenishment, bid side first
+    pending: [Option<SweepEvent>; 2],
+    completed: VecDeque<SweepEvent>,
+}
+
+/// Measures the time the touch takes to be replenished after each sweep.
+///
+/// A sweep is a trade that consumed at least one price level entirely. Its
+/// touch is replenished once the visible quantity at the best price of the
+/// swept side, whatever that price, reaches `replenishment_ratio` of the
+/// quantity resting at the touch before the sweep. Sweeps that are not
+/// replenished within `timeout_ms`, or that are followed by another sweep of
+/// the same side first, complete without a recovery time. The last `capacity`
+/// completed sweeps are kept.
+#[derive(Debug)]
+pub struct ResiliencyTracker {
+    replenishment_ratio: f64,
+    timeout_ms: u64,
+    capacity: usize,
+    state: Mutex<ResiliencyState>,
+}
+
+impl ResiliencyTracker {
+    /// Create a tracker waiting up to `timeout_ms` for the touch to hold
+    /// `replenishment_ratio` of its depth before each sweep
+    #[must_use]
+    pub fn new(replenishment_ratio: f64, timeout_ms: u64, capacity: usize) -> Self {
+        Self {
+            replenishment_ratio: replenishment_ratio.max(0.0),
+            timeout_ms,
+            capacity,
+            state: Mutex::new(ResiliencyState::default()),
+        }
+    }
+
+    /// Share of the depth before a sweep the touch has to hold again
+    #[must_use]
+    pub fn replenishment_ratio(&self) -> f64 {
+        self.replenishment_ratio
+    }
+
+    /// Longest wait for a replenishment, in milliseconds
+    #[must_use]
+    pub fn timeout_ms(&self) -> u64 {
+        self.timeout_ms
+    }
+
+    /// Records a sweep of `side` at `timestamp`, completing any sweep of the
+    /// same side still waiting for replenishment
+    pub fn record_sweep(
+        &self,
+        timestamp: u64,
+        side: Side,
+        levels_consumed: usize,
+        depth_before: u64,
+        depth_after: u64,
+    ) {
+        let mut sweep = SweepEvent {
+            timestamp,
+            side,
+            levels_consumed,
+            depth_before,
+            depth_after,
+            recovered_at: None,
+        };
+        let mut state = self.lock();
+        if let Some(previous) = state.pending[Self::index(side)].take() {
+            self.complete(&mut state, previous);
+        }
+        if self.is_replenished(&sweep, depth_after) {
+            sweep.recovered_at = Some(timestamp);
+            self.complete(&mut state, sweep);
+        } else {
+            state.pending[Self::index(side)] = Some(sweep);
+        }
+    }
+
+    /// Checks the pending sweeps against the best bid and offer at `timestamp`
+    pub fn observe(&self, timestamp: u64, bbo: &BboChangedEvent) {
+        let mut state = self.lock();
+        for (side, depth) in [
+            (Side::Buy, bbo.bid_quantity),
+            (Side::Sell, bbo.ask_quantity),
+        ] {
+            let Some(mut sweep) = state.pending[Self::index(side)] else {
+                continue;
+            };
+            let expired = timestamp.saturating_sub(sweep.timestamp) > self.timeout_ms;
+            if !expired && !self.is_replenished(&sweep, depth) {
+                continue;
+            }
+            if !expired {
+                sweep.recovered_at = Some(timestamp);
+            }
+            state.pending[Self::index(side)] = None;
+            self.complete(&mut state, sweep);
+        }
+    }
+
+    /// Sweeps still waiting for replenishment
+    #[must_use]
+    pub fn pending(&self) -> Vec<SweepEvent> {
+        self.lock().pending.iter().flatten().copied().collect()
+    }
+
+    /// Completed sweeps, oldest first
+    #[must_use]
+    pub fn sweeps(&self) -> Vec<SweepEvent> {
+        self.lock().completed.iter().copied().collect()
+    }
+
+    /// Summary of the completed sweeps of `side`, or of both sides
+    #[must_use]
+    pub fn stats(&self, side: Option<Side>) -> ResiliencyStats {
+        let state = self.lock();
+        let sweeps: Vec<&SweepEvent> = state
+            .completed
+            .iter()
+            .filter(|sweep| side.is_none_or(|side| sweep.side == side))
+            .collect();
+        let mut recoveries: Vec<u64> = sweeps
+            .iter()
+            .filter_map(|sweep| sweep.recovery_ms())
+            .collect();
+        recoveries.sort_unstable();
+
+        ResiliencyStats {
+            sweep_count: sweeps.len(),
+            recovered_count: recoveries.len(),
+            avg_recovery_ms: (!recoveries.is_empty()).then(|| {
+                recoveries.iter().map(|&ms| ms as f64).sum::<f64>() / recoveries.len() as f64
+            }),
+            median_recovery_ms: recoveries.get(recoveries.len() / 2).copied(),
+            max_recovery_ms: recoveries.last().copied(),
+        }
+    }
+
+    /// Drops every sweep, pending or completed
+    pub fn clear(&self) {
+        *self.lock() = ResiliencyState::default();
+    }
+
+    fn is_replenished(&self, sweep: &SweepEvent, depth: u64) -> bool {
+        depth as f64 >= sweep.depth_before as f64 * self.replenishment_ratio
+    }
+
+    fn complete(&self, state: &mut ResiliencyState, sweep: SweepEvent) {
+        if self.capacity == 0 {
+            return;
+        }
+        if state.completed.len() == self.capacity {
+            state.completed.pop_front();
+        }
+        state.completed.push_back(sweep);
+    }
+
+    fn index(side: Side) -> usize {
+        match side {
+            Side::Buy => 0,
+            Side::Sell => 1,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ResiliencyState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clone for ResiliencyTracker {
+    fn clone(&self) -> Self {
+        Self {
+            replenishment_ratio: self.replenishment_ratio,
+            timeout_ms: self.timeout_ms,
+            capacity: self.capacity,
+            state: Mutex::new(self.lock().clone()),
+        }
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Measures how quickly the touch is replenished after trades that consume
+    /// whole price levels, keeping the last `capacity` sweeps
+    ///
+    /// Replaces any tracker enabled before, dropping its sweeps.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{ManualClock, OrderBook};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    /// use std::sync::Arc;
+    ///
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// let clock = Arc::new(ManualClock::new(0));
+    /// book.set_clock(clock.clone());
+    /// book.enable_resiliency_tracking(0.8, 60_000, 100);
+    /// book.add_limit_order(OrderId::new(), 101, 20, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 102, 5, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// // Sweep the 20 at 101, leaving 5 at the touch
+    /// book.submit_market_order(OrderId::new(), 20, Side::Buy).unwrap();
+    ///
+    /// // 16 units at the touch again 250 ms later
+    /// clock.advance(250);
+    /// book.add_limit_order(OrderId::new(), 102, 11, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// let stats = book.resiliency_stats(Some(Side::Sell)).unwrap();
+    /// assert_eq!(stats.sweep_count, 1);
+    /// assert_eq!(stats.max_recovery_ms, Some(250));
+    /// ```
+    pub fn enable_resiliency_tracking(
+        &mut self,
+        replenishment_ratio: f64,
+        timeout_ms: u64,
+        capacity: usize,
+    ) {
+        let current = self.current_bbo();
+        *self.last_bbo.get_mut().unwrap_or_else(|e| e.into_inner()) = current;
+        self.resiliency = Some(ResiliencyTracker::new(
+            replenishment_ratio,
+            timeout_ms,
+            capacity,
+        ));
+    }
+
+    /// Stop measuring the book resiliency
+    pub fn disable_resiliency_tracking(&mut self) {
+        self.resiliency = None;
+    }
+
+    /// The resiliency tracker of the book, if enabled
+    #[must_use]
+    pub fn resiliency_tracker(&self) -> Option<&ResiliencyTracker> {
+        self.resiliency.as_ref()
+    }
+
+    /// Summary of the completed sweeps of `side`, or of both sides, if tracked
+    #[must_use]
+    pub fn resiliency_stats(&self, side: Option<Side>) -> Option<ResiliencyStats> {
+        self.resiliency.as_ref().map(|tracker| tracker.stats(side))
+    }
+
+    /// Records `trade` as a sweep on the resiliency tracker if it consumed at
+    /// least one price level entirely
+    pub(super) fn record_sweep(&self, trade: &TradeResult) {
+        let Some(ref tracker) = self.resiliency else {
+            return;
+        };
+        let transactions = trade.match_result.transactions.as_vec();
+        let Some(first) = transactions.first() else {
+            return;
+        };
+        let side = first.taker_side.opposite();
+
+        // Executions walk the levels from the touch outwards
+        let mut prices: Vec<u64> = Vec::new();
+        for transaction in transactions {
+            if prices.last() != Some(&transaction.price) {
+                prices.push(transaction.price);
+            }
+        }
+        let levels_consumed = prices
+            .iter()
+            .take_while(|&&price| {
+                [false, true]
+                    .into_iter()
+                    .all(|hidden| self.side_levels(side, hidden).get(&price).is_none())
+            })
+            .count();
+        if levels_consumed == 0 {
+            return;
+        }
+
+        let depth_before = transactions
+            .iter()
+            .filter(|transaction| transaction.price == first.price)
+            .map(|transaction| transaction.quantity)
+            .sum();
+        let bbo = self.current_bbo();
+        let depth_after = match side {
+            Side::Buy => bbo.bid_quantity,
+            Side::Sell => bbo.ask_quantity,
+        };
+        tracker.record_sweep(
+            self.clock.now_millis(),
+            side,
+            levels_consumed,
+            depth_before,
+            depth_after,
+        );
+    }
+}
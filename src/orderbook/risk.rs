@@ -0,0 +1,238 @@
+//! Pre-trade risk checks run before an order is accepted by a book.
+
+use super::book::OrderBook;
+use super::error::OrderBookError;
+use super::owner::OwnerId;
+use pricelevel::{OrderId, Side};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// An order about to be accepted, as seen by the risk checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskOrder {
+    /// ID of the order
+    pub order_id: OrderId,
+    /// Side of the order
+    pub side: Side,
+    /// Limit price, or `None` for market orders
+    pub price: Option<u64>,
+    /// Total quantity, including any hidden or reserve quantity
+    pub quantity: u64,
+    /// Owner the order is submitted on behalf of, if any
+    pub owner: Option<OwnerId>,
+}
+
+/// Market state of the book at the time of the check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskContext {
+    /// Best bid price, if any
+    pub best_bid: Option<u64>,
+    /// Best ask price, if any
+    pub best_ask: Option<u64>,
+    /// Price of the last trade, if any
+    pub last_trade_price: Option<u64>,
+}
+
+impl RiskContext {
+    /// Best price on the side an order of `side` would trade against
+    pub fn opposite_price(&self, side: Side) -> Option<u64> {
+        match side {
+            Side::Buy => self.best_ask,
+            Side::Sell => self.best_bid,
+        }
+    }
+
+    /// Best price on the same side as an order of `side`
+    pub fn same_side_price(&self, side: Side) -> Option<u64> {
+        match side {
+            Side::Buy => self.best_bid,
+            Side::Sell => self.best_ask,
+        }
+    }
+}
+
+/// A pre-trade risk check.
+///
+/// Checks run in the order they were added to the book, before the order is
+/// accepted. The first failing check rejects the order with
+/// [`OrderBookError::RiskRejected`].
+pub trait RiskChecker: Send + Sync + fmt::Debug {
+    /// Returns the reason for rejecting `order`, if it must be rejected
+    fn check(&self, order: &RiskOrder, context: &RiskContext) -> Result<(), String>;
+}
+
+/// Rejects orders larger than a maximum quantity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaxOrderQuantity {
+    /// Largest accepted total quantity
+    pub max_quantity: u64,
+}
+
+impl MaxOrderQuantity {
+    /// Create a check rejecting orders above `max_quantity`
+    pub fn new(max_quantity: u64) -> Self {
+        Self { max_quantity }
+    }
+}
+
+impl RiskChecker for MaxOrderQuantity {
+    fn check(&self, order: &RiskOrder, _context: &RiskContext) -> Result<(), String> {
+        if order.quantity > self.max_quantity {
+            return Err(format!(
+                "quantity {} exceeds the maximum of {}",
+                order.quantity, self.max_quantity
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Rejects orders whose notional value, price times quantity, exceeds a maximum.
+///
+/// Market orders are valued at the best opposite price and pass when the
+/// opposite side is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaxNotional {
+    /// Largest accepted notional value
+    pub max_notional: u128,
+}
+
+impl MaxNotional {
+    /// Create a check rejecting orders worth more than `max_notional`
+    pub fn new(max_notional: u128) -> Self {
+        Self { max_notional }
+    }
+}
+
+impl RiskChecker for MaxNotional {
+    fn check(&self, order: &RiskOrder, context: &RiskContext) -> Result<(), String> {
+        let Some(price) = order.price.or_else(|| context.opposite_price(order.side)) else {
+            return Ok(());
+        };
+        let notional = u128::from(price) * u128::from(order.quantity);
+        if notional > self.max_notional {
+            return Err(format!(
+                "notional {notional} exceeds the maximum of {}",
+                self.max_notional
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Fat-finger collar rejecting limit orders priced too far through the market.
+///
+/// Buy orders may not be priced more than `max_deviation_bps` basis points above
+/// the best ask, and sell orders no more than that below the best bid. When the
+/// opposite side is empty the best price on the order's own side is used, and
+/// the check passes on an empty book or for market orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceCollar {
+    /// Largest accepted deviation from the reference price, in basis points
+    pub max_deviation_bps: u64,
+}
+
+impl PriceCollar {
+    /// Create a collar of `max_deviation_bps` basis points around the best price
+    pub fn new(max_deviation_bps: u64) -> Self {
+        Self { max_deviation_bps }
+    }
+}
+
+impl RiskChecker for PriceCollar {
+    fn check(&self, order: &RiskOrder, context: &RiskContext) -> Result<(), String> {
+        let Some(price) = order.price else {
+            return Ok(());
+        };
+        let Some(reference) = context
+            .opposite_price(order.side)
+            .or_else(|| context.same_side_price(order.side))
+        else {
+            return Ok(());
+        };
+        let deviation =
+            (u128::from(reference) * u128::from(self.max_deviation_bps) / 10_000) as u64;
+        let (outside, limit) = match order.side {
+            Side::Buy => {
+                let limit = reference.saturating_add(deviation);
+                (price > limit, limit)
+            }
+            Side::Sell => {
+                let limit = reference.saturating_sub(deviation);
+                (price < limit, limit)
+            }
+        };
+        if outside {
+            return Err(format!(
+                "price {price} is beyond the collar limit of {limit} around {reference}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Runs `checker` on every new order, after the checks added before it
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{MaxOrderQuantity, OrderBook, OrderBookError, PriceCollar};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    /// use std::sync::Arc;
+    ///
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// book.add_risk_checker(Arc::new(MaxOrderQuantity::new(100)));
+    /// book.add_risk_checker(Arc::new(PriceCollar::new(1_000)));
+    /// book.add_limit_order(OrderId::new(), 1_000, 10, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// let too_large = book.add_limit_order(OrderId::new(), 990, 500, Side::Buy, TimeInForce::Gtc, None);
+    /// assert!(matches!(too_large, Err(OrderBookError::RiskRejected { .. })));
+    /// let fat_finger = book.add_limit_order(OrderId::new(), 2_000, 1, Side::Buy, TimeInForce::Gtc, None);
+    /// assert!(matches!(fat_finger, Err(OrderBookError::RiskRejected { .. })));
+    /// ```
+    pub fn add_risk_checker(&mut self, checker: Arc<dyn RiskChecker>) {
+        self.risk_checkers.push(checker);
+    }
+
+    /// Removes every risk check of the book
+    pub fn clear_risk_checkers(&mut self) {
+        self.risk_checkers.clear();
+    }
+
+    /// Risk checks run on every new order, in order
+    #[must_use]
+    pub fn risk_checkers(&self) -> &[Arc<dyn RiskChecker>] {
+        &self.risk_checkers
+    }
+
+    /// Market state passed to the risk checks
+    #[must_use]
+    pub fn risk_context(&self) -> RiskContext {
+        RiskContext {
+            best_bid: self.best_bid(),
+            best_ask: self.best_ask(),
+            last_trade_price: self.last_trade_price(),
+        }
+    }
+
+    /// Runs every risk check on `order`, stopping at the first rejection
+    pub(super) fn check_risk(&self, order: &RiskOrder) -> Result<(), OrderBookError> {
+        if self.risk_checkers.is_empty() {
+            return Ok(());
+        }
+        let context = self.risk_context();
+        for checker in &self.risk_checkers {
+            checker
+                .check(order, &context)
+                .map_err(|reason| OrderBookError::RiskRejected {
+                    order_id: order.order_id,
+                    reason,
+                })?;
+        }
+        Ok(())
+    }
+}
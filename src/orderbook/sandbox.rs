@@ -0,0 +1,328 @@
+//! What-if sandbox: hypothetical orders applied to an overlay of a live book.
+
+use super::book::OrderBook;
+use super::iterators::{Directed, LevelIter};
+use super::market_impact::{MarketImpact, OrderSimulation};
+use crossbeam_skiplist::map::Entry;
+use pricelevel::{OrderId, PriceLevel, Side};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet, btree_map};
+use std::iter::{Map, Peekable};
+use std::sync::Arc;
+
+/// A copy-on-write view of an [`OrderBook`] for what-if analysis.
+///
+/// The sandbox borrows the book and records hypothetical orders and
+/// cancellations as quantity changes per price level, so creating one is free
+/// and the live book is never modified. Every query reads the levels of the
+/// book merged with those changes.
+///
+/// The view works on aggregated price levels, like the depth queries of the
+/// book: hypothetical fills reduce level quantities without tracking which
+/// resting orders they consumed, and fully hidden orders are not part of it.
+pub struct OrderBookSandbox<'a, T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    book: &'a OrderBook<T>,
+    bids: BTreeMap<u64, i128>,
+    asks: BTreeMap<u64, i128>,
+    cancelled: HashSet<OrderId>,
+}
+
+impl<'a, T> OrderBookSandbox<'a, T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Create an empty overlay of `book`
+    #[must_use]
+    pub fn new(book: &'a OrderBook<T>) -> Self {
+        Self {
+            book,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            cancelled: HashSet::new(),
+        }
+    }
+
+    /// The live book under the overlay
+    #[must_use]
+    pub fn book(&self) -> &'a OrderBook<T> {
+        self.book
+    }
+
+    /// Whether no hypothetical order or cancellation has been applied
+    #[must_use]
+    pub fn is_unchanged(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty() && self.cancelled.is_empty()
+    }
+
+    /// Drop every hypothetical change, going back to the live book
+    pub fn reset(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+        self.cancelled.clear();
+    }
+
+    /// Apply a hypothetical limit order
+    ///
+    /// The order matches the opposite side of the view up to `price`, and the
+    /// remainder rests at `price`.
+    ///
+    /// # Returns
+    /// The fills of the order, with the quantity left resting in `remaining_quantity`
+    pub fn add_limit_order(&mut self, price: u64, quantity: u64, side: Side) -> OrderSimulation {
+        let simulation = self.execute(Some(price), quantity, side);
+        if simulation.remaining_quantity > 0 {
+            self.adjust(side, price, i128::from(simulation.remaining_quantity));
+        }
+        simulation
+    }
+
+    /// Apply a hypothetical market order
+    ///
+    /// # Returns
+    /// The fills of the order, with the quantity left unfilled in `remaining_quantity`
+    pub fn submit_market_order(&mut self, quantity: u64, side: Side) -> OrderSimulation {
+        self.execute(None, quantity, side)
+    }
+
+    /// Apply a hypothetical cancellation of a resting order of the live book
+    ///
+    /// # Returns
+    /// `true` if the order rests in the book and was not cancelled in the sandbox before
+    pub fn cancel_order(&mut self, order_id: OrderId) -> bool {
+        if self.cancelled.contains(&order_id) {
+            return false;
+        }
+        let Some(order) = self.book.get_order(order_id) else {
+            return false;
+        };
+        if !self.book.is_hidden_order(order_id) {
+            let quantity = order.visible_quantity() + order.hidden_quantity();
+            self.adjust(order.side(), order.price(), -i128::from(quantity));
+        }
+        self.cancelled.insert(order_id);
+        true
+    }
+
+    /// Price levels of `side` in the view, as (price, quantity) pairs, best first
+    pub fn levels(&self, side: Side) -> impl Iterator<Item = (u64, u64)> + '_ {
+        let (book_levels, changes) = match side {
+            Side::Buy => (&self.book.bids, &self.bids),
+            Side::Sell => (&self.book.asks, &self.asks),
+        };
+        let base: BaseLevels<'_> = LevelIter::best_first(book_levels.iter(), side)
+            .map(|entry| (*entry.key(), entry.value().total_quantity()));
+        let overlay: OverlayLevels<'_> = Directed::best_first(changes.iter(), side);
+        MergedLevels {
+            base: base.peekable(),
+            overlay: overlay.peekable(),
+            ascending: side == Side::Sell,
+        }
+    }
+
+    /// Best bid of the view
+    #[must_use]
+    pub fn best_bid(&self) -> Option<u64> {
+        self.levels(Side::Buy).next().map(|(price, _)| price)
+    }
+
+    /// Best ask of the view
+    #[must_use]
+    pub fn best_ask(&self) -> Option<u64> {
+        self.levels(Side::Sell).next().map(|(price, _)| price)
+    }
+
+    /// Mid price of the view
+    #[must_use]
+    pub fn mid_price(&self) -> Option<f64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some((bid as f64 + ask as f64) / 2.0),
+            _ => None,
+        }
+    }
+
+    /// Spread of the view
+    #[must_use]
+    pub fn spread(&self) -> Option<u64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some(ask.saturating_sub(bid)),
+            _ => None,
+        }
+    }
+
+    /// Total quantity in the top `levels` of `side` of the view
+    #[must_use]
+    pub fn total_depth_at_levels(&self, levels: usize, side: Side) -> u64 {
+        self.levels(side)
+            .take(levels)
+            .map(|(_, quantity)| quantity)
+            .sum()
+    }
+
+    /// Imbalance of the top `levels` of the view, as in [`OrderBook::order_book_imbalance`]
+    #[must_use]
+    pub fn order_book_imbalance(&self, levels: usize) -> f64 {
+        let bid_volume = self.total_depth_at_levels(levels, Side::Buy) as f64;
+        let ask_volume = self.total_depth_at_levels(levels, Side::Sell) as f64;
+        let total_volume = bid_volume + ask_volume;
+        if total_volume == 0.0 {
+            return 0.0;
+        }
+        (bid_volume - ask_volume) / total_volume
+    }
+
+    /// Market impact of an order on the view, as in [`OrderBook::market_impact`]
+    #[must_use]
+    pub fn market_impact(&self, quantity: u64, side: Side) -> MarketImpact {
+        MarketImpact::from_levels(self.levels(side.opposite()), quantity, side)
+    }
+
+    /// VWAP of an order on the view, as in [`OrderBook::vwap`]
+    #[must_use]
+    pub fn vwap(&self, quantity: u64, side: Side) -> Option<f64> {
+        let impact = self.market_impact(quantity, side);
+        (quantity > 0 && impact.can_fill(quantity)).then_some(impact.avg_price)
+    }
+
+    fn execute(&mut self, limit: Option<u64>, quantity: u64, side: Side) -> OrderSimulation {
+        let crosses = |price: u64| match (side, limit) {
+            (_, None) => true,
+            (Side::Buy, Some(limit)) => price <= limit,
+            (Side::Sell, Some(limit)) => price >= limit,
+        };
+
+        let mut remaining = quantity;
+        let mut fills = Vec::new();
+        for (price, available) in self.levels(side.opposite()) {
+            if remaining == 0 || !crosses(price) {
+                break;
+            }
+            let fill_qty = remaining.min(available);
+            fills.push((price, fill_qty));
+            remaining -= fill_qty;
+        }
+
+        let mut total_cost = 0u128;
+        for &(price, fill_qty) in &fills {
+            self.adjust(side.opposite(), price, -i128::from(fill_qty));
+            total_cost = total_cost.saturating_add((price as u128) * (fill_qty as u128));
+        }
+        let total_filled = quantity - remaining;
+
+        OrderSimulation {
+            fills,
+            avg_price: if total_filled > 0 {
+                total_cost as f64 / total_filled as f64
+            } else {
+                0.0
+            },
+            total_filled,
+            remaining_quantity: remaining,
+        }
+    }
+
+    fn adjust(&mut self, side: Side, price: u64, delta: i128) {
+        let overlay = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        let change = overlay.entry(price).or_insert(0);
+        *change += delta;
+        if *change == 0 {
+            overlay.remove(&price);
+        }
+    }
+}
+
+type BaseLevels<'s> = Map<LevelIter<'s>, fn(Entry<'s, u64, Arc<PriceLevel>>) -> (u64, u64)>;
+type OverlayLevels<'s> = Directed<btree_map::Iter<'s, u64, i128>>;
+
+/// Levels of the book merged with the changes of a sandbox, in price-priority order
+struct MergedLevels<'s> {
+    base: Peekable<BaseLevels<'s>>,
+    overlay: Peekable<OverlayLevels<'s>>,
+    ascending: bool,
+}
+
+impl Iterator for MergedLevels<'_> {
+    type Item = (u64, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let next = match (self.base.peek(), self.overlay.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => self
+                    .base
+                    .next()
+                    .map(|(price, quantity)| (price, i128::from(quantity))),
+                (None, Some(_)) => self.overlay.next().map(|(&price, &change)| (price, change)),
+                (Some(&(base_price, _)), Some(&(&overlay_price, _))) => {
+                    let order = base_price.cmp(&overlay_price);
+                    let order = if self.ascending {
+                        order
+                    } else {
+                        order.reverse()
+                    };
+                    match order {
+                        Ordering::Less => self
+                            .base
+                            .next()
+                            .map(|(price, quantity)| (price, i128::from(quantity))),
+                        Ordering::Greater => {
+                            self.overlay.next().map(|(&price, &change)| (price, change))
+                        }
+                        Ordering::Equal => {
+                            let (price, quantity) = self.base.next()?;
+                            let (_, &change) = self.overlay.next()?;
+                            Some((price, i128::from(quantity) + change))
+                        }
+                    }
+                }
+            };
+            if let Some((price, quantity)) = next
+                && quantity > 0
+            {
+                return Some((price, quantity as u64));
+            }
+        }
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Opens a what-if sandbox over the book
+    ///
+    /// Hypothetical orders and cancellations applied to the sandbox change
+    /// only its view, so spread, imbalance and market impact can be compared
+    /// before and after without cloning or touching the book.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let _ = book.add_limit_order(OrderId::new(), 99, 10, Side::Buy, TimeInForce::Gtc, None);
+    /// let ask = OrderId::new();
+    /// let _ = book.add_limit_order(ask, 101, 10, Side::Sell, TimeInForce::Gtc, None);
+    /// let _ = book.add_limit_order(OrderId::new(), 103, 10, Side::Sell, TimeInForce::Gtc, None);
+    ///
+    /// let mut sandbox = book.sandbox();
+    /// sandbox.cancel_order(ask);
+    /// sandbox.add_limit_order(100, 5, Side::Buy);
+    ///
+    /// assert_eq!(sandbox.spread(), Some(3));
+    /// assert_eq!(sandbox.market_impact(10, Side::Buy).worst_price, 103);
+    ///
+    /// // The live book is unchanged
+    /// assert_eq!(book.spread(), Some(2));
+    /// ```
+    #[must_use]
+    pub fn sandbox(&self) -> OrderBookSandbox<'_, T> {
+        OrderBookSandbox::new(self)
+    }
+}
@@ -0,0 +1,465 @@
+//! Periodic snapshots of managed books with a retention policy.
+//!
+//! A [`SnapshotScheduler`] decides when a book is due for a snapshot, writes the
+//! checksum-protected package to a [`SnapshotStore`] and prunes old snapshots. It
+//! does not run a timer of its own: call [`SnapshotScheduler::run_due`] (or
+//! [`SnapshotScheduler::snapshot_if_due`]) from the application's event loop.
+
+use super::book::OrderBook;
+use super::error::OrderBookError;
+use super::manager::BookManager;
+use super::snapshot::{OrderBookSnapshotPackage, SnapshotFormat};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::trace;
+
+/// When a book becomes due for its next snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotTrigger {
+    /// At least this many milliseconds, measured with the book's clock, have passed
+    /// since the previous snapshot
+    IntervalMillis(u64),
+    /// At least this many price level changes happened since the previous snapshot
+    EveryEvents(u64),
+}
+
+/// Destination of the snapshots taken by a [`SnapshotScheduler`].
+///
+/// Snapshots are grouped by symbol and identified by a number that increases with
+/// every snapshot saved for that symbol.
+pub trait SnapshotStore: Send + Sync {
+    /// Persists `package` for `symbol` and returns its identifier
+    fn save(&self, symbol: &str, package: &OrderBookSnapshotPackage)
+    -> Result<u64, OrderBookError>;
+
+    /// Identifiers of the snapshots stored for `symbol`, oldest first
+    fn list(&self, symbol: &str) -> Result<Vec<u64>, OrderBookError>;
+
+    /// Loads the snapshot `id` of `symbol`
+    fn load(&self, symbol: &str, id: u64) -> Result<OrderBookSnapshotPackage, OrderBookError>;
+
+    /// Deletes the snapshot `id` of `symbol`
+    fn delete(&self, symbol: &str, id: u64) -> Result<(), OrderBookError>;
+
+    /// Loads the most recent snapshot of `symbol`, if any
+    fn load_latest(
+        &self,
+        symbol: &str,
+    ) -> Result<Option<OrderBookSnapshotPackage>, OrderBookError> {
+        match self.list(symbol)?.last() {
+            Some(&id) => self.load(symbol, id).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Snapshot store keeping every package in memory.
+#[derive(Debug, Default)]
+pub struct InMemorySnapshotStore {
+    snapshots: Mutex<HashMap<String, BTreeMap<u64, OrderBookSnapshotPackage>>>,
+}
+
+impl InMemorySnapshotStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SnapshotStore for InMemorySnapshotStore {
+    fn save(
+        &self,
+        symbol: &str,
+        package: &OrderBookSnapshotPackage,
+    ) -> Result<u64, OrderBookError> {
+        let mut snapshots = self.snapshots.lock().unwrap_or_else(|e| e.into_inner());
+        let stored = snapshots.entry(symbol.to_string()).or_default();
+        let id = stored.keys().next_back().map_or(1, |last| last + 1);
+        stored.insert(id, package.clone());
+        Ok(id)
+    }
+
+    fn list(&self, symbol: &str) -> Result<Vec<u64>, OrderBookError> {
+        let snapshots = self.snapshots.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(snapshots
+            .get(symbol)
+            .map(|stored| stored.keys().copied().collect())
+            .unwrap_or_default())
+    }
+
+    fn load(&self, symbol: &str, id: u64) -> Result<OrderBookSnapshotPackage, OrderBookError> {
+        let snapshots = self.snapshots.lock().unwrap_or_else(|e| e.into_inner());
+        snapshots
+            .get(symbol)
+            .and_then(|stored| stored.get(&id))
+            .cloned()
+            .ok_or_else(|| missing_snapshot(symbol, id))
+    }
+
+    fn delete(&self, symbol: &str, id: u64) -> Result<(), OrderBookError> {
+        let mut snapshots = self.snapshots.lock().unwrap_or_else(|e| e.into_inner());
+        snapshots
+            .get_mut(symbol)
+            .and_then(|stored| stored.remove(&id))
+            .map(|_| ())
+            .ok_or_else(|| missing_snapshot(symbol, id))
+    }
+}
+
+/// Snapshot store writing one file per snapshot.
+///
+/// Snapshots of a symbol live in their own directory below the root, named after
+/// their zero-padded identifier. Files are written to a temporary name first and
+/// renamed once complete, so a crash never leaves a truncated snapshot behind.
+#[derive(Debug)]
+pub struct FileSnapshotStore {
+    root: PathBuf,
+    format: SnapshotFormat,
+    /// Serializes identifier allocation between concurrent saves
+    save_lock: Mutex<()>,
+}
+
+impl FileSnapshotStore {
+    /// Open the store rooted at `root`, creating the directory if needed
+    ///
+    /// Snapshots are written in the binary format; see [`FileSnapshotStore::with_format`].
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::SnapshotStoreError` if the directory cannot be created.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, OrderBookError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root).map_err(store_error)?;
+        Ok(Self {
+            root,
+            format: SnapshotFormat::Binary,
+            save_lock: Mutex::new(()),
+        })
+    }
+
+    /// Sets the format new snapshots are written in
+    ///
+    /// Only snapshots stored in this format are listed and loaded.
+    #[must_use]
+    pub fn with_format(mut self, format: SnapshotFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Root directory of the store
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn extension(&self) -> &'static str {
+        match self.format {
+            SnapshotFormat::Json => "json",
+            SnapshotFormat::Binary => "bin",
+        }
+    }
+
+    /// Directory of `symbol`, with every byte outside `[A-Za-z0-9._-]` percent-encoded
+    /// so symbols such as `BTC/USD` map to a single path component.
+    fn symbol_dir(&self, symbol: &str) -> PathBuf {
+        let mut name = String::with_capacity(symbol.len());
+        for byte in symbol.bytes() {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'_' | b'-') {
+                name.push(byte as char);
+            } else {
+                name.push_str(&format!("%{byte:02X}"));
+            }
+        }
+        self.root.join(name)
+    }
+
+    fn snapshot_path(&self, symbol: &str, id: u64) -> PathBuf {
+        self.symbol_dir(symbol)
+            .join(format!("{id:020}.{}", self.extension()))
+    }
+}
+
+impl SnapshotStore for FileSnapshotStore {
+    fn save(
+        &self,
+        symbol: &str,
+        package: &OrderBookSnapshotPackage,
+    ) -> Result<u64, OrderBookError> {
+        let data = package.encode(self.format)?;
+
+        let _guard = self.save_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = self.symbol_dir(symbol);
+        fs::create_dir_all(&dir).map_err(store_error)?;
+        let id = self.list(symbol)?.last().map_or(1, |last| last + 1);
+
+        let path = self.snapshot_path(symbol, id);
+        let partial = path.with_extension("partial");
+        fs::write(&partial, data).map_err(store_error)?;
+        fs::rename(&partial, &path).map_err(store_error)?;
+        Ok(id)
+    }
+
+    fn list(&self, symbol: &str) -> Result<Vec<u64>, OrderBookError> {
+        let entries = match fs::read_dir(self.symbol_dir(symbol)) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(store_error(error)),
+        };
+
+        let mut ids = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(store_error)?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(self.extension()) {
+                continue;
+            }
+            if let Some(id) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+            {
+                ids.push(id);
+            }
+        }
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    fn load(&self, symbol: &str, id: u64) -> Result<OrderBookSnapshotPackage, OrderBookError> {
+        let data = match fs::read(self.snapshot_path(symbol, id)) {
+            Ok(data) => data,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Err(missing_snapshot(symbol, id));
+            }
+            Err(error) => return Err(store_error(error)),
+        };
+        OrderBookSnapshotPackage::decode(&data, self.format)
+    }
+
+    fn delete(&self, symbol: &str, id: u64) -> Result<(), OrderBookError> {
+        match fs::remove_file(self.snapshot_path(symbol, id)) {
+            Ok(()) => Ok(()),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                Err(missing_snapshot(symbol, id))
+            }
+            Err(error) => Err(store_error(error)),
+        }
+    }
+}
+
+fn store_error(error: std::io::Error) -> OrderBookError {
+    OrderBookError::SnapshotStoreError {
+        message: error.to_string(),
+    }
+}
+
+fn missing_snapshot(symbol: &str, id: u64) -> OrderBookError {
+    OrderBookError::SnapshotStoreError {
+        message: format!("Snapshot {id} of {symbol} not found"),
+    }
+}
+
+/// Scheduling state of one book.
+#[derive(Debug)]
+struct ScheduleState {
+    /// Book time of the previous snapshot, `None` before the first one
+    last_snapshot: Option<u64>,
+    /// Price level changes since the previous snapshot
+    events: Arc<AtomicU64>,
+}
+
+/// Takes snapshots of books on an interval or every N events and keeps the most
+/// recent ones in a [`SnapshotStore`].
+///
+/// A book is always due the first time the scheduler sees it, which gives every
+/// book a baseline snapshot.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::{
+///     BookManager, BookManagerStd, InMemorySnapshotStore, SnapshotScheduler, SnapshotStore,
+///     SnapshotTrigger,
+/// };
+/// use std::sync::Arc;
+///
+/// let mut manager = BookManagerStd::<()>::new();
+/// manager.add_book("BTC/USD");
+///
+/// let store = Arc::new(InMemorySnapshotStore::new());
+/// let scheduler = SnapshotScheduler::new(store.clone(), SnapshotTrigger::IntervalMillis(1_000))
+///     .with_retention(3);
+///
+/// let taken = scheduler.run_due(&manager).unwrap();
+/// assert_eq!(taken.len(), 1);
+/// assert_eq!(store.list("BTC/USD").unwrap(), vec![1]);
+/// ```
+pub struct SnapshotScheduler {
+    store: Arc<dyn SnapshotStore>,
+    trigger: SnapshotTrigger,
+    keep_last: Option<usize>,
+    depth: usize,
+    states: Mutex<HashMap<String, ScheduleState>>,
+}
+
+impl SnapshotScheduler {
+    /// Create a scheduler writing full-depth snapshots to `store` whenever `trigger` fires
+    ///
+    /// Every snapshot is kept until a retention policy is set with
+    /// [`SnapshotScheduler::with_retention`].
+    pub fn new(store: Arc<dyn SnapshotStore>, trigger: SnapshotTrigger) -> Self {
+        Self {
+            store,
+            trigger,
+            keep_last: None,
+            depth: usize::MAX,
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Keeps only the `keep_last` most recent snapshots of each book (at least one)
+    #[must_use]
+    pub fn with_retention(mut self, keep_last: usize) -> Self {
+        self.keep_last = Some(keep_last.max(1));
+        self
+    }
+
+    /// Limits the number of price levels per side included in each snapshot
+    #[must_use]
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// The store snapshots are written to
+    pub fn store(&self) -> &Arc<dyn SnapshotStore> {
+        &self.store
+    }
+
+    /// Returns `true` if `book` is due for a snapshot
+    pub fn is_due<T>(&self, book: &OrderBook<T>) -> bool
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let mut states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        self.due(&mut states, book)
+    }
+
+    /// Snapshots `book` if it is due, returning the identifier of the stored snapshot
+    ///
+    /// # Errors
+    /// Returns an error if the snapshot cannot be encoded, stored or pruned.
+    pub fn snapshot_if_due<T>(&self, book: &OrderBook<T>) -> Result<Option<u64>, OrderBookError>
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        if !self.is_due(book) {
+            return Ok(None);
+        }
+        self.snapshot_now(book).map(Some)
+    }
+
+    /// Snapshots `book` immediately and applies the retention policy
+    ///
+    /// # Errors
+    /// Returns an error if the snapshot cannot be encoded, stored or pruned.
+    pub fn snapshot_now<T>(&self, book: &OrderBook<T>) -> Result<u64, OrderBookError>
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let mut states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        let state = self.state(&mut states, book);
+        // Events arriving while the snapshot is taken count towards the next one
+        let events = state.events.swap(0, Ordering::Relaxed);
+
+        let symbol = book.symbol();
+        let result = book
+            .create_snapshot_package(self.depth)
+            .and_then(|package| self.store.save(symbol, &package));
+        let id = match result {
+            Ok(id) => id,
+            Err(error) => {
+                state.events.fetch_add(events, Ordering::Relaxed);
+                return Err(error);
+            }
+        };
+        state.last_snapshot = Some(book.clock().now_millis());
+        trace!("Stored snapshot {} of {}", id, symbol);
+
+        if let Some(keep_last) = self.keep_last {
+            let ids = self.store.list(symbol)?;
+            for &old in ids.iter().take(ids.len().saturating_sub(keep_last)) {
+                self.store.delete(symbol, old)?;
+                trace!("Pruned snapshot {} of {}", old, symbol);
+            }
+        }
+
+        Ok(id)
+    }
+
+    /// Snapshots every book of `manager` that is due
+    ///
+    /// Returns the symbol and snapshot identifier of every snapshot taken.
+    ///
+    /// # Errors
+    /// Stops at and returns the first error.
+    pub fn run_due<T, M>(&self, manager: &M) -> Result<Vec<(String, u64)>, OrderBookError>
+    where
+        T: Clone + Send + Sync + Default + 'static,
+        M: BookManager<T>,
+    {
+        let mut symbols = manager.symbols();
+        symbols.sort();
+
+        let mut taken = Vec::new();
+        for symbol in symbols {
+            if let Some(book) = manager.get_book(&symbol)
+                && let Some(id) = self.snapshot_if_due(book)?
+            {
+                taken.push((symbol, id));
+            }
+        }
+        Ok(taken)
+    }
+
+    fn due<T>(&self, states: &mut HashMap<String, ScheduleState>, book: &OrderBook<T>) -> bool
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let state = self.state(states, book);
+        let Some(last_snapshot) = state.last_snapshot else {
+            return true;
+        };
+
+        match self.trigger {
+            SnapshotTrigger::IntervalMillis(interval) => {
+                book.clock().now_millis().saturating_sub(last_snapshot) >= interval
+            }
+            SnapshotTrigger::EveryEvents(events) => state.events.load(Ordering::Relaxed) >= events,
+        }
+    }
+
+    /// Scheduling state of `book`, subscribing to its level changes on first sight
+    /// when snapshots are triggered by events.
+    fn state<'a, T>(
+        &self,
+        states: &'a mut HashMap<String, ScheduleState>,
+        book: &OrderBook<T>,
+    ) -> &'a mut ScheduleState
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        states.entry(book.symbol().to_string()).or_insert_with(|| {
+            let events = Arc::new(AtomicU64::new(0));
+            if let SnapshotTrigger::EveryEvents(_) = self.trigger {
+                let counter = Arc::clone(&events);
+                book.subscribe_price_level_listener(Arc::new(move |_| {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }));
+            }
+            ScheduleState {
+                last_snapshot: None,
+                events,
+            }
+        })
+    }
+}
@@ -0,0 +1,336 @@
+//! Trading session state of a book and the rules it applies to incoming orders.
+
+use super::book::OrderBook;
+use super::error::OrderBookError;
+use super::journal::JournalCommand;
+use super::order_event::OrderEvent;
+use pricelevel::{OrderId, Side, TimeInForce};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use tracing::{error, trace};
+
+/// Phase of the trading session of a book.
+///
+/// | State     | New orders                  | Matching |
+/// |-----------|-----------------------------|----------|
+/// | `PreOpen` | accepted, except IOC/FOK    | no       |
+/// | `Open`    | accepted                    | yes      |
+/// | `Halted`  | accepted unless aggressive  | no       |
+/// | `Closed`  | rejected                    | no       |
+///
+/// Cancellations are accepted in every state, and closing the session purges the
+/// resting DAY orders.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SessionState {
+    /// Orders are collected for an opening or closing auction without being matched
+    PreOpen,
+    /// Continuous trading
+    #[default]
+    Open,
+    /// Trading is suspended; only orders that cannot trade are accepted
+    Halted,
+    /// The session is over and no new orders are accepted
+    Closed,
+}
+
+impl SessionState {
+    /// Whether the book may move from this state to `next`
+    ///
+    /// Every state can be closed and halted sessions can resume, either directly or
+    /// through a new pre-open phase. An open session moves back to pre-open for a
+    /// closing auction. A closed session only reopens through pre-open or directly
+    /// into continuous trading.
+    pub fn can_transition_to(self, next: SessionState) -> bool {
+        use SessionState::*;
+        matches!(
+            (self, next),
+            (PreOpen, Open | Halted | Closed)
+                | (Open, PreOpen | Halted | Closed)
+                | (Halted, PreOpen | Open | Closed)
+                | (Closed, PreOpen | Open)
+        )
+    }
+
+    /// Whether incoming orders are matched against the book
+    pub fn is_matching(self) -> bool {
+        self == SessionState::Open
+    }
+
+    /// Whether the book accepts new orders at all
+    pub fn accepts_orders(self) -> bool {
+        self != SessionState::Closed
+    }
+
+    pub(super) fn to_u8(self) -> u8 {
+        match self {
+            SessionState::PreOpen => 0,
+            SessionState::Open => 1,
+            SessionState::Halted => 2,
+            SessionState::Closed => 3,
+        }
+    }
+
+    pub(super) fn from_u8(value: u8) -> Self {
+        match value {
+            0 => SessionState::PreOpen,
+            2 => SessionState::Halted,
+            3 => SessionState::Closed,
+            _ => SessionState::Open,
+        }
+    }
+}
+
+impl fmt::Display for SessionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SessionState::PreOpen => "PRE_OPEN",
+            SessionState::Open => "OPEN",
+            SessionState::Halted => "HALTED",
+            SessionState::Closed => "CLOSED",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A change of the session state of a book.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionStateChangedEvent {
+    /// Symbol of the book
+    pub symbol: String,
+    /// State before the change
+    pub previous: SessionState,
+    /// State after the change
+    pub state: SessionState,
+    /// Time of the change, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// DAY orders cancelled because the session closed
+    pub purged_orders: Vec<OrderId>,
+}
+
+/// A listener for session state changes.
+pub type SessionListener = Arc<dyn Fn(&SessionStateChangedEvent) + Send + Sync>;
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Current session state of the book
+    ///
+    /// Books start in [`SessionState::Open`].
+    #[must_use]
+    pub fn session_state(&self) -> SessionState {
+        SessionState::from_u8(self.session_state.load(Ordering::Acquire))
+    }
+
+    /// Moves the book to another session state
+    ///
+    /// Closing the session cancels every resting DAY order, visible or hidden.
+    /// A book crossed by orders collected before the open must be uncrossed, see
+    /// [`OrderBook::open_with_auction`], before it opens for continuous trading.
+    /// The change is reported to the session listener and returned.
+    ///
+    /// # Errors
+    /// Returns [`OrderBookError::InvalidSessionTransition`] if the current state
+    /// cannot move to `state`, [`OrderBookError::SessionRejected`] if the book
+    /// would open crossed, or an error if the change cannot be journaled.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{OrderBook, SessionState};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// book.set_session_state(SessionState::Halted).unwrap();
+    /// book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Day, None).unwrap();
+    /// assert!(book.submit_market_order(OrderId::new(), 5, Side::Buy).is_err());
+    ///
+    /// let event = book.set_session_state(SessionState::Closed).unwrap();
+    /// assert_eq!(event.purged_orders.len(), 1);
+    /// assert_eq!(book.best_ask(), None);
+    /// ```
+    pub fn set_session_state(
+        &self,
+        state: SessionState,
+    ) -> Result<SessionStateChangedEvent, OrderBookError> {
+        let _journaled = self.begin_journaled(|| JournalCommand::SetSessionState { state })?;
+        let previous = self.session_state();
+        if !previous.can_transition_to(state) {
+            return Err(OrderBookError::InvalidSessionTransition {
+                from: previous,
+                to: state,
+            });
+        }
+        if state.is_matching() && self.indicative_uncross().is_some() {
+            return Err(OrderBookError::SessionRejected {
+                state: previous,
+                reason: "a crossed book must be uncrossed before it opens".to_string(),
+            });
+        }
+
+        self.session_state.store(state.to_u8(), Ordering::Release);
+        trace!(
+            "Order book {}: Session state changed from {} to {}",
+            self.symbol, previous, state
+        );
+
+        let purged_orders = if state == SessionState::Closed {
+            self.record_prior_close();
+            self.purge_day_orders()
+        } else {
+            Vec::new()
+        };
+
+        let event = SessionStateChangedEvent {
+            symbol: self.symbol.clone(),
+            previous,
+            state,
+            timestamp: self.clock.now_millis(),
+            purged_orders,
+        };
+        if let Some(ref listener) = self.session_listener {
+            listener(&event);
+        }
+        Ok(event)
+    }
+
+    /// set session state change listener for this order book
+    pub fn set_session_listener(&mut self, listener: SessionListener) {
+        self.session_listener = Some(listener);
+    }
+
+    /// remove session state change listener for this order book
+    pub fn remove_session_listener(&mut self) {
+        self.session_listener = None;
+    }
+
+    /// Checks an order that would rest at `price` on `side` against the session
+    /// state. `immediate` orders must trade on arrival.
+    pub(super) fn check_session(
+        &self,
+        price: u64,
+        side: Side,
+        immediate: bool,
+    ) -> Result<(), OrderBookError> {
+        let state = self.session_state();
+        let reason = match state {
+            SessionState::Open => return Ok(()),
+            SessionState::Closed => "the session is closed",
+            SessionState::PreOpen if immediate => "immediate orders cannot match before the open",
+            SessionState::Halted if immediate || self.will_cross_market(price, side) => {
+                "aggressive orders are rejected while trading is halted"
+            }
+            SessionState::PreOpen | SessionState::Halted => return Ok(()),
+        };
+        Err(OrderBookError::SessionRejected {
+            state,
+            reason: reason.to_string(),
+        })
+    }
+
+    /// Removes every resting DAY order once `now` reaches the market close
+    ///
+    /// Each removed order is reported as [`OrderEvent::Expired`]. Nothing is
+    /// removed without a market close timestamp or before the close. The book
+    /// also runs this sweep on its own clock before the next submission or match
+    /// after the close.
+    ///
+    /// Returns the IDs of the expired orders.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let close = u64::MAX - 1;
+    /// book.set_market_close_timestamp(close);
+    /// let day = OrderId::new();
+    /// book.add_limit_order(day, 100, 10, Side::Buy, TimeInForce::Day, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 99, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// assert!(book.expire_day_orders(close - 1).is_empty());
+    /// assert_eq!(book.expire_day_orders(close), vec![day]);
+    /// assert_eq!(book.best_bid(), Some(99));
+    /// ```
+    pub fn expire_day_orders(&self, now: u64) -> Vec<OrderId> {
+        let _journaled = match self.begin_journaled(|| JournalCommand::ExpireDayOrders { now }) {
+            Ok(scope) => scope,
+            Err(e) => {
+                error!("Order book {}: {}", self.symbol, e);
+                return Vec::new();
+            }
+        };
+        if !self.has_market_close.load(Ordering::Acquire)
+            || now < self.market_close_timestamp.load(Ordering::Acquire)
+        {
+            return Vec::new();
+        }
+        self.day_orders_expired.store(true, Ordering::Release);
+
+        let day_orders = self.resting_day_orders();
+        let expired: Vec<OrderId> = self.with_batched_level_changes(|| {
+            day_orders
+                .into_iter()
+                .filter(|order_id| {
+                    self.remove_order(*order_id)
+                        .is_ok_and(|order| self.recycle(order))
+                })
+                .collect()
+        });
+        for order_id in &expired {
+            self.emit_order_event(OrderEvent::Expired {
+                order_id: *order_id,
+            });
+        }
+        if !expired.is_empty() {
+            trace!(
+                "Order book {}: Expired {} DAY orders at market close",
+                self.symbol,
+                expired.len()
+            );
+        }
+        expired
+    }
+
+    /// Expires the resting DAY orders the first time the book is used after the
+    /// market close has passed on its clock
+    pub(super) fn expire_day_orders_if_closed(&self) {
+        if self.has_market_close.load(Ordering::Acquire)
+            && !self.day_orders_expired.load(Ordering::Acquire)
+        {
+            let now = self.clock.now_millis();
+            if now >= self.market_close_timestamp.load(Ordering::Acquire) {
+                self.expire_day_orders(now);
+            }
+        }
+    }
+
+    /// IDs of every resting DAY order, visible or hidden
+    fn resting_day_orders(&self) -> Vec<OrderId> {
+        [Side::Buy, Side::Sell]
+            .into_iter()
+            .flat_map(|side| [self.side_levels(side, false), self.side_levels(side, true)])
+            .flat_map(|levels| levels.iter())
+            .flat_map(|entry| entry.value().iter_orders())
+            .filter(|order| order.time_in_force() == TimeInForce::Day)
+            .map(|order| order.id())
+            .collect()
+    }
+
+    /// Cancels every resting DAY order, returning their IDs
+    fn purge_day_orders(&self) -> Vec<OrderId> {
+        let day_orders = self.resting_day_orders();
+
+        self.with_batched_level_changes(|| {
+            day_orders
+                .into_iter()
+                .filter(|order_id| {
+                    self.cancel_resting_order(*order_id)
+                        .is_ok_and(|order| self.recycle(order))
+                })
+                .collect()
+        })
+    }
+}
@@ -0,0 +1,412 @@
+//! Book manager spreading books over a fixed pool of worker threads.
+//!
+//! With thousands of symbols, a thread per book is wasteful and a single trade
+//! processor becomes a bottleneck. [`ShardedBookManager`] hashes every symbol to
+//! one of N shards. Each shard is a worker thread that owns its books, handles
+//! the commands sent to them from a bounded channel and passes the trades they
+//! execute to the trade event handler. A symbol always maps to the same shard,
+//! so commands and trades of one symbol keep their order while different shards
+//! run in parallel.
+
+use crate::orderbook::OrderBook;
+use crate::orderbook::error::OrderBookError;
+use crate::orderbook::manager::{LoggingTradeHandler, TradeEventHandler};
+use crate::orderbook::snapshot::OrderBookSnapshot;
+use crate::orderbook::symbol_config::{SymbolConfig, SymbolRegistry};
+use crate::orderbook::trade::{TradeEvent, TradeListener, TradeResult};
+use crate::utils::{Clock, SystemClock};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::JoinHandle;
+use tracing::{error, info};
+
+/// Default number of commands a shard buffers before senders block.
+pub const SHARD_COMMAND_CAPACITY: usize = 4096;
+
+/// Work run by a shard against one of its books, which is `None` if the shard
+/// has no book for the symbol.
+type ShardJob<T> = Box<dyn FnOnce(Option<&mut OrderBook<T>>) + Send>;
+
+/// A command sent to a shard.
+enum ShardCommand<T> {
+    /// Create the book of a symbol
+    AddBook {
+        symbol: String,
+        config: SymbolConfig,
+    },
+    /// Remove the book of a symbol and send it back
+    RemoveBook {
+        symbol: String,
+        reply: mpsc::Sender<Option<OrderBook<T>>>,
+    },
+    /// Run a job against the book of a symbol
+    Execute { symbol: String, job: ShardJob<T> },
+    /// Stop once the commands queued before this one were handled
+    Stop,
+}
+
+/// A worker thread and the sender of its commands.
+struct Shard<T> {
+    sender: SyncSender<ShardCommand<T>>,
+    thread: JoinHandle<HashMap<String, OrderBook<T>>>,
+}
+
+/// Book manager running books on a fixed pool of worker threads.
+///
+/// Threads are started when the first book is added, so the clock, handler and
+/// channel capacity must be set before. Every command waits for room in its
+/// shard's channel, so a busy shard applies backpressure to its producers.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::orderbook::sharded_manager::ShardedBookManager;
+/// use pricelevel::{OrderId, Side, TimeInForce};
+///
+/// let mut manager = ShardedBookManager::<()>::new(4);
+/// for symbol in ["BTC/USD", "ETH/USD", "SOL/USD"] {
+///     manager.add_book(symbol);
+/// }
+///
+/// manager
+///     .dispatch("ETH/USD", |book| {
+///         let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+///     })
+///     .unwrap();
+/// let best_ask = manager.execute("ETH/USD", |book| book.best_ask()).unwrap();
+/// assert_eq!(best_ask, Some(100));
+///
+/// let books = manager.shutdown();
+/// assert_eq!(books.len(), 3);
+/// ```
+pub struct ShardedBookManager<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Number of shards
+    shard_count: usize,
+    /// Worker threads, once started
+    shards: Vec<Shard<T>>,
+    /// Configurations of the managed symbols
+    registry: SymbolRegistry,
+    /// Handler of the trade events of every shard
+    handler: Arc<dyn TradeEventHandler>,
+    /// Clock shared by every managed book and used to timestamp trade events
+    clock: Arc<dyn Clock>,
+    /// Capacity of the command channel of each shard
+    command_capacity: usize,
+}
+
+impl<T> ShardedBookManager<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Create a manager spreading its books over `shard_count` worker threads
+    ///
+    /// # Panics
+    /// Panics if `shard_count` is zero.
+    pub fn new(shard_count: usize) -> Self {
+        assert!(shard_count > 0, "shard count must be positive");
+        Self {
+            shard_count,
+            shards: Vec::new(),
+            registry: SymbolRegistry::new(),
+            handler: Arc::new(LoggingTradeHandler),
+            clock: Arc::new(SystemClock),
+            command_capacity: SHARD_COMMAND_CAPACITY,
+        }
+    }
+
+    /// Uses `clock` for the books and trade events
+    ///
+    /// # Panics
+    /// Panics if the worker threads already started.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        assert!(self.shards.is_empty(), "shards already started");
+        self.clock = clock;
+        self
+    }
+
+    /// Routes trade events to `handler` instead of logging them
+    ///
+    /// The handler is called on the thread of the shard that executed the trade,
+    /// after the command that caused it.
+    ///
+    /// # Panics
+    /// Panics if the worker threads already started.
+    #[must_use]
+    pub fn with_handler(mut self, handler: Arc<dyn TradeEventHandler>) -> Self {
+        assert!(self.shards.is_empty(), "shards already started");
+        self.handler = handler;
+        self
+    }
+
+    /// Sets the number of commands each shard buffers before senders block
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero or the worker threads already started.
+    #[must_use]
+    pub fn with_command_capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "command capacity must be positive");
+        assert!(self.shards.is_empty(), "shards already started");
+        self.command_capacity = capacity;
+        self
+    }
+
+    /// Number of shards
+    pub fn shard_count(&self) -> usize {
+        self.shard_count
+    }
+
+    /// Shard the book of `symbol` runs on
+    pub fn shard_of(&self, symbol: &str) -> usize {
+        let mut hasher = DefaultHasher::new();
+        symbol.hash(&mut hasher);
+        (hasher.finish() % self.shard_count as u64) as usize
+    }
+
+    /// Adds a book for `symbol`, registered as a spot instrument without trading rules
+    pub fn add_book(&mut self, symbol: &str) {
+        self.add_book_with_config(symbol, SymbolConfig::default());
+    }
+
+    /// Adds a book for `symbol` that enforces the trading rules of `config`
+    ///
+    /// An existing book of the symbol is replaced.
+    pub fn add_book_with_config(&mut self, symbol: &str, config: SymbolConfig) {
+        self.start();
+        let command = ShardCommand::AddBook {
+            symbol: symbol.to_string(),
+            config,
+        };
+        if self.send(symbol, command).is_ok() {
+            self.registry.register(symbol, config);
+            info!("Added sharded order book for symbol: {}", symbol);
+        }
+    }
+
+    /// Removes the book of `symbol` once the commands sent before were handled
+    pub fn remove_book(&mut self, symbol: &str) -> Option<OrderBook<T>> {
+        self.registry.remove(symbol)?;
+        let (reply, book) = mpsc::channel();
+        let command = ShardCommand::RemoveBook {
+            symbol: symbol.to_string(),
+            reply,
+        };
+        self.send(symbol, command).ok()?;
+        let book = book.recv().ok().flatten();
+        if book.is_some() {
+            info!("Removed sharded order book for symbol: {}", symbol);
+        }
+        book
+    }
+
+    /// Whether the manager has a book for `symbol`
+    pub fn has_book(&self, symbol: &str) -> bool {
+        self.registry.contains(symbol)
+    }
+
+    /// Symbols of the managed books
+    pub fn symbols(&self) -> Vec<String> {
+        self.registry
+            .iter()
+            .map(|(symbol, _)| symbol.to_string())
+            .collect()
+    }
+
+    /// Number of managed books
+    pub fn book_count(&self) -> usize {
+        self.registry.len()
+    }
+
+    /// Configurations of the managed symbols
+    pub fn registry(&self) -> &SymbolRegistry {
+        &self.registry
+    }
+
+    /// Runs `job` against the book of `symbol` on its shard and waits for the result
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::InvalidOperation` if there is no book for `symbol`,
+    /// or `OrderBookError::BookStopped` if its shard stopped.
+    pub fn execute<R, F>(&self, symbol: &str, job: F) -> Result<R, OrderBookError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut OrderBook<T>) -> R + Send + 'static,
+    {
+        let (reply, result) = mpsc::channel();
+        self.dispatch_job(
+            symbol,
+            Box::new(move |book| {
+                // The caller only stops waiting if it panicked
+                let _ = reply.send(book.map(job));
+            }),
+        )?;
+        match result.recv() {
+            Ok(Some(value)) => Ok(value),
+            Ok(None) => Err(unknown_symbol(symbol)),
+            Err(_) => Err(stopped(symbol)),
+        }
+    }
+
+    /// Queues `job` against the book of `symbol` without waiting for it to run
+    ///
+    /// Jobs dispatched to one symbol run in the order they were dispatched.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::InvalidOperation` if there is no book for `symbol`,
+    /// or `OrderBookError::BookStopped` if its shard stopped.
+    pub fn dispatch<F>(&self, symbol: &str, job: F) -> Result<(), OrderBookError>
+    where
+        F: FnOnce(&mut OrderBook<T>) + Send + 'static,
+    {
+        self.dispatch_job(
+            symbol,
+            Box::new(move |book| {
+                if let Some(book) = book {
+                    job(book);
+                }
+            }),
+        )
+    }
+
+    /// Captures up to `depth` levels per side of the book of `symbol`
+    ///
+    /// # Errors
+    /// Fails like [`ShardedBookManager::execute`].
+    pub fn snapshot(
+        &self,
+        symbol: &str,
+        depth: usize,
+    ) -> Result<OrderBookSnapshot, OrderBookError> {
+        self.execute(symbol, move |book| book.create_snapshot(depth))
+    }
+
+    /// Stops every shard and returns the books by symbol
+    ///
+    /// Every command sent before the call is handled, and every trade it caused is
+    /// passed to the handler, before the shards stop.
+    pub fn shutdown(mut self) -> HashMap<String, OrderBook<T>> {
+        let books = self.stop();
+        info!("Sharded book manager shut down");
+        books
+    }
+
+    fn dispatch_job(&self, symbol: &str, job: ShardJob<T>) -> Result<(), OrderBookError> {
+        if !self.registry.contains(symbol) {
+            return Err(unknown_symbol(symbol));
+        }
+        let command = ShardCommand::Execute {
+            symbol: symbol.to_string(),
+            job,
+        };
+        self.send(symbol, command)
+    }
+
+    fn send(&self, symbol: &str, command: ShardCommand<T>) -> Result<(), OrderBookError> {
+        let shard = &self.shards[self.shard_of(symbol)];
+        shard.sender.send(command).map_err(|_| stopped(symbol))
+    }
+
+    fn start(&mut self) {
+        if !self.shards.is_empty() {
+            return;
+        }
+        for index in 0..self.shard_count {
+            let (sender, receiver) = mpsc::sync_channel(self.command_capacity);
+            let handler = Arc::clone(&self.handler);
+            let clock = Arc::clone(&self.clock);
+            let thread = std::thread::Builder::new()
+                .name(format!("orderbook-shard-{index}"))
+                .spawn(move || Self::run(index, receiver, handler, clock))
+                .expect("failed to spawn shard thread");
+            self.shards.push(Shard { sender, thread });
+        }
+        info!("Started {} book shards", self.shard_count);
+    }
+
+    fn stop(&mut self) -> HashMap<String, OrderBook<T>> {
+        let shards: Vec<Shard<T>> = self.shards.drain(..).collect();
+        for shard in &shards {
+            // A closed channel means the shard already ended
+            let _ = shard.sender.send(ShardCommand::Stop);
+        }
+        let mut books = HashMap::new();
+        for (index, shard) in shards.into_iter().enumerate() {
+            match shard.thread.join() {
+                Ok(shard_books) => books.extend(shard_books),
+                Err(_) => error!("Book shard {} panicked", index),
+            }
+        }
+        books
+    }
+
+    /// Handles the commands of one shard until stopped
+    fn run(
+        index: usize,
+        receiver: Receiver<ShardCommand<T>>,
+        handler: Arc<dyn TradeEventHandler>,
+        clock: Arc<dyn Clock>,
+    ) -> HashMap<String, OrderBook<T>> {
+        let (trade_sender, trade_receiver) = mpsc::channel::<TradeEvent>();
+        let mut books: HashMap<String, OrderBook<T>> = HashMap::new();
+
+        while let Ok(command) = receiver.recv() {
+            match command {
+                ShardCommand::AddBook { symbol, config } => {
+                    let sender = trade_sender.clone();
+                    let listener_clock = Arc::clone(&clock);
+                    let trade_listener: TradeListener =
+                        Arc::new(move |trade_result: &TradeResult| {
+                            // The receiver lives as long as the shard's books
+                            let _ = sender.send(TradeEvent {
+                                symbol: trade_result.symbol.clone(),
+                                trade_result: trade_result.clone(),
+                                timestamp: listener_clock.now_millis(),
+                            });
+                        });
+                    let mut book = OrderBook::with_trade_listener(&symbol, trade_listener);
+                    book.set_clock(Arc::clone(&clock));
+                    book.apply_config(config.book);
+                    books.insert(symbol, book);
+                }
+                ShardCommand::RemoveBook { symbol, reply } => {
+                    let _ = reply.send(books.remove(&symbol));
+                }
+                ShardCommand::Execute { symbol, job } => job(books.get_mut(&symbol)),
+                ShardCommand::Stop => break,
+            }
+
+            while let Ok(trade_event) = trade_receiver.try_recv() {
+                handler.handle(&trade_event);
+            }
+        }
+
+        info!("Book shard {} stopped", index);
+        books
+    }
+}
+
+impl<T> Drop for ShardedBookManager<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn unknown_symbol(symbol: &str) -> OrderBookError {
+    OrderBookError::InvalidOperation {
+        message: format!("No book for symbol {symbol}"),
+    }
+}
+
+fn stopped(symbol: &str) -> OrderBookError {
+    OrderBookError::BookStopped {
+        symbol: symbol.to_string(),
+    }
+}
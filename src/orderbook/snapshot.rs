@@ -1,9 +1,12 @@
 //! Order book snapshot for market data
 
 use bitflags::bitflags;
-use pricelevel::PriceLevelSnapshot;
+use crossbeam_skiplist::map::Entry;
+use pricelevel::{OrderType, PriceLevel, PriceLevelSnapshot};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::Arc;
 use tracing::trace;
 
 use super::error::OrderBookError;
@@ -22,6 +25,13 @@ pub struct OrderBookSnapshot {
 
     /// Snapshot of ask price levels
     pub asks: Vec<PriceLevelSnapshot>,
+
+    /// Serialized extra fields of the snapshot's orders, keyed by order ID.
+    ///
+    /// Only filled in by `OrderBook::create_snapshot_with_extra_fields`; plain
+    /// snapshots leave it empty and it is then omitted from the serialized form.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra_fields: BTreeMap<String, serde_json::Value>,
 }
 
 impl OrderBookSnapshot {
@@ -120,7 +130,74 @@ impl OrderBookSnapshot {
 }
 
 /// Format version used for checksum-enabled order book snapshots.
-pub const ORDERBOOK_SNAPSHOT_FORMAT_VERSION: u32 = 1;
+///
+/// Version history:
+/// - 1: price levels with their orders
+/// - 2: adds the per-order `extra_fields` map
+pub const ORDERBOOK_SNAPSHOT_FORMAT_VERSION: u32 = 2;
+
+/// Oldest snapshot format version that can still be migrated to the current one.
+pub const ORDERBOOK_SNAPSHOT_MIN_SUPPORTED_VERSION: u32 = 1;
+
+/// Upgrades a package by exactly one format version.
+type SnapshotMigration =
+    fn(OrderBookSnapshotPackage) -> Result<OrderBookSnapshotPackage, OrderBookError>;
+
+/// Migrations indexed by source version, starting at
+/// `ORDERBOOK_SNAPSHOT_MIN_SUPPORTED_VERSION`.
+const SNAPSHOT_MIGRATIONS: &[SnapshotMigration] = &[migrate_v1_to_v2];
+
+/// Version 1 packages could not carry extra fields, and their payload serializes
+/// identically under version 2, so the checksum carries over unchanged.
+fn migrate_v1_to_v2(
+    mut package: OrderBookSnapshotPackage,
+) -> Result<OrderBookSnapshotPackage, OrderBookError> {
+    if !package.snapshot.extra_fields.is_empty() {
+        return Err(OrderBookError::InvalidOperation {
+            message: "Version 1 snapshots cannot contain extra fields".to_string(),
+        });
+    }
+
+    package.version = 2;
+    Ok(package)
+}
+
+/// Encoding used to persist an `OrderBookSnapshotPackage`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SnapshotFormat {
+    /// Human readable JSON, see `OrderBookSnapshotPackage::to_json`
+    #[default]
+    Json,
+    /// Compact binary encoding, see `OrderBookSnapshotPackage::to_bytes`
+    Binary,
+}
+
+/// Captures `levels` into `snapshots` in iteration order, overwriting the
+/// existing level snapshots in place and dropping those left over.
+///
+/// The order lists are not reused: a level can only hand out its orders as a
+/// freshly collected vector, which replaces the previous one.
+pub(super) fn capture_levels<'a>(
+    levels: impl Iterator<Item = Entry<'a, u64, Arc<PriceLevel>>>,
+    snapshots: &mut Vec<PriceLevelSnapshot>,
+) {
+    let mut captured = 0;
+    for entry in levels {
+        let level = entry.value();
+        match snapshots.get_mut(captured) {
+            Some(snapshot) => {
+                snapshot.price = level.price();
+                snapshot.visible_quantity = level.visible_quantity();
+                snapshot.hidden_quantity = level.hidden_quantity();
+                snapshot.order_count = level.order_count();
+                snapshot.orders = level.iter_orders();
+            }
+            None => snapshots.push(level.snapshot()),
+        }
+        captured += 1;
+    }
+    snapshots.truncate(captured);
+}
 
 /// Wrapper that provides checksum validation for `OrderBookSnapshot` instances.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -161,6 +238,50 @@ impl OrderBookSnapshotPackage {
         })
     }
 
+    /// Serializes the package to a compact binary encoding.
+    ///
+    /// The checksum is carried over unchanged, so a package validates the same way
+    /// whichever format it was stored in.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, OrderBookError> {
+        postcard::to_allocvec(&BinaryPackage::from_package(self)?).map_err(|error| {
+            OrderBookError::SerializationError {
+                message: error.to_string(),
+            }
+        })
+    }
+
+    /// Deserializes the package from the binary encoding produced by `to_bytes`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, OrderBookError> {
+        postcard::from_bytes::<BinaryPackage>(data)
+            .map_err(|error| OrderBookError::DeserializationError {
+                message: error.to_string(),
+            })?
+            .into_package()
+    }
+
+    /// Serializes the package in the given format.
+    pub fn encode(&self, format: SnapshotFormat) -> Result<Vec<u8>, OrderBookError> {
+        match format {
+            SnapshotFormat::Json => self.to_json().map(String::into_bytes),
+            SnapshotFormat::Binary => self.to_bytes(),
+        }
+    }
+
+    /// Deserializes a package stored in the given format.
+    pub fn decode(data: &[u8], format: SnapshotFormat) -> Result<Self, OrderBookError> {
+        match format {
+            SnapshotFormat::Json => {
+                let json = std::str::from_utf8(data).map_err(|error| {
+                    OrderBookError::DeserializationError {
+                        message: error.to_string(),
+                    }
+                })?;
+                Self::from_json(json)
+            }
+            SnapshotFormat::Binary => Self::from_bytes(data),
+        }
+    }
+
     /// Validates the checksum and version.
     pub fn validate(&self) -> Result<(), OrderBookError> {
         if self.version != ORDERBOOK_SNAPSHOT_FORMAT_VERSION {
@@ -183,10 +304,45 @@ impl OrderBookSnapshotPackage {
         Ok(())
     }
 
-    /// Consumes the package and returns the validated snapshot.
+    /// Upgrades a package written by an older format version to the current one.
+    ///
+    /// Packages already at the current version are returned unchanged. Packages
+    /// older than `ORDERBOOK_SNAPSHOT_MIN_SUPPORTED_VERSION` or newer than
+    /// `ORDERBOOK_SNAPSHOT_FORMAT_VERSION` are rejected.
+    pub fn migrate(mut self) -> Result<Self, OrderBookError> {
+        if !(ORDERBOOK_SNAPSHOT_MIN_SUPPORTED_VERSION..=ORDERBOOK_SNAPSHOT_FORMAT_VERSION)
+            .contains(&self.version)
+        {
+            return Err(OrderBookError::InvalidOperation {
+                message: format!(
+                    "Unsupported snapshot version: {} (supported {}..={})",
+                    self.version,
+                    ORDERBOOK_SNAPSHOT_MIN_SUPPORTED_VERSION,
+                    ORDERBOOK_SNAPSHOT_FORMAT_VERSION
+                ),
+            });
+        }
+
+        while self.version < ORDERBOOK_SNAPSHOT_FORMAT_VERSION {
+            let from = self.version;
+            let migration =
+                SNAPSHOT_MIGRATIONS[(from - ORDERBOOK_SNAPSHOT_MIN_SUPPORTED_VERSION) as usize];
+            self = migration(self)?;
+            trace!(
+                "Migrated snapshot package from version {} to {}",
+                from, self.version
+            );
+        }
+
+        Ok(self)
+    }
+
+    /// Consumes the package, migrating it to the current format version, and
+    /// returns the validated snapshot.
     pub fn into_snapshot(self) -> Result<OrderBookSnapshot, OrderBookError> {
-        self.validate()?;
-        Ok(self.snapshot)
+        let package = self.migrate()?;
+        package.validate()?;
+        Ok(package.snapshot)
     }
 
     fn compute_checksum(snapshot: &OrderBookSnapshot) -> Result<String, OrderBookError> {
@@ -203,6 +359,105 @@ impl OrderBookSnapshotPackage {
     }
 }
 
+/// Binary wire form of `OrderBookSnapshotPackage`.
+///
+/// Binary encodings are not self-describing, so price levels are written as plain
+/// sequences and the extra fields as embedded JSON documents.
+#[derive(Serialize, Deserialize)]
+struct BinaryPackage {
+    version: u32,
+    checksum: String,
+    symbol: String,
+    timestamp: u64,
+    bids: Vec<BinaryLevel>,
+    asks: Vec<BinaryLevel>,
+    extra_fields: Vec<(String, String)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BinaryLevel {
+    price: u64,
+    visible_quantity: u64,
+    hidden_quantity: u64,
+    order_count: usize,
+    orders: Vec<OrderType<()>>,
+}
+
+impl BinaryLevel {
+    fn from_level(level: &PriceLevelSnapshot) -> Self {
+        Self {
+            price: level.price,
+            visible_quantity: level.visible_quantity,
+            hidden_quantity: level.hidden_quantity,
+            order_count: level.order_count,
+            orders: level.orders.iter().map(|order| **order).collect(),
+        }
+    }
+
+    fn into_level(self) -> PriceLevelSnapshot {
+        PriceLevelSnapshot {
+            price: self.price,
+            visible_quantity: self.visible_quantity,
+            hidden_quantity: self.hidden_quantity,
+            order_count: self.order_count,
+            orders: self.orders.into_iter().map(Arc::new).collect(),
+        }
+    }
+}
+
+impl BinaryPackage {
+    fn from_package(package: &OrderBookSnapshotPackage) -> Result<Self, OrderBookError> {
+        let snapshot = &package.snapshot;
+        let extra_fields = snapshot
+            .extra_fields
+            .iter()
+            .map(|(order_id, value)| {
+                serde_json::to_string(value)
+                    .map(|json| (order_id.clone(), json))
+                    .map_err(|error| OrderBookError::SerializationError {
+                        message: error.to_string(),
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            version: package.version,
+            checksum: package.checksum.clone(),
+            symbol: snapshot.symbol.clone(),
+            timestamp: snapshot.timestamp,
+            bids: snapshot.bids.iter().map(BinaryLevel::from_level).collect(),
+            asks: snapshot.asks.iter().map(BinaryLevel::from_level).collect(),
+            extra_fields,
+        })
+    }
+
+    fn into_package(self) -> Result<OrderBookSnapshotPackage, OrderBookError> {
+        let extra_fields = self
+            .extra_fields
+            .into_iter()
+            .map(|(order_id, json)| {
+                serde_json::from_str(&json)
+                    .map(|value| (order_id, value))
+                    .map_err(|error| OrderBookError::DeserializationError {
+                        message: error.to_string(),
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(OrderBookSnapshotPackage {
+            version: self.version,
+            snapshot: OrderBookSnapshot {
+                symbol: self.symbol,
+                timestamp: self.timestamp,
+                bids: self.bids.into_iter().map(BinaryLevel::into_level).collect(),
+                asks: self.asks.into_iter().map(BinaryLevel::into_level).collect(),
+                extra_fields,
+            },
+            checksum: self.checksum,
+        })
+    }
+}
+
 bitflags! {
     /// Flags for selecting which metrics to calculate in enriched snapshots
     ///
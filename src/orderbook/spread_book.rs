@@ -0,0 +1,309 @@
+//! Implied prices of a spread instrument traded against its two legs.
+
+use super::book::{DepthLevel, OrderBook};
+use super::book_change_event::PriceLevelChangedEvent;
+use super::subscription::SubscriptionId;
+use pricelevel::Side;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// An implied price and the quantity available at it.
+///
+/// Prices are signed because a spread, such as a calendar spread in backwardation,
+/// can trade below zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpliedQuote {
+    /// Implied price (in price units)
+    pub price: i64,
+    /// Quantity available at the price (in units)
+    pub quantity: u64,
+}
+
+/// Implied bid and ask of an instrument.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpliedQuotes {
+    /// Implied bid, if both contributing sides are quoted
+    pub bid: Option<ImpliedQuote>,
+    /// Implied ask, if both contributing sides are quoted
+    pub ask: Option<ImpliedQuote>,
+}
+
+/// One of the two legs of a spread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpreadLeg {
+    /// The leg bought when buying the spread
+    Front,
+    /// The leg sold when buying the spread
+    Back,
+}
+
+/// Best bid and ask of a book, as `(price, quantity)`
+#[derive(Debug, Clone, Copy, Default)]
+struct LegTop {
+    bid: Option<(i64, u64)>,
+    ask: Option<(i64, u64)>,
+}
+
+impl LegTop {
+    fn of<T>(book: &OrderBook<T>) -> Self
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let (bids, asks) = book.depth(1);
+        let top = |levels: &[DepthLevel]| {
+            levels
+                .first()
+                .map(|&(price, quantity, _)| (price as i64, quantity))
+        };
+        Self {
+            bid: top(&bids),
+            ask: top(&asks),
+        }
+    }
+
+    /// Whether the level change can move the top
+    fn is_affected_by(&self, event: &PriceLevelChangedEvent) -> bool {
+        let price = event.price as i64;
+        match event.side {
+            Side::Buy => self.bid.is_none_or(|(best, _)| price >= best),
+            Side::Sell => self.ask.is_none_or(|(best, _)| price <= best),
+        }
+    }
+}
+
+/// Leg tops and implied quotes, recalculated when a leg's top may have changed
+#[derive(Debug, Default)]
+struct ImpliedState {
+    dirty: AtomicBool,
+    recalculations: AtomicU64,
+    cache: Mutex<(LegTop, LegTop, ImpliedQuotes)>,
+}
+
+impl ImpliedState {
+    fn on_level_change(&self, leg: SpreadLeg, event: &PriceLevelChangedEvent) {
+        if self.dirty.load(Ordering::Acquire) {
+            return;
+        }
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let top = match leg {
+            SpreadLeg::Front => &cache.0,
+            SpreadLeg::Back => &cache.1,
+        };
+        if top.is_affected_by(event) {
+            self.dirty.store(true, Ordering::Release);
+        }
+    }
+}
+
+/// Implied book of a spread instrument, such as a calendar or inter-product spread.
+///
+/// Buying one spread buys one unit of the front leg and sells one unit of the back
+/// leg, so the spread is priced as `front - back`. The implied bid of the spread is
+/// reached by selling the front leg at its bid and buying the back leg at its ask,
+/// and the implied ask by the opposite trades. Implied prices are recalculated on
+/// the next read after a change that can move the best bid or ask of a leg; changes
+/// deeper in the leg books do not trigger a recalculation.
+///
+/// When an outright book of the spread is attached with
+/// [`SpreadBook::with_outright`], prices can also be implied the other way round,
+/// for a leg from the outright spread and the other leg.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::OrderBook;
+/// use orderbook_rs::orderbook::spread_book::SpreadBook;
+/// use pricelevel::{OrderId, Side, TimeInForce};
+/// use std::sync::Arc;
+///
+/// let june = Arc::new(OrderBook::<()>::new("ES-JUN"));
+/// let sept = Arc::new(OrderBook::<()>::new("ES-SEP"));
+/// june.add_limit_order(OrderId::new(), 5_010, 4, Side::Buy, TimeInForce::Gtc, None).unwrap();
+/// sept.add_limit_order(OrderId::new(), 5_030, 6, Side::Sell, TimeInForce::Gtc, None).unwrap();
+///
+/// let spread = SpreadBook::new("ES-JUN-SEP", june.clone(), sept.clone());
+/// let bid = spread.implied_bid().unwrap();
+/// assert_eq!((bid.price, bid.quantity), (-20, 4));
+///
+/// june.add_limit_order(OrderId::new(), 5_015, 2, Side::Buy, TimeInForce::Gtc, None).unwrap();
+/// assert_eq!(spread.implied_bid().unwrap().price, -15);
+/// ```
+pub struct SpreadBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    symbol: String,
+    front: Arc<OrderBook<T>>,
+    back: Arc<OrderBook<T>>,
+    outright: Option<(Arc<OrderBook<T>>, i64)>,
+    state: Arc<ImpliedState>,
+    subscriptions: (SubscriptionId, SubscriptionId),
+}
+
+impl<T> SpreadBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Create the spread `symbol` of buying `front` and selling `back`
+    pub fn new(symbol: &str, front: Arc<OrderBook<T>>, back: Arc<OrderBook<T>>) -> Self {
+        let state = Arc::new(ImpliedState::default());
+        state.dirty.store(true, Ordering::Release);
+
+        let subscribe = |book: &OrderBook<T>, leg: SpreadLeg| {
+            let state = Arc::clone(&state);
+            book.subscribe_price_level_listener(Arc::new(move |event| {
+                state.on_level_change(leg, &event)
+            }))
+        };
+        let subscriptions = (
+            subscribe(&front, SpreadLeg::Front),
+            subscribe(&back, SpreadLeg::Back),
+        );
+
+        Self {
+            symbol: symbol.to_string(),
+            front,
+            back,
+            outright: None,
+            state,
+            subscriptions,
+        }
+    }
+
+    /// Attaches the outright book of the spread, where resting price `p` stands
+    /// for a spread price of `p - price_offset`
+    ///
+    /// The offset lets an unsigned book carry negative spread prices.
+    #[must_use]
+    pub fn with_outright(mut self, book: Arc<OrderBook<T>>, price_offset: i64) -> Self {
+        self.outright = Some((book, price_offset));
+        self
+    }
+
+    /// Symbol of the spread
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Book of `leg`
+    pub fn leg(&self, leg: SpreadLeg) -> &Arc<OrderBook<T>> {
+        match leg {
+            SpreadLeg::Front => &self.front,
+            SpreadLeg::Back => &self.back,
+        }
+    }
+
+    /// Outright book of the spread, if attached
+    pub fn outright(&self) -> Option<&Arc<OrderBook<T>>> {
+        self.outright.as_ref().map(|(book, _)| book)
+    }
+
+    /// Spread bid and ask implied from the best prices of the legs
+    pub fn implied_quotes(&self) -> ImpliedQuotes {
+        let mut cache = self.state.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if self.state.dirty.swap(false, Ordering::AcqRel) {
+            let (front, back) = (LegTop::of(&self.front), LegTop::of(&self.back));
+            let quotes = ImpliedQuotes {
+                bid: combine(front.bid, back.ask, |f, b| f - b),
+                ask: combine(front.ask, back.bid, |f, b| f - b),
+            };
+            *cache = (front, back, quotes);
+            self.state.recalculations.fetch_add(1, Ordering::Relaxed);
+        }
+        cache.2
+    }
+
+    /// Spread bid implied from the legs
+    pub fn implied_bid(&self) -> Option<ImpliedQuote> {
+        self.implied_quotes().bid
+    }
+
+    /// Spread ask implied from the legs
+    pub fn implied_ask(&self) -> Option<ImpliedQuote> {
+        self.implied_quotes().ask
+    }
+
+    /// Best spread bid, outright or implied
+    pub fn best_bid(&self) -> Option<i64> {
+        let outright = self.outright_top().and_then(|top| top.bid);
+        let implied = self.implied_bid().map(|quote| quote.price);
+        outright.map(|(price, _)| price).max(implied)
+    }
+
+    /// Best spread ask, outright or implied
+    pub fn best_ask(&self) -> Option<i64> {
+        let outright = self.outright_top().and_then(|top| top.ask);
+        let implied = self.implied_ask().map(|quote| quote.price);
+        match (outright.map(|(price, _)| price), implied) {
+            (Some(outright), Some(implied)) => Some(outright.min(implied)),
+            (outright, implied) => outright.or(implied),
+        }
+    }
+
+    /// Bid and ask of `leg` implied from the outright spread and the other leg
+    ///
+    /// Empty when no outright book is attached.
+    pub fn implied_leg_quotes(&self, leg: SpreadLeg) -> ImpliedQuotes {
+        let Some(spread) = self.outright_top() else {
+            return ImpliedQuotes::default();
+        };
+        match leg {
+            SpreadLeg::Front => {
+                let back = LegTop::of(&self.back);
+                ImpliedQuotes {
+                    bid: combine(spread.bid, back.bid, |s, b| s + b),
+                    ask: combine(spread.ask, back.ask, |s, b| s + b),
+                }
+            }
+            SpreadLeg::Back => {
+                let front = LegTop::of(&self.front);
+                ImpliedQuotes {
+                    bid: combine(front.bid, spread.ask, |f, s| f - s),
+                    ask: combine(front.ask, spread.bid, |f, s| f - s),
+                }
+            }
+        }
+    }
+
+    /// Number of times the implied spread prices were recalculated
+    pub fn recalculations(&self) -> u64 {
+        self.state.recalculations.load(Ordering::Relaxed)
+    }
+
+    /// Best levels of the outright book, with prices shifted to spread prices
+    fn outright_top(&self) -> Option<LegTop> {
+        let (book, offset) = self.outright.as_ref()?;
+        let top = LegTop::of(book);
+        let shift =
+            |level: Option<(i64, u64)>| level.map(|(price, quantity)| (price - offset, quantity));
+        Some(LegTop {
+            bid: shift(top.bid),
+            ask: shift(top.ask),
+        })
+    }
+}
+
+impl<T> Drop for SpreadBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    fn drop(&mut self) {
+        self.front
+            .unsubscribe_price_level_listener(self.subscriptions.0);
+        self.back
+            .unsubscribe_price_level_listener(self.subscriptions.1);
+    }
+}
+
+/// Implied quote of two levels, sized by the smaller of them
+fn combine(
+    a: Option<(i64, u64)>,
+    b: Option<(i64, u64)>,
+    price: impl Fn(i64, i64) -> i64,
+) -> Option<ImpliedQuote> {
+    let ((a_price, a_quantity), (b_price, b_quantity)) = (a?, b?);
+    Some(ImpliedQuote {
+        price: price(a_price, b_price),
+        quantity: a_quantity.min(b_quantity),
+    })
+}
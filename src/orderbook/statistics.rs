@@ -4,7 +4,12 @@
 //! helping quantitative traders detect market conditions, identify trends,
 //! and make informed trading decisions.
 
+use super::book::OrderBook;
+use super::trade::TradeResult;
+use pricelevel::Side;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
 
 /// Depth statistics for one side of the order book
 ///
@@ -58,6 +63,50 @@ impl DepthStats {
     }
 }
 
+/// Depth statistics of both sides of the order book, with skew metrics
+///
+/// Returned by [`OrderBook::depth_statistics_both`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BookDepthStats {
+    /// Statistics of the bid side
+    pub bid: DepthStats,
+
+    /// Statistics of the ask side
+    pub ask: DepthStats,
+
+    /// Mid price between the best bid and ask (in price units)
+    pub mid_price: Option<f64>,
+
+    /// Bid volume divided by ask volume over the analyzed levels
+    pub volume_ratio: Option<f64>,
+
+    /// Distance from the mid price down to the bid weighted average price (in price units)
+    pub bid_distance_from_mid: Option<f64>,
+
+    /// Distance from the mid price up to the ask weighted average price (in price units)
+    pub ask_distance_from_mid: Option<f64>,
+}
+
+impl BookDepthStats {
+    /// Returns the volume imbalance `(bid - ask) / (bid + ask)`, between -1 and 1
+    #[must_use]
+    pub fn volume_imbalance(&self) -> f64 {
+        let total = self.bid.total_volume.saturating_add(self.ask.total_volume);
+        if total == 0 {
+            return 0.0;
+        }
+        (self.bid.total_volume as f64 - self.ask.total_volume as f64) / total as f64
+    }
+
+    /// Returns how much further the ask liquidity sits from the mid than the bid liquidity
+    ///
+    /// Positive when the bid volume is concentrated closer to the mid.
+    #[must_use]
+    pub fn distance_skew(&self) -> Option<f64> {
+        Some(self.ask_distance_from_mid? - self.bid_distance_from_mid?)
+    }
+}
+
 /// Distribution bin for depth distribution analysis
 ///
 /// Represents a price range and the total volume within that range.
@@ -90,6 +139,525 @@ impl DistributionBin {
     }
 }
 
+/// Estimator of realized volatility from sampled price bars
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VolatilityEstimator {
+    /// Standard deviation of the log returns between consecutive closes
+    CloseToClose,
+    /// Parkinson estimator from the high-low range of each bar
+    Parkinson,
+    /// Garman-Klass estimator from the open, high, low and close of each bar
+    GarmanKlass,
+}
+
+/// Prices fed to a realized volatility tracker of an order book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VolatilitySource {
+    /// Every execution price of the book
+    Trades,
+    /// The mid price, sampled by [`OrderBook::sample_mid_price`]
+    MidPrice,
+}
+
+/// Open, high, low and close prices of one sampling interval
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriceBar {
+    /// Start of the interval, in milliseconds
+    pub start: u64,
+    /// First price of the interval
+    pub open: f64,
+    /// Highest price of the interval
+    pub high: f64,
+    /// Lowest price of the interval
+    pub low: f64,
+    /// Last price of the interval
+    pub close: f64,
+}
+
+impl PriceBar {
+    fn new(start: u64, price: f64) -> Self {
+        Self {
+            start,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+        }
+    }
+
+    fn update(&mut self, price: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+    }
+}
+
+/// Rolling realized volatility over the last `window` sampling intervals
+///
+/// Prices are grouped into bars of `sampling_interval_ms` milliseconds; intervals
+/// without any price produce no bar. Volatilities are annualized assuming
+/// continuous trading over 365 days, matching the time to expiry used by the
+/// implied volatility module, so both can be compared directly.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::{RealizedVolatility, VolatilityEstimator};
+///
+/// let mut volatility = RealizedVolatility::new(60_000, 30);
+/// for (minute, price) in [100.0, 101.0, 99.5, 100.5].into_iter().enumerate() {
+///     volatility.record(minute as u64 * 60_000, price);
+/// }
+/// assert_eq!(volatility.bars().len(), 4);
+/// assert!(volatility.estimate(VolatilityEstimator::CloseToClose).unwrap() > 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RealizedVolatility {
+    sampling_interval_ms: u64,
+    window: usize,
+    bars: VecDeque<PriceBar>,
+}
+
+impl RealizedVolatility {
+    /// Milliseconds in the year used for annualization
+    pub const MILLIS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0 * 1_000.0;
+
+    /// Create a tracker keeping `window` bars of `sampling_interval_ms` milliseconds
+    #[must_use]
+    pub fn new(sampling_interval_ms: u64, window: usize) -> Self {
+        Self {
+            sampling_interval_ms: sampling_interval_ms.max(1),
+            window,
+            bars: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Length of a sampling interval, in milliseconds
+    #[must_use]
+    pub fn sampling_interval_ms(&self) -> u64 {
+        self.sampling_interval_ms
+    }
+
+    /// Maximum number of bars kept
+    #[must_use]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Records `price` observed at `timestamp`, in milliseconds
+    ///
+    /// Prices older than the current bar are ignored, as are non-positive prices.
+    pub fn record(&mut self, timestamp: u64, price: f64) {
+        if price <= 0.0 || !price.is_finite() || self.window == 0 {
+            return;
+        }
+        let start = timestamp - timestamp % self.sampling_interval_ms;
+        match self.bars.back_mut() {
+            Some(bar) if bar.start == start => bar.update(price),
+            Some(bar) if bar.start > start => {}
+            _ => {
+                if self.bars.len() == self.window {
+                    self.bars.pop_front();
+                }
+                self.bars.push_back(PriceBar::new(start, price));
+            }
+        }
+    }
+
+    /// Bars in the window, oldest first, including the bar still being built
+    #[must_use]
+    pub fn bars(&self) -> Vec<PriceBar> {
+        self.bars.iter().copied().collect()
+    }
+
+    /// Annualized realized volatility over the window
+    ///
+    /// Returns `None` until enough bars were recorded: two for the close-to-close
+    /// estimator, which needs two returns, and one for the range estimators.
+    #[must_use]
+    pub fn estimate(&self, estimator: VolatilityEstimator) -> Option<f64> {
+        self.estimate_per_interval(estimator).map(|volatility| {
+            volatility * (Self::MILLIS_PER_YEAR / self.sampling_interval_ms as f64).sqrt()
+        })
+    }
+
+    /// Realized volatility of a single sampling interval, without annualization
+    #[must_use]
+    pub fn estimate_per_interval(&self, estimator: VolatilityEstimator) -> Option<f64> {
+        let variance = match estimator {
+            VolatilityEstimator::CloseToClose => {
+                let returns: Vec<f64> = self
+                    .bars
+                    .iter()
+                    .zip(self.bars.iter().skip(1))
+                    .map(|(previous, bar)| (bar.close / previous.close).ln())
+                    .collect();
+                if returns.len() < 2 {
+                    return None;
+                }
+                let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+                returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64
+            }
+            VolatilityEstimator::Parkinson => {
+                if self.bars.is_empty() {
+                    return None;
+                }
+                self.bars
+                    .iter()
+                    .map(|bar| (bar.high / bar.low).ln().powi(2))
+                    .sum::<f64>()
+                    / (4.0 * std::f64::consts::LN_2 * self.bars.len() as f64)
+            }
+            VolatilityEstimator::GarmanKlass => {
+                if self.bars.is_empty() {
+                    return None;
+                }
+                let sum: f64 = self
+                    .bars
+                    .iter()
+                    .map(|bar| {
+                        0.5 * (bar.high / bar.low).ln().powi(2)
+                            - (2.0 * std::f64::consts::LN_2 - 1.0)
+                                * (bar.close / bar.open).ln().powi(2)
+                    })
+                    .sum();
+                (sum / self.bars.len() as f64).max(0.0)
+            }
+        };
+        Some(variance.sqrt())
+    }
+
+    /// Drops every bar
+    pub fn clear(&mut self) {
+        self.bars.clear();
+    }
+}
+
+/// Kyle's lambda estimated by regressing mid-price moves on signed trade flow
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LambdaEstimate {
+    /// Mid-price move per unit of signed volume, the slope of the regression
+    pub lambda: f64,
+    /// Share of the mid-price variance explained by the signed flow, between 0 and 1
+    pub r_squared: f64,
+    /// Number of intervals used by the regression
+    pub samples: usize,
+}
+
+/// Price impact per unit volume estimated from past trades and from the resting depth
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImpactEstimate {
+    /// Kyle's lambda over the tracked intervals, if enough intervals were recorded
+    pub kyle: Option<LambdaEstimate>,
+    /// Distance between the average fill price of a buy and the mid, per unit,
+    /// if the asks can fill the quantity
+    pub buy_depth_lambda: Option<f64>,
+    /// Distance between the mid and the average fill price of a sell, per unit,
+    /// if the bids can fill the quantity
+    pub sell_depth_lambda: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FlowInterval {
+    start: u64,
+    signed_volume: i64,
+    close_mid: Option<f64>,
+}
+
+/// Rolling estimator of Kyle's lambda over the last `window` sampling intervals
+///
+/// Each interval accumulates the signed volume of the executions, positive when
+/// a buy takes liquidity, and closes at the last mid price observed in it, or
+/// the close of the previous interval if the mid did not move. The estimate is
+/// the least-squares slope of the mid-price change of each interval on its
+/// signed volume.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::KyleLambda;
+/// use pricelevel::Side;
+///
+/// let mut kyle = KyleLambda::new(1_000, 50);
+/// kyle.record_mid(0, 100.0);
+/// for (second, (side, quantity, mid)) in [
+///     (Side::Buy, 10, 101.0),
+///     (Side::Sell, 20, 99.0),
+///     (Side::Buy, 40, 103.0),
+/// ]
+/// .into_iter()
+/// .enumerate()
+/// {
+///     let now = (second as u64 + 1) * 1_000;
+///     kyle.record_trade(now, side, quantity);
+///     kyle.record_mid(now, mid);
+/// }
+/// let estimate = kyle.estimate().unwrap();
+/// assert!((estimate.lambda - 0.1).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct KyleLambda {
+    sampling_interval_ms: u64,
+    window: usize,
+    intervals: VecDeque<FlowInterval>,
+}
+
+impl KyleLambda {
+    /// Create an estimator over `window` intervals of `sampling_interval_ms` milliseconds
+    #[must_use]
+    pub fn new(sampling_interval_ms: u64, window: usize) -> Self {
+        Self {
+            sampling_interval_ms: sampling_interval_ms.max(1),
+            window,
+            intervals: VecDeque::with_capacity(window + 1),
+        }
+    }
+
+    /// Adds an execution of `quantity` taken by an `aggressor_side` order at `timestamp`
+    pub fn record_trade(&mut self, timestamp: u64, aggressor_side: Side, quantity: u64) {
+        let quantity = i64::try_from(quantity).unwrap_or(i64::MAX);
+        if let Some(interval) = self.interval_at(timestamp) {
+            interval.signed_volume = match aggressor_side {
+                Side::Buy => interval.signed_volume.saturating_add(quantity),
+                Side::Sell => interval.signed_volume.saturating_sub(quantity),
+            };
+        }
+    }
+
+    /// Records the mid price observed at `timestamp`
+    pub fn record_mid(&mut self, timestamp: u64, mid: f64) {
+        if let Some(interval) = self.interval_at(timestamp) {
+            interval.close_mid = Some(mid);
+        }
+    }
+
+    /// Regression of the mid-price changes on the signed volume of each interval
+    ///
+    /// Returns `None` until two intervals with a known mid-price change were
+    /// recorded, or if the signed volume never varied.
+    #[must_use]
+    pub fn estimate(&self) -> Option<LambdaEstimate> {
+        let points: Vec<(f64, f64)> = self
+            .intervals
+            .iter()
+            .zip(self.intervals.iter().skip(1))
+            .filter_map(|(previous, interval)| {
+                let change = interval.close_mid? - previous.close_mid?;
+                Some((interval.signed_volume as f64, change))
+            })
+            .collect();
+        if points.len() < 2 {
+            return None;
+        }
+
+        let count = points.len() as f64;
+        let mean_flow = points.iter().map(|(flow, _)| flow).sum::<f64>() / count;
+        let mean_change = points.iter().map(|(_, change)| change).sum::<f64>() / count;
+        let (mut covariance, mut flow_variance, mut change_variance) = (0.0, 0.0, 0.0);
+        for (flow, change) in &points {
+            covariance += (flow - mean_flow) * (change - mean_change);
+            flow_variance += (flow - mean_flow).powi(2);
+            change_variance += (change - mean_change).powi(2);
+        }
+        if flow_variance == 0.0 {
+            return None;
+        }
+
+        let lambda = covariance / flow_variance;
+        let r_squared = if change_variance == 0.0 {
+            0.0
+        } else {
+            covariance.powi(2) / (flow_variance * change_variance)
+        };
+        Some(LambdaEstimate {
+            lambda,
+            r_squared,
+            samples: points.len(),
+        })
+    }
+
+    /// Drops every interval
+    pub fn clear(&mut self) {
+        self.intervals.clear();
+    }
+
+    /// Interval containing `timestamp`, opening it if needed; `None` for past intervals
+    fn interval_at(&mut self, timestamp: u64) -> Option<&mut FlowInterval> {
+        let start = timestamp - timestamp % self.sampling_interval_ms;
+        let previous_close = match self.intervals.back() {
+            Some(last) if last.start > start => return None,
+            Some(last) if last.start == start => return self.intervals.back_mut(),
+            Some(last) => last.close_mid,
+            None => None,
+        };
+        // One interval more than the window, so that the window holds as many changes
+        if self.intervals.len() > self.window {
+            self.intervals.pop_front();
+        }
+        self.intervals.push_back(FlowInterval {
+            start,
+            signed_volume: 0,
+            close_mid: previous_close,
+        });
+        self.intervals.back_mut()
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Tracks the realized volatility of `source` prices over the last `window`
+    /// bars of `sampling_interval_ms` milliseconds of the book clock
+    ///
+    /// Trade prices are recorded as they execute; mid prices are recorded on
+    /// each call to [`OrderBook::sample_mid_price`]. Replaces any tracker enabled before.
+    pub fn enable_realized_volatility(
+        &mut self,
+        source: VolatilitySource,
+        sampling_interval_ms: u64,
+        window: usize,
+    ) {
+        self.realized_volatility = Some((
+            source,
+            Mutex::new(RealizedVolatility::new(sampling_interval_ms, window)),
+        ));
+    }
+
+    /// Stop tracking realized volatility
+    pub fn disable_realized_volatility(&mut self) {
+        self.realized_volatility = None;
+    }
+
+    /// Records the current mid price at the time of the book clock, if realized
+    /// volatility is tracked on mid prices and both sides are quoted
+    pub fn sample_mid_price(&self) {
+        if let Some((VolatilitySource::MidPrice, ref tracker)) = self.realized_volatility
+            && let Some(mid) = self.mid_price()
+        {
+            tracker
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .record(self.clock.now_millis(), mid);
+        }
+    }
+
+    /// Annualized realized volatility of the tracked prices, if tracked and
+    /// enough bars were recorded
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{ManualClock, OrderBook, VolatilityEstimator, VolatilitySource};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    /// use std::sync::Arc;
+    ///
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// let clock = Arc::new(ManualClock::new(0));
+    /// book.set_clock(clock.clone());
+    /// book.enable_realized_volatility(VolatilitySource::MidPrice, 1_000, 60);
+    ///
+    /// for (bid, ask) in [(99, 101), (100, 102), (98, 100)] {
+    ///     book.add_limit_order(OrderId::new(), bid, 1, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    ///     book.add_limit_order(OrderId::new(), ask, 1, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    ///     book.sample_mid_price();
+    ///     book.cancel_all().unwrap();
+    ///     clock.advance(1_000);
+    /// }
+    /// assert!(book.realized_volatility(VolatilityEstimator::CloseToClose).is_some());
+    /// ```
+    #[must_use]
+    pub fn realized_volatility(&self, estimator: VolatilityEstimator) -> Option<f64> {
+        self.realized_volatility.as_ref().and_then(|(_, tracker)| {
+            tracker
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .estimate(estimator)
+        })
+    }
+
+    /// Bars of the realized volatility tracker, oldest first, if tracked
+    #[must_use]
+    pub fn volatility_bars(&self) -> Option<Vec<PriceBar>> {
+        self.realized_volatility
+            .as_ref()
+            .map(|(_, tracker)| tracker.lock().unwrap_or_else(|e| e.into_inner()).bars())
+    }
+
+    /// Estimates Kyle's lambda of the book over the last `window` intervals of
+    /// `sampling_interval_ms` milliseconds of the book clock, from its executions
+    /// and best bid and offer changes
+    ///
+    /// Replaces any estimator enabled before.
+    pub fn enable_impact_estimation(&mut self, sampling_interval_ms: u64, window: usize) {
+        let mut kyle = KyleLambda::new(sampling_interval_ms, window);
+        if let Some(mid) = self.mid_price() {
+            kyle.record_mid(self.clock.now_millis(), mid);
+        }
+        self.kyle_lambda = Some(Mutex::new(kyle));
+    }
+
+    /// Stop estimating Kyle's lambda
+    pub fn disable_impact_estimation(&mut self) {
+        self.kyle_lambda = None;
+    }
+
+    /// Price impact per unit volume of an order of `quantity`
+    ///
+    /// Combines Kyle's lambda, if [`OrderBook::enable_impact_estimation`] was
+    /// called, with the impact of walking the resting depth on each side.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// book.add_limit_order(OrderId::new(), 99, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 101, 10, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 103, 10, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    ///
+    /// // Buying 20 fills at 102 on average, 2 above the mid of 100
+    /// let estimate = book.impact_estimate(20);
+    /// assert_eq!(estimate.buy_depth_lambda, Some(0.1));
+    /// assert_eq!(estimate.sell_depth_lambda, None);
+    /// assert!(estimate.kyle.is_none());
+    /// ```
+    #[must_use]
+    pub fn impact_estimate(&self, quantity: u64) -> ImpactEstimate {
+        let depth_lambda = |side: Side| {
+            let mid = self.mid_price()?;
+            let impact = self.market_impact(quantity, side);
+            (quantity > 0 && impact.total_quantity_available >= quantity)
+                .then(|| (impact.avg_price - mid).abs() / quantity as f64)
+        };
+        ImpactEstimate {
+            kyle: self
+                .kyle_lambda
+                .as_ref()
+                .and_then(|kyle| kyle.lock().unwrap_or_else(|e| e.into_inner()).estimate()),
+            buy_depth_lambda: depth_lambda(Side::Buy),
+            sell_depth_lambda: depth_lambda(Side::Sell),
+        }
+    }
+
+    /// Records the executions of a trade on the realized volatility and Kyle's
+    /// lambda estimators, if enabled
+    pub(super) fn record_trade_statistics(&self, trade: &TradeResult) {
+        if let Some((VolatilitySource::Trades, ref tracker)) = self.realized_volatility {
+            let now = self.clock.now_millis();
+            let mut tracker = tracker.lock().unwrap_or_else(|e| e.into_inner());
+            for transaction in trade.match_result.transactions.as_vec() {
+                tracker.record(now, transaction.price as f64);
+            }
+        }
+        if let Some(ref kyle) = self.kyle_lambda {
+            let now = self.clock.now_millis();
+            let mut kyle = kyle.lock().unwrap_or_else(|e| e.into_inner());
+            for transaction in trade.match_result.transactions.as_vec() {
+                kyle.record_trade(now, transaction.taker_side, transaction.quantity);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,4 +699,173 @@ mod tests {
         assert_eq!(bin.midpoint(), 150);
         assert_eq!(bin.width(), 100);
     }
+
+    #[test]
+    fn test_realized_volatility_groups_prices_into_bars() {
+        let mut volatility = RealizedVolatility::new(1_000, 2);
+        volatility.record(100, 100.0);
+        volatility.record(900, 104.0);
+        volatility.record(500, 90.0);
+        volatility.record(1_200, 102.0);
+        volatility.record(2_500, 101.0);
+        volatility.record(1_500, 50.0);
+
+        let bars = volatility.bars();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].start, 1_000);
+        assert_eq!(bars[1].start, 2_000);
+        assert_eq!(bars[1].close, 101.0);
+    }
+
+    #[test]
+    fn test_realized_volatility_estimators() {
+        let mut volatility = RealizedVolatility::new(1_000, 10);
+        assert_eq!(volatility.estimate(VolatilityEstimator::Parkinson), None);
+        volatility.record(0, 100.0);
+        volatility.record(500, 110.0);
+        volatility.record(900, 105.0);
+        assert_eq!(volatility.estimate(VolatilityEstimator::CloseToClose), None);
+
+        let range = (110.0_f64 / 100.0).ln();
+        let parkinson = volatility
+            .estimate_per_interval(VolatilityEstimator::Parkinson)
+            .unwrap();
+        assert!((parkinson - range / (4.0 * std::f64::consts::LN_2).sqrt()).abs() < 1e-12);
+
+        let body = (105.0_f64 / 100.0).ln();
+        let garman_klass = volatility
+            .estimate_per_interval(VolatilityEstimator::GarmanKlass)
+            .unwrap();
+        let expected =
+            (0.5 * range.powi(2) - (2.0 * std::f64::consts::LN_2 - 1.0) * body.powi(2)).sqrt();
+        assert!((garman_klass - expected).abs() < 1e-12);
+
+        volatility.record(1_000, 110.25);
+        volatility.record(2_000, 105.0);
+        let returns = [(110.25_f64 / 105.0).ln(), (105.0_f64 / 110.25).ln()];
+        let mean = (returns[0] + returns[1]) / 2.0;
+        let std_dev = ((returns[0] - mean).powi(2) + (returns[1] - mean).powi(2)).sqrt();
+        let close_to_close = volatility
+            .estimate(VolatilityEstimator::CloseToClose)
+            .unwrap();
+        let annualization = (RealizedVolatility::MILLIS_PER_YEAR / 1_000.0).sqrt();
+        assert!((close_to_close - std_dev * annualization).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_book_tracks_trade_volatility() {
+        use crate::utils::ManualClock;
+        use pricelevel::{OrderId, Side, TimeInForce};
+        use std::sync::Arc;
+
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        let clock = Arc::new(ManualClock::new(0));
+        book.set_clock(clock.clone());
+        assert_eq!(
+            book.realized_volatility(VolatilityEstimator::Parkinson),
+            None
+        );
+        book.enable_realized_volatility(VolatilitySource::Trades, 1_000, 10);
+
+        for price in [100, 102, 101] {
+            book.add_limit_order(OrderId::new(), price, 1, Side::Sell, TimeInForce::Gtc, None)
+                .unwrap();
+            book.submit_market_order(OrderId::new(), 1, Side::Buy)
+                .unwrap();
+            clock.advance(1_000);
+        }
+        // Mid prices are not sampled when tracking trades
+        book.sample_mid_price();
+
+        let bars = book.volatility_bars().unwrap();
+        let closes: Vec<f64> = bars.iter().map(|bar| bar.close).collect();
+        assert_eq!(closes, vec![100.0, 102.0, 101.0]);
+        assert!(
+            book.realized_volatility(VolatilityEstimator::CloseToClose)
+                .unwrap()
+                > 0.0
+        );
+        assert_eq!(
+            book.realized_volatility(VolatilityEstimator::Parkinson),
+            Some(0.0)
+        );
+
+        book.disable_realized_volatility();
+        assert!(book.volatility_bars().is_none());
+    }
+
+    #[test]
+    fn test_kyle_lambda_regression() {
+        let mut kyle = KyleLambda::new(100, 10);
+        assert_eq!(kyle.estimate(), None);
+
+        kyle.record_mid(0, 50.0);
+        kyle.record_trade(150, Side::Buy, 4);
+        kyle.record_mid(180, 52.0);
+        kyle.record_trade(250, Side::Sell, 1);
+        kyle.record_mid(260, 51.5);
+        kyle.record_mid(310, 48.5);
+        kyle.record_trade(350, Side::Sell, 6);
+        // Past intervals are ignored
+        kyle.record_trade(10, Side::Buy, 1_000);
+
+        let estimate = kyle.estimate().unwrap();
+        assert_eq!(estimate.samples, 3);
+        // Points (4, 2), (-1, -0.5) and (-6, -3): slope 0.5 with a perfect fit
+        assert!((estimate.lambda - 0.5).abs() < 1e-12);
+        assert!((estimate.r_squared - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_kyle_lambda_window_and_constant_flow() {
+        let mut kyle = KyleLambda::new(1, 2);
+        for timestamp in 0..10 {
+            kyle.record_trade(timestamp, Side::Buy, 1);
+            kyle.record_mid(timestamp, timestamp as f64);
+        }
+        // Identical flows leave the slope undefined
+        assert_eq!(kyle.estimate(), None);
+        kyle.clear();
+        assert_eq!(kyle.estimate(), None);
+    }
+
+    #[test]
+    fn test_book_impact_estimate_from_trades() {
+        use crate::utils::ManualClock;
+        use pricelevel::{OrderId, TimeInForce};
+        use std::sync::Arc;
+
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        let clock = Arc::new(ManualClock::new(0));
+        book.set_clock(clock.clone());
+        book.add_limit_order(OrderId::new(), 100, 100, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 102, 5, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 104, 100, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.enable_impact_estimation(1_000, 20);
+
+        // Lifting the best ask moves the mid from 101 to 102
+        clock.advance(1_000);
+        book.submit_market_order(OrderId::new(), 5, Side::Buy)
+            .unwrap();
+        // A small sell leaves the mid unchanged
+        clock.advance(1_000);
+        book.submit_market_order(OrderId::new(), 2, Side::Sell)
+            .unwrap();
+        clock.advance(1_000);
+        book.submit_market_order(OrderId::new(), 1, Side::Sell)
+            .unwrap();
+
+        let estimate = book.impact_estimate(10);
+        let kyle = estimate.kyle.unwrap();
+        assert_eq!(kyle.samples, 3);
+        assert!(kyle.lambda > 0.0);
+        assert!(estimate.buy_depth_lambda.is_some());
+        assert!(estimate.sell_depth_lambda.is_some());
+
+        book.disable_impact_estimation();
+        assert!(book.impact_estimate(10).kyle.is_none());
+    }
 }
@@ -0,0 +1,79 @@
+//! Async broadcast streams of trades and price level changes.
+
+use super::book::OrderBook;
+use super::book_change_event::PriceLevelChangedEvent;
+use super::trade::{TradeEvent, TradeResult};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Number of events a stream buffers for its slowest receiver.
+///
+/// A receiver that falls further behind gets `RecvError::Lagged` and skips ahead
+/// to the oldest event still buffered.
+pub const EVENT_STREAM_CAPACITY: usize = 1024;
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Subscribe to a broadcast stream of the trades executed in this book
+    ///
+    /// Every receiver gets every trade executed after it subscribed. The stream is
+    /// fed through the trade subscriber registry, so it coexists with callback
+    /// listeners. Sending never blocks the matching thread: a receiver that falls
+    /// more than [`EVENT_STREAM_CAPACITY`] events behind observes
+    /// `RecvError::Lagged`.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::OrderBook;
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let book = OrderBook::<()>::new("BTC/USD");
+    /// let mut trades = book.subscribe_trades();
+    ///
+    /// book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    /// book.submit_market_order(OrderId::new(), 4, Side::Buy).unwrap();
+    ///
+    /// let event = trades.try_recv().unwrap();
+    /// assert_eq!(event.symbol, "BTC/USD");
+    /// assert_eq!(event.trade_result.match_result.executed_quantity(), 4);
+    /// ```
+    pub fn subscribe_trades(&self) -> broadcast::Receiver<TradeEvent> {
+        self.trade_stream
+            .get_or_init(|| {
+                let (sender, _) = broadcast::channel(EVENT_STREAM_CAPACITY);
+                let stream = sender.clone();
+                let clock = Arc::clone(&self.clock);
+                self.subscribe_trade_listener(Arc::new(move |trade_result: &TradeResult| {
+                    // Sending only fails while there are no receivers
+                    let _ = stream.send(TradeEvent {
+                        symbol: trade_result.symbol.clone(),
+                        trade_result: trade_result.clone(),
+                        timestamp: clock.now_millis(),
+                    });
+                }));
+                sender
+            })
+            .subscribe()
+    }
+
+    /// Subscribe to a broadcast stream of the price level changes of this book
+    ///
+    /// Receivers get the same events as price level listeners, including the
+    /// consolidated final state of each level touched by a batched operation.
+    /// Lagging behaves as described for [`OrderBook::subscribe_trades`].
+    pub fn subscribe_book_changes(&self) -> broadcast::Receiver<PriceLevelChangedEvent> {
+        self.book_change_stream
+            .get_or_init(|| {
+                let (sender, _) = broadcast::channel(EVENT_STREAM_CAPACITY);
+                let stream = sender.clone();
+                self.subscribe_price_level_listener(Arc::new(move |event| {
+                    // Sending only fails while there are no receivers
+                    let _ = stream.send(event);
+                }));
+                sender
+            })
+            .subscribe()
+    }
+}
@@ -0,0 +1,90 @@
+//! Multi-subscriber listener registration.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Handle returned when subscribing a listener, used to unsubscribe it later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SubscriptionId(pub u64);
+
+impl fmt::Display for SubscriptionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A set of listeners that can be subscribed and unsubscribed through a shared reference.
+pub(crate) struct ListenerRegistry<L> {
+    next_id: AtomicU64,
+    subscribers: RwLock<Vec<(SubscriptionId, L)>>,
+}
+
+impl<L: Clone> ListenerRegistry<L> {
+    pub(crate) fn new() -> Self {
+        Self {
+            next_id: AtomicU64::new(1),
+            subscribers: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Registers a listener and returns the ID identifying its subscription.
+    pub(crate) fn subscribe(&self, listener: L) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.subscribers
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((id, listener));
+        id
+    }
+
+    /// Removes a subscription, returning whether it was registered.
+    pub(crate) fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subscribers = self.subscribers.write().unwrap_or_else(|e| e.into_inner());
+        let before = subscribers.len();
+        subscribers.retain(|(subscription, _)| *subscription != id);
+        subscribers.len() != before
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.subscribers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.subscribers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+
+    /// Invokes `deliver` for every subscriber in subscription order.
+    ///
+    /// The registry is not locked while listeners run, so a listener may subscribe
+    /// or unsubscribe without deadlocking.
+    pub(crate) fn notify(&self, mut deliver: impl FnMut(&L)) {
+        let listeners: Vec<L> = {
+            let subscribers = self.subscribers.read().unwrap_or_else(|e| e.into_inner());
+            if subscribers.is_empty() {
+                return;
+            }
+            subscribers
+                .iter()
+                .map(|(_, listener)| listener.clone())
+                .collect()
+        };
+
+        for listener in &listeners {
+            deliver(listener);
+        }
+    }
+}
+
+impl<L: Clone> Default for ListenerRegistry<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,558 @@
+//! Per-owner surveillance metrics with threshold alerts, as building blocks for
+//! market-abuse monitoring.
+
+use super::book::OrderBook;
+use super::owner::OwnerId;
+use super::trade::TradeResult;
+use pricelevel::{OrderId, Side};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Windows and thresholds of the surveillance metrics.
+///
+/// Every threshold is disabled until set.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SurveillanceConfig {
+    /// Length of the rolling window the metrics are computed over, in milliseconds
+    pub window_ms: u64,
+    /// Cancellations within this many milliseconds of placement are fleeting
+    pub fleeting_cancel_ms: u64,
+    /// Orders placed at the touch and cancelled within this many milliseconds flicker
+    pub flicker_ms: u64,
+    /// Orders an owner has to place in the window before ratio alerts can fire
+    pub min_orders: u64,
+    /// Highest order-to-trade ratio before an alert
+    pub max_order_to_trade_ratio: Option<f64>,
+    /// Highest share of orders cancelled fleetingly before an alert
+    pub max_fleeting_cancel_ratio: Option<f64>,
+    /// Highest number of flickering quotes in the window before an alert
+    pub max_flickers: Option<u64>,
+}
+
+impl SurveillanceConfig {
+    /// Create a configuration over a rolling window of `window_ms` milliseconds,
+    /// with fleeting cancellations under 500 ms, flickers under 100 ms and ratio
+    /// alerts from 10 orders
+    #[must_use]
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            fleeting_cancel_ms: 500,
+            flicker_ms: 100,
+            min_orders: 10,
+            max_order_to_trade_ratio: None,
+            max_fleeting_cancel_ratio: None,
+            max_flickers: None,
+        }
+    }
+
+    /// Sets the age under which a cancellation is fleeting
+    #[must_use]
+    pub fn with_fleeting_cancel_ms(mut self, fleeting_cancel_ms: u64) -> Self {
+        self.fleeting_cancel_ms = fleeting_cancel_ms;
+        self
+    }
+
+    /// Sets the age under which a cancelled quote placed at the touch flickers
+    #[must_use]
+    pub fn with_flicker_ms(mut self, flicker_ms: u64) -> Self {
+        self.flicker_ms = flicker_ms;
+        self
+    }
+
+    /// Sets the orders needed in the window before ratio alerts can fire
+    #[must_use]
+    pub fn with_min_orders(mut self, min_orders: u64) -> Self {
+        self.min_orders = min_orders;
+        self
+    }
+
+    /// Alerts when the order-to-trade ratio of an owner exceeds `ratio`
+    #[must_use]
+    pub fn with_max_order_to_trade_ratio(mut self, ratio: f64) -> Self {
+        self.max_order_to_trade_ratio = Some(ratio);
+        self
+    }
+
+    /// Alerts when the share of orders an owner cancels fleetingly exceeds `ratio`
+    #[must_use]
+    pub fn with_max_fleeting_cancel_ratio(mut self, ratio: f64) -> Self {
+        self.max_fleeting_cancel_ratio = Some(ratio);
+        self
+    }
+
+    /// Alerts when an owner has more than `flickers` flickering quotes in the window
+    #[must_use]
+    pub fn with_max_flickers(mut self, flickers: u64) -> Self {
+        self.max_flickers = Some(flickers);
+        self
+    }
+}
+
+/// Surveillance metric whose threshold was crossed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SurveillanceMetric {
+    /// Orders placed per execution
+    OrderToTradeRatio,
+    /// Share of orders cancelled shortly after placement
+    FleetingCancelRatio,
+    /// Quotes placed at the touch and cancelled almost at once
+    QuoteFlicker,
+}
+
+/// An owner crossed the threshold of a surveillance metric.
+///
+/// Each metric alerts once when its threshold is crossed, and again only
+/// after it went back under the threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SurveillanceAlert {
+    /// Owner whose activity crossed the threshold
+    pub owner: OwnerId,
+    /// Metric that crossed its threshold
+    pub metric: SurveillanceMetric,
+    /// Value of the metric over the window
+    pub value: f64,
+    /// Configured threshold
+    pub threshold: f64,
+    /// Time of the event that crossed the threshold, in milliseconds of the book clock
+    pub timestamp: u64,
+}
+
+/// A listener for surveillance alerts.
+pub type SurveillanceListener = Arc<dyn Fn(&SurveillanceAlert) + Send + Sync>;
+
+/// Activity of one owner over the surveillance window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnerActivity {
+    /// Orders accepted from the owner
+    pub orders: u64,
+    /// Executions the owner took part in, as maker or taker
+    pub trades: u64,
+    /// Resting orders of the owner that were cancelled
+    pub cancels: u64,
+    /// Cancellations within the fleeting cancellation age
+    pub fleeting_cancels: u64,
+    /// Quotes placed at the touch and cancelled within the flicker age
+    pub flickers: u64,
+}
+
+impl OwnerActivity {
+    /// Orders per execution, counting at least one execution
+    #[must_use]
+    pub fn order_to_trade_ratio(&self) -> f64 {
+        self.orders as f64 / self.trades.max(1) as f64
+    }
+
+    /// Share of the orders cancelled fleetingly, or `None` without orders
+    #[must_use]
+    pub fn fleeting_cancel_ratio(&self) -> Option<f64> {
+        (self.orders > 0).then(|| self.fleeting_cancels as f64 / self.orders as f64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ActivityKind {
+    Order,
+    Trade,
+    Cancel { fleeting: bool, flicker: bool },
+}
+
+#[derive(Debug, Clone, Default)]
+struct OwnerState {
+    events: VecDeque<(u64, ActivityKind)>,
+    activity: OwnerActivity,
+    alerted: [bool; 3],
+}
+
+impl OwnerState {
+    fn apply(&mut self, kind: ActivityKind, added: bool) {
+        let update = |count: &mut u64, hit: bool| {
+            if hit {
+                *count = if added { *count + 1 } else { *count - 1 };
+            }
+        };
+        let activity = &mut self.activity;
+        match kind {
+            ActivityKind::Order => update(&mut activity.orders, true),
+            ActivityKind::Trade => update(&mut activity.trades, true),
+            ActivityKind::Cancel { fleeting, flicker } => {
+                update(&mut activity.cancels, true);
+                update(&mut activity.fleeting_cancels, fleeting);
+                update(&mut activity.flickers, flicker);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct SurveillanceState {
+    owners: HashMap<OwnerId, OwnerState>,
+    /// Placement time and whether the order joined or improved the touch
+    placements: HashMap<OrderId, (u64, bool)>,
+    placement_order: VecDeque<(u64, OrderId)>,
+}
+
+/// Rolling per-owner surveillance metrics.
+///
+/// Records order placements, cancellations and executions per owner over a
+/// rolling window, and reports a [`SurveillanceAlert`] whenever a metric
+/// crosses its configured threshold. Orders without an owner are not tracked.
+#[derive(Debug)]
+pub struct Surveillance {
+    config: SurveillanceConfig,
+    state: Mutex<SurveillanceState>,
+}
+
+impl Surveillance {
+    /// Create a tracker with the given windows and thresholds
+    #[must_use]
+    pub fn new(config: SurveillanceConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(SurveillanceState::default()),
+        }
+    }
+
+    /// Windows and thresholds of the tracker
+    #[must_use]
+    pub fn config(&self) -> SurveillanceConfig {
+        self.config
+    }
+
+    /// Records an order accepted from `owner` at `timestamp`, `at_touch` if it
+    /// joined or improved the best price of its side
+    pub fn record_order(
+        &self,
+        timestamp: u64,
+        owner: OwnerId,
+        order_id: OrderId,
+        at_touch: bool,
+    ) -> Vec<SurveillanceAlert> {
+        let mut state = self.lock();
+        state.placements.insert(order_id, (timestamp, at_touch));
+        state.placement_order.push_back((timestamp, order_id));
+        self.prune_placements(&mut state, timestamp);
+        self.record(&mut state, timestamp, owner, ActivityKind::Order)
+    }
+
+    /// Records the cancellation of the resting order `order_id` of `owner` at `timestamp`
+    pub fn record_cancel(
+        &self,
+        timestamp: u64,
+        owner: OwnerId,
+        order_id: OrderId,
+    ) -> Vec<SurveillanceAlert> {
+        let mut state = self.lock();
+        let (fleeting, flicker) = match state.placements.remove(&order_id) {
+            Some((placed, at_touch)) => {
+                let age = timestamp.saturating_sub(placed);
+                (
+                    age < self.config.fleeting_cancel_ms,
+                    at_touch && age < self.config.flicker_ms,
+                )
+            }
+            None => (false, false),
+        };
+        self.record(
+            &mut state,
+            timestamp,
+            owner,
+            ActivityKind::Cancel { fleeting, flicker },
+        )
+    }
+
+    /// Records an execution `owner` took part in at `timestamp`
+    pub fn record_execution(&self, timestamp: u64, owner: OwnerId) -> Vec<SurveillanceAlert> {
+        let mut state = self.lock();
+        self.record(&mut state, timestamp, owner, ActivityKind::Trade)
+    }
+
+    /// Records every transaction of `trade` for its taker and maker owners
+    pub fn record_trade(&self, trade: &TradeResult, timestamp: u64) -> Vec<SurveillanceAlert> {
+        let mut alerts = Vec::new();
+        for transaction in trade.match_result.transactions.as_vec() {
+            let owners = [
+                trade.taker_owner,
+                trade.maker_owner(transaction.maker_order_id),
+            ];
+            for owner in owners.into_iter().flatten() {
+                alerts.extend(self.record_execution(timestamp, owner));
+            }
+        }
+        alerts
+    }
+
+    /// Activity of `owner` over the window ending at `now`
+    #[must_use]
+    pub fn activity(&self, owner: OwnerId, now: u64) -> OwnerActivity {
+        let mut state = self.lock();
+        match state.owners.get_mut(&owner) {
+            Some(owner_state) => {
+                self.prune(owner_state, now);
+                owner_state.activity
+            }
+            None => OwnerActivity::default(),
+        }
+    }
+
+    /// Every owner with activity in the window ending at `now`, with its activity
+    #[must_use]
+    pub fn activities(&self, now: u64) -> Vec<(OwnerId, OwnerActivity)> {
+        let mut state = self.lock();
+        for owner_state in state.owners.values_mut() {
+            self.prune(owner_state, now);
+        }
+        state
+            .owners
+            .retain(|_, owner_state| !owner_state.events.is_empty());
+        let mut activities: Vec<(OwnerId, OwnerActivity)> = state
+            .owners
+            .iter()
+            .map(|(owner, owner_state)| (*owner, owner_state.activity))
+            .collect();
+        activities.sort_by_key(|(owner, _)| *owner);
+        activities
+    }
+
+    /// Forgets every recorded event
+    pub fn clear(&self) {
+        *self.lock() = SurveillanceState::default();
+    }
+
+    fn record(
+        &self,
+        state: &mut SurveillanceState,
+        timestamp: u64,
+        owner: OwnerId,
+        kind: ActivityKind,
+    ) -> Vec<SurveillanceAlert> {
+        let owner_state = state.owners.entry(owner).or_default();
+        owner_state.events.push_back((timestamp, kind));
+        owner_state.apply(kind, true);
+        self.prune(owner_state, timestamp);
+        self.check_thresholds(owner_state, owner, timestamp)
+    }
+
+    fn check_thresholds(
+        &self,
+        owner_state: &mut OwnerState,
+        owner: OwnerId,
+        timestamp: u64,
+    ) -> Vec<SurveillanceAlert> {
+        let activity = owner_state.activity;
+        let enough_orders = activity.orders >= self.config.min_orders;
+        let checks = [
+            (
+                SurveillanceMetric::OrderToTradeRatio,
+                self.config.max_order_to_trade_ratio,
+                enough_orders.then(|| activity.order_to_trade_ratio()),
+            ),
+            (
+                SurveillanceMetric::FleetingCancelRatio,
+                self.config.max_fleeting_cancel_ratio,
+                activity.fleeting_cancel_ratio().filter(|_| enough_orders),
+            ),
+            (
+                SurveillanceMetric::QuoteFlicker,
+                self.config.max_flickers.map(|flickers| flickers as f64),
+                Some(activity.flickers as f64),
+            ),
+        ];
+
+        let mut alerts = Vec::new();
+        for (index, (metric, threshold, value)) in checks.into_iter().enumerate() {
+            let Some(threshold) = threshold else {
+                continue;
+            };
+            match value {
+                Some(value) if value > threshold => {
+                    if !owner_state.alerted[index] {
+                        owner_state.alerted[index] = true;
+                        alerts.push(SurveillanceAlert {
+                            owner,
+                            metric,
+                            value,
+                            threshold,
+                            timestamp,
+                        });
+                    }
+                }
+                _ => owner_state.alerted[index] = false,
+            }
+        }
+        alerts
+    }
+
+    fn prune(&self, owner_state: &mut OwnerState, now: u64) {
+        let since = now.saturating_sub(self.config.window_ms);
+        while let Some(&(timestamp, kind)) = owner_state.events.front() {
+            if timestamp > since {
+                break;
+            }
+            owner_state.events.pop_front();
+            owner_state.apply(kind, false);
+        }
+    }
+
+    /// Forgets placements too old for their cancellation to be fleeting or flicker
+    fn prune_placements(&self, state: &mut SurveillanceState, now: u64) {
+        let max_age = self.config.fleeting_cancel_ms.max(self.config.flicker_ms);
+        let since = now.saturating_sub(max_age);
+        while let Some(&(timestamp, order_id)) = state.placement_order.front() {
+            if timestamp > since {
+                break;
+            }
+            state.placement_order.pop_front();
+            if state
+                .placements
+                .get(&order_id)
+                .is_some_and(|(placed, _)| *placed == timestamp)
+            {
+                state.placements.remove(&order_id);
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SurveillanceState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clone for Surveillance {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config,
+            state: Mutex::new(self.lock().clone()),
+        }
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Tracks per-owner surveillance metrics over the windows and thresholds of `config`
+    ///
+    /// Replaces any tracker enabled before, dropping its events. Alerts are
+    /// delivered to the listener set with [`OrderBook::set_surveillance_listener`].
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{
+    ///     ManualClock, OrderBook, OwnerId, SurveillanceAlert, SurveillanceConfig,
+    ///     SurveillanceMetric,
+    /// };
+    /// use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// let clock = Arc::new(ManualClock::new(10_000));
+    /// book.set_clock(clock.clone());
+    /// book.enable_surveillance(SurveillanceConfig::new(60_000).with_max_flickers(2));
+    /// let alerts = Arc::new(Mutex::new(Vec::<SurveillanceAlert>::new()));
+    /// let sink = Arc::clone(&alerts);
+    /// book.set_surveillance_listener(Arc::new(move |alert| sink.lock().unwrap().push(*alert)));
+    ///
+    /// for _ in 0..3 {
+    ///     let id = OrderId::new();
+    ///     let order = OrderType::Standard {
+    ///         id,
+    ///         price: 100,
+    ///         quantity: 10,
+    ///         side: Side::Buy,
+    ///         timestamp: 0,
+    ///         time_in_force: TimeInForce::Gtc,
+    ///         extra_fields: (),
+    ///     };
+    ///     book.add_order_with_owner(order, OwnerId(7)).unwrap();
+    ///     clock.advance(20);
+    ///     book.cancel_order(id).unwrap();
+    /// }
+    ///
+    /// let alerts = alerts.lock().unwrap();
+    /// assert_eq!(alerts.len(), 1);
+    /// assert_eq!(alerts[0].metric, SurveillanceMetric::QuoteFlicker);
+    /// assert_eq!(book.owner_activity(OwnerId(7)).unwrap().flickers, 3);
+    /// ```
+    pub fn enable_surveillance(&mut self, config: SurveillanceConfig) {
+        self.surveillance = Some(Surveillance::new(config));
+    }
+
+    /// Stop tracking surveillance metrics
+    pub fn disable_surveillance(&mut self) {
+        self.surveillance = None;
+    }
+
+    /// The surveillance tracker of the book, if enabled
+    #[must_use]
+    pub fn surveillance(&self) -> Option<&Surveillance> {
+        self.surveillance.as_ref()
+    }
+
+    /// set surveillance alert listener for this order book
+    pub fn set_surveillance_listener(&mut self, listener: SurveillanceListener) {
+        self.surveillance_listener = Some(listener);
+    }
+
+    /// remove surveillance alert listener for this order book
+    pub fn remove_surveillance_listener(&mut self) {
+        self.surveillance_listener = None;
+    }
+
+    /// Activity of `owner` over the surveillance window ending at the current
+    /// time of the book clock, if tracked
+    #[must_use]
+    pub fn owner_activity(&self, owner: OwnerId) -> Option<OwnerActivity> {
+        self.surveillance
+            .as_ref()
+            .map(|surveillance| surveillance.activity(owner, self.clock.now_millis()))
+    }
+
+    /// Records an order accepted from `owner` on the surveillance tracker, if enabled
+    pub(super) fn surveil_order(
+        &self,
+        owner: Option<OwnerId>,
+        order_id: OrderId,
+        side: Side,
+        price: u64,
+        hidden: bool,
+    ) {
+        let (Some(surveillance), Some(owner)) = (&self.surveillance, owner) else {
+            return;
+        };
+        let at_touch = !hidden
+            && match side {
+                Side::Buy => self.best_bid().is_none_or(|best| price >= best),
+                Side::Sell => self.best_ask().is_none_or(|best| price <= best),
+            };
+        let alerts = surveillance.record_order(self.clock.now_millis(), owner, order_id, at_touch);
+        self.emit_surveillance_alerts(alerts);
+    }
+
+    /// Records the cancellation of a resting order of `owner` on the surveillance
+    /// tracker, if enabled
+    pub(super) fn surveil_cancel(&self, owner: Option<OwnerId>, order_id: OrderId) {
+        let (Some(surveillance), Some(owner)) = (&self.surveillance, owner) else {
+            return;
+        };
+        let alerts = surveillance.record_cancel(self.clock.now_millis(), owner, order_id);
+        self.emit_surveillance_alerts(alerts);
+    }
+
+    /// Records the executions of a trade on the surveillance tracker, if enabled
+    pub(super) fn surveil_trade(&self, trade: &TradeResult) {
+        if let Some(ref surveillance) = self.surveillance {
+            let alerts = surveillance.record_trade(trade, self.clock.now_millis());
+            self.emit_surveillance_alerts(alerts);
+        }
+    }
+
+    fn emit_surveillance_alerts(&self, alerts: Vec<SurveillanceAlert>) {
+        if let Some(ref listener) = self.surveillance_listener {
+            for alert in &alerts {
+                listener(alert);
+            }
+        }
+    }
+}
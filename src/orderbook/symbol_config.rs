@@ -0,0 +1,126 @@
+//! Instrument metadata and trading rules of the symbols of a book manager.
+
+use super::config::BookConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Kind of instrument traded in a book.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InstrumentType {
+    /// Spot instrument, settled immediately
+    #[default]
+    Spot,
+    /// Futures contract
+    Future,
+    /// Options contract
+    Option,
+}
+
+/// Configuration of one symbol: its instrument type and the rules of its book.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::{BookConfig, BookManager, BookManagerStd, InstrumentType, SymbolConfig};
+/// use pricelevel::{OrderId, Side, TimeInForce};
+///
+/// let mut manager = BookManagerStd::<()>::new();
+/// let config = SymbolConfig::new(InstrumentType::Future).with_book_config(
+///     BookConfig::default()
+///         .with_tick_size(5)
+///         .with_lot_size(10)
+///         .with_price_band(1_000, 2_000),
+/// );
+/// manager.add_book_with_config("BTC-PERP", config);
+///
+/// let book = manager.get_book("BTC-PERP").unwrap();
+/// assert!(book.add_limit_order(OrderId::new(), 1_500, 10, Side::Buy, TimeInForce::Gtc, None).is_ok());
+/// assert!(book.add_limit_order(OrderId::new(), 2_500, 10, Side::Buy, TimeInForce::Gtc, None).is_err());
+///
+/// let registry = manager.registry();
+/// assert_eq!(registry.get("BTC-PERP").unwrap().instrument_type, InstrumentType::Future);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolConfig {
+    /// Kind of instrument
+    pub instrument_type: InstrumentType,
+    /// Tick size, lot size, minimum notional and price band enforced by the book
+    pub book: BookConfig,
+}
+
+impl SymbolConfig {
+    /// Create a configuration for an instrument of `instrument_type` without trading rules
+    pub fn new(instrument_type: InstrumentType) -> Self {
+        Self {
+            instrument_type,
+            book: BookConfig::default(),
+        }
+    }
+
+    /// Sets the rules enforced by the book
+    #[must_use]
+    pub fn with_book_config(mut self, book: BookConfig) -> Self {
+        self.book = book;
+        self
+    }
+}
+
+/// Configurations of the symbols of a book manager, indexed by symbol.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolRegistry {
+    configs: HashMap<String, SymbolConfig>,
+}
+
+impl SymbolRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the configuration of `symbol`, returning the one it replaces
+    pub fn register(&mut self, symbol: &str, config: SymbolConfig) -> Option<SymbolConfig> {
+        self.configs.insert(symbol.to_string(), config)
+    }
+
+    /// Removes the configuration of `symbol`
+    pub fn remove(&mut self, symbol: &str) -> Option<SymbolConfig> {
+        self.configs.remove(symbol)
+    }
+
+    /// Configuration of `symbol`
+    pub fn get(&self, symbol: &str) -> Option<&SymbolConfig> {
+        self.configs.get(symbol)
+    }
+
+    /// Whether `symbol` is registered
+    pub fn contains(&self, symbol: &str) -> bool {
+        self.configs.contains_key(symbol)
+    }
+
+    /// Number of registered symbols
+    pub fn len(&self) -> usize {
+        self.configs.len()
+    }
+
+    /// Whether no symbol is registered
+    pub fn is_empty(&self) -> bool {
+        self.configs.is_empty()
+    }
+
+    /// Registered symbols and their configurations, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &SymbolConfig)> {
+        self.configs
+            .iter()
+            .map(|(symbol, config)| (symbol.as_str(), config))
+    }
+
+    /// Symbols of the instruments of `instrument_type`, sorted
+    pub fn symbols_of_type(&self, instrument_type: InstrumentType) -> Vec<String> {
+        let mut symbols: Vec<String> = self
+            .iter()
+            .filter(|(_, config)| config.instrument_type == instrument_type)
+            .map(|(symbol, _)| symbol.to_string())
+            .collect();
+        symbols.sort();
+        symbols
+    }
+}
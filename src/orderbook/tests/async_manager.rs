@@ -0,0 +1,171 @@
+//! Unit tests for the async book manager.
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use crate::orderbook::OrderBookError;
+    use crate::orderbook::async_manager::AsyncBookManager;
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::future::Future;
+    use std::sync::Arc;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_commands_are_applied_in_order() {
+        block_on(async {
+            let mut manager = AsyncBookManager::<()>::new().with_command_capacity(2);
+            let book = manager.add_book("BTC/USD");
+
+            let ids: Vec<OrderId> = (0..10).map(|_| OrderId::new()).collect();
+            for (index, id) in ids.iter().enumerate() {
+                book.add_limit_order(
+                    *id,
+                    100 + index as u64,
+                    1,
+                    Side::Sell,
+                    TimeInForce::Gtc,
+                    None,
+                )
+                .await
+                .unwrap();
+            }
+            assert!(book.cancel_order(ids[0]).await.unwrap().is_some());
+            assert!(book.cancel_order(ids[0]).await.unwrap().is_none());
+
+            let snapshot = book.snapshot(3).await.unwrap();
+            let prices: Vec<u64> = snapshot.asks.iter().map(|level| level.price).collect();
+            assert_eq!(prices, vec![101, 102, 103]);
+            assert_eq!(
+                book.execute(|book| book.best_ask()).await.unwrap(),
+                Some(101)
+            );
+        });
+    }
+
+    #[test]
+    fn test_book_errors_are_returned() {
+        block_on(async {
+            let mut manager = AsyncBookManager::<()>::new();
+            let book = manager.add_book("BTC/USD");
+
+            let result = book.submit_market_order(OrderId::new(), 5, Side::Buy).await;
+            assert!(matches!(
+                result,
+                Err(OrderBookError::InsufficientLiquidity { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn test_trades_of_every_book_are_broadcast() {
+        block_on(async {
+            let mut manager = AsyncBookManager::<()>::with_clock(Arc::new(ManualClock::new(1_000)));
+            let mut first = manager.subscribe_trades();
+            let mut second = manager.subscribe_trades();
+
+            for (symbol, quantity) in [("BTC/USD", 3), ("ETH/USD", 4)] {
+                let book = manager.add_book(symbol);
+                book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None)
+                    .await
+                    .unwrap();
+                book.submit_market_order(OrderId::new(), quantity, Side::Buy)
+                    .await
+                    .unwrap();
+            }
+
+            for receiver in [&mut first, &mut second] {
+                let btc = receiver.recv().await.unwrap();
+                assert_eq!(btc.symbol, "BTC/USD");
+                assert_eq!(btc.timestamp, 1_000);
+                assert_eq!(btc.trade_result.match_result.executed_quantity(), 3);
+                let eth = receiver.recv().await.unwrap();
+                assert_eq!(eth.symbol, "ETH/USD");
+                assert_eq!(eth.trade_result.match_result.executed_quantity(), 4);
+            }
+        });
+    }
+
+    #[test]
+    fn test_add_book_returns_existing_handle() {
+        block_on(async {
+            let mut manager = AsyncBookManager::<()>::new();
+            let first = manager.add_book("BTC/USD");
+            first
+                .add_limit_order(OrderId::new(), 100, 1, Side::Buy, TimeInForce::Gtc, None)
+                .await
+                .unwrap();
+
+            let second = manager.add_book("BTC/USD");
+            assert_eq!(manager.book_count(), 1);
+            assert_eq!(
+                second.execute(|book| book.best_bid()).await.unwrap(),
+                Some(100)
+            );
+            assert_eq!(manager.book("BTC/USD").unwrap().symbol(), "BTC/USD");
+            assert!(manager.book("ETH/USD").is_none());
+        });
+    }
+
+    #[test]
+    fn test_remove_book_stops_its_task() {
+        block_on(async {
+            let mut manager = AsyncBookManager::<()>::new();
+            let book = manager.add_book("BTC/USD");
+            manager.add_book("ETH/USD");
+            book.add_limit_order(OrderId::new(), 100, 1, Side::Buy, TimeInForce::Gtc, None)
+                .await
+                .unwrap();
+
+            let removed = manager.remove_book("BTC/USD").await.unwrap();
+            assert_eq!(removed.best_bid(), Some(100));
+            assert!(!manager.has_book("BTC/USD"));
+            assert_eq!(manager.symbols(), vec!["ETH/USD".to_string()]);
+            assert!(manager.remove_book("BTC/USD").await.is_none());
+
+            assert!(!book.is_running());
+            let result = book.snapshot(1).await;
+            assert!(matches!(
+                result,
+                Err(OrderBookError::BookStopped { symbol }) if symbol == "BTC/USD"
+            ));
+        });
+    }
+
+    #[test]
+    fn test_shutdown_handles_queued_commands() {
+        block_on(async {
+            let mut manager = AsyncBookManager::<()>::new();
+            let book = manager.add_book("BTC/USD");
+
+            let producer = tokio::spawn({
+                let book = book.clone();
+                async move {
+                    for price in 1..=50 {
+                        book.add_limit_order(
+                            OrderId::new(),
+                            price,
+                            1,
+                            Side::Buy,
+                            TimeInForce::Gtc,
+                            None,
+                        )
+                        .await
+                        .unwrap();
+                    }
+                }
+            });
+            producer.await.unwrap();
+
+            let books = manager.shutdown().await;
+            assert_eq!(books["BTC/USD"].best_bid(), Some(50));
+            assert_eq!(books["BTC/USD"].create_snapshot(100).bids.len(), 50);
+            assert!(!book.is_running());
+        });
+    }
+}
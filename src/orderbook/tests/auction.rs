@@ -0,0 +1,221 @@
+//! Unit tests for auction equilibrium and uncrossing.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::error::OrderBookError;
+    use crate::orderbook::journal::InMemoryJournal;
+    use crate::orderbook::modifications::OrderQuantity;
+    use crate::orderbook::order_event::OrderEvent;
+    use crate::orderbook::session::SessionState;
+    use crate::orderbook::trade::TradeResult;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn add(book: &OrderBook<()>, price: u64, quantity: u64, side: Side) -> OrderId {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, side, TimeInForce::Gtc, None)
+            .unwrap();
+        id
+    }
+
+    fn pre_open_book() -> OrderBook<()> {
+        let book = OrderBook::<()>::new("BTC/USD");
+        book.set_session_state(SessionState::PreOpen).unwrap();
+        book
+    }
+
+    #[test]
+    fn test_no_uncross_when_not_crossed() {
+        let book = pre_open_book();
+        assert_eq!(book.indicative_uncross(), None);
+        add(&book, 99, 5, Side::Buy);
+        add(&book, 101, 5, Side::Sell);
+        assert_eq!(book.indicative_uncross(), None);
+        assert!(book.uncross().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_equilibrium_maximizes_volume_then_minimizes_imbalance() {
+        let book = pre_open_book();
+        add(&book, 103, 6, Side::Buy);
+        add(&book, 101, 4, Side::Buy);
+        add(&book, 100, 5, Side::Sell);
+        add(&book, 102, 5, Side::Sell);
+
+        // 102 and 103 both execute 6 and leave 4 of supply: sell pressure picks 102
+        let uncross = book.indicative_uncross().unwrap();
+        assert_eq!(uncross.price, 102);
+        assert_eq!(uncross.matched_quantity, 6);
+        assert_eq!(uncross.imbalance, 4);
+        assert_eq!(uncross.imbalance_side, Some(Side::Sell));
+    }
+
+    #[test]
+    fn test_buy_pressure_picks_highest_price() {
+        let book = pre_open_book();
+        add(&book, 102, 10, Side::Buy);
+        add(&book, 100, 4, Side::Sell);
+        add(&book, 101, 4, Side::Sell);
+
+        let uncross = book.indicative_uncross().unwrap();
+        assert_eq!(uncross.price, 102);
+        assert_eq!(uncross.imbalance_side, Some(Side::Buy));
+    }
+
+    #[test]
+    fn test_balanced_book_uses_reference_price() {
+        let book = pre_open_book();
+        add(&book, 102, 5, Side::Buy);
+        add(&book, 100, 5, Side::Sell);
+        // Without trades the middle of 100 and 102 is equidistant: the lower wins
+        assert_eq!(book.indicative_uncross().unwrap().price, 100);
+
+        let traded = OrderBook::<()>::new("BTC/USD");
+        add(&traded, 102, 1, Side::Sell);
+        add(&traded, 102, 1, Side::Buy);
+        assert_eq!(traded.last_trade_price(), Some(102));
+        traded.set_session_state(SessionState::PreOpen).unwrap();
+        add(&traded, 102, 5, Side::Buy);
+        add(&traded, 100, 5, Side::Sell);
+        let uncross = traded.indicative_uncross().unwrap();
+        assert_eq!(uncross.price, 102);
+        assert_eq!(uncross.imbalance_side, None);
+    }
+
+    #[test]
+    fn test_uncross_executes_at_single_price_in_priority() {
+        let mut book = pre_open_book();
+        let trades: Arc<Mutex<Vec<TradeResult>>> = Arc::default();
+        let sink = trades.clone();
+        book.set_trade_listener(Arc::new(move |trade| {
+            sink.lock().unwrap().push(trade.clone());
+        }));
+
+        let b1 = add(&book, 102, 5, Side::Buy);
+        let b2 = add(&book, 102, 3, Side::Buy);
+        let b3 = add(&book, 100, 4, Side::Buy);
+        let a1 = add(&book, 99, 6, Side::Sell);
+        let a2 = add(&book, 101, 4, Side::Sell);
+
+        let result = book.uncross().unwrap().unwrap();
+        assert_eq!(result.uncross.price, 101);
+        assert_eq!(result.uncross.matched_quantity, 8);
+
+        let executions: Vec<_> = result
+            .transactions
+            .iter()
+            .map(|t| (t.taker_order_id, t.maker_order_id, t.price, t.quantity))
+            .collect();
+        assert_eq!(
+            executions,
+            vec![(b1, a1, 101, 5), (b2, a1, 101, 1), (b2, a2, 101, 2)]
+        );
+
+        assert!(book.get_order(b1).is_none());
+        assert!(book.get_order(b2).is_none());
+        assert!(book.get_order(a1).is_none());
+        assert_eq!(book.get_order(a2).unwrap().total_quantity(), 2);
+        assert!(book.get_order(b3).is_some());
+        assert_eq!(book.best_bid(), Some(100));
+        assert_eq!(book.best_ask(), Some(101));
+        assert_eq!(book.last_trade_price(), Some(101));
+        assert_eq!(book.indicative_uncross(), None);
+
+        let trades = trades.lock().unwrap();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].match_result.order_id, b1);
+        assert_eq!(trades[1].match_result.executed_quantity(), 3);
+    }
+
+    #[test]
+    fn test_uncross_reports_fills_to_both_sides() {
+        let mut book = pre_open_book();
+        let events: Arc<Mutex<Vec<OrderEvent>>> = Arc::default();
+        let sink = events.clone();
+        book.set_order_event_listener(Arc::new(move |event| {
+            sink.lock().unwrap().push(event.clone());
+        }));
+        let buy = add(&book, 101, 5, Side::Buy);
+        let sell = add(&book, 100, 8, Side::Sell);
+        events.lock().unwrap().clear();
+
+        book.uncross().unwrap();
+        let events = events.lock().unwrap();
+        assert!(events.contains(&OrderEvent::Filled {
+            order_id: buy,
+            price: 100,
+            quantity: 5
+        }));
+        assert!(events.contains(&OrderEvent::PartiallyFilled {
+            order_id: sell,
+            price: 100,
+            quantity: 5
+        }));
+    }
+
+    #[test]
+    fn test_uncross_requires_pre_open() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        assert!(matches!(
+            book.uncross(),
+            Err(OrderBookError::SessionRejected {
+                state: SessionState::Open,
+                ..
+            })
+        ));
+        assert!(book.open_with_auction().is_err());
+    }
+
+    #[test]
+    fn test_open_with_auction_starts_continuous_trading() {
+        let book = pre_open_book();
+        add(&book, 101, 5, Side::Buy);
+        add(&book, 100, 5, Side::Sell);
+
+        let result = book.open_with_auction().unwrap().unwrap();
+        assert_eq!(result.uncross.matched_quantity, 5);
+        assert_eq!(book.session_state(), SessionState::Open);
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.best_ask(), None);
+    }
+
+    #[test]
+    fn test_close_with_auction_closes_the_session() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        add(&book, 98, 5, Side::Buy);
+        book.set_session_state(SessionState::PreOpen).unwrap();
+        add(&book, 101, 5, Side::Buy);
+        add(&book, 100, 3, Side::Sell);
+        let day = OrderId::new();
+        book.add_limit_order(day, 97, 1, Side::Buy, TimeInForce::Day, None)
+            .unwrap();
+
+        let result = book.close_with_auction().unwrap().unwrap();
+        assert_eq!(result.uncross.matched_quantity, 3);
+        assert_eq!(book.session_state(), SessionState::Closed);
+        assert!(book.get_order(day).is_none());
+        assert_eq!(book.best_bid(), Some(101));
+    }
+
+    #[test]
+    fn test_uncross_is_replayed() {
+        let journal = Arc::new(InMemoryJournal::new());
+        let mut book = pre_open_book();
+        book.set_journal(journal.clone());
+        add(&book, 102, 5, Side::Buy);
+        add(&book, 99, 3, Side::Sell);
+        add(&book, 101, 4, Side::Sell);
+        book.open_with_auction().unwrap();
+
+        let mut recovered = pre_open_book();
+        recovered.replay_journal(journal.as_ref(), 0).unwrap();
+        assert_eq!(recovered.session_state(), SessionState::Open);
+        assert_eq!(recovered.best_ask(), book.best_ask());
+        assert_eq!(
+            recovered.total_depth_at_levels(1, Side::Sell),
+            book.total_depth_at_levels(1, Side::Sell)
+        );
+        assert_eq!(recovered.last_trade_price(), Some(101));
+    }
+}
@@ -0,0 +1,146 @@
+//! Unit tests for the top-of-book accessor.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::book_change_event::BboChangedEvent;
+    use crate::orderbook::owner::OwnerId;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn add(book: &OrderBook<()>, price: u64, quantity: u64, side: Side) {
+        book.add_limit_order(
+            OrderId::new(),
+            price,
+            quantity,
+            side,
+            TimeInForce::Gtc,
+            None,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_bbo_reports_best_levels_and_quantities() {
+        let book = OrderBook::new("TEST");
+        add(&book, 99, 10, Side::Buy);
+        add(&book, 100, 4, Side::Buy);
+        add(&book, 100, 6, Side::Buy);
+        add(&book, 102, 7, Side::Sell);
+        add(&book, 103, 20, Side::Sell);
+
+        let bbo = book.bbo().unwrap();
+        assert_eq!(bbo.bid_px, 100);
+        assert_eq!(bbo.bid_qty, 10);
+        assert_eq!(bbo.ask_px, 102);
+        assert_eq!(bbo.ask_qty, 7);
+        assert!(bbo.ts > 0);
+    }
+
+    #[test]
+    fn test_bbo_requires_both_sides() {
+        let book = OrderBook::new("TEST");
+        assert!(book.bbo().is_none());
+
+        add(&book, 99, 10, Side::Buy);
+        assert!(book.bbo().is_none());
+
+        add(&book, 101, 10, Side::Sell);
+        assert!(book.bbo().is_some());
+    }
+
+    #[test]
+    fn test_bbo_follows_matching() {
+        let book = OrderBook::new("TEST");
+        add(&book, 99, 10, Side::Buy);
+        add(&book, 101, 5, Side::Sell);
+        add(&book, 102, 8, Side::Sell);
+
+        book.match_order(OrderId::new(), Side::Buy, 7, None)
+            .unwrap();
+
+        let bbo = book.bbo().unwrap();
+        assert_eq!((bbo.ask_px, bbo.ask_qty), (102, 6));
+    }
+
+    fn book_with_bbo_listener() -> (OrderBook<()>, Arc<Mutex<Vec<BboChangedEvent>>>) {
+        let mut book = OrderBook::new("TEST");
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        book.set_bbo_listener(Arc::new(move |event| sink.lock().unwrap().push(event)));
+        (book, events)
+    }
+
+    #[test]
+    fn test_bbo_listener_ignores_deep_levels() {
+        let (book, events) = book_with_bbo_listener();
+        add(&book, 100, 10, Side::Buy);
+        add(&book, 102, 10, Side::Sell);
+        assert_eq!(events.lock().unwrap().len(), 2);
+
+        add(&book, 98, 10, Side::Buy);
+        add(&book, 105, 10, Side::Sell);
+        assert_eq!(events.lock().unwrap().len(), 2);
+
+        add(&book, 100, 5, Side::Buy);
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[2],
+            BboChangedEvent {
+                bid_price: Some(100),
+                bid_quantity: 15,
+                ask_price: Some(102),
+                ask_quantity: 10,
+            }
+        );
+    }
+
+    #[test]
+    fn test_bbo_listener_reports_level_removal() {
+        let (book, events) = book_with_bbo_listener();
+        let id = OrderId::new();
+        book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        add(&book, 99, 7, Side::Buy);
+
+        book.cancel_order(id).unwrap();
+        let last = *events.lock().unwrap().last().unwrap();
+        assert_eq!(last.bid_price, Some(99));
+        assert_eq!(last.bid_quantity, 7);
+
+        book.cancel_all().unwrap();
+        let last = *events.lock().unwrap().last().unwrap();
+        assert_eq!(last, BboChangedEvent::default());
+    }
+
+    #[test]
+    fn test_bbo_listener_reports_once_per_batched_operation() {
+        let (book, events) = book_with_bbo_listener();
+        book.update_quotes(OwnerId(1), 99, 10, 101, 10).unwrap();
+        assert_eq!(events.lock().unwrap().len(), 1);
+
+        book.update_quotes(OwnerId(1), 100, 5, 102, 5).unwrap();
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].bid_price, Some(100));
+        assert_eq!(events[1].ask_price, Some(102));
+    }
+
+    #[test]
+    fn test_bbo_listener_starts_from_current_book() {
+        let mut book = OrderBook::new("TEST");
+        add(&book, 100, 10, Side::Buy);
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        book.set_bbo_listener(Arc::new(move |event| sink.lock().unwrap().push(event)));
+
+        add(&book, 99, 10, Side::Buy);
+        assert!(events.lock().unwrap().is_empty());
+
+        book.remove_bbo_listener();
+        add(&book, 101, 10, Side::Buy);
+        assert!(events.lock().unwrap().is_empty());
+    }
+}
@@ -868,6 +868,107 @@ mod test_book_remaining {
         );
     }
 
+    #[test]
+    fn test_best_prices_maintained_under_churn() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        let mut resting = Vec::new();
+
+        for step in 0..300u64 {
+            let (price, side) = if step % 2 == 0 {
+                (1000 - (step * 13) % 50, Side::Buy)
+            } else {
+                (1001 + (step * 17) % 50, Side::Sell)
+            };
+            let id = create_order_id();
+            book.add_limit_order(id, price, 1 + step % 5, side, TimeInForce::Gtc, None)
+                .unwrap();
+            resting.push(id);
+
+            if step % 3 == 0 {
+                let id = resting.swap_remove((step as usize * 7) % resting.len());
+                let _ = book.cancel_order(id);
+            }
+            if step % 20 == 19 {
+                let side = if step % 40 == 39 {
+                    Side::Buy
+                } else {
+                    Side::Sell
+                };
+                let _ = book.submit_market_order(create_order_id(), 12, side);
+            }
+
+            let best_bid = book.bids.back().map(|entry| *entry.key());
+            let best_ask = book.asks.front().map(|entry| *entry.key());
+            assert_eq!(book.best_bid(), best_bid);
+            assert_eq!(book.best_ask(), best_ask);
+        }
+    }
+
+    #[test]
+    fn test_side_totals_maintained_under_churn() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        let mut resting = Vec::new();
+        let summed = |side: Side| -> u64 {
+            let price_levels = match side {
+                Side::Buy => &book.bids,
+                Side::Sell => &book.asks,
+            };
+            price_levels
+                .iter()
+                .map(|entry| entry.value().total_quantity())
+                .sum()
+        };
+
+        for step in 0..300u64 {
+            let (price, side) = if step % 2 == 0 {
+                (1000 - (step * 13) % 50, Side::Buy)
+            } else {
+                (1001 + (step * 17) % 50, Side::Sell)
+            };
+            let id = create_order_id();
+            if step % 7 == 0 {
+                book.add_iceberg_order(id, price, 2, 8, side, TimeInForce::Gtc, None)
+                    .unwrap();
+            } else {
+                book.add_limit_order(id, price, 1 + step % 5, side, TimeInForce::Gtc, None)
+                    .unwrap();
+            }
+            resting.push(id);
+
+            if step % 3 == 0 {
+                let id = resting.swap_remove((step as usize * 7) % resting.len());
+                let _ = book.cancel_order(id);
+            }
+            if step % 5 == 2 && !resting.is_empty() {
+                let id = resting[(step as usize * 3) % resting.len()];
+                let _ = book.update_order(pricelevel::OrderUpdate::UpdateQuantity {
+                    order_id: id,
+                    new_quantity: 1 + step % 9,
+                });
+            }
+            if step % 20 == 19 {
+                let side = if step % 40 == 39 {
+                    Side::Buy
+                } else {
+                    Side::Sell
+                };
+                let _ = book.submit_market_order(create_order_id(), 12, side);
+            }
+            if step % 100 == 99 {
+                book.cancel_range(980, 990, Side::Buy).unwrap();
+            }
+
+            let (buy, sell) = book.buy_sell_pressure();
+            assert_eq!(buy, summed(Side::Buy));
+            assert_eq!(sell, summed(Side::Sell));
+            assert_eq!(book.level_count(Side::Buy), book.bids.len());
+            assert_eq!(
+                book.total_depth_at_levels(usize::MAX, Side::Sell),
+                summed(Side::Sell)
+            );
+        }
+    }
+
     #[test]
     fn test_best_bid_ask_with_multiple_levels() {
         let book: OrderBook<()> = OrderBook::new("TEST");
@@ -0,0 +1,217 @@
+//! Unit tests for circuit breaker price bands.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::circuit_breaker::{
+        BandAction, BandTrigger, CircuitBreaker, PriceBandEvent, PriceBandLimits,
+        ReferencePriceSource,
+    };
+    use crate::orderbook::error::OrderBookError;
+    use crate::orderbook::journal::InMemoryJournal;
+    use crate::orderbook::session::SessionState;
+    use pricelevel::{OrderId, OrderUpdate, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn add(
+        book: &OrderBook<()>,
+        price: u64,
+        quantity: u64,
+        side: Side,
+    ) -> Result<OrderId, OrderBookError> {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, side, TimeInForce::Gtc, None)
+            .map(|_| id)
+    }
+
+    fn recorded_book(breaker: CircuitBreaker) -> (OrderBook<()>, Arc<Mutex<Vec<PriceBandEvent>>>) {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.set_circuit_breaker(breaker);
+        let events: Arc<Mutex<Vec<PriceBandEvent>>> = Arc::default();
+        let sink = events.clone();
+        book.set_price_band_listener(Arc::new(move |event| {
+            sink.lock().unwrap().push(event.clone());
+        }));
+        (book, events)
+    }
+
+    #[test]
+    fn test_limits_combine_static_and_dynamic_bands() {
+        let breaker = CircuitBreaker::new(ReferencePriceSource::LastTrade)
+            .with_static_band(900, 1_200)
+            .with_dynamic_band_bps(1_000);
+
+        let limits = breaker.limits(Some(1_000));
+        assert_eq!((limits.lower, limits.upper), (Some(900), Some(1_100)));
+        assert!(limits.contains(1_100));
+        assert!(!limits.contains(1_101));
+
+        let limits = breaker.limits(Some(850));
+        assert_eq!((limits.lower, limits.upper), (Some(900), Some(935)));
+
+        // The dynamic band is inactive until the reference price is known
+        assert_eq!(
+            breaker.limits(None),
+            PriceBandLimits {
+                reference_price: None,
+                lower: Some(900),
+                upper: Some(1_200),
+            }
+        );
+    }
+
+    #[test]
+    fn test_static_band_rejects_orders_and_reports_hits() {
+        let (book, events) = recorded_book(
+            CircuitBreaker::new(ReferencePriceSource::LastTrade).with_static_band(90, 110),
+        );
+
+        assert!(add(&book, 100, 1, Side::Buy).is_ok());
+        let order = OrderId::new();
+        let error = book
+            .add_limit_order(order, 111, 1, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            OrderBookError::PriceOutOfBand {
+                price: 111,
+                min_price: Some(90),
+                max_price: Some(110),
+            }
+        ));
+        assert_eq!(book.session_state(), SessionState::Open);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].order_id, order);
+        assert_eq!(events[0].trigger, BandTrigger::Order);
+        assert_eq!(events[0].action, BandAction::Reject);
+    }
+
+    #[test]
+    fn test_dynamic_band_follows_last_trade() {
+        let (book, _) = recorded_book(
+            CircuitBreaker::new(ReferencePriceSource::LastTrade).with_dynamic_band_bps(500),
+        );
+        assert!(add(&book, 2_000, 1, Side::Sell).is_ok());
+        add(&book, 2_000, 1, Side::Buy).unwrap();
+        assert_eq!(book.last_trade_price(), Some(2_000));
+
+        assert!(add(&book, 2_100, 1, Side::Sell).is_ok());
+        assert!(add(&book, 2_101, 1, Side::Sell).is_err());
+        assert!(add(&book, 1_899, 1, Side::Buy).is_err());
+    }
+
+    #[test]
+    fn test_halt_action_halts_the_book() {
+        let (book, events) = recorded_book(
+            CircuitBreaker::new(ReferencePriceSource::PriorClose)
+                .with_dynamic_band_bps(1_000)
+                .with_action(BandAction::Halt),
+        );
+        book.set_reference_price(ReferencePriceSource::PriorClose, Some(100));
+        assert_eq!(
+            book.reference_price(ReferencePriceSource::PriorClose),
+            Some(100)
+        );
+
+        assert!(add(&book, 120, 1, Side::Buy).is_err());
+        assert_eq!(book.session_state(), SessionState::Halted);
+        assert_eq!(events.lock().unwrap()[0].action, BandAction::Halt);
+    }
+
+    #[test]
+    fn test_sweep_stops_at_band() {
+        let (book, events) = recorded_book(
+            CircuitBreaker::new(ReferencePriceSource::External).with_dynamic_band_bps(150),
+        );
+        add(&book, 1_010, 5, Side::Sell).unwrap();
+        add(&book, 1_020, 5, Side::Sell).unwrap();
+        // The asks rested before the reference price was known
+        book.set_reference_price(ReferencePriceSource::External, Some(1_000));
+
+        let result = book
+            .submit_market_order(OrderId::new(), 10, Side::Buy)
+            .unwrap();
+        assert_eq!(result.executed_quantity(), 5);
+        assert_eq!(book.best_ask(), Some(1_020));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].trigger, BandTrigger::Trade);
+        assert_eq!(events[0].price, 1_020);
+        assert_eq!(events[0].limits.upper, Some(1_015));
+        assert_eq!(book.session_state(), SessionState::Open);
+    }
+
+    #[test]
+    fn test_sweep_with_halt_action_halts_after_partial_fill() {
+        let (book, _) = recorded_book(
+            CircuitBreaker::new(ReferencePriceSource::External)
+                .with_dynamic_band_bps(100)
+                .with_action(BandAction::Halt),
+        );
+        add(&book, 1_000, 5, Side::Sell).unwrap();
+        add(&book, 1_050, 5, Side::Sell).unwrap();
+        book.set_reference_price(ReferencePriceSource::External, Some(1_000));
+
+        let result = book
+            .submit_market_order(OrderId::new(), 10, Side::Buy)
+            .unwrap();
+        assert_eq!(result.executed_quantity(), 5);
+        assert_eq!(book.session_state(), SessionState::Halted);
+    }
+
+    #[test]
+    fn test_price_updates_outside_band_keep_the_order() {
+        let (book, _) = recorded_book(
+            CircuitBreaker::new(ReferencePriceSource::LastTrade).with_static_band(90, 110),
+        );
+        let bid = add(&book, 100, 1, Side::Buy).unwrap();
+        let update = OrderUpdate::UpdatePrice {
+            order_id: bid,
+            new_price: 80,
+        };
+        assert!(book.update_order(update).is_err());
+        assert_eq!(book.best_bid(), Some(100));
+    }
+
+    #[test]
+    fn test_prior_close_recorded_at_close() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        add(&book, 105, 1, Side::Sell).unwrap();
+        add(&book, 105, 1, Side::Buy).unwrap();
+        assert_eq!(book.reference_price(ReferencePriceSource::PriorClose), None);
+
+        book.set_session_state(SessionState::Closed).unwrap();
+        assert_eq!(
+            book.reference_price(ReferencePriceSource::PriorClose),
+            Some(105)
+        );
+    }
+
+    #[test]
+    fn test_reference_prices_are_replayed() {
+        let journal = Arc::new(InMemoryJournal::new());
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.set_journal(journal.clone());
+        book.set_reference_price(ReferencePriceSource::External, Some(500));
+
+        let mut recovered = OrderBook::<()>::new("BTC/USD");
+        recovered.replay_journal(journal.as_ref(), 0).unwrap();
+        assert_eq!(
+            recovered.reference_price(ReferencePriceSource::External),
+            Some(500)
+        );
+    }
+
+    #[test]
+    fn test_no_breaker_no_limits() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        assert_eq!(book.price_band_limits(), None);
+        book.set_circuit_breaker(CircuitBreaker::new(ReferencePriceSource::LastTrade));
+        assert_eq!(book.price_band_limits(), Some(PriceBandLimits::default()));
+        book.remove_circuit_breaker();
+        assert!(book.circuit_breaker().is_none());
+    }
+}
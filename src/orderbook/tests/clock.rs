@@ -0,0 +1,84 @@
+//! Unit tests for driving order books with an injected clock.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::error::OrderBookError;
+    use crate::orderbook::manager::{BookManager, BookManagerStd};
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn book_at(now: u64) -> (OrderBook<()>, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock::new(now));
+        let mut book = OrderBook::new("TEST");
+        book.set_clock(clock.clone());
+        (book, clock)
+    }
+
+    #[test]
+    fn test_order_timestamps_come_from_clock() {
+        let (book, _) = book_at(5_000);
+        let id = OrderId::new();
+        book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        assert_eq!(book.get_order(id).unwrap().timestamp(), 5_000);
+        assert_eq!(book.create_snapshot(1).timestamp, 5_000);
+    }
+
+    #[test]
+    fn test_gtd_expiry_follows_clock() {
+        let (book, clock) = book_at(1_000);
+        book.add_limit_order(
+            OrderId::new(),
+            100,
+            10,
+            Side::Buy,
+            TimeInForce::Gtd(2_000),
+            None,
+        )
+        .unwrap();
+
+        clock.set(2_500);
+        let result = book.add_limit_order(
+            OrderId::new(),
+            100,
+            10,
+            Side::Buy,
+            TimeInForce::Gtd(2_000),
+            None,
+        );
+        assert!(matches!(
+            result,
+            Err(OrderBookError::InvalidOperation { .. })
+        ));
+    }
+
+    #[test]
+    fn test_day_expiry_follows_clock() {
+        let (book, clock) = book_at(1_000);
+        book.set_market_close_timestamp(3_000);
+        assert!(
+            book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Day, None)
+                .is_ok()
+        );
+
+        clock.set(3_000);
+        assert!(
+            book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Day, None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_manager_shares_clock_with_books() {
+        let clock = Arc::new(ManualClock::new(7_000));
+        let mut manager = BookManagerStd::<()>::with_clock(clock.clone());
+        manager.add_book("TEST");
+        let book = manager.get_book("TEST").unwrap();
+
+        clock.advance(100);
+        assert_eq!(book.create_snapshot(1).timestamp, 7_100);
+    }
+}
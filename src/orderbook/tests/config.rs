@@ -0,0 +1,195 @@
+//! Unit tests for per-book tick size, lot size and minimum notional rules.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::config::BookConfig;
+    use crate::orderbook::error::OrderBookError;
+    use pricelevel::{OrderId, OrderUpdate, Side, TimeInForce};
+
+    fn configured_book() -> OrderBook<()> {
+        OrderBook::new_with_config(
+            "TEST",
+            BookConfig::default()
+                .with_tick_size(5)
+                .with_lot_size(10)
+                .with_min_notional(1_000),
+        )
+    }
+
+    #[test]
+    fn test_default_config_accepts_anything() {
+        let book = OrderBook::<()>::new("TEST");
+        assert_eq!(*book.config(), BookConfig::default());
+        assert!(
+            book.add_limit_order(OrderId::new(), 101, 3, Side::Buy, TimeInForce::Gtc, None)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_rejects_off_tick_price() {
+        let book = configured_book();
+        let result =
+            book.add_limit_order(OrderId::new(), 102, 20, Side::Buy, TimeInForce::Gtc, None);
+        assert!(matches!(
+            result,
+            Err(OrderBookError::InvalidTickSize {
+                price: 102,
+                tick_size: 5
+            })
+        ));
+        assert_eq!(book.best_bid(), None);
+    }
+
+    #[test]
+    fn test_rejects_odd_lot_quantity() {
+        let book = configured_book();
+        let result =
+            book.add_limit_order(OrderId::new(), 100, 25, Side::Buy, TimeInForce::Gtc, None);
+        assert!(matches!(
+            result,
+            Err(OrderBookError::InvalidLotSize {
+                quantity: 25,
+                lot_size: 10
+            })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_iceberg_with_odd_lot_visible_quantity() {
+        let book = configured_book();
+        let result = book.add_iceberg_order(
+            OrderId::new(),
+            100,
+            5,
+            15,
+            Side::Buy,
+            TimeInForce::Gtc,
+            None,
+        );
+        assert!(matches!(
+            result,
+            Err(OrderBookError::InvalidLotSize { quantity: 5, .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_below_min_notional() {
+        let book = configured_book();
+        let result =
+            book.add_limit_order(OrderId::new(), 50, 10, Side::Buy, TimeInForce::Gtc, None);
+        assert!(matches!(
+            result,
+            Err(OrderBookError::BelowMinNotional {
+                notional: 500,
+                min_notional: 1_000
+            })
+        ));
+        assert!(
+            book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_market_orders_and_updates_are_validated() {
+        let book = configured_book();
+        let id = OrderId::new();
+        book.add_limit_order(id, 100, 20, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+
+        assert!(matches!(
+            book.submit_market_order(OrderId::new(), 7, Side::Buy),
+            Err(OrderBookError::InvalidLotSize { .. })
+        ));
+        assert!(matches!(
+            book.update_order(OrderUpdate::UpdateQuantity {
+                order_id: id,
+                new_quantity: 15,
+            }),
+            Err(OrderBookError::InvalidLotSize { .. })
+        ));
+        assert!(matches!(
+            book.update_order(OrderUpdate::UpdatePrice {
+                order_id: id,
+                new_price: 103,
+            }),
+            Err(OrderBookError::InvalidTickSize { .. })
+        ));
+        assert!(
+            book.submit_market_order(OrderId::new(), 10, Side::Buy)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_price_band() {
+        let book = OrderBook::<()>::new_with_config(
+            "TEST",
+            BookConfig::default().with_price_band(90, 110),
+        );
+        for price in [90, 100, 110] {
+            assert!(
+                book.add_limit_order(OrderId::new(), price, 1, Side::Buy, TimeInForce::Gtc, None)
+                    .is_ok()
+            );
+        }
+        let result =
+            book.add_limit_order(OrderId::new(), 111, 1, Side::Sell, TimeInForce::Gtc, None);
+        assert!(matches!(
+            result,
+            Err(OrderBookError::PriceOutOfBand {
+                price: 111,
+                min_price: Some(90),
+                max_price: Some(110),
+            })
+        ));
+        assert!(
+            book.add_limit_order(OrderId::new(), 89, 1, Side::Buy, TimeInForce::Gtc, None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_one_sided_price_band() {
+        let config = BookConfig {
+            min_price: Some(10),
+            ..BookConfig::default()
+        };
+        assert!(config.validate_price_band(u64::MAX).is_ok());
+        let error = config.validate_price_band(9).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Price 9 is outside the price band [10, -]"
+        );
+    }
+
+    #[test]
+    fn test_location_shards_keep_orders_locatable() {
+        // A count read from elsewhere need not be a power of two
+        let config = BookConfig {
+            location_shards: Some(3),
+            ..BookConfig::default()
+        };
+        let book = OrderBook::<()>::new_with_config("TEST", config);
+        let ids: Vec<OrderId> = (0..50).map(|_| OrderId::new()).collect();
+        for (i, &id) in ids.iter().enumerate() {
+            book.add_limit_order(id, 100 + i as u64, 10, Side::Buy, TimeInForce::Gtc, None)
+                .unwrap();
+        }
+        assert!(book.cancel_order(ids[0]).unwrap().is_some());
+        assert!(book.get_order(ids[0]).is_none());
+
+        // Restoring into a book with other shards moves the locations over
+        let mut restored = OrderBook::<()>::new("TEST");
+        restored
+            .restore_full_state(book.create_full_state())
+            .unwrap();
+        assert_eq!(restored.config().location_shards, Some(3));
+        for &id in &ids[1..] {
+            assert!(restored.get_order(id).is_some());
+        }
+        assert!(restored.cancel_order(ids[1]).unwrap().is_some());
+    }
+}
@@ -0,0 +1,129 @@
+//! Unit tests for the consolidated multi-venue book.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::consolidated::ConsolidatedBook;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn venue(orders: &[(u64, u64, Side)]) -> Arc<OrderBook<()>> {
+        let book = Arc::new(OrderBook::<()>::new("BTC/USD"));
+        for &(price, quantity, side) in orders {
+            book.add_limit_order(
+                OrderId::new(),
+                price,
+                quantity,
+                side,
+                TimeInForce::Gtc,
+                None,
+            )
+            .unwrap();
+        }
+        book
+    }
+
+    fn consolidated() -> ConsolidatedBook<()> {
+        let mut book = ConsolidatedBook::new("BTC/USD");
+        book.add_venue(
+            "alpha",
+            venue(&[
+                (100, 5, Side::Buy),
+                (99, 10, Side::Buy),
+                (103, 2, Side::Sell),
+            ]),
+        );
+        book.add_venue(
+            "beta",
+            venue(&[
+                (100, 7, Side::Buy),
+                (100, 1, Side::Buy),
+                (98, 4, Side::Buy),
+                (102, 6, Side::Sell),
+                (103, 3, Side::Sell),
+            ]),
+        );
+        book
+    }
+
+    #[test]
+    fn test_depth_merges_venues() {
+        let book = consolidated();
+        let (bids, asks) = book.depth(10);
+
+        let bid_prices: Vec<u64> = bids.iter().map(|level| level.price).collect();
+        assert_eq!(bid_prices, vec![100, 99, 98]);
+        assert_eq!((bids[0].quantity, bids[0].order_count), (13, 3));
+        let venues: Vec<(&str, u64)> = bids[0]
+            .venues
+            .iter()
+            .map(|v| (v.venue.as_str(), v.quantity))
+            .collect();
+        assert_eq!(venues, vec![("beta", 8), ("alpha", 5)]);
+
+        let ask_prices: Vec<u64> = asks.iter().map(|level| level.price).collect();
+        assert_eq!(ask_prices, vec![102, 103]);
+        assert_eq!(asks[1].quantity, 5);
+        assert_eq!(asks[1].venues.len(), 2);
+
+        let (bids, asks) = book.depth(1);
+        assert_eq!((bids.len(), asks.len()), (1, 1));
+    }
+
+    #[test]
+    fn test_nbbo() {
+        let book = consolidated();
+        let nbbo = book.nbbo();
+        assert_eq!(nbbo.bid.as_ref().unwrap().price, 100);
+        assert_eq!(nbbo.ask.as_ref().unwrap().venues[0].venue, "beta");
+        assert_eq!(nbbo.spread(), Some(2));
+        assert!(!nbbo.is_locked() && !nbbo.is_crossed());
+        assert_eq!(book.best_bid(), Some(100));
+        assert_eq!(book.best_ask(), Some(102));
+        assert_eq!(book.mid_price(), Some(101.0));
+    }
+
+    #[test]
+    fn test_crossed_venues() {
+        let mut book = consolidated();
+        book.add_venue("gamma", venue(&[(101, 1, Side::Sell)]));
+        book.add_venue("delta", venue(&[(102, 1, Side::Buy)]));
+
+        let nbbo = book.nbbo();
+        assert!(nbbo.is_crossed());
+        assert_eq!(nbbo.spread(), None);
+        assert_eq!(book.venues(), vec!["alpha", "beta", "gamma", "delta"]);
+
+        book.remove_venue("delta").unwrap();
+        assert!(book.remove_venue("delta").is_none());
+        book.add_venue("gamma", venue(&[(100, 1, Side::Sell)]));
+        assert!(book.nbbo().is_locked());
+        assert_eq!(book.venues().len(), 3);
+    }
+
+    #[test]
+    fn test_imbalance_and_shares() {
+        let book = consolidated();
+        assert_eq!(book.total_depth(2, Side::Buy), 23);
+        assert_eq!(book.total_depth(2, Side::Sell), 11);
+        let expected = (23.0 - 11.0) / 34.0;
+        assert!((book.imbalance(2) - expected).abs() < 1e-12);
+
+        let shares = book.venue_shares(10, Side::Sell);
+        assert_eq!(shares[0].0, "alpha");
+        assert!((shares[0].1 - 2.0 / 11.0).abs() < 1e-12);
+        assert!((shares[1].1 - 9.0 / 11.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_views_follow_venue_updates() {
+        let book = consolidated();
+        let beta = Arc::clone(book.venue("beta").unwrap());
+        beta.add_limit_order(OrderId::new(), 101, 2, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        assert_eq!(book.best_bid(), Some(101));
+        assert_eq!(book.nbbo().bid.unwrap().venues[0].venue, "beta");
+        assert_eq!(ConsolidatedBook::<()>::new("X").imbalance(5), 0.0);
+    }
+}
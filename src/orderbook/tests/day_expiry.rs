@@ -0,0 +1,134 @@
+//! Unit tests for expiring DAY orders at the market close.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::journal::InMemoryJournal;
+    use crate::orderbook::order_event::OrderEvent;
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn book_at(now: u64) -> (OrderBook<()>, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock::new(now));
+        let mut book = OrderBook::new("TEST");
+        book.set_clock(clock.clone());
+        (book, clock)
+    }
+
+    fn add(book: &OrderBook<()>, price: u64, side: Side, time_in_force: TimeInForce) -> OrderId {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, 10, side, time_in_force, None)
+            .unwrap();
+        id
+    }
+
+    #[test]
+    fn test_sweep_expires_day_orders_at_close() {
+        let (mut book, _) = book_at(1_000);
+        let events: Arc<Mutex<Vec<OrderEvent>>> = Arc::default();
+        let sink = events.clone();
+        book.set_order_event_listener(Arc::new(move |event| {
+            sink.lock().unwrap().push(event.clone());
+        }));
+        book.set_market_close_timestamp(2_000);
+        let day_bid = add(&book, 100, Side::Buy, TimeInForce::Day);
+        let day_ask = add(&book, 110, Side::Sell, TimeInForce::Day);
+        let gtc = add(&book, 99, Side::Buy, TimeInForce::Gtc);
+
+        assert!(book.expire_day_orders(1_999).is_empty());
+        let expired = book.expire_day_orders(2_000);
+        assert_eq!(expired.len(), 2);
+        assert!(expired.contains(&day_bid) && expired.contains(&day_ask));
+
+        assert!(book.get_order(day_bid).is_none());
+        assert!(book.get_order(gtc).is_some());
+        assert_eq!(book.best_bid(), Some(99));
+        assert_eq!(book.best_ask(), None);
+
+        let events = events.lock().unwrap();
+        assert!(events.contains(&OrderEvent::Expired { order_id: day_bid }));
+        assert!(events.contains(&OrderEvent::Expired { order_id: day_ask }));
+        assert!(
+            !events
+                .iter()
+                .any(|e| matches!(e, OrderEvent::Cancelled { .. }))
+        );
+    }
+
+    #[test]
+    fn test_sweep_needs_market_close() {
+        let (book, _) = book_at(1_000);
+        let day = add(&book, 100, Side::Buy, TimeInForce::Day);
+        assert!(book.expire_day_orders(u64::MAX).is_empty());
+
+        book.set_market_close_timestamp(2_000);
+        book.clear_market_close_timestamp();
+        assert!(book.expire_day_orders(u64::MAX).is_empty());
+        assert!(book.get_order(day).is_some());
+    }
+
+    #[test]
+    fn test_day_orders_expire_lazily_on_next_submission() {
+        let (book, clock) = book_at(1_000);
+        book.set_market_close_timestamp(2_000);
+        let day = add(&book, 100, Side::Buy, TimeInForce::Day);
+
+        clock.advance(1_500);
+        assert!(book.get_order(day).is_some());
+        add(&book, 98, Side::Buy, TimeInForce::Gtc);
+        assert!(book.get_order(day).is_none());
+        assert_eq!(book.best_bid(), Some(98));
+
+        // New DAY orders are rejected once the close has passed
+        assert!(
+            book.add_limit_order(OrderId::new(), 97, 1, Side::Buy, TimeInForce::Day, None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_market_orders_do_not_fill_expired_day_orders() {
+        let (book, clock) = book_at(1_000);
+        book.set_market_close_timestamp(2_000);
+        add(&book, 100, Side::Sell, TimeInForce::Day);
+        let gtc = add(&book, 105, Side::Sell, TimeInForce::Gtc);
+
+        clock.advance(1_000);
+        let result = book
+            .submit_market_order(OrderId::new(), 5, Side::Buy)
+            .unwrap();
+        assert_eq!(result.transactions.as_vec()[0].maker_order_id, gtc);
+        assert_eq!(result.transactions.as_vec()[0].price, 105);
+    }
+
+    #[test]
+    fn test_new_close_rearms_expiry() {
+        let (book, clock) = book_at(1_000);
+        book.set_market_close_timestamp(2_000);
+        clock.advance(1_000);
+        add(&book, 90, Side::Buy, TimeInForce::Gtc);
+
+        book.set_market_close_timestamp(5_000);
+        let day = add(&book, 100, Side::Buy, TimeInForce::Day);
+        clock.advance(3_000);
+        add(&book, 90, Side::Buy, TimeInForce::Gtc);
+        assert!(book.get_order(day).is_none());
+    }
+
+    #[test]
+    fn test_sweep_is_replayed() {
+        let journal = Arc::new(InMemoryJournal::new());
+        let (mut book, _) = book_at(1_000);
+        book.set_journal(journal.clone());
+        book.set_market_close_timestamp(2_000);
+        let day = add(&book, 100, Side::Buy, TimeInForce::Day);
+        add(&book, 99, Side::Buy, TimeInForce::Gtc);
+        book.expire_day_orders(2_000);
+
+        let (mut recovered, _) = book_at(1_000);
+        recovered.replay_journal(journal.as_ref(), 0).unwrap();
+        assert!(recovered.get_order(day).is_none());
+        assert_eq!(recovered.best_bid(), Some(99));
+    }
+}
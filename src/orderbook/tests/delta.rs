@@ -0,0 +1,245 @@
+//! Unit tests for incremental level deltas.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::config::BookConfig;
+    use crate::orderbook::delta::LevelChange;
+    use crate::orderbook::order_event::OrderEvent;
+    use crate::orderbook::owner::OwnerId;
+    use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn add(book: &OrderBook<()>, price: u64, quantity: u64, side: Side) -> OrderId {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, side, TimeInForce::Gtc, None)
+            .unwrap();
+        id
+    }
+
+    fn tracked_book(capacity: usize) -> OrderBook<()> {
+        let mut book = OrderBook::new("TEST");
+        book.enable_delta_tracking(capacity);
+        book
+    }
+
+    #[test]
+    fn test_delta_requires_tracking() {
+        let book = OrderBook::<()>::new("TEST");
+        assert_eq!(book.delta_sequence(), None);
+        assert!(book.delta_since(0).is_err());
+    }
+
+    #[test]
+    fn test_delta_reports_added_updated_and_removed_levels() {
+        let book = tracked_book(100);
+        let resting = add(&book, 100, 10, Side::Buy);
+        add(&book, 105, 5, Side::Sell);
+        let start = book.delta_sequence().unwrap();
+
+        add(&book, 100, 4, Side::Buy);
+        add(&book, 99, 7, Side::Buy);
+        book.cancel_order(add(&book, 106, 3, Side::Sell)).unwrap();
+        book.cancel_order(resting).unwrap();
+        book.submit_market_order(OrderId::new(), 5, Side::Buy)
+            .unwrap();
+
+        let delta = book.delta_since(start).unwrap();
+        assert_eq!(delta.symbol, "TEST");
+        assert_eq!(delta.from_sequence, start);
+        assert_eq!(delta.to_sequence, book.delta_sequence().unwrap());
+        assert_eq!(
+            delta.changes,
+            vec![
+                LevelChange::Updated {
+                    side: Side::Buy,
+                    price: 100,
+                    quantity: 4
+                },
+                LevelChange::Added {
+                    side: Side::Buy,
+                    price: 99,
+                    quantity: 7
+                },
+                LevelChange::Removed {
+                    side: Side::Sell,
+                    price: 105
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_delta_since_latest_sequence_is_empty() {
+        let book = tracked_book(100);
+        add(&book, 100, 10, Side::Buy);
+
+        let delta = book.delta_since(book.delta_sequence().unwrap()).unwrap();
+        assert!(delta.is_empty());
+        assert!(
+            book.delta_since(book.delta_sequence().unwrap() + 1)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_delta_rejects_sequence_evicted_from_history() {
+        let book = tracked_book(2);
+        add(&book, 100, 10, Side::Buy);
+        add(&book, 101, 10, Side::Buy);
+        add(&book, 102, 10, Side::Buy);
+
+        assert!(book.delta_since(0).is_err());
+        let delta = book.delta_since(1).unwrap();
+        assert_eq!(delta.changes.len(), 2);
+    }
+
+    #[test]
+    fn test_snapshot_plus_delta_reproduces_depth() {
+        let source = tracked_book(1_000);
+        let maker = add(&source, 100, 10, Side::Buy);
+        add(&source, 101, 3, Side::Buy);
+        add(&source, 103, 8, Side::Sell);
+
+        let snapshot = source.create_snapshot(usize::MAX);
+        let start = source.delta_sequence().unwrap();
+        let replica = OrderBook::<()>::new("TEST");
+        replica.restore_from_snapshot(snapshot).unwrap();
+
+        source.cancel_order(maker).unwrap();
+        add(&source, 104, 2, Side::Sell);
+        source
+            .submit_market_order(OrderId::new(), 5, Side::Buy)
+            .unwrap();
+        add(&source, 102, 1, Side::Buy);
+
+        let delta = source.delta_since(start).unwrap();
+        replica.apply_delta(&delta).unwrap();
+
+        assert_eq!(replica.depth(usize::MAX), source.depth(usize::MAX));
+        assert_eq!(replica.depth(usize::MAX).0, vec![(102, 1, 1), (101, 3, 1)]);
+        assert_eq!(replica.depth(usize::MAX).1, vec![(103, 3, 1), (104, 2, 1)]);
+    }
+
+    #[test]
+    fn test_apply_delta_rejects_other_symbol() {
+        let source = tracked_book(10);
+        add(&source, 100, 10, Side::Buy);
+        let delta = source.delta_since(0).unwrap();
+
+        let replica = OrderBook::<()>::new("OTHER");
+        assert!(replica.apply_delta(&delta).is_err());
+        assert!(replica.best_bid().is_none());
+    }
+
+    #[test]
+    fn test_restore_resets_delta_history() {
+        let book = tracked_book(100);
+        add(&book, 100, 10, Side::Buy);
+        let snapshot = book.create_snapshot(usize::MAX);
+        add(&book, 101, 10, Side::Buy);
+
+        book.restore_from_snapshot(snapshot).unwrap();
+        let sequence = book.delta_sequence().unwrap();
+        assert!(book.delta_since(sequence.saturating_sub(1)).is_err());
+
+        add(&book, 102, 5, Side::Sell);
+        let delta = book.delta_since(sequence).unwrap();
+        assert_eq!(
+            delta.changes,
+            vec![LevelChange::Added {
+                side: Side::Sell,
+                price: 102,
+                quantity: 5
+            }]
+        );
+    }
+
+    #[test]
+    fn test_l2_updates_reconcile_levels() {
+        let book = OrderBook::<()>::new("TEST");
+        add(&book, 100, 4, Side::Buy);
+        add(&book, 100, 6, Side::Buy);
+
+        book.apply_l2_update(100, 7, Side::Buy).unwrap();
+        book.apply_l2_update(101, 3, Side::Buy).unwrap();
+        book.apply_l2_update(103, 5, Side::Sell).unwrap();
+        assert_eq!(book.depth(usize::MAX).0, vec![(101, 3, 1), (100, 7, 1)]);
+        assert_eq!(book.best_ask(), Some(103));
+
+        book.apply_l2_update(101, 0, Side::Buy).unwrap();
+        book.apply_l2_update(99, 0, Side::Buy).unwrap();
+        assert_eq!(book.best_bid(), Some(100));
+        assert_eq!(book.depth(usize::MAX).0, vec![(100, 7, 1)]);
+    }
+
+    #[test]
+    fn test_l2_update_cancels_resting_orders() {
+        let mut book = OrderBook::<()>::new("TEST");
+        let owner = OwnerId(7);
+        let resting = OrderId::new();
+        let order = OrderType::Standard {
+            id: resting,
+            price: 100,
+            quantity: 4,
+            side: Side::Buy,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        };
+        book.add_order_with_owner(order, owner).unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        book.set_order_event_listener(Arc::new(move |event: &OrderEvent| {
+            sink.lock().unwrap().push(event.clone());
+        }));
+
+        book.apply_l2_update(100, 7, Side::Buy).unwrap();
+        assert!(book.get_order(resting).is_none());
+        assert!(book.get_orders_by_owner(owner).is_empty());
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![OrderEvent::Cancelled {
+                order_id: resting,
+                quantity: 4
+            }]
+        );
+    }
+
+    #[test]
+    fn test_l2_updates_support_analytics() {
+        let book = OrderBook::<()>::new("TEST");
+        book.apply_l2_update(100, 10, Side::Sell).unwrap();
+        book.apply_l2_update(102, 10, Side::Sell).unwrap();
+        book.apply_l2_update(98, 30, Side::Buy).unwrap();
+
+        assert_eq!(book.vwap(20, Side::Buy), Some(101.0));
+        assert!(book.order_book_imbalance(5) > 0.0);
+        assert_eq!(book.market_impact(15, Side::Buy).levels_consumed, 2);
+    }
+
+    #[test]
+    fn test_l2_update_rejects_off_tick_price() {
+        let book =
+            OrderBook::<()>::new_with_config("TEST", BookConfig::default().with_tick_size(5));
+        assert!(book.apply_l2_update(101, 10, Side::Buy).is_err());
+        assert!(book.best_bid().is_none());
+    }
+
+    #[test]
+    fn test_l2_update_is_tracked_in_deltas() {
+        let book = tracked_book(10);
+        let start = book.delta_sequence().unwrap();
+        book.apply_l2_update(100, 10, Side::Buy).unwrap();
+
+        assert_eq!(
+            book.delta_since(start).unwrap().changes,
+            vec![LevelChange::Added {
+                side: Side::Buy,
+                price: 100,
+                quantity: 10
+            }]
+        );
+    }
+}
@@ -255,4 +255,56 @@ mod tests {
         assert_eq!(book.total_depth_at_levels(2, Side::Sell), 40); // 101, 102
         assert_eq!(book.total_depth_at_levels(3, Side::Sell), 75); // 101, 102, 103
     }
+
+    #[test]
+    fn test_depth_returns_top_levels_in_book_order() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        for (price, quantity) in [(98, 30), (99, 20), (100, 10), (100, 5)] {
+            let _ = book.add_limit_order(
+                OrderId::new(),
+                price,
+                quantity,
+                Side::Buy,
+                TimeInForce::Gtc,
+                None,
+            );
+        }
+        for (price, quantity) in [(101, 15), (102, 25), (103, 35)] {
+            let _ = book.add_limit_order(
+                OrderId::new(),
+                price,
+                quantity,
+                Side::Sell,
+                TimeInForce::Gtc,
+                None,
+            );
+        }
+
+        let (bids, asks) = book.depth(2);
+        assert_eq!(bids, vec![(100, 15, 2), (99, 20, 1)]);
+        assert_eq!(asks, vec![(101, 15, 1), (102, 25, 1)]);
+
+        let (bids, asks) = book.depth(10);
+        assert_eq!(bids.len(), 3);
+        assert_eq!(asks.len(), 3);
+    }
+
+    #[test]
+    fn test_depth_reports_visible_quantity_only() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        let _ = book.add_iceberg_order(
+            OrderId::new(),
+            101,
+            5,
+            45,
+            Side::Sell,
+            TimeInForce::Gtc,
+            None,
+        );
+
+        let (bids, asks) = book.depth(5);
+        assert!(bids.is_empty());
+        assert_eq!(asks, vec![(101, 5, 1)]);
+        assert!(book.depth(0).1.is_empty());
+    }
 }
@@ -0,0 +1,198 @@
+//! Unit tests for the per-side depth limit and its eviction policies.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::config::{BookConfig, LevelEviction};
+    use crate::orderbook::error::OrderBookError;
+    use crate::orderbook::order_event::OrderEvent;
+    use pricelevel::{OrderId, OrderUpdate, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn capped_book(eviction: LevelEviction) -> OrderBook<()> {
+        OrderBook::new_with_config("TEST", BookConfig::default().with_max_levels(3, eviction))
+    }
+
+    fn add(book: &OrderBook<()>, price: u64, side: Side) -> Result<OrderId, OrderBookError> {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, 10, side, TimeInForce::Gtc, None)
+            .map(|_| id)
+    }
+
+    fn prices(book: &OrderBook<()>, side: Side) -> Vec<u64> {
+        book.levels_with_cumulative_depth(side)
+            .map(|level| level.price)
+            .collect()
+    }
+
+    #[test]
+    fn test_reject_keeps_existing_levels() {
+        let book = capped_book(LevelEviction::Reject);
+        for price in [101, 102, 103] {
+            add(&book, price, Side::Sell).unwrap();
+        }
+
+        for price in [100, 104] {
+            assert!(matches!(
+                add(&book, price, Side::Sell),
+                Err(OrderBookError::DepthLimitExceeded {
+                    side: Side::Sell,
+                    max_levels: 3,
+                    ..
+                })
+            ));
+        }
+        // Joining an existing level is always allowed
+        add(&book, 102, Side::Sell).unwrap();
+        assert_eq!(prices(&book, Side::Sell), vec![101, 102, 103]);
+        assert_eq!(book.level_count(Side::Sell), 3);
+        assert!(book.verify_integrity().is_consistent());
+    }
+
+    #[test]
+    fn test_concurrent_adds_never_evict_under_reject() {
+        let book = capped_book(LevelEviction::Reject);
+        let accepted = Mutex::new(Vec::new());
+        std::thread::scope(|scope| {
+            for thread in 0..4 {
+                let (book, accepted) = (&book, &accepted);
+                scope.spawn(move || {
+                    for offset in 0..25 {
+                        if let Ok(id) = add(book, 100 + thread * 25 + offset, Side::Buy) {
+                            accepted.lock().unwrap().push(id);
+                        }
+                    }
+                });
+            }
+        });
+
+        // Accepted orders keep resting and the side never grows past the limit
+        let accepted = accepted.into_inner().unwrap();
+        assert!(!accepted.is_empty());
+        assert!(book.level_count(Side::Buy) <= 3);
+        assert_eq!(book.level_count(Side::Buy), accepted.len());
+        assert!(accepted.iter().all(|id| book.get_order(*id).is_some()));
+        assert!(book.verify_integrity().is_consistent());
+    }
+
+    #[test]
+    fn test_drop_worst_evicts_worst_levels() {
+        let mut book = capped_book(LevelEviction::DropWorst);
+        let worst = add(&book, 97, Side::Buy).unwrap();
+        for price in [98, 99] {
+            add(&book, price, Side::Buy).unwrap();
+        }
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        book.set_order_event_listener(Arc::new(move |event: &OrderEvent| {
+            sink.lock().unwrap().push(event.clone());
+        }));
+
+        add(&book, 100, Side::Buy).unwrap();
+        assert_eq!(prices(&book, Side::Buy), vec![100, 99, 98]);
+        assert!(book.get_order(worst).is_none());
+        assert!(events.lock().unwrap().iter().any(|event| matches!(
+            event,
+            OrderEvent::Cancelled { order_id, quantity: 10 } if *order_id == worst
+        )));
+        assert_eq!(book.buy_sell_pressure().0, 30);
+
+        // A level worse than every level of a full side does not fit
+        assert!(add(&book, 97, Side::Buy).is_err());
+        assert!(book.verify_integrity().is_consistent());
+    }
+
+    #[test]
+    fn test_remainder_beyond_limit_is_cancelled_after_fills() {
+        let book = capped_book(LevelEviction::Reject);
+        add(&book, 105, Side::Sell).unwrap();
+        for price in [97, 98, 99] {
+            add(&book, price, Side::Buy).unwrap();
+        }
+
+        // The buy fills 10 at 105 and its remainder cannot open a fourth bid level
+        let result =
+            book.add_limit_order(OrderId::new(), 105, 15, Side::Buy, TimeInForce::Gtc, None);
+        assert!(matches!(
+            result,
+            Err(OrderBookError::DepthLimitExceeded { price: 105, .. })
+        ));
+        assert_eq!(book.best_ask(), None);
+        assert_eq!(prices(&book, Side::Buy), vec![99, 98, 97]);
+    }
+
+    #[test]
+    fn test_price_updates_respect_limit() {
+        let book = capped_book(LevelEviction::DropWorst);
+        let ids: Vec<OrderId> = [97, 98, 99]
+            .into_iter()
+            .map(|price| add(&book, price, Side::Buy).unwrap())
+            .collect();
+
+        book.update_order(OrderUpdate::UpdatePrice {
+            order_id: ids[0],
+            new_price: 101,
+        })
+        .unwrap();
+        assert_eq!(prices(&book, Side::Buy), vec![101, 99, 98]);
+        assert!(book.get_order(ids[0]).is_some());
+    }
+
+    #[test]
+    fn test_amends_beyond_limit_keep_the_order() {
+        let book = capped_book(LevelEviction::Reject);
+        let id = add(&book, 97, Side::Buy).unwrap();
+        add(&book, 97, Side::Buy).unwrap();
+        add(&book, 98, Side::Buy).unwrap();
+        add(&book, 99, Side::Buy).unwrap();
+
+        for update in [
+            OrderUpdate::UpdatePrice {
+                order_id: id,
+                new_price: 96,
+            },
+            OrderUpdate::UpdatePriceAndQuantity {
+                order_id: id,
+                new_price: 96,
+                new_quantity: 5,
+            },
+            OrderUpdate::Replace {
+                order_id: id,
+                price: 96,
+                quantity: 5,
+                side: Side::Buy,
+            },
+        ] {
+            assert!(matches!(
+                book.update_order(update),
+                Err(OrderBookError::DepthLimitExceeded { price: 96, .. })
+            ));
+            assert_eq!(book.get_order(id).unwrap().price(), 97);
+        }
+        assert_eq!(prices(&book, Side::Buy), vec![99, 98, 97]);
+
+        // Moving the last order of a level frees that level for the new price
+        let single = book.get_orders_at_price(98, Side::Buy)[0].id();
+        book.update_order(OrderUpdate::UpdatePrice {
+            order_id: single,
+            new_price: 96,
+        })
+        .unwrap();
+        assert_eq!(prices(&book, Side::Buy), vec![99, 97, 96]);
+    }
+
+    #[test]
+    fn test_mirrored_levels_stay_within_limit() {
+        let book = capped_book(LevelEviction::DropWorst);
+        for price in [101, 102, 103, 104] {
+            book.apply_l2_update(price, 5, Side::Sell).unwrap();
+        }
+        assert_eq!(prices(&book, Side::Sell), vec![101, 102, 103]);
+
+        book.apply_l2_update(100, 5, Side::Sell).unwrap();
+        assert_eq!(prices(&book, Side::Sell), vec![100, 101, 102]);
+        assert_eq!(book.order_locations.len(), 3);
+        assert!(book.verify_integrity().is_consistent());
+    }
+}
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::OrderBookError;
-    use pricelevel::{PriceLevelError, Side};
+    use pricelevel::{OrderId, PriceLevelError, Side};
 
     #[test]
     fn test_display_price_level_error() {
@@ -58,6 +58,29 @@ mod tests {
         assert_eq!(format!("{err}"), format!("Invalid operation: {}", message));
     }
 
+    #[test]
+    fn test_display_duplicate_order_id() {
+        let order_id = OrderId::from_u64(42);
+        let err = OrderBookError::DuplicateOrderId { order_id };
+        assert_eq!(
+            format!("{err}"),
+            format!("Duplicate order ID: {order_id} is already in the book")
+        );
+    }
+
+    #[test]
+    fn test_display_depth_limit_exceeded() {
+        let err = OrderBookError::DepthLimitExceeded {
+            side: Side::Sell,
+            price: 105,
+            max_levels: 10,
+        };
+        assert_eq!(
+            format!("{err}"),
+            "Price 105 would open a SELL level beyond the depth limit of 10 levels"
+        );
+    }
+
     #[test]
     fn test_from_price_level_error() {
         let price_level_error = PriceLevelError::InvalidFormat;
@@ -0,0 +1,174 @@
+//! Unit tests for storing and updating order extra fields.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use pricelevel::{OrderId, OrderUpdate, Side, TimeInForce};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+    struct ClientFields {
+        account: String,
+        tag: u32,
+    }
+
+    fn fields(account: &str, tag: u32) -> ClientFields {
+        ClientFields {
+            account: account.to_string(),
+            tag,
+        }
+    }
+
+    fn book_with_order() -> (OrderBook<ClientFields>, OrderId) {
+        let book = OrderBook::new("TEST");
+        let id = OrderId::new();
+        book.add_limit_order(
+            id,
+            100,
+            10,
+            Side::Buy,
+            TimeInForce::Gtc,
+            Some(fields("acc-1", 7)),
+        )
+        .unwrap();
+        (book, id)
+    }
+
+    #[test]
+    fn test_get_order_returns_stored_extra_fields() {
+        let (book, id) = book_with_order();
+        let order = book.get_order(id).unwrap();
+        assert_eq!(order.extra_fields(), &fields("acc-1", 7));
+        assert_eq!(book.get_all_orders()[0].extra_fields(), &fields("acc-1", 7));
+    }
+
+    #[test]
+    fn test_update_order_extra_fields() {
+        let (book, id) = book_with_order();
+
+        let updated = book
+            .update_order_extra_fields(id, fields("acc-2", 9))
+            .unwrap();
+
+        assert_eq!(updated.extra_fields(), &fields("acc-2", 9));
+        assert_eq!(updated.price(), 100);
+        assert_eq!(
+            book.get_order(id).unwrap().extra_fields(),
+            &fields("acc-2", 9)
+        );
+        assert!(
+            book.update_order_extra_fields(OrderId::new(), fields("x", 0))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_extra_fields_survive_updates_and_are_returned_on_cancel() {
+        let (book, id) = book_with_order();
+
+        book.update_order(OrderUpdate::UpdatePrice {
+            order_id: id,
+            new_price: 101,
+        })
+        .unwrap();
+        assert_eq!(
+            book.get_order(id).unwrap().extra_fields(),
+            &fields("acc-1", 7)
+        );
+
+        let cancelled = book.cancel_order(id).unwrap().unwrap();
+        assert_eq!(cancelled.extra_fields(), &fields("acc-1", 7));
+        assert!(book.order_extra_fields.is_empty());
+    }
+
+    #[test]
+    fn test_filled_order_releases_extra_fields() {
+        let (book, _) = book_with_order();
+        book.match_order(OrderId::new(), Side::Sell, 10, None)
+            .unwrap();
+        assert!(book.order_extra_fields.is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_round_trips_extra_fields() {
+        let (book, id) = book_with_order();
+        let plain = book.create_snapshot(10);
+        assert!(plain.extra_fields.is_empty());
+
+        let snapshot = book.create_snapshot_with_extra_fields(10).unwrap();
+        let json = serde_json::to_string(&snapshot).unwrap();
+
+        let restored = OrderBook::<ClientFields>::new("TEST");
+        restored
+            .restore_from_snapshot_with_extra_fields(serde_json::from_str(&json).unwrap())
+            .unwrap();
+        assert_eq!(
+            restored.get_order(id).unwrap().extra_fields(),
+            &fields("acc-1", 7)
+        );
+    }
+
+    #[test]
+    fn test_restore_rejects_malformed_extra_fields() {
+        let (book, _) = book_with_order();
+        let mut snapshot = book.create_snapshot_with_extra_fields(10).unwrap();
+        for value in snapshot.extra_fields.values_mut() {
+            *value = serde_json::json!("not a struct");
+        }
+
+        let restored = OrderBook::<ClientFields>::new("TEST");
+        assert!(
+            restored
+                .restore_from_snapshot_with_extra_fields(snapshot)
+                .is_err()
+        );
+        assert_eq!(restored.best_bid(), None);
+    }
+
+    #[test]
+    fn test_bulk_queries_return_stored_extra_fields() {
+        let (book, id) = book_with_order();
+        let hidden = OrderId::new();
+        book.add_hidden_order(
+            hidden,
+            99,
+            5,
+            Side::Buy,
+            TimeInForce::Gtc,
+            Some(fields("acc-2", 8)),
+        )
+        .unwrap();
+
+        let at_price = book.get_orders_at_price(100, Side::Buy);
+        assert_eq!(at_price[0].extra_fields(), &fields("acc-1", 7));
+
+        let all = book.get_all_orders();
+        assert_eq!(all.len(), 2);
+        for order in all {
+            let expected = if order.id() == id {
+                fields("acc-1", 7)
+            } else {
+                fields("acc-2", 8)
+            };
+            assert_eq!(order.extra_fields(), &expected);
+        }
+    }
+
+    #[test]
+    fn test_partially_filled_maker_keeps_extra_fields() {
+        let (book, id) = book_with_order();
+        book.match_order(OrderId::new(), Side::Sell, 4, None)
+            .unwrap();
+
+        let order = book.get_order(id).unwrap();
+        assert_eq!(order.visible_quantity(), 6);
+        assert_eq!(order.extra_fields(), &fields("acc-1", 7));
+    }
+
+    #[test]
+    fn test_serialized_book_includes_extra_fields() {
+        let (book, id) = book_with_order();
+        let value = serde_json::to_value(&book).unwrap();
+        assert_eq!(value["extra_fields"][id.to_string()]["account"], "acc-1");
+    }
+}
@@ -0,0 +1,86 @@
+//! Unit tests for the decay-weighted fair price.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use pricelevel::{OrderId, Side, TimeInForce};
+
+    fn add(book: &OrderBook<()>, price: u64, quantity: u64, side: Side) {
+        book.add_limit_order(
+            OrderId::new(),
+            price,
+            quantity,
+            side,
+            TimeInForce::Gtc,
+            None,
+        )
+        .unwrap();
+    }
+
+    fn setup_book() -> OrderBook<()> {
+        let book = OrderBook::new("TEST");
+        add(&book, 100, 30, Side::Buy);
+        add(&book, 99, 10, Side::Buy);
+        add(&book, 98, 40, Side::Buy);
+        add(&book, 101, 10, Side::Sell);
+        add(&book, 102, 30, Side::Sell);
+        book
+    }
+
+    #[test]
+    fn test_single_level_matches_micro_price() {
+        let book = setup_book();
+        let fair = book.weighted_fair_price(1, 1.0).unwrap();
+        assert_eq!(fair.value, book.micro_price().unwrap());
+        assert_eq!(fair.value, 100.75);
+        assert_eq!(fair.imbalance, 0.5);
+        assert_eq!(fair.levels.len(), 1);
+        assert_eq!(fair.levels[0].weight, 1.0);
+        assert_eq!(fair.levels[0].micro_price, 100.75);
+    }
+
+    #[test]
+    fn test_equal_weights_over_levels() {
+        let book = setup_book();
+        let fair = book.weighted_fair_price(5, 0.0).unwrap();
+
+        // Pairing stops at the two ask levels
+        assert_eq!(fair.levels.len(), 2);
+        let second = fair.levels[1];
+        assert_eq!(
+            (second.depth, second.bid_price, second.ask_price),
+            (1, 99, 102)
+        );
+        assert_eq!(second.micro_price, 99.75);
+
+        // (101*30 + 100*10 + 102*10 + 99*30) / 80
+        assert!((fair.value - 8_020.0 / 80.0).abs() < 1e-9);
+        assert_eq!(fair.imbalance, 0.0);
+        let total: f64 = fair.levels.iter().map(|level| level.contribution).sum();
+        assert!((total - fair.value).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_decay_moves_towards_the_touch() {
+        let book = setup_book();
+        let top = book.weighted_fair_price(1, 0.0).unwrap().value;
+        let flat = book.weighted_fair_price(2, 0.0).unwrap().value;
+        let decayed = book.weighted_fair_price(2, 2.0).unwrap();
+
+        assert_eq!(decayed.levels[1].weight, (-2.0f64).exp());
+        assert!(flat < decayed.value && decayed.value < top);
+        assert!(decayed.imbalance > 0.0);
+    }
+
+    #[test]
+    fn test_invalid_arguments_and_one_sided_book() {
+        let book = setup_book();
+        assert!(book.weighted_fair_price(0, 1.0).is_none());
+        assert!(book.weighted_fair_price(3, -1.0).is_none());
+        assert!(book.weighted_fair_price(3, f64::NAN).is_none());
+
+        let one_sided = OrderBook::<()>::new("TEST");
+        add(&one_sided, 100, 10, Side::Buy);
+        assert!(one_sided.weighted_fair_price(3, 1.0).is_none());
+    }
+}
@@ -0,0 +1,190 @@
+//! Unit tests for maker/taker fee schedules.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::fees::{FeeModel, FeeSchedule};
+    use crate::orderbook::manager::{BookManager, BookManagerStd};
+    use crate::orderbook::owner::OwnerId;
+    use crate::orderbook::session::SessionState;
+    use crate::orderbook::trade::{TradeInfo, TradeResult};
+    use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn order(side: Side, price: u64, quantity: u64) -> OrderType<()> {
+        OrderType::Standard {
+            id: OrderId::new(),
+            price,
+            quantity,
+            side,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        }
+    }
+
+    fn recorded(book: &OrderBook<()>) -> Arc<Mutex<Vec<TradeResult>>> {
+        let trades: Arc<Mutex<Vec<TradeResult>>> = Arc::default();
+        let sink = trades.clone();
+        book.subscribe_trade_listener(Arc::new(move |trade| {
+            sink.lock().unwrap().push(trade.clone());
+        }));
+        trades
+    }
+
+    #[test]
+    fn test_schedule_rates_minimums_and_tiers() {
+        let schedule = FeeSchedule::new(2, 10)
+            .with_min_maker_fee(5)
+            .with_min_taker_fee(20)
+            .with_tier(50_000, 0, 6)
+            .with_tier(10_000, 1, 8);
+
+        assert_eq!(schedule.rates(0), (2, 10));
+        assert_eq!(schedule.rates(10_000), (1, 8));
+        assert_eq!(schedule.rates(1_000_000), (0, 6));
+
+        assert_eq!(schedule.taker_fee(1_000, 100, 0), 100);
+        assert_eq!(schedule.taker_fee(100, 10, 0), 20);
+        assert_eq!(schedule.maker_fee(100, 10, 0), 5);
+        // A zero rate is not raised to the minimum
+        assert_eq!(schedule.maker_fee(100, 10, 50_000), 0);
+    }
+
+    #[test]
+    fn test_rebates_are_negative_and_truncated() {
+        let schedule = FeeSchedule::new(-3, 4).with_min_maker_fee(100);
+        assert_eq!(schedule.maker_fee(333, 100, 0), -9);
+        assert_eq!(schedule.taker_fee(333, 100, 0), 13);
+    }
+
+    #[test]
+    fn test_book_reports_fees_per_transaction() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.set_fee_schedule(FeeSchedule::new(-1, 5));
+        let trades = recorded(&book);
+        book.add_limit_order(
+            OrderId::new(),
+            1_000,
+            10,
+            Side::Sell,
+            TimeInForce::Gtc,
+            None,
+        )
+        .unwrap();
+        book.add_limit_order(
+            OrderId::new(),
+            1_010,
+            10,
+            Side::Sell,
+            TimeInForce::Gtc,
+            None,
+        )
+        .unwrap();
+        book.submit_market_order(OrderId::new(), 20, Side::Buy)
+            .unwrap();
+
+        let trades = trades.lock().unwrap();
+        let trade = &trades[0];
+        assert_eq!(trade.fees.len(), 2);
+        let first = trade.match_result.transactions.as_vec()[0];
+        let fee = trade.fee(first.transaction_id).unwrap();
+        assert_eq!((fee.maker_fee, fee.taker_fee), (-1, 5));
+        assert_eq!(trade.total_fees(), (-2, 10));
+
+        let info = TradeInfo::from(trade);
+        assert_eq!(info.transactions[1].maker_fee, -1);
+        assert_eq!(info.transactions[1].taker_fee, 5);
+    }
+
+    #[test]
+    fn test_no_fees_without_schedule() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        let trades = recorded(&book);
+        book.add_limit_order(OrderId::new(), 100, 1, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.submit_market_order(OrderId::new(), 1, Side::Buy)
+            .unwrap();
+
+        let trades = trades.lock().unwrap();
+        assert!(trades[0].fees.is_empty());
+        assert_eq!(TradeInfo::from(&trades[0]).transactions[0].taker_fee, 0);
+    }
+
+    #[test]
+    fn test_tiers_follow_owner_volume() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.set_fee_schedule(FeeSchedule::new(0, 10).with_tier(1_000, 0, 5));
+        let trades = recorded(&book);
+
+        for _ in 0..2 {
+            book.add_order_with_owner(order(Side::Sell, 100, 10), OwnerId(1))
+                .unwrap();
+            book.add_order_with_owner(order(Side::Buy, 100, 10), OwnerId(2))
+                .unwrap();
+        }
+
+        let model = book.fee_model().unwrap();
+        assert_eq!(model.volume(OwnerId(2)), 2_000);
+        assert_eq!(model.volume(OwnerId(1)), 2_000);
+        let trades = trades.lock().unwrap();
+        assert_eq!(trades[0].total_fees().1, 1);
+        assert_eq!(trades[1].total_fees().1, 0);
+
+        model.reset_volumes();
+        assert_eq!(model.volume(OwnerId(2)), 0);
+    }
+
+    #[test]
+    fn test_volume_accrues_without_listeners() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.set_fee_schedule(FeeSchedule::new(0, 10));
+        book.add_order_with_owner(order(Side::Sell, 100, 10), OwnerId(1))
+            .unwrap();
+        book.add_order_with_owner(order(Side::Buy, 100, 10), OwnerId(2))
+            .unwrap();
+        assert_eq!(book.fee_model().unwrap().volume(OwnerId(2)), 1_000);
+
+        book.remove_fee_schedule();
+        assert!(book.fee_model().is_none());
+    }
+
+    #[test]
+    fn test_auction_trades_are_charged() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.set_fee_schedule(FeeSchedule::new(1, 1));
+        let trades = recorded(&book);
+        book.set_session_state(SessionState::PreOpen).unwrap();
+        book.add_limit_order(OrderId::new(), 101, 100, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 100, 100, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.open_with_auction().unwrap();
+
+        let trades = trades.lock().unwrap();
+        assert_eq!(trades[0].fees.len(), 1);
+        assert!(trades[0].total_fees().1 > 0);
+    }
+
+    #[test]
+    fn test_manager_books_share_volume() {
+        let mut manager = BookManagerStd::<()>::new()
+            .with_fee_schedule(FeeSchedule::new(0, 10).with_tier(150, 0, 0));
+        manager.add_book("BTC/USD");
+        manager.add_book("ETH/USD");
+        for symbol in ["BTC/USD", "ETH/USD"] {
+            let book = manager.get_book(symbol).unwrap();
+            book.add_order_with_owner(order(Side::Sell, 10, 10), OwnerId(1))
+                .unwrap();
+            book.add_order_with_owner(order(Side::Buy, 10, 10), OwnerId(2))
+                .unwrap();
+        }
+
+        let model: &Arc<FeeModel> = manager.fee_model().unwrap();
+        assert_eq!(model.volume(OwnerId(2)), 200);
+        assert!(Arc::ptr_eq(
+            model,
+            manager.get_book("ETH/USD").unwrap().fee_model().unwrap()
+        ));
+    }
+}
@@ -0,0 +1,154 @@
+//! Unit tests for the per-level flow tracker and fill probability estimates.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::fill_probability::LevelFlowTracker;
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn setup_book(window_ms: u64) -> (OrderBook<()>, Arc<ManualClock>) {
+        let mut book = OrderBook::new("TEST");
+        let clock = Arc::new(ManualClock::new(100_000));
+        book.set_clock(clock.clone());
+        book.enable_fill_probability(window_ms);
+        (book, clock)
+    }
+
+    fn bid(book: &OrderBook<()>, clock: &ManualClock, price: u64, quantity: u64) -> OrderId {
+        clock.advance(1);
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        id
+    }
+
+    #[test]
+    fn test_tracker_rolls_events_out_of_the_window() {
+        let tracker = LevelFlowTracker::new(1_000);
+        tracker.record_execution(10_000, Side::Buy, 100, 5);
+        tracker.record_cancel(10_500, Side::Buy, 100, 3);
+        tracker.record_execution(10_500, Side::Sell, 100, 7);
+
+        let intensity = tracker.intensity(Side::Buy, 100, 10_900);
+        assert_eq!(intensity.traded_quantity, 5);
+        assert_eq!(intensity.trade_count, 1);
+        assert_eq!(intensity.cancelled_quantity, 3);
+        assert_eq!(intensity.cancel_count, 1);
+        assert_eq!(intensity.trade_rate(), 5.0);
+        assert_eq!(intensity.cancel_rate(), 3.0);
+
+        let intensity = tracker.intensity(Side::Buy, 100, 11_000);
+        assert_eq!(intensity.traded_quantity, 0);
+        assert_eq!(intensity.cancelled_quantity, 3);
+
+        assert_eq!(tracker.intensity(Side::Buy, 100, 11_500).cancel_count, 0);
+        tracker.clear();
+        assert_eq!(tracker.intensity(Side::Sell, 100, 10_500).trade_count, 0);
+    }
+
+    #[test]
+    fn test_book_records_executions_against_the_resting_side() {
+        let (book, clock) = setup_book(10_000);
+        bid(&book, &clock, 100, 10);
+        bid(&book, &clock, 99, 10);
+
+        book.submit_market_order(OrderId::new(), 15, Side::Sell)
+            .unwrap();
+
+        let top = book.level_intensity(100, Side::Buy).unwrap();
+        assert_eq!((top.traded_quantity, top.trade_count), (10, 1));
+        let next = book.level_intensity(99, Side::Buy).unwrap();
+        assert_eq!(next.traded_quantity, 5);
+        assert_eq!(
+            book.level_intensity(100, Side::Sell).unwrap().trade_count,
+            0
+        );
+    }
+
+    #[test]
+    fn test_book_records_cancellations() {
+        let (book, clock) = setup_book(10_000);
+        let id = bid(&book, &clock, 100, 10);
+        book.cancel_order(id).unwrap();
+
+        let intensity = book.level_intensity(100, Side::Buy).unwrap();
+        assert_eq!(intensity.cancelled_quantity, 10);
+        assert_eq!(intensity.cancel_count, 1);
+        assert_eq!(intensity.traded_quantity, 0);
+    }
+
+    #[test]
+    fn test_cancellations_ahead_shorten_the_expected_time() {
+        let (book, clock) = setup_book(10_000);
+        let front = bid(&book, &clock, 100, 40);
+        let mine = bid(&book, &clock, 100, 10);
+        let back = bid(&book, &clock, 100, 50);
+
+        // 10 units traded and 50 cancelled in the window
+        book.submit_market_order(OrderId::new(), 10, Side::Sell)
+            .unwrap();
+        book.cancel_order(back).unwrap();
+        let replacement = bid(&book, &clock, 100, 50);
+
+        // 30 ahead of 90 resting: 1 unit per second traded, 5 cancelled, 30%
+        // of the cancellations fall ahead
+        let estimate = book.fill_probability(mine, 10_000).unwrap();
+        assert_eq!(estimate.orders_ahead, 1);
+        assert_eq!(estimate.quantity_ahead, 30);
+        assert_eq!(estimate.remaining_quantity, 10);
+        let ahead_seconds = 30.0 / (1.0 + 5.0 * 30.0 / 90.0);
+        let expected = (ahead_seconds + 10.0) * 1_000.0;
+        assert!((estimate.expected_time_to_fill_ms.unwrap() - expected).abs() < 1e-6);
+        assert!((estimate.probability - (1.0 - (-10_000.0 / expected).exp())).abs() < 1e-9);
+
+        let front_estimate = book.fill_probability(front, 10_000).unwrap();
+        assert_eq!(front_estimate.quantity_ahead, 0);
+        assert_eq!(front_estimate.expected_time_to_fill_ms, Some(30_000.0));
+        let back_estimate = book.fill_probability(replacement, 10_000).unwrap();
+        assert!(back_estimate.probability < estimate.probability);
+    }
+
+    #[test]
+    fn test_probability_grows_with_the_horizon() {
+        let (book, clock) = setup_book(10_000);
+        bid(&book, &clock, 100, 20);
+        let mine = bid(&book, &clock, 100, 10);
+        book.submit_market_order(OrderId::new(), 10, Side::Sell)
+            .unwrap();
+
+        let short = book.fill_probability(mine, 1_000).unwrap().probability;
+        let long = book.fill_probability(mine, 60_000).unwrap().probability;
+        assert!(short > 0.0);
+        assert!(long > short);
+        assert!(long < 1.0);
+    }
+
+    #[test]
+    fn test_no_executions_means_no_fill() {
+        let (book, clock) = setup_book(1_000);
+        bid(&book, &clock, 100, 20);
+        let mine = bid(&book, &clock, 100, 10);
+        book.submit_market_order(OrderId::new(), 5, Side::Sell)
+            .unwrap();
+
+        clock.advance(1_000);
+        let estimate = book.fill_probability(mine, 60_000).unwrap();
+        assert_eq!(estimate.intensity.trade_count, 0);
+        assert_eq!(estimate.expected_time_to_fill_ms, None);
+        assert_eq!(estimate.probability, 0.0);
+    }
+
+    #[test]
+    fn test_requires_tracker_and_resting_order() {
+        let (mut book, clock) = setup_book(1_000);
+        let id = bid(&book, &clock, 100, 10);
+        assert!(book.fill_probability(OrderId::new(), 1_000).is_none());
+
+        book.disable_fill_probability();
+        assert!(book.level_flow().is_none());
+        assert!(book.fill_probability(id, 1_000).is_none());
+        assert!(book.level_intensity(100, Side::Buy).is_none());
+    }
+}
@@ -0,0 +1,300 @@
+//! Unit tests for full-state capture and restore.
+
+#[cfg(test)]
+mod tests {
+    use crate::BookConfig;
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::full_state::OrderBookFullState;
+    use crate::orderbook::owner::OwnerId;
+    use crate::utils::{Clock, ManualClock};
+    use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+    use proptest::prelude::*;
+    use std::sync::Arc;
+
+    fn book_with_clock(symbol: &str, clock: &Arc<ManualClock>) -> OrderBook<u64> {
+        let mut book = OrderBook::new(symbol);
+        book.set_clock(clock.clone());
+        book
+    }
+
+    fn state_json(state: &OrderBookFullState<u64>) -> serde_json::Value {
+        serde_json::to_value(state).unwrap()
+    }
+
+    /// Restores `source` into a fresh book sharing its clock, through a JSON round trip.
+    fn failover(source: &OrderBook<u64>, clock: &Arc<ManualClock>) -> OrderBook<u64> {
+        let json = serde_json::to_string(&source.create_full_state()).unwrap();
+        let mut standby = book_with_clock(source.symbol(), clock);
+        standby
+            .restore_full_state(serde_json::from_str(&json).unwrap())
+            .unwrap();
+        standby
+    }
+
+    fn transaction_ids(book: &OrderBook<u64>, side: Side) -> Vec<String> {
+        book.submit_market_order(OrderId::new(), 1_000, side)
+            .map(|result| {
+                result
+                    .transactions
+                    .as_vec()
+                    .iter()
+                    .map(|transaction| transaction.transaction_id.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_full_state_round_trips_off_book_state() {
+        let clock = Arc::new(ManualClock::new(1_000));
+        let mut source = book_with_clock("TEST", &clock);
+        source.config = BookConfig::default().with_lot_size(1);
+        source.set_replace_on_duplicate(true);
+        source.set_market_close_timestamp(90_000);
+
+        source
+            .add_limit_order(
+                OrderId::new(),
+                100,
+                10,
+                Side::Buy,
+                TimeInForce::Gtc,
+                Some(7),
+            )
+            .unwrap();
+        let hidden = OrderId::new();
+        source
+            .add_hidden_order(hidden, 99, 5, Side::Buy, TimeInForce::Gtc, Some(8))
+            .unwrap();
+        let owned = OrderId::new();
+        source
+            .add_order_with_owner(
+                OrderType::Standard {
+                    id: owned,
+                    price: 105,
+                    quantity: 4,
+                    side: Side::Sell,
+                    timestamp: 0,
+                    time_in_force: TimeInForce::Day,
+                    extra_fields: 9,
+                },
+                OwnerId(3),
+            )
+            .unwrap();
+        source.update_quotes(OwnerId(3), 101, 2, 104, 2).unwrap();
+        source
+            .submit_market_order(OrderId::new(), 1, Side::Sell)
+            .unwrap();
+
+        let standby = failover(&source, &clock);
+        assert_eq!(
+            state_json(&standby.create_full_state()),
+            state_json(&source.create_full_state())
+        );
+        assert_eq!(standby.last_trade_price(), Some(101));
+        assert!(standby.is_hidden_order(hidden));
+        assert_eq!(standby.order_owner(owned), Some(OwnerId(3)));
+        assert_eq!(standby.get_order(owned).unwrap().extra_fields(), &9);
+        assert_eq!(
+            standby.active_quotes(OwnerId(3)),
+            source.active_quotes(OwnerId(3))
+        );
+        assert_eq!(standby.config, source.config);
+        assert_eq!(
+            transaction_ids(&standby, Side::Sell),
+            transaction_ids(&source, Side::Sell)
+        );
+    }
+
+    #[test]
+    fn test_restore_full_state_rejects_other_symbol() {
+        let source = OrderBook::<u64>::new("ONE");
+        let mut other = OrderBook::<u64>::new("TWO");
+        assert!(
+            other
+                .restore_full_state(source.create_full_state())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_fork_copies_state_and_branches_independently() {
+        let clock = Arc::new(ManualClock::new(1_000));
+        let source = book_with_clock("TEST", &clock);
+        source.set_replace_on_duplicate(true);
+        let resting = OrderId::new();
+        source
+            .add_limit_order(resting, 100, 10, Side::Buy, TimeInForce::Gtc, Some(7))
+            .unwrap();
+        let hidden = OrderId::new();
+        source
+            .add_hidden_order(hidden, 99, 5, Side::Buy, TimeInForce::Gtc, Some(8))
+            .unwrap();
+        let owned = OrderId::new();
+        source
+            .add_order_with_owner(
+                OrderType::Standard {
+                    id: owned,
+                    price: 105,
+                    quantity: 4,
+                    side: Side::Sell,
+                    timestamp: 0,
+                    time_in_force: TimeInForce::Gtc,
+                    extra_fields: 9,
+                },
+                OwnerId(3),
+            )
+            .unwrap();
+        source
+            .submit_market_order(OrderId::new(), 1, Side::Sell)
+            .unwrap();
+        source.set_trading_enabled(false);
+
+        let fork = source.fork();
+        assert_eq!(
+            state_json(&fork.create_full_state()),
+            state_json(&source.create_full_state())
+        );
+        assert!(!fork.is_trading_enabled());
+        assert!(fork.is_hidden_order(hidden));
+        assert_eq!(fork.get_orders_by_owner(OwnerId(3)).len(), 1);
+
+        // Changes to the fork do not reach the source, nor the other way round
+        source.set_trading_enabled(true);
+        fork.set_trading_enabled(true);
+        fork.cancel_order(resting).unwrap();
+        assert_eq!(source.best_bid(), Some(100));
+        source.cancel_order(owned).unwrap();
+        assert_eq!(fork.best_ask(), Some(105));
+        assert_eq!(fork.order_owner(owned), Some(OwnerId(3)));
+
+        // Branches of the same state produce the same transaction IDs
+        let branch = fork.fork();
+        assert_eq!(
+            transaction_ids(&branch, Side::Sell),
+            transaction_ids(&fork, Side::Sell)
+        );
+    }
+
+    #[test]
+    fn test_fork_carries_tracker_history() {
+        let mut source = OrderBook::<u64>::new("TEST");
+        source.enable_trade_tape(10);
+        source
+            .add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        source
+            .submit_market_order(OrderId::new(), 2, Side::Buy)
+            .unwrap();
+
+        let fork = source.fork();
+        assert_eq!(fork.trade_tape().unwrap().len(), 1);
+
+        fork.submit_market_order(OrderId::new(), 2, Side::Buy)
+            .unwrap();
+        assert_eq!(fork.trade_tape().unwrap().len(), 2);
+        assert_eq!(source.trade_tape().unwrap().len(), 1);
+
+        // The fork continues the transaction IDs of the source
+        assert_eq!(
+            transaction_ids(&source.fork(), Side::Buy),
+            transaction_ids(&source, Side::Buy)
+        );
+    }
+
+    #[derive(Debug, Clone)]
+    enum Command {
+        Limit {
+            price: u64,
+            quantity: u64,
+            buy: bool,
+            hidden: bool,
+            owner: Option<u64>,
+        },
+        Market {
+            quantity: u64,
+            buy: bool,
+        },
+        Cancel {
+            index: usize,
+        },
+    }
+
+    fn command() -> impl Strategy<Value = Command> {
+        prop_oneof![
+            4 => (95u64..106, 1u64..20, any::<bool>(), any::<bool>(), proptest::option::of(0u64..3))
+                .prop_map(|(price, quantity, buy, hidden, owner)| Command::Limit {
+                    price,
+                    quantity,
+                    buy,
+                    hidden,
+                    owner
+                }),
+            1 => (1u64..30, any::<bool>()).prop_map(|(quantity, buy)| Command::Market { quantity, buy }),
+            1 => any::<usize>().prop_map(|index| Command::Cancel { index }),
+        ]
+    }
+
+    fn side(buy: bool) -> Side {
+        if buy { Side::Buy } else { Side::Sell }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_full_state_round_trip_is_exact(commands in proptest::collection::vec(command(), 0..60)) {
+            let clock = Arc::new(ManualClock::new(1_000));
+            let source = book_with_clock("PROP", &clock);
+            let mut ids = Vec::new();
+
+            for (n, command) in commands.into_iter().enumerate() {
+                match command {
+                    Command::Limit { price, quantity, buy, hidden, owner } => {
+                        let id = OrderId::new();
+                        let extra = Some(n as u64);
+                        let _ = match (hidden, owner) {
+                            (true, _) => source.add_hidden_order(id, price, quantity, side(buy), TimeInForce::Gtc, extra),
+                            (false, Some(owner)) => source.add_order_with_owner(
+                                OrderType::Standard {
+                                    id,
+                                    price,
+                                    quantity,
+                                    side: side(buy),
+                                    timestamp: clock.now_millis(),
+                                    time_in_force: TimeInForce::Gtc,
+                                    extra_fields: n as u64,
+                                },
+                                OwnerId(owner),
+                            ),
+                            (false, None) => source.add_limit_order(id, price, quantity, side(buy), TimeInForce::Gtc, extra),
+                        };
+                        ids.push(id);
+                    }
+                    Command::Market { quantity, buy } => {
+                        let _ = source.submit_market_order(OrderId::new(), quantity, side(buy));
+                    }
+                    Command::Cancel { index } => {
+                        if !ids.is_empty() {
+                            let _ = source.cancel_order(ids[index % ids.len()]);
+                        }
+                    }
+                }
+                clock.advance(1);
+            }
+
+            let fork = source.fork();
+            prop_assert_eq!(
+                state_json(&fork.create_full_state()),
+                state_json(&source.create_full_state())
+            );
+
+            let standby = failover(&source, &clock);
+            prop_assert_eq!(
+                state_json(&standby.create_full_state()),
+                state_json(&source.create_full_state())
+            );
+            prop_assert_eq!(standby.depth(usize::MAX), source.depth(usize::MAX));
+            prop_assert_eq!(transaction_ids(&standby, Side::Buy), transaction_ids(&source, Side::Buy));
+            prop_assert_eq!(transaction_ids(&standby, Side::Sell), transaction_ids(&source, Side::Sell));
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Unit tests for the liquidity heatmap recorder.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::heatmap::{HeatmapConfig, LiquidityHeatmap};
+    use crate::orderbook::iterators::LevelInfo;
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn level(price: u64, quantity: u64) -> LevelInfo {
+        LevelInfo {
+            price,
+            quantity,
+            cumulative_depth: 0,
+        }
+    }
+
+    #[test]
+    fn test_sampling_respects_interval_and_depth() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        let clock = Arc::new(ManualClock::new(0));
+        book.set_clock(clock.clone());
+        for price in [97, 98, 99] {
+            book.add_limit_order(OrderId::new(), price, 10, Side::Buy, TimeInForce::Gtc, None)
+                .unwrap();
+        }
+        book.add_limit_order(OrderId::new(), 101, 4, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+
+        let heatmap = LiquidityHeatmap::new(HeatmapConfig::new(500, 1, 2));
+        assert!(heatmap.sample(&book));
+        clock.advance(499);
+        assert!(!heatmap.sample(&book));
+        clock.advance(1);
+        book.add_limit_order(OrderId::new(), 101, 6, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        assert!(heatmap.sample(&book));
+
+        let columns = heatmap.columns();
+        assert_eq!(columns.len(), 2);
+        // Only the two best bids are sampled
+        let bids: Vec<u64> = columns[0]
+            .cells
+            .iter()
+            .filter(|cell| cell.side == Side::Buy)
+            .map(|cell| cell.price)
+            .collect();
+        assert_eq!(bids, vec![98, 99]);
+        assert_eq!(columns[1].timestamp, 500);
+        assert_eq!(columns[1].cells.last().unwrap().quantity, 10);
+
+        let points = heatmap.points();
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[5].timestamp, 500);
+    }
+
+    #[test]
+    fn test_downsampling_bounds_memory() {
+        let heatmap = LiquidityHeatmap::new(HeatmapConfig::new(100, 10, 10).with_max_columns(4));
+        for (index, quantity) in [10, 20, 30, 50].into_iter().enumerate() {
+            heatmap.record(index as u64 * 100, [level(105, quantity)], []);
+        }
+        assert_eq!(heatmap.len(), 4);
+
+        heatmap.record(400, [level(101, 7)], [level(120, 3)]);
+        let columns = heatmap.columns();
+        assert_eq!(columns.len(), 3);
+        assert_eq!(heatmap.interval_ms(), 200);
+        assert_eq!(columns[0].timestamp, 0);
+        assert_eq!(columns[0].cells[0].quantity, 15);
+        assert_eq!(columns[1].timestamp, 200);
+        assert_eq!(columns[1].cells[0].quantity, 40);
+        assert_eq!(columns[2].cells.len(), 2);
+        assert_eq!(columns[2].cells[1].side, Side::Sell);
+
+        heatmap.clear();
+        assert!(heatmap.is_empty());
+        assert_eq!(heatmap.interval_ms(), 100);
+    }
+}
@@ -0,0 +1,190 @@
+//! Unit tests for fully hidden orders.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::OrderBookError;
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::book_change_event::PriceLevelChangedEvent;
+    use pricelevel::{OrderId, OrderUpdate, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn setup_book() -> OrderBook<()> {
+        OrderBook::new("TEST")
+    }
+
+    #[test]
+    fn test_hidden_order_is_not_visible() {
+        let book = setup_book();
+        let id = OrderId::new();
+        book.add_hidden_order(id, 100, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+
+        assert!(book.is_hidden_order(id));
+        assert!(book.get_order(id).is_some());
+        assert_eq!(book.best_ask(), None);
+        assert_eq!(book.hidden_quantity(Side::Sell), 10);
+        assert_eq!(book.liquidity_in_range(0, 1_000, Side::Sell), 0);
+        assert_eq!(book.total_depth_at_levels(10, Side::Sell), 0);
+        assert_eq!(book.levels_with_cumulative_depth(Side::Sell).count(), 0);
+        assert!(book.get_orders_at_price(100, Side::Sell).is_empty());
+
+        let snapshot = book.create_snapshot(10);
+        assert!(snapshot.asks.is_empty());
+        assert!(snapshot.bids.is_empty());
+    }
+
+    #[test]
+    fn test_hidden_order_matches_incoming_order() {
+        let book = setup_book();
+        let hidden_id = OrderId::new();
+        book.add_hidden_order(hidden_id, 100, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+
+        let result = book
+            .match_order(OrderId::new(), Side::Buy, 4, Some(100))
+            .unwrap();
+        assert!(result.is_complete);
+        assert_eq!(book.last_trade_price(), Some(100));
+        assert_eq!(book.hidden_quantity(Side::Sell), 6);
+
+        // A market order takes the rest and removes the hidden order entirely
+        let result = book
+            .match_order(OrderId::new(), Side::Buy, 6, None)
+            .unwrap();
+        assert!(result.is_complete);
+        assert!(book.get_order(hidden_id).is_none());
+        assert!(!book.is_hidden_order(hidden_id));
+        assert!(book.hidden_asks.is_empty());
+    }
+
+    #[test]
+    fn test_visible_order_has_priority_at_same_price() {
+        let book = setup_book();
+        let hidden_id = OrderId::new();
+        let visible_id = OrderId::new();
+        book.add_hidden_order(hidden_id, 100, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(visible_id, 100, 5, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+
+        let result = book
+            .match_order(OrderId::new(), Side::Buy, 7, None)
+            .unwrap();
+        let makers: Vec<OrderId> = result
+            .transactions
+            .as_vec()
+            .iter()
+            .map(|t| t.maker_order_id)
+            .collect();
+        assert_eq!(makers, vec![visible_id, hidden_id]);
+        assert_eq!(book.best_ask(), None);
+        assert_eq!(book.hidden_quantity(Side::Sell), 8);
+    }
+
+    #[test]
+    fn test_matching_walks_hidden_and_visible_levels_by_price() {
+        let book = setup_book();
+        book.add_limit_order(OrderId::new(), 101, 5, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_hidden_order(OrderId::new(), 102, 5, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 100, 5, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        let result = book
+            .match_order(OrderId::new(), Side::Sell, 12, None)
+            .unwrap();
+        let prices: Vec<u64> = result
+            .transactions
+            .as_vec()
+            .iter()
+            .map(|t| t.price)
+            .collect();
+        assert_eq!(prices, vec![102, 101, 100]);
+        assert_eq!(book.best_bid(), Some(100));
+        assert_eq!(book.hidden_quantity(Side::Buy), 0);
+    }
+
+    #[test]
+    fn test_incoming_hidden_order_matches_before_resting() {
+        let book = setup_book();
+        book.add_limit_order(OrderId::new(), 100, 4, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+
+        let id = OrderId::new();
+        book.add_hidden_order(id, 101, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        assert_eq!(book.best_ask(), None);
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.hidden_quantity(Side::Buy), 6);
+        assert_eq!(book.get_order(id).unwrap().price(), 101);
+    }
+
+    #[test]
+    fn test_fok_and_post_only_account_for_hidden_liquidity() {
+        let book = setup_book();
+        book.add_hidden_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+
+        assert_eq!(book.peek_match(Side::Buy, 15, Some(100)), 10);
+        assert!(book.will_cross_market(100, Side::Buy));
+
+        let result =
+            book.add_post_only_order(OrderId::new(), 100, 5, Side::Buy, TimeInForce::Gtc, None);
+        assert!(matches!(result, Err(OrderBookError::PriceCrossing { .. })));
+
+        book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Fok, None)
+            .unwrap();
+        assert_eq!(book.hidden_quantity(Side::Sell), 0);
+    }
+
+    #[test]
+    fn test_cancel_and_update_hidden_order() {
+        let book = setup_book();
+        let id = OrderId::new();
+        book.add_hidden_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        book.update_order(OrderUpdate::UpdateQuantity {
+            order_id: id,
+            new_quantity: 7,
+        })
+        .unwrap();
+        assert_eq!(book.hidden_quantity(Side::Buy), 7);
+
+        // Repricing keeps the order hidden
+        book.update_order(OrderUpdate::UpdatePrice {
+            order_id: id,
+            new_price: 99,
+        })
+        .unwrap();
+        assert!(book.is_hidden_order(id));
+        assert_eq!(book.get_order(id).unwrap().price(), 99);
+        assert_eq!(book.best_bid(), None);
+
+        assert!(book.cancel_order(id).unwrap().is_some());
+        assert!(!book.is_hidden_order(id));
+        assert!(book.hidden_bids.is_empty());
+        assert_eq!(book.hidden_quantity(Side::Buy), 0);
+    }
+
+    #[test]
+    fn test_hidden_levels_are_not_published() {
+        let mut book = setup_book();
+        let events: Arc<Mutex<Vec<PriceLevelChangedEvent>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        book.set_price_level_listener(Arc::new(move |event| {
+            sink.lock().unwrap().push(event);
+        }));
+
+        let id = OrderId::new();
+        book.add_hidden_order(id, 100, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.match_order(OrderId::new(), Side::Buy, 3, None)
+            .unwrap();
+        book.cancel_order(id).unwrap();
+
+        assert!(events.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+//! Unit tests for the order book integrity checker.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::integrity::CrossedBook;
+    use pricelevel::{OrderId, OrderType, PriceLevel, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn populated_book() -> OrderBook<()> {
+        let book = OrderBook::new("TEST");
+        book.add_limit_order(OrderId::new(), 99, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 101, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_hidden_order(OrderId::new(), 100, 5, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book
+    }
+
+    #[test]
+    fn test_healthy_book_is_consistent() {
+        let book = populated_book();
+        book.match_order(OrderId::new(), Side::Buy, 8, None)
+            .unwrap();
+        book.cancel_side(Side::Buy).unwrap();
+
+        let report = book.verify_integrity();
+        assert!(report.is_consistent(), "{report:?}");
+        book.debug_assert_integrity();
+    }
+
+    #[test]
+    fn test_detects_orphaned_location() {
+        let book = populated_book();
+        let orphan = OrderId::new();
+        book.order_locations.insert(orphan, (99, Side::Buy));
+
+        let report = book.verify_integrity();
+        assert_eq!(report.orphaned_locations, vec![orphan]);
+        assert!(!report.is_consistent());
+    }
+
+    #[test]
+    fn test_detects_untracked_order() {
+        let book = populated_book();
+        let id = OrderId::new();
+        book.add_limit_order(id, 98, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.order_locations.remove(&id);
+
+        assert_eq!(book.verify_integrity().untracked_orders, vec![id]);
+    }
+
+    #[test]
+    fn test_detects_empty_level() {
+        let book = populated_book();
+        book.bids.insert(90, Arc::new(PriceLevel::new(90)));
+
+        assert_eq!(book.verify_integrity().empty_levels, vec![(Side::Buy, 90)]);
+    }
+
+    #[test]
+    fn test_detects_crossed_book_including_hidden_orders() {
+        let book = populated_book();
+        let level = Arc::new(PriceLevel::new(100));
+        let id = OrderId::new();
+        level.add_order(OrderType::Standard {
+            id,
+            price: 100,
+            quantity: 1,
+            side: Side::Buy,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        });
+        book.bids.insert(100, level);
+        book.order_locations.insert(id, (100, Side::Buy));
+
+        let report = book.verify_integrity();
+        assert_eq!(
+            report.crossed,
+            Some(CrossedBook {
+                best_bid: 100,
+                best_ask: 100
+            })
+        );
+        assert!(report.orphaned_locations.is_empty());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "failed integrity check")]
+    fn test_debug_assert_integrity_panics_on_violation() {
+        let book = populated_book();
+        book.order_locations.insert(OrderId::new(), (99, Side::Buy));
+        book.debug_assert_integrity();
+    }
+}
@@ -339,4 +339,19 @@ mod tests {
 
         assert_eq!(count, 2); // 25 and 30
     }
+
+    #[test]
+    fn test_directed_walks_best_price_first() {
+        use crate::orderbook::iterators::Directed;
+
+        let prices = [80u64, 90, 100];
+        let bids: Vec<_> = Directed::best_first(prices.iter(), Side::Buy).collect();
+        let asks: Vec<_> = Directed::best_first(prices.iter(), Side::Sell).collect();
+        assert_eq!(bids, [&100, &90, &80]);
+        assert_eq!(asks, [&80, &90, &100]);
+        assert_eq!(
+            Directed::best_first(prices.iter(), Side::Buy).size_hint(),
+            (3, Some(3))
+        );
+    }
 }
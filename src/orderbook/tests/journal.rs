@@ -0,0 +1,379 @@
+//! Unit tests for the write-ahead journal and replay.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::error::OrderBookError;
+    use crate::orderbook::journal::{
+        FileJournal, InMemoryJournal, Journal, JournalCommand, JournalEntry,
+    };
+    use crate::orderbook::modifications::OrderQuantity;
+    use crate::orderbook::owner::OwnerId;
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, OrderType, OrderUpdate, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn journaled_book<T>() -> (OrderBook<T>, Arc<InMemoryJournal<T>>)
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let journal = Arc::new(InMemoryJournal::new());
+        let mut book = OrderBook::new("TEST");
+        book.set_journal(journal.clone());
+        (book, journal)
+    }
+
+    fn book_state(book: &OrderBook<()>) -> Vec<(OrderId, u64, u64, Side)> {
+        let mut orders: Vec<_> = book
+            .get_all_orders()
+            .iter()
+            .map(|order| {
+                (
+                    order.id(),
+                    order.price(),
+                    order.total_quantity(),
+                    order.side(),
+                )
+            })
+            .collect();
+        orders.sort_by_key(|(id, ..)| id.to_string());
+        orders
+    }
+
+    fn replayed(journal: &InMemoryJournal<()>) -> OrderBook<()> {
+        let mut book = OrderBook::new("TEST");
+        book.replay_journal(journal, 0).unwrap();
+        book
+    }
+
+    #[test]
+    fn test_commands_are_journaled_with_gapless_sequence() {
+        let (book, journal) = journaled_book::<()>();
+        let id = OrderId::new();
+        book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.update_order(OrderUpdate::UpdatePrice {
+            order_id: id,
+            new_price: 99,
+        })
+        .unwrap();
+        book.cancel_order(id).unwrap();
+
+        let entries = journal.entries_after(0).unwrap();
+        let sequences: Vec<u64> = entries.iter().map(|entry| entry.sequence).collect();
+        assert_eq!(sequences, vec![1, 2, 3]);
+        assert!(matches!(
+            entries[0].command,
+            JournalCommand::AddOrder { .. }
+        ));
+        assert!(matches!(
+            entries[1].command,
+            JournalCommand::UpdateOrder { .. }
+        ));
+        assert!(matches!(
+            entries[2].command,
+            JournalCommand::CancelOrder { .. }
+        ));
+        assert_eq!(book.journal_sequence(), 3);
+    }
+
+    #[test]
+    fn test_replay_reconstructs_book() {
+        let (book, journal) = journaled_book::<()>();
+        let resting = OrderId::new();
+        book.add_limit_order(resting, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_iceberg_order(
+            OrderId::new(),
+            105,
+            5,
+            20,
+            Side::Sell,
+            TimeInForce::Gtc,
+            None,
+        )
+        .unwrap();
+        book.add_limit_order(OrderId::new(), 98, 7, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.submit_market_order(OrderId::new(), 12, Side::Buy)
+            .unwrap();
+        book.update_order(OrderUpdate::UpdateQuantity {
+            order_id: resting,
+            new_quantity: 4,
+        })
+        .unwrap();
+        book.add_limit_order(OrderId::new(), 103, 6, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.cancel_range(0, 99, Side::Buy).unwrap();
+
+        let recovered = replayed(&journal);
+        assert_eq!(book_state(&recovered), book_state(&book));
+        assert_eq!(recovered.last_trade_price(), book.last_trade_price());
+        assert_eq!(recovered.journal_sequence(), book.journal_sequence());
+    }
+
+    #[test]
+    fn test_nested_operations_are_not_journaled_twice() {
+        let (book, journal) = journaled_book::<()>();
+        book.set_replace_on_duplicate(true);
+        let id = OrderId::new();
+        book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(id, 101, 5, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 101, 3, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        assert_eq!(journal.len(), 3);
+
+        let mut recovered = OrderBook::<()>::new("TEST");
+        recovered.set_replace_on_duplicate(true);
+        recovered.replay_journal(journal.as_ref(), 0).unwrap();
+        assert_eq!(book_state(&recovered), book_state(&book));
+    }
+
+    #[test]
+    fn test_replay_preserves_owner_hidden_and_extra_fields() {
+        let (book, journal) = journaled_book::<String>();
+        let owned = OrderId::new();
+        book.add_order_with_owner(
+            OrderType::Standard {
+                id: owned,
+                price: 100,
+                quantity: 10,
+                side: Side::Buy,
+                timestamp: 0,
+                time_in_force: TimeInForce::Gtc,
+                extra_fields: "desk-a".to_string(),
+            },
+            OwnerId(7),
+        )
+        .unwrap();
+        let hidden = OrderId::new();
+        book.add_hidden_order(hidden, 99, 5, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.update_order_extra_fields(owned, "desk-b".to_string());
+
+        let mut recovered = OrderBook::<String>::new("TEST");
+        recovered.replay_journal(journal.as_ref(), 0).unwrap();
+        assert_eq!(recovered.order_owner(owned), Some(OwnerId(7)));
+        assert!(recovered.is_hidden_order(hidden));
+        assert_eq!(recovered.get_order(owned).unwrap().extra_fields(), "desk-b");
+    }
+
+    #[test]
+    fn test_replay_uses_entry_timestamps_for_expiry() {
+        let clock = Arc::new(ManualClock::new(1_000));
+        let (mut book, journal) = journaled_book::<()>();
+        book.set_clock(clock.clone());
+        let id = OrderId::new();
+        book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtd(2_000), None)
+            .unwrap();
+
+        // Replayed long after the order's expiry, it is still accepted as it was originally
+        let recovered = replayed(&journal);
+        assert!(recovered.get_order(id).is_some());
+    }
+
+    #[test]
+    fn test_replay_after_snapshot_sequence() {
+        let (book, journal) = journaled_book::<()>();
+        book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        let snapshot = book.create_snapshot(usize::MAX);
+        let sequence = book.journal_sequence();
+        book.add_limit_order(OrderId::new(), 101, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+
+        let mut recovered = OrderBook::<()>::new("TEST");
+        recovered.restore_from_snapshot(snapshot).unwrap();
+        assert_eq!(
+            recovered
+                .replay_journal(journal.as_ref(), sequence)
+                .unwrap(),
+            2
+        );
+        assert_eq!(book_state(&recovered), book_state(&book));
+
+        // A reattached journal continues the sequence
+        recovered.set_journal(journal.clone());
+        recovered.cancel_all().unwrap();
+        assert_eq!(journal.entries_after(2).unwrap()[0].sequence, 3);
+    }
+
+    #[test]
+    fn test_concurrent_commands_replay_to_same_book() {
+        let (book, journal) = journaled_book::<()>();
+        let book = Arc::new(book);
+        let writers: Vec<_> = (0..4u64)
+            .map(|writer| {
+                let book = Arc::clone(&book);
+                std::thread::spawn(move || {
+                    for step in 0..200u64 {
+                        let side = if (writer + step) % 2 == 0 {
+                            Side::Buy
+                        } else {
+                            Side::Sell
+                        };
+                        let id = OrderId::new();
+                        let price = 99 + (step * 7 + writer) % 3;
+                        let _ = book.add_limit_order(
+                            id,
+                            price,
+                            1 + step % 5,
+                            side,
+                            TimeInForce::Gtc,
+                            None,
+                        );
+                        if step % 4 == 0 {
+                            let _ = book.cancel_order(id);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        assert_eq!(book_state(&replayed(&journal)), book_state(&book));
+    }
+
+    struct FailingJournal;
+
+    impl Journal<()> for FailingJournal {
+        fn append(&self, _entry: &JournalEntry<()>) -> Result<(), OrderBookError> {
+            Err(OrderBookError::JournalError {
+                message: "disk full".to_string(),
+            })
+        }
+
+        fn entries_after(&self, _after: u64) -> Result<Vec<JournalEntry<()>>, OrderBookError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn test_command_not_applied_when_journal_fails() {
+        let mut book = OrderBook::<()>::new("TEST");
+        book.set_journal(Arc::new(FailingJournal));
+
+        let result =
+            book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+        assert!(matches!(result, Err(OrderBookError::JournalError { .. })));
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.journal_sequence(), 0);
+    }
+
+    #[test]
+    fn test_mass_cancel_fails_loudly_when_journal_fails() {
+        let mut book = OrderBook::<()>::new("TEST");
+        let order = OrderType::Standard {
+            id: OrderId::new(),
+            price: 100,
+            quantity: 10,
+            side: Side::Buy,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        };
+        book.add_order_with_owner(order, OwnerId(1)).unwrap();
+        book.set_journal(Arc::new(FailingJournal));
+
+        let journal_error = |result: Result<Vec<OrderId>, OrderBookError>| {
+            matches!(result, Err(OrderBookError::JournalError { .. }))
+        };
+        assert!(journal_error(book.cancel_all()));
+        assert!(journal_error(book.cancel_side(Side::Buy)));
+        assert!(journal_error(book.cancel_range(0, 200, Side::Buy)));
+        assert!(journal_error(book.cancel_by_owner(OwnerId(1))));
+        assert!(journal_error(book.disable_owner(OwnerId(1))));
+        assert_eq!(book.best_bid(), Some(100));
+        assert!(book.is_owner_enabled(OwnerId(1)));
+    }
+
+    #[test]
+    fn test_file_journal_round_trip_ignores_torn_tail() {
+        let path = std::env::temp_dir().join(format!("orderbook-journal-{}.log", OrderId::new()));
+        let journal = Arc::new(FileJournal::open(&path).unwrap());
+        let mut book = OrderBook::<()>::new("TEST");
+        book.set_journal(journal.clone());
+        book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 101, 4, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        journal.sync().unwrap();
+
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| std::io::Write::write_all(&mut file, b"{\"sequence\":3,"))
+            .unwrap();
+
+        let reopened = FileJournal::open(&path).unwrap();
+        let mut recovered = OrderBook::<()>::new("TEST");
+        assert_eq!(recovered.replay_journal(&reopened, 0).unwrap(), 2);
+        assert_eq!(book_state(&recovered), book_state(&book));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(all(feature = "mmap", unix))]
+    #[test]
+    fn test_mmap_journal_reopens_after_last_complete_entry() {
+        use crate::orderbook::journal::MmapJournal;
+
+        let path = std::env::temp_dir().join(format!("orderbook-journal-{}.mmap", OrderId::new()));
+        let journal = Arc::new(MmapJournal::open(&path, 64 * 1024).unwrap());
+        let mut book = OrderBook::<()>::new("TEST");
+        book.set_journal(journal.clone());
+        book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 101, 4, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        journal.sync().unwrap();
+        let remaining = journal.remaining();
+        drop(book);
+        drop(journal);
+
+        // A torn append leaves a partial line before the zeroed tail
+        {
+            use std::io::{Seek, SeekFrom, Write};
+            let mut file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+            file.seek(SeekFrom::Start((64 * 1024 - remaining) as u64))
+                .unwrap();
+            file.write_all(b"{\"sequence\":3,").unwrap();
+        }
+
+        let reopened = Arc::new(MmapJournal::open(&path, 64 * 1024).unwrap());
+        assert_eq!(reopened.remaining(), remaining);
+        let mut recovered = OrderBook::<()>::new("TEST");
+        assert_eq!(recovered.replay_journal(reopened.as_ref(), 0).unwrap(), 2);
+        assert_eq!(recovered.best_bid(), Some(100));
+        assert_eq!(recovered.best_ask(), Some(101));
+
+        // Appending continues the sequence over the erased tail
+        recovered.set_journal(reopened.clone());
+        recovered
+            .add_limit_order(OrderId::new(), 99, 1, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        let entries = Journal::<()>::entries_after(reopened.as_ref(), 0).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].sequence, 3);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(all(feature = "mmap", unix))]
+    #[test]
+    fn test_full_mmap_journal_rejects_command() {
+        use crate::orderbook::journal::MmapJournal;
+
+        let path = std::env::temp_dir().join(format!("orderbook-journal-{}.mmap", OrderId::new()));
+        let mut book = OrderBook::<()>::new("TEST");
+        book.set_journal(Arc::new(MmapJournal::open(&path, 16).unwrap()));
+
+        let result =
+            book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+        assert!(matches!(result, Err(OrderBookError::JournalError { .. })));
+        assert_eq!(book.best_bid(), None);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+//! Unit tests for the book-wide and per-owner kill switches.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::error::OrderBookError;
+    use crate::orderbook::journal::InMemoryJournal;
+    use crate::orderbook::modifications::OrderQuantity;
+    use crate::orderbook::owner::OwnerId;
+    use pricelevel::{OrderId, OrderType, OrderUpdate, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn order(price: u64, side: Side) -> OrderType<()> {
+        OrderType::Standard {
+            id: OrderId::new(),
+            price,
+            quantity: 10,
+            side,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        }
+    }
+
+    #[test]
+    fn test_global_switch_rejects_new_orders() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        let resting = OrderId::new();
+        book.add_limit_order(resting, 100, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+
+        book.set_trading_enabled(false);
+        assert!(!book.is_trading_enabled());
+        assert!(matches!(
+            book.add_order(order(90, Side::Buy)),
+            Err(OrderBookError::TradingDisabled { owner: None })
+        ));
+        assert!(matches!(
+            book.match_market_order(OrderId::new(), 5, Side::Buy),
+            Err(OrderBookError::TradingDisabled { owner: None })
+        ));
+        assert!(
+            book.match_limit_order(OrderId::new(), 5, Side::Buy, 100)
+                .is_err()
+        );
+
+        // Resting orders are kept and can still be cancelled
+        assert_eq!(book.best_ask(), Some(100));
+        assert!(book.cancel_order(resting).unwrap().is_some());
+
+        book.set_trading_enabled(true);
+        assert!(book.add_order(order(90, Side::Buy)).is_ok());
+    }
+
+    #[test]
+    fn test_amends_are_rejected_without_losing_the_order() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        let id = OrderId::new();
+        book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.set_trading_enabled(false);
+
+        for update in [
+            OrderUpdate::UpdatePrice {
+                order_id: id,
+                new_price: 99,
+            },
+            OrderUpdate::UpdatePriceAndQuantity {
+                order_id: id,
+                new_price: 99,
+                new_quantity: 5,
+            },
+            OrderUpdate::Replace {
+                order_id: id,
+                price: 99,
+                quantity: 5,
+                side: Side::Buy,
+            },
+        ] {
+            assert!(matches!(
+                book.update_order(update),
+                Err(OrderBookError::TradingDisabled { owner: None })
+            ));
+            assert_eq!(book.get_order(id).unwrap().price(), 100);
+        }
+    }
+
+    #[test]
+    fn test_quantity_amends_are_rejected_by_both_switches() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        let owned = OrderId::new();
+        let mut resting = order(100, Side::Buy);
+        if let OrderType::Standard { id, quantity, .. } = &mut resting {
+            (*id, *quantity) = (owned, 5);
+        }
+        book.add_order_with_owner(resting, OwnerId(4)).unwrap();
+        let grow = OrderUpdate::UpdateQuantity {
+            order_id: owned,
+            new_quantity: 50,
+        };
+
+        book.set_trading_enabled(false);
+        assert!(matches!(
+            book.update_order(grow),
+            Err(OrderBookError::TradingDisabled { owner: None })
+        ));
+        assert_eq!(book.get_order(owned).unwrap().total_quantity(), 5);
+        book.set_trading_enabled(true);
+
+        // Disabling the owner cancels its orders, so block it without doing so
+        book.disabled_owners.insert(OwnerId(4));
+        assert!(matches!(
+            book.update_order(grow),
+            Err(OrderBookError::TradingDisabled {
+                owner: Some(OwnerId(4))
+            })
+        ));
+        assert_eq!(book.get_order(owned).unwrap().total_quantity(), 5);
+    }
+
+    #[test]
+    fn test_disable_owner_cancels_and_blocks_only_that_owner() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        book.add_order_with_owner(order(100, Side::Sell), OwnerId(1))
+            .unwrap();
+        book.add_order_with_owner(order(90, Side::Buy), OwnerId(1))
+            .unwrap();
+        book.add_order_with_owner(order(101, Side::Sell), OwnerId(2))
+            .unwrap();
+
+        assert_eq!(book.disable_owner(OwnerId(1)).unwrap().len(), 2);
+        assert!(!book.is_owner_enabled(OwnerId(1)));
+        assert_eq!(book.disabled_owners(), vec![OwnerId(1)]);
+        assert_eq!(book.best_ask(), Some(101));
+        assert_eq!(book.best_bid(), None);
+
+        assert!(matches!(
+            book.add_order_with_owner(order(90, Side::Buy), OwnerId(1)),
+            Err(OrderBookError::TradingDisabled {
+                owner: Some(OwnerId(1))
+            })
+        ));
+        assert!(
+            book.add_order_with_owner(order(90, Side::Buy), OwnerId(2))
+                .is_ok()
+        );
+
+        assert!(book.enable_owner(OwnerId(1)));
+        assert!(!book.enable_owner(OwnerId(1)));
+        assert!(
+            book.add_order_with_owner(order(90, Side::Buy), OwnerId(1))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_kill_switches_are_replayed() {
+        let journal = Arc::new(InMemoryJournal::new());
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.set_journal(journal.clone());
+        book.add_order_with_owner(order(100, Side::Sell), OwnerId(3))
+            .unwrap();
+        book.disable_owner(OwnerId(3)).unwrap();
+        book.set_trading_enabled(false);
+
+        let mut recovered = OrderBook::<()>::new("BTC/USD");
+        recovered.replay_journal(journal.as_ref(), 0).unwrap();
+        assert!(!recovered.is_trading_enabled());
+        assert!(!recovered.is_owner_enabled(OwnerId(3)));
+        assert_eq!(recovered.best_ask(), None);
+    }
+}
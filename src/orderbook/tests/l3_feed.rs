@@ -0,0 +1,233 @@
+//! Unit tests for the market-by-order feed applier.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::error::OrderBookError;
+    use crate::orderbook::l3_feed::{
+        L3ApplyOutcome, L3FeedApplier, L3Message, L3Order, L3Snapshot, L3Update,
+    };
+    use pricelevel::Side;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    fn add(sequence: u64, id: &str, side: Side, price: u64, quantity: u64) -> L3Update {
+        L3Update {
+            sequence,
+            message: L3Message::Add {
+                exchange_id: id.to_string(),
+                side,
+                price,
+                quantity,
+            },
+        }
+    }
+
+    fn order(id: &str, side: Side, price: u64, quantity: u64) -> L3Order {
+        L3Order {
+            exchange_id: id.to_string(),
+            side,
+            price,
+            quantity,
+        }
+    }
+
+    /// Applier whose snapshot source serves the shared snapshot and counts fetches.
+    fn feed(snapshot: L3Snapshot) -> (L3FeedApplier<()>, Arc<Mutex<L3Snapshot>>, Arc<AtomicUsize>) {
+        let shared = Arc::new(Mutex::new(snapshot));
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let (source, counter) = (shared.clone(), fetches.clone());
+        let applier = L3FeedApplier::new(OrderBook::new("TEST"), move |_: &str| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(source.lock().unwrap().clone())
+        });
+        (applier, shared, fetches)
+    }
+
+    #[test]
+    fn test_first_update_loads_snapshot() {
+        let (mut feed, _, fetches) = feed(L3Snapshot {
+            sequence: 10,
+            orders: vec![
+                order("b1", Side::Buy, 100, 5),
+                order("a1", Side::Sell, 102, 7),
+            ],
+        });
+
+        let outcome = feed.apply(add(11, "b2", Side::Buy, 101, 3)).unwrap();
+        assert_eq!(outcome, L3ApplyOutcome::Resynced(10));
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(feed.sequence(), Some(11));
+        assert_eq!(feed.book().best_bid(), Some(101));
+        assert_eq!(feed.book().best_ask(), Some(102));
+        assert!(feed.local_order_id("b1").is_some());
+    }
+
+    #[test]
+    fn test_messages_maintain_book() {
+        let (mut feed, _, _) = feed(L3Snapshot::default());
+        feed.apply(add(1, "b1", Side::Buy, 100, 10)).unwrap();
+        feed.apply(add(2, "b2", Side::Buy, 100, 4)).unwrap();
+        feed.apply(add(3, "a1", Side::Sell, 105, 6)).unwrap();
+
+        let outcome = feed
+            .apply(L3Update {
+                sequence: 4,
+                message: L3Message::Execute {
+                    exchange_id: "b1".to_string(),
+                    quantity: 3,
+                },
+            })
+            .unwrap();
+        assert_eq!(outcome, L3ApplyOutcome::Applied);
+        feed.apply(L3Update {
+            sequence: 5,
+            message: L3Message::Modify {
+                exchange_id: "a1".to_string(),
+                price: 104,
+                quantity: 2,
+            },
+        })
+        .unwrap();
+        feed.apply(L3Update {
+            sequence: 6,
+            message: L3Message::Delete {
+                exchange_id: "b2".to_string(),
+            },
+        })
+        .unwrap();
+
+        let (bids, asks) = feed.book().depth(usize::MAX);
+        assert_eq!(bids, vec![(100, 7, 1)]);
+        assert_eq!(asks, vec![(104, 2, 1)]);
+
+        feed.apply(L3Update {
+            sequence: 7,
+            message: L3Message::Execute {
+                exchange_id: "b1".to_string(),
+                quantity: 7,
+            },
+        })
+        .unwrap();
+        assert_eq!(feed.book().best_bid(), None);
+        assert!(feed.local_order_id("b1").is_none());
+    }
+
+    #[test]
+    fn test_crossing_add_does_not_match() {
+        let (mut feed, _, _) = feed(L3Snapshot::default());
+        feed.apply(add(1, "a1", Side::Sell, 100, 5)).unwrap();
+        feed.apply(add(2, "b1", Side::Buy, 101, 5)).unwrap();
+
+        assert_eq!(feed.book().best_ask(), Some(100));
+        assert_eq!(feed.book().best_bid(), Some(101));
+        assert_eq!(feed.book().last_trade_price(), None);
+    }
+
+    #[test]
+    fn test_stale_updates_are_ignored() {
+        let (mut feed, _, _) = feed(L3Snapshot::default());
+        feed.apply(add(1, "b1", Side::Buy, 100, 5)).unwrap();
+
+        let outcome = feed.apply(add(1, "b1", Side::Buy, 100, 5)).unwrap();
+        assert_eq!(outcome, L3ApplyOutcome::Stale);
+        assert_eq!(feed.book().depth(usize::MAX).0, vec![(100, 5, 1)]);
+    }
+
+    #[test]
+    fn test_gap_triggers_resync() {
+        let (mut feed, shared, fetches) = feed(L3Snapshot::default());
+        feed.apply(add(1, "b1", Side::Buy, 100, 5)).unwrap();
+        feed.apply(add(2, "b2", Side::Buy, 99, 5)).unwrap();
+
+        *shared.lock().unwrap() = L3Snapshot {
+            sequence: 5,
+            orders: vec![
+                order("b1", Side::Buy, 100, 2),
+                order("a9", Side::Sell, 103, 1),
+            ],
+        };
+        let outcome = feed.apply(add(6, "a2", Side::Sell, 104, 8)).unwrap();
+
+        assert_eq!(outcome, L3ApplyOutcome::Resynced(5));
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+        assert_eq!(feed.resyncs(), 2);
+        assert_eq!(feed.sequence(), Some(6));
+        let (bids, asks) = feed.book().depth(usize::MAX);
+        assert_eq!(bids, vec![(100, 2, 1)]);
+        assert_eq!(asks, vec![(103, 1, 1), (104, 8, 1)]);
+        assert!(feed.local_order_id("b2").is_none());
+    }
+
+    #[test]
+    fn test_update_covered_by_snapshot_is_not_reapplied() {
+        let (mut feed, shared, _) = feed(L3Snapshot::default());
+        feed.apply(add(1, "b1", Side::Buy, 100, 5)).unwrap();
+
+        *shared.lock().unwrap() = L3Snapshot {
+            sequence: 8,
+            orders: vec![
+                order("b1", Side::Buy, 100, 5),
+                order("b4", Side::Buy, 98, 1),
+            ],
+        };
+        let outcome = feed.apply(add(4, "b4", Side::Buy, 98, 1)).unwrap();
+
+        assert_eq!(outcome, L3ApplyOutcome::Resynced(8));
+        assert_eq!(feed.sequence(), Some(8));
+        assert_eq!(
+            feed.book().depth(usize::MAX).0,
+            vec![(100, 5, 1), (98, 1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_gap_remaining_after_resync_is_reported() {
+        let (mut feed, _, _) = feed(L3Snapshot {
+            sequence: 3,
+            orders: Vec::new(),
+        });
+
+        let err = feed.apply(add(7, "b1", Side::Buy, 100, 5)).unwrap_err();
+        assert!(matches!(
+            err,
+            OrderBookError::SequenceGap {
+                expected: 4,
+                received: 7
+            }
+        ));
+        assert_eq!(feed.sequence(), Some(3));
+        assert_eq!(feed.book().best_bid(), None);
+    }
+
+    #[test]
+    fn test_unknown_exchange_order_is_rejected() {
+        let (mut feed, _, _) = feed(L3Snapshot::default());
+        feed.apply(add(1, "b1", Side::Buy, 100, 5)).unwrap();
+
+        let err = feed
+            .apply(L3Update {
+                sequence: 2,
+                message: L3Message::Delete {
+                    exchange_id: "missing".to_string(),
+                },
+            })
+            .unwrap_err();
+        assert!(matches!(err, OrderBookError::OrderNotFound(_)));
+        assert_eq!(feed.sequence(), Some(1));
+    }
+
+    #[test]
+    fn test_failed_snapshot_fetch_leaves_book_untouched() {
+        let mut feed = L3FeedApplier::<()>::new(OrderBook::new("TEST"), |_: &str| {
+            Err(OrderBookError::InvalidOperation {
+                message: "unavailable".to_string(),
+            })
+        });
+
+        assert!(feed.apply(add(1, "b1", Side::Buy, 100, 5)).is_err());
+        assert_eq!(feed.sequence(), None);
+        assert_eq!(feed.resyncs(), 0);
+        assert_eq!(feed.book().best_bid(), None);
+    }
+}
@@ -0,0 +1,204 @@
+//! Unit tests for books indexing their levels in a dense price ladder.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::config::BookConfig;
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    const TICK: u64 = 5;
+
+    fn config() -> BookConfig {
+        BookConfig::default()
+            .with_tick_size(TICK)
+            .with_price_band(1_000, 2_000)
+    }
+
+    fn dense_book() -> OrderBook<()> {
+        let book = OrderBook::new_with_config("ES", config().with_dense_ladder());
+        assert!(book.ladder.is_some());
+        book
+    }
+
+    fn add(book: &OrderBook<()>, price: u64, quantity: u64, side: Side) -> OrderId {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, side, TimeInForce::Gtc, None)
+            .unwrap();
+        id
+    }
+
+    fn assert_same_top(dense: &OrderBook<()>, sparse: &OrderBook<()>) {
+        assert_eq!(dense.best_bid(), sparse.best_bid());
+        assert_eq!(dense.best_ask(), sparse.best_ask());
+    }
+
+    /// Checks that the ladder of `book` holds exactly the levels of its level
+    /// maps, walking them in the same order.
+    fn assert_ladder_holds_levels(book: &OrderBook<()>) {
+        let ladder = book.ladder.as_ref().unwrap();
+        for side in [Side::Buy, Side::Sell] {
+            let mut mapped: Vec<_> = book
+                .side_levels(side, false)
+                .iter()
+                .map(|entry| (*entry.key(), Arc::clone(entry.value())))
+                .collect();
+            if side == Side::Buy {
+                mapped.reverse();
+            }
+            let laddered: Vec<_> = ladder.levels_best_first(side).collect();
+            assert_eq!(laddered.len(), mapped.len());
+            for ((price, level), (mapped_price, mapped_level)) in laddered.iter().zip(&mapped) {
+                assert_eq!(price, mapped_price);
+                assert!(Arc::ptr_eq(level, mapped_level));
+            }
+        }
+    }
+
+    #[test]
+    fn test_dense_book_matches_skipmap_book() {
+        // Both books stamp orders from one clock that ticks every step, so
+        // orders resting at a price keep the same time priority in both
+        let clock = Arc::new(ManualClock::new(1_000));
+        let mut dense = dense_book();
+        dense.set_clock(clock.clone());
+        let mut sparse = OrderBook::<()>::new_with_config("ES", config());
+        sparse.set_clock(clock.clone());
+        assert!(sparse.ladder.is_none());
+
+        let mut resting = Vec::new();
+        for step in 0..200u64 {
+            let offset = (step * 37) % 40 * TICK;
+            let (price, side) = if step % 2 == 0 {
+                (1_400 - offset, Side::Buy)
+            } else {
+                (1_405 + offset, Side::Sell)
+            };
+            let quantity = 1 + step % 7;
+            let id = add(&dense, price, quantity, side);
+            sparse
+                .add_limit_order(id, price, quantity, side, TimeInForce::Gtc, None)
+                .unwrap();
+            resting.push(id);
+            clock.advance(1);
+            assert_same_top(&dense, &sparse);
+
+            if step % 3 == 0 {
+                let id = resting.remove((step as usize * 7) % resting.len());
+                dense.cancel_order(id).unwrap();
+                sparse.cancel_order(id).unwrap();
+                assert_same_top(&dense, &sparse);
+            }
+            if step % 25 == 24 {
+                let side = if step % 50 == 49 {
+                    Side::Buy
+                } else {
+                    Side::Sell
+                };
+                dense.submit_market_order(OrderId::new(), 30, side).unwrap();
+                sparse
+                    .submit_market_order(OrderId::new(), 30, side)
+                    .unwrap();
+                assert_same_top(&dense, &sparse);
+            }
+        }
+
+        for price in (1_000..=2_000).step_by(TICK as usize) {
+            for side in [Side::Buy, Side::Sell] {
+                let ids = |book: &OrderBook<()>| {
+                    book.get_orders_at_price(price, side)
+                        .iter()
+                        .map(|order| order.id())
+                        .collect::<Vec<OrderId>>()
+                };
+                assert_eq!(ids(&dense), ids(&sparse));
+            }
+        }
+        assert_ladder_holds_levels(&dense);
+    }
+
+    #[test]
+    fn test_dense_book_resolves_levels_through_ladder() {
+        let book = dense_book();
+        let first = add(&book, 1_500, 10, Side::Sell);
+        add(&book, 1_500, 5, Side::Sell);
+        add(&book, 1_510, 10, Side::Sell);
+        add(&book, 1_520, 10, Side::Sell);
+        assert_eq!(book.asks.len(), 3);
+        assert_ladder_holds_levels(&book);
+
+        book.cancel_order(first).unwrap();
+        assert_eq!(book.get_orders_at_price(1_500, Side::Sell).len(), 1);
+
+        // A limit order sweeps the ladder in price order and stops at its limit
+        let result = book
+            .match_limit_order(OrderId::new(), 25, Side::Buy, 1_510)
+            .unwrap();
+        let prices: Vec<u64> = result
+            .transactions
+            .as_vec()
+            .iter()
+            .map(|transaction| transaction.price)
+            .collect();
+        assert_eq!(prices, [1_500, 1_510]);
+        assert_eq!(result.remaining_quantity, 10);
+        assert_eq!(book.best_ask(), Some(1_520));
+        assert_ladder_holds_levels(&book);
+
+        book.cancel_all().unwrap();
+        assert_ladder_holds_levels(&book);
+    }
+
+    #[test]
+    fn test_dense_book_bulk_operations() {
+        let book = dense_book();
+        add(&book, 1_100, 10, Side::Buy);
+        add(&book, 1_200, 10, Side::Buy);
+        add(&book, 1_300, 10, Side::Sell);
+
+        book.cancel_range(1_150, 1_250, Side::Buy).unwrap();
+        assert_eq!(book.best_bid(), Some(1_100));
+
+        let snapshot = book.create_snapshot(10);
+        book.cancel_all().unwrap();
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.best_ask(), None);
+
+        book.restore_from_snapshot(snapshot).unwrap();
+        assert_eq!(book.best_bid(), Some(1_100));
+        assert_eq!(book.best_ask(), Some(1_300));
+
+        let fork = book.fork();
+        assert!(fork.ladder.is_some());
+        fork.submit_market_order(OrderId::new(), 10, Side::Buy)
+            .unwrap();
+        assert_eq!(fork.best_ask(), None);
+        assert_eq!(book.best_ask(), Some(1_300));
+
+        let mut restored = OrderBook::<()>::new("ES");
+        restored
+            .restore_full_state(book.create_full_state())
+            .unwrap();
+        assert!(restored.ladder.is_some());
+        assert_eq!(restored.best_bid(), Some(1_100));
+        assert_eq!(restored.get_orders_at_price(1_300, Side::Sell).len(), 1);
+        assert_ladder_holds_levels(&restored);
+    }
+
+    #[test]
+    fn test_hidden_levels_stay_out_of_ladder() {
+        let book = dense_book();
+        book.add_hidden_order(
+            OrderId::new(),
+            1_500,
+            10,
+            Side::Sell,
+            TimeInForce::Gtc,
+            None,
+        )
+        .unwrap();
+        add(&book, 1_600, 10, Side::Sell);
+        assert_eq!(book.best_ask(), Some(1_600));
+    }
+}
@@ -0,0 +1,219 @@
+//! Unit tests for the book managers.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::manager::{BookManager, BookManagerStd, TradeEventHandler};
+    use crate::orderbook::trade::TradeEvent;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    /// Handler collecting the symbol and executed quantity of every trade
+    #[derive(Default)]
+    struct Collector {
+        trades: Mutex<Vec<(String, u64)>>,
+    }
+
+    impl TradeEventHandler for Collector {
+        fn handle(&self, event: &TradeEvent) {
+            self.trades.lock().unwrap().push((
+                event.symbol.clone(),
+                event.trade_result.match_result.executed_quantity(),
+            ));
+        }
+    }
+
+    fn trade<M: BookManager<()>>(manager: &M, symbol: &str, quantity: u64) {
+        let book = manager.get_book(symbol).unwrap();
+        book.add_limit_order(
+            OrderId::new(),
+            100,
+            quantity,
+            Side::Sell,
+            TimeInForce::Gtc,
+            None,
+        )
+        .unwrap();
+        book.submit_market_order(OrderId::new(), quantity, Side::Buy)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_shutdown_snapshots_every_book() {
+        let mut manager = BookManagerStd::<()>::new();
+        manager.add_book("BTC/USD");
+        manager.add_book("ETH/USD");
+        manager.start_trade_processor();
+        manager
+            .get_book("BTC/USD")
+            .unwrap()
+            .add_limit_order(OrderId::new(), 99, 5, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        trade(&manager, "ETH/USD", 3);
+
+        let snapshots = manager.shutdown(Some(10));
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots["BTC/USD"].bids.len(), 1);
+        assert_eq!(snapshots["BTC/USD"].bids[0].price, 99);
+        assert!(snapshots["ETH/USD"].asks.is_empty());
+    }
+
+    #[test]
+    fn test_shutdown_without_snapshots() {
+        let mut manager = BookManagerStd::<()>::new();
+        manager.add_book("BTC/USD");
+        manager.start_trade_processor();
+        trade(&manager, "BTC/USD", 2);
+
+        assert!(manager.shutdown(None).is_empty());
+    }
+
+    #[test]
+    fn test_shutdown_without_processor() {
+        let mut manager = BookManagerStd::<()>::new();
+        manager.add_book("BTC/USD");
+        trade(&manager, "BTC/USD", 2);
+
+        let snapshots = manager.shutdown(Some(1));
+        assert_eq!(snapshots["BTC/USD"].symbol, "BTC/USD");
+    }
+
+    #[test]
+    fn test_shutdown_does_not_wait_for_removed_books() {
+        let mut manager = BookManagerStd::<()>::new();
+        manager.add_book("BTC/USD");
+        manager.start_trade_processor();
+        let removed = manager.remove_book("BTC/USD").unwrap();
+
+        assert!(manager.shutdown(Some(1)).is_empty());
+        removed
+            .add_limit_order(OrderId::new(), 100, 1, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        removed
+            .submit_market_order(OrderId::new(), 1, Side::Buy)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_handler_receives_every_trade_before_shutdown_returns() {
+        let collector = Arc::new(Collector::default());
+        let mut manager = BookManagerStd::<()>::new().with_handler(collector.clone());
+        manager.add_book("BTC/USD");
+        manager.add_book("ETH/USD");
+        manager.start_trade_processor();
+
+        for quantity in 1..=20 {
+            trade(&manager, "BTC/USD", quantity);
+        }
+        trade(&manager, "ETH/USD", 7);
+        manager.shutdown(None);
+
+        let trades = collector.trades.lock().unwrap();
+        assert_eq!(trades.len(), 21);
+        assert_eq!(trades[0], ("BTC/USD".to_string(), 1));
+        assert_eq!(trades[20], ("ETH/USD".to_string(), 7));
+    }
+
+    #[test]
+    fn test_closure_handler_without_processor() {
+        let count = Arc::new(Mutex::new(0));
+        let counter = Arc::clone(&count);
+        let mut manager =
+            BookManagerStd::<()>::new().with_handler(Arc::new(move |_: &TradeEvent| {
+                *counter.lock().unwrap() += 1
+            }));
+        manager.add_book("BTC/USD");
+        trade(&manager, "BTC/USD", 1);
+        trade(&manager, "BTC/USD", 2);
+
+        assert_eq!(*count.lock().unwrap(), 0);
+        manager.shutdown(None);
+        assert_eq!(*count.lock().unwrap(), 2);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_tokio_shutdown_awaits_processor() {
+        use crate::orderbook::manager::BookManagerTokio;
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let collector = Arc::new(Collector::default());
+            let mut manager = BookManagerTokio::<()>::new().with_handler(collector.clone());
+            manager.add_book("BTC/USD");
+            manager.start_trade_processor();
+            trade(&manager, "BTC/USD", 4);
+
+            let snapshots = manager.shutdown(Some(5)).await;
+            assert_eq!(snapshots.len(), 1);
+            assert!(snapshots["BTC/USD"].asks.is_empty());
+            assert_eq!(
+                *collector.trades.lock().unwrap(),
+                vec![("BTC/USD".to_string(), 4)]
+            );
+        });
+    }
+
+    #[test]
+    fn test_manager_trade_subscription_covers_later_books() {
+        let mut manager = BookManagerStd::<()>::new();
+        manager.add_book("BTC/USD");
+        let symbols = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&symbols);
+        let id = manager.subscribe_trades(Arc::new(move |trade| {
+            seen.lock().unwrap().push(trade.symbol.clone());
+        }));
+        manager.add_book("ETH/USD");
+
+        trade(&manager, "BTC/USD", 1);
+        trade(&manager, "ETH/USD", 1);
+        assert!(manager.unsubscribe_trades(id));
+        assert!(!manager.unsubscribe_trades(id));
+        trade(&manager, "BTC/USD", 1);
+
+        assert_eq!(
+            *symbols.lock().unwrap(),
+            vec!["BTC/USD".to_string(), "ETH/USD".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_book_subscriptions_come_and_go() {
+        let mut manager = BookManagerStd::<()>::new();
+        manager.add_book("BTC/USD");
+        let trades = Arc::new(Mutex::new(0));
+        let changes = Arc::new(Mutex::new(0));
+        let (trade_count, change_count) = (Arc::clone(&trades), Arc::clone(&changes));
+
+        let trade_id = manager
+            .subscribe_book_trades(
+                "BTC/USD",
+                Arc::new(move |_| *trade_count.lock().unwrap() += 1),
+            )
+            .unwrap();
+        let change_id = manager
+            .subscribe_book_price_levels(
+                "BTC/USD",
+                Arc::new(move |_| *change_count.lock().unwrap() += 1),
+            )
+            .unwrap();
+        assert!(
+            manager
+                .subscribe_book_trades("ETH/USD", Arc::new(|_| {}))
+                .is_none()
+        );
+
+        trade(&manager, "BTC/USD", 2);
+        assert_eq!(*trades.lock().unwrap(), 1);
+        let observed_changes = *changes.lock().unwrap();
+        assert!(observed_changes > 0);
+
+        assert!(manager.unsubscribe_book_trades("BTC/USD", trade_id));
+        assert!(manager.unsubscribe_book_price_levels("BTC/USD", change_id));
+        assert!(!manager.unsubscribe_book_trades("ETH/USD", trade_id));
+        trade(&manager, "BTC/USD", 2);
+        assert_eq!(*trades.lock().unwrap(), 1);
+        assert_eq!(*changes.lock().unwrap(), observed_changes);
+    }
+}
@@ -0,0 +1,108 @@
+//! Unit tests for bulk snapshot and restore of managed books.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::OrderBookError;
+    use crate::orderbook::config::BookConfig;
+    use crate::orderbook::manager::{BookManager, BookManagerStd};
+    use crate::orderbook::manager_snapshot::ManagerSnapshot;
+    use crate::orderbook::symbol_config::{InstrumentType, SymbolConfig};
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn primary() -> BookManagerStd<()> {
+        let mut manager = BookManagerStd::<()>::with_clock(Arc::new(ManualClock::new(5_000)));
+        manager.add_book("BTC/USD");
+        manager.add_book_with_config(
+            "ETH-PERP",
+            SymbolConfig::new(InstrumentType::Future)
+                .with_book_config(BookConfig::default().with_tick_size(5)),
+        );
+        for (symbol, price, side) in [
+            ("BTC/USD", 99, Side::Buy),
+            ("BTC/USD", 101, Side::Sell),
+            ("ETH-PERP", 50, Side::Buy),
+        ] {
+            manager
+                .get_book(symbol)
+                .unwrap()
+                .add_limit_order(OrderId::new(), price, 10, side, TimeInForce::Gtc, None)
+                .unwrap();
+        }
+        manager
+    }
+
+    #[test]
+    fn test_snapshot_all() {
+        let snapshot = primary().snapshot_all(10).unwrap();
+        snapshot.validate().unwrap();
+
+        assert_eq!(snapshot.timestamp, 5_000);
+        let symbols: Vec<&str> = snapshot
+            .books
+            .iter()
+            .map(|book| book.snapshot.symbol.as_str())
+            .collect();
+        assert_eq!(symbols, vec!["BTC/USD", "ETH-PERP"]);
+        let perp = snapshot.book("ETH-PERP").unwrap();
+        assert_eq!(perp.config.instrument_type, InstrumentType::Future);
+        assert_eq!(perp.snapshot.bids[0].price, 50);
+    }
+
+    #[test]
+    fn test_restore_all_round_trip() {
+        let json = primary()
+            .snapshot_all(usize::MAX)
+            .unwrap()
+            .to_json()
+            .unwrap();
+
+        let mut standby = BookManagerStd::<()>::new();
+        standby.add_book("SOL/USD");
+        let restored = standby
+            .restore_all(ManagerSnapshot::from_json(&json).unwrap())
+            .unwrap();
+
+        assert_eq!(restored, 2);
+        assert_eq!(standby.book_count(), 3);
+        let btc = standby.get_book("BTC/USD").unwrap();
+        assert_eq!((btc.best_bid(), btc.best_ask()), (Some(99), Some(101)));
+        let perp = standby.get_book("ETH-PERP").unwrap();
+        assert_eq!(perp.config().tick_size, Some(5));
+        assert_eq!(
+            standby.symbol_config("ETH-PERP").unwrap().instrument_type,
+            InstrumentType::Future
+        );
+
+        let result = perp.add_limit_order(OrderId::new(), 52, 1, Side::Buy, TimeInForce::Gtc, None);
+        assert!(matches!(
+            result,
+            Err(OrderBookError::InvalidTickSize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_tampered_snapshot_is_rejected() {
+        let mut snapshot = primary().snapshot_all(10).unwrap();
+        snapshot.books[0].snapshot.bids[0].visible_quantity += 1;
+
+        let mut standby = BookManagerStd::<()>::new();
+        let result = standby.restore_all(snapshot);
+        assert!(matches!(
+            result,
+            Err(OrderBookError::ChecksumMismatch { .. })
+        ));
+        assert_eq!(standby.book_count(), 0);
+    }
+
+    #[test]
+    fn test_unsupported_version_is_rejected() {
+        let mut snapshot = primary().snapshot_all(10).unwrap();
+        snapshot.version += 1;
+        assert!(matches!(
+            snapshot.validate(),
+            Err(OrderBookError::InvalidOperation { .. })
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+//! Unit tests for aggregated book manager metrics.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::manager::{BookManager, BookManagerStd};
+    use crate::orderbook::manager_stats::TRADE_RATE_WINDOW_SECS;
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn manager(clock: &Arc<ManualClock>) -> BookManagerStd<()> {
+        let mut manager = BookManagerStd::<()>::with_clock(clock.clone());
+        manager.add_book("BTC/USD");
+        manager.add_book("ETH/USD");
+        manager
+    }
+
+    fn rest(manager: &BookManagerStd<()>, symbol: &str, price: u64, quantity: u64, side: Side) {
+        manager
+            .get_book(symbol)
+            .unwrap()
+            .add_limit_order(
+                OrderId::new(),
+                price,
+                quantity,
+                side,
+                TimeInForce::Gtc,
+                None,
+            )
+            .unwrap();
+    }
+
+    fn trade(manager: &BookManagerStd<()>, symbol: &str, quantity: u64) {
+        manager
+            .get_book(symbol)
+            .unwrap()
+            .submit_market_order(OrderId::new(), quantity, Side::Buy)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_depth_metrics() {
+        let clock = Arc::new(ManualClock::new(1_000_000));
+        let manager = manager(&clock);
+        rest(&manager, "BTC/USD", 99, 5, Side::Buy);
+        rest(&manager, "BTC/USD", 99, 3, Side::Buy);
+        rest(&manager, "BTC/USD", 98, 2, Side::Buy);
+        rest(&manager, "BTC/USD", 101, 4, Side::Sell);
+        rest(&manager, "ETH/USD", 50, 10, Side::Sell);
+
+        let stats = manager.aggregate_stats();
+        assert_eq!(stats.timestamp, 1_000_000);
+        let symbols: Vec<&str> = stats.books.iter().map(|b| b.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["BTC/USD", "ETH/USD"]);
+
+        let btc = stats.book("BTC/USD").unwrap();
+        assert_eq!(btc.order_count, 4);
+        assert_eq!((btc.bid_levels, btc.ask_levels), (2, 1));
+        assert_eq!((btc.bid_volume, btc.ask_volume), (10, 4));
+        assert_eq!(btc.trade_count, 0);
+        assert_eq!(btc.last_trade_timestamp, None);
+        assert_eq!(btc.last_event_timestamp, Some(1_000_000));
+
+        assert_eq!(stats.total_orders, 5);
+        assert_eq!(stats.total_levels, 4);
+        assert_eq!(stats.total_volume, 24);
+    }
+
+    #[test]
+    fn test_trade_metrics() {
+        let clock = Arc::new(ManualClock::new(1_000_000));
+        let manager = manager(&clock);
+        rest(&manager, "BTC/USD", 100, 100, Side::Sell);
+        rest(&manager, "ETH/USD", 100, 100, Side::Sell);
+
+        clock.advance(1_500);
+        trade(&manager, "BTC/USD", 3);
+        trade(&manager, "BTC/USD", 4);
+        clock.advance(500);
+        trade(&manager, "ETH/USD", 5);
+        clock.advance(2_000);
+
+        let stats = manager.aggregate_stats();
+        let btc = stats.book("BTC/USD").unwrap();
+        assert_eq!(btc.trade_count, 2);
+        assert_eq!(btc.traded_volume, 7);
+        assert_eq!(btc.last_trade_timestamp, Some(1_001_500));
+        assert_eq!(btc.trades_per_second, 0.5);
+        let eth = stats.book("ETH/USD").unwrap();
+        assert_eq!(eth.last_event_timestamp, Some(1_002_000));
+
+        assert_eq!(stats.total_trades, 3);
+        assert_eq!(stats.total_traded_volume, 12);
+        assert_eq!(stats.trades_per_second, 0.75);
+        assert_eq!(stats.last_event_timestamp, Some(1_002_000));
+    }
+
+    #[test]
+    fn test_trade_rate_window() {
+        let clock = Arc::new(ManualClock::new(0));
+        let manager = manager(&clock);
+        rest(&manager, "BTC/USD", 100, 100, Side::Sell);
+        for _ in 0..6 {
+            trade(&manager, "BTC/USD", 1);
+        }
+
+        clock.advance(TRADE_RATE_WINDOW_SECS * 1000 - 1);
+        let btc = manager.aggregate_stats().book("BTC/USD").cloned().unwrap();
+        assert_eq!(btc.trades_per_second, 6.0 / TRADE_RATE_WINDOW_SECS as f64);
+
+        clock.advance(10_000);
+        let stats = manager.aggregate_stats();
+        assert_eq!(stats.book("BTC/USD").unwrap().trades_per_second, 0.0);
+        assert_eq!(stats.total_trades, 6);
+    }
+
+    #[test]
+    fn test_removed_books_are_not_reported() {
+        let clock = Arc::new(ManualClock::new(0));
+        let mut manager = manager(&clock);
+        manager.remove_book("ETH/USD");
+
+        let stats = manager.aggregate_stats();
+        assert_eq!(stats.books.len(), 1);
+        assert!(stats.book("ETH/USD").is_none());
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.contains("\"symbol\":\"BTC/USD\""));
+    }
+}
@@ -3,6 +3,7 @@
 #[cfg(test)]
 mod tests {
     use crate::OrderBook;
+    use crate::orderbook::config::BookConfig;
     use pricelevel::{OrderId, Side, TimeInForce};
 
     #[test]
@@ -115,6 +116,107 @@ mod tests {
         assert_eq!(impact.slippage_bps, 100.0);
     }
 
+    #[test]
+    fn test_slippage_curve_matches_market_impact() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 105, 15, Side::Sell, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 110, 20, Side::Sell, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 95, 10, Side::Buy, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 90, 10, Side::Buy, TimeInForce::Gtc, None);
+
+        // Unsorted, duplicated, on level boundaries, zero and beyond the liquidity
+        let sizes = [30, 10, 0, 5, 100, 25, 10, 45, 11];
+        for side in [Side::Buy, Side::Sell] {
+            let curve = book.slippage_curve(&sizes, side);
+            assert_eq!(curve.len(), sizes.len());
+            for (size, impact) in sizes.iter().zip(&curve) {
+                assert_eq!(*impact, book.market_impact(*size, side), "size {size}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_slippage_curve_values() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 95, 15, Side::Buy, TimeInForce::Gtc, None);
+
+        let curve = book.slippage_curve(&[10, 20, 40], Side::Sell);
+
+        assert_eq!(curve[0].slippage, 0);
+        assert_eq!(curve[0].levels_consumed, 1);
+        // (100*10 + 95*10) / 20
+        assert_eq!(curve[1].avg_price, 97.5);
+        assert_eq!(curve[1].slippage_bps, 500.0);
+        assert_eq!(curve[2].total_quantity_available, 25);
+        assert!(!curve[2].can_fill(40));
+    }
+
+    #[test]
+    fn test_slippage_curve_empty_book() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        let curve = book.slippage_curve(&[10, 20], Side::Buy);
+        assert_eq!(curve.len(), 2);
+        assert!(curve.iter().all(|impact| impact.levels_consumed == 0));
+        assert!(book.slippage_curve(&[], Side::Buy).is_empty());
+    }
+
+    #[test]
+    fn test_cost_to_move_buy_side() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 101, 15, Side::Sell, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 102, 20, Side::Sell, TimeInForce::Gtc, None);
+
+        // One tick: take the 10 at 100
+        let cost = book.cost_to_move(1, Side::Buy).unwrap();
+        assert_eq!((cost.start_price, cost.target_price), (100, 101));
+        assert_eq!(cost.resulting_price, 101);
+        assert_eq!(cost.quantity, 10);
+        assert_eq!(cost.notional, 1000);
+        assert_eq!(cost.levels_consumed, 1);
+
+        // Two ticks: 100 and 101
+        let cost = book.cost_to_move(2, Side::Buy).unwrap();
+        assert_eq!(cost.quantity, 25);
+        assert_eq!(cost.notional, 1000 + 1515);
+
+        // Three ticks would empty the asks
+        assert_eq!(book.cost_to_move(3, Side::Buy), None);
+        assert_eq!(book.cost_to_move(0, Side::Buy), None);
+    }
+
+    #[test]
+    fn test_cost_to_move_sell_side_with_tick_size() {
+        let book: OrderBook<()> =
+            OrderBook::new_with_config("TEST", BookConfig::default().with_tick_size(5));
+
+        let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 95, 15, Side::Buy, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 80, 20, Side::Buy, TimeInForce::Gtc, None);
+
+        let cost = book.cost_to_move(2, Side::Sell).unwrap();
+        assert_eq!(cost.target_price, 90);
+        // The gap below 95 moves the bid past the target to 80
+        assert_eq!(cost.resulting_price, 80);
+        assert_eq!(cost.quantity, 25);
+        assert_eq!(cost.notional, 1000 + 1425);
+        assert_eq!(cost.levels_consumed, 2);
+    }
+
+    #[test]
+    fn test_cost_to_move_empty_book() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        assert_eq!(book.cost_to_move(1, Side::Buy), None);
+        assert_eq!(book.cost_to_move(1, Side::Sell), None);
+    }
+
     #[test]
     fn test_simulate_market_order_basic() {
         let book: OrderBook<()> = OrderBook::new("TEST");
@@ -153,6 +255,83 @@ mod tests {
         assert_eq!(simulation.levels_count(), 2);
     }
 
+    #[test]
+    fn test_simulate_limit_order_partial_match() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 101, 10, Side::Sell, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 103, 10, Side::Sell, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 95, 10, Side::Buy, TimeInForce::Gtc, None);
+
+        let simulation = book.simulate_limit_order(101, 25, Side::Buy);
+
+        assert_eq!(simulation.execution.fills, vec![(100, 10), (101, 10)]);
+        assert_eq!(simulation.execution.total_filled, 20);
+        assert_eq!(simulation.execution.avg_price, 100.5);
+        assert_eq!(simulation.resting_quantity, 5);
+        assert!(!simulation.is_fully_filled());
+        assert_eq!(simulation.queue_position, Some((0, 0)));
+        assert_eq!(simulation.best_bid, Some((101, 5)));
+        assert_eq!(simulation.best_ask, Some((103, 10)));
+
+        // The book is untouched
+        assert_eq!(book.best_ask(), Some(100));
+        assert_eq!(book.best_bid(), Some(95));
+    }
+
+    #[test]
+    fn test_simulate_limit_order_fully_filled() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 99, 10, Side::Buy, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 105, 10, Side::Sell, TimeInForce::Gtc, None);
+
+        let simulation = book.simulate_limit_order(99, 4, Side::Sell);
+
+        assert_eq!(simulation.execution.fills, vec![(100, 4)]);
+        assert!(simulation.is_fully_filled());
+        assert_eq!(simulation.queue_position, None);
+        assert_eq!(simulation.best_bid, Some((100, 6)));
+        assert_eq!(simulation.best_ask, Some((105, 10)));
+    }
+
+    #[test]
+    fn test_simulate_limit_order_joins_queue() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        let _ = book.add_limit_order(OrderId::new(), 105, 10, Side::Sell, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 105, 15, Side::Sell, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+
+        // Joining the best ask
+        let simulation = book.simulate_limit_order(105, 5, Side::Sell);
+        assert!(simulation.execution.fills.is_empty());
+        assert_eq!(simulation.queue_position, Some((2, 25)));
+        assert_eq!(simulation.best_ask, Some((105, 30)));
+
+        // Behind the best ask, the top does not change
+        let simulation = book.simulate_limit_order(107, 5, Side::Sell);
+        assert_eq!(simulation.queue_position, Some((0, 0)));
+        assert_eq!(simulation.best_ask, Some((105, 25)));
+        assert_eq!(simulation.best_bid, Some((100, 10)));
+    }
+
+    #[test]
+    fn test_simulate_limit_order_empty_book() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        let simulation = book.simulate_limit_order(100, 10, Side::Buy);
+        assert_eq!(simulation.resting_quantity, 10);
+        assert_eq!(simulation.best_bid, Some((100, 10)));
+        assert_eq!(simulation.best_ask, None);
+
+        let simulation = book.simulate_limit_order(100, 0, Side::Buy);
+        assert!(simulation.is_fully_filled());
+        assert_eq!(simulation.best_bid, None);
+    }
+
     #[test]
     fn test_simulate_market_order_empty_book() {
         let book: OrderBook<()> = OrderBook::new("TEST");
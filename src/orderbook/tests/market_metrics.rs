@@ -135,6 +135,58 @@ mod tests {
         assert_eq!(book.vwap(0, Side::Buy), None);
     }
 
+    #[test]
+    fn test_vwap_for_notional_buy_side() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 105, 15, Side::Sell, TimeInForce::Gtc, None);
+
+        // Notional of 1000 is exactly the first level
+        assert_eq!(book.vwap_for_notional(1000, Side::Buy), Some(100.0));
+        assert_eq!(book.quantity_for_notional(1000, Side::Buy), Some(10));
+
+        // 10@100 + 10@105 = 2050 for 20 units
+        assert_eq!(book.vwap_for_notional(2050, Side::Buy), Some(102.5));
+        assert_eq!(book.quantity_for_notional(2050, Side::Buy), Some(20));
+
+        // 2051 needs one more unit at 105: (2050 + 105) / 21
+        assert_eq!(book.quantity_for_notional(2051, Side::Buy), Some(21));
+        assert_eq!(book.vwap_for_notional(2051, Side::Buy), Some(2155.0 / 21.0));
+
+        // The whole book is 2575
+        assert_eq!(book.quantity_for_notional(2575, Side::Buy), Some(25));
+        assert_eq!(book.quantity_for_notional(2576, Side::Buy), None);
+        assert_eq!(book.vwap_for_notional(2576, Side::Buy), None);
+    }
+
+    #[test]
+    fn test_vwap_for_notional_sell_side() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+        let _ = book.add_limit_order(OrderId::new(), 95, 15, Side::Buy, TimeInForce::Gtc, None);
+
+        // 10@100 + 5@95 = 1475 for 15 units
+        assert_eq!(book.quantity_for_notional(1400, Side::Sell), Some(15));
+        assert_eq!(
+            book.vwap_for_notional(1475, Side::Sell),
+            Some(1475.0 / 15.0)
+        );
+        assert_eq!(book.quantity_for_notional(50, Side::Sell), Some(1));
+    }
+
+    #[test]
+    fn test_vwap_for_notional_zero_and_empty() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        assert_eq!(book.vwap_for_notional(1000, Side::Buy), None);
+        assert_eq!(book.quantity_for_notional(1000, Side::Sell), None);
+
+        let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None);
+        assert_eq!(book.vwap_for_notional(0, Side::Buy), None);
+        assert_eq!(book.quantity_for_notional(0, Side::Buy), None);
+    }
+
     #[test]
     fn test_micro_price() {
         let book: OrderBook<()> = OrderBook::new("TEST");
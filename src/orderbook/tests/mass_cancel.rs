@@ -0,0 +1,174 @@
+//! Unit tests for bulk cancellation.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::book_change_event::{BookChangedEvent, PriceLevelChangedEvent};
+    use crate::orderbook::owner::OwnerId;
+    use pricelevel::{OrderId, OrderType, OrderUpdate, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn add(book: &OrderBook<()>, price: u64, side: Side) -> OrderId {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, 10, side, TimeInForce::Gtc, None)
+            .unwrap();
+        id
+    }
+
+    fn add_owned(book: &OrderBook<()>, price: u64, side: Side, owner: u64) -> OrderId {
+        let order = OrderType::Standard {
+            id: OrderId::new(),
+            price,
+            quantity: 10,
+            side,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        };
+        let id = order.id();
+        book.add_order_with_owner(order, OwnerId(owner)).unwrap();
+        id
+    }
+
+    fn populated_book() -> OrderBook<()> {
+        let book = OrderBook::new("TEST");
+        for price in [97, 98, 99] {
+            add(&book, price, Side::Buy);
+        }
+        for price in [101, 102, 103] {
+            add(&book, price, Side::Sell);
+        }
+        book.add_hidden_order(OrderId::new(), 98, 5, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book
+    }
+
+    #[test]
+    fn test_cancel_all_empties_book() {
+        let book = populated_book();
+        add_owned(&book, 96, Side::Buy, 1);
+
+        let cancelled = book.cancel_all().unwrap();
+
+        assert_eq!(cancelled.len(), 8);
+        assert!(book.bids.is_empty());
+        assert!(book.asks.is_empty());
+        assert!(book.hidden_bids.is_empty());
+        assert!(book.order_locations.is_empty());
+        assert!(book.hidden_order_ids.is_empty());
+        assert!(book.order_owners.is_empty());
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.best_ask(), None);
+    }
+
+    #[test]
+    fn test_cancel_side_leaves_other_side() {
+        let book = populated_book();
+
+        let cancelled = book.cancel_side(Side::Buy).unwrap();
+
+        assert_eq!(cancelled.len(), 4);
+        assert!(cancelled.iter().all(|id| book.get_order(*id).is_none()));
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.hidden_quantity(Side::Buy), 0);
+        assert_eq!(book.best_ask(), Some(101));
+        assert_eq!(book.order_locations.len(), 3);
+    }
+
+    #[test]
+    fn test_cancel_range_is_inclusive() {
+        let book = populated_book();
+
+        let cancelled = book.cancel_range(98, 99, Side::Buy).unwrap();
+
+        assert_eq!(cancelled.len(), 3);
+        assert_eq!(book.best_bid(), Some(97));
+        assert_eq!(book.hidden_quantity(Side::Buy), 0);
+        assert_eq!(book.order_locations.len(), 4);
+        assert!(book.cancel_range(110, 100, Side::Sell).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cancel_by_owner_only_cancels_owned_orders() {
+        let book = populated_book();
+        let first = add_owned(&book, 96, Side::Buy, 1);
+        let second = add_owned(&book, 104, Side::Sell, 1);
+        let other = add_owned(&book, 104, Side::Sell, 2);
+
+        let mut cancelled = book.cancel_by_owner(OwnerId(1)).unwrap();
+        cancelled.sort_by_key(|id| id.to_string());
+        let mut expected = vec![first, second];
+        expected.sort_by_key(|id| id.to_string());
+
+        assert_eq!(cancelled, expected);
+        assert!(book.get_order(other).is_some());
+        assert_eq!(book.order_owner(first), None);
+        assert_eq!(book.order_owner(other), Some(OwnerId(2)));
+        assert!(book.cancel_by_owner(OwnerId(1)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_owner_survives_price_update_and_clears_on_fill() {
+        let book = OrderBook::<()>::new("TEST");
+        let id = add_owned(&book, 100, Side::Sell, 3);
+
+        book.update_order(OrderUpdate::UpdatePrice {
+            order_id: id,
+            new_price: 101,
+        })
+        .unwrap();
+        assert_eq!(book.order_owner(id), Some(OwnerId(3)));
+
+        book.match_order(OrderId::new(), Side::Buy, 10, None)
+            .unwrap();
+        assert_eq!(book.order_owner(id), None);
+    }
+
+    #[test]
+    fn test_mass_cancel_emits_single_aggregated_event() {
+        let mut book = populated_book();
+        let level_events: Arc<Mutex<Vec<PriceLevelChangedEvent>>> =
+            Arc::new(Mutex::new(Vec::new()));
+        let book_events: Arc<Mutex<Vec<BookChangedEvent>>> = Arc::new(Mutex::new(Vec::new()));
+        let level_sink = Arc::clone(&level_events);
+        let book_sink = Arc::clone(&book_events);
+        book.set_price_level_listener(Arc::new(move |event| {
+            level_sink.lock().unwrap().push(event);
+        }));
+        book.set_book_changed_listener(Arc::new(move |event| {
+            book_sink.lock().unwrap().push(event);
+        }));
+
+        book.cancel_side(Side::Sell).unwrap();
+
+        let book_events = book_events.lock().unwrap();
+        assert_eq!(book_events.len(), 1);
+        let mut prices: Vec<u64> = book_events[0].changes.iter().map(|c| c.price).collect();
+        prices.sort_unstable();
+        assert_eq!(prices, vec![101, 102, 103]);
+        assert!(book_events[0].changes.iter().all(|c| c.quantity == 0));
+        assert_eq!(level_events.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_cancel_all_keeps_concurrent_orders_tracked() {
+        let book = Arc::new(OrderBook::<()>::new("TEST"));
+        let adder = {
+            let book = Arc::clone(&book);
+            std::thread::spawn(move || {
+                for i in 0..2_000 {
+                    add_owned(&book, 90 + i % 10, Side::Buy, 7);
+                }
+            })
+        };
+        while !adder.is_finished() {
+            book.cancel_all().unwrap();
+        }
+        adder.join().unwrap();
+
+        for order in book.get_all_orders() {
+            assert!(book.get_order(order.id()).is_some());
+            assert_eq!(book.order_owner(order.id()), Some(OwnerId(7)));
+        }
+    }
+}
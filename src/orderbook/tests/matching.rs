@@ -184,4 +184,167 @@ mod tests {
         let matched_quantity = book.peek_match(Side::Buy, 10, None);
         assert_eq!(matched_quantity, 0);
     }
+
+    // Helper to build a market-to-limit order.
+    fn market_to_limit(side: Side, quantity: u64, time_in_force: TimeInForce) -> OrderType<()> {
+        OrderType::MarketToLimit {
+            id: OrderId::new(),
+            price: 0,
+            quantity,
+            side,
+            timestamp: 0,
+            time_in_force,
+            extra_fields: (),
+        }
+    }
+
+    #[test]
+    fn test_market_to_limit_fully_filled_at_best_price() {
+        let book = setup_book();
+        add_limit_order(&book, Side::Sell, 100, 10);
+        add_limit_order(&book, Side::Sell, 101, 10);
+
+        let order = market_to_limit(Side::Buy, 10, TimeInForce::Gtc);
+        let order_id = order.id();
+        book.add_order(order).unwrap();
+
+        assert_eq!(book.last_trade_price(), Some(100));
+        assert!(book.get_order(order_id).is_none());
+        assert_eq!(book.best_ask(), Some(101));
+        assert_eq!(book.best_bid(), None);
+    }
+
+    #[test]
+    fn test_market_to_limit_remainder_rests_at_execution_price() {
+        let book = setup_book();
+        add_limit_order(&book, Side::Sell, 100, 4);
+        add_limit_order(&book, Side::Sell, 101, 10);
+
+        let order = market_to_limit(Side::Buy, 10, TimeInForce::Gtc);
+        let order_id = order.id();
+        let rested = book.add_order(order).unwrap();
+
+        // Executed only at the best level, never walking to 101
+        assert_eq!(book.last_trade_price(), Some(100));
+        assert_eq!(book.best_ask(), Some(101));
+        assert_eq!(book.liquidity_in_range(101, 101, Side::Sell), 10);
+
+        // Remainder rests as a standard limit order at the execution price
+        assert!(matches!(*rested, OrderType::Standard { .. }));
+        let resting = book.get_order(order_id).unwrap();
+        assert_eq!(resting.price(), 100);
+        assert_eq!(resting.visible_quantity(), 6);
+        assert_eq!(book.best_bid(), Some(100));
+    }
+
+    #[test]
+    fn test_market_to_limit_sell_side_partial_fill() {
+        let book = setup_book();
+        add_limit_order(&book, Side::Buy, 99, 3);
+        add_limit_order(&book, Side::Buy, 98, 20);
+
+        let order = market_to_limit(Side::Sell, 5, TimeInForce::Gtc);
+        let order_id = order.id();
+        book.add_order(order).unwrap();
+
+        let resting = book.get_order(order_id).unwrap();
+        assert_eq!(resting.side(), Side::Sell);
+        assert_eq!(resting.price(), 99);
+        assert_eq!(resting.visible_quantity(), 2);
+        assert_eq!(book.best_bid(), Some(98));
+        assert_eq!(book.best_ask(), Some(99));
+    }
+
+    #[test]
+    fn test_market_to_limit_ioc_remainder_is_cancelled() {
+        let book = setup_book();
+        add_limit_order(&book, Side::Sell, 100, 4);
+        add_limit_order(&book, Side::Sell, 101, 10);
+
+        let order = market_to_limit(Side::Buy, 10, TimeInForce::Ioc);
+        let order_id = order.id();
+        let result = book.add_order(order);
+
+        assert!(matches!(
+            result,
+            Err(OrderBookError::InsufficientLiquidity { .. })
+        ));
+        assert!(book.get_order(order_id).is_none());
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.best_ask(), Some(101));
+    }
+
+    #[test]
+    fn test_market_to_limit_fok_only_considers_best_level() {
+        let book = setup_book();
+        add_limit_order(&book, Side::Sell, 100, 4);
+        add_limit_order(&book, Side::Sell, 101, 10);
+
+        let result = book.add_order(market_to_limit(Side::Buy, 10, TimeInForce::Fok));
+
+        assert!(matches!(
+            result,
+            Err(OrderBookError::InsufficientLiquidity { available: 4, .. })
+        ));
+        assert_eq!(book.liquidity_in_range(100, 101, Side::Sell), 14);
+    }
+
+    #[test]
+    fn test_market_to_limit_anchors_on_hidden_levels() {
+        let book = setup_book();
+        book.add_hidden_order(OrderId::new(), 99, 4, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        add_limit_order(&book, Side::Sell, 100, 10);
+
+        let order = market_to_limit(Side::Buy, 10, TimeInForce::Gtc);
+        let order_id = order.id();
+        book.add_order(order).unwrap();
+
+        // Executed only against the hidden level, the remainder rests at 99
+        assert_eq!(book.last_trade_price(), Some(99));
+        assert_eq!(book.liquidity_in_range(100, 100, Side::Sell), 10);
+        let resting = book.get_order(order_id).unwrap();
+        assert_eq!(resting.price(), 99);
+        assert_eq!(resting.visible_quantity(), 6);
+        assert_eq!(book.best_bid(), Some(99));
+    }
+
+    #[test]
+    fn test_market_to_limit_without_opposite_liquidity_uses_own_price() {
+        let book = setup_book();
+
+        let order = OrderType::MarketToLimit {
+            id: OrderId::new(),
+            price: 95,
+            quantity: 10,
+            side: Side::Buy,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        };
+        let order_id = order.id();
+        book.add_order(order).unwrap();
+
+        let resting = book.get_order(order_id).unwrap();
+        assert_eq!(resting.price(), 95);
+        assert_eq!(book.best_bid(), Some(95));
+    }
+
+    #[test]
+    fn test_convert_market_to_limit_leaves_other_types_unchanged() {
+        let book = setup_book();
+        add_limit_order(&book, Side::Sell, 100, 4);
+
+        let order = OrderType::Standard {
+            id: OrderId::new(),
+            price: 90,
+            quantity: 1,
+            side: Side::Buy,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        };
+
+        assert_eq!(book.convert_market_to_limit(order), order);
+    }
 }
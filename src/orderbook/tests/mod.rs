@@ -1,17 +1,66 @@
+mod async_manager;
+mod auction;
+mod bbo;
 mod book;
+mod circuit_breaker;
+mod clock;
+mod config;
+mod consolidated;
+mod day_expiry;
+mod delta;
 mod depth_analysis;
+mod depth_limit;
 mod enriched_snapshot_tests;
 mod error;
+mod extra_fields;
+mod fair_price;
+mod fees;
+mod fill_probability;
+mod full_state;
+mod heatmap;
+mod hidden_orders;
+mod integrity;
 mod iterator_tests;
+mod journal;
+mod kill_switch;
+mod l3_feed;
+mod ladder;
+mod manager;
+mod manager_snapshot;
+mod manager_stats;
 mod market_impact_tests;
 mod market_metrics;
+mod mass_cancel;
 mod matching;
 mod modifications;
 mod operations;
 mod order;
+mod order_events;
+mod order_flow;
 mod order_placement_tests;
+mod owner;
+mod positions;
+mod queue_position;
+mod quotes;
+mod rate_limit;
+mod replay;
+mod resiliency;
+mod risk;
+mod sandbox;
+mod scheduler;
 mod serialize_tests;
+mod session;
+mod sharded_manager;
 mod snapshot;
+mod spread_book;
 mod statistics_tests;
+mod stream;
+mod subscription;
+mod surveillance;
+mod symbol_config;
 mod time_in_force;
+mod time_weighted;
+mod trade;
+mod trade_tape;
 mod uuid;
+mod volume_profile;
@@ -764,3 +764,65 @@ mod tests {
         }
     }
 }
+
+#[cfg(test)]
+mod test_compaction {
+    use crate::OrderBook;
+    use pricelevel::{OrderId, PriceLevel, Side, TimeInForce};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_cancel_removes_emptied_level() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        let id = OrderId::new_uuid();
+        book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        book.cancel_order(id).unwrap();
+
+        assert!(book.bids.is_empty());
+        assert_eq!(book.compact(), 0);
+    }
+
+    #[test]
+    fn test_cancel_prunes_lingering_empty_level() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        let id = OrderId::new_uuid();
+        book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        // Simulate a level left empty by a concurrent operation
+        book.bids
+            .get(&100)
+            .unwrap()
+            .value()
+            .update_order(pricelevel::OrderUpdate::Cancel { order_id: id })
+            .unwrap();
+
+        assert!(book.cancel_order(id).unwrap().is_none());
+        assert!(book.bids.is_empty());
+    }
+
+    #[test]
+    fn test_compact_removes_only_empty_levels() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        book.add_limit_order(
+            OrderId::new_uuid(),
+            100,
+            10,
+            Side::Buy,
+            TimeInForce::Gtc,
+            None,
+        )
+        .unwrap();
+        book.bids.insert(95, Arc::new(PriceLevel::new(95)));
+        book.asks.insert(105, Arc::new(PriceLevel::new(105)));
+        book.hidden_asks.insert(106, Arc::new(PriceLevel::new(106)));
+
+        assert_eq!(book.compact(), 3);
+        assert_eq!(book.bids.len(), 1);
+        assert!(book.asks.is_empty());
+        assert!(book.hidden_asks.is_empty());
+        assert!(book.verify_integrity().is_consistent());
+        assert_eq!(book.compact(), 0);
+    }
+}
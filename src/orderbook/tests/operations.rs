@@ -456,6 +456,62 @@ mod tests {
             "Sell order quantity should be unchanged"
         );
     }
+
+    #[test]
+    fn test_duplicate_order_id_is_rejected() {
+        let order_book = create_test_order_book();
+        let id = new_order_id();
+        order_book
+            .add_limit_order(id, 1000, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        let result = order_book.add_limit_order(id, 1010, 5, Side::Sell, TimeInForce::Gtc, None);
+
+        assert!(matches!(
+            result,
+            Err(OrderBookError::DuplicateOrderId { order_id }) if order_id == id
+        ));
+        let resting = order_book.get_order(id).unwrap();
+        assert_eq!(resting.price(), 1000);
+        assert_eq!(resting.visible_quantity(), 10);
+        assert_eq!(order_book.best_ask(), None);
+    }
+
+    #[test]
+    fn test_duplicate_order_id_allowed_after_order_leaves_book() {
+        let order_book = create_test_order_book();
+        let id = new_order_id();
+        order_book
+            .add_limit_order(id, 1000, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        order_book.cancel_order(id).unwrap();
+
+        assert!(
+            order_book
+                .add_limit_order(id, 1000, 10, Side::Buy, TimeInForce::Gtc, None)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_replace_on_duplicate_mode() {
+        let order_book = create_test_order_book();
+        order_book.set_replace_on_duplicate(true);
+        let id = new_order_id();
+        order_book
+            .add_limit_order(id, 1000, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        order_book
+            .add_limit_order(id, 990, 4, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        let resting = order_book.get_order(id).unwrap();
+        assert_eq!(resting.price(), 990);
+        assert_eq!(resting.visible_quantity(), 4);
+        assert_eq!(order_book.best_bid(), Some(990));
+        assert_eq!(order_book.get_all_orders().len(), 1);
+    }
 }
 
 #[cfg(test)]
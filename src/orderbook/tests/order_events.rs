@@ -0,0 +1,234 @@
+//! Unit tests for order lifecycle events.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::order_event::OrderEvent;
+    use pricelevel::{OrderId, OrderUpdate, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn recording_book() -> (OrderBook<()>, Arc<Mutex<Vec<OrderEvent>>>) {
+        let mut book = OrderBook::new("TEST");
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        book.set_order_event_listener(Arc::new(move |event: &OrderEvent| {
+            sink.lock().unwrap().push(event.clone());
+        }));
+        (book, events)
+    }
+
+    fn take(events: &Arc<Mutex<Vec<OrderEvent>>>) -> Vec<OrderEvent> {
+        std::mem::take(&mut *events.lock().unwrap())
+    }
+
+    #[test]
+    fn test_resting_order_is_accepted_then_rested() {
+        let (book, events) = recording_book();
+        let id = OrderId::new();
+        book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        assert_eq!(
+            take(&events),
+            vec![
+                OrderEvent::Accepted {
+                    order_id: id,
+                    side: Side::Buy,
+                    price: 100,
+                    quantity: 10,
+                },
+                OrderEvent::Rested {
+                    order_id: id,
+                    side: Side::Buy,
+                    price: 100,
+                    quantity: 10,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fills_are_reported_to_maker_and_taker() {
+        let (book, events) = recording_book();
+        let maker = OrderId::new();
+        book.add_limit_order(maker, 100, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        take(&events);
+
+        let taker = OrderId::new();
+        book.add_limit_order(taker, 100, 4, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        assert_eq!(
+            take(&events),
+            vec![
+                OrderEvent::Accepted {
+                    order_id: taker,
+                    side: Side::Buy,
+                    price: 100,
+                    quantity: 4,
+                },
+                OrderEvent::PartiallyFilled {
+                    order_id: maker,
+                    price: 100,
+                    quantity: 4,
+                },
+                OrderEvent::Filled {
+                    order_id: taker,
+                    price: 100,
+                    quantity: 4,
+                },
+            ]
+        );
+
+        book.match_order(OrderId::new(), Side::Buy, 6, None)
+            .unwrap();
+        assert!(take(&events).contains(&OrderEvent::Filled {
+            order_id: maker,
+            price: 100,
+            quantity: 6,
+        }));
+    }
+
+    #[test]
+    fn test_partial_taker_fill_rests_remainder() {
+        let (book, events) = recording_book();
+        book.add_limit_order(OrderId::new(), 100, 3, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        take(&events);
+
+        let taker = OrderId::new();
+        book.add_limit_order(taker, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        let taker_events: Vec<OrderEvent> = take(&events)
+            .into_iter()
+            .filter(|event| event.order_id() == taker)
+            .collect();
+        assert_eq!(taker_events.len(), 3);
+        assert!(matches!(
+            taker_events[1],
+            OrderEvent::PartiallyFilled { quantity: 3, .. }
+        ));
+        assert!(matches!(
+            taker_events[2],
+            OrderEvent::Rested { quantity: 7, .. }
+        ));
+    }
+
+    #[test]
+    fn test_cancel_and_ioc_remainder_report_cancelled() {
+        let (book, events) = recording_book();
+        let id = OrderId::new();
+        book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        take(&events);
+
+        book.cancel_order(id).unwrap();
+        assert_eq!(
+            take(&events),
+            vec![OrderEvent::Cancelled {
+                order_id: id,
+                quantity: 10,
+            }]
+        );
+
+        let ioc = OrderId::new();
+        let _ = book.add_limit_order(ioc, 100, 5, Side::Sell, TimeInForce::Ioc, None);
+        assert_eq!(
+            take(&events).last(),
+            Some(&OrderEvent::Cancelled {
+                order_id: ioc,
+                quantity: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn test_rejected_and_expired_orders() {
+        let (book, events) = recording_book();
+        book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        take(&events);
+
+        let post_only = OrderId::new();
+        assert!(
+            book.add_post_only_order(post_only, 100, 5, Side::Buy, TimeInForce::Gtc, None)
+                .is_err()
+        );
+        let rejected = take(&events);
+        assert_eq!(rejected.len(), 1);
+        assert!(
+            matches!(rejected[0], OrderEvent::Rejected { order_id, .. } if order_id == post_only)
+        );
+
+        let expired = OrderId::new();
+        assert!(
+            book.add_limit_order(expired, 90, 5, Side::Buy, TimeInForce::Gtd(1), None)
+                .is_err()
+        );
+        assert_eq!(
+            take(&events),
+            vec![OrderEvent::Expired { order_id: expired }]
+        );
+    }
+
+    #[test]
+    fn test_update_reports_modified_without_cancel() {
+        let (book, events) = recording_book();
+        let id = OrderId::new();
+        book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        take(&events);
+
+        book.update_order(OrderUpdate::UpdatePrice {
+            order_id: id,
+            new_price: 101,
+        })
+        .unwrap();
+        book.update_order(OrderUpdate::UpdateQuantity {
+            order_id: id,
+            new_quantity: 4,
+        })
+        .unwrap();
+
+        assert_eq!(
+            take(&events),
+            vec![
+                OrderEvent::Modified {
+                    order_id: id,
+                    price: 101,
+                    quantity: 10,
+                },
+                OrderEvent::Modified {
+                    order_id: id,
+                    price: 101,
+                    quantity: 4,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mass_cancel_reports_each_order() {
+        let (book, events) = recording_book();
+        let first = OrderId::new();
+        let second = OrderId::new();
+        book.add_limit_order(first, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(second, 99, 5, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        take(&events);
+
+        book.cancel_all().unwrap();
+
+        let cancelled = take(&events);
+        assert_eq!(cancelled.len(), 2);
+        assert!(cancelled.contains(&OrderEvent::Cancelled {
+            order_id: first,
+            quantity: 10,
+        }));
+        assert!(cancelled.contains(&OrderEvent::Cancelled {
+            order_id: second,
+            quantity: 5,
+        }));
+    }
+}
@@ -0,0 +1,98 @@
+//! Unit tests for the order flow imbalance calculator.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::book_change_event::BboChangedEvent;
+    use crate::orderbook::order_flow::OrderFlowImbalance;
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn bbo(bid: Option<(u64, u64)>, ask: Option<(u64, u64)>) -> BboChangedEvent {
+        BboChangedEvent {
+            bid_price: bid.map(|(price, _)| price),
+            bid_quantity: bid.map_or(0, |(_, quantity)| quantity),
+            ask_price: ask.map(|(price, _)| price),
+            ask_quantity: ask.map_or(0, |(_, quantity)| quantity),
+        }
+    }
+
+    #[test]
+    fn test_contribution_of_price_moves() {
+        let start = bbo(Some((100, 10)), Some((102, 20)));
+
+        // Bid improves: all new quantity counts
+        let better_bid = bbo(Some((101, 3)), Some((102, 20)));
+        assert_eq!(OrderFlowImbalance::contribution(&start, &better_bid), 3);
+
+        // Bid drops: the previous quantity is removed
+        let worse_bid = bbo(Some((99, 50)), Some((102, 20)));
+        assert_eq!(OrderFlowImbalance::contribution(&start, &worse_bid), -10);
+
+        // Ask improves: new selling quantity is selling pressure
+        let better_ask = bbo(Some((100, 10)), Some((101, 7)));
+        assert_eq!(OrderFlowImbalance::contribution(&start, &better_ask), -7);
+
+        // Ask lifted: the previous quantity is removed
+        let worse_ask = bbo(Some((100, 10)), Some((103, 1)));
+        assert_eq!(OrderFlowImbalance::contribution(&start, &worse_ask), 20);
+    }
+
+    #[test]
+    fn test_contribution_with_empty_sides() {
+        let empty = bbo(None, None);
+        let quoted = bbo(Some((100, 10)), Some((101, 4)));
+        assert_eq!(OrderFlowImbalance::contribution(&empty, &quoted), 6);
+        assert_eq!(OrderFlowImbalance::contribution(&quoted, &empty), -6);
+        assert_eq!(OrderFlowImbalance::contribution(&empty, &empty), 0);
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        let mut ofi = OrderFlowImbalance::new(1_000, bbo(Some((100, 10)), None));
+        assert_eq!(ofi.observe(1_000, bbo(Some((100, 15)), None)), 5);
+        assert_eq!(ofi.observe(1_500, bbo(Some((100, 12)), None)), -3);
+        assert_eq!(ofi.observe(1_600, bbo(Some((100, 12)), None)), 0);
+        assert_eq!(ofi.value(1_600), 2);
+        assert_eq!(ofi.samples(1_600).len(), 2);
+
+        assert_eq!(ofi.observe(2_200, bbo(Some((101, 1)), None)), 1);
+        assert_eq!(ofi.value(2_200), -2);
+        assert_eq!(ofi.samples(2_200).len(), 2);
+        assert_eq!(ofi.samples(2_600).len(), 1);
+        assert_eq!(ofi.cumulative(), 3);
+    }
+
+    #[test]
+    fn test_book_feeds_order_flow_imbalance() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        assert_eq!(book.order_flow_imbalance(), None);
+        let clock = Arc::new(ManualClock::new(10_000));
+        book.set_clock(clock.clone());
+        book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        let ask = OrderId::new();
+        book.add_limit_order(ask, 101, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.enable_order_flow_imbalance(1_000);
+        assert_eq!(book.order_flow_imbalance(), Some(0));
+
+        // A buy lifting the whole ask removes selling quantity
+        book.submit_market_order(OrderId::new(), 10, Side::Buy)
+            .unwrap();
+        assert_eq!(book.order_flow_imbalance(), Some(10));
+
+        clock.advance(500);
+        book.add_limit_order(OrderId::new(), 102, 5, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        assert_eq!(book.order_flow_imbalance(), Some(5));
+
+        clock.advance(600);
+        assert_eq!(book.order_flow_imbalance(), Some(-5));
+        assert_eq!(book.order_flow_imbalance_series().unwrap().len(), 1);
+
+        book.disable_order_flow_imbalance();
+        assert!(book.order_flow_imbalance_series().is_none());
+    }
+}
@@ -0,0 +1,90 @@
+//! Unit tests for the per-owner order index.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::owner::OwnerId;
+    use pricelevel::{OrderId, OrderType, OrderUpdate, Side, TimeInForce};
+
+    fn add_owned(
+        book: &OrderBook<()>,
+        price: u64,
+        quantity: u64,
+        side: Side,
+        owner: u64,
+    ) -> OrderId {
+        let order = OrderType::Standard {
+            id: OrderId::new(),
+            price,
+            quantity,
+            side,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        };
+        let id = order.id();
+        book.add_order_with_owner(order, OwnerId(owner)).unwrap();
+        id
+    }
+
+    #[test]
+    fn test_get_orders_by_owner() {
+        let book = OrderBook::new("TEST");
+        let first = add_owned(&book, 99, 10, Side::Buy, 1);
+        let second = add_owned(&book, 101, 5, Side::Sell, 1);
+        add_owned(&book, 98, 10, Side::Buy, 2);
+        book.add_limit_order(OrderId::new(), 97, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        let owned = book.get_orders_by_owner(OwnerId(1));
+        assert_eq!(owned.len(), 2);
+        assert!(owned.iter().any(|order| order.id() == first));
+        assert!(owned.iter().any(|order| order.id() == second));
+        assert!(book.get_orders_by_owner(OwnerId(9)).is_empty());
+    }
+
+    #[test]
+    fn test_open_quantity_by_owner_per_side() {
+        let book = OrderBook::new("TEST");
+        add_owned(&book, 99, 10, Side::Buy, 1);
+        add_owned(&book, 98, 15, Side::Buy, 1);
+        add_owned(&book, 101, 5, Side::Sell, 1);
+        add_owned(&book, 97, 40, Side::Buy, 2);
+
+        assert_eq!(book.open_quantity_by_owner(OwnerId(1), Side::Buy), 25);
+        assert_eq!(book.open_quantity_by_owner(OwnerId(1), Side::Sell), 5);
+        assert_eq!(book.open_quantity_by_owner(OwnerId(2), Side::Sell), 0);
+    }
+
+    #[test]
+    fn test_index_follows_fills_updates_and_cancels() {
+        let book = OrderBook::new("TEST");
+        let id = add_owned(&book, 99, 10, Side::Buy, 1);
+
+        book.match_order(OrderId::new(), Side::Sell, 4, None)
+            .unwrap();
+        assert_eq!(book.open_quantity_by_owner(OwnerId(1), Side::Buy), 6);
+
+        book.update_order(OrderUpdate::UpdatePrice {
+            order_id: id,
+            new_price: 95,
+        })
+        .unwrap();
+        assert_eq!(book.get_orders_by_owner(OwnerId(1))[0].price(), 95);
+
+        book.cancel_order(id).unwrap();
+        assert!(book.get_orders_by_owner(OwnerId(1)).is_empty());
+        assert!(book.owner_orders.is_empty());
+    }
+
+    #[test]
+    fn test_index_cleared_when_fully_filled() {
+        let book = OrderBook::new("TEST");
+        add_owned(&book, 101, 10, Side::Sell, 1);
+
+        book.match_order(OrderId::new(), Side::Buy, 10, None)
+            .unwrap();
+        assert_eq!(book.open_quantity_by_owner(OwnerId(1), Side::Sell), 0);
+        assert!(book.owner_orders.is_empty());
+    }
+}
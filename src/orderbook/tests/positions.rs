@@ -0,0 +1,202 @@
+//! Unit tests for owner-attributed trades and position tracking.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::manager::{BookManager, BookManagerStd};
+    use crate::orderbook::owner::OwnerId;
+    use crate::orderbook::positions::{Position, PositionTracker};
+    use crate::orderbook::session::SessionState;
+    use crate::orderbook::trade::TradeResult;
+    use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn order(side: Side, price: u64, quantity: u64) -> OrderType<()> {
+        OrderType::Standard {
+            id: OrderId::new(),
+            price,
+            quantity,
+            side,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        }
+    }
+
+    fn tracked_book() -> (OrderBook<()>, Arc<PositionTracker>) {
+        let tracker = Arc::new(PositionTracker::new());
+        let book = OrderBook::<()>::new("BTC/USD");
+        let sink = Arc::clone(&tracker);
+        book.subscribe_trade_listener(Arc::new(move |trade| sink.record_trade(trade)));
+        (book, tracker)
+    }
+
+    #[test]
+    fn test_position_averages_and_realizes() {
+        let mut position = Position::new(OwnerId(1), "BTC/USD");
+        position.apply_fill(Side::Buy, 100, 10);
+        position.apply_fill(Side::Buy, 110, 10);
+        assert_eq!(position.net_quantity, 20);
+        assert_eq!(position.average_price, 105.0);
+        assert_eq!(position.exposure(), 2_100.0);
+        assert_eq!(position.unrealized_pnl(110), 100.0);
+
+        position.apply_fill(Side::Sell, 120, 5);
+        assert_eq!(position.net_quantity, 15);
+        assert_eq!(position.average_price, 105.0);
+        assert_eq!(position.realized_pnl, 75.0);
+
+        // Selling through flat opens a short at the fill price
+        position.apply_fill(Side::Sell, 90, 20);
+        assert_eq!(position.net_quantity, -5);
+        assert_eq!(position.average_price, 90.0);
+        assert_eq!(position.realized_pnl, 75.0 - 225.0);
+        assert_eq!(position.unrealized_pnl(80), 50.0);
+
+        position.apply_fill(Side::Buy, 80, 5);
+        assert!(position.is_flat());
+        assert_eq!(position.average_price, 0.0);
+        assert_eq!(position.realized_pnl, -100.0);
+        assert_eq!(position.bought_quantity, 25);
+        assert_eq!(position.sold_quantity, 25);
+        assert_eq!(position.fill_count, 5);
+    }
+
+    #[test]
+    fn test_trades_carry_owner_attribution() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        let trades: Arc<Mutex<Vec<TradeResult>>> = Arc::default();
+        let sink = trades.clone();
+        book.subscribe_trade_listener(Arc::new(move |trade| {
+            sink.lock().unwrap().push(trade.clone());
+        }));
+        let maker_a = book
+            .add_order_with_owner(order(Side::Sell, 100, 5), OwnerId(1))
+            .unwrap()
+            .id();
+        let anonymous = OrderId::new();
+        book.add_limit_order(anonymous, 100, 5, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_order_with_owner(order(Side::Buy, 100, 10), OwnerId(2))
+            .unwrap();
+
+        let trades = trades.lock().unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].taker_owner, Some(OwnerId(2)));
+        assert_eq!(trades[0].maker_owners, vec![(maker_a, OwnerId(1))]);
+        assert_eq!(trades[0].maker_owner(anonymous), None);
+    }
+
+    #[test]
+    fn test_tracker_follows_makers_and_takers() {
+        let (book, tracker) = tracked_book();
+        book.add_order_with_owner(order(Side::Sell, 100, 5), OwnerId(1))
+            .unwrap();
+        book.add_order_with_owner(order(Side::Sell, 101, 5), OwnerId(3))
+            .unwrap();
+        book.add_order_with_owner(order(Side::Buy, 101, 8), OwnerId(2))
+            .unwrap();
+        book.add_order_with_owner(order(Side::Sell, 105, 8), OwnerId(2))
+            .unwrap();
+        book.submit_market_order(OrderId::new(), 10, Side::Buy)
+            .unwrap();
+
+        let taker = tracker.position(OwnerId(2), "BTC/USD").unwrap();
+        assert!(taker.is_flat());
+        assert_eq!(taker.bought_quantity, 8);
+        assert_eq!(taker.realized_pnl, 8.0 * 105.0 - (500.0 + 303.0));
+
+        assert_eq!(
+            tracker
+                .position(OwnerId(1), "BTC/USD")
+                .unwrap()
+                .net_quantity,
+            -5
+        );
+        let split = tracker.position(OwnerId(3), "BTC/USD").unwrap();
+        assert_eq!(split.net_quantity, -5);
+        assert_eq!(split.average_price, 101.0);
+        assert_eq!(split.fill_count, 2);
+        assert_eq!(tracker.gross_exposure(OwnerId(3)), 505.0);
+        assert_eq!(tracker.all_positions().len(), 3);
+    }
+
+    #[test]
+    fn test_auction_trades_are_attributed() {
+        let (book, tracker) = tracked_book();
+        book.set_session_state(SessionState::PreOpen).unwrap();
+        book.add_order_with_owner(order(Side::Buy, 101, 6), OwnerId(1))
+            .unwrap();
+        book.add_order_with_owner(order(Side::Sell, 100, 4), OwnerId(2))
+            .unwrap();
+        book.add_order_with_owner(order(Side::Sell, 100, 4), OwnerId(3))
+            .unwrap();
+        let price = book.open_with_auction().unwrap().unwrap().uncross.price;
+
+        let buyer = tracker.position(OwnerId(1), "BTC/USD").unwrap();
+        assert_eq!(buyer.net_quantity, 6);
+        assert_eq!(buyer.average_price, price as f64);
+        assert_eq!(
+            tracker
+                .position(OwnerId(2), "BTC/USD")
+                .unwrap()
+                .net_quantity,
+            -4
+        );
+        assert_eq!(
+            tracker
+                .position(OwnerId(3), "BTC/USD")
+                .unwrap()
+                .net_quantity,
+            -2
+        );
+    }
+
+    #[test]
+    fn test_manager_positions_span_symbols() {
+        let mut manager = BookManagerStd::<()>::new().with_position_tracking();
+        manager.add_book("BTC/USD");
+        manager.add_book("ETH/USD");
+        for (symbol, price) in [("BTC/USD", 100), ("ETH/USD", 10)] {
+            let book = manager.get_book(symbol).unwrap();
+            book.add_order_with_owner(order(Side::Sell, price, 2), OwnerId(1))
+                .unwrap();
+            book.add_order_with_owner(order(Side::Buy, price, 2), OwnerId(2))
+                .unwrap();
+        }
+
+        let positions = manager.positions().unwrap().positions_of(OwnerId(2));
+        let symbols: Vec<&str> = positions.iter().map(|p| p.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["BTC/USD", "ETH/USD"]);
+        assert_eq!(
+            manager.positions().unwrap().gross_exposure(OwnerId(1)),
+            220.0
+        );
+        assert_eq!(
+            manager
+                .position(OwnerId(1), "ETH/USD")
+                .unwrap()
+                .net_quantity,
+            -2
+        );
+        assert!(manager.position(OwnerId(9), "ETH/USD").is_none());
+
+        let untracked = BookManagerStd::<()>::new();
+        assert!(untracked.positions().is_none());
+    }
+
+    #[test]
+    fn test_anonymous_trades_are_ignored() {
+        let (book, tracker) = tracked_book();
+        book.add_limit_order(OrderId::new(), 100, 5, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.submit_market_order(OrderId::new(), 5, Side::Buy)
+            .unwrap();
+        assert!(tracker.all_positions().is_empty());
+
+        tracker.record_fill(OwnerId(1), "BTC/USD", Side::Buy, 100, 1);
+        assert_eq!(tracker.realized_pnl(OwnerId(1)), 0.0);
+        tracker.clear();
+        assert!(tracker.position(OwnerId(1), "BTC/USD").is_none());
+    }
+}
@@ -0,0 +1,114 @@
+//! Unit tests for the queue position estimate of resting orders.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn setup_book() -> (OrderBook<()>, Arc<ManualClock>) {
+        let mut book = OrderBook::new("TEST");
+        let clock = Arc::new(ManualClock::new(1_000));
+        book.set_clock(clock.clone());
+        (book, clock)
+    }
+
+    fn add(book: &OrderBook<()>, clock: &ManualClock, price: u64, quantity: u64) -> OrderId {
+        clock.advance(1);
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        id
+    }
+
+    #[test]
+    fn test_counts_only_orders_placed_before() {
+        let (book, clock) = setup_book();
+        let first = add(&book, &clock, 100, 10);
+        let second = add(&book, &clock, 100, 20);
+        let third = add(&book, &clock, 100, 30);
+        add(&book, &clock, 101, 40);
+
+        assert_eq!(book.estimated_queue_position(first), Some((0, 0)));
+        assert_eq!(book.estimated_queue_position(second), Some((1, 10)));
+        assert_eq!(book.estimated_queue_position(third), Some((2, 30)));
+        assert_eq!(book.queue_ahead_at_price(100, Side::Buy), 3);
+    }
+
+    #[test]
+    fn test_position_improves_as_orders_ahead_leave() {
+        let (book, clock) = setup_book();
+        let first = add(&book, &clock, 100, 10);
+        let second = add(&book, &clock, 100, 20);
+        let mine = add(&book, &clock, 100, 5);
+
+        book.cancel_order(second).unwrap();
+        assert_eq!(book.estimated_queue_position(mine), Some((1, 10)));
+
+        book.match_market_order(OrderId::new(), 10, Side::Sell)
+            .unwrap();
+        assert!(book.get_order(first).is_none());
+        assert_eq!(book.estimated_queue_position(mine), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_orders_sharing_a_timestamp_count_as_ahead() {
+        let (book, _clock) = setup_book();
+        let first = OrderId::new();
+        let second = OrderId::new();
+        book.add_limit_order(first, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(second, 100, 20, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        assert_eq!(book.estimated_queue_position(first), Some((1, 20)));
+        assert_eq!(book.estimated_queue_position(second), Some((1, 10)));
+    }
+
+    #[test]
+    fn test_hidden_order_queues_behind_visible_orders() {
+        let (book, clock) = setup_book();
+        clock.advance(1);
+        let hidden = OrderId::new();
+        book.add_hidden_order(hidden, 100, 15, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        let visible = add(&book, &clock, 100, 10);
+        clock.advance(1);
+        let later_hidden = OrderId::new();
+        book.add_hidden_order(later_hidden, 100, 5, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        assert_eq!(book.estimated_queue_position(visible), Some((0, 0)));
+        assert_eq!(book.estimated_queue_position(hidden), Some((1, 10)));
+        assert_eq!(book.estimated_queue_position(later_hidden), Some((2, 25)));
+    }
+
+    #[test]
+    fn test_iceberg_orders_count_their_hidden_quantity() {
+        let (book, clock) = setup_book();
+        book.add_iceberg_order(
+            OrderId::new(),
+            100,
+            5,
+            45,
+            Side::Buy,
+            TimeInForce::Gtc,
+            None,
+        )
+        .unwrap();
+        let mine = add(&book, &clock, 100, 10);
+
+        assert_eq!(book.estimated_queue_position(mine), Some((1, 50)));
+    }
+
+    #[test]
+    fn test_unknown_order_has_no_position() {
+        let (book, clock) = setup_book();
+        let id = add(&book, &clock, 100, 10);
+        book.cancel_order(id).unwrap();
+
+        assert_eq!(book.estimated_queue_position(id), None);
+        assert_eq!(book.estimated_queue_position(OrderId::new()), None);
+    }
+}
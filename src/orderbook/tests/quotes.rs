@@ -0,0 +1,261 @@
+//! Tests for two-sided market maker quote updates
+
+#[cfg(test)]
+mod tests {
+    use crate::OrderBook;
+    use crate::OrderBookError;
+    use crate::orderbook::book_change_event::{BookChangedEvent, PriceLevelChangedEvent};
+    use crate::orderbook::config::BookConfig;
+    use crate::orderbook::owner::OwnerId;
+    use crate::orderbook::rate_limit::RateLimit;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    const MAKER: OwnerId = OwnerId(1);
+
+    #[test]
+    fn test_update_quotes_posts_both_sides() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        let quotes = book.update_quotes(MAKER, 99, 10, 101, 20).unwrap();
+
+        assert!(quotes.bid_order_id.is_some());
+        assert!(quotes.ask_order_id.is_some());
+        assert_eq!(book.best_bid(), Some(99));
+        assert_eq!(book.best_ask(), Some(101));
+        assert_eq!(book.active_quotes(MAKER), quotes);
+    }
+
+    #[test]
+    fn test_update_quotes_replaces_previous_pair() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        let first = book.update_quotes(MAKER, 99, 10, 101, 10).unwrap();
+        let second = book.update_quotes(MAKER, 100, 5, 102, 5).unwrap();
+
+        assert!(book.get_order(first.bid_order_id.unwrap()).is_none());
+        assert!(book.get_order(first.ask_order_id.unwrap()).is_none());
+        assert_eq!(book.get_all_orders().len(), 2);
+        assert_eq!(book.best_bid(), Some(100));
+        assert_eq!(book.best_ask(), Some(102));
+        assert_eq!(book.active_quotes(MAKER), second);
+    }
+
+    #[test]
+    fn test_update_quotes_never_self_crosses_on_transition() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+
+        book.update_quotes(MAKER, 99, 10, 101, 10).unwrap();
+        // New bid is above the old ask: must not trade against the old ask
+        book.update_quotes(MAKER, 102, 10, 103, 10).unwrap();
+
+        assert_eq!(book.last_trade_price(), None);
+        assert_eq!(book.best_bid(), Some(102));
+        assert_eq!(book.best_ask(), Some(103));
+    }
+
+    #[test]
+    fn test_update_quotes_rejects_crossed_pair() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        let original = book.update_quotes(MAKER, 99, 10, 101, 10).unwrap();
+
+        let result = book.update_quotes(MAKER, 101, 10, 101, 10);
+
+        assert!(matches!(result, Err(OrderBookError::PriceCrossing { .. })));
+        assert_eq!(book.active_quotes(MAKER), original);
+    }
+
+    #[test]
+    fn test_update_quotes_zero_quantity_withdraws_side() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        book.update_quotes(MAKER, 99, 10, 101, 10).unwrap();
+
+        let quotes = book.update_quotes(MAKER, 99, 10, 0, 0).unwrap();
+
+        assert!(quotes.bid_order_id.is_some());
+        assert!(quotes.ask_order_id.is_none());
+        assert_eq!(book.best_ask(), None);
+    }
+
+    #[test]
+    fn test_active_quotes_drops_filled_side() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        book.update_quotes(MAKER, 99, 10, 101, 10).unwrap();
+
+        book.submit_market_order(OrderId::new(), 10, Side::Buy)
+            .unwrap();
+
+        let active = book.active_quotes(MAKER);
+        assert!(active.bid_order_id.is_some());
+        assert!(active.ask_order_id.is_none());
+
+        // Requoting must succeed even though the old ask no longer exists
+        assert!(book.update_quotes(MAKER, 98, 10, 100, 10).is_ok());
+    }
+
+    #[test]
+    fn test_cancel_quotes_removes_both_sides() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        let quotes = book.update_quotes(MAKER, 99, 10, 101, 10).unwrap();
+
+        let cancelled = book.cancel_quotes(MAKER).unwrap();
+
+        assert_eq!(cancelled, quotes);
+        assert!(book.active_quotes(MAKER).is_empty());
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.best_ask(), None);
+    }
+
+    #[test]
+    fn test_update_quotes_emits_single_consolidated_event() {
+        let mut book: OrderBook<()> = OrderBook::new("TEST");
+        let batches: Arc<Mutex<Vec<BookChangedEvent>>> = Arc::new(Mutex::new(Vec::new()));
+        let level_events: Arc<Mutex<Vec<PriceLevelChangedEvent>>> =
+            Arc::new(Mutex::new(Vec::new()));
+
+        let batches_clone = batches.clone();
+        book.set_book_changed_listener(Arc::new(move |event| {
+            batches_clone.lock().unwrap().push(event);
+        }));
+        let level_clone = level_events.clone();
+        book.set_price_level_listener(Arc::new(move |event| {
+            level_clone.lock().unwrap().push(event);
+        }));
+
+        book.update_quotes(MAKER, 99, 10, 101, 10).unwrap();
+        book.update_quotes(MAKER, 99, 5, 102, 10).unwrap();
+
+        let batches = batches.lock().unwrap();
+        assert_eq!(batches.len(), 2);
+
+        // Second update: bid level 99 reported once with its final quantity
+        let second = &batches[1];
+        let bid_changes: Vec<_> = second
+            .changes
+            .iter()
+            .filter(|change| change.side == Side::Buy)
+            .collect();
+        assert_eq!(bid_changes.len(), 1);
+        assert_eq!(bid_changes[0].price, 99);
+        assert_eq!(bid_changes[0].quantity, 5);
+        assert!(second.changes.contains(&PriceLevelChangedEvent {
+            side: Side::Sell,
+            price: 101,
+            quantity: 0,
+        }));
+        assert!(second.changes.contains(&PriceLevelChangedEvent {
+            side: Side::Sell,
+            price: 102,
+            quantity: 10,
+        }));
+
+        // Price level listener only sees final states, never transient ones
+        let level_events = level_events.lock().unwrap();
+        assert!(
+            !level_events
+                .iter()
+                .any(|event| event.side == Side::Buy && event.price == 99 && event.quantity == 0)
+        );
+    }
+
+    #[test]
+    fn test_quotes_coexist_with_other_orders() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        let other = OrderId::new();
+        book.add_limit_order(other, 98, 7, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        book.update_quotes(MAKER, 99, 10, 101, 10).unwrap();
+        book.update_quotes(MAKER, 97, 10, 101, 10).unwrap();
+
+        assert!(book.get_order(other).is_some());
+        assert_eq!(book.best_bid(), Some(98));
+    }
+
+    #[test]
+    fn test_quotes_are_kept_per_owner() {
+        let book: OrderBook<()> = OrderBook::new("TEST");
+        let other = OwnerId(2);
+        let theirs = book.update_quotes(other, 98, 10, 102, 10).unwrap();
+
+        let mine = book.update_quotes(MAKER, 99, 10, 101, 10).unwrap();
+        book.update_quotes(MAKER, 97, 10, 103, 10).unwrap();
+        assert!(book.get_order(mine.bid_order_id.unwrap()).is_none());
+        assert_eq!(book.active_quotes(other), theirs);
+        assert_eq!(book.best_bid(), Some(98));
+        assert_eq!(
+            book.order_owner(book.active_quotes(MAKER).bid_order_id.unwrap()),
+            Some(MAKER)
+        );
+
+        book.cancel_quotes(MAKER).unwrap();
+        assert_eq!(book.active_quotes(other), theirs);
+        assert_eq!(book.get_all_orders().len(), 2);
+    }
+
+    #[test]
+    fn test_rejected_quote_keeps_previous_pair() {
+        let book: OrderBook<()> =
+            OrderBook::new_with_config("TEST", BookConfig::default().with_tick_size(5));
+        let original = book.update_quotes(MAKER, 95, 10, 105, 10).unwrap();
+
+        // The ask is off the tick grid, so nothing is cancelled or posted
+        assert!(matches!(
+            book.update_quotes(MAKER, 100, 10, 103, 10),
+            Err(OrderBookError::InvalidTickSize { price: 103, .. })
+        ));
+        assert_eq!(book.active_quotes(MAKER), original);
+        assert_eq!(book.best_bid(), Some(95));
+        assert_eq!(book.get_all_orders().len(), 2);
+
+        book.disable_owner(OwnerId(2)).unwrap();
+        assert!(matches!(
+            book.update_quotes(OwnerId(2), 90, 10, 110, 10),
+            Err(OrderBookError::TradingDisabled { .. })
+        ));
+        assert!(book.active_quotes(OwnerId(2)).is_empty());
+    }
+
+    #[test]
+    fn test_failed_post_cancels_the_posted_side() {
+        let mut book: OrderBook<()> = OrderBook::new("TEST");
+        book.set_rate_limit(RateLimit::new(3, 0));
+        book.update_quotes(MAKER, 99, 10, 101, 10).unwrap();
+
+        // The bid uses the last token, so the ask is rate limited once posted
+        assert!(matches!(
+            book.update_quotes(MAKER, 98, 10, 102, 10),
+            Err(OrderBookError::RateLimited { .. })
+        ));
+        assert!(book.active_quotes(MAKER).is_empty());
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.best_ask(), None);
+    }
+
+    #[test]
+    fn test_panicking_batch_still_emits_and_ends() {
+        let mut book: OrderBook<()> = OrderBook::new("TEST");
+        let level_events: Arc<Mutex<Vec<PriceLevelChangedEvent>>> =
+            Arc::new(Mutex::new(Vec::new()));
+        let level_clone = level_events.clone();
+        book.set_price_level_listener(Arc::new(move |event| {
+            level_clone.lock().unwrap().push(event);
+        }));
+
+        let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            book.with_batched_level_changes(|| {
+                book.add_limit_order(OrderId::new(), 99, 10, Side::Buy, TimeInForce::Gtc, None)
+                    .unwrap();
+                panic!("operation failed mid-batch");
+            })
+        }));
+        assert!(outcome.is_err());
+        // Changes made before the panic are still reported
+        assert_eq!(level_events.lock().unwrap().len(), 1);
+
+        // Later changes are delivered right away instead of staying buffered
+        book.add_limit_order(OrderId::new(), 101, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        assert_eq!(level_events.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,194 @@
+//! Unit tests for per-owner rate limiting.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::error::OrderBookError;
+    use crate::orderbook::journal::{InMemoryJournal, Journal, JournalCommand};
+    use crate::orderbook::owner::OwnerId;
+    use crate::orderbook::rate_limit::{RateLimit, RateLimiter};
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, OrderType, OrderUpdate, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn order(id: OrderId, price: u64) -> OrderType<()> {
+        OrderType::Standard {
+            id,
+            price,
+            quantity: 10,
+            side: Side::Buy,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        }
+    }
+
+    fn limited_book(capacity: u32, refill_per_second: u32) -> (OrderBook<()>, Arc<ManualClock>) {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        let clock = Arc::new(ManualClock::new(1_000));
+        book.set_clock(clock.clone());
+        book.set_rate_limit(RateLimit::new(capacity, refill_per_second));
+        (book, clock)
+    }
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let limiter = RateLimiter::new(RateLimit::new(2, 4));
+        let owner = OwnerId(1);
+        assert_eq!(limiter.try_acquire(owner, 0), Ok(()));
+        assert_eq!(limiter.try_acquire(owner, 0), Ok(()));
+        assert_eq!(limiter.try_acquire(owner, 0), Err(250));
+        assert_eq!(limiter.try_acquire(owner, 100), Err(150));
+        assert_eq!(limiter.try_acquire(owner, 250), Ok(()));
+        assert_eq!(limiter.available(owner, 10_000), 2);
+
+        // Other owners have their own bucket
+        assert_eq!(limiter.available(OwnerId(2), 0), 2);
+    }
+
+    #[test]
+    fn test_excess_orders_are_rejected_with_retry_hint() {
+        let (book, clock) = limited_book(2, 10);
+        let owner = OwnerId(7);
+        book.add_order_with_owner(order(OrderId::new(), 100), owner)
+            .unwrap();
+        book.add_order_with_owner(order(OrderId::new(), 99), owner)
+            .unwrap();
+
+        let error = book
+            .add_order_with_owner(order(OrderId::new(), 98), owner)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            OrderBookError::RateLimited {
+                owner: OwnerId(7),
+                retry_after_ms: 100,
+            }
+        ));
+        // Orders without an owner are not limited
+        assert!(book.add_order(order(OrderId::new(), 97)).is_ok());
+
+        clock.advance(100);
+        assert!(
+            book.add_order_with_owner(order(OrderId::new(), 98), owner)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_cancellations_share_the_owner_bucket() {
+        let (book, _) = limited_book(2, 1);
+        let owner = OwnerId(3);
+        let first = OrderId::new();
+        let second = OrderId::new();
+        book.add_order_with_owner(order(first, 100), owner).unwrap();
+        book.add_order_with_owner(order(second, 99), owner).unwrap();
+
+        assert!(matches!(
+            book.cancel_order(first),
+            Err(OrderBookError::RateLimited { .. })
+        ));
+        assert!(book.order_locations.contains_key(&first));
+
+        // Cancellations made by the book itself are not limited
+        assert_eq!(book.cancel_by_owner(owner).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_amendments_share_the_owner_bucket() {
+        let (book, clock) = limited_book(2, 1);
+        let owner = OwnerId(5);
+        let id = OrderId::new();
+        book.add_order_with_owner(order(id, 100), owner).unwrap();
+        book.update_order(OrderUpdate::UpdatePrice {
+            order_id: id,
+            new_price: 99,
+        })
+        .unwrap();
+
+        for update in [
+            OrderUpdate::UpdatePrice {
+                order_id: id,
+                new_price: 98,
+            },
+            OrderUpdate::UpdateQuantity {
+                order_id: id,
+                new_quantity: 5,
+            },
+            OrderUpdate::UpdatePriceAndQuantity {
+                order_id: id,
+                new_price: 98,
+                new_quantity: 5,
+            },
+            OrderUpdate::Replace {
+                order_id: id,
+                price: 98,
+                quantity: 5,
+                side: Side::Buy,
+            },
+            OrderUpdate::Cancel { order_id: id },
+        ] {
+            assert!(matches!(
+                book.update_order(update),
+                Err(OrderBookError::RateLimited { .. })
+            ));
+        }
+        assert_eq!(book.best_bid(), Some(99));
+
+        clock.advance(1_000);
+        assert!(
+            book.update_order(OrderUpdate::Cancel { order_id: id })
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(book.best_bid(), None);
+    }
+
+    #[test]
+    fn test_cancel_updates_are_journaled_as_cancellations() {
+        let journal = Arc::new(InMemoryJournal::new());
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.set_journal(journal.clone());
+        let id = OrderId::new();
+        book.add_order(order(id, 100)).unwrap();
+
+        assert!(
+            book.update_order(OrderUpdate::Cancel { order_id: id })
+                .unwrap()
+                .is_some()
+        );
+        // Unknown orders are not reported as cancelled
+        assert!(
+            book.update_order(OrderUpdate::Cancel { order_id: id })
+                .unwrap()
+                .is_none()
+        );
+
+        let entries = journal.entries_after(0).unwrap();
+        assert!(matches!(
+            entries[1].command,
+            JournalCommand::CancelOrder { order_id } if order_id == id
+        ));
+    }
+
+    #[test]
+    fn test_rejected_requests_are_not_replayed() {
+        let journal = Arc::new(InMemoryJournal::new());
+        let (mut book, _) = limited_book(1, 1);
+        book.set_journal(journal.clone());
+        let accepted = OrderId::new();
+        book.add_order_with_owner(order(accepted, 100), OwnerId(1))
+            .unwrap();
+        assert!(
+            book.add_order_with_owner(order(OrderId::new(), 99), OwnerId(1))
+                .is_err()
+        );
+
+        let mut recovered = OrderBook::<()>::new("BTC/USD");
+        recovered.set_rate_limit(RateLimit::new(1, 0));
+        recovered.replay_journal(journal.as_ref(), 0).unwrap();
+        assert_eq!(journal.entries_after(0).unwrap().len(), 1);
+        assert_eq!(recovered.best_bid(), Some(100));
+        assert!(recovered.rate_limiter().is_some());
+    }
+}
@@ -0,0 +1,118 @@
+//! Unit tests for the replay engine.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::config::BookConfig;
+    use crate::orderbook::journal::{InMemoryJournal, Journal};
+    use crate::orderbook::replay::{ReplayEngine, ReplaySpeed, ReplayStatus};
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    /// Records four commands one second apart: two bids, an ask and a cancel of
+    /// the first bid.
+    fn recorded_session() -> Arc<InMemoryJournal<()>> {
+        let journal = Arc::new(InMemoryJournal::new());
+        let clock = Arc::new(ManualClock::new(10_000));
+        let mut book = OrderBook::<()>::new("TEST");
+        book.set_clock(clock.clone());
+        book.set_journal(journal.clone());
+
+        let first = OrderId::new();
+        book.add_limit_order(first, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        clock.advance(1_000);
+        book.add_limit_order(OrderId::new(), 99, 5, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        clock.advance(1_000);
+        book.add_limit_order(OrderId::new(), 105, 5, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        clock.advance(1_000);
+        book.cancel_order(first).unwrap();
+        journal
+    }
+
+    #[test]
+    fn test_replay_stops_at_breakpoints() {
+        let journal = recorded_session();
+        let mut replay = ReplayEngine::from_journal(OrderBook::new("TEST"), journal.as_ref(), 0)
+            .unwrap()
+            .with_breakpoint(1)
+            .with_breakpoint(3);
+
+        assert_eq!(replay.run(), ReplayStatus::Breakpoint(1));
+        assert_eq!(replay.position(), 1);
+        assert_eq!(replay.book().best_bid(), Some(100));
+        assert_eq!(replay.book().best_ask(), None);
+        assert_eq!(replay.book().clock().now_millis(), 10_000);
+
+        assert_eq!(replay.run(), ReplayStatus::Breakpoint(3));
+        assert_eq!(replay.book().best_ask(), Some(105));
+        assert_eq!(replay.book().journal_sequence(), 3);
+        assert_eq!(replay.remaining(), 1);
+
+        assert_eq!(replay.run(), ReplayStatus::Finished);
+        assert_eq!(replay.book().best_bid(), Some(99));
+        assert_eq!(replay.run(), ReplayStatus::Finished);
+    }
+
+    #[test]
+    fn test_step_ignores_breakpoints() {
+        let journal = recorded_session();
+        let mut replay = ReplayEngine::from_journal(OrderBook::new("TEST"), journal.as_ref(), 2)
+            .unwrap()
+            .with_breakpoint(3);
+
+        assert_eq!(replay.step(), Some(3));
+        assert_eq!(replay.step(), Some(4));
+        assert_eq!(replay.step(), None);
+        assert_eq!(replay.book().best_ask(), Some(105));
+        assert_eq!(replay.book().best_bid(), None);
+    }
+
+    #[test]
+    fn test_replay_skips_rejected_commands() {
+        let journal = recorded_session();
+        let book = OrderBook::new_with_config("TEST", BookConfig::default().with_tick_size(5));
+        let mut replay = ReplayEngine::from_journal(book, journal.as_ref(), 0).unwrap();
+
+        assert_eq!(replay.run(), ReplayStatus::Finished);
+        // The bid at 99 is off the tick grid
+        assert_eq!(replay.rejected(), 1);
+        assert_eq!(replay.position(), 4);
+        assert_eq!(replay.book().best_bid(), None);
+        assert_eq!(replay.book().best_ask(), Some(105));
+    }
+
+    #[test]
+    fn test_accelerated_replay_paces_commands() {
+        let journal = recorded_session();
+        let mut replay = ReplayEngine::from_journal(OrderBook::new("TEST"), journal.as_ref(), 0)
+            .unwrap()
+            .with_speed(ReplaySpeed::Accelerated(50.0));
+
+        let started = Instant::now();
+        assert_eq!(replay.run(), ReplayStatus::Finished);
+        // Three seconds of recorded time at 50x
+        assert!(started.elapsed() >= Duration::from_millis(60));
+    }
+
+    #[test]
+    fn test_replay_from_recorded_entries_matches_original() {
+        let journal = recorded_session();
+        let entries = journal.entries_after(0).unwrap();
+
+        let mut book = OrderBook::<()>::new("TEST");
+        book.set_journal(Arc::new(InMemoryJournal::new()));
+        let mut replay = ReplayEngine::new(book, entries);
+        assert_eq!(replay.run(), ReplayStatus::Finished);
+
+        let book = replay.into_book();
+        assert_eq!(book.best_bid(), Some(99));
+        assert_eq!(book.best_ask(), Some(105));
+        assert_eq!(book.journal_sequence(), 4);
+        assert_eq!(journal.len(), 4);
+    }
+}
@@ -0,0 +1,171 @@
+//! Unit tests for the book resiliency tracker.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::book_change_event::BboChangedEvent;
+    use crate::orderbook::resiliency::ResiliencyTracker;
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn setup_book() -> (OrderBook<()>, Arc<ManualClock>) {
+        let mut book = OrderBook::new("TEST");
+        let clock = Arc::new(ManualClock::new(10_000));
+        book.set_clock(clock.clone());
+        book.enable_resiliency_tracking(0.5, 5_000, 100);
+        (book, clock)
+    }
+
+    fn ask(book: &OrderBook<()>, price: u64, quantity: u64) -> OrderId {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        id
+    }
+
+    fn asks(bid_quantity: u64, ask_quantity: u64) -> BboChangedEvent {
+        BboChangedEvent {
+            bid_price: Some(99),
+            bid_quantity,
+            ask_price: Some(101),
+            ask_quantity,
+        }
+    }
+
+    #[test]
+    fn test_tracker_measures_time_to_replenishment() {
+        let tracker = ResiliencyTracker::new(0.5, 1_000, 10);
+        tracker.record_sweep(10_000, Side::Sell, 2, 40, 5);
+        assert_eq!(tracker.pending().len(), 1);
+
+        tracker.observe(10_100, &asks(0, 19));
+        assert!(tracker.sweeps().is_empty());
+        tracker.observe(10_300, &asks(0, 20));
+
+        let sweeps = tracker.sweeps();
+        assert_eq!(sweeps.len(), 1);
+        assert_eq!(sweeps[0].levels_consumed, 2);
+        assert_eq!(sweeps[0].recovery_ms(), Some(300));
+        assert!(tracker.pending().is_empty());
+    }
+
+    #[test]
+    fn test_tracker_expires_and_supersedes_pending_sweeps() {
+        let tracker = ResiliencyTracker::new(1.0, 1_000, 10);
+        tracker.record_sweep(10_000, Side::Sell, 1, 10, 0);
+        tracker.observe(11_001, &asks(0, 10));
+        tracker.record_sweep(12_000, Side::Buy, 1, 10, 0);
+        tracker.record_sweep(12_500, Side::Buy, 1, 10, 0);
+        // Replenished at once
+        tracker.record_sweep(13_000, Side::Sell, 1, 10, 10);
+
+        let sweeps = tracker.sweeps();
+        assert_eq!(sweeps.len(), 3);
+        assert!(sweeps[0].recovered_at.is_none());
+        assert!(sweeps[1].recovered_at.is_none());
+        assert_eq!(sweeps[2].recovery_ms(), Some(0));
+
+        let stats = tracker.stats(None);
+        assert_eq!(stats.sweep_count, 3);
+        assert_eq!(stats.recovered_count, 1);
+        assert_eq!(stats.recovery_ratio(), Some(1.0 / 3.0));
+        assert_eq!(tracker.stats(Some(Side::Buy)).sweep_count, 1);
+        assert_eq!(tracker.pending()[0].timestamp, 12_500);
+
+        tracker.clear();
+        assert!(tracker.sweeps().is_empty() && tracker.pending().is_empty());
+    }
+
+    #[test]
+    fn test_tracker_keeps_the_last_sweeps() {
+        let tracker = ResiliencyTracker::new(0.5, 1_000, 2);
+        for timestamp in [1, 2, 3] {
+            tracker.record_sweep(timestamp, Side::Buy, 1, 10, 10);
+        }
+        let sweeps = tracker.sweeps();
+        assert_eq!(sweeps.len(), 2);
+        assert_eq!(sweeps[0].timestamp, 2);
+    }
+
+    #[test]
+    fn test_stats_of_recovery_times() {
+        let tracker = ResiliencyTracker::new(1.0, 10_000, 10);
+        for (start, recovery) in [(0, 100), (1_000, 300), (2_000, 200)] {
+            tracker.record_sweep(start, Side::Sell, 1, 10, 0);
+            tracker.observe(start + recovery, &asks(0, 10));
+        }
+
+        let stats = tracker.stats(Some(Side::Sell));
+        assert_eq!(stats.recovered_count, 3);
+        assert_eq!(stats.avg_recovery_ms, Some(200.0));
+        assert_eq!(stats.median_recovery_ms, Some(200));
+        assert_eq!(stats.max_recovery_ms, Some(300));
+    }
+
+    #[test]
+    fn test_book_detects_sweeps_of_whole_levels() {
+        let (book, clock) = setup_book();
+        ask(&book, 101, 10);
+        ask(&book, 102, 10);
+        ask(&book, 103, 2);
+
+        // Partial fill of the touch: no sweep
+        book.submit_market_order(OrderId::new(), 5, Side::Buy)
+            .unwrap();
+        assert!(book.resiliency_tracker().unwrap().pending().is_empty());
+
+        // Consumes 101 and 102 entirely
+        book.submit_market_order(OrderId::new(), 15, Side::Buy)
+            .unwrap();
+        let pending = book.resiliency_tracker().unwrap().pending();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].side, Side::Sell);
+        assert_eq!(pending[0].levels_consumed, 2);
+        assert_eq!(pending[0].depth_before, 5);
+        assert_eq!(pending[0].depth_after, 2);
+
+        assert_eq!(book.resiliency_stats(None).unwrap().sweep_count, 0);
+        clock.advance(10);
+        ask(&book, 103, 1);
+        let stats = book.resiliency_stats(Some(Side::Sell)).unwrap();
+        assert_eq!(stats.sweep_count, 1);
+        assert_eq!(stats.max_recovery_ms, Some(10));
+    }
+
+    #[test]
+    fn test_book_sweep_recovered_by_new_orders() {
+        let (book, clock) = setup_book();
+        book.add_limit_order(OrderId::new(), 100, 30, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 99, 2, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        book.submit_market_order(OrderId::new(), 30, Side::Sell)
+            .unwrap();
+        clock.advance(400);
+        book.add_limit_order(OrderId::new(), 99, 5, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        clock.advance(600);
+        book.add_limit_order(OrderId::new(), 99, 8, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        let sweeps = book.resiliency_tracker().unwrap().sweeps();
+        assert_eq!(sweeps.len(), 1);
+        assert_eq!(sweeps[0].side, Side::Buy);
+        assert_eq!(sweeps[0].depth_before, 30);
+        assert_eq!(sweeps[0].recovery_ms(), Some(1_000));
+    }
+
+    #[test]
+    fn test_disabled_tracking() {
+        let (mut book, _clock) = setup_book();
+        book.disable_resiliency_tracking();
+        ask(&book, 101, 10);
+        book.submit_market_order(OrderId::new(), 10, Side::Buy)
+            .unwrap();
+
+        assert!(book.resiliency_tracker().is_none());
+        assert!(book.resiliency_stats(None).is_none());
+    }
+}
@@ -0,0 +1,337 @@
+//! Unit tests for pre-trade risk checks.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::error::OrderBookError;
+    use crate::orderbook::modifications::OrderQuantity;
+    use crate::orderbook::order_event::OrderEvent;
+    use crate::orderbook::owner::OwnerId;
+    use crate::orderbook::risk::{
+        MaxNotional, MaxOrderQuantity, PriceCollar, RiskChecker, RiskContext, RiskOrder,
+    };
+    use pricelevel::{OrderId, OrderType, OrderUpdate, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn add(
+        book: &OrderBook<()>,
+        price: u64,
+        quantity: u64,
+        side: Side,
+    ) -> Result<OrderId, OrderBookError> {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, side, TimeInForce::Gtc, None)
+            .map(|_| id)
+    }
+
+    fn order(side: Side, price: Option<u64>, quantity: u64) -> RiskOrder {
+        RiskOrder {
+            order_id: OrderId::new(),
+            side,
+            price,
+            quantity,
+            owner: None,
+        }
+    }
+
+    #[test]
+    fn test_max_order_quantity() {
+        let check = MaxOrderQuantity::new(100);
+        let context = RiskContext::default();
+        assert!(
+            check
+                .check(&order(Side::Buy, Some(10), 100), &context)
+                .is_ok()
+        );
+        assert!(check.check(&order(Side::Buy, None, 101), &context).is_err());
+    }
+
+    #[test]
+    fn test_max_notional_values_market_orders_at_opposite_price() {
+        let check = MaxNotional::new(10_000);
+        let context = RiskContext {
+            best_bid: Some(90),
+            best_ask: Some(110),
+            last_trade_price: None,
+        };
+        assert!(
+            check
+                .check(&order(Side::Buy, Some(100), 100), &context)
+                .is_ok()
+        );
+        assert!(
+            check
+                .check(&order(Side::Buy, Some(101), 100), &context)
+                .is_err()
+        );
+        assert!(check.check(&order(Side::Buy, None, 100), &context).is_err());
+        assert!(check.check(&order(Side::Sell, None, 100), &context).is_ok());
+        assert!(
+            check
+                .check(&order(Side::Buy, None, 1_000), &RiskContext::default())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_price_collar_limits_aggressive_prices() {
+        let check = PriceCollar::new(500);
+        let context = RiskContext {
+            best_bid: Some(1_000),
+            best_ask: Some(1_020),
+            last_trade_price: None,
+        };
+        assert!(
+            check
+                .check(&order(Side::Buy, Some(1_071), 1), &context)
+                .is_ok()
+        );
+        assert!(
+            check
+                .check(&order(Side::Buy, Some(1_072), 1), &context)
+                .is_err()
+        );
+        assert!(
+            check
+                .check(&order(Side::Sell, Some(950), 1), &context)
+                .is_ok()
+        );
+        assert!(
+            check
+                .check(&order(Side::Sell, Some(949), 1), &context)
+                .is_err()
+        );
+        // Passive orders far from the market are not fat-finger risks
+        assert!(check.check(&order(Side::Buy, Some(1), 1), &context).is_ok());
+        assert!(check.check(&order(Side::Buy, None, 1), &context).is_ok());
+
+        let one_sided = RiskContext {
+            best_bid: Some(1_000),
+            ..RiskContext::default()
+        };
+        assert!(
+            check
+                .check(&order(Side::Buy, Some(1_051), 1), &one_sided)
+                .is_err()
+        );
+        assert!(
+            check
+                .check(&order(Side::Buy, Some(5_000), 1), &RiskContext::default())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_book_rejects_and_reports_risk_failures() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        let events: Arc<Mutex<Vec<OrderEvent>>> = Arc::default();
+        let sink = events.clone();
+        book.set_order_event_listener(Arc::new(move |event| {
+            sink.lock().unwrap().push(event.clone());
+        }));
+        book.add_risk_checker(Arc::new(MaxOrderQuantity::new(50)));
+
+        let id = OrderId::new();
+        let error = book
+            .add_limit_order(id, 100, 60, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            OrderBookError::RiskRejected { order_id, .. } if order_id == id
+        ));
+        assert_eq!(
+            error.to_string(),
+            format!("Order {id} rejected by risk check: quantity 60 exceeds the maximum of 50")
+        );
+        assert_eq!(book.best_bid(), None);
+        assert!(matches!(
+            events.lock().unwrap().as_slice(),
+            [OrderEvent::Rejected { order_id, .. }] if *order_id == id
+        ));
+    }
+
+    #[test]
+    fn test_iceberg_orders_are_checked_on_total_quantity() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.add_risk_checker(Arc::new(MaxOrderQuantity::new(50)));
+        let result = book.add_iceberg_order(
+            OrderId::new(),
+            100,
+            10,
+            50,
+            Side::Sell,
+            TimeInForce::Gtc,
+            None,
+        );
+        assert!(matches!(result, Err(OrderBookError::RiskRejected { .. })));
+    }
+
+    #[test]
+    fn test_market_orders_are_checked() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        add(&book, 100, 100, Side::Sell).unwrap();
+        book.add_risk_checker(Arc::new(MaxNotional::new(5_000)));
+
+        assert!(matches!(
+            book.submit_market_order(OrderId::new(), 60, Side::Buy),
+            Err(OrderBookError::RiskRejected { .. })
+        ));
+        assert_eq!(book.best_ask(), Some(100));
+        assert!(
+            book.submit_market_order(OrderId::new(), 50, Side::Buy)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_limit_matches_are_checked() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        add(&book, 101, 1_000, Side::Sell).unwrap();
+        book.add_risk_checker(Arc::new(MaxOrderQuantity::new(100)));
+
+        assert!(matches!(
+            book.match_limit_order(OrderId::new(), 500, Side::Buy, 101),
+            Err(OrderBookError::RiskRejected { .. })
+        ));
+        assert_eq!(
+            book.get_orders_at_price(101, Side::Sell)[0].total_quantity(),
+            1_000
+        );
+        let result = book
+            .match_limit_order(OrderId::new(), 100, Side::Buy, 101)
+            .unwrap();
+        assert_eq!(result.executed_quantity(), 100);
+    }
+
+    #[derive(Debug)]
+    struct BlockedOwner(OwnerId);
+
+    impl RiskChecker for BlockedOwner {
+        fn check(&self, order: &RiskOrder, _context: &RiskContext) -> Result<(), String> {
+            if order.owner == Some(self.0) {
+                return Err(format!("owner {} is blocked", self.0.0));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_custom_checkers_see_the_owner() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.add_risk_checker(Arc::new(BlockedOwner(OwnerId(7))));
+        let standard = |id| OrderType::Standard {
+            id,
+            price: 100,
+            quantity: 1,
+            side: Side::Buy,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        };
+
+        assert!(
+            book.add_order_with_owner(standard(OrderId::new()), OwnerId(1))
+                .is_ok()
+        );
+        assert!(matches!(
+            book.add_order_with_owner(standard(OrderId::new()), OwnerId(7)),
+            Err(OrderBookError::RiskRejected { reason, .. }) if reason == "owner 7 is blocked"
+        ));
+    }
+
+    #[test]
+    fn test_checks_run_in_order_and_can_be_cleared() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.add_risk_checker(Arc::new(MaxOrderQuantity::new(10)));
+        book.add_risk_checker(Arc::new(MaxNotional::new(100)));
+        assert_eq!(book.risk_checkers().len(), 2);
+
+        let rejected = add(&book, 100, 20, Side::Buy).unwrap_err();
+        assert!(rejected.to_string().contains("quantity 20"));
+
+        book.clear_risk_checkers();
+        assert!(add(&book, 100, 20, Side::Buy).is_ok());
+    }
+
+    /// Amends a buy of 10 at 100 to a price of 200, over a notional limit of 1500,
+    /// and checks the original order is still resting untouched.
+    fn assert_amend_rejected_keeps_order(amend: impl Fn(OrderId) -> OrderUpdate) {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        let events: Arc<Mutex<Vec<OrderEvent>>> = Arc::default();
+        let sink = events.clone();
+        book.set_order_event_listener(Arc::new(move |event| {
+            sink.lock().unwrap().push(event.clone());
+        }));
+        let id = add(&book, 100, 10, Side::Buy).unwrap();
+        book.add_risk_checker(Arc::new(MaxNotional::new(1_500)));
+
+        assert!(matches!(
+            book.update_order(amend(id)),
+            Err(OrderBookError::RiskRejected { order_id, .. }) if order_id == id
+        ));
+        let order = book.get_order(id).unwrap();
+        assert_eq!((order.price(), order.total_quantity()), (100, 10));
+        assert_eq!(book.best_bid(), Some(100));
+        assert!(
+            !events
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|event| matches!(event, OrderEvent::Cancelled { .. }))
+        );
+    }
+
+    #[test]
+    fn test_rejected_price_update_keeps_order() {
+        assert_amend_rejected_keeps_order(|order_id| OrderUpdate::UpdatePrice {
+            order_id,
+            new_price: 200,
+        });
+    }
+
+    #[test]
+    fn test_rejected_price_and_quantity_update_keeps_order() {
+        assert_amend_rejected_keeps_order(|order_id| OrderUpdate::UpdatePriceAndQuantity {
+            order_id,
+            new_price: 200,
+            new_quantity: 10,
+        });
+    }
+
+    #[test]
+    fn test_rejected_replace_keeps_order() {
+        assert_amend_rejected_keeps_order(|order_id| OrderUpdate::Replace {
+            order_id,
+            price: 200,
+            quantity: 10,
+            side: Side::Buy,
+        });
+    }
+
+    #[test]
+    fn test_rejected_quantity_update_keeps_order() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        let id = add(&book, 100, 10, Side::Buy).unwrap();
+        book.add_risk_checker(Arc::new(MaxOrderQuantity::new(100)));
+
+        assert!(matches!(
+            book.update_order(OrderUpdate::UpdateQuantity {
+                order_id: id,
+                new_quantity: 1_000_000,
+            }),
+            Err(OrderBookError::RiskRejected { order_id, .. }) if order_id == id
+        ));
+        assert_eq!(book.get_order(id).unwrap().total_quantity(), 10);
+        assert_eq!(book.buy_sell_pressure().0, 10);
+
+        assert!(
+            book.update_order(OrderUpdate::UpdateQuantity {
+                order_id: id,
+                new_quantity: 100,
+            })
+            .unwrap()
+            .is_some()
+        );
+        assert_eq!(book.get_order(id).unwrap().total_quantity(), 100);
+    }
+}
@@ -0,0 +1,133 @@
+//! Unit tests for the what-if sandbox.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use pricelevel::{OrderId, Side, TimeInForce};
+
+    fn add(book: &OrderBook<()>, price: u64, quantity: u64, side: Side) -> OrderId {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, side, TimeInForce::Gtc, None)
+            .unwrap();
+        id
+    }
+
+    fn setup_book() -> OrderBook<()> {
+        let book = OrderBook::new("TEST");
+        add(&book, 100, 10, Side::Buy);
+        add(&book, 98, 20, Side::Buy);
+        add(&book, 102, 10, Side::Sell);
+        add(&book, 104, 30, Side::Sell);
+        book
+    }
+
+    #[test]
+    fn test_unchanged_sandbox_mirrors_the_book() {
+        let book = setup_book();
+        let sandbox = book.sandbox();
+
+        assert!(sandbox.is_unchanged());
+        assert_eq!(sandbox.best_bid(), book.best_bid());
+        assert_eq!(sandbox.best_ask(), book.best_ask());
+        assert_eq!(sandbox.spread(), book.spread());
+        assert_eq!(sandbox.mid_price(), book.mid_price());
+        assert_eq!(
+            sandbox.order_book_imbalance(5),
+            book.order_book_imbalance(5)
+        );
+        assert_eq!(
+            sandbox.market_impact(25, Side::Buy),
+            book.market_impact(25, Side::Buy)
+        );
+        assert_eq!(sandbox.vwap(25, Side::Sell), book.vwap(25, Side::Sell));
+        assert_eq!(
+            sandbox.levels(Side::Buy).collect::<Vec<_>>(),
+            vec![(100, 10), (98, 20)]
+        );
+    }
+
+    #[test]
+    fn test_hypothetical_orders_merge_with_the_levels() {
+        let book = setup_book();
+        let mut sandbox = book.sandbox();
+
+        let resting = sandbox.add_limit_order(99, 5, Side::Buy);
+        assert!(resting.fills.is_empty());
+        assert_eq!(resting.remaining_quantity, 5);
+        sandbox.add_limit_order(100, 5, Side::Buy);
+        sandbox.add_limit_order(103, 7, Side::Sell);
+
+        assert_eq!(
+            sandbox.levels(Side::Buy).collect::<Vec<_>>(),
+            vec![(100, 15), (99, 5), (98, 20)]
+        );
+        assert_eq!(
+            sandbox.levels(Side::Sell).collect::<Vec<_>>(),
+            vec![(102, 10), (103, 7), (104, 30)]
+        );
+        assert_eq!(sandbox.total_depth_at_levels(2, Side::Buy), 20);
+        assert!(!sandbox.is_unchanged());
+
+        // The live book is untouched
+        assert_eq!(book.total_depth_at_levels(5, Side::Buy), 30);
+    }
+
+    #[test]
+    fn test_hypothetical_orders_match_the_view() {
+        let book = setup_book();
+        let mut sandbox = book.sandbox();
+
+        // Crosses the ask at 102 and rests 5 at 103
+        let result = sandbox.add_limit_order(103, 15, Side::Buy);
+        assert_eq!(result.fills, vec![(102, 10)]);
+        assert_eq!(result.remaining_quantity, 5);
+        assert_eq!(sandbox.best_bid(), Some(103));
+        assert_eq!(sandbox.best_ask(), Some(104));
+
+        let result = sandbox.submit_market_order(100, Side::Sell);
+        assert_eq!(result.fills, vec![(103, 5), (100, 10), (98, 20)]);
+        assert_eq!(result.total_filled, 35);
+        assert_eq!(result.remaining_quantity, 65);
+        assert_eq!(sandbox.best_bid(), None);
+        assert_eq!(sandbox.spread(), None);
+        assert_eq!(sandbox.order_book_imbalance(5), -1.0);
+
+        assert_eq!(book.best_bid(), Some(100));
+        assert_eq!(book.best_ask(), Some(102));
+    }
+
+    #[test]
+    fn test_cancellations_and_reset() {
+        let book = setup_book();
+        let best_ask = book.best_ask().unwrap();
+        let deep = add(&book, best_ask, 5, Side::Sell);
+        let mut sandbox = book.sandbox();
+
+        assert!(sandbox.cancel_order(deep));
+        assert!(!sandbox.cancel_order(deep));
+        assert!(!sandbox.cancel_order(OrderId::new()));
+        assert_eq!(sandbox.levels(Side::Sell).next(), Some((102, 10)));
+
+        let impact = sandbox.market_impact(15, Side::Buy);
+        assert_eq!(impact.worst_price, 104);
+        assert_eq!(impact.levels_consumed, 2);
+        assert_eq!(book.market_impact(15, Side::Buy).worst_price, 102);
+
+        sandbox.reset();
+        assert!(sandbox.is_unchanged());
+        assert_eq!(sandbox.levels(Side::Sell).next(), Some((102, 15)));
+    }
+
+    #[test]
+    fn test_cancelling_a_whole_level() {
+        let book = setup_book();
+        let bid = add(&book, 101, 4, Side::Buy);
+        let mut sandbox = book.sandbox();
+
+        sandbox.cancel_order(bid);
+        assert_eq!(sandbox.best_bid(), Some(100));
+        assert_eq!(sandbox.vwap(10, Side::Sell), Some(100.0));
+        assert_eq!(sandbox.vwap(0, Side::Sell), None);
+        assert_eq!(sandbox.vwap(31, Side::Sell), None);
+    }
+}
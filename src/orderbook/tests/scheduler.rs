@@ -0,0 +1,133 @@
+//! Unit tests for the snapshot scheduler and snapshot stores.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::manager::{BookManager, BookManagerStd};
+    use crate::orderbook::scheduler::{
+        FileSnapshotStore, InMemorySnapshotStore, SnapshotScheduler, SnapshotStore, SnapshotTrigger,
+    };
+    use crate::orderbook::snapshot::SnapshotFormat;
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn add(book: &OrderBook<()>, price: u64, side: Side) {
+        book.add_limit_order(OrderId::new(), price, 10, side, TimeInForce::Gtc, None)
+            .unwrap();
+    }
+
+    fn clocked_book(clock: &Arc<ManualClock>) -> OrderBook<()> {
+        let mut book = OrderBook::new("TEST");
+        book.set_clock(clock.clone());
+        book
+    }
+
+    #[test]
+    fn test_interval_trigger_uses_book_clock() {
+        let clock = Arc::new(ManualClock::new(1_000));
+        let book = clocked_book(&clock);
+        let store = Arc::new(InMemorySnapshotStore::new());
+        let scheduler = SnapshotScheduler::new(store.clone(), SnapshotTrigger::IntervalMillis(500));
+
+        assert_eq!(scheduler.snapshot_if_due(&book).unwrap(), Some(1));
+        clock.advance(499);
+        assert_eq!(scheduler.snapshot_if_due(&book).unwrap(), None);
+        clock.advance(1);
+        assert_eq!(scheduler.snapshot_if_due(&book).unwrap(), Some(2));
+        assert_eq!(store.list("TEST").unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_event_trigger_counts_level_changes() {
+        let book = OrderBook::<()>::new("TEST");
+        let store = Arc::new(InMemorySnapshotStore::new());
+        let scheduler = SnapshotScheduler::new(store.clone(), SnapshotTrigger::EveryEvents(3));
+
+        assert!(scheduler.snapshot_if_due(&book).unwrap().is_some());
+        add(&book, 100, Side::Buy);
+        add(&book, 101, Side::Buy);
+        assert!(!scheduler.is_due(&book));
+        add(&book, 102, Side::Sell);
+        assert!(scheduler.is_due(&book));
+
+        scheduler.snapshot_now(&book).unwrap();
+        assert!(!scheduler.is_due(&book));
+        let latest = store.load_latest("TEST").unwrap().unwrap();
+        assert_eq!(latest.snapshot.bids.len(), 2);
+        assert_eq!(latest.snapshot.asks.len(), 1);
+    }
+
+    #[test]
+    fn test_retention_keeps_most_recent_snapshots() {
+        let book = OrderBook::<()>::new("TEST");
+        let store = Arc::new(InMemorySnapshotStore::new());
+        let scheduler = SnapshotScheduler::new(store.clone(), SnapshotTrigger::IntervalMillis(0))
+            .with_retention(2);
+
+        for _ in 0..5 {
+            scheduler.snapshot_now(&book).unwrap();
+        }
+        assert_eq!(store.list("TEST").unwrap(), vec![4, 5]);
+    }
+
+    #[test]
+    fn test_run_due_snapshots_every_managed_book() {
+        let clock = Arc::new(ManualClock::new(1_000));
+        let mut manager = BookManagerStd::<()>::with_clock(clock.clone());
+        manager.add_book("AAA");
+        manager.add_book("BBB");
+        add(manager.get_book("AAA").unwrap(), 100, Side::Buy);
+
+        let store = Arc::new(InMemorySnapshotStore::new());
+        let scheduler =
+            SnapshotScheduler::new(store.clone(), SnapshotTrigger::IntervalMillis(60_000));
+
+        let taken = scheduler.run_due(&manager).unwrap();
+        assert_eq!(taken, vec![("AAA".to_string(), 1), ("BBB".to_string(), 1)]);
+        assert!(scheduler.run_due(&manager).unwrap().is_empty());
+
+        clock.advance(60_000);
+        assert_eq!(scheduler.run_due(&manager).unwrap().len(), 2);
+
+        let restored = OrderBook::<()>::new("AAA");
+        let package = store.load_latest("AAA").unwrap().unwrap();
+        restored.restore_from_snapshot_package(package).unwrap();
+        assert_eq!(restored.best_bid(), Some(100));
+    }
+
+    #[test]
+    fn test_file_store_round_trip_and_retention() {
+        let root = std::env::temp_dir().join(format!("orderbook-snapshots-{}", OrderId::new()));
+        for format in [SnapshotFormat::Binary, SnapshotFormat::Json] {
+            let store = Arc::new(FileSnapshotStore::open(&root).unwrap().with_format(format));
+            let book = OrderBook::<()>::new("BTC/USD");
+            add(&book, 100, Side::Buy);
+            let scheduler =
+                SnapshotScheduler::new(store.clone(), SnapshotTrigger::IntervalMillis(0))
+                    .with_retention(2);
+
+            for _ in 0..3 {
+                scheduler.snapshot_now(&book).unwrap();
+            }
+            assert_eq!(store.list("BTC/USD").unwrap(), vec![2, 3]);
+            assert!(store.load("BTC/USD", 1).is_err());
+            assert!(root.join("BTC%2FUSD").is_dir());
+
+            let restored = OrderBook::<()>::new("BTC/USD");
+            let package = store.load_latest("BTC/USD").unwrap().unwrap();
+            restored.restore_from_snapshot_package(package).unwrap();
+            assert_eq!(restored.best_bid(), Some(100));
+        }
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_store_reports_missing_snapshots() {
+        let store = InMemorySnapshotStore::new();
+        assert!(store.list("NONE").unwrap().is_empty());
+        assert!(store.load_latest("NONE").unwrap().is_none());
+        assert!(store.load("NONE", 1).is_err());
+        assert!(store.delete("NONE", 1).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Unit tests for trading session states.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::error::OrderBookError;
+    use crate::orderbook::journal::InMemoryJournal;
+    use crate::orderbook::modifications::OrderQuantity;
+    use crate::orderbook::order_event::OrderEvent;
+    use crate::orderbook::session::{SessionState, SessionStateChangedEvent};
+    use pricelevel::{OrderId, OrderUpdate, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn add(
+        book: &OrderBook<()>,
+        price: u64,
+        quantity: u64,
+        side: Side,
+        time_in_force: TimeInForce,
+    ) -> Result<OrderId, OrderBookError> {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, side, time_in_force, None)
+            .map(|_| id)
+    }
+
+    #[test]
+    fn test_books_start_open() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        assert_eq!(book.session_state(), SessionState::Open);
+        assert_eq!(SessionState::default(), SessionState::Open);
+    }
+
+    #[test]
+    fn test_transitions() {
+        use SessionState::*;
+        assert!(PreOpen.can_transition_to(Open));
+        assert!(Open.can_transition_to(Halted));
+        assert!(Halted.can_transition_to(Open));
+        assert!(Halted.can_transition_to(PreOpen));
+        assert!(Open.can_transition_to(Closed));
+        assert!(Closed.can_transition_to(PreOpen));
+        assert!(Open.can_transition_to(PreOpen));
+        assert!(!Closed.can_transition_to(Halted));
+        assert!(!Open.can_transition_to(Open));
+
+        let book = OrderBook::<()>::new("BTC/USD");
+        book.set_session_state(Closed).unwrap();
+        let error = book.set_session_state(Halted).unwrap_err();
+        assert!(matches!(
+            error,
+            OrderBookError::InvalidSessionTransition {
+                from: Closed,
+                to: Halted
+            }
+        ));
+        assert_eq!(book.session_state(), Closed);
+    }
+
+    #[test]
+    fn test_pre_open_rests_crossing_orders_without_matching() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        book.set_session_state(SessionState::Halted).unwrap();
+        book.set_session_state(SessionState::PreOpen).unwrap();
+
+        add(&book, 100, 10, Side::Sell, TimeInForce::Gtc).unwrap();
+        add(&book, 101, 4, Side::Buy, TimeInForce::Gtc).unwrap();
+        assert_eq!(book.best_bid(), Some(101));
+        assert_eq!(book.best_ask(), Some(100));
+        assert_eq!(
+            book.get_orders_at_price(100, Side::Sell)[0].total_quantity(),
+            10
+        );
+    }
+
+    #[test]
+    fn test_crossed_book_cannot_open_before_uncrossing() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        book.set_session_state(SessionState::PreOpen).unwrap();
+        add(&book, 100, 10, Side::Sell, TimeInForce::Gtc).unwrap();
+        add(&book, 101, 4, Side::Buy, TimeInForce::Gtc).unwrap();
+
+        assert!(matches!(
+            book.set_session_state(SessionState::Open),
+            Err(OrderBookError::SessionRejected {
+                state: SessionState::PreOpen,
+                ..
+            })
+        ));
+        // Going through a halt does not open it crossed either
+        book.set_session_state(SessionState::Halted).unwrap();
+        assert!(matches!(
+            book.set_session_state(SessionState::Open),
+            Err(OrderBookError::SessionRejected {
+                state: SessionState::Halted,
+                ..
+            })
+        ));
+        assert_eq!(book.session_state(), SessionState::Halted);
+        assert_eq!(book.best_bid(), Some(101));
+
+        book.set_session_state(SessionState::PreOpen).unwrap();
+        book.uncross().unwrap().unwrap();
+        book.set_session_state(SessionState::Open).unwrap();
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.best_ask(), Some(100));
+    }
+
+    #[test]
+    fn test_uncrossed_book_opens_from_pre_open() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        book.set_session_state(SessionState::PreOpen).unwrap();
+        add(&book, 100, 10, Side::Sell, TimeInForce::Gtc).unwrap();
+        add(&book, 99, 4, Side::Buy, TimeInForce::Gtc).unwrap();
+
+        book.set_session_state(SessionState::Open).unwrap();
+        assert_eq!(book.session_state(), SessionState::Open);
+        assert_eq!(book.spread(), Some(1));
+    }
+
+    #[test]
+    fn test_pre_open_rejects_immediate_and_market_orders() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        add(&book, 100, 10, Side::Sell, TimeInForce::Gtc).unwrap();
+        book.set_session_state(SessionState::Closed).unwrap();
+        book.set_session_state(SessionState::PreOpen).unwrap();
+
+        let error = add(&book, 100, 5, Side::Buy, TimeInForce::Ioc).unwrap_err();
+        assert!(matches!(
+            error,
+            OrderBookError::SessionRejected {
+                state: SessionState::PreOpen,
+                ..
+            }
+        ));
+        assert!(
+            book.submit_market_order(OrderId::new(), 5, Side::Buy)
+                .is_err()
+        );
+        assert_eq!(
+            book.get_orders_at_price(100, Side::Sell)[0].total_quantity(),
+            10
+        );
+    }
+
+    #[test]
+    fn test_halt_rejects_aggressive_orders() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        add(&book, 100, 10, Side::Sell, TimeInForce::Gtc).unwrap();
+        book.set_session_state(SessionState::Halted).unwrap();
+
+        assert!(add(&book, 100, 5, Side::Buy, TimeInForce::Gtc).is_err());
+        assert!(add(&book, 95, 5, Side::Buy, TimeInForce::Ioc).is_err());
+        assert!(
+            book.submit_market_order(OrderId::new(), 5, Side::Buy)
+                .is_err()
+        );
+
+        // Passive orders and cancellations are still accepted
+        let passive = add(&book, 99, 5, Side::Buy, TimeInForce::Gtc).unwrap();
+        assert_eq!(book.best_bid(), Some(99));
+        assert!(book.cancel_order(passive).unwrap().is_some());
+        assert_eq!(book.best_ask(), Some(100));
+    }
+
+    #[test]
+    fn test_halt_rejects_aggressive_price_updates_without_losing_the_order() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        add(&book, 100, 10, Side::Sell, TimeInForce::Gtc).unwrap();
+        let bid = add(&book, 95, 5, Side::Buy, TimeInForce::Gtc).unwrap();
+        book.set_session_state(SessionState::Halted).unwrap();
+
+        let update = OrderUpdate::UpdatePrice {
+            order_id: bid,
+            new_price: 100,
+        };
+        assert!(book.update_order(update).is_err());
+        assert_eq!(book.best_bid(), Some(95));
+    }
+
+    #[test]
+    fn test_resuming_trading_matches_again() {
+        let book = OrderBook::<()>::new("BTC/USD");
+        add(&book, 100, 10, Side::Sell, TimeInForce::Gtc).unwrap();
+        book.set_session_state(SessionState::Halted).unwrap();
+        book.set_session_state(SessionState::Open).unwrap();
+
+        let result = book
+            .submit_market_order(OrderId::new(), 4, Side::Buy)
+            .unwrap();
+        assert_eq!(result.executed_quantity(), 4);
+    }
+
+    #[test]
+    fn test_close_purges_day_orders_and_rejects_new_orders() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        let cancelled = Arc::new(Mutex::new(Vec::new()));
+        let events = cancelled.clone();
+        book.set_order_event_listener(Arc::new(move |event| {
+            if let OrderEvent::Cancelled { order_id, .. } = event {
+                events.lock().unwrap().push(*order_id);
+            }
+        }));
+
+        let day_bid = add(&book, 99, 10, Side::Buy, TimeInForce::Day).unwrap();
+        let day_ask = add(&book, 101, 10, Side::Sell, TimeInForce::Day).unwrap();
+        let gtc_bid = add(&book, 98, 10, Side::Buy, TimeInForce::Gtc).unwrap();
+
+        let event = book.set_session_state(SessionState::Closed).unwrap();
+        assert_eq!(event.purged_orders.len(), 2);
+        assert!(event.purged_orders.contains(&day_bid));
+        assert!(event.purged_orders.contains(&day_ask));
+        assert_eq!(cancelled.lock().unwrap().len(), 2);
+        assert!(book.get_order(gtc_bid).is_some());
+        assert_eq!(book.best_bid(), Some(98));
+        assert_eq!(book.best_ask(), None);
+
+        assert!(matches!(
+            add(&book, 97, 1, Side::Buy, TimeInForce::Gtc),
+            Err(OrderBookError::SessionRejected {
+                state: SessionState::Closed,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_session_listener_receives_changes() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        let received: Arc<Mutex<Vec<SessionStateChangedEvent>>> = Arc::default();
+        let sink = received.clone();
+        book.set_session_listener(Arc::new(move |event| {
+            sink.lock().unwrap().push(event.clone());
+        }));
+
+        book.set_session_state(SessionState::Halted).unwrap();
+        book.set_session_state(SessionState::Open).unwrap();
+        assert!(book.set_session_state(SessionState::Open).is_err());
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].symbol, "BTC/USD");
+        assert_eq!(received[0].previous, SessionState::Open);
+        assert_eq!(received[0].state, SessionState::Halted);
+        assert_eq!(received[1].state, SessionState::Open);
+    }
+
+    #[test]
+    fn test_session_changes_are_replayed() {
+        let journal = Arc::new(InMemoryJournal::new());
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.set_journal(journal.clone());
+        add(&book, 99, 10, Side::Buy, TimeInForce::Day).unwrap();
+        book.set_session_state(SessionState::Closed).unwrap();
+        book.set_session_state(SessionState::PreOpen).unwrap();
+
+        let mut recovered = OrderBook::<()>::new("BTC/USD");
+        recovered.replay_journal(journal.as_ref(), 0).unwrap();
+        assert_eq!(recovered.session_state(), SessionState::PreOpen);
+        assert_eq!(recovered.best_bid(), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(SessionState::PreOpen.to_string(), "PRE_OPEN");
+        assert_eq!(SessionState::Halted.to_string(), "HALTED");
+    }
+}
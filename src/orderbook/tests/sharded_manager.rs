@@ -0,0 +1,142 @@
+//! Unit tests for the sharded book manager.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::OrderBookError;
+    use crate::orderbook::config::BookConfig;
+    use crate::orderbook::sharded_manager::ShardedBookManager;
+    use crate::orderbook::symbol_config::{InstrumentType, SymbolConfig};
+    use crate::orderbook::trade::TradeEvent;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    fn symbols(count: usize) -> Vec<String> {
+        (0..count).map(|index| format!("SYM{index}")).collect()
+    }
+
+    #[test]
+    fn test_symbols_spread_over_shards() {
+        let manager = ShardedBookManager::<()>::new(4);
+        let mut used = [0usize; 4];
+        for symbol in symbols(200) {
+            let shard = manager.shard_of(&symbol);
+            assert_eq!(shard, manager.shard_of(&symbol));
+            used[shard] += 1;
+        }
+        assert!(used.iter().all(|&count| count > 0));
+        assert_eq!(manager.shard_count(), 4);
+    }
+
+    #[test]
+    fn test_per_symbol_order_is_preserved() {
+        let trades: Arc<Mutex<HashMap<String, Vec<u64>>>> = Arc::default();
+        let collected = Arc::clone(&trades);
+        let mut manager = ShardedBookManager::<()>::new(3)
+            .with_command_capacity(8)
+            .with_handler(Arc::new(move |event: &TradeEvent| {
+                collected
+                    .lock()
+                    .unwrap()
+                    .entry(event.symbol.clone())
+                    .or_default()
+                    .push(event.trade_result.match_result.executed_quantity());
+            }));
+        let symbols = symbols(12);
+        for symbol in &symbols {
+            manager.add_book(symbol);
+        }
+
+        for quantity in 1..=20 {
+            for symbol in &symbols {
+                manager
+                    .dispatch(symbol, move |book| {
+                        book.add_limit_order(
+                            OrderId::new(),
+                            100,
+                            quantity,
+                            Side::Sell,
+                            TimeInForce::Gtc,
+                            None,
+                        )
+                        .unwrap();
+                        book.submit_market_order(OrderId::new(), quantity, Side::Buy)
+                            .unwrap();
+                    })
+                    .unwrap();
+            }
+        }
+        let books = manager.shutdown();
+
+        assert_eq!(books.len(), 12);
+        let trades = trades.lock().unwrap();
+        let expected: Vec<u64> = (1..=20).collect();
+        for symbol in &symbols {
+            assert_eq!(trades[symbol], expected);
+        }
+    }
+
+    #[test]
+    fn test_execute_returns_results_and_errors() {
+        let mut manager = ShardedBookManager::<()>::new(2);
+        manager.add_book_with_config(
+            "BTC-PERP",
+            SymbolConfig::new(InstrumentType::Future)
+                .with_book_config(BookConfig::default().with_tick_size(5)),
+        );
+
+        let result = manager
+            .execute("BTC-PERP", |book| {
+                book.add_limit_order(OrderId::new(), 102, 1, Side::Buy, TimeInForce::Gtc, None)
+            })
+            .unwrap();
+        assert!(matches!(
+            result,
+            Err(OrderBookError::InvalidTickSize { .. })
+        ));
+        manager
+            .execute("BTC-PERP", |book| {
+                book.add_limit_order(OrderId::new(), 100, 1, Side::Buy, TimeInForce::Gtc, None)
+            })
+            .unwrap()
+            .unwrap();
+
+        let snapshot = manager.snapshot("BTC-PERP", 5).unwrap();
+        assert_eq!(snapshot.bids[0].price, 100);
+        assert!(matches!(
+            manager.execute("ETH-PERP", |book| book.best_bid()),
+            Err(OrderBookError::InvalidOperation { .. })
+        ));
+        assert!(manager.dispatch("ETH-PERP", |_| {}).is_err());
+        assert_eq!(
+            manager.registry().get("BTC-PERP").unwrap().instrument_type,
+            InstrumentType::Future
+        );
+    }
+
+    #[test]
+    fn test_remove_book() {
+        let mut manager = ShardedBookManager::<()>::new(2);
+        manager.add_book("BTC/USD");
+        manager.add_book("ETH/USD");
+        manager
+            .dispatch("BTC/USD", |book| {
+                book.add_limit_order(OrderId::new(), 100, 1, Side::Buy, TimeInForce::Gtc, None)
+                    .unwrap();
+            })
+            .unwrap();
+
+        let book = manager.remove_book("BTC/USD").unwrap();
+        assert_eq!(book.best_bid(), Some(100));
+        assert!(manager.remove_book("BTC/USD").is_none());
+        assert!(!manager.has_book("BTC/USD"));
+        assert_eq!(manager.symbols(), vec!["ETH/USD".to_string()]);
+        assert_eq!(manager.book_count(), 1);
+    }
+
+    #[test]
+    fn test_unused_manager_starts_no_threads() {
+        let manager = ShardedBookManager::<()>::new(8);
+        assert!(manager.shutdown().is_empty());
+    }
+}
@@ -10,6 +10,7 @@ mod tests {
             timestamp: 12345678,
             bids: Vec::new(),
             asks: Vec::new(),
+            extra_fields: Default::default(),
         }
     }
 
@@ -54,6 +55,7 @@ mod tests {
             timestamp: 12345678,
             bids: vec![bid1, bid2],
             asks: vec![ask1, ask2],
+            extra_fields: Default::default(),
         }
     }
 
@@ -294,6 +296,7 @@ mod tests {
             timestamp: 12345678,
             bids: vec![bid1, bid2],
             asks: Vec::new(),
+            extra_fields: Default::default(),
         };
 
         // Best bid should still be the highest price (1000), even though it's not first in array
@@ -437,6 +440,7 @@ mod tests_bis {
             timestamp: 12345678,
             bids: vec![bid1, bid3, bid2], // Deliberately unordered
             asks: vec![ask2, ask1, ask3], // Deliberately unordered
+            extra_fields: Default::default(),
         }
     }
 
@@ -510,10 +514,12 @@ mod tests_bis {
         let mut snapshot = create_unordered_snapshot();
 
         // Sort the bids by price in descending order
-        snapshot.bids.sort_by(|a, b| b.price.cmp(&a.price));
+        snapshot
+            .bids
+            .sort_by_key(|level| std::cmp::Reverse(level.price));
 
         // Sort the asks by price in ascending order
-        snapshot.asks.sort_by(|a, b| a.price.cmp(&b.price));
+        snapshot.asks.sort_by_key(|level| level.price);
 
         // Now the first element should be the best price
         let best_bid = snapshot
@@ -622,6 +628,7 @@ mod test_orderbook_snapshot {
             timestamp: 12345678,
             bids: vec![bid1, bid2],
             asks: vec![ask1, ask2],
+            extra_fields: Default::default(),
         };
 
         // Test total_bid_volume
@@ -650,6 +657,7 @@ mod test_snapshot_remaining {
             timestamp: 12345678,
             bids: Vec::new(),
             asks: Vec::new(),
+            extra_fields: Default::default(),
         };
 
         // Test volume methods on empty snapshot
@@ -684,6 +692,7 @@ mod test_snapshot_remaining {
             timestamp: 12345678,
             bids: vec![bid],
             asks: vec![ask],
+            extra_fields: Default::default(),
         };
 
         // Test methods that involve tracing
@@ -738,6 +747,7 @@ mod test_snapshot_specific {
             timestamp: 12345678,
             bids: vec![bid],
             asks: vec![ask],
+            extra_fields: Default::default(),
         };
 
         // Call functions that have trace output
@@ -753,4 +763,55 @@ mod test_snapshot_specific {
         assert_eq!(best_bid, Some((1000, 10)));
         assert_eq!(best_ask, Some((1010, 15)));
     }
+
+    #[test]
+    fn test_create_snapshot_into_reuses_buffers() {
+        use crate::OrderBook;
+        use pricelevel::{OrderId, Side, TimeInForce};
+
+        let book = OrderBook::<()>::new("TEST");
+        let mut resting = Vec::new();
+        for offset in 0..5 {
+            for side in [Side::Buy, Side::Sell] {
+                let id = OrderId::new();
+                let price = match side {
+                    Side::Buy => 1000 - offset,
+                    Side::Sell => 1001 + offset,
+                };
+                book.add_limit_order(id, price, 10 + offset, side, TimeInForce::Gtc, None)
+                    .unwrap();
+                resting.push(id);
+            }
+        }
+
+        let mut snapshot = OrderBookSnapshot {
+            symbol: "STALE".to_string(),
+            timestamp: 0,
+            bids: (0..5).map(PriceLevelSnapshot::new).collect(),
+            asks: Vec::new(),
+            extra_fields: Default::default(),
+        };
+        book.create_snapshot_into(3, &mut snapshot);
+        let fresh = book.create_snapshot(3);
+        assert_eq!(snapshot.symbol, "TEST");
+        let prices = |levels: &[PriceLevelSnapshot]| -> Vec<u64> {
+            levels.iter().map(|level| level.price).collect()
+        };
+        assert_eq!(prices(&snapshot.bids), vec![1000, 999, 998]);
+        assert_eq!(prices(&snapshot.asks), vec![1001, 1002, 1003]);
+        assert_eq!(prices(&snapshot.bids), prices(&fresh.bids));
+        assert_eq!(snapshot.bids[1].visible_quantity, 11);
+        assert_eq!(snapshot.bids[1].orders.len(), 1);
+
+        // Shrinking the book drops the level snapshots left over
+        for id in resting.drain(2..) {
+            book.cancel_order(id).unwrap();
+        }
+        let level_buffer = snapshot.bids.as_ptr();
+        book.create_snapshot_into(3, &mut snapshot);
+        assert_eq!(prices(&snapshot.bids), vec![1000]);
+        assert_eq!(prices(&snapshot.asks), vec![1001]);
+        assert_eq!(snapshot.bids.as_ptr(), level_buffer);
+        assert_eq!(snapshot.asks[0].order_count, 1);
+    }
 }
@@ -0,0 +1,192 @@
+//! Unit tests for the implied spread book.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::spread_book::{ImpliedQuote, SpreadBook, SpreadLeg};
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn add(book: &OrderBook<()>, price: u64, quantity: u64, side: Side) -> OrderId {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, side, TimeInForce::Gtc, None)
+            .unwrap();
+        id
+    }
+
+    fn legs() -> (Arc<OrderBook<()>>, Arc<OrderBook<()>>) {
+        let front = Arc::new(OrderBook::<()>::new("CL-DEC"));
+        let back = Arc::new(OrderBook::<()>::new("CL-JAN"));
+        add(&front, 7_000, 10, Side::Buy);
+        add(&front, 7_004, 8, Side::Sell);
+        add(&back, 6_950, 5, Side::Buy);
+        add(&back, 6_956, 12, Side::Sell);
+        (front, back)
+    }
+
+    #[test]
+    fn test_implied_quotes_from_legs() {
+        let (front, back) = legs();
+        let spread = SpreadBook::new("CL-DEC-JAN", front, back);
+
+        let quotes = spread.implied_quotes();
+        assert_eq!(
+            quotes.bid,
+            Some(ImpliedQuote {
+                price: 44,
+                quantity: 10
+            })
+        );
+        assert_eq!(
+            quotes.ask,
+            Some(ImpliedQuote {
+                price: 54,
+                quantity: 5
+            })
+        );
+        assert_eq!(spread.best_bid(), Some(44));
+        assert_eq!(spread.best_ask(), Some(54));
+    }
+
+    #[test]
+    fn test_implied_side_missing_without_leg_quote() {
+        let front = Arc::new(OrderBook::<()>::new("CL-DEC"));
+        let back = Arc::new(OrderBook::<()>::new("CL-JAN"));
+        add(&front, 7_000, 10, Side::Buy);
+        add(&back, 6_950, 5, Side::Buy);
+        let spread = SpreadBook::new("CL-DEC-JAN", front, back);
+
+        assert_eq!(spread.implied_bid(), None);
+        assert_eq!(spread.implied_ask(), None);
+    }
+
+    #[test]
+    fn test_negative_spread_prices() {
+        let (front, back) = legs();
+        let spread = SpreadBook::new("CL-JAN-DEC", back, front);
+
+        assert_eq!(spread.implied_bid().unwrap().price, 6_950 - 7_004);
+        assert_eq!(spread.implied_ask().unwrap().price, 6_956 - 7_000);
+    }
+
+    #[test]
+    fn test_recalculates_on_best_price_change() {
+        let (front, back) = legs();
+        let spread = SpreadBook::new("CL-DEC-JAN", front.clone(), back.clone());
+        assert_eq!(spread.implied_bid().unwrap().price, 44);
+        assert_eq!(spread.recalculations(), 1);
+
+        let better = add(&front, 7_002, 3, Side::Buy);
+        let bid = spread.implied_bid().unwrap();
+        assert_eq!((bid.price, bid.quantity), (46, 3));
+        assert_eq!(spread.recalculations(), 2);
+
+        front.cancel_order(better).unwrap();
+        assert_eq!(spread.implied_bid().unwrap().price, 44);
+
+        add(&back, 6_955, 4, Side::Sell);
+        let bid = spread.implied_bid().unwrap();
+        assert_eq!((bid.price, bid.quantity), (45, 4));
+    }
+
+    #[test]
+    fn test_deep_level_changes_do_not_recalculate() {
+        let (front, back) = legs();
+        let spread = SpreadBook::new("CL-DEC-JAN", front.clone(), back.clone());
+        spread.implied_quotes();
+        let before = spread.recalculations();
+
+        add(&front, 6_990, 20, Side::Buy);
+        add(&back, 6_970, 20, Side::Sell);
+        spread.implied_quotes();
+        assert_eq!(spread.recalculations(), before);
+    }
+
+    #[test]
+    fn test_trades_on_leg_update_implieds() {
+        let (front, back) = legs();
+        let spread = SpreadBook::new("CL-DEC-JAN", front.clone(), back);
+        spread.implied_quotes();
+
+        front
+            .submit_market_order(OrderId::new(), 10, Side::Sell)
+            .unwrap();
+        assert_eq!(spread.implied_bid(), None);
+        assert!(spread.implied_ask().is_some());
+    }
+
+    #[test]
+    fn test_outright_improves_best_prices() {
+        let (front, back) = legs();
+        let outright = Arc::new(OrderBook::<()>::new("CL-DEC-JAN"));
+        // Outright prices are offset by 1_000 so negative spreads can rest
+        add(&outright, 1_046, 2, Side::Buy);
+        add(&outright, 1_060, 2, Side::Sell);
+        let spread = SpreadBook::new("CL-DEC-JAN", front, back).with_outright(outright, 1_000);
+
+        assert_eq!(spread.best_bid(), Some(46));
+        assert_eq!(spread.best_ask(), Some(54));
+    }
+
+    #[test]
+    fn test_implied_leg_quotes_from_outright() {
+        let (front, back) = legs();
+        let outright = Arc::new(OrderBook::<()>::new("CL-DEC-JAN"));
+        add(&outright, 1_046, 2, Side::Buy);
+        add(&outright, 1_052, 7, Side::Sell);
+        let spread = SpreadBook::new("CL-DEC-JAN", front, back).with_outright(outright, 1_000);
+
+        let front_quotes = spread.implied_leg_quotes(SpreadLeg::Front);
+        assert_eq!(
+            front_quotes.bid,
+            Some(ImpliedQuote {
+                price: 46 + 6_950,
+                quantity: 2
+            })
+        );
+        assert_eq!(
+            front_quotes.ask,
+            Some(ImpliedQuote {
+                price: 52 + 6_956,
+                quantity: 7
+            })
+        );
+
+        let back_quotes = spread.implied_leg_quotes(SpreadLeg::Back);
+        assert_eq!(
+            back_quotes.bid,
+            Some(ImpliedQuote {
+                price: 7_000 - 52,
+                quantity: 7
+            })
+        );
+        assert_eq!(
+            back_quotes.ask,
+            Some(ImpliedQuote {
+                price: 7_004 - 46,
+                quantity: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_implied_leg_quotes_empty_without_outright() {
+        let (front, back) = legs();
+        let spread = SpreadBook::new("CL-DEC-JAN", front, back);
+        assert_eq!(spread.implied_leg_quotes(SpreadLeg::Front).bid, None);
+        assert_eq!(spread.implied_leg_quotes(SpreadLeg::Back).ask, None);
+    }
+
+    #[test]
+    fn test_drop_unsubscribes_from_legs() {
+        let (front, back) = legs();
+        let spread = SpreadBook::new("CL-DEC-JAN", front.clone(), back.clone());
+        assert_eq!(front.subscriber_counts().1, 1);
+        assert_eq!(back.subscriber_counts().1, 1);
+        assert_eq!(spread.leg(SpreadLeg::Front).symbol(), "CL-DEC");
+
+        drop(spread);
+        assert_eq!(front.subscriber_counts().1, 0);
+        assert_eq!(back.subscriber_counts().1, 0);
+    }
+}
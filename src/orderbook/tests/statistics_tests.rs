@@ -93,6 +93,48 @@ mod tests {
         assert!((stats.weighted_avg_price - 98.666).abs() < 0.01);
     }
 
+    #[test]
+    fn test_depth_statistics_both_matches_each_side() {
+        let book = setup_test_book();
+
+        let stats = book.depth_statistics_both(3);
+
+        assert_eq!(stats.bid, book.depth_statistics(Side::Buy, 3));
+        assert_eq!(stats.ask, book.depth_statistics(Side::Sell, 3));
+        assert_eq!(stats.mid_price, Some(100.5));
+    }
+
+    #[test]
+    fn test_depth_statistics_both_skew_metrics() {
+        let book = setup_test_book();
+
+        let stats = book.depth_statistics_both(0);
+
+        // 150 bid units against 120 ask units
+        assert_eq!(stats.volume_ratio, Some(1.25));
+        assert!((stats.volume_imbalance() - 30.0 / 270.0).abs() < 1e-9);
+
+        // Bid wavg = 14600 / 150 = 97.333..., ask wavg = 12350 / 120 = 102.916...
+        assert!((stats.bid_distance_from_mid.unwrap() - (100.5 - 14_600.0 / 150.0)).abs() < 1e-9);
+        assert!((stats.ask_distance_from_mid.unwrap() - (12_350.0 / 120.0 - 100.5)).abs() < 1e-9);
+        assert!(stats.distance_skew().unwrap() < 0.0);
+    }
+
+    #[test]
+    fn test_depth_statistics_both_one_sided_book() {
+        let book = OrderBook::<()>::new("TEST");
+        let _ = book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None);
+
+        let stats = book.depth_statistics_both(5);
+
+        assert_eq!(stats.bid.total_volume, 10);
+        assert!(stats.ask.is_empty());
+        assert_eq!(stats.mid_price, None);
+        assert_eq!(stats.volume_ratio, None);
+        assert_eq!(stats.distance_skew(), None);
+        assert_eq!(stats.volume_imbalance(), 1.0);
+    }
+
     #[test]
     fn test_buy_sell_pressure() {
         let book = setup_test_book();
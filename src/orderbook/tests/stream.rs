@@ -0,0 +1,92 @@
+//! Unit tests for the async trade and book change streams.
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::book_change_event::PriceLevelChangedEvent;
+    use crate::orderbook::stream::EVENT_STREAM_CAPACITY;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    fn trade(book: &OrderBook<()>, quantity: u64) {
+        book.add_limit_order(
+            OrderId::new(),
+            100,
+            quantity,
+            Side::Sell,
+            TimeInForce::Gtc,
+            None,
+        )
+        .unwrap();
+        book.submit_market_order(OrderId::new(), quantity, Side::Buy)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_every_trade_receiver_gets_every_trade() {
+        let book = OrderBook::<()>::new("TEST");
+        let mut first = book.subscribe_trades();
+        let mut second = book.subscribe_trades();
+        assert_eq!(book.subscriber_counts().0, 1);
+
+        trade(&book, 3);
+        trade(&book, 5);
+
+        for receiver in [&mut first, &mut second] {
+            let quantities: Vec<u64> = std::iter::from_fn(|| receiver.try_recv().ok())
+                .map(|event| event.trade_result.match_result.executed_quantity())
+                .collect();
+            assert_eq!(quantities, vec![3, 5]);
+        }
+    }
+
+    #[test]
+    fn test_trade_receiver_only_sees_later_trades() {
+        let book = OrderBook::<()>::new("TEST");
+        drop(book.subscribe_trades());
+        trade(&book, 3);
+
+        let mut late = book.subscribe_trades();
+        assert_eq!(late.try_recv().unwrap_err(), TryRecvError::Empty);
+        trade(&book, 4);
+        assert_eq!(late.try_recv().unwrap().symbol, "TEST");
+    }
+
+    #[test]
+    fn test_book_change_stream() {
+        let book = OrderBook::<()>::new("TEST");
+        let mut changes = book.subscribe_book_changes();
+
+        let id = OrderId::new();
+        book.add_limit_order(id, 99, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.cancel_order(id).unwrap();
+
+        let received: Vec<PriceLevelChangedEvent> =
+            std::iter::from_fn(|| changes.try_recv().ok()).collect();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].quantity, 10);
+        assert_eq!(received[1].quantity, 0);
+    }
+
+    #[test]
+    fn test_slow_receiver_lags_instead_of_blocking() {
+        let book = OrderBook::<()>::new("TEST");
+        let mut changes = book.subscribe_book_changes();
+
+        for price in 0..(EVENT_STREAM_CAPACITY as u64 + 10) {
+            book.add_limit_order(
+                OrderId::new(),
+                price + 1,
+                1,
+                Side::Buy,
+                TimeInForce::Gtc,
+                None,
+            )
+            .unwrap();
+        }
+
+        assert_eq!(changes.try_recv().unwrap_err(), TryRecvError::Lagged(10));
+        assert!(changes.try_recv().is_ok());
+    }
+}
@@ -0,0 +1,114 @@
+//! Unit tests for multi-subscriber listener registration.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::book_change_event::PriceLevelChangedEvent;
+    use crate::orderbook::owner::OwnerId;
+    use crate::orderbook::trade::{TradeListener, TradeResult};
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    fn counter() -> (Arc<AtomicUsize>, TradeListener) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let sink = Arc::clone(&count);
+        (
+            count,
+            Arc::new(move |_: &TradeResult| {
+                sink.fetch_add(1, Ordering::SeqCst);
+            }),
+        )
+    }
+
+    fn trade(book: &OrderBook<()>) {
+        book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.submit_market_order(OrderId::new(), 10, Side::Buy)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_multiple_trade_subscribers_through_shared_book() {
+        let mut book = OrderBook::<()>::new("TEST");
+        let (primary, listener) = counter();
+        book.set_trade_listener(listener);
+        let book = Arc::new(book);
+
+        let (first, listener) = counter();
+        book.subscribe_trade_listener(listener);
+        let (second, listener) = counter();
+        let shared = Arc::clone(&book);
+        std::thread::spawn(move || shared.subscribe_trade_listener(listener))
+            .join()
+            .unwrap();
+        assert_eq!(book.subscriber_counts(), (2, 0));
+
+        trade(&book);
+        assert_eq!(primary.load(Ordering::SeqCst), 1);
+        assert_eq!(first.load(Ordering::SeqCst), 1);
+        assert_eq!(second.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_unsubscribe_trade_listener_by_id() {
+        let book = OrderBook::<()>::new("TEST");
+        let (kept, listener) = counter();
+        book.subscribe_trade_listener(listener);
+        let (removed, listener) = counter();
+        let id = book.subscribe_trade_listener(listener);
+
+        assert!(book.unsubscribe_trade_listener(id));
+        assert!(!book.unsubscribe_trade_listener(id));
+
+        trade(&book);
+        assert_eq!(kept.load(Ordering::SeqCst), 1);
+        assert_eq!(removed.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_price_level_subscribers_receive_every_change() {
+        let book = OrderBook::<()>::new("TEST");
+        let events = Arc::new(Mutex::new(Vec::<PriceLevelChangedEvent>::new()));
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let sink = Arc::clone(&events);
+            ids.push(book.subscribe_price_level_listener(Arc::new(move |event| {
+                sink.lock().unwrap().push(event)
+            })));
+        }
+        assert_ne!(ids[0], ids[1]);
+
+        book.add_limit_order(OrderId::new(), 99, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.update_quotes(OwnerId(1), 98, 5, 101, 5).unwrap();
+        // One direct change plus two batched changes, delivered to both subscribers
+        assert_eq!(events.lock().unwrap().len(), 6);
+
+        book.unsubscribe_price_level_listener(ids[0]);
+        book.cancel_quotes(OwnerId(1)).unwrap();
+        assert_eq!(events.lock().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_listener_can_unsubscribe_itself() {
+        let book = Arc::new(OrderBook::<()>::new("TEST"));
+        let count = Arc::new(AtomicUsize::new(0));
+        let own_id = Arc::new(Mutex::new(None));
+
+        let weak = Arc::downgrade(&book);
+        let sink = Arc::clone(&count);
+        let slot = Arc::clone(&own_id);
+        let id = book.subscribe_trade_listener(Arc::new(move |_| {
+            sink.fetch_add(1, Ordering::SeqCst);
+            if let (Some(book), Some(id)) = (weak.upgrade(), *slot.lock().unwrap()) {
+                book.unsubscribe_trade_listener(id);
+            }
+        }));
+        *own_id.lock().unwrap() = Some(id);
+
+        trade(&book);
+        trade(&book);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,219 @@
+//! Unit tests for the per-owner surveillance metrics and alerts.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::owner::OwnerId;
+    use crate::orderbook::surveillance::{
+        Surveillance, SurveillanceAlert, SurveillanceConfig, SurveillanceMetric,
+    };
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    const OWNER: OwnerId = OwnerId(1);
+
+    fn order(price: u64, side: Side) -> OrderType<()> {
+        OrderType::Standard {
+            id: OrderId::new(),
+            price,
+            quantity: 10,
+            side,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        }
+    }
+
+    fn setup_book(
+        config: SurveillanceConfig,
+    ) -> (
+        OrderBook<()>,
+        Arc<ManualClock>,
+        Arc<Mutex<Vec<SurveillanceAlert>>>,
+    ) {
+        let mut book = OrderBook::new("TEST");
+        let clock = Arc::new(ManualClock::new(100_000));
+        book.set_clock(clock.clone());
+        book.enable_surveillance(config);
+        let alerts = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&alerts);
+        book.set_surveillance_listener(Arc::new(move |alert| sink.lock().unwrap().push(*alert)));
+        (book, clock, alerts)
+    }
+
+    #[test]
+    fn test_cancel_ages_classify_fleeting_and_flickering_orders() {
+        let surveillance = Surveillance::new(
+            SurveillanceConfig::new(60_000)
+                .with_fleeting_cancel_ms(500)
+                .with_flicker_ms(100),
+        );
+        let (touch, deep, slow, unknown) = (
+            OrderId::new(),
+            OrderId::new(),
+            OrderId::new(),
+            OrderId::new(),
+        );
+        surveillance.record_order(10_000, OWNER, touch, true);
+        surveillance.record_order(10_000, OWNER, deep, false);
+        surveillance.record_order(10_000, OWNER, slow, true);
+        surveillance.record_cancel(10_050, OWNER, touch);
+        surveillance.record_cancel(10_050, OWNER, deep);
+        surveillance.record_cancel(10_800, OWNER, slow);
+        surveillance.record_cancel(10_800, OWNER, unknown);
+
+        let activity = surveillance.activity(OWNER, 10_800);
+        assert_eq!(activity.orders, 3);
+        assert_eq!(activity.cancels, 4);
+        assert_eq!(activity.fleeting_cancels, 2);
+        assert_eq!(activity.flickers, 1);
+        assert_eq!(activity.fleeting_cancel_ratio(), Some(2.0 / 3.0));
+    }
+
+    #[test]
+    fn test_activity_rolls_out_of_the_window() {
+        let surveillance = Surveillance::new(SurveillanceConfig::new(1_000));
+        surveillance.record_order(10_000, OWNER, OrderId::new(), false);
+        surveillance.record_execution(10_500, OWNER);
+        surveillance.record_order(10_500, OwnerId(2), OrderId::new(), false);
+
+        assert_eq!(surveillance.activity(OWNER, 10_900).orders, 1);
+        let activity = surveillance.activity(OWNER, 11_000);
+        assert_eq!((activity.orders, activity.trades), (0, 1));
+        assert_eq!(activity.order_to_trade_ratio(), 0.0);
+
+        assert_eq!(surveillance.activities(11_000).len(), 2);
+        let remaining = surveillance.activities(11_500);
+        assert!(remaining.is_empty());
+
+        surveillance.record_order(12_000, OWNER, OrderId::new(), false);
+        surveillance.clear();
+        assert_eq!(surveillance.activity(OWNER, 12_000).orders, 0);
+    }
+
+    #[test]
+    fn test_order_to_trade_alert_fires_once_until_rearmed() {
+        let surveillance = Surveillance::new(
+            SurveillanceConfig::new(60_000)
+                .with_min_orders(3)
+                .with_max_order_to_trade_ratio(2.0),
+        );
+        let mut alerts = Vec::new();
+        for timestamp in 1..=2 {
+            alerts.extend(surveillance.record_order(timestamp, OWNER, OrderId::new(), false));
+        }
+        // Two orders without a trade is a ratio of 2, but under the minimum order count
+        assert!(alerts.is_empty());
+
+        alerts.extend(surveillance.record_order(3, OWNER, OrderId::new(), false));
+        alerts.extend(surveillance.record_order(4, OWNER, OrderId::new(), false));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].metric, SurveillanceMetric::OrderToTradeRatio);
+        assert_eq!(alerts[0].value, 3.0);
+        assert_eq!(alerts[0].threshold, 2.0);
+        assert_eq!(alerts[0].timestamp, 3);
+
+        // Back under the threshold, then over it again
+        assert!(surveillance.record_execution(5, OWNER).is_empty());
+        assert!(surveillance.record_execution(5, OWNER).is_empty());
+        let rearmed: Vec<_> = (6..=7)
+            .flat_map(|timestamp| {
+                surveillance.record_order(timestamp, OWNER, OrderId::new(), false)
+            })
+            .collect();
+        assert_eq!(rearmed.len(), 1);
+        assert_eq!(rearmed[0].value, 2.5);
+    }
+
+    #[test]
+    fn test_book_reports_flickering_quotes() {
+        let (book, clock, alerts) = setup_book(
+            SurveillanceConfig::new(60_000)
+                .with_flicker_ms(100)
+                .with_max_flickers(1),
+        );
+        book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+
+        // Behind the touch: fleeting but not flickering
+        let behind = book
+            .add_order_with_owner(order(99, Side::Buy), OWNER)
+            .unwrap();
+        clock.advance(10);
+        book.cancel_order(behind.id()).unwrap();
+
+        for _ in 0..2 {
+            let quote = book
+                .add_order_with_owner(order(100, Side::Buy), OWNER)
+                .unwrap();
+            clock.advance(10);
+            book.cancel_order(quote.id()).unwrap();
+        }
+
+        let activity = book.owner_activity(OWNER).unwrap();
+        assert_eq!(activity.orders, 3);
+        assert_eq!(activity.cancels, 3);
+        assert_eq!(activity.fleeting_cancels, 3);
+        assert_eq!(activity.flickers, 2);
+
+        let alerts = alerts.lock().unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].owner, OWNER);
+        assert_eq!(alerts[0].metric, SurveillanceMetric::QuoteFlicker);
+        assert_eq!(alerts[0].value, 2.0);
+    }
+
+    #[test]
+    fn test_book_counts_executions_of_makers_and_takers() {
+        let (book, _clock, _alerts) = setup_book(SurveillanceConfig::new(60_000));
+        book.add_order_with_owner(order(100, Side::Sell), OWNER)
+            .unwrap();
+        book.add_order_with_owner(order(100, Side::Buy), OwnerId(2))
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 101, 10, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+
+        assert_eq!(book.owner_activity(OWNER).unwrap().trades, 1);
+        let taker = book.owner_activity(OwnerId(2)).unwrap();
+        assert_eq!((taker.orders, taker.trades), (1, 1));
+        assert_eq!(taker.order_to_trade_ratio(), 1.0);
+    }
+
+    #[test]
+    fn test_book_fleeting_cancel_ratio_alert() {
+        let (book, clock, alerts) = setup_book(
+            SurveillanceConfig::new(60_000)
+                .with_min_orders(4)
+                .with_fleeting_cancel_ms(200)
+                .with_max_fleeting_cancel_ratio(0.5),
+        );
+        for delay in [1_000, 50, 50, 50] {
+            let resting = book
+                .add_order_with_owner(order(90, Side::Buy), OWNER)
+                .unwrap();
+            clock.advance(delay);
+            book.cancel_order(resting.id()).unwrap();
+        }
+
+        let alerts = alerts.lock().unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].metric, SurveillanceMetric::FleetingCancelRatio);
+        assert_eq!(alerts[0].value, 0.75);
+    }
+
+    #[test]
+    fn test_ownerless_orders_and_disabled_surveillance() {
+        let (mut book, _clock, alerts) =
+            setup_book(SurveillanceConfig::new(60_000).with_max_flickers(0));
+        let id = OrderId::new();
+        book.add_limit_order(id, 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.cancel_order(id).unwrap();
+        assert!(alerts.lock().unwrap().is_empty());
+
+        book.disable_surveillance();
+        assert!(book.surveillance().is_none());
+        assert!(book.owner_activity(OWNER).is_none());
+    }
+}
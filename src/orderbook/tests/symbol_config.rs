@@ -0,0 +1,95 @@
+//! Unit tests for the symbol configuration registry.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::config::BookConfig;
+    use crate::orderbook::error::OrderBookError;
+    use crate::orderbook::manager::{BookManager, BookManagerStd};
+    use crate::orderbook::symbol_config::{InstrumentType, SymbolConfig, SymbolRegistry};
+    use pricelevel::{OrderId, Side, TimeInForce};
+
+    fn future() -> SymbolConfig {
+        SymbolConfig::new(InstrumentType::Future)
+            .with_book_config(BookConfig::default().with_tick_size(5).with_lot_size(10))
+    }
+
+    #[test]
+    fn test_registry_lookup() {
+        let mut registry = SymbolRegistry::new();
+        assert!(registry.is_empty());
+        assert!(registry.register("BTC-PERP", future()).is_none());
+        registry.register("ETH-PERP", future());
+        registry.register("BTC/USD", SymbolConfig::default());
+        registry.register("BTC-C-50000", SymbolConfig::new(InstrumentType::Option));
+
+        assert_eq!(registry.len(), 4);
+        assert!(registry.contains("BTC/USD"));
+        assert_eq!(registry.get("BTC-PERP").unwrap().book.tick_size, Some(5));
+        assert_eq!(
+            registry.symbols_of_type(InstrumentType::Future),
+            vec!["BTC-PERP".to_string(), "ETH-PERP".to_string()]
+        );
+        assert_eq!(
+            registry.symbols_of_type(InstrumentType::Spot),
+            vec!["BTC/USD".to_string()]
+        );
+
+        let replaced = registry.register("BTC/USD", future()).unwrap();
+        assert_eq!(replaced.instrument_type, InstrumentType::Spot);
+        assert_eq!(registry.remove("BTC/USD"), Some(future()));
+        assert!(registry.get("BTC/USD").is_none());
+    }
+
+    #[test]
+    fn test_manager_books_enforce_their_config() {
+        let mut manager = BookManagerStd::<()>::new();
+        manager.add_book_with_config("BTC-PERP", future());
+        manager.add_book("BTC/USD");
+
+        let perp = manager.get_book("BTC-PERP").unwrap();
+        assert_eq!(*perp.config(), future().book);
+        let result =
+            perp.add_limit_order(OrderId::new(), 102, 10, Side::Buy, TimeInForce::Gtc, None);
+        assert!(matches!(
+            result,
+            Err(OrderBookError::InvalidTickSize { .. })
+        ));
+
+        let spot = manager.get_book("BTC/USD").unwrap();
+        assert!(
+            spot.add_limit_order(OrderId::new(), 102, 3, Side::Buy, TimeInForce::Gtc, None)
+                .is_ok()
+        );
+
+        assert_eq!(
+            manager.symbol_config("BTC-PERP").unwrap().instrument_type,
+            InstrumentType::Future
+        );
+        assert_eq!(
+            manager.symbol_config("BTC/USD"),
+            Some(&SymbolConfig::default())
+        );
+        assert_eq!(manager.registry().len(), 2);
+    }
+
+    #[test]
+    fn test_removing_a_book_unregisters_its_symbol() {
+        let mut manager = BookManagerStd::<()>::new();
+        manager.add_book_with_config("BTC-PERP", future());
+        manager.remove_book("BTC-PERP").unwrap();
+
+        assert!(manager.symbol_config("BTC-PERP").is_none());
+        assert!(manager.registry().is_empty());
+    }
+
+    #[test]
+    fn test_config_serialization() {
+        let config = future();
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<SymbolConfig>(&json).unwrap(), config);
+
+        let legacy = r#"{"tick_size":5,"lot_size":null,"min_notional":null}"#;
+        let book: BookConfig = serde_json::from_str(legacy).unwrap();
+        assert_eq!(book.min_price, None);
+    }
+}
@@ -0,0 +1,125 @@
+//! Unit tests for the time-weighted spread, depth and imbalance metrics.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::book_change_event::BboChangedEvent;
+    use crate::orderbook::time_weighted::TimeWeightedLiquidity;
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn bbo(bid: Option<(u64, u64)>, ask: Option<(u64, u64)>) -> BboChangedEvent {
+        BboChangedEvent {
+            bid_price: bid.map(|(price, _)| price),
+            bid_quantity: bid.map_or(0, |(_, quantity)| quantity),
+            ask_price: ask.map(|(price, _)| price),
+            ask_quantity: ask.map_or(0, |(_, quantity)| quantity),
+        }
+    }
+
+    #[test]
+    fn test_states_are_weighted_by_duration() {
+        let mut tracker =
+            TimeWeightedLiquidity::new(60_000, 10_000, bbo(Some((100, 30)), Some((101, 10))));
+        tracker.observe(19_000, bbo(Some((99, 10)), Some((109, 10))));
+
+        let stats = tracker.stats(10_000, 20_000);
+        assert_eq!(stats.observed_ms, 10_000);
+        assert_eq!(stats.two_sided_ms, 10_000);
+        assert!((stats.avg_spread.unwrap() - 1.9).abs() < 1e-9);
+        let bps = 0.9 * 1.0 / 100.5 * 10_000.0 + 0.1 * 10.0 / 104.0 * 10_000.0;
+        assert!((stats.avg_spread_bps.unwrap() - bps).abs() < 1e-9);
+        assert!((stats.avg_bid_depth - 28.0).abs() < 1e-9);
+        assert!((stats.avg_ask_depth - 10.0).abs() < 1e-9);
+        assert!((stats.avg_top_depth - 38.0).abs() < 1e-9);
+        assert!((stats.avg_imbalance.unwrap() - 0.45).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_window_only_covers_its_own_time() {
+        let mut tracker =
+            TimeWeightedLiquidity::new(60_000, 10_000, bbo(Some((100, 10)), Some((110, 10))));
+        tracker.observe(15_000, bbo(Some((100, 10)), Some((102, 10))));
+
+        assert_eq!(tracker.stats(1_000, 16_000).avg_spread, Some(2.0));
+        assert_eq!(tracker.stats(2_000, 16_000).avg_spread, Some(6.0));
+        // The tracker is younger than the window
+        let stats = tracker.stats(30_000, 16_000);
+        assert_eq!(stats.observed_ms, 6_000);
+        assert!((stats.avg_spread.unwrap() - 52.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_one_sided_time_is_left_out_of_the_spread() {
+        let mut tracker =
+            TimeWeightedLiquidity::new(60_000, 10_000, bbo(Some((100, 10)), Some((104, 10))));
+        tracker.observe(11_000, bbo(Some((100, 10)), None));
+        tracker.observe(13_000, bbo(None, None));
+
+        let stats = tracker.stats(10_000, 14_000);
+        assert_eq!(stats.observed_ms, 4_000);
+        assert_eq!(stats.two_sided_ms, 1_000);
+        assert_eq!(stats.avg_spread, Some(4.0));
+        assert_eq!(stats.avg_bid_depth, 7.5);
+        assert_eq!(stats.avg_ask_depth, 2.5);
+        // Three seconds with quantity: balanced for one, all bids for two
+        assert!((stats.avg_imbalance.unwrap() - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_retention_caps_the_window_and_drops_old_states() {
+        let mut tracker =
+            TimeWeightedLiquidity::new(1_000, 10_000, bbo(Some((100, 10)), Some((120, 10))));
+        tracker.observe(10_500, bbo(Some((100, 10)), Some((101, 10))));
+        tracker.observe(12_000, bbo(Some((100, 10)), Some((102, 10))));
+
+        let stats = tracker.stats(60_000, 12_500);
+        assert_eq!(stats.observed_ms, 1_000);
+        assert_eq!(stats.avg_spread, Some(1.5));
+    }
+
+    #[test]
+    fn test_unchanged_and_out_of_order_states_are_ignored() {
+        let initial = bbo(Some((100, 10)), Some((102, 10)));
+        let mut tracker = TimeWeightedLiquidity::new(60_000, 10_000, initial);
+        tracker.observe(11_000, initial);
+        tracker.observe(12_000, bbo(Some((100, 10)), Some((104, 10))));
+        tracker.observe(11_500, bbo(Some((100, 10)), Some((200, 10))));
+
+        assert_eq!(tracker.stats(10_000, 14_000).avg_spread, Some(3.0));
+    }
+
+    #[test]
+    fn test_book_feeds_the_tracker_from_bbo_changes() {
+        let mut book = OrderBook::<()>::new("TEST");
+        let clock = Arc::new(ManualClock::new(100_000));
+        book.set_clock(clock.clone());
+        book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 101, 30, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.enable_time_weighted_metrics(60_000);
+
+        clock.advance(3_000);
+        book.submit_market_order(OrderId::new(), 10, Side::Sell)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 99, 30, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        clock.advance(1_000);
+
+        let stats = book.time_weighted_stats(10_000).unwrap();
+        assert_eq!(stats.observed_ms, 4_000);
+        assert_eq!(stats.avg_spread, Some(1.25));
+        assert_eq!(book.avg_top_depth(10_000), Some(45.0));
+        let bps = book.avg_spread_bps(10_000).unwrap();
+        let expected = 0.75 / 100.5 * 10_000.0 + 0.5 / 100.0 * 10_000.0;
+        assert!((bps - expected).abs() < 1e-9);
+        let imbalance = book.avg_top_imbalance(10_000).unwrap();
+        assert!((imbalance - (-0.5 * 0.75)).abs() < 1e-9);
+
+        book.disable_time_weighted_metrics();
+        assert!(book.time_weighted_stats(10_000).is_none());
+        assert!(book.avg_spread_bps(10_000).is_none());
+    }
+}
@@ -0,0 +1,84 @@
+//! Unit tests for maker/taker attribution of trades.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::owner::OwnerId;
+    use crate::orderbook::trade::{TradeInfo, TradeResult};
+    use pricelevel::{OrderId, OrderType, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
+
+    fn order(id: OrderId, side: Side, price: u64, quantity: u64) -> OrderType<()> {
+        OrderType::Standard {
+            id,
+            price,
+            quantity,
+            side,
+            timestamp: 0,
+            time_in_force: TimeInForce::Gtc,
+            extra_fields: (),
+        }
+    }
+
+    fn recorded_book() -> (OrderBook<()>, Arc<Mutex<Vec<TradeResult>>>) {
+        let book = OrderBook::<()>::new("BTC/USD");
+        let trades: Arc<Mutex<Vec<TradeResult>>> = Arc::default();
+        let sink = trades.clone();
+        book.subscribe_trade_listener(Arc::new(move |trade| {
+            sink.lock().unwrap().push(trade.clone());
+        }));
+        (book, trades)
+    }
+
+    #[test]
+    fn test_transactions_carry_maker_and_taker_attribution() {
+        let (book, trades) = recorded_book();
+        let owned_maker = OrderId::new();
+        let anonymous_maker = OrderId::new();
+        book.add_order_with_owner(order(owned_maker, Side::Sell, 100, 5), OwnerId(1))
+            .unwrap();
+        book.add_order(order(anonymous_maker, Side::Sell, 101, 5))
+            .unwrap();
+
+        let taker = OrderId::new();
+        book.add_order_with_owner(order(taker, Side::Buy, 101, 10), OwnerId(2))
+            .unwrap();
+
+        let trades = trades.lock().unwrap();
+        assert_eq!(trades.len(), 1);
+        let trade = &trades[0];
+        assert_eq!(trade.taker_order_id(), taker);
+        assert_eq!(trade.aggressor_side(), Some(Side::Buy));
+
+        let info = TradeInfo::from(trade);
+        assert_eq!(info.transactions.len(), 2);
+        let first = &info.transactions[0];
+        assert_eq!(first.maker_order_id, owned_maker.to_string());
+        assert_eq!(first.taker_order_id, taker.to_string());
+        assert_eq!(first.aggressor_side, Side::Buy);
+        assert_eq!(first.maker_owner, Some(OwnerId(1)));
+        assert_eq!(first.taker_owner, Some(OwnerId(2)));
+
+        let second = &info.transactions[1];
+        assert_eq!(second.maker_order_id, anonymous_maker.to_string());
+        assert_eq!(second.maker_owner, None);
+        assert_eq!(second.taker_owner, Some(OwnerId(2)));
+    }
+
+    #[test]
+    fn test_sell_aggressor_without_owners() {
+        let (book, trades) = recorded_book();
+        book.add_order(order(OrderId::new(), Side::Buy, 100, 5))
+            .unwrap();
+        book.submit_market_order(OrderId::new(), 5, Side::Sell)
+            .unwrap();
+
+        let trades = trades.lock().unwrap();
+        let trade = &trades[0];
+        assert_eq!(trade.aggressor_side(), Some(Side::Sell));
+        let info = TradeInfo::from(trade);
+        assert_eq!(info.transactions[0].aggressor_side, Side::Sell);
+        assert_eq!(info.transactions[0].maker_owner, None);
+        assert_eq!(info.transactions[0].taker_owner, None);
+    }
+}
@@ -0,0 +1,98 @@
+//! Unit tests for the trade tape and its rolling-window statistics.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::trade_tape::{TapeEntry, TapeWindow, TradeTape};
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn entry(timestamp: u64, price: u64, quantity: u64, aggressor_side: Side) -> TapeEntry {
+        TapeEntry {
+            timestamp,
+            price,
+            quantity,
+            aggressor_side,
+            maker_order_id: OrderId::new(),
+            taker_order_id: OrderId::new(),
+        }
+    }
+
+    #[test]
+    fn test_tape_drops_oldest_entries() {
+        let tape = TradeTape::new(2);
+        tape.record(entry(1, 100, 1, Side::Buy));
+        tape.record(entry(2, 101, 2, Side::Buy));
+        tape.record(entry(3, 102, 3, Side::Sell));
+
+        assert_eq!(tape.len(), 2);
+        let prices: Vec<u64> = tape.entries().iter().map(|entry| entry.price).collect();
+        assert_eq!(prices, vec![101, 102]);
+        assert_eq!(tape.recent(1)[0].price, 102);
+        assert_eq!(tape.recent(10).len(), 2);
+
+        let empty = TradeTape::new(0);
+        empty.record(entry(1, 100, 1, Side::Buy));
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_window_statistics() {
+        let tape = TradeTape::new(10);
+        tape.record(entry(1_000, 100, 10, Side::Buy));
+        tape.record(entry(2_000, 110, 30, Side::Sell));
+        tape.record(entry(3_000, 120, 10, Side::Buy));
+
+        let all = tape.stats(TapeWindow::All, 3_000);
+        assert_eq!(all.trade_count, 3);
+        assert_eq!(all.volume, 50);
+        assert_eq!(all.notional, 5_500);
+        assert_eq!(all.vwap, Some(110.0));
+        assert_eq!((all.buy_volume, all.sell_volume), (20, 30));
+        assert_eq!(all.buy_ratio(), Some(0.4));
+        assert_eq!(all.largest_trade.unwrap().price, 110);
+
+        let recent = tape.stats(TapeWindow::LastMillis(1_000), 3_000);
+        assert_eq!(recent.trade_count, 2);
+        assert_eq!(recent.volume, 40);
+
+        let last = tape.stats(TapeWindow::LastTrades(1), 3_000);
+        assert_eq!(last.vwap, Some(120.0));
+        assert_eq!(last.buy_sell_ratio(), None);
+
+        let none = tape.stats(TapeWindow::LastMillis(10), 10_000);
+        assert_eq!(none.trade_count, 0);
+        assert_eq!(none.vwap, None);
+        assert_eq!(none.buy_ratio(), None);
+    }
+
+    #[test]
+    fn test_book_records_executions_with_clock_time() {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        assert!(book.tape_stats(TapeWindow::All).is_none());
+        let clock = Arc::new(ManualClock::new(5_000));
+        book.set_clock(clock.clone());
+        book.enable_trade_tape(100);
+
+        book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.submit_market_order(OrderId::new(), 4, Side::Sell)
+            .unwrap();
+        clock.advance(2_000);
+        book.add_limit_order(OrderId::new(), 100, 6, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+
+        let tape = book.trade_tape().unwrap();
+        assert_eq!(tape.len(), 2);
+        assert_eq!(tape.entries()[0].timestamp, 5_000);
+        assert_eq!(tape.entries()[1].timestamp, 7_000);
+
+        let stats = book.tape_stats(TapeWindow::LastMillis(1_000)).unwrap();
+        assert_eq!(stats.trade_count, 1);
+        assert_eq!(stats.sell_volume, 6);
+
+        book.disable_trade_tape();
+        assert!(book.trade_tape().is_none());
+    }
+}
@@ -0,0 +1,83 @@
+//! Unit tests for the volume profile.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::book::OrderBook;
+    use crate::orderbook::volume_profile::{VolumeProfile, VolumeProfileSummary};
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_volume_is_bucketed_by_price() {
+        let profile = VolumeProfile::new(5);
+        profile.record(101, 10, Side::Buy);
+        profile.record(104, 5, Side::Sell);
+        profile.record(105, 7, Side::Buy);
+
+        let levels = profile.levels();
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].price, 100);
+        assert_eq!((levels[0].buy_volume, levels[0].sell_volume), (10, 5));
+        assert_eq!(levels[0].trade_count, 2);
+        assert_eq!(levels[1].volume(), 7);
+        assert_eq!(profile.total_volume(), 22);
+        assert_eq!(profile.bucket_of(109), 105);
+    }
+
+    #[test]
+    fn test_point_of_control_and_value_area() {
+        let profile = VolumeProfile::new(1);
+        for (price, quantity) in [(10, 5), (11, 10), (12, 30), (13, 20), (14, 15), (15, 20)] {
+            profile.record(price, quantity, Side::Buy);
+        }
+        assert_eq!(profile.point_of_control(), Some(12));
+        // 70 of 100: 12, then 13, 14 and 15 are each larger than 11
+        assert_eq!(profile.value_area(), Some((12, 15)));
+
+        let wide = VolumeProfile::new(1).with_value_area_ratio(1.0);
+        wide.record(10, 1, Side::Sell);
+        wide.record(20, 1, Side::Sell);
+        // Ties pick the lowest bucket
+        assert_eq!(wide.point_of_control(), Some(10));
+        assert_eq!(wide.value_area(), Some((10, 20)));
+    }
+
+    #[test]
+    fn test_empty_profile() {
+        let profile = VolumeProfile::new(10);
+        assert_eq!(profile.point_of_control(), None);
+        assert_eq!(profile.value_area(), None);
+        let summary = profile.summary();
+        assert_eq!(summary.total_volume, 0);
+        assert!(summary.levels.is_empty());
+    }
+
+    #[test]
+    fn test_profile_fed_by_trades_and_serialized() {
+        let profile = Arc::new(VolumeProfile::new(10));
+        let book = OrderBook::<()>::new("BTC/USD");
+        let sink = Arc::clone(&profile);
+        book.subscribe_trade_listener(Arc::new(move |trade| sink.record_trade(trade)));
+
+        book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), 95, 10, Side::Buy, TimeInForce::Gtc, None)
+            .unwrap();
+        book.submit_market_order(OrderId::new(), 15, Side::Sell)
+            .unwrap();
+
+        let summary = profile.summary();
+        assert_eq!(summary.levels.len(), 2);
+        assert_eq!(summary.point_of_control, Some(100));
+        assert_eq!(summary.value_area_low, Some(90));
+        assert_eq!(summary.value_area_high, Some(100));
+        assert_eq!(summary.levels[1].sell_volume, 10);
+
+        let json = serde_json::to_string(&summary).unwrap();
+        let decoded: VolumeProfileSummary = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, summary);
+
+        profile.reset();
+        assert_eq!(profile.total_volume(), 0);
+    }
+}
@@ -0,0 +1,259 @@
+//! Time-weighted averages of the spread, top-of-book depth and imbalance over rolling windows.
+
+use super::book::{DEFAULT_BASIS_POINTS_MULTIPLIER, OrderBook};
+use super::book_change_event::BboChangedEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Time-weighted averages of the top of the book over a window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimeWeightedStats {
+    /// Time covered by the averages, in milliseconds, shorter than the
+    /// requested window while the tracker is younger than it
+    pub observed_ms: u64,
+    /// Time during which both sides were quoted, in milliseconds
+    pub two_sided_ms: u64,
+    /// Average spread in price units while both sides were quoted
+    pub avg_spread: Option<f64>,
+    /// Average spread in basis points of the mid price while both sides were quoted
+    pub avg_spread_bps: Option<f64>,
+    /// Average quantity at the best bid, counting an empty side as zero
+    pub avg_bid_depth: f64,
+    /// Average quantity at the best ask, counting an empty side as zero
+    pub avg_ask_depth: f64,
+    /// Average quantity at the best bid and the best ask together
+    pub avg_top_depth: f64,
+    /// Average of `(bid - ask) / (bid + ask)` over the quantities at the best
+    /// bid and ask, while the top of the book held any quantity
+    pub avg_imbalance: Option<f64>,
+}
+
+/// Top of the book since a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TopOfBook {
+    since: u64,
+    bbo: BboChangedEvent,
+}
+
+/// Rolling record of the best bid and offer, averaged by the time each state lasted.
+///
+/// Every state is weighted by how long it stayed on the book, so a spread that
+/// widens for a few milliseconds weighs far less than one that stays wide for
+/// minutes. States older than the retention are dropped as new ones are
+/// observed, and queried windows are capped to the retention.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::TimeWeightedLiquidity;
+/// use orderbook_rs::orderbook::book_change_event::BboChangedEvent;
+///
+/// let bbo = |ask_price, ask_quantity| BboChangedEvent {
+///     bid_price: Some(100),
+///     bid_quantity: 10,
+///     ask_price: Some(ask_price),
+///     ask_quantity,
+/// };
+/// let mut tracker = TimeWeightedLiquidity::new(60_000, 0, bbo(102, 10));
+/// tracker.observe(3_000, bbo(106, 30));
+///
+/// // 3 seconds at a spread of 2, then 1 second at 6
+/// let stats = tracker.stats(60_000, 4_000);
+/// assert_eq!(stats.observed_ms, 4_000);
+/// assert_eq!(stats.avg_spread, Some(3.0));
+/// assert_eq!(stats.avg_top_depth, 25.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeWeightedLiquidity {
+    retention_ms: u64,
+    states: VecDeque<TopOfBook>,
+}
+
+impl TimeWeightedLiquidity {
+    /// Create a tracker keeping `retention_ms` milliseconds of history, starting
+    /// from `initial` at `timestamp`
+    #[must_use]
+    pub fn new(retention_ms: u64, timestamp: u64, initial: BboChangedEvent) -> Self {
+        Self {
+            retention_ms,
+            states: VecDeque::from([TopOfBook {
+                since: timestamp,
+                bbo: initial,
+            }]),
+        }
+    }
+
+    /// Longest window that can be queried, in milliseconds
+    #[must_use]
+    pub fn retention_ms(&self) -> u64 {
+        self.retention_ms
+    }
+
+    /// Records a new best bid and offer at `timestamp`
+    pub fn observe(&mut self, timestamp: u64, bbo: BboChangedEvent) {
+        // Unchanged states and out-of-order observations carry no new information
+        if self
+            .states
+            .back()
+            .is_some_and(|last| last.bbo == bbo || timestamp < last.since)
+        {
+            return;
+        }
+        self.states.push_back(TopOfBook {
+            since: timestamp,
+            bbo,
+        });
+        self.prune(timestamp);
+    }
+
+    /// Time-weighted averages over the `window_ms` milliseconds ending at `now`
+    #[must_use]
+    pub fn stats(&self, window_ms: u64, now: u64) -> TimeWeightedStats {
+        let start = now.saturating_sub(window_ms.min(self.retention_ms));
+        let mut stats = TimeWeightedStats::default();
+        let (mut spread, mut spread_bps, mut imbalance) = (0.0, 0.0, 0.0);
+        let mut imbalance_ms = 0;
+
+        for (index, state) in self.states.iter().enumerate() {
+            let until = self
+                .states
+                .get(index + 1)
+                .map_or(now, |next| next.since)
+                .min(now);
+            let from = state.since.max(start);
+            if until <= from {
+                continue;
+            }
+            let duration = until - from;
+            let weight = duration as f64;
+            let bbo = state.bbo;
+
+            stats.observed_ms += duration;
+            stats.avg_bid_depth += bbo.bid_quantity as f64 * weight;
+            stats.avg_ask_depth += bbo.ask_quantity as f64 * weight;
+            if let (Some(bid), Some(ask)) = (bbo.bid_price, bbo.ask_price) {
+                let width = ask.saturating_sub(bid) as f64;
+                let mid = (bid as f64 + ask as f64) / 2.0;
+                stats.two_sided_ms += duration;
+                spread += width * weight;
+                if mid > 0.0 {
+                    spread_bps += width / mid * DEFAULT_BASIS_POINTS_MULTIPLIER * weight;
+                }
+            }
+            let depth = bbo.bid_quantity.saturating_add(bbo.ask_quantity);
+            if depth > 0 {
+                let (bid, ask) = (bbo.bid_quantity as f64, bbo.ask_quantity as f64);
+                imbalance_ms += duration;
+                imbalance += (bid - ask) / (bid + ask) * weight;
+            }
+        }
+
+        if stats.observed_ms > 0 {
+            let observed = stats.observed_ms as f64;
+            stats.avg_bid_depth /= observed;
+            stats.avg_ask_depth /= observed;
+            stats.avg_top_depth = stats.avg_bid_depth + stats.avg_ask_depth;
+        }
+        if stats.two_sided_ms > 0 {
+            let two_sided = stats.two_sided_ms as f64;
+            stats.avg_spread = Some(spread / two_sided);
+            stats.avg_spread_bps = Some(spread_bps / two_sided);
+        }
+        if imbalance_ms > 0 {
+            stats.avg_imbalance = Some(imbalance / imbalance_ms as f64);
+        }
+        stats
+    }
+
+    /// Drops the states that ended before the retention window
+    fn prune(&mut self, now: u64) {
+        let start = now.saturating_sub(self.retention_ms);
+        while self.states.len() > 1 && self.states[1].since <= start {
+            self.states.pop_front();
+        }
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Tracks time-weighted averages of the spread, top-of-book depth and
+    /// imbalance, keeping `retention_ms` milliseconds of history on the book clock
+    ///
+    /// Replaces any tracker enabled before, starting from the current best bid and offer.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{ManualClock, OrderBook};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    /// use std::sync::Arc;
+    ///
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// let clock = Arc::new(ManualClock::new(0));
+    /// book.set_clock(clock.clone());
+    /// book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+    /// let ask = OrderId::new();
+    /// book.add_limit_order(ask, 102, 10, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    /// book.enable_time_weighted_metrics(3_600_000);
+    ///
+    /// // The spread is 2 for 9 seconds, then 6 for 1 second
+    /// clock.advance(9_000);
+    /// book.cancel_order(ask).unwrap();
+    /// book.add_limit_order(OrderId::new(), 106, 10, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    /// clock.advance(1_000);
+    ///
+    /// let stats = book.time_weighted_stats(60_000).unwrap();
+    /// assert_eq!(stats.avg_spread, Some(2.4));
+    /// assert_eq!(book.avg_top_depth(60_000), Some(20.0));
+    /// ```
+    pub fn enable_time_weighted_metrics(&mut self, retention_ms: u64) {
+        let current = self.current_bbo();
+        *self.last_bbo.get_mut().unwrap_or_else(|e| e.into_inner()) = current;
+        self.time_weighted_liquidity = Some(Mutex::new(TimeWeightedLiquidity::new(
+            retention_ms,
+            self.clock.now_millis(),
+            current,
+        )));
+    }
+
+    /// Stop tracking time-weighted averages
+    pub fn disable_time_weighted_metrics(&mut self) {
+        self.time_weighted_liquidity = None;
+    }
+
+    /// Time-weighted averages over the last `window_ms` milliseconds of the book
+    /// clock, if tracked
+    #[must_use]
+    pub fn time_weighted_stats(&self, window_ms: u64) -> Option<TimeWeightedStats> {
+        self.time_weighted_liquidity.as_ref().map(|tracker| {
+            tracker
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .stats(window_ms, self.clock.now_millis())
+        })
+    }
+
+    /// Time-weighted average spread in basis points over the last `window_ms`
+    /// milliseconds, if tracked and both sides were quoted
+    #[must_use]
+    pub fn avg_spread_bps(&self, window_ms: u64) -> Option<f64> {
+        self.time_weighted_stats(window_ms)?.avg_spread_bps
+    }
+
+    /// Time-weighted average quantity at the best bid and ask together over the
+    /// last `window_ms` milliseconds, if tracked and any time was observed
+    #[must_use]
+    pub fn avg_top_depth(&self, window_ms: u64) -> Option<f64> {
+        self.time_weighted_stats(window_ms)
+            .filter(|stats| stats.observed_ms > 0)
+            .map(|stats| stats.avg_top_depth)
+    }
+
+    /// Time-weighted average top-of-book imbalance over the last `window_ms`
+    /// milliseconds, if tracked and the top of the book held any quantity
+    #[must_use]
+    pub fn avg_top_imbalance(&self, window_ms: u64) -> Option<f64> {
+        self.time_weighted_stats(window_ms)?.avg_imbalance
+    }
+}
@@ -3,8 +3,11 @@
    Email: jb@taunais.com
    Date: 2/10/25
 ******************************************************************************/
-use pricelevel::MatchResult;
+use crate::orderbook::fees::TransactionFee;
+use crate::orderbook::owner::OwnerId;
+use pricelevel::{MatchResult, OrderId, Side};
 use std::sync::Arc;
+use uuid::Uuid;
 
 /// Enhanced trade result that includes symbol information
 #[derive(Debug, Clone)]
@@ -13,16 +16,66 @@ pub struct TradeResult {
     pub symbol: String,
     /// The underlying match result from the pricelevel crate
     pub match_result: MatchResult,
+    /// Owner of the incoming (taker) order, if it was submitted with one
+    pub taker_owner: Option<OwnerId>,
+    /// Owners of the resting (maker) orders of the transactions that have one
+    pub maker_owners: Vec<(OrderId, OwnerId)>,
+    /// Fees of each transaction, in the same order, if the book charges fees
+    pub fees: Vec<TransactionFee>,
 }
 
 impl TradeResult {
-    /// Create a new TradeResult
+    /// Create a new TradeResult without owner attribution
     pub fn new(symbol: String, match_result: MatchResult) -> Self {
         Self {
             symbol,
             match_result,
+            taker_owner: None,
+            maker_owners: Vec::new(),
+            fees: Vec::new(),
         }
     }
+
+    /// Owner of the resting order `maker_order_id`, if it has one
+    #[must_use]
+    pub fn maker_owner(&self, maker_order_id: OrderId) -> Option<OwnerId> {
+        self.maker_owners
+            .iter()
+            .find(|(order_id, _)| *order_id == maker_order_id)
+            .map(|(_, owner)| *owner)
+    }
+
+    /// ID of the incoming (taker) order
+    #[must_use]
+    pub fn taker_order_id(&self) -> OrderId {
+        self.match_result.order_id
+    }
+
+    /// Side of the incoming order that took liquidity, if anything was executed
+    #[must_use]
+    pub fn aggressor_side(&self) -> Option<Side> {
+        self.match_result
+            .transactions
+            .transactions
+            .first()
+            .map(|transaction| transaction.taker_side)
+    }
+
+    /// Fees charged on the transaction `transaction_id`, if the book charges fees
+    #[must_use]
+    pub fn fee(&self, transaction_id: Uuid) -> Option<&TransactionFee> {
+        self.fees
+            .iter()
+            .find(|fee| fee.transaction_id == transaction_id)
+    }
+
+    /// Sum of the maker and taker fees of every transaction
+    #[must_use]
+    pub fn total_fees(&self) -> (i64, i64) {
+        self.fees.iter().fold((0, 0), |(maker, taker), fee| {
+            (maker + fee.maker_fee, taker + fee.taker_fee)
+        })
+    }
 }
 
 /// Trade listener specification using Arc for shared ownership
@@ -71,4 +124,50 @@ pub struct TransactionInfo {
     pub maker_order_id: String,
     /// Order ID of the taker (aggressive) side
     pub taker_order_id: String,
+    /// Side of the taker order
+    pub aggressor_side: Side,
+    /// Owner of the maker order, if it was submitted with one
+    pub maker_owner: Option<OwnerId>,
+    /// Owner of the taker order, if it was submitted with one
+    pub taker_owner: Option<OwnerId>,
+    /// Fee charged to the maker, negative for a rebate, or 0 without a fee schedule
+    pub maker_fee: i64,
+    /// Fee charged to the taker, negative for a rebate, or 0 without a fee schedule
+    pub taker_fee: i64,
+}
+
+impl From<&TradeResult> for TradeInfo {
+    fn from(trade_result: &TradeResult) -> Self {
+        let match_result = &trade_result.match_result;
+        let transactions = match_result
+            .transactions
+            .as_vec()
+            .iter()
+            .map(|tx| {
+                let fee = trade_result.fee(tx.transaction_id);
+                TransactionInfo {
+                    price: tx.price,
+                    quantity: tx.quantity,
+                    transaction_id: tx.transaction_id.to_string(),
+                    maker_order_id: tx.maker_order_id.to_string(),
+                    taker_order_id: tx.taker_order_id.to_string(),
+                    aggressor_side: tx.taker_side,
+                    maker_owner: trade_result.maker_owner(tx.maker_order_id),
+                    taker_owner: trade_result.taker_owner,
+                    maker_fee: fee.map_or(0, |fee| fee.maker_fee),
+                    taker_fee: fee.map_or(0, |fee| fee.taker_fee),
+                }
+            })
+            .collect();
+
+        TradeInfo {
+            symbol: trade_result.symbol.clone(),
+            order_id: match_result.order_id.to_string(),
+            executed_quantity: match_result.executed_quantity(),
+            remaining_quantity: match_result.remaining_quantity,
+            is_complete: match_result.is_complete,
+            transaction_count: match_result.transactions.transactions.len(),
+            transactions,
+        }
+    }
 }
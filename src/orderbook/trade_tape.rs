@@ -0,0 +1,249 @@
+//! Bounded tape of recent executions with rolling-window statistics.
+
+use super::book::OrderBook;
+use super::trade::TradeResult;
+use pricelevel::{OrderId, Side};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// One execution recorded on the tape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TapeEntry {
+    /// Time of the execution, in milliseconds of the book clock
+    pub timestamp: u64,
+    /// Execution price
+    pub price: u64,
+    /// Executed quantity
+    pub quantity: u64,
+    /// Side of the order that took liquidity
+    pub aggressor_side: Side,
+    /// ID of the resting order
+    pub maker_order_id: OrderId,
+    /// ID of the incoming order
+    pub taker_order_id: OrderId,
+}
+
+/// Executions a statistic is computed over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TapeWindow {
+    /// Every execution still on the tape
+    All,
+    /// Executions of the last given milliseconds
+    LastMillis(u64),
+    /// The given number of most recent executions
+    LastTrades(usize),
+}
+
+/// Statistics of the executions in a [`TapeWindow`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TapeStats {
+    /// Number of executions
+    pub trade_count: usize,
+    /// Total executed quantity
+    pub volume: u64,
+    /// Total executed notional, price times quantity
+    pub notional: u128,
+    /// Volume-weighted average price, or `None` without executions
+    pub vwap: Option<f64>,
+    /// Quantity executed by buy aggressors
+    pub buy_volume: u64,
+    /// Quantity executed by sell aggressors
+    pub sell_volume: u64,
+    /// Largest execution by quantity, the earliest one on ties
+    pub largest_trade: Option<TapeEntry>,
+}
+
+impl TapeStats {
+    /// Share of the volume taken by buy aggressors, between 0 and 1
+    #[must_use]
+    pub fn buy_ratio(&self) -> Option<f64> {
+        (self.volume > 0).then(|| self.buy_volume as f64 / self.volume as f64)
+    }
+
+    /// Buy aggressor volume divided by sell aggressor volume
+    #[must_use]
+    pub fn buy_sell_ratio(&self) -> Option<f64> {
+        (self.sell_volume > 0).then(|| self.buy_volume as f64 / self.sell_volume as f64)
+    }
+}
+
+/// Ring buffer of the most recent executions of a book.
+///
+/// Once `capacity` executions are recorded the oldest ones are dropped, so
+/// windows reaching further back only cover what is still on the tape.
+#[derive(Debug)]
+pub struct TradeTape {
+    capacity: usize,
+    entries: Mutex<VecDeque<TapeEntry>>,
+}
+
+impl TradeTape {
+    /// Create an empty tape keeping at most `capacity` executions
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Largest number of executions kept
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of executions on the tape
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether the tape holds no execution
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Appends an execution, dropping the oldest one when the tape is full
+    pub fn record(&self, entry: TapeEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.lock();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Appends every transaction of `trade`, executed at `timestamp`
+    pub fn record_trade(&self, trade: &TradeResult, timestamp: u64) {
+        for transaction in trade.match_result.transactions.as_vec() {
+            self.record(TapeEntry {
+                timestamp,
+                price: transaction.price,
+                quantity: transaction.quantity,
+                aggressor_side: transaction.taker_side,
+                maker_order_id: transaction.maker_order_id,
+                taker_order_id: transaction.taker_order_id,
+            });
+        }
+    }
+
+    /// Up to `count` most recent executions, oldest first
+    #[must_use]
+    pub fn recent(&self, count: usize) -> Vec<TapeEntry> {
+        let entries = self.lock();
+        entries
+            .iter()
+            .skip(entries.len().saturating_sub(count))
+            .copied()
+            .collect()
+    }
+
+    /// Every execution on the tape, oldest first
+    #[must_use]
+    pub fn entries(&self) -> Vec<TapeEntry> {
+        self.lock().iter().copied().collect()
+    }
+
+    /// Statistics of the executions in `window`, with `now` the current time in milliseconds
+    #[must_use]
+    pub fn stats(&self, window: TapeWindow, now: u64) -> TapeStats {
+        let entries = self.lock();
+        let skip = match window {
+            TapeWindow::All => 0,
+            TapeWindow::LastTrades(count) => entries.len().saturating_sub(count),
+            TapeWindow::LastMillis(millis) => {
+                let since = now.saturating_sub(millis);
+                entries.partition_point(|entry| entry.timestamp < since)
+            }
+        };
+
+        let mut stats = TapeStats::default();
+        for entry in entries.iter().skip(skip) {
+            stats.trade_count += 1;
+            stats.volume += entry.quantity;
+            stats.notional += u128::from(entry.price) * u128::from(entry.quantity);
+            match entry.aggressor_side {
+                Side::Buy => stats.buy_volume += entry.quantity,
+                Side::Sell => stats.sell_volume += entry.quantity,
+            }
+            if stats
+                .largest_trade
+                .is_none_or(|largest| entry.quantity > largest.quantity)
+            {
+                stats.largest_trade = Some(*entry);
+            }
+        }
+        if stats.volume > 0 {
+            stats.vwap = Some(stats.notional as f64 / stats.volume as f64);
+        }
+        stats
+    }
+
+    /// Drops every execution
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<TapeEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clone for TradeTape {
+    fn clone(&self) -> Self {
+        Self {
+            capacity: self.capacity,
+            entries: Mutex::new(self.lock().clone()),
+        }
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Clone + Send + Sync + Default + 'static,
+{
+    /// Records the last `capacity` executions of the book on a [`TradeTape`]
+    ///
+    /// Replaces any tape enabled before, dropping its executions.
+    ///
+    /// # Examples
+    /// ```
+    /// use orderbook_rs::{OrderBook, TapeWindow};
+    /// use pricelevel::{OrderId, Side, TimeInForce};
+    ///
+    /// let mut book = OrderBook::<()>::new("BTC/USD");
+    /// book.enable_trade_tape(1_000);
+    /// book.add_limit_order(OrderId::new(), 100, 10, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    /// book.add_limit_order(OrderId::new(), 101, 10, Side::Sell, TimeInForce::Gtc, None).unwrap();
+    /// book.submit_market_order(OrderId::new(), 15, Side::Buy).unwrap();
+    ///
+    /// let stats = book.tape_stats(TapeWindow::All).unwrap();
+    /// assert_eq!(stats.trade_count, 2);
+    /// assert_eq!(stats.volume, 15);
+    /// assert_eq!(stats.buy_ratio(), Some(1.0));
+    /// ```
+    pub fn enable_trade_tape(&mut self, capacity: usize) {
+        self.trade_tape = Some(TradeTape::new(capacity));
+    }
+
+    /// Stop recording executions, dropping the tape
+    pub fn disable_trade_tape(&mut self) {
+        self.trade_tape = None;
+    }
+
+    /// The trade tape of the book, if enabled
+    #[must_use]
+    pub fn trade_tape(&self) -> Option<&TradeTape> {
+        self.trade_tape.as_ref()
+    }
+
+    /// Statistics of the executions in `window` up to the current time of the
+    /// book clock, if the tape is enabled
+    #[must_use]
+    pub fn tape_stats(&self, window: TapeWindow) -> Option<TapeStats> {
+        self.trade_tape
+            .as_ref()
+            .map(|tape| tape.stats(window, self.clock.now_millis()))
+    }
+}
@@ -0,0 +1,233 @@
+//! Volume profile accumulating executed volume per price bucket from the trade stream.
+
+use super::trade::TradeResult;
+use pricelevel::Side;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Executed volume of one price bucket.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VolumeProfileLevel {
+    /// Lowest price of the bucket
+    pub price: u64,
+    /// Quantity executed by buy aggressors
+    pub buy_volume: u64,
+    /// Quantity executed by sell aggressors
+    pub sell_volume: u64,
+    /// Number of executions in the bucket
+    pub trade_count: u64,
+}
+
+impl VolumeProfileLevel {
+    /// Total quantity executed in the bucket
+    #[must_use]
+    pub fn volume(&self) -> u64 {
+        self.buy_volume + self.sell_volume
+    }
+}
+
+/// Volume profile of a session, ready to be serialized for display.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolumeProfileSummary {
+    /// Width of each price bucket
+    pub bucket_size: u64,
+    /// Every bucket with executed volume, by ascending price
+    pub levels: Vec<VolumeProfileLevel>,
+    /// Total executed quantity
+    pub total_volume: u64,
+    /// Bucket with the most volume, the lowest one on ties
+    pub point_of_control: Option<u64>,
+    /// Highest bucket of the value area
+    pub value_area_high: Option<u64>,
+    /// Lowest bucket of the value area
+    pub value_area_low: Option<u64>,
+}
+
+/// Executed volume per price bucket over a session.
+///
+/// Feed it from a trade listener with [`VolumeProfile::record_trade`] and call
+/// [`VolumeProfile::reset`] at the start of each session. The value area is
+/// built from the point of control by repeatedly adding the neighbouring
+/// bucket with more volume, among the buckets that traded, until it holds the
+/// value area ratio of the total volume (70% by default).
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::{OrderBook, VolumeProfile};
+/// use pricelevel::{OrderId, Side, TimeInForce};
+/// use std::sync::Arc;
+///
+/// let profile = Arc::new(VolumeProfile::new(10));
+/// let book = OrderBook::<()>::new("BTC/USD");
+/// let sink = Arc::clone(&profile);
+/// book.subscribe_trade_listener(Arc::new(move |trade| sink.record_trade(trade)));
+///
+/// for (price, quantity) in [(101, 5), (104, 20), (115, 3)] {
+///     book.add_limit_order(OrderId::new(), price, quantity, Side::Sell, TimeInForce::Gtc, None).unwrap();
+/// }
+/// book.submit_market_order(OrderId::new(), 28, Side::Buy).unwrap();
+///
+/// assert_eq!(profile.total_volume(), 28);
+/// assert_eq!(profile.point_of_control(), Some(100));
+/// assert_eq!(profile.value_area(), Some((100, 100)));
+/// ```
+#[derive(Debug)]
+pub struct VolumeProfile {
+    bucket_size: u64,
+    value_area_ratio: f64,
+    levels: Mutex<BTreeMap<u64, VolumeProfileLevel>>,
+}
+
+impl VolumeProfile {
+    /// Create an empty profile with buckets of `bucket_size` price units
+    #[must_use]
+    pub fn new(bucket_size: u64) -> Self {
+        Self {
+            bucket_size: bucket_size.max(1),
+            value_area_ratio: 0.7,
+            levels: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Sets the share of the total volume held by the value area, between 0 and 1
+    #[must_use]
+    pub fn with_value_area_ratio(mut self, ratio: f64) -> Self {
+        self.value_area_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Width of each price bucket
+    #[must_use]
+    pub fn bucket_size(&self) -> u64 {
+        self.bucket_size
+    }
+
+    /// Adds every transaction of `trade` to the profile
+    pub fn record_trade(&self, trade: &TradeResult) {
+        let mut levels = self.lock();
+        for transaction in trade.match_result.transactions.as_vec() {
+            Self::add(
+                &mut levels,
+                self.bucket_of(transaction.price),
+                transaction.taker_side,
+                transaction.quantity,
+            );
+        }
+    }
+
+    /// Adds a single execution of `quantity` at `price` taken by an `aggressor_side` order
+    pub fn record(&self, price: u64, quantity: u64, aggressor_side: Side) {
+        Self::add(
+            &mut self.lock(),
+            self.bucket_of(price),
+            aggressor_side,
+            quantity,
+        );
+    }
+
+    /// Bucket containing `price`
+    #[must_use]
+    pub fn bucket_of(&self, price: u64) -> u64 {
+        price - price % self.bucket_size
+    }
+
+    /// Every bucket with executed volume, by ascending price
+    #[must_use]
+    pub fn levels(&self) -> Vec<VolumeProfileLevel> {
+        self.lock().values().copied().collect()
+    }
+
+    /// Total executed quantity
+    #[must_use]
+    pub fn total_volume(&self) -> u64 {
+        self.lock().values().map(VolumeProfileLevel::volume).sum()
+    }
+
+    /// Bucket with the most volume, the lowest one on ties
+    #[must_use]
+    pub fn point_of_control(&self) -> Option<u64> {
+        Self::point_of_control_index(&self.levels()).map(|(_, level)| level.price)
+    }
+
+    /// Lowest and highest buckets of the value area
+    #[must_use]
+    pub fn value_area(&self) -> Option<(u64, u64)> {
+        self.value_area_of(&self.levels())
+    }
+
+    /// Profile of the session so far, with its point of control and value area
+    #[must_use]
+    pub fn summary(&self) -> VolumeProfileSummary {
+        let levels = self.levels();
+        let value_area = self.value_area_of(&levels);
+        VolumeProfileSummary {
+            bucket_size: self.bucket_size,
+            total_volume: levels.iter().map(VolumeProfileLevel::volume).sum(),
+            point_of_control: Self::point_of_control_index(&levels).map(|(_, level)| level.price),
+            value_area_low: value_area.map(|(low, _)| low),
+            value_area_high: value_area.map(|(_, high)| high),
+            levels,
+        }
+    }
+
+    /// Forgets every execution, starting a new session
+    pub fn reset(&self) {
+        self.lock().clear();
+    }
+
+    fn add(levels: &mut BTreeMap<u64, VolumeProfileLevel>, bucket: u64, side: Side, quantity: u64) {
+        let level = levels.entry(bucket).or_insert_with(|| VolumeProfileLevel {
+            price: bucket,
+            ..Default::default()
+        });
+        match side {
+            Side::Buy => level.buy_volume += quantity,
+            Side::Sell => level.sell_volume += quantity,
+        }
+        level.trade_count += 1;
+    }
+
+    fn point_of_control_index(
+        levels: &[VolumeProfileLevel],
+    ) -> Option<(usize, &VolumeProfileLevel)> {
+        levels
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, level)| level.volume())
+    }
+
+    fn value_area_of(&self, levels: &[VolumeProfileLevel]) -> Option<(u64, u64)> {
+        let (poc, level) = Self::point_of_control_index(levels)?;
+        let total: u64 = levels.iter().map(VolumeProfileLevel::volume).sum();
+        let target = (total as f64 * self.value_area_ratio).ceil() as u64;
+
+        let (mut low, mut high) = (poc, poc);
+        let mut volume = level.volume();
+        while volume < target {
+            let below = low.checked_sub(1).map(|index| levels[index].volume());
+            let above = levels.get(high + 1).map(VolumeProfileLevel::volume);
+            match (below, above) {
+                (Some(below), Some(above)) if above >= below => {
+                    high += 1;
+                    volume += above;
+                }
+                (Some(below), _) => {
+                    low -= 1;
+                    volume += below;
+                }
+                (None, Some(above)) => {
+                    high += 1;
+                    volume += above;
+                }
+                (None, None) => break,
+            }
+        }
+        Some((levels[low].price, levels[high].price))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<u64, VolumeProfileLevel>> {
+        self.levels.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -19,7 +19,14 @@
 // Core order book types
 pub use crate::orderbook::OrderBook;
 pub use crate::orderbook::OrderBookError;
-pub use crate::orderbook::manager::{BookManager, BookManagerStd, BookManagerTokio};
+#[cfg(feature = "tokio")]
+pub use crate::orderbook::async_manager::{AsyncBookManager, BookHandle};
+#[cfg(feature = "tokio")]
+pub use crate::orderbook::manager::BookManagerTokio;
+pub use crate::orderbook::manager::{BookManager, BookManagerStd, TradeEventHandler};
+pub use crate::orderbook::scheduler::{SnapshotScheduler, SnapshotStore, SnapshotTrigger};
+pub use crate::orderbook::session::{SessionState, SessionStateChangedEvent};
+pub use crate::orderbook::symbol_config::{InstrumentType, SymbolConfig, SymbolRegistry};
 
 // Iterator types
 pub use crate::orderbook::iterators::LevelInfo;
@@ -28,10 +35,15 @@ pub use crate::orderbook::iterators::LevelInfo;
 pub use crate::orderbook::market_impact::{MarketImpact, OrderSimulation};
 
 // Snapshot types
-pub use crate::orderbook::snapshot::{EnrichedSnapshot, MetricFlags, OrderBookSnapshot};
+pub use crate::orderbook::snapshot::{
+    EnrichedSnapshot, MetricFlags, OrderBookSnapshot, SnapshotFormat,
+};
 
 // Statistics types
-pub use crate::orderbook::statistics::{DepthStats, DistributionBin};
+pub use crate::orderbook::statistics::{
+    DepthStats, DistributionBin, ImpactEstimate, KyleLambda, LambdaEstimate, PriceBar,
+    RealizedVolatility, VolatilityEstimator, VolatilitySource,
+};
 
 // Trade-related types
 pub use crate::orderbook::trade::{
@@ -42,7 +54,7 @@ pub use crate::orderbook::trade::{
 pub use pricelevel::{OrderId, OrderType, Side, TimeInForce};
 
 // Utility functions
-pub use crate::utils::current_time_millis;
+pub use crate::utils::{Clock, ManualClock, SystemClock, current_time_millis};
 
 // Type aliases for common use cases
 pub use crate::{DefaultOrderBook, DefaultOrderType, LegacyOrderBook, LegacyOrderType};
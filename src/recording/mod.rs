@@ -0,0 +1,13 @@
+//! Recording of trades and book states into CSV or newline-delimited JSON files.
+//!
+//! A [`TradeRecorder`] or [`DepthRecorder`] subscribes to the listeners of a book
+//! and appends one row per event to a [`RecordSink`], which rotates its files by
+//! size or age so long captures stay manageable.
+
+mod recorders;
+mod sink;
+
+mod tests;
+
+pub use recorders::{DepthRecorder, TradeRecorder};
+pub use sink::{RecordFormat, RecordSink};
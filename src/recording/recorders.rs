@@ -0,0 +1,350 @@
+//! Recorders turning book listener events into rows of a [`RecordSink`].
+
+use super::sink::{RecordSink, Row};
+use crate::orderbook::book_change_event::PriceLevelChangedEvent;
+use crate::orderbook::subscription::SubscriptionId;
+use crate::orderbook::trade::{TradeListener, TradeResult};
+use crate::orderbook::{OrderBook, OrderBookError};
+use pricelevel::Side;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::error;
+
+/// One transaction of a trade.
+#[derive(Debug, Clone, Serialize)]
+struct TradeRow<'a> {
+    timestamp: u64,
+    symbol: &'a str,
+    transaction_id: String,
+    price: u64,
+    quantity: u64,
+    taker_side: Side,
+    taker_order_id: String,
+    maker_order_id: String,
+}
+
+impl Row for TradeRow<'_> {
+    const COLUMNS: &'static [&'static str] = &[
+        "timestamp",
+        "symbol",
+        "transaction_id",
+        "price",
+        "quantity",
+        "taker_side",
+        "taker_order_id",
+        "maker_order_id",
+    ];
+
+    fn values(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_string(),
+            self.symbol.to_string(),
+            self.transaction_id.clone(),
+            self.price.to_string(),
+            self.quantity.to_string(),
+            self.taker_side.to_string(),
+            self.taker_order_id.clone(),
+            self.maker_order_id.clone(),
+        ]
+    }
+}
+
+/// Kind of a depth row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum DepthRowKind {
+    /// A price level changed
+    Update,
+    /// A level of a captured ladder
+    Snapshot,
+}
+
+/// The visible quantity of one price level.
+#[derive(Debug, Clone, Serialize)]
+struct DepthRow<'a> {
+    timestamp: u64,
+    symbol: &'a str,
+    kind: DepthRowKind,
+    side: Side,
+    price: u64,
+    quantity: u64,
+}
+
+impl Row for DepthRow<'_> {
+    const COLUMNS: &'static [&'static str] =
+        &["timestamp", "symbol", "kind", "side", "price", "quantity"];
+
+    fn values(&self) -> Vec<String> {
+        let kind = match self.kind {
+            DepthRowKind::Update => "update",
+            DepthRowKind::Snapshot => "snapshot",
+        };
+        vec![
+            self.timestamp.to_string(),
+            self.symbol.to_string(),
+            kind.to_string(),
+            self.side.to_string(),
+            self.price.to_string(),
+            self.quantity.to_string(),
+        ]
+    }
+}
+
+/// Sink shared between a recorder and the listeners it subscribed.
+#[derive(Debug, Clone)]
+struct SharedSink {
+    sink: Arc<Mutex<RecordSink>>,
+    failures: Arc<AtomicU64>,
+}
+
+impl SharedSink {
+    fn new(sink: RecordSink) -> Self {
+        Self {
+            sink: Arc::new(Mutex::new(sink)),
+            failures: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    fn write<R: Row>(&self, rows: &[R]) -> Result<usize, OrderBookError> {
+        let mut sink = self.sink.lock().unwrap_or_else(|e| e.into_inner());
+        for row in rows {
+            sink.write_row(row)?;
+        }
+        Ok(rows.len())
+    }
+
+    /// Records the outcome of a write done by a listener, which cannot return it
+    fn track(&self, result: Result<usize, OrderBookError>) {
+        if let Err(e) = result {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+            error!("Recording: Dropped rows: {}", e);
+        }
+    }
+
+    fn flush(&self) -> Result<(), OrderBookError> {
+        self.sink.lock().unwrap_or_else(|e| e.into_inner()).flush()
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        self.sink
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .files()
+            .to_vec()
+    }
+}
+
+/// Records every transaction of the trades of one or more books.
+///
+/// Rows hold the transaction time, symbol, transaction ID, price, quantity, taker
+/// side and the taker and maker order IDs. Recorders are cheap to clone; clones
+/// share the sink.
+///
+/// # Examples
+/// ```no_run
+/// use orderbook_rs::recording::{RecordFormat, RecordSink, TradeRecorder};
+/// use orderbook_rs::DefaultOrderBook;
+///
+/// let book = DefaultOrderBook::new("BTC/USD");
+/// let sink = RecordSink::new("recordings", "trades", RecordFormat::Csv)
+///     .with_max_bytes(64 * 1024 * 1024);
+/// let recorder = TradeRecorder::new(sink);
+/// recorder.attach(&book);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TradeRecorder {
+    shared: SharedSink,
+}
+
+impl TradeRecorder {
+    /// Create a recorder appending to `sink`
+    pub fn new(sink: RecordSink) -> Self {
+        Self {
+            shared: SharedSink::new(sink),
+        }
+    }
+
+    /// Appends the transactions of `trade`, returning the number of rows written
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::RecordingError` if the rows cannot be written.
+    pub fn record(&self, trade: &TradeResult) -> Result<usize, OrderBookError> {
+        let rows: Vec<TradeRow<'_>> = trade
+            .match_result
+            .transactions
+            .as_vec()
+            .iter()
+            .map(|transaction| TradeRow {
+                timestamp: transaction.timestamp,
+                symbol: &trade.symbol,
+                transaction_id: transaction.transaction_id.to_string(),
+                price: transaction.price,
+                quantity: transaction.quantity,
+                taker_side: transaction.taker_side,
+                taker_order_id: transaction.taker_order_id.to_string(),
+                maker_order_id: transaction.maker_order_id.to_string(),
+            })
+            .collect();
+        self.shared.write(&rows)
+    }
+
+    /// Trade listener recording every trade it receives
+    ///
+    /// Failed writes are logged and counted by [`TradeRecorder::failures`].
+    pub fn listener(&self) -> TradeListener {
+        let recorder = self.clone();
+        Arc::new(move |trade| recorder.shared.track(recorder.record(trade)))
+    }
+
+    /// Subscribes the recorder to the trades of `book`
+    ///
+    /// Pass the returned ID to [`OrderBook::unsubscribe_trade_listener`] to stop recording.
+    pub fn attach<T>(&self, book: &OrderBook<T>) -> SubscriptionId
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        book.subscribe_trade_listener(self.listener())
+    }
+
+    /// Number of trades the listeners failed to record
+    pub fn failures(&self) -> u64 {
+        self.shared.failures.load(Ordering::Relaxed)
+    }
+
+    /// Writes the buffered rows to disk
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::RecordingError` if the file cannot be written.
+    pub fn flush(&self) -> Result<(), OrderBookError> {
+        self.shared.flush()
+    }
+
+    /// Paths of the files written so far, oldest first
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.shared.files()
+    }
+}
+
+/// Records the price level changes and depth ladders of one or more books.
+///
+/// Every change is an `update` row with the new visible quantity of the level,
+/// zero once the level is gone. [`DepthRecorder::record_depth`] adds `snapshot`
+/// rows with the top levels of a book, so a dataset can start from (or be
+/// resynchronized to) a full ladder. Rows are timestamped with the book's clock.
+///
+/// # Examples
+/// ```no_run
+/// use orderbook_rs::recording::{DepthRecorder, RecordFormat, RecordSink};
+/// use orderbook_rs::DefaultOrderBook;
+/// use std::time::Duration;
+///
+/// let book = DefaultOrderBook::new("BTC/USD");
+/// let sink = RecordSink::new("recordings", "depth", RecordFormat::Ndjson)
+///     .with_max_age(Duration::from_secs(3600));
+/// let recorder = DepthRecorder::new(sink);
+/// recorder.record_depth(&book, 20).unwrap();
+/// recorder.attach(&book);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DepthRecorder {
+    shared: SharedSink,
+}
+
+impl DepthRecorder {
+    /// Create a recorder appending to `sink`
+    pub fn new(sink: RecordSink) -> Self {
+        Self {
+            shared: SharedSink::new(sink),
+        }
+    }
+
+    /// Appends an `update` row for a price level change of `symbol`
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::RecordingError` if the row cannot be written.
+    pub fn record_change(
+        &self,
+        symbol: &str,
+        timestamp: u64,
+        event: &PriceLevelChangedEvent,
+    ) -> Result<(), OrderBookError> {
+        let row = DepthRow {
+            timestamp,
+            symbol,
+            kind: DepthRowKind::Update,
+            side: event.side,
+            price: event.price,
+            quantity: event.quantity,
+        };
+        self.shared.write(&[row]).map(|_| ())
+    }
+
+    /// Appends `snapshot` rows for up to `levels` levels per side of `book`, bids
+    /// first, and returns the number of rows written
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::RecordingError` if the rows cannot be written.
+    pub fn record_depth<T>(
+        &self,
+        book: &OrderBook<T>,
+        levels: usize,
+    ) -> Result<usize, OrderBookError>
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let timestamp = book.clock().now_millis();
+        let (bids, asks) = book.depth(levels);
+        let rows: Vec<DepthRow<'_>> = bids
+            .iter()
+            .map(|level| (Side::Buy, level))
+            .chain(asks.iter().map(|level| (Side::Sell, level)))
+            .map(|(side, &(price, quantity, _))| DepthRow {
+                timestamp,
+                symbol: book.symbol(),
+                kind: DepthRowKind::Snapshot,
+                side,
+                price,
+                quantity,
+            })
+            .collect();
+        self.shared.write(&rows)
+    }
+
+    /// Subscribes the recorder to the price level changes of `book`
+    ///
+    /// Pass the returned ID to [`OrderBook::unsubscribe_price_level_listener`] to
+    /// stop recording. Failed writes are logged and counted by
+    /// [`DepthRecorder::failures`].
+    pub fn attach<T>(&self, book: &OrderBook<T>) -> SubscriptionId
+    where
+        T: Clone + Send + Sync + Default + 'static,
+    {
+        let recorder = self.clone();
+        let symbol = book.symbol().to_string();
+        let clock = Arc::clone(book.clock());
+        book.subscribe_price_level_listener(Arc::new(move |event| {
+            let result = recorder.record_change(&symbol, clock.now_millis(), &event);
+            recorder.shared.track(result.map(|()| 1));
+        }))
+    }
+
+    /// Number of changes the listeners failed to record
+    pub fn failures(&self) -> u64 {
+        self.shared.failures.load(Ordering::Relaxed)
+    }
+
+    /// Writes the buffered rows to disk
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::RecordingError` if the file cannot be written.
+    pub fn flush(&self) -> Result<(), OrderBookError> {
+        self.shared.flush()
+    }
+
+    /// Paths of the files written so far, oldest first
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.shared.files()
+    }
+}
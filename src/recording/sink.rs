@@ -0,0 +1,231 @@
+//! Rotating CSV and newline-delimited JSON files.
+
+use crate::orderbook::OrderBookError;
+use crate::utils::{Clock, SystemClock};
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::trace;
+
+/// File format of a recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecordFormat {
+    /// Comma separated values with a header line at the start of every file
+    #[default]
+    Csv,
+    /// One JSON object per line
+    Ndjson,
+}
+
+impl RecordFormat {
+    /// File extension of the format, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RecordFormat::Csv => "csv",
+            RecordFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// A row of a recording.
+pub(crate) trait Row: Serialize {
+    /// Column names, in the order of [`Row::values`]
+    const COLUMNS: &'static [&'static str];
+
+    /// Field values as written to CSV files
+    fn values(&self) -> Vec<String>;
+}
+
+/// Destination of a recording: a directory of files rotated by size and age.
+///
+/// Files are named `<prefix>-<opened at>-<index>.<extension>`, where `opened at`
+/// is the time the file was opened in milliseconds since the Unix epoch and
+/// `index` counts the files opened by this sink. No file is created until the
+/// first row is written.
+///
+/// A file is rotated before a row is written once it holds at least the maximum
+/// number of bytes, or once it was opened at least the maximum age ago, so a file
+/// can exceed the size limit by one row.
+#[derive(Debug)]
+pub struct RecordSink {
+    directory: PathBuf,
+    prefix: String,
+    format: RecordFormat,
+    max_bytes: Option<u64>,
+    max_age: Option<Duration>,
+    clock: Arc<dyn Clock>,
+    file: Option<BufWriter<File>>,
+    opened_at: u64,
+    bytes: u64,
+    files: Vec<PathBuf>,
+}
+
+impl RecordSink {
+    /// Create a sink writing `format` files named after `prefix` into `directory`
+    ///
+    /// Files are never rotated until a size or age limit is set.
+    pub fn new(directory: impl AsRef<Path>, prefix: &str, format: RecordFormat) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+            prefix: prefix.to_string(),
+            format,
+            max_bytes: None,
+            max_age: None,
+            clock: Arc::new(SystemClock),
+            file: None,
+            opened_at: 0,
+            bytes: 0,
+            files: Vec::new(),
+        }
+    }
+
+    /// Rotates files once they hold `max_bytes` bytes
+    #[must_use]
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Rotates files once they were opened `max_age` ago
+    #[must_use]
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Sets the clock used for file names and age based rotation
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// File format of the recording
+    pub fn format(&self) -> RecordFormat {
+        self.format
+    }
+
+    /// Paths of the files opened so far, oldest first; the last one is being written
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Writes the buffered rows of the current file to disk
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::RecordingError` if the file cannot be written.
+    pub fn flush(&mut self) -> Result<(), OrderBookError> {
+        match self.file.as_mut() {
+            Some(file) => file
+                .flush()
+                .map_err(|e| recording_error(&self.directory, e)),
+            None => Ok(()),
+        }
+    }
+
+    /// Appends `row`, rotating the current file first if it reached a limit
+    pub(crate) fn write_row<R: Row>(&mut self, row: &R) -> Result<(), OrderBookError> {
+        let line = match self.format {
+            RecordFormat::Csv => csv_line(&row.values()),
+            RecordFormat::Ndjson => {
+                let mut line =
+                    serde_json::to_string(row).map_err(|e| OrderBookError::SerializationError {
+                        message: e.to_string(),
+                    })?;
+                line.push('\n');
+                line
+            }
+        };
+
+        if self.is_due_for_rotation() {
+            self.flush()?;
+            self.file = None;
+        }
+        if self.file.is_none() {
+            self.open::<R>()?;
+        }
+        self.append(&line)
+    }
+
+    fn is_due_for_rotation(&self) -> bool {
+        if self.file.is_none() {
+            return false;
+        }
+        let full = self.max_bytes.is_some_and(|max| self.bytes >= max);
+        let expired = self.max_age.is_some_and(|max| {
+            self.clock.now_millis().saturating_sub(self.opened_at) >= max.as_millis() as u64
+        });
+        full || expired
+    }
+
+    fn open<R: Row>(&mut self) -> Result<(), OrderBookError> {
+        fs::create_dir_all(&self.directory).map_err(|e| recording_error(&self.directory, e))?;
+        self.opened_at = self.clock.now_millis();
+        let path = self.directory.join(format!(
+            "{}-{}-{:04}.{}",
+            self.prefix,
+            self.opened_at,
+            self.files.len() + 1,
+            self.format.extension()
+        ));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|e| recording_error(&path, e))?;
+        trace!("Recording: Opened {}", path.display());
+
+        self.file = Some(BufWriter::new(file));
+        self.files.push(path);
+        self.bytes = 0;
+        if self.format == RecordFormat::Csv {
+            let columns: Vec<String> = R::COLUMNS.iter().map(|c| c.to_string()).collect();
+            self.append(&csv_line(&columns))?;
+        }
+        Ok(())
+    }
+
+    fn append(&mut self, line: &str) -> Result<(), OrderBookError> {
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+        file.write_all(line.as_bytes())
+            .map_err(|e| recording_error(&self.directory, e))?;
+        self.bytes += line.len() as u64;
+        Ok(())
+    }
+}
+
+impl Drop for RecordSink {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Joins `values` into a CSV line, quoting the values that need it
+fn csv_line(values: &[String]) -> String {
+    let mut line = String::new();
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            line.push(',');
+        }
+        if value.contains([',', '"', '\n', '\r']) {
+            line.push('"');
+            line.push_str(&value.replace('"', "\"\""));
+            line.push('"');
+        } else {
+            line.push_str(value);
+        }
+    }
+    line.push('\n');
+    line
+}
+
+fn recording_error(path: &Path, e: std::io::Error) -> OrderBookError {
+    OrderBookError::RecordingError {
+        message: format!("{}: {e}", path.display()),
+    }
+}
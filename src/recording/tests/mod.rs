@@ -0,0 +1,2 @@
+mod recorders;
+mod sink;
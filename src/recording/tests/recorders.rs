@@ -0,0 +1,127 @@
+//! Unit tests for trade and depth recorders.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::OrderBook;
+    use crate::orderbook::book_change_event::PriceLevelChangedEvent;
+    use crate::recording::{DepthRecorder, RecordFormat, RecordSink, TradeRecorder};
+    use crate::utils::ManualClock;
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use serde_json::Value;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("orderbook-recorders-{}", OrderId::new()))
+    }
+
+    fn book() -> OrderBook<()> {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.set_clock(Arc::new(ManualClock::new(1_000)));
+        book
+    }
+
+    fn add(book: &OrderBook<()>, price: u64, quantity: u64, side: Side) -> OrderId {
+        let id = OrderId::new();
+        book.add_limit_order(id, price, quantity, side, TimeInForce::Gtc, None)
+            .unwrap();
+        id
+    }
+
+    #[test]
+    fn test_trade_recorder_writes_transactions() {
+        let dir = temp_dir();
+        let book = book();
+        let maker = add(&book, 100, 5, Side::Sell);
+        add(&book, 101, 5, Side::Sell);
+
+        let recorder = TradeRecorder::new(RecordSink::new(&dir, "trades", RecordFormat::Csv));
+        let id = recorder.attach(&book);
+        let taker = OrderId::new();
+        book.submit_market_order(taker, 7, Side::Buy).unwrap();
+        assert!(book.unsubscribe_trade_listener(id));
+        book.submit_market_order(OrderId::new(), 1, Side::Buy)
+            .unwrap();
+        recorder.flush().unwrap();
+
+        let content = std::fs::read_to_string(&recorder.files()[0]).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(
+            lines[0],
+            "timestamp,symbol,transaction_id,price,quantity,taker_side,taker_order_id,maker_order_id"
+        );
+        assert_eq!(lines.len(), 3);
+        let first: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(first[1..2], ["BTC/USD"]);
+        assert_eq!(first[3..6], ["100", "5", "BUY"]);
+        assert_eq!(first[6], taker.to_string());
+        assert_eq!(first[7], maker.to_string());
+        assert!(lines[2].contains(",101,2,BUY,"));
+        assert_eq!(recorder.failures(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_depth_recorder_snapshot_and_updates() {
+        let dir = temp_dir();
+        let book = book();
+        add(&book, 100, 5, Side::Buy);
+        add(&book, 102, 3, Side::Sell);
+
+        let recorder = DepthRecorder::new(RecordSink::new(&dir, "depth", RecordFormat::Ndjson));
+        assert_eq!(recorder.record_depth(&book, 10).unwrap(), 2);
+        recorder.attach(&book);
+        let order = add(&book, 100, 2, Side::Buy);
+        book.cancel_order(order).unwrap();
+        recorder.flush().unwrap();
+
+        let rows: Vec<Value> = std::fs::read_to_string(&recorder.files()[0])
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let summary: Vec<(&str, &str, u64, u64)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row["kind"].as_str().unwrap(),
+                    row["side"].as_str().unwrap(),
+                    row["price"].as_u64().unwrap(),
+                    row["quantity"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("snapshot", "BUY", 100, 5),
+                ("snapshot", "SELL", 102, 3),
+                ("update", "BUY", 100, 7),
+                ("update", "BUY", 100, 5),
+            ]
+        );
+        assert!(rows.iter().all(|row| row["timestamp"] == 1_000));
+        assert!(rows.iter().all(|row| row["symbol"] == "BTC/USD"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_listener_failures_are_counted() {
+        let file = temp_dir();
+        std::fs::write(&file, "not a directory").unwrap();
+        let book = book();
+
+        let recorder = DepthRecorder::new(RecordSink::new(&file, "depth", RecordFormat::Csv));
+        recorder.attach(&book);
+        add(&book, 100, 5, Side::Buy);
+
+        assert_eq!(recorder.failures(), 1);
+        let event = PriceLevelChangedEvent {
+            side: Side::Buy,
+            price: 100,
+            quantity: 0,
+        };
+        assert!(recorder.record_change("BTC/USD", 0, &event).is_err());
+        std::fs::remove_file(&file).unwrap();
+    }
+}
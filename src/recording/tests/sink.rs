@@ -0,0 +1,133 @@
+//! Unit tests for rotating recording sinks.
+
+#[cfg(test)]
+mod tests {
+    use crate::recording::sink::{RecordFormat, RecordSink, Row};
+    use crate::utils::ManualClock;
+    use pricelevel::OrderId;
+    use serde::Serialize;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[derive(Serialize)]
+    struct NoteRow {
+        id: u64,
+        note: String,
+    }
+
+    impl Row for NoteRow {
+        const COLUMNS: &'static [&'static str] = &["id", "note"];
+
+        fn values(&self) -> Vec<String> {
+            vec![self.id.to_string(), self.note.clone()]
+        }
+    }
+
+    fn row(id: u64, note: &str) -> NoteRow {
+        NoteRow {
+            id,
+            note: note.to_string(),
+        }
+    }
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("orderbook-recording-{}", OrderId::new()))
+    }
+
+    fn read(path: &PathBuf) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn test_csv_header_and_quoting() {
+        let dir = temp_dir();
+        let clock = Arc::new(ManualClock::new(1_000));
+        let mut sink = RecordSink::new(&dir, "notes", RecordFormat::Csv).with_clock(clock);
+        assert!(sink.files().is_empty());
+
+        sink.write_row(&row(1, "plain")).unwrap();
+        sink.write_row(&row(2, "a, \"quoted\" note")).unwrap();
+        sink.flush().unwrap();
+
+        assert_eq!(sink.files(), &[dir.join("notes-1000-0001.csv")]);
+        assert_eq!(
+            read(&sink.files()[0]),
+            "id,note\n1,plain\n2,\"a, \"\"quoted\"\" note\"\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_ndjson_lines() {
+        let dir = temp_dir();
+        let mut sink = RecordSink::new(&dir, "notes", RecordFormat::Ndjson);
+        sink.write_row(&row(1, "first")).unwrap();
+        sink.write_row(&row(2, "second")).unwrap();
+        sink.flush().unwrap();
+
+        assert_eq!(sink.files()[0].extension().unwrap(), "ndjson");
+        assert_eq!(
+            read(&sink.files()[0]),
+            "{\"id\":1,\"note\":\"first\"}\n{\"id\":2,\"note\":\"second\"}\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotation_by_size() {
+        let dir = temp_dir();
+        let mut sink = RecordSink::new(&dir, "notes", RecordFormat::Csv)
+            .with_clock(Arc::new(ManualClock::new(0)))
+            .with_max_bytes(20);
+
+        for id in 0..5 {
+            sink.write_row(&row(id, "abcdef")).unwrap();
+        }
+        sink.flush().unwrap();
+
+        // The header and two rows fill a file past 20 bytes
+        assert_eq!(sink.files().len(), 3);
+        assert_eq!(read(&sink.files()[0]), "id,note\n0,abcdef\n1,abcdef\n");
+        assert_eq!(read(&sink.files()[2]), "id,note\n4,abcdef\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotation_by_age() {
+        let dir = temp_dir();
+        let clock = Arc::new(ManualClock::new(5_000));
+        let mut sink = RecordSink::new(&dir, "notes", RecordFormat::Ndjson)
+            .with_clock(clock.clone())
+            .with_max_age(Duration::from_secs(60));
+
+        sink.write_row(&row(1, "a")).unwrap();
+        clock.advance(59_999);
+        sink.write_row(&row(2, "b")).unwrap();
+        clock.advance(1);
+        sink.write_row(&row(3, "c")).unwrap();
+        sink.flush().unwrap();
+
+        assert_eq!(
+            sink.files(),
+            &[
+                dir.join("notes-5000-0001.ndjson"),
+                dir.join("notes-65000-0002.ndjson")
+            ]
+        );
+        assert_eq!(read(&sink.files()[0]).lines().count(), 2);
+        assert_eq!(read(&sink.files()[1]).lines().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_unwritable_directory_fails() {
+        let file = temp_dir();
+        std::fs::write(&file, "not a directory").unwrap();
+
+        let mut sink = RecordSink::new(&file, "notes", RecordFormat::Csv);
+        assert!(sink.write_row(&row(1, "a")).is_err());
+        assert!(sink.files().is_empty());
+        std::fs::remove_file(&file).unwrap();
+    }
+}
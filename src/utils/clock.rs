@@ -0,0 +1,64 @@
+use crate::utils::time::current_time_millis;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Source of the current time used by order books and book managers.
+///
+/// Inject a custom clock to drive time deterministically in tests and replays,
+/// for example to exercise GTD and DAY expiry without waiting on the wall clock.
+pub trait Clock: Send + Sync + fmt::Debug {
+    /// Returns the current time in milliseconds since UNIX epoch
+    fn now_millis(&self) -> u64;
+}
+
+/// Clock backed by the system wall clock. This is the default clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> u64 {
+        current_time_millis()
+    }
+}
+
+/// Clock that only moves when told to.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::{Clock, ManualClock};
+///
+/// let clock = ManualClock::new(1_000);
+/// clock.advance(500);
+/// assert_eq!(clock.now_millis(), 1_500);
+/// clock.set(42);
+/// assert_eq!(clock.now_millis(), 42);
+/// ```
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: AtomicU64,
+}
+
+impl ManualClock {
+    /// Create a manual clock reading `now_millis`
+    pub fn new(now_millis: u64) -> Self {
+        Self {
+            now: AtomicU64::new(now_millis),
+        }
+    }
+
+    /// Set the current time, in milliseconds since UNIX epoch
+    pub fn set(&self, now_millis: u64) {
+        self.now.store(now_millis, Ordering::SeqCst);
+    }
+
+    /// Move the clock forward by `millis`
+    pub fn advance(&self, millis: u64) {
+        self.now.fetch_add(millis, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_millis(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
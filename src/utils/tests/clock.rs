@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod tests {
+    use crate::current_time_millis;
+    use crate::utils::{Clock, ManualClock, SystemClock};
+
+    #[test]
+    fn test_manual_clock_only_moves_when_told() {
+        let clock = ManualClock::new(1_000);
+        assert_eq!(clock.now_millis(), 1_000);
+        assert_eq!(clock.now_millis(), 1_000);
+
+        clock.advance(250);
+        assert_eq!(clock.now_millis(), 1_250);
+
+        clock.set(10);
+        assert_eq!(clock.now_millis(), 10);
+    }
+
+    #[test]
+    fn test_system_clock_tracks_wall_clock() {
+        let before = current_time_millis();
+        let now = SystemClock.now_millis();
+        assert!(now >= before);
+        assert!(now <= current_time_millis());
+    }
+}
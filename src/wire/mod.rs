@@ -0,0 +1,419 @@
+//! Zero-copy FlatBuffers wire format for snapshots and deltas.
+//!
+//! Messages follow `schema/orderbook.fbs`, so processes written in any language
+//! with FlatBuffers support can read them. A [`WireEncoder`] reuses its buffers
+//! between messages; [`WireMessage::decode`] verifies a buffer once and then
+//! reads fields in place, without allocating or copying the levels.
+//!
+//! Snapshots carry the aggregated state of each price level, not the individual
+//! orders, which is what a market data consumer needs to rebuild the book's
+//! depth. Deltas carry every field of an [`OrderBookDelta`].
+
+mod schema;
+
+mod tests;
+
+use crate::orderbook::{LevelChange, OrderBookDelta, OrderBookError, OrderBookSnapshot};
+use flatbuffers::{FlatBufferBuilder, Table, Vector, WIPOffset};
+use pricelevel::Side;
+use schema::{
+    ChangeColumns, DeltaTable, FILE_IDENTIFIER, LevelColumns, LevelsTable, MessageTable,
+    PAYLOAD_DELTA, PAYLOAD_SNAPSHOT, SnapshotTable,
+};
+
+/// Version of the wire schema written by this crate.
+///
+/// Decoding rejects messages with a higher version.
+pub const WIRE_VERSION: u16 = 1;
+
+const SIDE_BUY: u8 = 0;
+const SIDE_SELL: u8 = 1;
+const KIND_ADDED: u8 = 0;
+const KIND_UPDATED: u8 = 1;
+const KIND_REMOVED: u8 = 2;
+
+/// Encodes snapshots and deltas, reusing its buffers between messages.
+///
+/// # Examples
+/// ```
+/// use orderbook_rs::wire::{WireEncoder, WireMessage};
+/// use orderbook_rs::{DefaultOrderBook, OrderId, Side, TimeInForce};
+///
+/// let book = DefaultOrderBook::new("BTC/USD");
+/// book.add_limit_order(OrderId::new(), 100, 10, Side::Buy, TimeInForce::Gtc, None).unwrap();
+///
+/// let mut encoder = WireEncoder::new();
+/// let bytes = encoder.encode_snapshot(&book.create_snapshot(10));
+///
+/// let Ok(WireMessage::Snapshot(snapshot)) = WireMessage::decode(bytes) else {
+///     panic!("not a snapshot");
+/// };
+/// assert_eq!(snapshot.symbol(), "BTC/USD");
+/// assert_eq!(snapshot.best_bid(), Some((100, 10)));
+/// ```
+#[derive(Default)]
+pub struct WireEncoder {
+    builder: FlatBufferBuilder<'static>,
+    bids: LevelColumns,
+    asks: LevelColumns,
+    changes: ChangeColumns,
+}
+
+impl WireEncoder {
+    /// Create an encoder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encodes `snapshot`, returning the message bytes
+    ///
+    /// The bytes stay valid until the next call to the encoder.
+    pub fn encode_snapshot(&mut self, snapshot: &OrderBookSnapshot) -> &[u8] {
+        self.builder.reset();
+        for (columns, levels) in [
+            (&mut self.bids, &snapshot.bids),
+            (&mut self.asks, &snapshot.asks),
+        ] {
+            columns.clear();
+            for level in levels {
+                columns.prices.push(level.price);
+                columns.visible_quantities.push(level.visible_quantity);
+                columns.hidden_quantities.push(level.hidden_quantity);
+                columns.order_counts.push(level.order_count as u64);
+            }
+        }
+
+        let bids = LevelsTable::create(&mut self.builder, &self.bids);
+        let asks = LevelsTable::create(&mut self.builder, &self.asks);
+        let payload = SnapshotTable::create(
+            &mut self.builder,
+            &snapshot.symbol,
+            snapshot.timestamp,
+            bids,
+            asks,
+        );
+        self.finish(PAYLOAD_SNAPSHOT, payload)
+    }
+
+    /// Encodes `delta`, returning the message bytes
+    ///
+    /// The bytes stay valid until the next call to the encoder.
+    pub fn encode_delta(&mut self, delta: &OrderBookDelta) -> &[u8] {
+        self.builder.reset();
+        self.changes.clear();
+        for change in &delta.changes {
+            let (kind, side, price, quantity) = match *change {
+                LevelChange::Added {
+                    side,
+                    price,
+                    quantity,
+                } => (KIND_ADDED, side, price, quantity),
+                LevelChange::Updated {
+                    side,
+                    price,
+                    quantity,
+                } => (KIND_UPDATED, side, price, quantity),
+                LevelChange::Removed { side, price } => (KIND_REMOVED, side, price, 0),
+            };
+            self.changes.kinds.push(kind);
+            self.changes.sides.push(match side {
+                Side::Buy => SIDE_BUY,
+                Side::Sell => SIDE_SELL,
+            });
+            self.changes.prices.push(price);
+            self.changes.quantities.push(quantity);
+        }
+
+        let payload = DeltaTable::create(
+            &mut self.builder,
+            &delta.symbol,
+            delta.from_sequence,
+            delta.to_sequence,
+            &self.changes,
+        );
+        self.finish(PAYLOAD_DELTA, payload)
+    }
+
+    fn finish(&mut self, payload_type: u8, payload: WIPOffset<Table<'static>>) -> &[u8] {
+        let message = MessageTable::create(&mut self.builder, WIRE_VERSION, payload_type, payload);
+        self.builder.finish(message, Some(FILE_IDENTIFIER));
+        self.builder.finished_data()
+    }
+}
+
+/// Encodes `snapshot` into a new buffer
+pub fn encode_snapshot(snapshot: &OrderBookSnapshot) -> Vec<u8> {
+    WireEncoder::new().encode_snapshot(snapshot).to_vec()
+}
+
+/// Encodes `delta` into a new buffer
+pub fn encode_delta(delta: &OrderBookDelta) -> Vec<u8> {
+    WireEncoder::new().encode_delta(delta).to_vec()
+}
+
+/// A decoded message, borrowing the buffer it was decoded from.
+#[derive(Debug, Clone, Copy)]
+pub enum WireMessage<'a> {
+    /// An aggregated book snapshot
+    Snapshot(SnapshotView<'a>),
+    /// A set of price level changes
+    Delta(DeltaView<'a>),
+}
+
+impl<'a> WireMessage<'a> {
+    /// Verifies `bytes` and returns a view of the message
+    ///
+    /// Verification walks the structure of the buffer once; the accessors of the
+    /// returned views then read it in place.
+    ///
+    /// # Errors
+    /// Returns `OrderBookError::DeserializationError` if the buffer is not a valid
+    /// message, has an unknown payload, or was written by a newer schema version.
+    pub fn decode(bytes: &'a [u8]) -> Result<Self, OrderBookError> {
+        if bytes.len() < 8 || !flatbuffers::buffer_has_identifier(bytes, FILE_IDENTIFIER, false) {
+            return Err(invalid("missing file identifier".to_string()));
+        }
+        let message =
+            flatbuffers::root::<MessageTable>(bytes).map_err(|e| invalid(e.to_string()))?;
+        let version = message.version();
+        if !(1..=WIRE_VERSION).contains(&version) {
+            return Err(invalid(format!("unsupported version {version}")));
+        }
+
+        if let Some(snapshot) = message.snapshot() {
+            let (Some(bids), Some(asks)) = (snapshot.bids(), snapshot.asks()) else {
+                return Err(invalid("snapshot without levels".to_string()));
+            };
+            return Ok(WireMessage::Snapshot(SnapshotView {
+                table: snapshot,
+                bids: LevelsView::new(bids)?,
+                asks: LevelsView::new(asks)?,
+            }));
+        }
+        if let Some(delta) = message.delta() {
+            return DeltaView::new(delta).map(WireMessage::Delta);
+        }
+        Err(invalid(format!(
+            "unknown payload type {}",
+            message.payload_type()
+        )))
+    }
+}
+
+/// Aggregated state of one price level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireLevel {
+    /// Level price
+    pub price: u64,
+    /// Visible quantity of the level
+    pub visible_quantity: u64,
+    /// Hidden quantity of the level
+    pub hidden_quantity: u64,
+    /// Number of orders at the level
+    pub order_count: u64,
+}
+
+/// View of an encoded snapshot.
+#[derive(Debug, Clone, Copy)]
+pub struct SnapshotView<'a> {
+    table: SnapshotTable<'a>,
+    bids: LevelsView<'a>,
+    asks: LevelsView<'a>,
+}
+
+impl<'a> SnapshotView<'a> {
+    /// Book symbol
+    pub fn symbol(&self) -> &'a str {
+        self.table.symbol()
+    }
+
+    /// Snapshot time in milliseconds since the Unix epoch
+    pub fn timestamp(&self) -> u64 {
+        self.table.timestamp()
+    }
+
+    /// Bid levels, in the order of the encoded snapshot (best first for book snapshots)
+    pub fn bids(&self) -> LevelsView<'a> {
+        self.bids
+    }
+
+    /// Ask levels, in the order of the encoded snapshot (best first for book snapshots)
+    pub fn asks(&self) -> LevelsView<'a> {
+        self.asks
+    }
+
+    /// Price and visible quantity of the first bid level
+    pub fn best_bid(&self) -> Option<(u64, u64)> {
+        self.bids
+            .get(0)
+            .map(|level| (level.price, level.visible_quantity))
+    }
+
+    /// Price and visible quantity of the first ask level
+    pub fn best_ask(&self) -> Option<(u64, u64)> {
+        self.asks
+            .get(0)
+            .map(|level| (level.price, level.visible_quantity))
+    }
+}
+
+/// View of the encoded levels of one side of a snapshot.
+#[derive(Debug, Clone, Copy)]
+pub struct LevelsView<'a> {
+    prices: Vector<'a, u64>,
+    visible_quantities: Vector<'a, u64>,
+    hidden_quantities: Vector<'a, u64>,
+    order_counts: Vector<'a, u64>,
+}
+
+impl<'a> LevelsView<'a> {
+    fn new(table: LevelsTable<'a>) -> Result<Self, OrderBookError> {
+        let view = Self {
+            prices: table.prices(),
+            visible_quantities: table.visible_quantities(),
+            hidden_quantities: table.hidden_quantities(),
+            order_counts: table.order_counts(),
+        };
+        let len = view.prices.len();
+        if [
+            view.visible_quantities.len(),
+            view.hidden_quantities.len(),
+            view.order_counts.len(),
+        ]
+        .iter()
+        .any(|&other| other != len)
+        {
+            return Err(invalid("level columns differ in length".to_string()));
+        }
+        Ok(view)
+    }
+
+    /// Number of levels
+    pub fn len(&self) -> usize {
+        self.prices.len()
+    }
+
+    /// Returns `true` if the side has no levels
+    pub fn is_empty(&self) -> bool {
+        self.prices.is_empty()
+    }
+
+    /// The level at `index`
+    pub fn get(&self, index: usize) -> Option<WireLevel> {
+        (index < self.len()).then(|| WireLevel {
+            price: self.prices.get(index),
+            visible_quantity: self.visible_quantities.get(index),
+            hidden_quantity: self.hidden_quantities.get(index),
+            order_count: self.order_counts.get(index),
+        })
+    }
+
+    /// Iterator over the levels
+    pub fn iter(&self) -> impl Iterator<Item = WireLevel> + 'a {
+        let view = *self;
+        (0..view.len()).filter_map(move |index| view.get(index))
+    }
+}
+
+/// View of an encoded delta.
+#[derive(Debug, Clone, Copy)]
+pub struct DeltaView<'a> {
+    table: DeltaTable<'a>,
+    sides: Vector<'a, u8>,
+    kinds: Vector<'a, u8>,
+    prices: Vector<'a, u64>,
+    quantities: Vector<'a, u64>,
+}
+
+impl<'a> DeltaView<'a> {
+    fn new(table: DeltaTable<'a>) -> Result<Self, OrderBookError> {
+        let view = Self {
+            table,
+            sides: table.sides(),
+            kinds: table.kinds(),
+            prices: table.prices(),
+            quantities: table.quantities(),
+        };
+        let len = view.kinds.len();
+        if [view.sides.len(), view.prices.len(), view.quantities.len()]
+            .iter()
+            .any(|&other| other != len)
+        {
+            return Err(invalid("change columns differ in length".to_string()));
+        }
+        if view.sides.bytes().iter().any(|&side| side > SIDE_SELL) {
+            return Err(invalid("unknown side".to_string()));
+        }
+        if view.kinds.bytes().iter().any(|&kind| kind > KIND_REMOVED) {
+            return Err(invalid("unknown change kind".to_string()));
+        }
+        Ok(view)
+    }
+
+    /// Book symbol
+    pub fn symbol(&self) -> &'a str {
+        self.table.symbol()
+    }
+
+    /// Sequence number the delta starts from (exclusive)
+    pub fn from_sequence(&self) -> u64 {
+        self.table.from_sequence()
+    }
+
+    /// Sequence number the delta brings a replica up to (inclusive)
+    pub fn to_sequence(&self) -> u64 {
+        self.table.to_sequence()
+    }
+
+    /// Number of changes
+    pub fn len(&self) -> usize {
+        self.kinds.len()
+    }
+
+    /// Returns `true` if no level changed
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+    }
+
+    /// Iterator over the changes, in encoded order
+    pub fn changes(&self) -> impl Iterator<Item = LevelChange> + 'a {
+        let view = *self;
+        (0..view.len()).map(move |index| {
+            let side = match view.sides.get(index) {
+                SIDE_BUY => Side::Buy,
+                _ => Side::Sell,
+            };
+            let price = view.prices.get(index);
+            let quantity = view.quantities.get(index);
+            match view.kinds.get(index) {
+                KIND_ADDED => LevelChange::Added {
+                    side,
+                    price,
+                    quantity,
+                },
+                KIND_UPDATED => LevelChange::Updated {
+                    side,
+                    price,
+                    quantity,
+                },
+                _ => LevelChange::Removed { side, price },
+            }
+        })
+    }
+
+    /// Copies the delta out of the buffer
+    pub fn to_delta(&self) -> OrderBookDelta {
+        OrderBookDelta {
+            symbol: self.symbol().to_string(),
+            from_sequence: self.from_sequence(),
+            to_sequence: self.to_sequence(),
+            changes: self.changes().collect(),
+        }
+    }
+}
+
+fn invalid(reason: String) -> OrderBookError {
+    OrderBookError::DeserializationError {
+        message: format!("Invalid wire message: {reason}"),
+    }
+}
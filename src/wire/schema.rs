@@ -0,0 +1,348 @@
+//! Table accessors and verifiers for `schema/orderbook.fbs`.
+//!
+//! These follow the layout `flatc --rust` produces for the schema and must be
+//! kept in sync with it: every `VT_*` constant is the vtable offset of the field
+//! with the same position in the schema (`4 + 2 * index`).
+//!
+//! Accessors are only created for buffers that passed the [`Verifiable`]
+//! implementations below, which is what makes their unchecked reads sound.
+
+use flatbuffers::{
+    FlatBufferBuilder, Follow, ForwardsUOffset, InvalidFlatbuffer, Table, VOffsetT, Vector,
+    Verifiable, Verifier, WIPOffset,
+};
+
+/// `file_identifier` of the schema.
+pub(super) const FILE_IDENTIFIER: &str = "OBWF";
+
+/// `Payload` union discriminants.
+pub(super) const PAYLOAD_SNAPSHOT: u8 = 1;
+pub(super) const PAYLOAD_DELTA: u8 = 2;
+
+/// Defines a table wrapper with its `Follow` and `Verifiable` implementations.
+macro_rules! table {
+    ($name:ident, |$v:ident| $verify:expr) => {
+        #[derive(Debug, Clone, Copy)]
+        pub(super) struct $name<'a> {
+            tab: Table<'a>,
+        }
+
+        impl<'a> Follow<'a> for $name<'a> {
+            type Inner = Self;
+
+            unsafe fn follow(buf: &'a [u8], loc: usize) -> Self {
+                // SAFETY: the caller guarantees `loc` is the position of this table
+                Self {
+                    tab: unsafe { Table::new(buf, loc) },
+                }
+            }
+        }
+
+        impl Verifiable for $name<'_> {
+            fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+                let $v = v.visit_table(pos)?;
+                $verify.finish();
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Reads a field of a verified table, falling back to `default` when it is absent.
+macro_rules! field {
+    ($tab:expr, $ty:ty, $slot:expr, $default:expr) => {
+        // SAFETY: the table was verified to hold a `$ty` in this slot
+        unsafe { $tab.get::<$ty>($slot, Some($default)) }.unwrap_or($default)
+    };
+}
+
+type U64s<'a> = ForwardsUOffset<Vector<'a, u64>>;
+type U8s<'a> = ForwardsUOffset<Vector<'a, u8>>;
+
+/// Column values of a `Levels` table.
+#[derive(Debug, Default)]
+pub(super) struct LevelColumns {
+    pub(super) prices: Vec<u64>,
+    pub(super) visible_quantities: Vec<u64>,
+    pub(super) hidden_quantities: Vec<u64>,
+    pub(super) order_counts: Vec<u64>,
+}
+
+impl LevelColumns {
+    pub(super) fn clear(&mut self) {
+        self.prices.clear();
+        self.visible_quantities.clear();
+        self.hidden_quantities.clear();
+        self.order_counts.clear();
+    }
+}
+
+/// Column values of the changes of a `Delta` table.
+#[derive(Debug, Default)]
+pub(super) struct ChangeColumns {
+    pub(super) sides: Vec<u8>,
+    pub(super) kinds: Vec<u8>,
+    pub(super) prices: Vec<u64>,
+    pub(super) quantities: Vec<u64>,
+}
+
+impl ChangeColumns {
+    pub(super) fn clear(&mut self) {
+        self.sides.clear();
+        self.kinds.clear();
+        self.prices.clear();
+        self.quantities.clear();
+    }
+}
+
+table!(MessageTable, |v| v
+    .visit_field::<u16>("version", MessageTable::VT_VERSION, false)?
+    .visit_union::<u8, _>(
+        "payload_type",
+        MessageTable::VT_PAYLOAD_TYPE,
+        "payload",
+        MessageTable::VT_PAYLOAD,
+        true,
+        |kind, v, pos| match kind {
+            PAYLOAD_SNAPSHOT => {
+                v.verify_union_variant::<ForwardsUOffset<SnapshotTable>>("Snapshot", pos)
+            }
+            PAYLOAD_DELTA => v.verify_union_variant::<ForwardsUOffset<DeltaTable>>("Delta", pos),
+            _ => Ok(()),
+        },
+    )?);
+
+impl<'a> MessageTable<'a> {
+    const VT_VERSION: VOffsetT = 4;
+    const VT_PAYLOAD_TYPE: VOffsetT = 6;
+    const VT_PAYLOAD: VOffsetT = 8;
+
+    pub(super) fn version(&self) -> u16 {
+        field!(self.tab, u16, Self::VT_VERSION, 0)
+    }
+
+    pub(super) fn payload_type(&self) -> u8 {
+        field!(self.tab, u8, Self::VT_PAYLOAD_TYPE, 0)
+    }
+
+    /// The payload if it is a snapshot
+    pub(super) fn snapshot(&self) -> Option<SnapshotTable<'a>> {
+        if self.payload_type() != PAYLOAD_SNAPSHOT {
+            return None;
+        }
+        // SAFETY: the verifier checked the payload against its discriminant
+        unsafe {
+            self.tab
+                .get::<ForwardsUOffset<SnapshotTable<'a>>>(Self::VT_PAYLOAD, None)
+        }
+    }
+
+    /// The payload if it is a delta
+    pub(super) fn delta(&self) -> Option<DeltaTable<'a>> {
+        if self.payload_type() != PAYLOAD_DELTA {
+            return None;
+        }
+        // SAFETY: the verifier checked the payload against its discriminant
+        unsafe {
+            self.tab
+                .get::<ForwardsUOffset<DeltaTable<'a>>>(Self::VT_PAYLOAD, None)
+        }
+    }
+
+    pub(super) fn create<'b>(
+        fbb: &mut FlatBufferBuilder<'b>,
+        version: u16,
+        payload_type: u8,
+        payload: WIPOffset<Table<'b>>,
+    ) -> WIPOffset<MessageTable<'b>> {
+        let start = fbb.start_table();
+        fbb.push_slot_always(Self::VT_PAYLOAD, payload);
+        fbb.push_slot::<u16>(Self::VT_VERSION, version, 0);
+        fbb.push_slot::<u8>(Self::VT_PAYLOAD_TYPE, payload_type, 0);
+        let end = fbb.end_table(start);
+        fbb.required(end, Self::VT_PAYLOAD, "payload");
+        WIPOffset::new(end.value())
+    }
+}
+
+table!(LevelsTable, |v| v
+    .visit_field::<U64s>("prices", LevelsTable::VT_PRICES, false)?
+    .visit_field::<U64s>("visible_quantities", LevelsTable::VT_VISIBLE, false)?
+    .visit_field::<U64s>("hidden_quantities", LevelsTable::VT_HIDDEN, false)?
+    .visit_field::<U64s>(
+        "order_counts",
+        LevelsTable::VT_ORDER_COUNTS,
+        false
+    )?);
+
+impl<'a> LevelsTable<'a> {
+    const VT_PRICES: VOffsetT = 4;
+    const VT_VISIBLE: VOffsetT = 6;
+    const VT_HIDDEN: VOffsetT = 8;
+    const VT_ORDER_COUNTS: VOffsetT = 10;
+
+    pub(super) fn prices(&self) -> Vector<'a, u64> {
+        field!(self.tab, U64s<'a>, Self::VT_PRICES, Vector::default())
+    }
+
+    pub(super) fn visible_quantities(&self) -> Vector<'a, u64> {
+        field!(self.tab, U64s<'a>, Self::VT_VISIBLE, Vector::default())
+    }
+
+    pub(super) fn hidden_quantities(&self) -> Vector<'a, u64> {
+        field!(self.tab, U64s<'a>, Self::VT_HIDDEN, Vector::default())
+    }
+
+    pub(super) fn order_counts(&self) -> Vector<'a, u64> {
+        field!(self.tab, U64s<'a>, Self::VT_ORDER_COUNTS, Vector::default())
+    }
+
+    pub(super) fn create<'b>(
+        fbb: &mut FlatBufferBuilder<'b>,
+        columns: &LevelColumns,
+    ) -> WIPOffset<LevelsTable<'b>> {
+        let prices = fbb.create_vector(&columns.prices);
+        let visible = fbb.create_vector(&columns.visible_quantities);
+        let hidden = fbb.create_vector(&columns.hidden_quantities);
+        let order_counts = fbb.create_vector(&columns.order_counts);
+        let start = fbb.start_table();
+        fbb.push_slot_always(Self::VT_PRICES, prices);
+        fbb.push_slot_always(Self::VT_VISIBLE, visible);
+        fbb.push_slot_always(Self::VT_HIDDEN, hidden);
+        fbb.push_slot_always(Self::VT_ORDER_COUNTS, order_counts);
+        WIPOffset::new(fbb.end_table(start).value())
+    }
+}
+
+table!(SnapshotTable, |v| v
+    .visit_field::<ForwardsUOffset<&str>>("symbol", SnapshotTable::VT_SYMBOL, true)?
+    .visit_field::<u64>("timestamp", SnapshotTable::VT_TIMESTAMP, false)?
+    .visit_field::<ForwardsUOffset<LevelsTable>>("bids", SnapshotTable::VT_BIDS, true)?
+    .visit_field::<ForwardsUOffset<LevelsTable>>(
+        "asks",
+        SnapshotTable::VT_ASKS,
+        true
+    )?);
+
+impl<'a> SnapshotTable<'a> {
+    const VT_SYMBOL: VOffsetT = 4;
+    const VT_TIMESTAMP: VOffsetT = 6;
+    const VT_BIDS: VOffsetT = 8;
+    const VT_ASKS: VOffsetT = 10;
+
+    pub(super) fn symbol(&self) -> &'a str {
+        field!(self.tab, ForwardsUOffset<&str>, Self::VT_SYMBOL, "")
+    }
+
+    pub(super) fn timestamp(&self) -> u64 {
+        field!(self.tab, u64, Self::VT_TIMESTAMP, 0)
+    }
+
+    pub(super) fn bids(&self) -> Option<LevelsTable<'a>> {
+        // SAFETY: the verifier checked the type of the field
+        unsafe {
+            self.tab
+                .get::<ForwardsUOffset<LevelsTable>>(Self::VT_BIDS, None)
+        }
+    }
+
+    pub(super) fn asks(&self) -> Option<LevelsTable<'a>> {
+        // SAFETY: the verifier checked the type of the field
+        unsafe {
+            self.tab
+                .get::<ForwardsUOffset<LevelsTable>>(Self::VT_ASKS, None)
+        }
+    }
+
+    pub(super) fn create<'b>(
+        fbb: &mut FlatBufferBuilder<'b>,
+        symbol: &str,
+        timestamp: u64,
+        bids: WIPOffset<LevelsTable<'b>>,
+        asks: WIPOffset<LevelsTable<'b>>,
+    ) -> WIPOffset<Table<'b>> {
+        let symbol = fbb.create_string(symbol);
+        let start = fbb.start_table();
+        fbb.push_slot::<u64>(Self::VT_TIMESTAMP, timestamp, 0);
+        fbb.push_slot_always(Self::VT_SYMBOL, symbol);
+        fbb.push_slot_always(Self::VT_BIDS, bids);
+        fbb.push_slot_always(Self::VT_ASKS, asks);
+        WIPOffset::new(fbb.end_table(start).value())
+    }
+}
+
+table!(DeltaTable, |v| v
+    .visit_field::<ForwardsUOffset<&str>>("symbol", DeltaTable::VT_SYMBOL, true)?
+    .visit_field::<u64>("from_sequence", DeltaTable::VT_FROM_SEQUENCE, false)?
+    .visit_field::<u64>("to_sequence", DeltaTable::VT_TO_SEQUENCE, false)?
+    .visit_field::<U8s>("sides", DeltaTable::VT_SIDES, false)?
+    .visit_field::<U8s>("kinds", DeltaTable::VT_KINDS, false)?
+    .visit_field::<U64s>("prices", DeltaTable::VT_PRICES, false)?
+    .visit_field::<U64s>(
+        "quantities",
+        DeltaTable::VT_QUANTITIES,
+        false
+    )?);
+
+// Accessors are named after the schema fields
+#[allow(clippy::wrong_self_convention)]
+impl<'a> DeltaTable<'a> {
+    const VT_SYMBOL: VOffsetT = 4;
+    const VT_FROM_SEQUENCE: VOffsetT = 6;
+    const VT_TO_SEQUENCE: VOffsetT = 8;
+    const VT_SIDES: VOffsetT = 10;
+    const VT_KINDS: VOffsetT = 12;
+    const VT_PRICES: VOffsetT = 14;
+    const VT_QUANTITIES: VOffsetT = 16;
+
+    pub(super) fn symbol(&self) -> &'a str {
+        field!(self.tab, ForwardsUOffset<&str>, Self::VT_SYMBOL, "")
+    }
+
+    pub(super) fn from_sequence(&self) -> u64 {
+        field!(self.tab, u64, Self::VT_FROM_SEQUENCE, 0)
+    }
+
+    pub(super) fn to_sequence(&self) -> u64 {
+        field!(self.tab, u64, Self::VT_TO_SEQUENCE, 0)
+    }
+
+    pub(super) fn sides(&self) -> Vector<'a, u8> {
+        field!(self.tab, U8s<'a>, Self::VT_SIDES, Vector::default())
+    }
+
+    pub(super) fn kinds(&self) -> Vector<'a, u8> {
+        field!(self.tab, U8s<'a>, Self::VT_KINDS, Vector::default())
+    }
+
+    pub(super) fn prices(&self) -> Vector<'a, u64> {
+        field!(self.tab, U64s<'a>, Self::VT_PRICES, Vector::default())
+    }
+
+    pub(super) fn quantities(&self) -> Vector<'a, u64> {
+        field!(self.tab, U64s<'a>, Self::VT_QUANTITIES, Vector::default())
+    }
+
+    pub(super) fn create<'b>(
+        fbb: &mut FlatBufferBuilder<'b>,
+        symbol: &str,
+        from_sequence: u64,
+        to_sequence: u64,
+        changes: &ChangeColumns,
+    ) -> WIPOffset<Table<'b>> {
+        let symbol = fbb.create_string(symbol);
+        let sides = fbb.create_vector(&changes.sides);
+        let kinds = fbb.create_vector(&changes.kinds);
+        let prices = fbb.create_vector(&changes.prices);
+        let quantities = fbb.create_vector(&changes.quantities);
+        let start = fbb.start_table();
+        fbb.push_slot::<u64>(Self::VT_FROM_SEQUENCE, from_sequence, 0);
+        fbb.push_slot::<u64>(Self::VT_TO_SEQUENCE, to_sequence, 0);
+        fbb.push_slot_always(Self::VT_SYMBOL, symbol);
+        fbb.push_slot_always(Self::VT_SIDES, sides);
+        fbb.push_slot_always(Self::VT_KINDS, kinds);
+        fbb.push_slot_always(Self::VT_PRICES, prices);
+        fbb.push_slot_always(Self::VT_QUANTITIES, quantities);
+        WIPOffset::new(fbb.end_table(start).value())
+    }
+}
@@ -0,0 +1,177 @@
+//! Unit tests for the FlatBuffers wire format.
+
+#[cfg(test)]
+mod tests {
+    use crate::orderbook::{LevelChange, OrderBook, OrderBookDelta};
+    use crate::utils::ManualClock;
+    use crate::wire::{
+        WIRE_VERSION, WireEncoder, WireLevel, WireMessage, encode_delta, encode_snapshot,
+    };
+    use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::Arc;
+
+    fn book() -> OrderBook<()> {
+        let mut book = OrderBook::<()>::new("BTC/USD");
+        book.set_clock(Arc::new(ManualClock::new(1_234)));
+        for (price, quantity, side) in [
+            (100, 10, Side::Buy),
+            (100, 5, Side::Buy),
+            (99, 7, Side::Buy),
+            (101, 3, Side::Sell),
+        ] {
+            book.add_limit_order(
+                OrderId::new(),
+                price,
+                quantity,
+                side,
+                TimeInForce::Gtc,
+                None,
+            )
+            .unwrap();
+        }
+        book.add_iceberg_order(
+            OrderId::new(),
+            102,
+            2,
+            8,
+            Side::Sell,
+            TimeInForce::Gtc,
+            None,
+        )
+        .unwrap();
+        book
+    }
+
+    fn delta() -> OrderBookDelta {
+        OrderBookDelta {
+            symbol: "BTC/USD".to_string(),
+            from_sequence: 7,
+            to_sequence: 12,
+            changes: vec![
+                LevelChange::Added {
+                    side: Side::Buy,
+                    price: 100,
+                    quantity: 5,
+                },
+                LevelChange::Updated {
+                    side: Side::Sell,
+                    price: 101,
+                    quantity: 9,
+                },
+                LevelChange::Removed {
+                    side: Side::Sell,
+                    price: 103,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let snapshot = book().create_snapshot(10);
+        let bytes = encode_snapshot(&snapshot);
+
+        let Ok(WireMessage::Snapshot(view)) = WireMessage::decode(&bytes) else {
+            panic!("expected a snapshot");
+        };
+        assert_eq!(view.symbol(), "BTC/USD");
+        assert_eq!(view.timestamp(), 1_234);
+        assert_eq!(view.best_bid(), Some((100, 15)));
+        assert_eq!(view.best_ask(), Some((101, 3)));
+        assert_eq!(view.bids().len(), 2);
+        assert_eq!(
+            view.asks().iter().collect::<Vec<_>>(),
+            vec![
+                WireLevel {
+                    price: 101,
+                    visible_quantity: 3,
+                    hidden_quantity: 0,
+                    order_count: 1,
+                },
+                WireLevel {
+                    price: 102,
+                    visible_quantity: 2,
+                    hidden_quantity: 8,
+                    order_count: 1,
+                },
+            ]
+        );
+        assert_eq!(view.bids().get(2), None);
+    }
+
+    #[test]
+    fn test_empty_snapshot() {
+        let snapshot = OrderBook::<()>::new("EMPTY").create_snapshot(10);
+        let bytes = encode_snapshot(&snapshot);
+
+        let Ok(WireMessage::Snapshot(view)) = WireMessage::decode(&bytes) else {
+            panic!("expected a snapshot");
+        };
+        assert!(view.bids().is_empty());
+        assert_eq!(view.best_ask(), None);
+    }
+
+    #[test]
+    fn test_delta_round_trip() {
+        let delta = delta();
+        let bytes = encode_delta(&delta);
+
+        let Ok(WireMessage::Delta(view)) = WireMessage::decode(&bytes) else {
+            panic!("expected a delta");
+        };
+        assert_eq!(view.symbol(), "BTC/USD");
+        assert_eq!((view.from_sequence(), view.to_sequence()), (7, 12));
+        assert_eq!(view.len(), 3);
+        assert_eq!(view.to_delta(), delta);
+    }
+
+    #[test]
+    fn test_encoder_reuses_buffers() {
+        let mut encoder = WireEncoder::new();
+        let snapshot = encoder
+            .encode_snapshot(&book().create_snapshot(10))
+            .to_vec();
+        let delta = encoder.encode_delta(&delta()).to_vec();
+
+        assert_eq!(snapshot, encode_snapshot(&book().create_snapshot(10)));
+        assert!(matches!(
+            WireMessage::decode(&delta),
+            Ok(WireMessage::Delta(_))
+        ));
+        assert!(matches!(
+            WireMessage::decode(&snapshot),
+            Ok(WireMessage::Snapshot(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_invalid_buffers() {
+        assert!(WireMessage::decode(&[]).is_err());
+        assert!(WireMessage::decode(b"not a flatbuffer").is_err());
+
+        let bytes = encode_delta(&delta());
+        assert!(WireMessage::decode(&bytes[..bytes.len() / 2]).is_err());
+
+        let mut renamed = bytes.clone();
+        renamed[4..8].copy_from_slice(b"XXXX");
+        assert!(WireMessage::decode(&renamed).is_err());
+    }
+
+    #[test]
+    fn test_rejects_newer_versions() {
+        let mut bytes = encode_delta(&delta());
+        let read_i32 = |bytes: &[u8], at: usize| {
+            i32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()) as isize
+        };
+
+        // Root table, its vtable and the slot of the version field
+        let table = read_i32(&bytes, 0) as usize;
+        let vtable = table.checked_sub_signed(read_i32(&bytes, table)).unwrap();
+        let slot = u16::from_le_bytes([bytes[vtable + 4], bytes[vtable + 5]]) as usize;
+        let version = table + slot;
+        assert_eq!(bytes[version..version + 2], WIRE_VERSION.to_le_bytes());
+
+        bytes[version..version + 2].copy_from_slice(&(WIRE_VERSION + 1).to_le_bytes());
+        assert!(WireMessage::decode(&bytes).is_err());
+    }
+}
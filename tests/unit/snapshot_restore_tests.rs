@@ -1,8 +1,13 @@
 #[cfg(test)]
 mod tests_snapshot_restore {
-    use orderbook_rs::orderbook::ORDERBOOK_SNAPSHOT_FORMAT_VERSION;
-    use orderbook_rs::{DefaultOrderBook, OrderBook, OrderBookError};
+    use orderbook_rs::orderbook::book_change_event::{BookChangedEvent, BookReset};
+    use orderbook_rs::orderbook::{
+        ORDERBOOK_SNAPSHOT_FORMAT_VERSION, ORDERBOOK_SNAPSHOT_MIN_SUPPORTED_VERSION,
+        OrderBookSnapshotPackage,
+    };
+    use orderbook_rs::{DefaultOrderBook, OrderBook, OrderBookError, SnapshotFormat};
     use pricelevel::{OrderId, Side, TimeInForce};
+    use std::sync::{Arc, Mutex};
 
     fn populate_order_book(book: &OrderBook<()>) -> Vec<OrderId> {
         let first = OrderId::from_u64(1);
@@ -112,4 +117,258 @@ mod tests_snapshot_restore {
 
         assert!(matches!(err, OrderBookError::InvalidOperation { .. }));
     }
+
+    #[test]
+    fn snapshot_binary_round_trip_restores_book_state() {
+        let original = DefaultOrderBook::new("BIN");
+        let order_ids = populate_order_book(&original);
+        original
+            .add_iceberg_order(
+                OrderId::from_u64(5),
+                9_800,
+                2,
+                8,
+                Side::Buy,
+                TimeInForce::Gtc,
+                None,
+            )
+            .expect("add iceberg");
+
+        let bytes = original
+            .snapshot_to_bytes(10, SnapshotFormat::Binary)
+            .expect("serialize snapshot to bytes");
+        let json_payload = original.snapshot_to_json(10).expect("serialize to json");
+        assert!(bytes.len() < json_payload.len());
+
+        let restored = DefaultOrderBook::new("BIN");
+        restored
+            .restore_from_snapshot_bytes(&bytes, SnapshotFormat::Binary)
+            .expect("restore from bytes");
+
+        assert_eq!(restored.best_bid(), Some(10_000));
+        assert_eq!(restored.best_ask(), Some(10_100));
+        assert_eq!(restored.depth(10), original.depth(10));
+        for order_id in order_ids {
+            assert!(restored.get_order(order_id).is_some());
+        }
+        let iceberg = restored
+            .get_order(OrderId::from_u64(5))
+            .expect("iceberg should be restored");
+        assert_eq!(iceberg.hidden_quantity(), 8);
+    }
+
+    #[test]
+    fn snapshot_binary_keeps_checksum_and_extra_fields() {
+        let book = OrderBook::<String>::new("EXT");
+        book.add_limit_order(
+            OrderId::from_u64(1),
+            10_000,
+            5,
+            Side::Buy,
+            TimeInForce::Gtc,
+            Some("desk-a".to_string()),
+        )
+        .expect("add bid");
+
+        let snapshot = book
+            .create_snapshot_with_extra_fields(10)
+            .expect("snapshot with extra fields");
+        let package = OrderBookSnapshotPackage::new(snapshot).expect("snapshot package");
+
+        let decoded = OrderBookSnapshotPackage::from_bytes(&package.to_bytes().expect("encode"))
+            .expect("decode");
+        assert_eq!(decoded.checksum, package.checksum);
+        assert_eq!(decoded.snapshot.extra_fields, package.snapshot.extra_fields);
+        decoded.validate().expect("decoded package should validate");
+
+        let restored = OrderBook::<String>::new("EXT");
+        restored
+            .restore_from_snapshot_with_extra_fields(decoded.into_snapshot().expect("valid"))
+            .expect("restore with extra fields");
+        let order = restored
+            .get_order(OrderId::from_u64(1))
+            .expect("order should be restored");
+        assert_eq!(order.extra_fields(), &"desk-a".to_string());
+    }
+
+    #[test]
+    fn snapshot_formats_decode_only_their_own_encoding() {
+        let book = DefaultOrderBook::new("FMT");
+        populate_order_book(&book);
+        let package = book.create_snapshot_package(10).expect("snapshot package");
+
+        for format in [SnapshotFormat::Json, SnapshotFormat::Binary] {
+            let data = package.encode(format).expect("encode");
+            let decoded = OrderBookSnapshotPackage::decode(&data, format).expect("decode");
+            assert_eq!(decoded.checksum, package.checksum);
+        }
+
+        let json = package.encode(SnapshotFormat::Json).expect("encode json");
+        assert!(OrderBookSnapshotPackage::decode(&json, SnapshotFormat::Binary).is_err());
+        let binary = package
+            .encode(SnapshotFormat::Binary)
+            .expect("encode binary");
+        assert!(OrderBookSnapshotPackage::decode(&binary, SnapshotFormat::Json).is_err());
+    }
+
+    #[test]
+    fn restore_rejects_tampered_binary_snapshot() {
+        let book = DefaultOrderBook::new("TMP");
+        populate_order_book(&book);
+
+        let mut package = book.create_snapshot_package(10).expect("snapshot package");
+        package.snapshot.timestamp += 1;
+        let bytes = package.to_bytes().expect("encode");
+
+        let restored = DefaultOrderBook::new("TMP");
+        let err = restored
+            .restore_from_snapshot_bytes(&bytes, SnapshotFormat::Binary)
+            .expect_err("checksum mismatch should be detected");
+
+        assert!(matches!(err, OrderBookError::ChecksumMismatch { .. }));
+    }
+
+    fn version_one_json(book: &OrderBook<()>) -> String {
+        let mut package = book.create_snapshot_package(10).expect("snapshot package");
+        package.version = 1;
+        package.to_json().expect("serialize package")
+    }
+
+    #[test]
+    fn version_one_package_is_migrated_on_restore() {
+        let book = DefaultOrderBook::new("MIG");
+        let order_ids = populate_order_book(&book);
+
+        let restored = DefaultOrderBook::new("MIG");
+        restored
+            .restore_from_snapshot_json(&version_one_json(&book))
+            .expect("version 1 snapshot should be migrated");
+
+        assert_eq!(restored.best_bid(), Some(10_000));
+        for order_id in order_ids {
+            assert!(restored.get_order(order_id).is_some());
+        }
+    }
+
+    #[test]
+    fn migrate_upgrades_to_current_version() {
+        let book = DefaultOrderBook::new("MIG");
+        populate_order_book(&book);
+
+        let package =
+            OrderBookSnapshotPackage::from_json(&version_one_json(&book)).expect("deserialize");
+        assert!(package.validate().is_err());
+
+        let migrated = package.clone().migrate().expect("migrate");
+        assert_eq!(migrated.version, ORDERBOOK_SNAPSHOT_FORMAT_VERSION);
+        assert_eq!(migrated.checksum, package.checksum);
+        migrated
+            .validate()
+            .expect("migrated package should validate");
+
+        let current = migrated.clone().migrate().expect("migrate current");
+        assert_eq!(current.version, migrated.version);
+    }
+
+    #[test]
+    fn migration_still_detects_tampering() {
+        let book = DefaultOrderBook::new("MIG");
+        populate_order_book(&book);
+
+        let mut package =
+            OrderBookSnapshotPackage::from_json(&version_one_json(&book)).expect("deserialize");
+        package.snapshot.timestamp += 1;
+
+        let err = package
+            .into_snapshot()
+            .expect_err("checksum mismatch should be detected");
+        assert!(matches!(err, OrderBookError::ChecksumMismatch { .. }));
+    }
+
+    #[test]
+    fn migration_rejects_unsupported_versions() {
+        let book = DefaultOrderBook::new("MIG");
+        populate_order_book(&book);
+
+        let mut package = book.create_snapshot_package(10).expect("snapshot package");
+        package.version = ORDERBOOK_SNAPSHOT_MIN_SUPPORTED_VERSION - 1;
+
+        let err = package.migrate().expect_err("version 0 should be rejected");
+        assert!(matches!(err, OrderBookError::InvalidOperation { .. }));
+    }
+
+    #[test]
+    fn restore_emits_book_reset_with_every_level() {
+        let book = DefaultOrderBook::new("RST");
+        populate_order_book(&book);
+        let snapshot = book.create_snapshot(10);
+        let snapshot_ts = snapshot.timestamp;
+
+        let events: Arc<Mutex<Vec<BookChangedEvent>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let mut restored = DefaultOrderBook::new("RST");
+        restored
+            .add_limit_order(OrderId::new(), 500, 1, Side::Buy, TimeInForce::Gtc, None)
+            .expect("add stale bid");
+        restored.set_book_changed_listener(Arc::new(move |event| {
+            sink.lock().unwrap().push(event);
+        }));
+
+        restored.restore_from_snapshot(snapshot).expect("restore");
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].reset,
+            Some(BookReset {
+                sequence: 0,
+                snapshot_ts,
+            })
+        );
+        let mut levels: Vec<(Side, u64, u64)> = events[0]
+            .changes
+            .iter()
+            .map(|change| (change.side, change.price, change.quantity))
+            .collect();
+        levels.sort_by_key(|(_, price, _)| *price);
+        assert_eq!(
+            levels,
+            vec![
+                (Side::Buy, 9_900, 7),
+                (Side::Buy, 10_000, 5),
+                (Side::Sell, 10_100, 4),
+                (Side::Sell, 10_200, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn restore_primes_best_price_cache() {
+        let book = DefaultOrderBook::new("CACHE");
+        populate_order_book(&book);
+
+        let restored = DefaultOrderBook::new("CACHE");
+        restored
+            .add_limit_order(
+                OrderId::new(),
+                20_000,
+                1,
+                Side::Sell,
+                TimeInForce::Gtc,
+                None,
+            )
+            .expect("add stale ask");
+        assert_eq!(restored.best_ask(), Some(20_000));
+
+        restored
+            .restore_from_snapshot(book.create_snapshot(10))
+            .expect("restore");
+
+        let cache = serde_json::to_value(&restored).expect("serialize book")["cache"].clone();
+        assert_eq!(cache["cache_valid"], true);
+        assert_eq!(cache["best_bid_price"], 10_000);
+        assert_eq!(cache["best_ask_price"], 10_100);
+        assert_eq!(restored.best_bid(), Some(10_000));
+        assert_eq!(restored.best_ask(), Some(10_100));
+    }
 }